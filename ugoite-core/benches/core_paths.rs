@@ -0,0 +1,142 @@
+//! Benchmarks for the core entry paths (create/get/list) across backends and
+//! table sizes.
+//!
+//! Scales default to 1k/10k/100k entries and can be narrowed with
+//! `UGOITE_BENCH_SCALES=1000,10000`. Seeding the largest scale takes a while,
+//! since every entry is a separate Iceberg commit just like in production.
+//!
+//! Build with `--features flamegraph` to write a flamegraph per benchmark
+//! under `target/criterion/<bench>/profile/` when run with `--profile-time`.
+
+use _ugoite_core::entry;
+use _ugoite_core::form;
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::space;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use opendal::services::{Fs, Memory};
+use opendal::Operator;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::runtime::Runtime;
+
+const DEFAULT_SCALES: [usize; 3] = [1_000, 10_000, 100_000];
+
+fn scales() -> Vec<usize> {
+    match std::env::var("UGOITE_BENCH_SCALES") {
+        Ok(raw) => raw
+            .split(',')
+            .filter_map(|part| part.trim().parse().ok())
+            .collect(),
+        Err(_) => DEFAULT_SCALES.to_vec(),
+    }
+}
+
+fn backends() -> anyhow::Result<Vec<(&'static str, Operator)>> {
+    let memory = Operator::new(Memory::default())?.finish();
+    let root = std::env::temp_dir().join(format!("ugoite-bench-{}", uuid::Uuid::new_v4().simple()));
+    std::fs::create_dir_all(&root)?;
+    let fs = Operator::new(Fs::default().root(&root.to_string_lossy()))?.finish();
+    Ok(vec![("memory", memory), ("fs", fs)])
+}
+
+fn entry_markdown(idx: usize) -> String {
+    format!(
+        "---\nform: Entry\n---\n# Bench {}\n\n## Body\nBenchmark body {}",
+        idx, idx
+    )
+}
+
+async fn seed_space(op: &Operator, space_id: &str, count: usize) -> anyhow::Result<String> {
+    space::create_space(op, space_id, "/tmp").await?;
+    let ws_path = format!("spaces/{}", space_id);
+    let form_def = serde_json::json!({
+        "name": "Entry",
+        "template": "# Entry\n\n## Body\n",
+        "fields": {"Body": {"type": "markdown"}},
+    });
+    form::upsert_form(op, &ws_path, &form_def).await?;
+    for idx in 0..count {
+        entry::create_entry(
+            op,
+            &ws_path,
+            &format!("entry-{}", idx),
+            &entry_markdown(idx),
+            "bench",
+            &FakeIntegrityProvider,
+        )
+        .await?;
+    }
+    Ok(ws_path)
+}
+
+fn bench_core_paths(c: &mut Criterion) {
+    let runtime = Runtime::new().expect("tokio runtime");
+    let backends = backends().expect("bench backends");
+    let counter = AtomicUsize::new(0);
+
+    for scale in scales() {
+        for (backend, op) in &backends {
+            let space_id = format!("bench-{}-{}", backend, scale);
+            let ws_path = runtime
+                .block_on(seed_space(op, &space_id, scale))
+                .expect("seed space");
+            let mut group = c.benchmark_group(format!("entry/{}", backend));
+            group.sample_size(10);
+
+            group.bench_with_input(
+                BenchmarkId::new("create_entry", scale),
+                &ws_path,
+                |b, ws| {
+                    b.to_async(&runtime).iter(|| async {
+                        let idx = scale + counter.fetch_add(1, Ordering::Relaxed);
+                        entry::create_entry(
+                            op,
+                            ws,
+                            &format!("entry-{}", idx),
+                            &entry_markdown(idx),
+                            "bench",
+                            &FakeIntegrityProvider,
+                        )
+                        .await
+                        .expect("create entry")
+                    });
+                },
+            );
+
+            let target = format!("entry-{}", scale / 2);
+            group.bench_with_input(BenchmarkId::new("get_entry", scale), &ws_path, |b, ws| {
+                b.to_async(&runtime)
+                    .iter(|| async { entry::get_entry(op, ws, &target).await.expect("get entry") });
+            });
+
+            group.bench_with_input(
+                BenchmarkId::new("list_entries", scale),
+                &ws_path,
+                |b, ws| {
+                    b.to_async(&runtime).iter(|| async {
+                        entry::list_entries(op, ws).await.expect("list entries")
+                    });
+                },
+            );
+
+            group.finish();
+        }
+    }
+}
+
+#[cfg(feature = "flamegraph")]
+fn config() -> Criterion {
+    use pprof::criterion::{Output, PProfProfiler};
+    Criterion::default().with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)))
+}
+
+#[cfg(not(feature = "flamegraph"))]
+fn config() -> Criterion {
+    Criterion::default()
+}
+
+criterion_group! {
+    name = benches;
+    config = config();
+    targets = bench_core_paths
+}
+criterion_main!(benches);
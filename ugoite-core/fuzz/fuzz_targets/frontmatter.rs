@@ -0,0 +1,48 @@
+#![no_main]
+
+//! Feeds arbitrary text through the frontmatter/section parser and checks
+//! that whatever parses also survives a render -> parse round trip.
+
+use _ugoite_core::entry;
+use libfuzzer_sys::fuzz_target;
+use serde_json::Value;
+
+fuzz_target!(|content: &str| {
+    let Ok((frontmatter, sections)) = entry::parse_markdown(content) else {
+        return;
+    };
+    let Some(form_name) = frontmatter.get("form").and_then(Value::as_str) else {
+        return;
+    };
+    let tags: Vec<String> = match frontmatter.get("tags") {
+        None => Vec::new(),
+        Some(Value::Array(items)) => {
+            let tags: Vec<String> = items
+                .iter()
+                .filter_map(|tag| tag.as_str().map(str::to_string))
+                .collect();
+            if tags.len() != items.len() {
+                return;
+            }
+            tags
+        }
+        Some(_) => return,
+    };
+
+    let rendered = entry::render_markdown("Fuzz", form_name, &tags, &sections, &[]);
+    let (reparsed_frontmatter, reparsed_sections) =
+        entry::parse_markdown(&rendered).expect("rendered markdown must parse");
+    assert_eq!(reparsed_frontmatter["form"].as_str(), Some(form_name));
+    assert_eq!(reparsed_sections, sections);
+    let reparsed_tags: Vec<String> = reparsed_frontmatter
+        .get("tags")
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|tag| tag.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    assert_eq!(reparsed_tags, tags);
+});
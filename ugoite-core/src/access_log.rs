@@ -0,0 +1,261 @@
+use anyhow::Result;
+use futures::TryStreamExt;
+use opendal::{EntryMode, Operator};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Mutex, OnceLock};
+
+use crate::entry;
+use crate::storage;
+
+const ACCESS_DIR: &str = "access_log";
+const EVENTS_DIR: &str = "events";
+const SUMMARY_FILE: &str = "summary.json";
+/// Number of access events recorded by a process that triggers a compaction
+/// into the summary.
+pub const COMPACTION_THRESHOLD: usize = 64;
+
+/// Access events recorded per space by this process since it last compacted.
+static RECORDED_SINCE_COMPACTION: OnceLock<Mutex<HashMap<String, usize>>> = OnceLock::new();
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AccessStats {
+    pub last_accessed_at: f64,
+    pub access_count: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct AccessSummary {
+    #[serde(default)]
+    entries: HashMap<String, AccessStats>,
+    #[serde(default)]
+    compacted_at: Option<f64>,
+    /// Event files already folded into `entries` that may not be deleted
+    /// yet. Compaction skips them, so a compaction that is repeated, runs
+    /// concurrently or stops before deleting its events counts nothing twice.
+    #[serde(default)]
+    compacted_events: BTreeSet<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct AccessEvent {
+    entry_id: String,
+    accessed_at: f64,
+}
+
+fn access_root(ws_path: &str) -> String {
+    format!("{}/{}", ws_path.trim_end_matches('/'), ACCESS_DIR)
+}
+
+fn events_root(ws_path: &str) -> String {
+    format!("{}/{}/", access_root(ws_path), EVENTS_DIR)
+}
+
+fn summary_path(ws_path: &str) -> String {
+    format!("{}/{}", access_root(ws_path), SUMMARY_FILE)
+}
+
+async fn read_summary(op: &Operator, ws_path: &str) -> Result<AccessSummary> {
+    let path = summary_path(ws_path);
+    if !op.exists(&path).await? {
+        return Ok(AccessSummary::default());
+    }
+    let bytes = op.read(&path).await?;
+    Ok(serde_json::from_slice(&bytes.to_vec())?)
+}
+
+/// Returns (file name, event) pairs for every pending event file, oldest first.
+async fn read_pending_events(op: &Operator, ws_path: &str) -> Result<Vec<(String, AccessEvent)>> {
+    let root = events_root(ws_path);
+    if !op.exists(&root).await? {
+        return Ok(Vec::new());
+    }
+
+    let mut events = Vec::new();
+    let mut lister = op.lister(&root).await?;
+    while let Some(item) = lister.try_next().await? {
+        if item.metadata().mode() != EntryMode::FILE || !item.name().ends_with(".json") {
+            continue;
+        }
+        let name = item.name().to_string();
+        let bytes = op.read(item.path()).await?;
+        // A torn or foreign file must not break listing; it is dropped on compaction.
+        match serde_json::from_slice::<AccessEvent>(&bytes.to_vec()) {
+            Ok(event) => events.push((name, event)),
+            Err(_) => events.push((
+                name,
+                AccessEvent {
+                    entry_id: String::new(),
+                    accessed_at: 0.0,
+                },
+            )),
+        }
+    }
+    events.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(events)
+}
+
+fn fold_event(entries: &mut HashMap<String, AccessStats>, event: &AccessEvent) {
+    if event.entry_id.is_empty() {
+        return;
+    }
+    let stats = entries.entry(event.entry_id.clone()).or_default();
+    stats.access_count += 1;
+    if event.accessed_at > stats.last_accessed_at {
+        stats.last_accessed_at = event.accessed_at;
+    }
+}
+
+/// Counts an event recorded for `ws_path`; `true` once
+/// [`COMPACTION_THRESHOLD`] events were recorded since the last compaction.
+fn count_recorded_event(ws_path: &str) -> bool {
+    let mut counts = RECORDED_SINCE_COMPACTION
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let count = counts
+        .entry(ws_path.trim_end_matches('/').to_string())
+        .or_default();
+    *count += 1;
+    *count >= COMPACTION_THRESHOLD
+}
+
+fn reset_recorded_events(ws_path: &str) {
+    RECORDED_SINCE_COMPACTION
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .remove(ws_path.trim_end_matches('/'));
+}
+
+/// Appends an access event for an entry.
+///
+/// Events are written as individual small objects so recording an access never
+/// reads or rewrites shared state. Each process counts the events it records
+/// and folds them into the summary with [`compact_access_log`] after every
+/// [`COMPACTION_THRESHOLD`] of them.
+pub async fn record_entry_access(op: &Operator, ws_path: &str, entry_id: &str) -> Result<()> {
+    let accessed_at = entry::now_ts();
+    let event = AccessEvent {
+        entry_id: entry_id.to_string(),
+        accessed_at,
+    };
+    let file_name = format!(
+        "{:020}-{}.json",
+        (accessed_at * 1_000_000.0) as i64,
+        uuid::Uuid::new_v4().simple()
+    );
+    op.write(
+        &format!("{}{}", events_root(ws_path), file_name),
+        serde_json::to_vec(&event)?,
+    )
+    .await?;
+
+    if count_recorded_event(ws_path) {
+        compact_access_log(op, ws_path).await?;
+    }
+    Ok(())
+}
+
+/// Folds pending access events into the summary and removes them.
+/// Returns the number of events that were compacted.
+///
+/// The summary is updated with a conditional write and remembers the events
+/// it folded until they are gone, so concurrent or repeated compactions
+/// count every event once.
+pub async fn compact_access_log(op: &Operator, ws_path: &str) -> Result<usize> {
+    reset_recorded_events(ws_path);
+    let pending = read_pending_events(op, ws_path).await?;
+    let Some(oldest) = pending.first().map(|(name, _)| name.clone()) else {
+        return Ok(0);
+    };
+    let listed: BTreeSet<String> = pending.iter().map(|(name, _)| name.clone()).collect();
+
+    let compacted_at = entry::now_ts();
+    let compacted = storage::update_json(op, &summary_path(ws_path), |current| {
+        let mut summary: AccessSummary = match current {
+            Some(value) => serde_json::from_value(value)?,
+            None => AccessSummary::default(),
+        };
+        let mut compacted = 0;
+        for (name, event) in &pending {
+            if summary.compacted_events.insert(name.clone()) {
+                fold_event(&mut summary.entries, event);
+                compacted += 1;
+            }
+        }
+        // Events are deleted once compacted, so one older than every pending
+        // event cannot be listed again.
+        summary
+            .compacted_events
+            .retain(|name| listed.contains(name) || *name > oldest);
+        summary.compacted_at = Some(compacted_at);
+        Ok((serde_json::to_value(&summary)?, compacted))
+    })
+    .await?;
+
+    let root = events_root(ws_path);
+    for name in &listed {
+        op.delete(&format!("{}{}", root, name)).await?;
+    }
+    Ok(compacted)
+}
+
+/// Returns access statistics per entry, including events not yet compacted.
+pub async fn load_access_stats(
+    op: &Operator,
+    ws_path: &str,
+) -> Result<HashMap<String, AccessStats>> {
+    let summary = read_summary(op, ws_path).await?;
+    let mut entries = summary.entries;
+    for (name, event) in read_pending_events(op, ws_path).await? {
+        if !summary.compacted_events.contains(&name) {
+            fold_event(&mut entries, &event);
+        }
+    }
+    Ok(entries)
+}
+
+fn stats_to_values(stats: Vec<(String, AccessStats)>, limit: usize) -> Vec<Value> {
+    stats
+        .into_iter()
+        .take(limit)
+        .map(|(entry_id, stats)| {
+            serde_json::json!({
+                "entry_id": entry_id,
+                "last_accessed_at": stats.last_accessed_at,
+                "access_count": stats.access_count,
+            })
+        })
+        .collect()
+}
+
+/// Lists entries ordered by most recent access.
+pub async fn list_recent_entries(op: &Operator, ws_path: &str, limit: usize) -> Result<Vec<Value>> {
+    let mut stats: Vec<(String, AccessStats)> =
+        load_access_stats(op, ws_path).await?.into_iter().collect();
+    stats.sort_by(|a, b| {
+        b.1.last_accessed_at
+            .total_cmp(&a.1.last_accessed_at)
+            .then_with(|| a.0.cmp(&b.0))
+    });
+    Ok(stats_to_values(stats, limit))
+}
+
+/// Lists entries ordered by access count, most recent access breaking ties.
+pub async fn list_frequent_entries(
+    op: &Operator,
+    ws_path: &str,
+    limit: usize,
+) -> Result<Vec<Value>> {
+    let mut stats: Vec<(String, AccessStats)> =
+        load_access_stats(op, ws_path).await?.into_iter().collect();
+    stats.sort_by(|a, b| {
+        b.1.access_count
+            .cmp(&a.1.access_count)
+            .then_with(|| b.1.last_accessed_at.total_cmp(&a.1.last_accessed_at))
+            .then_with(|| a.0.cmp(&b.0))
+    });
+    Ok(stats_to_values(stats, limit))
+}
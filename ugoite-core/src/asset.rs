@@ -3,13 +3,63 @@ use chrono::Utc;
 use futures::TryStreamExt;
 use opendal::{EntryMode, Operator};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
 use uuid::Uuid;
 
 use crate::entry;
+use crate::extraction;
 use crate::form;
-use crate::integrity::RealIntegrityProvider;
+use crate::integrity::{self, IntegrityError, IntegrityProvider, RealIntegrityProvider};
+use crate::space;
+use crate::storage;
 
-const ASSET_FORM_NAME: &str = "Assets";
+pub(crate) const ASSET_FORM_NAME: &str = "Assets";
+/// Part size for asset uploads; object stores switch to multipart uploads above it.
+const ASSET_WRITE_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+const ASSET_TYPES_FILE: &str = "asset_types.json";
+const ASSET_INTEGRITY_FILE: &str = "asset_integrity.json";
+pub(crate) const ASSET_LINK_PREFIX: &str = "ugoite://asset/";
+
+/// Content types known by extension: `(extension, declared type, type the
+/// magic bytes sniff as)`. Text formats have no signature to check.
+const DECLARED_TYPES: &[(&str, &str, Option<&str>)] = &[
+    ("png", "image/png", Some("image/png")),
+    ("jpg", "image/jpeg", Some("image/jpeg")),
+    ("jpeg", "image/jpeg", Some("image/jpeg")),
+    ("gif", "image/gif", Some("image/gif")),
+    ("webp", "image/webp", Some("image/webp")),
+    ("pdf", "application/pdf", Some("application/pdf")),
+    ("zip", "application/zip", Some("application/zip")),
+    (
+        "docx",
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        Some("application/zip"),
+    ),
+    (
+        "xlsx",
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        Some("application/zip"),
+    ),
+    (
+        "pptx",
+        "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        Some("application/zip"),
+    ),
+    ("gz", "application/gzip", Some("application/gzip")),
+    ("mp3", "audio/mpeg", Some("audio/mpeg")),
+    ("wav", "audio/wav", Some("audio/wav")),
+    ("ogg", "audio/ogg", Some("audio/ogg")),
+    ("m4a", "audio/mp4", Some("video/mp4")),
+    ("mp4", "video/mp4", Some("video/mp4")),
+    ("mov", "video/quicktime", Some("video/mp4")),
+    ("webm", "video/webm", Some("video/webm")),
+    ("md", "text/markdown", None),
+    ("txt", "text/plain", None),
+    ("csv", "text/csv", None),
+    ("json", "application/json", None),
+    ("svg", "image/svg+xml", None),
+];
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct AssetInfo {
@@ -18,6 +68,79 @@ pub struct AssetInfo {
     pub path: String,
     pub link: String,
     pub uploaded_at: String,
+    /// Content type implied by the file extension.
+    #[serde(default)]
+    pub declared_type: Option<String>,
+    /// Content type sniffed from the leading bytes.
+    #[serde(default)]
+    pub detected_type: Option<String>,
+    #[serde(default)]
+    pub type_mismatch: bool,
+}
+
+/// `assets` in settings.json. With `reject_type_mismatch` set, uploads whose
+/// bytes do not match their extension are refused instead of flagged.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct AssetSettings {
+    pub reject_type_mismatch: bool,
+}
+
+impl AssetSettings {
+    pub async fn for_space(op: &Operator, ws_path: &str) -> Result<Self> {
+        let settings = space::read_space_settings(op, ws_path).await?;
+        match settings.get("assets") {
+            Some(value) if value.is_object() => Ok(serde_json::from_value(value.clone())?),
+            _ => Ok(Self::default()),
+        }
+    }
+}
+
+/// Declared and sniffed content types recorded for an asset at upload.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AssetTypeCheck {
+    pub declared_type: Option<String>,
+    pub detected_type: Option<String>,
+    pub mismatch: bool,
+}
+
+/// Digest and signature recorded for an asset's bytes at upload.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AssetIntegrity {
+    /// Tagged checksum of the content, e.g. `blake3:{hex}`; records made
+    /// before tagging hold a bare SHA-256 hex digest.
+    pub checksum: String,
+    /// Space HMAC over the asset id, size and checksum.
+    pub signature: String,
+    pub size: u64,
+}
+
+impl AssetIntegrity {
+    fn compute<I: IntegrityProvider>(integrity: &I, asset_id: &str, content: &[u8]) -> Self {
+        let mut record = Self {
+            checksum: integrity.checksum_algorithm().checksum(content),
+            signature: String::new(),
+            size: content.len() as u64,
+        };
+        record.sign(integrity, asset_id);
+        record
+    }
+
+    fn sign<I: IntegrityProvider>(&mut self, integrity: &I, asset_id: &str) {
+        self.signature = integrity.signature(&format!(
+            "asset\n{}\n{}\n{}",
+            asset_id, self.size, self.checksum
+        ));
+    }
+}
+
+/// Bytes read from part of an asset. `total_size` is the size of the whole
+/// object so hosts can answer HTTP range requests with `Content-Range`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetRange {
+    pub data: Vec<u8>,
+    pub offset: u64,
+    pub total_size: u64,
 }
 
 fn asset_form_definition() -> serde_json::Value {
@@ -33,10 +156,156 @@ fn asset_form_definition() -> serde_json::Value {
     })
 }
 
-async fn ensure_asset_form(op: &Operator, ws_path: &str) -> Result<()> {
+pub(crate) async fn ensure_asset_form(op: &Operator, ws_path: &str) -> Result<()> {
     form::upsert_metadata_form(op, ws_path, &asset_form_definition()).await
 }
 
+/// Identifies content from its leading magic bytes. Executables and
+/// scripts are reported so they are flagged whatever they are named.
+pub fn sniff_content_type(content: &[u8]) -> Option<&'static str> {
+    let starts = |magic: &[u8]| content.starts_with(magic);
+    let detected = if starts(b"\x89PNG\r\n\x1a\n") {
+        "image/png"
+    } else if starts(b"\xff\xd8\xff") {
+        "image/jpeg"
+    } else if starts(b"GIF87a") || starts(b"GIF89a") {
+        "image/gif"
+    } else if starts(b"RIFF") && content.get(8..12) == Some(&b"WEBP"[..]) {
+        "image/webp"
+    } else if starts(b"RIFF") && content.get(8..12) == Some(&b"WAVE"[..]) {
+        "audio/wav"
+    } else if starts(b"%PDF-") {
+        "application/pdf"
+    } else if starts(b"PK\x03\x04") {
+        "application/zip"
+    } else if starts(b"\x1f\x8b") {
+        "application/gzip"
+    } else if starts(b"ID3") {
+        "audio/mpeg"
+    } else if starts(b"OggS") {
+        "audio/ogg"
+    } else if content.get(4..8) == Some(&b"ftyp"[..]) {
+        "video/mp4"
+    } else if starts(b"\x1a\x45\xdf\xa3") {
+        "video/webm"
+    } else if starts(b"\x7fELF") {
+        "application/x-executable"
+    } else if starts(b"MZ") {
+        "application/x-msdownload"
+    } else if [
+        b"\xfe\xed\xfa\xce",
+        b"\xfe\xed\xfa\xcf",
+        b"\xce\xfa\xed\xfe",
+        b"\xcf\xfa\xed\xfe",
+        b"\xca\xfe\xba\xbe",
+    ]
+    .iter()
+    .any(|magic| starts(*magic))
+    {
+        "application/x-mach-binary"
+    } else if starts(b"#!") {
+        "text/x-shellscript"
+    } else {
+        return None;
+    };
+    Some(detected)
+}
+
+/// Compares the type `filename`'s extension declares with what `content`
+/// sniffs as. Sniffed content must match the extension; extensions of
+/// binary formats must be backed by their signature.
+pub fn check_asset_type(filename: &str, content: &[u8]) -> AssetTypeCheck {
+    let extension = filename
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    let declared = DECLARED_TYPES.iter().find(|(ext, _, _)| *ext == extension);
+    let detected = sniff_content_type(content);
+    let mismatch = match (declared, detected) {
+        (Some((_, _, signature)), Some(detected)) => *signature != Some(detected),
+        (None, Some(_)) => true,
+        (Some((_, _, signature)), None) => signature.is_some(),
+        (None, None) => false,
+    };
+    AssetTypeCheck {
+        declared_type: declared.map(|(_, mime, _)| mime.to_string()),
+        detected_type: detected.map(str::to_string),
+        mismatch,
+    }
+}
+
+fn asset_types_path(ws_path: &str) -> String {
+    format!("{}/{}", ws_path.trim_end_matches('/'), ASSET_TYPES_FILE)
+}
+
+async fn read_asset_types(
+    op: &Operator,
+    ws_path: &str,
+) -> Result<BTreeMap<String, AssetTypeCheck>> {
+    let (value, _) = storage::read_json_versioned(op, &asset_types_path(ws_path)).await?;
+    Ok(match value {
+        Some(value) => serde_json::from_value(value)?,
+        None => BTreeMap::new(),
+    })
+}
+
+fn asset_integrity_path(ws_path: &str) -> String {
+    format!("{}/{}", ws_path.trim_end_matches('/'), ASSET_INTEGRITY_FILE)
+}
+
+/// Integrity records of every asset that has one, by asset id.
+pub async fn read_asset_integrity(
+    op: &Operator,
+    ws_path: &str,
+) -> Result<BTreeMap<String, AssetIntegrity>> {
+    let (value, _) = storage::read_json_versioned(op, &asset_integrity_path(ws_path)).await?;
+    Ok(match value {
+        Some(value) => serde_json::from_value(value)?,
+        None => BTreeMap::new(),
+    })
+}
+
+async fn update_asset_integrity(
+    op: &Operator,
+    ws_path: &str,
+    records: &[(String, Option<AssetIntegrity>)],
+) -> Result<()> {
+    storage::update_json(op, &asset_integrity_path(ws_path), |current| {
+        let mut doc: BTreeMap<String, AssetIntegrity> = match current {
+            Some(value) => serde_json::from_value(value)?,
+            None => BTreeMap::new(),
+        };
+        for (asset_id, record) in records {
+            match record {
+                Some(record) => doc.insert(asset_id.clone(), record.clone()),
+                None => doc.remove(asset_id),
+            };
+        }
+        Ok((serde_json::to_value(&doc)?, ()))
+    })
+    .await
+}
+
+async fn update_asset_types(
+    op: &Operator,
+    ws_path: &str,
+    asset_id: &str,
+    check: Option<&AssetTypeCheck>,
+) -> Result<()> {
+    storage::update_json(op, &asset_types_path(ws_path), |current| {
+        let mut types: BTreeMap<String, AssetTypeCheck> = match current {
+            Some(value) => serde_json::from_value(value)?,
+            None => BTreeMap::new(),
+        };
+        match check {
+            Some(check) => types.insert(asset_id.to_string(), check.clone()),
+            None => types.remove(asset_id),
+        };
+        Ok((serde_json::to_value(&types)?, ()))
+    })
+    .await
+}
+
 fn space_id_from_ws_path(ws_path: &str) -> String {
     ws_path
         .trim_end_matches('/')
@@ -58,6 +327,21 @@ pub async fn save_asset(
     filename: &str,
     content: &[u8],
 ) -> Result<AssetInfo> {
+    let type_check = check_asset_type(filename, content);
+    if type_check.mismatch
+        && AssetSettings::for_space(op, ws_path)
+            .await?
+            .reject_type_mismatch
+    {
+        return Err(anyhow!(
+            "Asset content ({}) does not match its name {}",
+            type_check
+                .detected_type
+                .as_deref()
+                .unwrap_or("unrecognized"),
+            filename
+        ));
+    }
     ensure_asset_form(op, ws_path).await?;
     let asset_id = Uuid::new_v4().to_string();
     let safe_name = if filename.is_empty() {
@@ -67,9 +351,11 @@ pub async fn save_asset(
     };
     let relative_path = format!("assets/{}_{}", asset_id, safe_name);
     let asset_path = format!("{}/{}", ws_path, relative_path);
-    let link = format!("ugoite://asset/{asset_id}");
+    let link = format!("{}{}", ASSET_LINK_PREFIX, asset_id);
     let uploaded_at = Utc::now().to_rfc3339();
-    op.write(&asset_path, content.to_vec()).await?;
+    op.write_with(&asset_path, content.to_vec())
+        .chunk(ASSET_WRITE_CHUNK_SIZE)
+        .await?;
 
     let space_id = space_id_from_ws_path(ws_path);
     let integrity = RealIntegrityProvider::from_space(op, &space_id).await?;
@@ -85,6 +371,19 @@ pub async fn save_asset(
         }
         return Err(error);
     }
+    update_asset_types(op, ws_path, &asset_id, Some(&type_check)).await?;
+    let record = AssetIntegrity::compute(&integrity, &asset_id, content);
+    update_asset_integrity(op, ws_path, &[(asset_id.clone(), Some(record))]).await?;
+    let ctx = extraction::AssetContext {
+        ws_path,
+        asset_id: &asset_id,
+        filename: &safe_name,
+        content_type: type_check
+            .declared_type
+            .as_deref()
+            .or(type_check.detected_type.as_deref()),
+    };
+    extraction::extract_asset_text(op, &ctx, content).await?;
 
     Ok(AssetInfo {
         id: asset_id,
@@ -92,6 +391,9 @@ pub async fn save_asset(
         path: relative_path,
         link,
         uploaded_at,
+        declared_type: type_check.declared_type,
+        detected_type: type_check.detected_type,
+        type_mismatch: type_check.mismatch,
     })
 }
 
@@ -126,6 +428,7 @@ pub async fn list_assets(op: &Operator, ws_path: &str) -> Result<Vec<AssetInfo>>
         return Ok(vec![]);
     }
 
+    let types = read_asset_types(op, ws_path).await?;
     let mut lister = op.lister(&assets_path).await?;
     let mut assets = Vec::new();
 
@@ -141,12 +444,16 @@ pub async fn list_assets(op: &Operator, ws_path: &str) -> Result<Vec<AssetInfo>>
                     .get(id)
                     .cloned()
                     .unwrap_or((format!("ugoite://asset/{id}"), String::new()));
+                let check = types.get(id);
                 assets.push(AssetInfo {
                     id: id.to_string(),
                     name: original.to_string(),
                     path: format!("assets/{}", name),
                     link,
                     uploaded_at,
+                    declared_type: check.and_then(|c| c.declared_type.clone()),
+                    detected_type: check.and_then(|c| c.detected_type.clone()),
+                    type_mismatch: check.is_some_and(|c| c.mismatch),
                 });
             }
         }
@@ -155,9 +462,312 @@ pub async fn list_assets(op: &Operator, ws_path: &str) -> Result<Vec<AssetInfo>>
     Ok(assets)
 }
 
+/// File name an asset was uploaded under, from the `name` field of its
+/// entry in the Assets form. A point lookup on that one entry.
+async fn recorded_asset_name(
+    op: &Operator,
+    ws_path: &str,
+    asset_id: &str,
+) -> Result<Option<String>> {
+    let Ok(form_def) = form::read_form_definition(op, ws_path, ASSET_FORM_NAME).await else {
+        return Ok(None);
+    };
+    let rows = entry::list_projected_entry_rows(
+        op,
+        ws_path,
+        ASSET_FORM_NAME,
+        &form_def,
+        Some(asset_id),
+        &["name".to_string()],
+    )
+    .await?;
+    Ok(rows
+        .into_iter()
+        .filter(|row| row.entry_id == asset_id)
+        .max_by_key(|row| row.updated_at)
+        .and_then(|row| row.fields.get("name")?.as_str().map(str::to_string)))
+}
+
+/// Path of an asset's file, `assets/{asset_id}_{name}` with the name from its
+/// asset record, checked with a single stat. The assets directory is listed
+/// only for assets without a record (stored before uploads created one) or
+/// whose recorded name does not lead to a file.
+async fn find_asset_file(op: &Operator, ws_path: &str, asset_id: &str) -> Result<Option<String>> {
+    if let Some(name) = recorded_asset_name(op, ws_path, asset_id).await? {
+        let path = format!("{}/assets/{}_{}", ws_path, asset_id, name);
+        match op.stat(&path).await {
+            Ok(meta) if meta.mode() == EntryMode::FILE => return Ok(Some(path)),
+            Ok(_) => {}
+            Err(err) if err.kind() == opendal::ErrorKind::NotFound => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+    list_asset_file(op, ws_path, asset_id).await
+}
+
+async fn list_asset_file(op: &Operator, ws_path: &str, asset_id: &str) -> Result<Option<String>> {
+    let assets_path = format!("{}/assets/", ws_path);
+    if !op.exists(&assets_path).await? {
+        return Ok(None);
+    }
+    let prefix = format!("{}_", asset_id);
+    let mut lister = op.lister(&assets_path).await?;
+    while let Some(entry) = lister.try_next().await? {
+        if entry.metadata().mode() != EntryMode::FILE {
+            continue;
+        }
+        let name = entry.name().split('/').next_back().unwrap_or("");
+        if name.starts_with(&prefix) {
+            return Ok(Some(format!("{}{}", assets_path, name)));
+        }
+    }
+    Ok(None)
+}
+
+/// Reads `length` bytes of an asset starting at `offset`, or through the end
+/// when `length` is `None`. Only the requested range is fetched from storage;
+/// ranges past the end are clamped, and an offset past the end is an error.
+pub async fn read_asset_range(
+    op: &Operator,
+    ws_path: &str,
+    asset_id: &str,
+    offset: u64,
+    length: Option<u64>,
+) -> Result<AssetRange> {
+    let path = find_asset_file(op, ws_path, asset_id)
+        .await?
+        .ok_or_else(|| anyhow!("Asset {} not found", asset_id))?;
+    let total_size = op.stat(&path).await?.content_length();
+    if offset > total_size || (offset == total_size && total_size > 0) {
+        return Err(anyhow!(
+            "Range starting at {} is not satisfiable for asset {} of {} bytes",
+            offset,
+            asset_id,
+            total_size
+        ));
+    }
+    let end = match length {
+        Some(length) => offset.saturating_add(length).min(total_size),
+        None => total_size,
+    };
+    let data = if end > offset {
+        op.read_with(&path).range(offset..end).await?.to_vec()
+    } else {
+        Vec::new()
+    };
+    Ok(AssetRange {
+        data,
+        offset,
+        total_size,
+    })
+}
+
+/// [`read_asset_range`] that, in strict mode, first reads the whole asset
+/// and checks it against the digest and signature recorded at upload,
+/// failing with [`IntegrityError`] on a mismatch or a missing record.
+/// `strict` overrides the space's `integrity.strict_reads` setting.
+pub async fn read_asset_range_verified<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    asset_id: &str,
+    offset: u64,
+    length: Option<u64>,
+    integrity: &I,
+    strict: Option<bool>,
+) -> Result<AssetRange> {
+    let strict = match strict {
+        Some(strict) => strict,
+        None => integrity::strict_reads(&space::read_space_settings(op, ws_path).await?),
+    };
+    if strict {
+        verify_asset(op, ws_path, asset_id, integrity).await?;
+    }
+    read_asset_range(op, ws_path, asset_id, offset, length).await
+}
+
+/// Checks an asset's bytes against the digest and signature recorded at
+/// upload and returns the record.
+pub async fn verify_asset<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    asset_id: &str,
+    integrity: &I,
+) -> Result<AssetIntegrity> {
+    let path = find_asset_file(op, ws_path, asset_id)
+        .await?
+        .ok_or_else(|| anyhow!("Asset {} not found", asset_id))?;
+    let recorded = read_asset_integrity(op, ws_path)
+        .await?
+        .remove(asset_id)
+        .ok_or_else(|| IntegrityError::AssetUnrecorded {
+            asset_id: asset_id.to_string(),
+        })?;
+    let content = op.read(&path).await?.to_vec();
+    if !integrity::ChecksumAlgorithm::matches(&recorded.checksum, &content)
+        || content.len() as u64 != recorded.size
+    {
+        return Err(IntegrityError::AssetChecksumMismatch {
+            asset_id: asset_id.to_string(),
+            expected: recorded.checksum,
+            actual: AssetIntegrity::compute(integrity, asset_id, &content).checksum,
+        }
+        .into());
+    }
+    let mut expected = recorded.clone();
+    expected.sign(integrity, asset_id);
+    if expected.signature != recorded.signature {
+        return Err(IntegrityError::AssetSignatureMismatch {
+            asset_id: asset_id.to_string(),
+        }
+        .into());
+    }
+    Ok(recorded)
+}
+
+/// Signs every recorded asset digest again with `integrity`, keeping the
+/// digests. Returns the number of assets signed.
+pub(crate) async fn resign_asset_integrity<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    integrity: &I,
+) -> Result<usize> {
+    let records: Vec<(String, Option<AssetIntegrity>)> = read_asset_integrity(op, ws_path)
+        .await?
+        .into_iter()
+        .map(|(asset_id, mut record)| {
+            record.sign(integrity, &asset_id);
+            (asset_id, Some(record))
+        })
+        .collect();
+    if !records.is_empty() {
+        update_asset_integrity(op, ws_path, &records).await?;
+    }
+    Ok(records.len())
+}
+
+/// Records digests and signatures for assets uploaded before uploads were
+/// recorded. Returns the ids recorded; existing records are left as they are.
+pub async fn backfill_asset_integrity<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    integrity: &I,
+) -> Result<Vec<String>> {
+    let recorded = read_asset_integrity(op, ws_path).await?;
+    let mut records = Vec::new();
+    for asset in list_assets(op, ws_path).await? {
+        if recorded.contains_key(&asset.id) {
+            continue;
+        }
+        let content = op
+            .read(&format!("{}/{}", ws_path.trim_end_matches('/'), asset.path))
+            .await?
+            .to_vec();
+        let record = AssetIntegrity::compute(integrity, &asset.id, &content);
+        records.push((asset.id, Some(record)));
+    }
+    if !records.is_empty() {
+        update_asset_integrity(op, ws_path, &records).await?;
+    }
+    Ok(records.into_iter().map(|(id, _)| id).collect())
+}
+
+/// Asset id of an `asset_ref` value: a bare id or a `ugoite://asset/{id}`
+/// link.
+pub fn asset_ref_id(value: &str) -> Option<String> {
+    let value = value.trim();
+    let id = value.strip_prefix(ASSET_LINK_PREFIX).unwrap_or(value);
+    let valid = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid.then(|| id.to_string())
+}
+
+/// Names of the `asset_ref` fields of a form.
+pub(crate) fn asset_ref_fields(form_def: &Value) -> Vec<String> {
+    form_def
+        .get("fields")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .filter(|(_, def)| def.get("type").and_then(Value::as_str) == Some("asset_ref"))
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+/// `(field, asset id)` for each `asset_ref` field of `fields` that is set.
+pub(crate) fn referenced_asset_ids(form_def: &Value, fields: &Value) -> Vec<(String, String)> {
+    asset_ref_fields(form_def)
+        .into_iter()
+        .filter_map(|name| {
+            let id = fields
+                .get(&name)
+                .and_then(Value::as_str)
+                .and_then(asset_ref_id)?;
+            Some((name, id))
+        })
+        .collect()
+}
+
+/// Fails when an `asset_ref` field of `fields` names an asset that does not
+/// exist in the space.
+pub(crate) async fn validate_asset_refs(
+    op: &Operator,
+    ws_path: &str,
+    form_def: &Value,
+    fields: &Value,
+) -> Result<()> {
+    for (name, id) in referenced_asset_ids(form_def, fields) {
+        if find_asset_file(op, ws_path, &id).await?.is_none() {
+            return Err(anyhow!("Asset {} not found (field '{}')", id, name));
+        }
+    }
+    Ok(())
+}
+
+/// Resolved metadata of the assets the `asset_ref` fields of `fields` point
+/// at, keyed by field name. Assets removed since the write resolve to null.
+pub async fn resolve_asset_refs(
+    op: &Operator,
+    ws_path: &str,
+    form_def: &Value,
+    fields: &Value,
+) -> Result<Value> {
+    let references = referenced_asset_ids(form_def, fields);
+    let mut resolved = Map::new();
+    if references.is_empty() {
+        return Ok(Value::Object(resolved));
+    }
+    let types = read_asset_types(op, ws_path).await?;
+    for (name, id) in references {
+        let Some(path) = find_asset_file(op, ws_path, &id).await? else {
+            resolved.insert(name, Value::Null);
+            continue;
+        };
+        let file_name = path.rsplit('/').next().unwrap_or_default();
+        let original = file_name
+            .strip_prefix(&format!("{}_", id))
+            .unwrap_or(file_name);
+        let check = types.get(&id);
+        let content_type = check.and_then(|c| c.declared_type.clone().or(c.detected_type.clone()));
+        resolved.insert(
+            name,
+            serde_json::json!({
+                "id": id,
+                "name": original,
+                "size": op.stat(&path).await?.content_length(),
+                "content_type": content_type,
+                "link": format!("{}{}", ASSET_LINK_PREFIX, id),
+            }),
+        );
+    }
+    Ok(Value::Object(resolved))
+}
+
 async fn is_asset_referenced(op: &Operator, ws_path: &str, asset_id: &str) -> Result<bool> {
     let rows = entry::list_entry_rows(op, ws_path).await?;
-    for (_form_name, row) in rows {
+    let mut form_defs: HashMap<String, Value> = HashMap::new();
+    for (form_name, row) in rows {
         if row.deleted {
             continue;
         }
@@ -168,6 +778,18 @@ async fn is_asset_referenced(op: &Operator, ws_path: &str, asset_id: &str) -> Re
         {
             return Ok(true);
         }
+        if !form_defs.contains_key(&form_name) {
+            let form_def = form::read_form_definition(op, ws_path, &form_name)
+                .await
+                .unwrap_or(Value::Null);
+            form_defs.insert(form_name.clone(), form_def);
+        }
+        if referenced_asset_ids(&form_defs[&form_name], &row.fields)
+            .iter()
+            .any(|(_, id)| id == asset_id)
+        {
+            return Ok(true);
+        }
     }
 
     Ok(false)
@@ -202,6 +824,9 @@ pub async fn delete_asset(op: &Operator, ws_path: &str, asset_id: &str) -> Resul
         return Err(anyhow!("Asset {} not found", asset_id));
     }
 
+    update_asset_types(op, ws_path, asset_id, None).await?;
+    update_asset_integrity(op, ws_path, &[(asset_id.to_string(), None)]).await?;
+    extraction::delete_asset_text(op, ws_path, asset_id).await?;
     if let Err(error) = entry::delete_entry(op, ws_path, asset_id, false).await {
         eprintln!(
             "failed to cleanup asset metadata entry after file delete (asset_id={}, ws_path={}): {}",
@@ -0,0 +1,85 @@
+use anyhow::Result;
+use futures::TryStreamExt;
+use opendal::{EntryMode, Operator};
+use serde_json::Value;
+
+use crate::entry;
+
+const AUDIT_DIR: &str = "audit";
+
+fn audit_root(ws_path: &str) -> String {
+    format!("{}/{}/", ws_path.trim_end_matches('/'), AUDIT_DIR)
+}
+
+/// Appends an event to the space audit log and returns it.
+///
+/// Each event is its own object so concurrent writers never contend, and the
+/// log is never rewritten: not even redaction removes audit events. Callers
+/// must keep sensitive content out of `details`.
+pub async fn record_audit_event(
+    op: &Operator,
+    ws_path: &str,
+    action: &str,
+    actor: &str,
+    details: Value,
+) -> Result<Value> {
+    let root = audit_root(ws_path);
+    if !op.exists(&root).await? {
+        op.create_dir(&root).await?;
+    }
+
+    let recorded_at = entry::now_ts();
+    let event_id = uuid::Uuid::new_v4().simple().to_string();
+    let event = serde_json::json!({
+        "id": event_id,
+        "action": action,
+        "actor": actor,
+        "recorded_at": recorded_at,
+        "details": details,
+    });
+    let file_name = format!(
+        "{:020}-{}.json",
+        (recorded_at * 1_000_000.0) as i64,
+        event_id
+    );
+    op.write(
+        &format!("{}{}", root, file_name),
+        serde_json::to_vec_pretty(&event)?,
+    )
+    .await?;
+    Ok(event)
+}
+
+/// Lists audit events oldest first, optionally filtered by action.
+pub async fn list_audit_events(
+    op: &Operator,
+    ws_path: &str,
+    action: Option<&str>,
+) -> Result<Vec<Value>> {
+    let root = audit_root(ws_path);
+    if !op.exists(&root).await? {
+        return Ok(Vec::new());
+    }
+
+    let mut paths = Vec::new();
+    let mut lister = op.lister(&root).await?;
+    while let Some(item) = lister.try_next().await? {
+        if item.metadata().mode() == EntryMode::FILE && item.name().ends_with(".json") {
+            paths.push(item.path().to_string());
+        }
+    }
+    paths.sort();
+
+    let mut events = Vec::new();
+    for path in paths {
+        let event: Value = serde_json::from_slice(&op.read(&path).await?.to_vec())?;
+        let matches = match action {
+            Some(action) => event.get("action").and_then(|v| v.as_str()) == Some(action),
+            None => true,
+        };
+        if matches {
+            events.push(event);
+        }
+    }
+    Ok(events)
+}
@@ -0,0 +1,289 @@
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose, Engine as _};
+use opendal::Operator;
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::entry::{self, RevisionRow};
+use crate::storage;
+
+const AUTHOR_KEYS_FILE: &str = "author_keys.json";
+const SIGNATURES_DIR: &str = "revision_signatures";
+const SIGNING_DOMAIN: &str = "ugoite-revision-signature/1";
+pub const AUTHOR_KEY_ALGORITHM: &str = "ed25519";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AuthorKey {
+    pub key_id: String,
+    pub algorithm: String,
+    /// Base64-encoded raw public key.
+    pub public_key: String,
+    pub registered_at: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RevisionSignature {
+    pub author: String,
+    pub key_id: String,
+    /// Base64-encoded signature over [`revision_signing_payload`].
+    pub signature: String,
+    pub signed_at: f64,
+}
+
+/// `author_keys.json`: author -> keys, oldest first. Keys are never removed
+/// so revisions signed with a rotated key still verify.
+type AuthorKeysDoc = BTreeMap<String, Vec<AuthorKey>>;
+/// `revision_signatures/{entry_id}.json`: revision_id -> signature.
+type SignaturesDoc = BTreeMap<String, RevisionSignature>;
+
+fn keys_path(ws_path: &str) -> String {
+    format!("{}/{}", ws_path.trim_end_matches('/'), AUTHOR_KEYS_FILE)
+}
+
+fn signatures_path(ws_path: &str, entry_id: &str) -> String {
+    format!(
+        "{}/{}/{}.json",
+        ws_path.trim_end_matches('/'),
+        SIGNATURES_DIR,
+        entry_id
+    )
+}
+
+fn parse_doc<T: Default + serde::de::DeserializeOwned>(value: Option<Value>) -> Result<T> {
+    Ok(match value {
+        Some(value) => serde_json::from_value(value)?,
+        None => T::default(),
+    })
+}
+
+fn key_id_for(public_key: &[u8]) -> String {
+    hex::encode(&Sha256::digest(public_key)[..8])
+}
+
+/// Registers an Ed25519 public key (base64, 32 raw bytes) for `author` in
+/// this space. Registering a key again returns the existing record.
+pub async fn register_author_key(
+    op: &Operator,
+    ws_path: &str,
+    author: &str,
+    public_key_b64: &str,
+) -> Result<AuthorKey> {
+    if author.is_empty() {
+        return Err(anyhow!("Author is required"));
+    }
+    let public_key = general_purpose::STANDARD
+        .decode(public_key_b64)
+        .map_err(|e| anyhow!("Invalid public key encoding: {}", e))?;
+    if public_key.len() != 32 {
+        return Err(anyhow!(
+            "Ed25519 public keys are 32 bytes, got {}",
+            public_key.len()
+        ));
+    }
+    let key = AuthorKey {
+        key_id: key_id_for(&public_key),
+        algorithm: AUTHOR_KEY_ALGORITHM.to_string(),
+        public_key: general_purpose::STANDARD.encode(&public_key),
+        registered_at: entry::now_ts(),
+    };
+    storage::update_json(op, &keys_path(ws_path), |current| {
+        let mut doc: AuthorKeysDoc = parse_doc(current)?;
+        if let Some((owner, _)) = doc
+            .iter()
+            .find(|(owner, keys)| *owner != author && keys.iter().any(|k| k.key_id == key.key_id))
+        {
+            return Err(anyhow!("Key {} is registered to {}", key.key_id, owner));
+        }
+        let keys = doc.entry(author.to_string()).or_default();
+        let registered = match keys.iter().find(|k| k.key_id == key.key_id) {
+            Some(existing) => existing.clone(),
+            None => {
+                keys.push(key.clone());
+                key.clone()
+            }
+        };
+        Ok((serde_json::to_value(&doc)?, registered))
+    })
+    .await
+}
+
+/// Keys registered for `author`, oldest first.
+pub async fn list_author_keys(
+    op: &Operator,
+    ws_path: &str,
+    author: &str,
+) -> Result<Vec<AuthorKey>> {
+    let (value, _) = storage::read_json_versioned(op, &keys_path(ws_path)).await?;
+    let mut doc: AuthorKeysDoc = parse_doc(value)?;
+    Ok(doc.remove(author).unwrap_or_default())
+}
+
+async fn find_key(
+    op: &Operator,
+    ws_path: &str,
+    key_id: &str,
+) -> Result<Option<(String, AuthorKey)>> {
+    let (value, _) = storage::read_json_versioned(op, &keys_path(ws_path)).await?;
+    let doc: AuthorKeysDoc = parse_doc(value)?;
+    Ok(doc.into_iter().find_map(|(author, keys)| {
+        keys.into_iter()
+            .find(|k| k.key_id == key_id)
+            .map(|key| (author, key))
+    }))
+}
+
+fn signing_payload(space_id: &str, revision: &RevisionRow) -> String {
+    format!(
+        "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}",
+        SIGNING_DOMAIN,
+        space_id,
+        revision.entry_id,
+        revision.revision_id,
+        revision.parent_revision_id.as_deref().unwrap_or(""),
+        revision.author,
+        revision.markdown_checksum,
+        revision.integrity.signature
+    )
+}
+
+async fn load_revision(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+    revision_id: &str,
+) -> Result<RevisionRow> {
+    let revision = entry::get_entry_revision(op, ws_path, entry_id, revision_id).await?;
+    Ok(serde_json::from_value(revision)?)
+}
+
+/// The bytes an author signs for a revision: the revision's identity,
+/// author, content checksum and the space's HMAC signature, so the author
+/// signature vouches for exactly what the server recorded.
+pub async fn revision_signing_payload(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+    revision_id: &str,
+) -> Result<String> {
+    let ws_path = ws_path.trim_end_matches('/');
+    let space_id = ws_path.rsplit('/').next().unwrap_or(ws_path);
+    let revision = load_revision(op, ws_path, entry_id, revision_id).await?;
+    Ok(signing_payload(space_id, &revision))
+}
+
+fn verify_signature(key: &AuthorKey, payload: &str, signature_b64: &str) -> Result<bool> {
+    let public_key = general_purpose::STANDARD.decode(&key.public_key)?;
+    let Ok(signature) = general_purpose::STANDARD.decode(signature_b64) else {
+        return Ok(false);
+    };
+    Ok(UnparsedPublicKey::new(&ED25519, &public_key)
+        .verify(payload.as_bytes(), &signature)
+        .is_ok())
+}
+
+/// Revisions of `entry_id` holding an author signature.
+pub(crate) async fn signed_revision_ids(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+) -> Result<BTreeSet<String>> {
+    let (value, _) = storage::read_json_versioned(op, &signatures_path(ws_path, entry_id)).await?;
+    let signatures: SignaturesDoc = parse_doc(value)?;
+    Ok(signatures.into_keys().collect())
+}
+
+/// Stores an author's signature for a revision they wrote. The signature is
+/// checked before it is kept, and the key must belong to the revision's
+/// author.
+pub async fn attach_revision_signature(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+    revision_id: &str,
+    key_id: &str,
+    signature_b64: &str,
+) -> Result<RevisionSignature> {
+    let ws_path = ws_path.trim_end_matches('/');
+    let space_id = ws_path.rsplit('/').next().unwrap_or(ws_path);
+    let (author, key) = find_key(op, ws_path, key_id)
+        .await?
+        .ok_or_else(|| anyhow!("Unknown author key: {}", key_id))?;
+    let revision = load_revision(op, ws_path, entry_id, revision_id).await?;
+    if revision.author != author {
+        return Err(anyhow!(
+            "Revision {} was written by {}, not {}",
+            revision_id,
+            revision.author,
+            author
+        ));
+    }
+    if !verify_signature(&key, &signing_payload(space_id, &revision), signature_b64)? {
+        return Err(anyhow!("Signature does not match revision {}", revision_id));
+    }
+    let signature = RevisionSignature {
+        author,
+        key_id: key.key_id,
+        signature: signature_b64.to_string(),
+        signed_at: entry::now_ts(),
+    };
+    storage::update_json(op, &signatures_path(ws_path, entry_id), |current| {
+        let mut doc: SignaturesDoc = parse_doc(current)?;
+        doc.insert(revision_id.to_string(), signature.clone());
+        Ok((serde_json::to_value(&doc)?, ()))
+    })
+    .await?;
+    Ok(signature)
+}
+
+/// Checks a revision's author signature against the author's registered key
+/// and the revision as currently stored. Returns `{"signed", "valid",
+/// "author", "key_id", "reason"}`; `reason` explains an invalid result.
+pub async fn verify_revision_author(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+    revision_id: &str,
+) -> Result<Value> {
+    let ws_path = ws_path.trim_end_matches('/');
+    let space_id = ws_path.rsplit('/').next().unwrap_or(ws_path);
+    let revision = load_revision(op, ws_path, entry_id, revision_id).await?;
+    let mut report = serde_json::json!({
+        "entry_id": entry_id,
+        "revision_id": revision_id,
+        "author": revision.author,
+        "signed": false,
+        "valid": false,
+        "key_id": Value::Null,
+        "reason": Value::Null,
+    });
+
+    let (value, _) = storage::read_json_versioned(op, &signatures_path(ws_path, entry_id)).await?;
+    let mut signatures: SignaturesDoc = parse_doc(value)?;
+    let Some(signature) = signatures.remove(revision_id) else {
+        report["reason"] = serde_json::json!("unsigned");
+        return Ok(report);
+    };
+    report["signed"] = serde_json::json!(true);
+    report["key_id"] = serde_json::json!(signature.key_id);
+
+    let reason = match find_key(op, ws_path, &signature.key_id).await? {
+        None => Some("unknown_key"),
+        Some((author, _)) if author != revision.author || author != signature.author => {
+            Some("author_mismatch")
+        }
+        Some((_, key)) => {
+            let payload = signing_payload(space_id, &revision);
+            if verify_signature(&key, &payload, &signature.signature)? {
+                None
+            } else {
+                Some("bad_signature")
+            }
+        }
+    };
+    report["valid"] = serde_json::json!(reason.is_none());
+    report["reason"] = serde_json::json!(reason);
+    Ok(report)
+}
@@ -0,0 +1,242 @@
+use anyhow::{anyhow, Result};
+use futures::TryStreamExt;
+use opendal::{EntryMode, Operator};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::entry;
+use crate::storage;
+
+const BOARDS_DIR: &str = "boards";
+/// Position digits, in ascending byte order.
+const DIGITS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Placement {
+    pub column: String,
+    pub position: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct BoardDoc {
+    #[serde(default)]
+    entries: HashMap<String, Placement>,
+    #[serde(default)]
+    updated_at: f64,
+}
+
+fn digit_value(byte: u8) -> usize {
+    DIGITS.iter().position(|d| *d == byte).unwrap_or(0)
+}
+
+/// Midpoint of two fractional digit strings, with `b = None` meaning 1.0.
+/// Inputs never end in `0`, and neither does the result, so there is always
+/// room for another key between any two.
+fn midpoint(a: &[u8], b: Option<&[u8]>) -> Vec<u8> {
+    if let Some(b) = b {
+        let mut n = 0;
+        while n < b.len() && a.get(n).copied().unwrap_or(DIGITS[0]) == b[n] {
+            n += 1;
+        }
+        if n > 0 {
+            let mut key = b[..n].to_vec();
+            key.extend(midpoint(a.get(n..).unwrap_or_default(), Some(&b[n..])));
+            return key;
+        }
+    }
+    let low = a.first().map(|d| digit_value(*d)).unwrap_or(0);
+    let high = b
+        .and_then(|b| b.first())
+        .map(|d| digit_value(*d))
+        .unwrap_or(DIGITS.len());
+    if high - low > 1 {
+        return vec![DIGITS[(low + high).div_ceil(2)]];
+    }
+    match b {
+        Some(b) if b.len() > 1 => vec![b[0]],
+        _ => {
+            let mut key = vec![DIGITS[low]];
+            key.extend(midpoint(a.get(1..).unwrap_or_default(), None));
+            key
+        }
+    }
+}
+
+/// Returns a position key sorting strictly between `before` and `after`
+/// (either may be open), so moving one card never renumbers the others.
+pub fn position_between(before: Option<&str>, after: Option<&str>) -> Result<String> {
+    let low = before.unwrap_or("");
+    for key in [before, after].into_iter().flatten() {
+        if key.is_empty() || key.ends_with('0') || !key.bytes().all(|b| DIGITS.contains(&b)) {
+            return Err(anyhow!("Invalid position key: {}", key));
+        }
+    }
+    if let Some(high) = after {
+        if low >= high {
+            return Err(anyhow!("Position {} is not before {}", low, high));
+        }
+    }
+    let key = midpoint(low.as_bytes(), after.map(str::as_bytes));
+    Ok(String::from_utf8(key)?)
+}
+
+fn board_path(ws_path: &str, board: &str) -> Result<String> {
+    if board.is_empty() || board.contains('/') || board.starts_with('.') {
+        return Err(anyhow!("Invalid board name: {}", board));
+    }
+    Ok(format!(
+        "{}/{}/{}.json",
+        ws_path.trim_end_matches('/'),
+        BOARDS_DIR,
+        board
+    ))
+}
+
+async fn read_board(op: &Operator, path: &str) -> Result<BoardDoc> {
+    let (value, _) = storage::read_json_versioned(op, path).await?;
+    Ok(match value {
+        Some(value) => serde_json::from_value(value)?,
+        None => BoardDoc::default(),
+    })
+}
+
+async fn update_board<F, T>(op: &Operator, ws_path: &str, board: &str, update: F) -> Result<T>
+where
+    F: Fn(&mut BoardDoc) -> Result<T>,
+{
+    storage::update_json(op, &board_path(ws_path, board)?, |current| {
+        let mut doc: BoardDoc = match current {
+            Some(value) => serde_json::from_value(value)?,
+            None => BoardDoc::default(),
+        };
+        let result = update(&mut doc)?;
+        doc.updated_at = entry::now_ts();
+        Ok((serde_json::to_value(&doc)?, result))
+    })
+    .await
+}
+
+/// Cards of one column in display order as `(entry_id, position)`.
+fn column_cards<'a>(doc: &'a BoardDoc, column: &str) -> Vec<(&'a str, &'a str)> {
+    let mut cards: Vec<(&str, &str)> = doc
+        .entries
+        .iter()
+        .filter(|(_, placement)| placement.column == column)
+        .map(|(id, placement)| (id.as_str(), placement.position.as_str()))
+        .collect();
+    cards.sort_by(|a, b| a.1.cmp(b.1).then_with(|| a.0.cmp(b.0)));
+    cards
+}
+
+/// Places `entry_id` in `column` of `board`, directly after `after_id`, or at
+/// the top of the column when `after_id` is `None`. Only the moved entry's
+/// placement changes; entries themselves are not rewritten.
+pub async fn move_entry(
+    op: &Operator,
+    ws_path: &str,
+    board: &str,
+    column: &str,
+    entry_id: &str,
+    after_id: Option<&str>,
+) -> Result<Placement> {
+    if after_id == Some(entry_id) {
+        return Err(anyhow!("Cannot place {} after itself", entry_id));
+    }
+    update_board(op, ws_path, board, |doc| {
+        doc.entries.remove(entry_id);
+        let cards = column_cards(doc, column);
+        let index = match after_id {
+            Some(after_id) => {
+                cards
+                    .iter()
+                    .position(|(id, _)| *id == after_id)
+                    .ok_or_else(|| anyhow!("{} is not in column {}", after_id, column))?
+                    + 1
+            }
+            None => 0,
+        };
+        let before = index.checked_sub(1).map(|i| cards[i].1);
+        let after = cards.get(index).map(|card| card.1);
+        let placement = Placement {
+            column: column.to_string(),
+            position: position_between(before, after)?,
+        };
+        doc.entries.insert(entry_id.to_string(), placement.clone());
+        Ok(placement)
+    })
+    .await
+}
+
+/// Places `copy_id` directly after `entry_id` on every board that has
+/// `entry_id`. Returns the boards changed.
+pub(crate) async fn place_copy(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+    copy_id: &str,
+) -> Result<Vec<String>> {
+    let dir = format!("{}/{}/", ws_path.trim_end_matches('/'), BOARDS_DIR);
+    if !op.exists(&dir).await? {
+        return Ok(Vec::new());
+    }
+    let mut boards = Vec::new();
+    let mut lister = op.lister(&dir).await?;
+    while let Some(item) = lister.try_next().await? {
+        if item.metadata().mode() != EntryMode::FILE {
+            continue;
+        }
+        if let Some(board) = item.name().strip_suffix(".json") {
+            boards.push(board.to_string());
+        }
+    }
+    boards.sort();
+
+    let mut placed = Vec::new();
+    for board in boards {
+        let doc = read_board(op, &board_path(ws_path, &board)?).await?;
+        if let Some(placement) = doc.entries.get(entry_id) {
+            let column = placement.column.clone();
+            move_entry(op, ws_path, &board, &column, copy_id, Some(entry_id)).await?;
+            placed.push(board);
+        }
+    }
+    Ok(placed)
+}
+
+/// Removes an entry from a board. Returns whether it was on the board.
+pub async fn remove_from_board(
+    op: &Operator,
+    ws_path: &str,
+    board: &str,
+    entry_id: &str,
+) -> Result<bool> {
+    update_board(op, ws_path, board, |doc| {
+        Ok(doc.entries.remove(entry_id).is_some())
+    })
+    .await
+}
+
+/// Returns a board as `{"board", "columns": {column: [{"entry_id",
+/// "position"}]}, "updated_at"}` with each column in display order.
+pub async fn get_board(op: &Operator, ws_path: &str, board: &str) -> Result<Value> {
+    let doc = read_board(op, &board_path(ws_path, board)?).await?;
+    let mut columns: BTreeMap<&str, Vec<Value>> = BTreeMap::new();
+    for placement in doc.entries.values() {
+        if columns.contains_key(placement.column.as_str()) {
+            continue;
+        }
+        let cards = column_cards(&doc, &placement.column)
+            .into_iter()
+            .map(|(entry_id, position)| {
+                serde_json::json!({"entry_id": entry_id, "position": position})
+            })
+            .collect();
+        columns.insert(placement.column.as_str(), cards);
+    }
+    Ok(serde_json::json!({
+        "board": board,
+        "columns": columns,
+        "updated_at": doc.updated_at,
+    }))
+}
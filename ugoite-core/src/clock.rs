@@ -0,0 +1,83 @@
+use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+/// Last timestamp issued per space by [`next_micros`].
+static LAST_ISSUED: OnceLock<Mutex<HashMap<String, i64>>> = OnceLock::new();
+
+/// Wall clock time in Unix microseconds.
+pub fn now_micros() -> i64 {
+    Utc::now().timestamp_micros()
+}
+
+/// Converts API seconds to microseconds. Microsecond timestamps fit in an
+/// f64 mantissa, so the conversion round-trips exactly.
+pub fn to_micros(ts: f64) -> i64 {
+    (ts * 1_000_000.0).round() as i64
+}
+
+/// Converts microseconds to the f64 seconds used in the serialized API.
+pub fn from_micros(micros: i64) -> f64 {
+    micros as f64 / 1_000_000.0
+}
+
+/// Issues a write timestamp for `ws_path` in microseconds.
+///
+/// The clock is hybrid logical: it follows the wall clock, but each value is
+/// strictly later than every value issued before for the space in this
+/// process and than `after` (e.g. the `updated_at` of the row being
+/// replaced). Revisions therefore keep their order when writes land in the
+/// same microsecond or the wall clock steps backwards.
+pub fn next_micros(ws_path: &str, after: Option<i64>) -> i64 {
+    let ws_path = ws_path.trim_end_matches('/');
+    let mut last_issued = LAST_ISSUED
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let floor = last_issued.get(ws_path).copied().max(after);
+    let micros = match floor {
+        Some(floor) => now_micros().max(floor + 1),
+        None => now_micros(),
+    };
+    last_issued.insert(ws_path.to_string(), micros);
+    micros
+}
+
+/// Serializes an `i64` microsecond field as the f64 seconds of the API.
+pub mod micros_as_seconds {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(micros: &i64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(super::from_micros(*micros))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+        f64::deserialize(deserializer).map(super::to_micros)
+    }
+}
+
+/// [`micros_as_seconds`] for optional timestamps.
+pub mod opt_micros_as_seconds {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        micros: &Option<i64>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match micros {
+            Some(micros) => serializer.serialize_some(&super::from_micros(*micros)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<i64>, D::Error> {
+        Option::<f64>::deserialize(deserializer).map(|ts| ts.map(super::to_micros))
+    }
+}
+
+/// [`next_micros`] for callers that hold f64 seconds.
+pub fn next_ts(ws_path: &str, after: Option<f64>) -> f64 {
+    from_micros(next_micros(ws_path, after.map(to_micros)))
+}
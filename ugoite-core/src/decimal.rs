@@ -0,0 +1,134 @@
+use serde_json::Value;
+
+/// Largest precision a Decimal128 column holds.
+pub const MAX_PRECISION: u8 = 38;
+
+/// Precision and scale of a bare `decimal` field type.
+pub const DEFAULT_DECIMAL: DecimalType = DecimalType {
+    precision: 18,
+    scale: 2,
+};
+
+/// A `decimal(precision, scale)` field type: at most `precision` digits, of
+/// which `scale` follow the decimal point.
+///
+/// Values are held as unscaled `i128` integers (`12.30` at scale 2 is
+/// `1230`) and cross the API as decimal strings, so they never pass through
+/// a float.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecimalType {
+    pub precision: u8,
+    pub scale: u8,
+}
+
+impl DecimalType {
+    pub fn new(precision: u8, scale: u8) -> Option<Self> {
+        let valid = (1..=MAX_PRECISION).contains(&precision) && scale <= precision;
+        valid.then_some(Self { precision, scale })
+    }
+
+    /// Reads `decimal` or `decimal(precision, scale)`.
+    pub fn parse(field_type: &str) -> Option<Self> {
+        let rest = field_type.trim().strip_prefix("decimal")?.trim();
+        if rest.is_empty() {
+            return Some(DEFAULT_DECIMAL);
+        }
+        let (precision, scale) = rest.strip_prefix('(')?.strip_suffix(')')?.split_once(',')?;
+        Self::new(precision.trim().parse().ok()?, scale.trim().parse().ok()?)
+    }
+
+    /// The canonical field type string, e.g. `decimal(18,2)`.
+    pub fn field_type(&self) -> String {
+        format!("decimal({},{})", self.precision, self.scale)
+    }
+
+    /// Reads a decimal string or JSON number as an unscaled integer.
+    ///
+    /// `,` group separators are ignored. Values with more significant
+    /// fractional digits than the scale, or more integer digits than the
+    /// precision allows, are rejected rather than rounded.
+    pub fn parse_value(&self, value: &Value) -> Option<i128> {
+        let text = match value {
+            Value::String(s) => s.trim().replace(',', ""),
+            Value::Number(n) => n.to_string(),
+            _ => return None,
+        };
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text.strip_prefix('+').unwrap_or(&text)),
+        };
+        let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+        let all_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if (int_part.is_empty() && frac_part.is_empty())
+            || !all_digits(int_part)
+            || !all_digits(frac_part)
+        {
+            return None;
+        }
+        let scale = usize::from(self.scale);
+        let int_part = int_part.trim_start_matches('0');
+        let frac_part = frac_part.trim_end_matches('0');
+        if frac_part.len() > scale || int_part.len() > usize::from(self.precision) - scale {
+            return None;
+        }
+        let unscaled = format!("{}{:0<scale$}", int_part, frac_part, scale = scale);
+        let magnitude: i128 = if unscaled.trim_start_matches('0').is_empty() {
+            0
+        } else {
+            unscaled.parse().ok()?
+        };
+        Some(if negative { -magnitude } else { magnitude })
+    }
+
+    /// Renders an unscaled integer with exactly `scale` fractional digits.
+    pub fn format(&self, unscaled: i128) -> String {
+        let sign = if unscaled < 0 { "-" } else { "" };
+        let digits = unscaled.unsigned_abs().to_string();
+        let scale = usize::from(self.scale);
+        if scale == 0 {
+            return format!("{}{}", sign, digits);
+        }
+        let padded = format!("{:0>width$}", digits, width = scale + 1);
+        let (int_part, frac_part) = padded.split_at(padded.len() - scale);
+        format!("{}{}.{}", sign, int_part, frac_part)
+    }
+
+    /// Casts an input value to its canonical decimal string.
+    pub fn normalize(&self, value: &Value) -> Option<String> {
+        self.parse_value(value)
+            .map(|unscaled| self.format(unscaled))
+    }
+}
+
+/// The decimal type of `field` in `form_def`, if it is a decimal field.
+pub fn field_decimal_type(form_def: &Value, field: &str) -> Option<DecimalType> {
+    let fields = form_def.get("fields")?;
+    let def = match fields {
+        Value::Array(items) => items
+            .iter()
+            .find(|item| item.get("name").and_then(Value::as_str) == Some(field))?,
+        _ => fields.get(field)?,
+    };
+    DecimalType::parse(def.get("type").and_then(Value::as_str)?)
+}
+
+/// Exact sum of unscaled values; `None` on overflow.
+pub fn sum(values: &[i128]) -> Option<i128> {
+    values
+        .iter()
+        .try_fold(0i128, |total, value| total.checked_add(*value))
+}
+
+/// Mean of unscaled values at the same scale, rounded half to even.
+pub fn average(values: &[i128]) -> Option<i128> {
+    let count = i128::try_from(values.len()).ok().filter(|n| *n > 0)?;
+    let total = sum(values)?;
+    let quotient = total / count;
+    let twice_remainder = (total % count).abs() * 2;
+    let round_away = twice_remainder > count || (twice_remainder == count && quotient % 2 != 0);
+    Some(if round_away {
+        quotient + total.signum()
+    } else {
+        quotient
+    })
+}
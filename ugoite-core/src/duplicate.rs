@@ -0,0 +1,187 @@
+use anyhow::{anyhow, Result};
+use opendal::Operator;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+use crate::asset::{self, ASSET_FORM_NAME, ASSET_LINK_PREFIX};
+use crate::board;
+use crate::entry;
+use crate::entry_id;
+use crate::extraction;
+use crate::folder;
+use crate::form;
+use crate::integrity::IntegrityProvider;
+
+/// Rewrites `ugoite://asset/{old}` links in `value` to the copied assets.
+fn repoint_asset_links(value: &Value, copies: &BTreeMap<String, String>) -> Value {
+    match value {
+        Value::String(text) => {
+            let mut text = text.clone();
+            for (old, new) in copies {
+                text = text.replace(
+                    &format!("{}{}", ASSET_LINK_PREFIX, old),
+                    &format!("{}{}", ASSET_LINK_PREFIX, new),
+                );
+            }
+            Value::String(text)
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| repoint_asset_links(item, copies))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, item)| (key.clone(), repoint_asset_links(item, copies)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Copies every asset an entry owns or names in an `asset_ref` field,
+/// returning old id -> new id.
+async fn copy_owned_assets(
+    op: &Operator,
+    ws_path: &str,
+    row: &entry::EntryRow,
+    form_def: &Value,
+) -> Result<BTreeMap<String, String>> {
+    let mut owned: Vec<String> = extraction::owned_asset_ids(row)
+        .into_iter()
+        .chain(
+            asset::referenced_asset_ids(form_def, &row.fields)
+                .into_iter()
+                .map(|(_, id)| id),
+        )
+        .filter(|id| *id != row.entry_id)
+        .collect();
+    owned.sort();
+    owned.dedup();
+    let mut copies = BTreeMap::new();
+    if owned.is_empty() {
+        return Ok(copies);
+    }
+    let names: BTreeMap<String, String> = asset::list_assets(op, ws_path)
+        .await?
+        .into_iter()
+        .map(|info| (info.id, info.name))
+        .collect();
+    for asset_id in owned {
+        // Links to assets that no longer exist are left as they are.
+        let Some(name) = names.get(&asset_id) else {
+            continue;
+        };
+        let content = asset::read_asset_range(op, ws_path, &asset_id, 0, None)
+            .await?
+            .data;
+        let copy = asset::save_asset(op, ws_path, name, &content).await?;
+        copies.insert(asset_id, copy.id);
+    }
+    Ok(copies)
+}
+
+/// Copies a live entry to a new entry with its own revision history.
+///
+/// The copy keeps the title, tags, fields, attachment list, folder and board
+/// placements (directly after the original) of the source, but not its
+/// links or history. Without `new_entry_id` an id is suggested from the
+/// title. With `copy_assets` every asset the entry owns is uploaded again
+/// and the copy points at the new assets; otherwise both entries share
+/// them. Returns the new entry as [`entry::get_entry`] does, with
+/// `duplicated_from`, `copied_assets` (old id -> new id) and `boards`.
+pub async fn duplicate_entry<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+    new_entry_id: Option<&str>,
+    copy_assets: bool,
+    author: &str,
+    integrity: &I,
+) -> Result<Value> {
+    let form_name = entry::find_entry_form(op, ws_path, entry_id)
+        .await?
+        .ok_or_else(|| entry::entry_not_found(entry_id))?;
+    if form_name == ASSET_FORM_NAME {
+        return Err(anyhow!("Asset entries cannot be duplicated"));
+    }
+    let source = entry::read_entry_row(op, ws_path, &form_name, entry_id).await?;
+    if source.deleted {
+        return Err(entry::entry_not_found(entry_id));
+    }
+    let form_def = form::read_form_definition(op, ws_path, &form_name).await?;
+    let copy_id = match new_entry_id {
+        Some(id) => id.to_string(),
+        None => entry_id::suggest_entry_id(op, ws_path, &source.title, &form_name).await?,
+    };
+    if entry::exists(op, ws_path, &copy_id).await?.is_some() {
+        return Err(anyhow!("Entry already exists: {}", copy_id));
+    }
+
+    let copies = if copy_assets {
+        copy_owned_assets(op, ws_path, &source, &form_def).await?
+    } else {
+        BTreeMap::new()
+    };
+    let mut fields = repoint_asset_links(&source.fields, &copies);
+    // `asset_ref` fields may hold a bare asset id rather than a link.
+    for (name, id) in asset::referenced_asset_ids(&form_def, &fields) {
+        if let Some(new_id) = copies.get(&id) {
+            fields[name.as_str()] = Value::String(new_id.clone());
+        }
+    }
+    let extra_attributes = repoint_asset_links(&source.extra_attributes, &copies);
+    let content = entry::render_markdown_for_form(
+        &source.title,
+        &form_name,
+        &source.tags,
+        &fields,
+        &extra_attributes,
+        &form_def,
+    );
+    entry::create_entry(op, ws_path, &copy_id, &content, author, integrity).await?;
+
+    if !source.assets.is_empty() {
+        let mut row = entry::read_entry_row(op, ws_path, &form_name, &copy_id).await?;
+        row.assets = source
+            .assets
+            .iter()
+            .map(|item| repoint_asset_links(&copy_asset_item(item, &copies), &copies))
+            .collect();
+        entry::write_entry_row(op, ws_path, &form_name, &copy_id, &row).await?;
+    }
+    let source_folder = folder::entry_folder(op, ws_path, entry_id).await?;
+    if !source_folder.is_empty() {
+        folder::move_entry(op, ws_path, &copy_id, Some(&source_folder)).await?;
+    }
+    let boards = board::place_copy(op, ws_path, entry_id, &copy_id).await?;
+
+    let mut duplicated = entry::get_entry(op, ws_path, &copy_id).await?;
+    duplicated["duplicated_from"] = Value::String(entry_id.to_string());
+    duplicated["copied_assets"] = serde_json::to_value(&copies)?;
+    duplicated["boards"] = serde_json::to_value(&boards)?;
+    Ok(duplicated)
+}
+
+/// An attachment record pointing at the copied asset, if it was copied.
+fn copy_asset_item(item: &Value, copies: &BTreeMap<String, String>) -> Value {
+    let Some(new_id) = item
+        .get("id")
+        .and_then(Value::as_str)
+        .and_then(|id| copies.get(id))
+    else {
+        return item.clone();
+    };
+    let mut copied: Map<String, Value> = item.as_object().cloned().unwrap_or_default();
+    copied.insert("id".to_string(), Value::String(new_id.clone()));
+    let old_id = item["id"].as_str().unwrap_or_default();
+    if let Some(path) = copied
+        .get("path")
+        .and_then(Value::as_str)
+        .map(|path| path.replacen(old_id, new_id, 1))
+    {
+        copied.insert("path".to_string(), Value::String(path));
+    }
+    Value::Object(copied)
+}
@@ -0,0 +1,551 @@
+use anyhow::{anyhow, Result};
+use base64::Engine as _;
+use chrono::{DateTime, SecondsFormat, Utc};
+use opendal::Operator;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::sync::OnceLock;
+
+use crate::asset;
+use crate::entry;
+use crate::entry_id;
+use crate::form;
+use crate::integrity::IntegrityProvider;
+use crate::timezone::SpaceTimeZone;
+
+/// Form fields filled from message headers, matched by name ignoring case
+/// and punctuation (`Message-ID`, `message_id`, ...).
+const HEADER_FIELDS: [(&str, &str); 6] = [
+    ("from", "From"),
+    ("to", "To"),
+    ("cc", "Cc"),
+    ("subject", "Subject"),
+    ("date", "Date"),
+    ("messageid", "Message-ID"),
+];
+/// Field names that take the message body, before any markdown field.
+const BODY_FIELDS: [&str; 3] = ["body", "content", "message"];
+const ATTACHMENTS_FIELD: &str = "attachments";
+
+/// A decoded MIME leaf part.
+struct Leaf {
+    mime: String,
+    filename: Option<String>,
+    content_id: Option<String>,
+    attachment: bool,
+    bytes: Vec<u8>,
+    charset: Option<String>,
+}
+
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|ch| ch.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Splits raw message bytes at the blank line ending the header block and
+/// unfolds the headers.
+fn split_message(bytes: &[u8]) -> (Vec<(String, String)>, &[u8]) {
+    if let Some(body) = bytes
+        .strip_prefix(b"\r\n")
+        .or_else(|| bytes.strip_prefix(b"\n"))
+    {
+        return (Vec::new(), body);
+    }
+    let mut end = bytes.len();
+    let mut body_start = bytes.len();
+    for (i, window) in bytes.windows(2).enumerate() {
+        if window == b"\n\n" {
+            (end, body_start) = (i, i + 2);
+            break;
+        }
+        if window == b"\n\r" && bytes.get(i + 2) == Some(&b'\n') {
+            (end, body_start) = (i, i + 3);
+            break;
+        }
+    }
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in String::from_utf8_lossy(&bytes[..end]).lines() {
+        let line = line.trim_end_matches('\r');
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    (headers, &bytes[body_start.min(bytes.len())..])
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+/// `text/plain; charset="utf-8"` -> (`text/plain`, params). RFC 2231
+/// `name*=utf-8''...` parameters are decoded.
+fn parse_header_params(value: &str) -> (String, Vec<(String, String)>) {
+    let mut parts = value.split(';');
+    let kind = parts.next().unwrap_or_default().trim().to_lowercase();
+    let params = parts
+        .filter_map(|part| {
+            let (key, value) = part.split_once('=')?;
+            let key = key.trim().to_lowercase();
+            let value = value.trim().trim_matches('"');
+            Some(match key.strip_suffix('*') {
+                Some(key) => {
+                    let encoded = value.splitn(3, '\'').nth(2).unwrap_or(value);
+                    let bytes = decode_percent(encoded);
+                    (
+                        key.to_string(),
+                        String::from_utf8_lossy(&bytes).into_owned(),
+                    )
+                }
+                None => (key, decode_words(value)),
+            })
+        })
+        .collect();
+    (kind, params)
+}
+
+fn param<'a>(params: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    params
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+fn decode_percent(text: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(text.len());
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            if let Some(byte) = text
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    out
+}
+
+fn decode_quoted_printable(bytes: &[u8], underscore_space: bool) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'=' if bytes.get(i + 1) == Some(&b'\n') => i += 2,
+            b'=' if bytes.get(i + 1..i + 3) == Some(b"\r\n") => i += 3,
+            b'=' => match bytes
+                .get(i + 1..i + 3)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                Some(byte) => {
+                    out.push(byte);
+                    i += 3;
+                }
+                None => {
+                    out.push(b'=');
+                    i += 1;
+                }
+            },
+            b'_' if underscore_space => {
+                out.push(b' ');
+                i += 1;
+            }
+            byte => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+fn decode_base64(bytes: &[u8]) -> Result<Vec<u8>> {
+    let compact: Vec<u8> = bytes
+        .iter()
+        .copied()
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .collect();
+    base64::engine::general_purpose::STANDARD
+        .decode(&compact)
+        .map_err(|e| anyhow!("Invalid base64 in message part: {}", e))
+}
+
+/// Text in `charset`. UTF-8 and Latin-1 are decoded exactly; anything else
+/// is read as UTF-8 with replacement characters.
+fn decode_charset(bytes: &[u8], charset: Option<&str>) -> String {
+    match charset.map(str::to_lowercase).as_deref() {
+        Some("iso-8859-1" | "latin1" | "latin-1" | "windows-1252" | "cp1252") => {
+            bytes.iter().map(|&byte| char::from(byte)).collect()
+        }
+        _ => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+/// `=?charset?B|Q?...?=` (RFC 2047)
+fn encoded_word_regex() -> &'static Regex {
+    static ENCODED_WORD_REGEX: OnceLock<Regex> = OnceLock::new();
+    ENCODED_WORD_REGEX.get_or_init(|| {
+        Regex::new(r"=\?([^?]+)\?([BbQq])\?([^?]*)\?=").expect("encoded word regex must compile")
+    })
+}
+
+/// Whitespace between two adjacent encoded words, which is not displayed.
+fn encoded_word_gap_regex() -> &'static Regex {
+    static ENCODED_WORD_GAP_REGEX: OnceLock<Regex> = OnceLock::new();
+    ENCODED_WORD_GAP_REGEX.get_or_init(|| {
+        Regex::new(r"(=\?[^?]+\?[BbQq]\?[^?]*\?=)\s+(=\?)")
+            .expect("encoded word gap regex must compile")
+    })
+}
+
+/// Decodes RFC 2047 encoded words in a header value.
+fn decode_words(value: &str) -> String {
+    let mut joined = value.to_string();
+    // Each pass joins every other gap, since matches cannot overlap.
+    while encoded_word_gap_regex().is_match(&joined) {
+        joined = encoded_word_gap_regex()
+            .replace_all(&joined, "$1$2")
+            .into_owned();
+    }
+    encoded_word_regex()
+        .replace_all(&joined, |caps: &regex::Captures| {
+            let bytes = match &caps[2] {
+                "B" | "b" => decode_base64(caps[3].as_bytes()).unwrap_or_default(),
+                _ => decode_quoted_printable(caps[3].as_bytes(), true),
+            };
+            decode_charset(&bytes, Some(&caps[1]))
+        })
+        .into_owned()
+}
+
+fn decode_transfer(bytes: &[u8], encoding: Option<&str>) -> Result<Vec<u8>> {
+    match encoding.map(|e| e.trim().to_lowercase()).as_deref() {
+        Some("base64") => decode_base64(bytes),
+        Some("quoted-printable") => Ok(decode_quoted_printable(bytes, false)),
+        _ => Ok(bytes.to_vec()),
+    }
+}
+
+/// Splits a multipart body on its boundary lines.
+fn split_multipart<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{}", boundary);
+    let mut parts = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut offset = 0;
+    for line in body.split_inclusive(|&byte| byte == b'\n') {
+        let text = String::from_utf8_lossy(line);
+        let text = text.trim_end();
+        if text == delimiter || text == format!("{}--", delimiter) {
+            if let Some(start) = start {
+                // Drop the line break that belongs to the delimiter.
+                let mut end = offset;
+                if body[..end].ends_with(b"\n") {
+                    end -= 1;
+                }
+                if body[..end].ends_with(b"\r") {
+                    end -= 1;
+                }
+                parts.push(&body[start..end.max(start)]);
+            }
+            if text != delimiter {
+                break;
+            }
+            start = Some(offset + line.len());
+        }
+        offset += line.len();
+    }
+    parts
+}
+
+/// Collects the leaf parts of a message, depth first.
+fn collect_leaves(headers: &[(String, String)], body: &[u8], leaves: &mut Vec<Leaf>) -> Result<()> {
+    let (mime, params) =
+        parse_header_params(header(headers, "Content-Type").unwrap_or("text/plain"));
+    if mime.starts_with("multipart/") {
+        let boundary = param(&params, "boundary")
+            .ok_or_else(|| anyhow!("Multipart message part without a boundary"))?;
+        for part in split_multipart(body, boundary) {
+            let (part_headers, part_body) = split_message(part);
+            collect_leaves(&part_headers, part_body, leaves)?;
+        }
+        return Ok(());
+    }
+    let (disposition, disposition_params) =
+        parse_header_params(header(headers, "Content-Disposition").unwrap_or_default());
+    let filename = param(&disposition_params, "filename")
+        .or_else(|| param(&params, "name"))
+        .map(str::to_string);
+    let is_text = mime == "text/plain" || mime == "text/html";
+    leaves.push(Leaf {
+        attachment: disposition == "attachment"
+            || !is_text
+            || (filename.is_some() && disposition != "inline"),
+        content_id: header(headers, "Content-ID").map(|id| {
+            id.trim()
+                .trim_start_matches('<')
+                .trim_end_matches('>')
+                .to_string()
+        }),
+        filename,
+        charset: param(&params, "charset").map(str::to_string),
+        bytes: decode_transfer(body, header(headers, "Content-Transfer-Encoding"))?,
+        mime,
+    });
+    Ok(())
+}
+
+/// A rough markdown rendering of an HTML body: paragraphs, line breaks,
+/// list items, links and images are kept; other markup is dropped.
+fn html_to_markdown(html: &str) -> String {
+    static TAG_REGEXES: OnceLock<[Regex; 6]> = OnceLock::new();
+    let [skip, link, image, block, item, tag] = TAG_REGEXES.get_or_init(|| {
+        [
+            Regex::new(r"(?is)<(script|style|head)\b.*?</(script|style|head)>"),
+            Regex::new(r#"(?is)<a\b[^>]*href\s*=\s*["']([^"']*)["'][^>]*>(.*?)</a>"#),
+            Regex::new(r#"(?is)<img\b[^>]*src\s*=\s*["']([^"']*)["'][^>]*>"#),
+            Regex::new(r"(?i)<br\s*/?>|</(p|div|h[1-6]|tr|ul|ol|li|blockquote)>"),
+            Regex::new(r"(?i)<li\b[^>]*>"),
+            Regex::new(r"(?s)<[^>]*>"),
+        ]
+        .map(|regex| regex.expect("html regex must compile"))
+    });
+    let text = skip.replace_all(html, "");
+    let text = link.replace_all(&text, "[$2]($1)");
+    let text = image.replace_all(&text, "![]($1)");
+    let text = item.replace_all(&text, "- ");
+    let text = block.replace_all(&text, "\n");
+    let text = tag.replace_all(&text, "");
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+    let mut lines: Vec<&str> = Vec::new();
+    for line in text.lines().map(str::trim_end) {
+        if line.trim().is_empty() && lines.last().is_none_or(|last| last.trim().is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    lines.join("\n").trim().to_string()
+}
+
+/// Creates an entry of `form_name` from an RFC 822 message (`.eml`).
+///
+/// `From`, `To`, `Cc`, `Subject`, `Date` and `Message-ID` fill form fields of
+/// the same name; a `date` field gets the day in the space's timezone. The
+/// text body (or the HTML body as markdown) fills a `Body`, `Content` or
+/// `Message` field, else the form's first markdown field. Attachments and
+/// inline images are saved as assets; `cid:` references in the body point at
+/// them and an `Attachments` field, if the form has one, lists them.
+///
+/// The subject is the title and the entry id is suggested from it. Returns a
+/// report with the entry id, the fields filled, the attachments and any
+/// warnings.
+pub async fn import_eml<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+    bytes: &[u8],
+    author: &str,
+    integrity: &I,
+) -> Result<Value> {
+    let form_def = form::get_form(op, ws_path, form_name).await?;
+    let field_types: Vec<(String, String)> = match form_def.get("fields") {
+        Some(Value::Object(fields)) => fields
+            .iter()
+            .map(|(name, def)| {
+                let field_type = def.get("type").and_then(Value::as_str).unwrap_or("string");
+                (name.clone(), field_type.to_string())
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    let find_field = |normalized: &str| {
+        field_types
+            .iter()
+            .find(|(name, _)| normalize_name(name) == normalized)
+            .cloned()
+    };
+
+    let (headers, body) = split_message(bytes);
+    if headers.is_empty() {
+        return Err(anyhow!("Not an RFC 822 message: no headers found"));
+    }
+    let mut leaves = Vec::new();
+    collect_leaves(&headers, body, &mut leaves)?;
+    let mut warnings = Vec::new();
+
+    let mut attachments = Vec::new();
+    let mut cid_links = Vec::new();
+    for (index, leaf) in leaves.iter().filter(|leaf| leaf.attachment).enumerate() {
+        let filename = leaf.filename.clone().unwrap_or_else(|| {
+            let extension = leaf.mime.rsplit('/').next().unwrap_or("bin");
+            format!("attachment-{}.{}", index + 1, extension)
+        });
+        let info = asset::save_asset(op, ws_path, &filename, &leaf.bytes).await?;
+        if let Some(content_id) = &leaf.content_id {
+            cid_links.push((format!("cid:{}", content_id), info.link.clone()));
+        }
+        attachments.push(serde_json::json!({
+            "filename": filename,
+            "content_type": leaf.mime,
+            "asset_id": info.id,
+            "link": info.link,
+        }));
+    }
+
+    let text_of = |mime: &str| -> Vec<String> {
+        leaves
+            .iter()
+            .filter(|leaf| !leaf.attachment && leaf.mime == mime)
+            .map(|leaf| decode_charset(&leaf.bytes, leaf.charset.as_deref()))
+            .collect()
+    };
+    let plain = text_of("text/plain");
+    let mut body_text = if plain.is_empty() {
+        text_of("text/html")
+            .iter()
+            .map(|html| html_to_markdown(html))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    } else {
+        plain.join("\n\n").replace("\r\n", "\n")
+    };
+    for (cid, link) in &cid_links {
+        body_text = body_text.replace(cid.as_str(), link);
+    }
+    let (body_text, rewritten) = entry::section_safe_markdown(&body_text);
+    if rewritten > 0 {
+        warnings.push(format!(
+            "Rewrote {} body lines starting with '#' to keep the body in one field",
+            rewritten
+        ));
+    }
+
+    let tz = SpaceTimeZone::for_space(op, ws_path).await?;
+    let mut fields = Map::new();
+    let mut order = Vec::new();
+    let mut mapped = Map::new();
+    for (key, header_name) in HEADER_FIELDS {
+        let Some(raw) = header(&headers, header_name) else {
+            continue;
+        };
+        let Some((field, field_type)) = find_field(key) else {
+            continue;
+        };
+        let value = decode_words(raw);
+        let value = if key == "date" && field_type != "string" {
+            match DateTime::parse_from_rfc2822(&value) {
+                Ok(sent) => {
+                    let local = tz.localize(&sent.with_timezone(&Utc));
+                    match field_type.as_str() {
+                        "date" => local.date_naive().to_string(),
+                        _ => local.to_rfc3339_opts(SecondsFormat::Secs, false),
+                    }
+                }
+                Err(_) => {
+                    warnings.push(format!("Could not parse Date header: {}", value));
+                    continue;
+                }
+            }
+        } else {
+            value
+        };
+        mapped.insert(header_name.to_string(), Value::String(field.clone()));
+        fields.insert(field.clone(), Value::String(value));
+        order.push(field);
+    }
+
+    let body_field = BODY_FIELDS
+        .iter()
+        .find_map(|name| find_field(name))
+        .or_else(|| {
+            field_types
+                .iter()
+                .find(|(name, field_type)| field_type == "markdown" && !fields.contains_key(name))
+                .cloned()
+        });
+    match body_field {
+        Some((field, _)) if !body_text.is_empty() => {
+            mapped.insert("body".to_string(), Value::String(field.clone()));
+            fields.insert(field.clone(), Value::String(body_text));
+            order.push(field);
+        }
+        Some(_) => {}
+        None if body_text.is_empty() => {}
+        None => warnings.push(format!(
+            "Form {} has no field for the message body",
+            form_name
+        )),
+    }
+
+    if !attachments.is_empty() {
+        match find_field(ATTACHMENTS_FIELD) {
+            Some((field, field_type)) => {
+                let links = attachments
+                    .iter()
+                    .map(|a| a["link"].as_str().unwrap_or_default());
+                let value = match field_type.as_str() {
+                    "list" => Value::Array(links.map(Value::from).collect()),
+                    "asset_ref" => Value::from(links.take(1).collect::<String>()),
+                    _ => Value::String(
+                        attachments
+                            .iter()
+                            .map(|a| {
+                                format!(
+                                    "- [{}]({})",
+                                    a["filename"].as_str().unwrap_or_default(),
+                                    a["link"].as_str().unwrap_or_default()
+                                )
+                            })
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    ),
+                };
+                mapped.insert("attachments".to_string(), Value::String(field.clone()));
+                fields.insert(field.clone(), value);
+                order.push(field);
+            }
+            None if attachments.len() > cid_links.len() => warnings.push(format!(
+                "Form {} has no Attachments field; attachments were saved as assets only",
+                form_name
+            )),
+            None => {}
+        }
+    }
+
+    let title = header(&headers, "Subject")
+        .map(decode_words)
+        .filter(|subject| !subject.trim().is_empty())
+        .unwrap_or_else(|| "(no subject)".to_string());
+    let content = entry::render_markdown(&title, form_name, &[], &Value::Object(fields), &order);
+    let entry_id = entry_id::suggest_entry_id(op, ws_path, &title, form_name).await?;
+    entry::create_entry(op, ws_path, &entry_id, &content, author, integrity).await?;
+    Ok(serde_json::json!({
+        "entry_id": entry_id,
+        "form": form_name,
+        "title": title,
+        "fields": mapped,
+        "attachments": attachments,
+        "warnings": warnings,
+    }))
+}
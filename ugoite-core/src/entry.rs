@@ -1,14 +1,29 @@
+use crate::asset;
+use crate::clock;
+use crate::decimal::DecimalType;
+use crate::entry_id;
 use crate::form;
+use crate::geo;
+use crate::hooks;
+use crate::i18n;
 use crate::iceberg_store;
 use crate::index;
-use crate::integrity::IntegrityProvider;
+use crate::integrity::{IntegrityError, IntegrityProvider};
 use crate::link::Link;
-use anyhow::{anyhow, Result};
-use arrow_array::builder::{FixedSizeBinaryBuilder, ListBuilder, StringBuilder, StructBuilder};
+use crate::progress::ProgressSender;
+use crate::rollup;
+use crate::space::{self, FormatUpgradeRequired};
+use crate::timezone::SpaceTimeZone;
+use crate::url_field;
+use anyhow::{anyhow, Context, Result};
+use arrow_array::builder::{
+    FixedSizeBinaryBuilder, Float64Builder, ListBuilder, StringBuilder, StructBuilder,
+};
 use arrow_array::{
-    Array, ArrayRef, BooleanArray, Date32Array, FixedSizeBinaryArray, Float32Array, Float64Array,
-    Int32Array, Int64Array, LargeBinaryArray, ListArray, RecordBatch, StringArray, StructArray,
-    Time64MicrosecondArray, TimestampMicrosecondArray, TimestampNanosecondArray,
+    Array, ArrayRef, BooleanArray, Date32Array, Decimal128Array, FixedSizeBinaryArray,
+    Float32Array, Float64Array, Int32Array, Int64Array, LargeBinaryArray, ListArray, RecordBatch,
+    StringArray, StructArray, Time64MicrosecondArray, TimestampMicrosecondArray,
+    TimestampNanosecondArray,
 };
 use arrow_schema::{DataType, Fields};
 use base64::Engine as _;
@@ -16,18 +31,24 @@ use chrono::{DateTime, NaiveTime, SecondsFormat, Timelike, Utc};
 use futures::TryStreamExt;
 use iceberg::arrow::schema_to_arrow_schema;
 use iceberg::arrow::ArrowReaderBuilder;
+use iceberg::expr::Reference;
 use iceberg::spec::DataFile;
+use iceberg::spec::Datum;
 use iceberg::transaction::ApplyTransactionAction;
 use iceberg::transaction::Transaction;
 use iceberg::writer::file_writer::{FileWriter, FileWriterBuilder, ParquetWriterBuilder};
 use iceberg::MemoryCatalog;
 use opendal::Operator;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::basic::{BrotliLevel, Compression, GzipLevel, ZstdLevel};
 use parquet::file::properties::WriterProperties;
+use parquet::schema::types::ColumnPath;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::Instant;
 use url::Url;
 use uuid::Uuid;
 
@@ -81,6 +102,9 @@ pub struct EntryMeta {
     pub deleted_at: Option<f64>,
     #[serde(default)]
     pub properties: Value,
+    /// Soft warnings from the write, e.g. a deprecated field was set.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<Value>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -92,8 +116,11 @@ pub struct EntryRow {
     pub tags: Vec<String>,
     #[serde(default)]
     pub links: Vec<Link>,
-    pub created_at: f64,
-    pub updated_at: f64,
+    /// Unix microseconds, serialized as f64 seconds.
+    #[serde(with = "clock::micros_as_seconds")]
+    pub created_at: i64,
+    #[serde(with = "clock::micros_as_seconds")]
+    pub updated_at: i64,
     #[serde(default)]
     pub fields: Value,
     #[serde(default)]
@@ -106,10 +133,31 @@ pub struct EntryRow {
     pub integrity: IntegrityPayload,
     #[serde(default)]
     pub deleted: bool,
-    #[serde(default)]
-    pub deleted_at: Option<f64>,
+    #[serde(default, with = "clock::opt_micros_as_seconds")]
+    pub deleted_at: Option<i64>,
     #[serde(default)]
     pub author: String,
+    /// Client metadata (pinned, color, ...) kept outside form fields and
+    /// revision history.
+    #[serde(default)]
+    pub metadata: Value,
+    /// Set aside by [`archive_entry`]: still queryable, but left out of
+    /// default listings and search.
+    #[serde(default)]
+    pub archived: bool,
+    /// Deleted with `hard_delete`: cannot be undeleted and is removed for
+    /// good by [`crate::trash::gc_deleted_entries`].
+    #[serde(default)]
+    pub hard_deleted: bool,
+}
+
+/// Where an entry id is in use. Deleted entries still hold their id.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EntryExistence {
+    pub entry_id: String,
+    pub form: String,
+    pub deleted: bool,
+    pub updated_at: f64,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -117,7 +165,9 @@ pub struct RevisionRow {
     pub revision_id: String,
     pub entry_id: String,
     pub parent_revision_id: Option<String>,
-    pub timestamp: f64,
+    /// Unix microseconds, serialized as f64 seconds.
+    #[serde(with = "clock::micros_as_seconds")]
+    pub timestamp: i64,
     pub author: String,
     #[serde(default)]
     pub fields: Value,
@@ -128,21 +178,95 @@ pub struct RevisionRow {
     pub integrity: IntegrityPayload,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub restored_from: Option<String>,
+    /// Fields this revision changed relative to its parent.
+    #[serde(default)]
+    pub changed_fields: Vec<FieldChange>,
+    /// Why the edit was made, like a commit message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
 }
 
-pub(crate) fn now_ts() -> f64 {
-    Utc::now().timestamp_millis() as f64 / 1000.0
+/// One field changed by a revision. `old` and `new` are recorded only for
+/// scalar values (numbers, booleans and short single-line strings); for
+/// anything larger only the field name is kept.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub field: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new: Option<Value>,
+}
+
+const MAX_SCALAR_CHANGE_CHARS: usize = 200;
+
+fn is_scalar_change(value: &Value) -> bool {
+    match value {
+        Value::Null | Value::Bool(_) | Value::Number(_) => true,
+        Value::String(s) => !s.contains('\n') && s.chars().count() <= MAX_SCALAR_CHANGE_CHARS,
+        _ => false,
+    }
+}
+
+/// Compares two merged field maps (fields plus extra attributes) and lists
+/// the fields whose values differ, sorted by name. Missing and null values
+/// are treated as equal.
+pub fn diff_fields(before: &Value, after: &Value) -> Vec<FieldChange> {
+    let empty = Map::new();
+    let before = before.as_object().unwrap_or(&empty);
+    let after = after.as_object().unwrap_or(&empty);
+    let names: std::collections::BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let old = before.get(name).unwrap_or(&Value::Null);
+            let new = after.get(name).unwrap_or(&Value::Null);
+            if old == new {
+                return None;
+            }
+            let scalar = is_scalar_change(old) && is_scalar_change(new);
+            Some(FieldChange {
+                field: name.clone(),
+                old: scalar.then(|| old.clone()),
+                new: scalar.then(|| new.clone()),
+            })
+        })
+        .collect()
+}
+
+const MAX_REVISION_MESSAGE_CHARS: usize = 1000;
+
+/// Trims an edit message; blank messages are dropped and long ones cut to
+/// `MAX_REVISION_MESSAGE_CHARS`.
+fn normalize_revision_message(message: Option<&str>) -> Option<String> {
+    let message = message?.trim();
+    (!message.is_empty()).then(|| message.chars().take(MAX_REVISION_MESSAGE_CHARS).collect())
+}
+
+fn changed_fields_to_string(changes: &[FieldChange]) -> Option<String> {
+    if changes.is_empty() {
+        return None;
+    }
+    serde_json::to_string(changes).ok()
+}
+
+fn changed_fields_from_string(raw: &str) -> Vec<FieldChange> {
+    serde_json::from_str(raw).unwrap_or_default()
 }
 
-fn to_timestamp_micros(ts: f64) -> i64 {
-    (ts * 1_000_000.0).round() as i64
+pub(crate) fn entry_not_found(entry_id: &str) -> anyhow::Error {
+    i18n::error(
+        "entry_not_found",
+        serde_json::json!({ "entry_id": entry_id }),
+    )
 }
 
-fn from_timestamp_micros(micros: i64) -> f64 {
-    micros as f64 / 1_000_000.0
+pub(crate) fn now_ts() -> f64 {
+    clock::from_micros(clock::now_micros())
 }
 
-fn extract_title(content: &str, fallback: &str) -> String {
+/// Returns the first `# ` heading of `content`, or `fallback` when there is none.
+pub fn extract_title(content: &str, fallback: &str) -> String {
     for line in content.lines() {
         if let Some(stripped) = line.strip_prefix("# ") {
             return stripped.trim().to_string();
@@ -151,21 +275,31 @@ fn extract_title(content: &str, fallback: &str) -> String {
     fallback.to_string()
 }
 
-fn extract_frontmatter(content: &str) -> (Value, String) {
+/// Splits a leading `---` YAML block from `content`, returning it as JSON
+/// together with the remaining body.
+///
+/// A frontmatter block that is not a valid YAML mapping is an error rather
+/// than being dropped, so a typo cannot silently discard the form or tags.
+pub fn extract_frontmatter(content: &str) -> Result<(Value, String)> {
     let re = Regex::new(r"(?s)^---\s*\n(.*?)\n---\s*\n").unwrap();
     if let Some(caps) = re.captures(content) {
         let yaml_str = caps.get(1).unwrap().as_str();
-        let fm_yaml: Option<serde_yaml::Value> = serde_yaml::from_str(yaml_str).ok();
-        let fm_json = fm_yaml
-            .and_then(|y| serde_json::to_value(y).ok())
-            .unwrap_or_else(|| Value::Object(Map::new()));
+        let fm_yaml: serde_yaml::Value = serde_yaml::from_str(yaml_str)
+            .map_err(|e| anyhow!("Invalid frontmatter YAML: {}", e))?;
+        let fm_json = match serde_json::to_value(fm_yaml)? {
+            Value::Null => Value::Object(Map::new()),
+            value @ Value::Object(_) => value,
+            _ => return Err(anyhow!("Invalid frontmatter YAML: expected a mapping")),
+        };
         let end = caps.get(0).unwrap().end();
-        return (fm_json, content[end..].to_string());
+        return Ok((fm_json, content[end..].to_string()));
     }
-    (Value::Object(Map::new()), content.to_string())
+    Ok((Value::Object(Map::new()), content.to_string()))
 }
 
-fn extract_sections(body: &str) -> Value {
+/// Collects `## Heading` sections of a markdown body into a JSON object.
+/// Any other heading ends the current section.
+pub fn extract_sections(body: &str) -> Value {
     let mut sections: Map<String, Value> = Map::new();
     let header_re = Regex::new(r"^##\s+(.+)$").unwrap();
     let mut current_key: Option<String> = None;
@@ -201,10 +335,52 @@ fn extract_sections(body: &str) -> Value {
     Value::Object(sections)
 }
 
-fn parse_markdown(content: &str) -> (Value, Value) {
-    let (frontmatter, body) = extract_frontmatter(content);
+/// Parses entry markdown into its frontmatter and sections.
+pub fn parse_markdown(content: &str) -> Result<(Value, Value)> {
+    let (frontmatter, body) = extract_frontmatter(content)?;
     let sections = extract_sections(&body);
-    (frontmatter, sections)
+    Ok((frontmatter, sections))
+}
+
+/// Rewrites lines starting with `#` so imported markdown stays inside one
+/// `##` section (see [`extract_sections`]): headings become bold lines,
+/// other `#` lines are escaped, and inside code fences they are indented by
+/// a space. Returns the text and how many lines were rewritten.
+pub(crate) fn section_safe_markdown(body: &str) -> (String, usize) {
+    static HEADING_REGEX: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+    let heading = HEADING_REGEX.get_or_init(|| {
+        Regex::new(r"^#{1,6}\s+(.*?)\s*#*\s*$").expect("heading regex must compile")
+    });
+    let mut in_fence = false;
+    let mut rewritten = 0;
+    let mut lines = Vec::new();
+    for line in body.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        if !line.starts_with('#') {
+            lines.push(line.to_string());
+            continue;
+        }
+        rewritten += 1;
+        if in_fence {
+            lines.push(format!(" {}", line));
+        } else if let Some(caps) = heading.captures(line) {
+            lines.push(format!("**{}**", &caps[1]));
+        } else {
+            lines.push(format!("\\{}", line));
+        }
+    }
+    (lines.join("\n").trim().to_string(), rewritten)
+}
+
+/// The entry id a row_reference value points at, accepting both a bare id
+/// and a `ugoite://entry/{id}` link.
+pub(crate) fn row_reference_id(value: &Value) -> Option<String> {
+    let text = value.as_str()?.trim();
+    let id = text.strip_prefix("ugoite://entry/").unwrap_or(text);
+    (!id.is_empty()).then(|| id.to_string())
 }
 
 fn normalize_ugoite_links(content: &str) -> String {
@@ -258,6 +434,14 @@ fn extra_attributes_policy(form_def: &Value) -> ExtraAttributesPolicy {
     }
 }
 
+/// Whether entries of the form may carry fields it does not define.
+pub(crate) fn allows_extra_attributes(form_def: &Value) -> bool {
+    !matches!(
+        extra_attributes_policy(form_def),
+        ExtraAttributesPolicy::Deny
+    )
+}
+
 fn collect_extra_attributes(sections: &Value, form_set: &HashSet<String>) -> (Vec<String>, Value) {
     let mut extras = Vec::new();
     let mut entries = Vec::new();
@@ -297,7 +481,7 @@ pub(crate) fn merge_entry_fields(fields: &Value, extra_attributes: &Value) -> Va
     Value::Object(merged)
 }
 
-fn form_field_names(form_def: &Value) -> Vec<String> {
+pub(crate) fn form_field_names(form_def: &Value) -> Vec<String> {
     let mut names = Vec::new();
     if let Some(fields) = form_def.get("fields") {
         match fields {
@@ -319,13 +503,42 @@ fn form_field_names(form_def: &Value) -> Vec<String> {
     names
 }
 
+/// Renders a string as a YAML scalar, quoting it only when the plain form
+/// would read back as something else (a number, bool, null, or a mapping).
+fn yaml_scalar(value: &str) -> String {
+    let plain_round_trips = !value.is_empty()
+        && !value.contains('\n')
+        && serde_yaml::from_str::<serde_yaml::Value>(&format!("v: {}", value))
+            .ok()
+            .and_then(|parsed| parsed.get("v").cloned())
+            == Some(serde_yaml::Value::String(value.to_string()));
+    if plain_round_trips {
+        return value.to_string();
+    }
+    // JSON strings are valid YAML double-quoted scalars, except that YAML also
+    // treats NEL/LS/PS as line breaks and rejects other non-printable chars.
+    let mut quoted = String::with_capacity(value.len() + 2);
+    for ch in serde_json::to_string(value).unwrap_or_default().chars() {
+        let printable = matches!(
+            ch,
+            '\u{20}'..='\u{7E}' | '\u{A0}'..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}'
+        ) || ch >= '\u{10000}';
+        if printable {
+            quoted.push(ch);
+        } else {
+            quoted.push_str(&format!("\\u{:04X}", ch as u32));
+        }
+    }
+    quoted
+}
+
 fn render_frontmatter(form_name: &str, tags: &[String]) -> String {
     let mut frontmatter = String::from("---\n");
-    frontmatter.push_str(&format!("form: {}\n", form_name));
+    frontmatter.push_str(&format!("form: {}\n", yaml_scalar(form_name)));
     if !tags.is_empty() {
         frontmatter.push_str("tags:\n");
         for tag in tags {
-            frontmatter.push_str(&format!("  - {}\n", tag));
+            frontmatter.push_str(&format!("  - {}\n", yaml_scalar(tag)));
         }
     }
     frontmatter.push_str("---\n");
@@ -361,45 +574,146 @@ fn section_value_to_string(value: &Value) -> String {
     }
 }
 
-pub(crate) fn render_markdown(
+/// Where a form's fields go in rendered markdown, from the optional `order`
+/// (ascending; unordered fields follow by name) and `group` field metadata.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FieldLayout {
+    /// Fields rendered right after the title.
+    pub ungrouped: Vec<String>,
+    /// Groups rendered after the ungrouped fields, each under a `### group`
+    /// subheading, ordered by their first field.
+    pub groups: Vec<(String, Vec<String>)>,
+}
+
+impl FieldLayout {
+    pub fn for_form(form_def: &Value) -> Self {
+        Self::for_fields(form_def.get("fields"))
+    }
+
+    pub fn for_fields(fields: Option<&Value>) -> Self {
+        let defs: Vec<(String, Value)> = match fields {
+            Some(Value::Object(map)) => map
+                .iter()
+                .map(|(name, def)| (name.clone(), def.clone()))
+                .collect(),
+            Some(Value::Array(items)) => items
+                .iter()
+                .filter_map(|item| {
+                    let name = item.get("name").and_then(|v| v.as_str())?;
+                    Some((name.to_string(), item.clone()))
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        let mut ranked: Vec<(i64, usize, String, Option<String>)> = defs
+            .into_iter()
+            .enumerate()
+            .map(|(position, (name, def))| {
+                let order = def
+                    .get("order")
+                    .and_then(|v| v.as_i64())
+                    .unwrap_or(i64::MAX);
+                let group = def
+                    .get("group")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string);
+                (order, position, name, group)
+            })
+            .collect();
+        ranked.sort_by_key(|a| (a.0, a.1));
+
+        let mut layout = Self::default();
+        for (_, _, name, group) in ranked {
+            match group {
+                None => layout.ungrouped.push(name),
+                Some(group) => match layout.groups.iter_mut().find(|(g, _)| *g == group) {
+                    Some((_, names)) => names.push(name),
+                    None => layout.groups.push((group, vec![name])),
+                },
+            }
+        }
+        layout
+    }
+
+    /// Every field name in rendering order.
+    pub fn field_names(&self) -> Vec<String> {
+        self.ungrouped
+            .iter()
+            .chain(self.groups.iter().flat_map(|(_, names)| names))
+            .cloned()
+            .collect()
+    }
+}
+
+fn push_section(markdown: &mut String, name: &str, value: &Value) {
+    markdown.push_str(&format!("## {}\n", name));
+    let rendered = section_value_to_string(value);
+    if !rendered.is_empty() {
+        markdown.push_str(&rendered);
+        markdown.push('\n');
+    }
+    markdown.push('\n');
+}
+
+/// Renders entry markdown: frontmatter, `# title`, then one `## field`
+/// section per field in `field_order`, followed by any remaining fields
+/// sorted by name.
+pub fn render_markdown(
     title: &str,
     form_name: &str,
     tags: &[String],
     fields: &Value,
     field_order: &[String],
+) -> String {
+    let layout = FieldLayout {
+        ungrouped: field_order.to_vec(),
+        groups: Vec::new(),
+    };
+    render_markdown_with_layout(title, form_name, tags, fields, &layout)
+}
+
+/// [`render_markdown`] with grouped fields under `### group` subheadings
+/// after the ungrouped ones. Fields outside the layout follow the ungrouped
+/// fields, sorted by name. Section parsing skips the subheadings, so the
+/// markdown reads back to the same fields.
+pub fn render_markdown_with_layout(
+    title: &str,
+    form_name: &str,
+    tags: &[String],
+    fields: &Value,
+    layout: &FieldLayout,
 ) -> String {
     let mut markdown = String::new();
     markdown.push_str(&render_frontmatter(form_name, tags));
     markdown.push_str(&format!("# {}\n\n", title));
 
-    let mut ordered_fields = Vec::new();
-    let field_map = fields.as_object();
-    if let Some(map) = field_map {
-        let mut seen = HashSet::new();
-        for name in field_order {
-            if let Some(value) = map.get(name) {
-                ordered_fields.push((name.clone(), value.clone()));
-                seen.insert(name.clone());
-            }
-        }
-        let mut remaining = Vec::new();
-        for (name, value) in map {
-            if !seen.contains(name) {
-                remaining.push((name.clone(), value.clone()));
-            }
+    let Some(map) = fields.as_object() else {
+        return markdown.trim_end().to_string();
+    };
+    let placed: HashSet<String> = layout.field_names().into_iter().collect();
+    for name in &layout.ungrouped {
+        if let Some(value) = map.get(name) {
+            push_section(&mut markdown, name, value);
         }
-        remaining.sort_by(|a, b| a.0.cmp(&b.0));
-        ordered_fields.extend(remaining);
     }
-
-    for (name, value) in ordered_fields {
-        markdown.push_str(&format!("## {}\n", name));
-        let rendered = section_value_to_string(&value);
-        if !rendered.is_empty() {
-            markdown.push_str(&rendered);
-            markdown.push('\n');
+    let mut remaining: Vec<(&String, &Value)> = map
+        .iter()
+        .filter(|(name, _)| !placed.contains(*name))
+        .collect();
+    remaining.sort_by(|a, b| a.0.cmp(b.0));
+    for (name, value) in remaining {
+        push_section(&mut markdown, name, value);
+    }
+    for (group, names) in &layout.groups {
+        if !names.iter().any(|name| map.contains_key(name)) {
+            continue;
+        }
+        markdown.push_str(&format!("### {}\n\n", group));
+        for name in names {
+            if let Some(value) = map.get(name) {
+                push_section(&mut markdown, name, value);
+            }
         }
-        markdown.push('\n');
     }
 
     markdown.trim_end().to_string()
@@ -423,9 +737,9 @@ pub(crate) fn render_markdown_for_form(
     extra_attributes: &Value,
     form_def: &Value,
 ) -> String {
-    let field_order = form_field_names(form_def);
+    let layout = FieldLayout::for_form(form_def);
     let merged_fields = merge_entry_fields(fields, extra_attributes);
-    render_markdown(title, form_name, tags, &merged_fields, &field_order)
+    render_markdown_with_layout(title, form_name, tags, &merged_fields, &layout)
 }
 
 fn form_field_defs(form_def: &Value) -> Vec<(String, String)> {
@@ -634,6 +948,35 @@ fn object_list_array_from_values(
     Ok(Arc::new(list_builder.finish()))
 }
 
+fn decimal_array_from_value(value: Option<&Value>, decimal: DecimalType) -> Result<ArrayRef> {
+    let unscaled = value.and_then(|v| decimal.parse_value(v));
+    let array = Decimal128Array::from(vec![unscaled])
+        .with_precision_and_scale(decimal.precision, decimal.scale as i8)
+        .map_err(|e| anyhow!("Failed to build decimal array: {}", e))?;
+    Ok(Arc::new(array))
+}
+
+fn geopoint_array_from_value(
+    value: Option<&Value>,
+    field: &arrow_schema::Field,
+) -> Result<ArrayRef> {
+    let struct_fields = struct_fields_from_field(field)?;
+    let point = value.and_then(geo::parse_geopoint);
+    let mut builder = StructBuilder::from_fields(struct_fields.clone(), 1);
+    for (idx, coordinate) in struct_fields.iter().enumerate() {
+        let number = point.map(|point| match coordinate.name().as_str() {
+            "lat" => point.lat,
+            _ => point.lon,
+        });
+        builder
+            .field_builder::<Float64Builder>(idx)
+            .ok_or_else(|| anyhow!("Invalid geopoint field builder: {}", coordinate.name()))?
+            .append_option(number);
+    }
+    builder.append(point.is_some());
+    Ok(Arc::new(builder.finish()))
+}
+
 fn list_struct_fields_from_field(list_field: &arrow_schema::Field) -> Result<Fields> {
     let element_field = list_element_field(list_field)?;
     match element_field.data_type() {
@@ -950,6 +1293,11 @@ fn struct_array_from_fields(
         let field_type = type_map.get(name).map(String::as_str).unwrap_or("string");
         let value = fields_value.get(name);
 
+        if let Some(decimal) = DecimalType::parse(field_type) {
+            arrays.push(decimal_array_from_value(value, decimal)?);
+            continue;
+        }
+
         let array: ArrayRef = match field_type {
             "number" | "double" => {
                 let number = value.and_then(|v| v.as_f64());
@@ -1028,7 +1376,8 @@ fn struct_array_from_fields(
             }
             "list" => list_array_from_values(value, field.as_ref())?,
             "object_list" => object_list_array_from_values(value, field.as_ref())?,
-            "sql" | "markdown" | "string" | "row_reference" => {
+            "geopoint" => geopoint_array_from_value(value, field.as_ref())?,
+            "sql" | "markdown" | "string" | "row_reference" | "asset_ref" | "url" | "geojson" => {
                 let string_value = value.and_then(|v| v.as_str()).map(|s| s.to_string());
                 Arc::new(StringArray::from(vec![string_value]))
             }
@@ -1047,7 +1396,53 @@ fn struct_array_from_fields(
 }
 
 async fn scan_table_batches(table: &iceberg::table::Table) -> Result<Vec<RecordBatch>> {
-    let scan = table.scan().build()?;
+    read_scan_batches(table, table.scan().build()?).await
+}
+
+/// Scans only rows whose string `column` equals `value`.
+///
+/// The predicate prunes data files through manifest column bounds and row
+/// groups through Parquet statistics, so point lookups on `entry_id` or
+/// `revision_id` skip unrelated files instead of decoding the whole table.
+async fn scan_table_batches_matching(
+    table: &iceberg::table::Table,
+    column: &str,
+    value: &str,
+) -> Result<Vec<RecordBatch>> {
+    if column == "entry_id" {
+        crate::storage::record_entry_lookup(value);
+    }
+    let predicate = Reference::new(column).equal_to(Datum::string(value));
+    let scan = table
+        .scan()
+        .with_filter(predicate)
+        .with_row_group_filtering_enabled(true)
+        .build()?;
+    read_scan_batches(table, scan).await
+}
+
+/// Scans `columns` of the table only, optionally restricted to rows whose
+/// `entry_id` equals `entry_id`. Unselected columns are never decoded.
+async fn scan_table_columns(
+    table: &iceberg::table::Table,
+    columns: &[&str],
+    entry_id: Option<&str>,
+) -> Result<Vec<RecordBatch>> {
+    let mut scan = table.scan().select(columns.iter().copied());
+    if let Some(entry_id) = entry_id {
+        crate::storage::record_entry_lookup(entry_id);
+        scan = scan
+            .with_filter(Reference::new("entry_id").equal_to(Datum::string(entry_id)))
+            .with_row_group_filtering_enabled(true);
+    }
+    read_scan_batches(table, scan.build()?).await
+}
+
+async fn read_scan_batches(
+    table: &iceberg::table::Table,
+    scan: iceberg::scan::TableScan,
+) -> Result<Vec<RecordBatch>> {
+    let started = Instant::now();
     let tasks = scan.plan_files().await?;
     let reader = ArrowReaderBuilder::new(table.file_io().clone()).build();
     let mut stream = reader.read(tasks)?;
@@ -1055,6 +1450,7 @@ async fn scan_table_batches(table: &iceberg::table::Table) -> Result<Vec<RecordB
     while let Some(batch) = stream.try_next().await? {
         batches.push(batch);
     }
+    crate::storage::record_iceberg_scan(&iceberg_store::table_key(table), started.elapsed());
     Ok(batches)
 }
 
@@ -1066,7 +1462,7 @@ async fn latest_revision_for_entry(
     entry_id: &str,
 ) -> Result<Option<RevisionRow>> {
     let (_, table) = iceberg_store::load_revisions_table(op, ws_path, form_name).await?;
-    let batches = scan_table_batches(&table).await?;
+    let batches = scan_table_batches_matching(&table, "entry_id", entry_id).await?;
     let rows = revision_rows_from_batches(&batches, form_def)?;
     let mut selected: Option<RevisionRow> = None;
     for row in rows {
@@ -1103,6 +1499,20 @@ fn value_from_struct_array(struct_array: &StructArray, row: usize, form_def: &Va
         let field_type = type_map.get(name).map(String::as_str).unwrap_or("string");
         let column = struct_array.column(idx);
 
+        if let Some(decimal) = DecimalType::parse(field_type) {
+            if let Some(array) = column
+                .as_any()
+                .downcast_ref::<Decimal128Array>()
+                .filter(|array| !array.is_null(row))
+            {
+                map.insert(
+                    name.to_string(),
+                    Value::String(decimal.format(array.value(row))),
+                );
+            }
+            continue;
+        }
+
         let value = match field_type {
             "number" | "double" => {
                 column
@@ -1312,16 +1722,34 @@ fn value_from_struct_array(struct_array: &StructArray, row: usize, form_def: &Va
                     }
                     Some(Value::Array(items))
                 }),
-            "sql" | "markdown" | "string" | "row_reference" => column
+            "geopoint" => column
                 .as_any()
-                .downcast_ref::<StringArray>()
+                .downcast_ref::<StructArray>()
                 .and_then(|array| {
                     if array.is_null(row) {
-                        None
-                    } else {
-                        Some(Value::String(array.value(row).to_string()))
+                        return None;
                     }
+                    let coordinate = |name: &str| {
+                        array
+                            .column_by_name(name)
+                            .and_then(|col| col.as_any().downcast_ref::<Float64Array>())
+                            .filter(|col| !col.is_null(row))
+                            .map(|col| col.value(row))
+                    };
+                    Some(serde_json::json!({"lat": coordinate("lat")?, "lon": coordinate("lon")?}))
                 }),
+            "sql" | "markdown" | "string" | "row_reference" | "asset_ref" | "url" | "geojson" => {
+                column
+                    .as_any()
+                    .downcast_ref::<StringArray>()
+                    .and_then(|array| {
+                        if array.is_null(row) {
+                            None
+                        } else {
+                            Some(Value::String(array.value(row).to_string()))
+                        }
+                    })
+            }
             _ => column
                 .as_any()
                 .downcast_ref::<StringArray>()
@@ -1363,6 +1791,16 @@ fn entry_rows_from_batches(
         let integrity = column_as::<StructArray>(batch, "integrity")?;
         let deleted = column_as::<BooleanArray>(batch, "deleted")?;
         let deleted_at = column_as::<TimestampMicrosecondArray>(batch, "deleted_at")?;
+        let metadata = batch
+            .column_by_name("metadata")
+            .and_then(|col| col.as_any().downcast_ref::<StringArray>());
+        // Tables created before archiving existed lack the column.
+        let archived = batch
+            .column_by_name("archived")
+            .and_then(|col| col.as_any().downcast_ref::<BooleanArray>());
+        let hard_deleted = batch
+            .column_by_name("hard_deleted")
+            .and_then(|col| col.as_any().downcast_ref::<BooleanArray>());
 
         for row_idx in 0..batch.num_rows() {
             if entry_ids.is_null(row_idx) {
@@ -1393,7 +1831,7 @@ fn entry_rows_from_batches(
             let deleted_at_value = if deleted_at.is_null(row_idx) {
                 None
             } else {
-                Some(from_timestamp_micros(deleted_at.value(row_idx)))
+                Some(deleted_at.value(row_idx))
             };
 
             rows.push(EntryRow {
@@ -1407,14 +1845,14 @@ fn entry_rows_from_batches(
                 tags: tags_value,
                 links: links_value,
                 created_at: if created_at.is_null(row_idx) {
-                    0.0
+                    0
                 } else {
-                    from_timestamp_micros(created_at.value(row_idx))
+                    created_at.value(row_idx)
                 },
                 updated_at: if updated_at.is_null(row_idx) {
-                    0.0
+                    0
                 } else {
-                    from_timestamp_micros(updated_at.value(row_idx))
+                    updated_at.value(row_idx)
                 },
                 fields: fields_value,
                 extra_attributes: extra_attributes_value,
@@ -1425,6 +1863,15 @@ fn entry_rows_from_batches(
                 deleted: !deleted.is_null(row_idx) && deleted.value(row_idx),
                 deleted_at: deleted_at_value,
                 author: "".to_string(),
+                metadata: extra_attributes_from_string(
+                    metadata
+                        .filter(|array| !array.is_null(row_idx))
+                        .map(|array| array.value(row_idx)),
+                ),
+                archived: archived
+                    .is_some_and(|array| !array.is_null(row_idx) && array.value(row_idx)),
+                hard_deleted: hard_deleted
+                    .is_some_and(|array| !array.is_null(row_idx) && array.value(row_idx)),
             });
         }
     }
@@ -1449,6 +1896,12 @@ fn revision_rows_from_batches(
         let checksums = column_as::<StringArray>(batch, "markdown_checksum")?;
         let integrity = column_as::<StructArray>(batch, "integrity")?;
         let restored_from = column_as::<StringArray>(batch, "restored_from")?;
+        let changed_fields = batch
+            .column_by_name("changed_fields")
+            .and_then(|col| col.as_any().downcast_ref::<StringArray>());
+        let messages = batch
+            .column_by_name("message")
+            .and_then(|col| col.as_any().downcast_ref::<StringArray>());
 
         for row_idx in 0..batch.num_rows() {
             if revision_ids.is_null(row_idx) {
@@ -1486,9 +1939,9 @@ fn revision_rows_from_batches(
                     Some(parent_revision_ids.value(row_idx).to_string())
                 },
                 timestamp: if timestamps.is_null(row_idx) {
-                    0.0
+                    0
                 } else {
-                    from_timestamp_micros(timestamps.value(row_idx))
+                    timestamps.value(row_idx)
                 },
                 author: if authors.is_null(row_idx) {
                     "".to_string()
@@ -1508,6 +1961,15 @@ fn revision_rows_from_batches(
                 } else {
                     Some(restored_from.value(row_idx).to_string())
                 },
+                changed_fields: match changed_fields {
+                    Some(array) if !array.is_null(row_idx) => {
+                        changed_fields_from_string(array.value(row_idx))
+                    }
+                    _ => Vec::new(),
+                },
+                message: messages
+                    .filter(|array| !array.is_null(row_idx))
+                    .map(|array| array.value(row_idx).to_string()),
             });
         }
     }
@@ -1528,12 +1990,8 @@ fn entry_row_to_record_batch(
             "title" => Arc::new(StringArray::from(vec![Some(row.title.clone())])),
             "tags" => list_array_from_strings(&row.tags, field.as_ref())?,
             "links" => list_links_array_from_links(&row.links, field.as_ref())?,
-            "created_at" => Arc::new(TimestampMicrosecondArray::from(vec![Some(
-                to_timestamp_micros(row.created_at),
-            )])),
-            "updated_at" => Arc::new(TimestampMicrosecondArray::from(vec![Some(
-                to_timestamp_micros(row.updated_at),
-            )])),
+            "created_at" => Arc::new(TimestampMicrosecondArray::from(vec![Some(row.created_at)])),
+            "updated_at" => Arc::new(TimestampMicrosecondArray::from(vec![Some(row.updated_at)])),
             "fields" => {
                 let struct_fields = struct_fields_from_field(field.as_ref())?;
                 struct_array_from_fields(form_def, &row.fields, &struct_fields)?
@@ -1548,9 +2006,12 @@ fn entry_row_to_record_batch(
                 struct_array_from_integrity(&row.integrity, &struct_fields)?
             }
             "deleted" => Arc::new(BooleanArray::from(vec![Some(row.deleted)])),
-            "deleted_at" => Arc::new(TimestampMicrosecondArray::from(vec![row
-                .deleted_at
-                .map(to_timestamp_micros)])),
+            "deleted_at" => Arc::new(TimestampMicrosecondArray::from(vec![row.deleted_at])),
+            "metadata" => Arc::new(StringArray::from(vec![extra_attributes_to_string(
+                &row.metadata,
+            )])),
+            "archived" => Arc::new(BooleanArray::from(vec![Some(row.archived)])),
+            "hard_deleted" => Arc::new(BooleanArray::from(vec![Some(row.hard_deleted)])),
             other => {
                 return Err(anyhow!("Unexpected column in entries schema: {}", other));
             }
@@ -1576,9 +2037,7 @@ fn revision_row_to_record_batch(
             "parent_revision_id" => {
                 Arc::new(StringArray::from(vec![row.parent_revision_id.clone()]))
             }
-            "timestamp" => Arc::new(TimestampMicrosecondArray::from(vec![Some(
-                to_timestamp_micros(row.timestamp),
-            )])),
+            "timestamp" => Arc::new(TimestampMicrosecondArray::from(vec![Some(row.timestamp)])),
             "author" => Arc::new(StringArray::from(vec![Some(row.author.clone())])),
             "fields" => {
                 let struct_fields = struct_fields_from_field(field.as_ref())?;
@@ -1596,6 +2055,10 @@ fn revision_row_to_record_batch(
                 struct_array_from_integrity(&row.integrity, &struct_fields)?
             }
             "restored_from" => Arc::new(StringArray::from(vec![row.restored_from.clone()])),
+            "changed_fields" => Arc::new(StringArray::from(vec![changed_fields_to_string(
+                &row.changed_fields,
+            )])),
+            "message" => Arc::new(StringArray::from(vec![row.message.clone()])),
             other => {
                 return Err(anyhow!("Unexpected column in revisions schema: {}", other));
             }
@@ -1606,9 +2069,110 @@ fn revision_row_to_record_batch(
     RecordBatch::try_new(arrow_schema, arrays).map_err(|e| anyhow!("Record batch error: {}", e))
 }
 
-async fn write_record_batch(table: &iceberg::table::Table, batch: RecordBatch) -> Result<DataFile> {
+/// Parquet writer settings for a space's data files.
+///
+/// Read from the `parquet` object in the space `settings.json`, e.g.
+/// `{"compression": "zstd", "compression_level": 3, "dictionary": true,
+/// "max_row_group_size": 65536, "bloom_filters": true}`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ParquetSettings {
+    /// One of `uncompressed`, `snappy`, `gzip`, `lz4`, `zstd` or `brotli`.
+    #[serde(default = "default_parquet_compression")]
+    pub compression: String,
+    /// Codec level for `zstd`, `gzip` and `brotli`; the codec default when unset.
+    #[serde(default)]
+    pub compression_level: Option<u32>,
+    #[serde(default = "default_true")]
+    pub dictionary: bool,
+    #[serde(default)]
+    pub max_row_group_size: Option<usize>,
+    /// Write Parquet bloom filters for the id columns used by point lookups.
+    #[serde(default = "default_true")]
+    pub bloom_filters: bool,
+}
+
+/// Columns that point lookups filter on and therefore get bloom filters.
+const BLOOM_FILTER_COLUMNS: [&str; 2] = ["entry_id", "revision_id"];
+
+fn default_parquet_compression() -> String {
+    "zstd".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for ParquetSettings {
+    fn default() -> Self {
+        Self {
+            compression: default_parquet_compression(),
+            compression_level: None,
+            dictionary: true,
+            max_row_group_size: None,
+            bloom_filters: true,
+        }
+    }
+}
+
+impl ParquetSettings {
+    pub async fn for_space(op: &Operator, ws_path: &str) -> Result<Self> {
+        let settings = space::read_space_settings(op, ws_path).await?;
+        match settings.get("parquet") {
+            Some(value) if value.is_object() => Ok(serde_json::from_value(value.clone())?),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn compression(&self) -> Result<Compression> {
+        let level = self.compression_level;
+        let invalid = |e: parquet::errors::ParquetError| {
+            anyhow!("Invalid {} compression level: {}", self.compression, e)
+        };
+        Ok(match self.compression.to_ascii_lowercase().as_str() {
+            "uncompressed" | "none" => Compression::UNCOMPRESSED,
+            "snappy" => Compression::SNAPPY,
+            "lz4" => Compression::LZ4_RAW,
+            "zstd" => Compression::ZSTD(match level {
+                Some(level) => ZstdLevel::try_new(level as i32).map_err(invalid)?,
+                None => ZstdLevel::default(),
+            }),
+            "gzip" => Compression::GZIP(match level {
+                Some(level) => GzipLevel::try_new(level).map_err(invalid)?,
+                None => GzipLevel::default(),
+            }),
+            "brotli" => Compression::BROTLI(match level {
+                Some(level) => BrotliLevel::try_new(level).map_err(invalid)?,
+                None => BrotliLevel::default(),
+            }),
+            other => return Err(anyhow!("Unsupported parquet compression: {}", other)),
+        })
+    }
+
+    pub fn writer_properties(&self) -> Result<WriterProperties> {
+        let mut builder = WriterProperties::builder()
+            .set_compression(self.compression()?)
+            .set_dictionary_enabled(self.dictionary);
+        if let Some(size) = self.max_row_group_size {
+            if size == 0 {
+                return Err(anyhow!("max_row_group_size must be positive"));
+            }
+            builder = builder.set_max_row_group_size(size);
+        }
+        if self.bloom_filters {
+            for column in BLOOM_FILTER_COLUMNS {
+                builder = builder.set_column_bloom_filter_enabled(ColumnPath::from(column), true);
+            }
+        }
+        Ok(builder.build())
+    }
+}
+
+async fn write_record_batch(
+    table: &iceberg::table::Table,
+    batch: RecordBatch,
+    props: WriterProperties,
+) -> Result<DataFile> {
     let schema = table.metadata().current_schema();
-    let props = WriterProperties::builder().build();
     let output_path = format!(
         "{}/data/{}.parquet",
         table.metadata().location(),
@@ -1635,33 +2199,49 @@ async fn write_record_batch(table: &iceberg::table::Table, batch: RecordBatch) -
 }
 
 async fn append_entry_row_to_table(
+    op: &Operator,
+    ws_path: &str,
     catalog: &MemoryCatalog,
     table: &iceberg::table::Table,
     row: &EntryRow,
     form_def: &Value,
 ) -> Result<()> {
     let batch = entry_row_to_record_batch(row, form_def, table.metadata().current_schema())?;
-    let data_file = write_record_batch(table, batch).await?;
+    crate::storage::wait_space_turn(op, ws_path).await;
+    let props = ParquetSettings::for_space(op, ws_path)
+        .await?
+        .writer_properties()?;
+    let started = Instant::now();
+    let data_file = write_record_batch(table, batch, props).await?;
     let tx = Transaction::new(table);
     let action = tx.fast_append().add_data_files(vec![data_file]);
     let tx = action.apply(tx)?;
-    tx.commit(catalog).await?;
-    Ok(())
+    let committed = tx.commit(catalog).await?;
+    crate::storage::record_iceberg_commit(&iceberg_store::table_key(table), started.elapsed());
+    iceberg_store::record_table_location(op, ws_path, &committed).await
 }
 
 async fn append_revision_row_to_table(
+    op: &Operator,
+    ws_path: &str,
     catalog: &MemoryCatalog,
     table: &iceberg::table::Table,
     row: &RevisionRow,
     form_def: &Value,
 ) -> Result<()> {
     let batch = revision_row_to_record_batch(row, form_def, table.metadata().current_schema())?;
-    let data_file = write_record_batch(table, batch).await?;
+    crate::storage::wait_space_turn(op, ws_path).await;
+    let props = ParquetSettings::for_space(op, ws_path)
+        .await?
+        .writer_properties()?;
+    let started = Instant::now();
+    let data_file = write_record_batch(table, batch, props).await?;
     let tx = Transaction::new(table);
     let action = tx.fast_append().add_data_files(vec![data_file]);
     let tx = action.apply(tx)?;
-    tx.commit(catalog).await?;
-    Ok(())
+    let committed = tx.commit(catalog).await?;
+    crate::storage::record_iceberg_commit(&iceberg_store::table_key(table), started.elapsed());
+    iceberg_store::record_table_location(op, ws_path, &committed).await
 }
 
 pub(crate) async fn list_form_names(op: &Operator, ws_path: &str) -> Result<Vec<String>> {
@@ -1673,11 +2253,60 @@ pub(crate) async fn find_entry_form(
     ws_path: &str,
     entry_id: &str,
 ) -> Result<Option<String>> {
-    let rows = list_entry_rows(op, ws_path).await?;
-    Ok(rows
-        .into_iter()
-        .find(|(_, row)| row.entry_id == entry_id)
-        .map(|(form_name, _)| form_name))
+    Ok(exists(op, ws_path, entry_id)
+        .await?
+        .map(|existence| existence.form))
+}
+
+/// Checks whether `entry_id` is in use, and by which form, from the per-form
+/// id lists kept next to the index shards. Forms whose list is stale fall
+/// back to a filtered scan of that form's table only.
+pub async fn exists(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+) -> Result<Option<EntryExistence>> {
+    let indexed = index::index_is_compatible(op, ws_path).await?;
+    let mut found: Option<EntryExistence> = None;
+    for form_name in list_form_names(op, ws_path).await? {
+        let lookup = if indexed {
+            index::lookup_indexed_entry(op, ws_path, &form_name, entry_id).await?
+        } else {
+            index::IndexedEntry::Stale
+        };
+        let state = match lookup {
+            index::IndexedEntry::Present {
+                deleted,
+                updated_at,
+            } => Some((deleted, updated_at)),
+            index::IndexedEntry::Absent => None,
+            index::IndexedEntry::Stale => {
+                let form_def = form::read_form_definition(op, ws_path, &form_name).await?;
+                let (_, table) = iceberg_store::load_entries_table(op, ws_path, &form_name).await?;
+                let batches = scan_table_batches_matching(&table, "entry_id", entry_id).await?;
+                entry_rows_from_batches(&batches, &form_def, &form_name)?
+                    .into_iter()
+                    .filter(|row| row.entry_id == entry_id)
+                    .max_by_key(|row| row.updated_at)
+                    .map(|row| (row.deleted, clock::from_micros(row.updated_at)))
+            }
+        };
+        let Some((deleted, updated_at)) = state else {
+            continue;
+        };
+        if found
+            .as_ref()
+            .is_none_or(|existing| updated_at >= existing.updated_at)
+        {
+            found = Some(EntryExistence {
+                entry_id: entry_id.to_string(),
+                form: form_name,
+                deleted,
+                updated_at,
+            });
+        }
+    }
+    Ok(found)
 }
 
 pub(crate) async fn read_entry_row(
@@ -1688,7 +2317,7 @@ pub(crate) async fn read_entry_row(
 ) -> Result<EntryRow> {
     let form_def = form::read_form_definition(op, ws_path, form_name).await?;
     let (_, table) = iceberg_store::load_entries_table(op, ws_path, form_name).await?;
-    let batches = scan_table_batches(&table).await?;
+    let batches = scan_table_batches_matching(&table, "entry_id", entry_id).await?;
     let rows = entry_rows_from_batches(&batches, &form_def, form_name)?;
     let mut selected: Option<EntryRow> = None;
     for row in rows {
@@ -1703,7 +2332,7 @@ pub(crate) async fn read_entry_row(
             selected = Some(row);
         }
     }
-    let mut selected = selected.ok_or_else(|| anyhow!("Entry not found: {}", entry_id))?;
+    let mut selected = selected.ok_or_else(|| entry_not_found(entry_id))?;
     if let Some(latest) =
         latest_revision_for_entry(op, ws_path, form_name, &form_def, entry_id).await?
     {
@@ -1729,7 +2358,7 @@ pub(crate) async fn write_entry_row(
     let (catalog, table): (Arc<MemoryCatalog>, iceberg::table::Table) =
         iceberg_store::load_entries_table(op, ws_path, form_name).await?;
     let form_def = form::read_form_definition(op, ws_path, form_name).await?;
-    append_entry_row_to_table(catalog.as_ref(), &table, row, &form_def).await
+    append_entry_row_to_table(op, ws_path, catalog.as_ref(), &table, row, &form_def).await
 }
 
 pub(crate) async fn list_entry_rows(
@@ -1766,93 +2395,250 @@ pub(crate) async fn list_form_entry_rows(
     let (_, table) = iceberg_store::load_entries_table(op, ws_path, form_name).await?;
     let batches = scan_table_batches(&table).await?;
     let rows = entry_rows_from_batches(&batches, form_def, form_name)?;
-    let mut latest: std::collections::HashMap<String, EntryRow> = std::collections::HashMap::new();
-    for row in rows {
-        let entry = latest.get(&row.entry_id);
-        let should_replace = match entry {
-            Some(existing) => row.updated_at >= existing.updated_at,
-            None => true,
-        };
-        if should_replace {
-            latest.insert(row.entry_id.clone(), row);
-        }
-    }
-    Ok(latest.into_values().collect())
-}
-
-pub(crate) async fn list_form_revision_rows(
-    op: &Operator,
-    ws_path: &str,
-    form_name: &str,
-    form_def: &Value,
-) -> Result<Vec<RevisionRow>> {
-    let (_, table) = iceberg_store::load_revisions_table(op, ws_path, form_name).await?;
-    let batches = scan_table_batches(&table).await?;
-    revision_rows_from_batches(&batches, form_def)
+    Ok(latest_entry_rows(rows))
 }
 
-pub(crate) async fn append_revision_row_for_form(
+/// Newest row of each entry in `form_name` (or of `entry_id` alone) with
+/// only the requested `fields` read.
+///
+/// The scan projects the entries table to the id, title, tags,
+/// `updated_at` and `deleted` columns plus the `fields` struct and
+/// `extra_attributes` only when a requested name lives there, so links,
+/// assets, integrity and unrequested fields are not deserialized. The
+/// returned rows leave every other column at its default.
+pub(crate) async fn list_projected_entry_rows(
     op: &Operator,
     ws_path: &str,
     form_name: &str,
-    row: &RevisionRow,
     form_def: &Value,
-) -> Result<()> {
-    let (catalog, table): (Arc<MemoryCatalog>, iceberg::table::Table) =
-        iceberg_store::load_revisions_table(op, ws_path, form_name).await?;
-    append_revision_row_to_table(catalog.as_ref(), &table, row, form_def).await
-}
-
-fn extract_tags(frontmatter: &Value) -> Vec<String> {
-    match frontmatter.get("tags") {
-        Some(Value::Array(items)) => items
-            .iter()
-            .filter_map(|v| v.as_str().map(|s| s.to_string()))
-            .collect(),
-        Some(Value::String(tag)) => vec![tag.to_string()],
-        _ => Vec::new(),
+    entry_id: Option<&str>,
+    fields: &[String],
+) -> Result<Vec<EntryRow>> {
+    let form_fields = form_field_type_map(form_def);
+    let (in_form, extra): (Vec<&String>, Vec<&String>) = fields
+        .iter()
+        .partition(|name| form_fields.contains_key(name.as_str()));
+    let mut columns = vec!["entry_id", "title", "tags", "updated_at", "deleted"];
+    if !in_form.is_empty() {
+        columns.push("fields");
     }
-}
+    let (_, table) = iceberg_store::load_entries_table(op, ws_path, form_name).await?;
+    // Tables created before extra attributes were stored lack the column.
+    let has_extra_attributes = table
+        .metadata()
+        .current_schema()
+        .field_by_name("extra_attributes")
+        .is_some();
+    if !extra.is_empty() && has_extra_attributes {
+        columns.push("extra_attributes");
+    }
+    let batches = scan_table_columns(&table, &columns, entry_id).await?;
 
-fn extract_form(frontmatter: &Value) -> Option<String> {
-    frontmatter
-        .get("form")
-        .and_then(|v| v.as_str())
-        .map(|s| s.to_string())
-}
+    let mut rows = Vec::new();
+    for batch in &batches {
+        let entry_ids = column_as::<StringArray>(batch, "entry_id")?;
+        let titles = column_as::<StringArray>(batch, "title")?;
+        let tags = column_as::<ListArray>(batch, "tags")?;
+        let updated_at = column_as::<TimestampMicrosecondArray>(batch, "updated_at")?;
+        let deleted = column_as::<BooleanArray>(batch, "deleted")?;
+        let field_values = match batch
+            .column_by_name("fields")
+            .and_then(|col| col.as_any().downcast_ref::<StructArray>())
+        {
+            Some(array) => project_struct_array(array, &in_form)?,
+            None => None,
+        };
+        let extra_attributes = batch
+            .column_by_name("extra_attributes")
+            .and_then(|col| col.as_any().downcast_ref::<StringArray>());
 
-pub async fn create_entry<I: IntegrityProvider>(
+        for row_idx in 0..batch.num_rows() {
+            if entry_ids.is_null(row_idx) {
+                continue;
+            }
+            let fields_value = match &field_values {
+                Some(array) if !array.is_null(row_idx) => {
+                    value_from_struct_array(array, row_idx, form_def)
+                }
+                _ => Value::Object(Map::new()),
+            };
+            let mut extra_value = Map::new();
+            if let Some(array) = extra_attributes.filter(|array| !array.is_null(row_idx)) {
+                if let Value::Object(map) = extra_attributes_from_string(Some(array.value(row_idx)))
+                {
+                    extra_value = map
+                        .into_iter()
+                        .filter(|(key, _)| extra.iter().any(|name| name.as_str() == key))
+                        .collect();
+                }
+            }
+            rows.push(EntryRow {
+                entry_id: entry_ids.value(row_idx).to_string(),
+                title: if titles.is_null(row_idx) {
+                    "".to_string()
+                } else {
+                    titles.value(row_idx).to_string()
+                },
+                form: form_name.to_string(),
+                tags: list_strings_from_array(tags, row_idx),
+                links: Vec::new(),
+                created_at: 0,
+                updated_at: if updated_at.is_null(row_idx) {
+                    0
+                } else {
+                    updated_at.value(row_idx)
+                },
+                fields: fields_value,
+                extra_attributes: Value::Object(extra_value),
+                revision_id: "".to_string(),
+                parent_revision_id: None,
+                assets: Vec::new(),
+                integrity: IntegrityPayload::default(),
+                deleted: !deleted.is_null(row_idx) && deleted.value(row_idx),
+                deleted_at: None,
+                author: "".to_string(),
+                metadata: Value::Object(Map::new()),
+                archived: false,
+                hard_deleted: false,
+            });
+        }
+    }
+    rows.retain(|row| entry_id.is_none_or(|id| row.entry_id == id));
+    Ok(latest_entry_rows(rows))
+}
+
+/// `array` with only the children named in `names`, or `None` when none of
+/// them is stored.
+fn project_struct_array(array: &StructArray, names: &[&String]) -> Result<Option<StructArray>> {
+    let (fields, columns): (Vec<_>, Vec<_>) = array
+        .fields()
+        .iter()
+        .zip(array.columns())
+        .filter(|(field, _)| names.iter().any(|name| *name == field.name()))
+        .map(|(field, column)| (field.clone(), column.clone()))
+        .unzip();
+    if fields.is_empty() {
+        return Ok(None);
+    }
+    StructArray::try_new(Fields::from(fields), columns, array.nulls().cloned())
+        .map(Some)
+        .map_err(|e| anyhow!("Failed to project fields struct array: {}", e))
+}
+
+/// Keeps the newest row of each entry.
+fn latest_entry_rows(rows: Vec<EntryRow>) -> Vec<EntryRow> {
+    let mut latest: std::collections::HashMap<String, EntryRow> = std::collections::HashMap::new();
+    for row in rows {
+        let entry = latest.get(&row.entry_id);
+        let should_replace = match entry {
+            Some(existing) => row.updated_at >= existing.updated_at,
+            None => true,
+        };
+        if should_replace {
+            latest.insert(row.entry_id.clone(), row);
+        }
+    }
+    latest.into_values().collect()
+}
+
+/// Rows of `form_name` written after entries-table snapshot `since`, read
+/// from the data files appended since then, newest per entry, with the
+/// snapshot they are current as of. `None` when the table history does not
+/// allow an incremental read (see
+/// [`iceberg_store::data_files_appended_since`]).
+pub(crate) async fn list_form_entry_rows_since(
     op: &Operator,
     ws_path: &str,
-    entry_id: &str,
-    content: &str,
-    author: &str,
-    integrity: &I,
-) -> Result<EntryMeta> {
-    if find_entry_form(op, ws_path, entry_id).await?.is_some() {
-        return Err(anyhow!("Entry already exists: {}", entry_id));
+    form_name: &str,
+    form_def: &Value,
+    since: Option<i64>,
+) -> Result<Option<(Option<i64>, Vec<EntryRow>)>> {
+    let (_, table) = iceberg_store::load_entries_table(op, ws_path, form_name).await?;
+    let Some((snapshot_id, files)) =
+        iceberg_store::data_files_appended_since(&table, since).await?
+    else {
+        return Ok(None);
+    };
+    let mut batches = Vec::new();
+    for path in files {
+        let bytes = table.file_io().new_input(&path)?.read().await?;
+        for batch in ParquetRecordBatchReaderBuilder::try_new(bytes)?.build()? {
+            batches.push(batch?);
+        }
     }
+    let rows = entry_rows_from_batches(&batches, form_def, form_name)?;
+    Ok(Some((snapshot_id, latest_entry_rows(rows))))
+}
 
-    let normalized_content = normalize_ugoite_links(content);
-    let (frontmatter, sections) = parse_markdown(&normalized_content);
-    let form_name =
-        extract_form(&frontmatter).ok_or_else(|| anyhow!("Form is required for entry creation"))?;
-    let form_def = form::read_form_definition(op, ws_path, &form_name).await?;
+pub(crate) async fn list_form_revision_rows(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+    form_def: &Value,
+) -> Result<Vec<RevisionRow>> {
+    let (_, table) = iceberg_store::load_revisions_table(op, ws_path, form_name).await?;
+    let batches = scan_table_batches(&table).await?;
+    revision_rows_from_batches(&batches, form_def)
+}
+
+pub(crate) async fn append_revision_row_for_form(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+    row: &RevisionRow,
+    form_def: &Value,
+) -> Result<()> {
+    let (catalog, table): (Arc<MemoryCatalog>, iceberg::table::Table) =
+        iceberg_store::load_revisions_table(op, ws_path, form_name).await?;
+    require_revisions_columns(form_name, &table)?;
+    append_revision_row_to_table(op, ws_path, catalog.as_ref(), &table, row, form_def).await
+}
+
+fn extract_tags(frontmatter: &Value) -> Vec<String> {
+    match frontmatter.get("tags") {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|v| v.as_str().map(|s| s.to_string()))
+            .collect(),
+        Some(Value::String(tag)) => vec![tag.to_string()],
+        _ => Vec::new(),
+    }
+}
+
+fn extract_form(frontmatter: &Value) -> Option<String> {
+    frontmatter
+        .get("form")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+}
 
-    let form_fields = form_field_names(&form_def);
+/// Form fields and extra attributes of `normalized_content`, checked
+/// against `form_def` as a write would check them.
+async fn validated_fields(
+    op: &Operator,
+    ws_path: &str,
+    form_def: &Value,
+    normalized_content: &str,
+    sections: &Value,
+) -> Result<(Map<String, Value>, Value)> {
+    let form_fields = form_field_names(form_def);
     let form_set: HashSet<String> = form_fields.iter().cloned().collect();
-    let policy = extra_attributes_policy(&form_def);
-    let (extras, extra_attributes) = collect_extra_attributes(&sections, &form_set);
+    let policy = extra_attributes_policy(form_def);
+    let (extras, extra_attributes) = collect_extra_attributes(sections, &form_set);
     if !extras.is_empty() && policy == ExtraAttributesPolicy::Deny {
-        return Err(anyhow!("Unknown form fields: {}", extras.join(", ")));
+        return Err(i18n::error(
+            "unknown_form_fields",
+            serde_json::json!({"fields": extras}),
+        ));
     }
 
-    let properties = index::extract_properties(&normalized_content);
-    let (casted, warnings) = index::validate_properties(&properties, &form_def)?;
+    let properties = index::extract_properties(normalized_content);
+    let tz = SpaceTimeZone::for_space(op, ws_path).await?;
+    let (casted, warnings) = index::validate_properties_in_zone(&properties, form_def, &tz)?;
     if !warnings.is_empty() {
-        return Err(anyhow!(
-            "Form validation failed: {}",
-            serde_json::to_string(&warnings)?
+        return Err(i18n::error(
+            "form_validation_failed",
+            serde_json::json!({"warnings": warnings}),
         ));
     }
 
@@ -1871,14 +2657,104 @@ pub async fn create_entry<I: IntegrityProvider>(
             }
         }
     }
+    Ok((fields, extra_attributes))
+}
+
+/// Checks `content` as [`create_entry`] and [`update_entry`] would before
+/// writing anything: it must name an existing form and its fields must
+/// validate against it. Returns the form name.
+pub async fn validate_entry_content(op: &Operator, ws_path: &str, content: &str) -> Result<String> {
+    let normalized_content = normalize_ugoite_links(content);
+    let (frontmatter, sections) = parse_markdown(&normalized_content)?;
+    let form_name = extract_form(&frontmatter).ok_or_else(|| anyhow!("Form is required"))?;
+    let form_def = form::read_form_definition(op, ws_path, &form_name).await?;
+    validated_fields(op, ws_path, &form_def, &normalized_content, &sections).await?;
+    Ok(form_name)
+}
+
+/// Runs the form's before-write hooks on `fields`, then checks whatever they
+/// changed against the form as the fields parsed from content were checked.
+async fn run_before_write_hooks(
+    op: &Operator,
+    ctx: &hooks::WriteContext<'_>,
+    form_def: &Value,
+    fields: &mut Map<String, Value>,
+) -> Result<()> {
+    if !hooks::run_before_write(ctx, form_def, fields)? {
+        return Ok(());
+    }
+    let form_set: HashSet<String> = form_field_names(form_def).into_iter().collect();
+    let unknown: Vec<&String> = fields.keys().filter(|k| !form_set.contains(*k)).collect();
+    if !unknown.is_empty() {
+        return Err(i18n::error(
+            "unknown_form_fields",
+            serde_json::json!({"fields": unknown}),
+        ));
+    }
+    let tz = SpaceTimeZone::for_space(op, ctx.ws_path).await?;
+    let (casted, warnings) =
+        index::validate_properties_in_zone(&Value::Object(fields.clone()), form_def, &tz)?;
+    if !warnings.is_empty() {
+        return Err(i18n::error(
+            "form_validation_failed",
+            serde_json::json!({"warnings": warnings}),
+        ));
+    }
+    if let Value::Object(casted) = casted {
+        *fields = casted;
+    }
+    Ok(())
+}
+
+pub async fn create_entry<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+    content: &str,
+    author: &str,
+    integrity: &I,
+) -> Result<EntryMeta> {
+    if exists(op, ws_path, entry_id).await?.is_some() {
+        return Err(anyhow!("Entry already exists: {}", entry_id));
+    }
 
+    let normalized_content = normalize_ugoite_links(content);
+    let (frontmatter, sections) = parse_markdown(&normalized_content)?;
+    let form_name =
+        extract_form(&frontmatter).ok_or_else(|| anyhow!("Form is required for entry creation"))?;
+    let form_def = form::read_form_definition(op, ws_path, &form_name).await?;
+
+    let (mut fields, extra_attributes) =
+        validated_fields(op, ws_path, &form_def, &normalized_content, &sections).await?;
+
+    let warnings = form::deprecated_field_warnings(&form_def, &Value::Null, &fields);
     let title = extract_title(&normalized_content, entry_id);
     let tags = extract_tags(&frontmatter);
-    let timestamp = now_ts();
-    let revision_id = Uuid::new_v4().to_string();
-    let checksum = integrity.checksum(&normalized_content);
-    let signature = integrity.signature(&normalized_content);
-
+    let hook_ctx = hooks::WriteContext {
+        ws_path,
+        form_name: &form_name,
+        entry_id,
+        event: hooks::WriteEvent::Create,
+        author,
+    };
+    run_before_write_hooks(op, &hook_ctx, &form_def, &mut fields).await?;
+    // Sign the entry as stored, so a read can check what it renders.
+    let signed_content = render_markdown_for_form(
+        &title,
+        &form_name,
+        &tags,
+        &Value::Object(fields.clone()),
+        &extra_attributes,
+        &form_def,
+    );
+    asset::validate_asset_refs(op, ws_path, &form_def, &Value::Object(fields.clone())).await?;
+    let timestamp = clock::next_micros(ws_path, None);
+    let revision_id = entry_id::new_revision_id(op, ws_path).await?;
+    let checksum = integrity.checksum(&signed_content);
+    let signature = integrity.signature(&signed_content);
+
+    let fields = Value::Object(fields);
+    let metadata = url_field::capture_url_metadata(&form_def, &fields, &Value::Null).await;
     let entry_row = EntryRow {
         entry_id: entry_id.to_string(),
         title: title.clone(),
@@ -1887,7 +2763,7 @@ pub async fn create_entry<I: IntegrityProvider>(
         links: Vec::new(),
         created_at: timestamp,
         updated_at: timestamp,
-        fields: Value::Object(fields),
+        fields,
         extra_attributes: extra_attributes.clone(),
         revision_id: revision_id.clone(),
         parent_revision_id: None,
@@ -1899,6 +2775,9 @@ pub async fn create_entry<I: IntegrityProvider>(
         deleted: false,
         deleted_at: None,
         author: author.to_string(),
+        metadata,
+        archived: false,
+        hard_deleted: false,
     };
 
     write_entry_row(op, ws_path, &form_name, entry_id, &entry_row).await?;
@@ -1917,10 +2796,24 @@ pub async fn create_entry<I: IntegrityProvider>(
             signature: signature.clone(),
         },
         restored_from: None,
+        changed_fields: diff_fields(
+            &Value::Object(Map::new()),
+            &merge_entry_fields(&entry_row.fields, &entry_row.extra_attributes),
+        ),
+        message: None,
     };
-    let (rev_catalog, rev_table): (Arc<MemoryCatalog>, iceberg::table::Table) =
-        iceberg_store::load_revisions_table(op, ws_path, &form_name).await?;
-    append_revision_row_to_table(rev_catalog.as_ref(), &rev_table, &revision, &form_def).await?;
+    append_revision_row_for_form(op, ws_path, &form_name, &revision, &form_def).await?;
+    hooks::run_after_write(&hook_ctx, &form_def, &revision_id, &entry_row.fields)?;
+    rollup::refresh_after_write(
+        op,
+        ws_path,
+        &form_name,
+        &form_def,
+        entry_id,
+        None,
+        Some(&entry_row.fields),
+    )
+    .await?;
 
     let ws_id = ws_path
         .trim_end_matches('/')
@@ -1936,8 +2829,8 @@ pub async fn create_entry<I: IntegrityProvider>(
         form: Some(form_name),
         tags: entry_row.tags.clone(),
         links: entry_row.links.clone(),
-        created_at: timestamp,
-        updated_at: timestamp,
+        created_at: clock::from_micros(timestamp),
+        updated_at: clock::from_micros(timestamp),
         integrity: IntegrityPayload {
             checksum,
             signature,
@@ -1945,49 +2838,263 @@ pub async fn create_entry<I: IntegrityProvider>(
         deleted: false,
         deleted_at: None,
         properties: Value::Object(Map::new()),
+        warnings,
+    })
+}
+
+pub(crate) fn entry_summary(form_name: &str, row: &EntryRow) -> Value {
+    let merged_fields = merge_entry_fields(&row.fields, &row.extra_attributes);
+    serde_json::json!({
+        "id": row.entry_id,
+        "title": row.title,
+        "form": form_name,
+        "tags": row.tags,
+        "properties": merged_fields,
+        "links": row.links,
+        "created_at": clock::from_micros(row.created_at),
+        "updated_at": clock::from_micros(row.updated_at),
+        "metadata": row.metadata,
+        "archived": row.archived,
     })
 }
 
+/// Heavy fields of each form, less `include_fields`, by form name.
+async fn listing_omissions(
+    op: &Operator,
+    ws_path: &str,
+    include_fields: &[String],
+) -> Result<std::collections::HashMap<String, HashSet<String>>> {
+    let mut omissions = std::collections::HashMap::new();
+    for form_name in list_form_names(op, ws_path).await? {
+        let form_def = form::read_form_definition(op, ws_path, &form_name).await?;
+        let mut heavy = form::heavy_field_names(&form_def);
+        heavy.retain(|name| !include_fields.contains(name));
+        if !heavy.is_empty() {
+            omissions.insert(form_name, heavy);
+        }
+    }
+    Ok(omissions)
+}
+
+/// [`entry_summary`] without the `omitted` properties.
+fn listing_summary(form_name: &str, row: &EntryRow, omitted: Option<&HashSet<String>>) -> Value {
+    let mut summary = entry_summary(form_name, row);
+    if let (Some(omitted), Some(properties)) = (
+        omitted,
+        summary.get_mut("properties").and_then(Value::as_object_mut),
+    ) {
+        properties.retain(|name, _| !omitted.contains(name));
+    }
+    summary
+}
+
+/// Summaries of every live entry that is not archived. Fields their form
+/// marks `heavy` are left out of `properties`; see
+/// [`list_entries_including`].
 pub async fn list_entries(op: &Operator, ws_path: &str) -> Result<Vec<Value>> {
+    list_entries_including(op, ws_path, &[], ArchivedFilter::Exclude).await
+}
+
+/// [`list_entries`] that keeps the heavy fields named in `include_fields`
+/// and picks entries by `archived` state.
+pub async fn list_entries_including(
+    op: &Operator,
+    ws_path: &str,
+    include_fields: &[String],
+    archived: ArchivedFilter,
+) -> Result<Vec<Value>> {
+    let omissions = listing_omissions(op, ws_path, include_fields).await?;
     let mut entries = Vec::new();
     for (form_name, row) in list_entry_rows(op, ws_path).await? {
-        if row.deleted {
+        if row.deleted || !archived.keeps(row.archived) {
             continue;
         }
-        let merged_fields = merge_entry_fields(&row.fields, &row.extra_attributes);
-        entries.push(serde_json::json!({
-            "id": row.entry_id,
-            "title": row.title,
-            "form": form_name,
-            "tags": row.tags,
-            "properties": merged_fields,
-            "links": row.links,
-            "created_at": row.created_at,
-            "updated_at": row.updated_at,
-        }));
+        entries.push(listing_summary(&form_name, &row, omissions.get(&form_name)));
     }
     Ok(entries)
 }
 
-pub async fn get_entry(op: &Operator, ws_path: &str, entry_id: &str) -> Result<Value> {
+/// One page of [`list_entries`] narrowed by `filter`, ordered by entry id:
+/// `{"entries", "offset", "limit", "total_count"}`, where `total_count`
+/// counts every entry the filter matches. Without `limit` the page runs to
+/// the end. Heavy fields not in `include_fields` and entries `archived`
+/// does not keep are left out as in [`list_entries_including`].
+pub async fn list_entries_page(
+    op: &Operator,
+    ws_path: &str,
+    filter: &crate::export::SyncFilter,
+    offset: usize,
+    limit: Option<usize>,
+    include_fields: &[String],
+    archived: ArchivedFilter,
+) -> Result<Value> {
+    let omissions = listing_omissions(op, ws_path, include_fields).await?;
+    let mut rows: Vec<(String, EntryRow)> = list_entry_rows(op, ws_path)
+        .await?
+        .into_iter()
+        .filter(|(_, row)| !row.deleted && archived.keeps(row.archived))
+        .collect();
+    rows.sort_by(|a, b| a.1.entry_id.cmp(&b.1.entry_id));
+    let matching: Vec<Value> = rows
+        .iter()
+        .map(|(form_name, row)| listing_summary(form_name, row, omissions.get(form_name)))
+        .filter(|summary| filter.matches(summary))
+        .collect();
+    let total = matching.len();
+    let entries: Vec<Value> = matching
+        .into_iter()
+        .skip(offset)
+        .take(limit.unwrap_or(usize::MAX))
+        .collect();
+    Ok(serde_json::json!({
+        "entries": entries,
+        "offset": offset,
+        "limit": limit,
+        "total_count": total,
+    }))
+}
+
+/// Sends the same summaries as [`list_entries`] to `progress` as `items`
+/// messages of at most `chunk_size` entries, one form at a time, so only a
+/// form's rows are held in memory and the consumer sets the pace. Heavy
+/// fields not in `include_fields` and entries `archived` does not keep are
+/// left out.
+pub async fn stream_entries(
+    op: &Operator,
+    ws_path: &str,
+    chunk_size: usize,
+    include_fields: &[String],
+    archived: ArchivedFilter,
+    progress: &ProgressSender,
+) -> Result<()> {
+    let chunk_size = chunk_size.max(1);
+    let mut chunk = Vec::with_capacity(chunk_size);
+    for form_name in list_form_names(op, ws_path).await? {
+        let form_def = form::read_form_definition(op, ws_path, &form_name).await?;
+        let mut omitted = form::heavy_field_names(&form_def);
+        omitted.retain(|name| !include_fields.contains(name));
+        let mut rows = list_form_entry_rows(op, ws_path, &form_name, &form_def).await?;
+        rows.retain(|row| !row.deleted && archived.keeps(row.archived));
+        rows.sort_by(|a, b| a.entry_id.cmp(&b.entry_id));
+        for row in &rows {
+            chunk.push(listing_summary(&form_name, row, Some(&omitted)));
+            if chunk.len() == chunk_size {
+                progress.items(std::mem::take(&mut chunk)).await?;
+            }
+        }
+    }
+    if !chunk.is_empty() {
+        progress.items(chunk).await?;
+    }
+    Ok(())
+}
+
+/// A live entry with its form definition and the markdown it renders to.
+async fn read_live_entry(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+) -> Result<(String, EntryRow, Value, String)> {
     let form_name = find_entry_form(op, ws_path, entry_id)
         .await?
-        .ok_or_else(|| anyhow!("Entry not found: {}", entry_id))?;
+        .ok_or_else(|| entry_not_found(entry_id))?;
     let row = read_entry_row(op, ws_path, &form_name, entry_id).await?;
     if row.deleted {
-        return Err(anyhow!("Entry not found: {}", entry_id));
+        return Err(entry_not_found(entry_id));
     }
-
     let form_def = form::read_form_definition(op, ws_path, &form_name).await?;
-    let field_order = form_field_names(&form_def);
-    let merged_fields = merge_entry_fields(&row.fields, &row.extra_attributes);
-    let markdown = render_markdown(
+    let markdown = render_markdown_for_form(
         &row.title,
         &form_name,
         &row.tags,
-        &merged_fields,
-        &field_order,
+        &row.fields,
+        &row.extra_attributes,
+        &form_def,
     );
+    Ok((form_name, row, form_def, markdown))
+}
+
+pub async fn get_entry(op: &Operator, ws_path: &str, entry_id: &str) -> Result<Value> {
+    let (form_name, row, form_def, markdown) = read_live_entry(op, ws_path, entry_id).await?;
+    entry_view(op, ws_path, entry_id, &form_name, row, &form_def, markdown).await
+}
+
+/// Reads only `fields` of a live entry, as `{id, form, title, tags,
+/// updated_at, fields}`, without rendering its markdown or decoding the
+/// rest of the row (see [`list_projected_entry_rows`]). Requested names the
+/// entry has no value for are left out of `fields`.
+pub async fn get_entry_fields(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+    fields: &[String],
+) -> Result<Value> {
+    let form_name = find_entry_form(op, ws_path, entry_id)
+        .await?
+        .ok_or_else(|| entry_not_found(entry_id))?;
+    let form_def = form::read_form_definition(op, ws_path, &form_name).await?;
+    let row = list_projected_entry_rows(op, ws_path, &form_name, &form_def, Some(entry_id), fields)
+        .await?
+        .into_iter()
+        .find(|row| !row.deleted)
+        .ok_or_else(|| entry_not_found(entry_id))?;
+    Ok(serde_json::json!({
+        "id": entry_id,
+        "form": form_name,
+        "title": row.title,
+        "tags": row.tags,
+        "updated_at": clock::from_micros(row.updated_at),
+        "fields": merge_entry_fields(&row.fields, &row.extra_attributes),
+    }))
+}
+
+/// [`get_entry`] that, in strict mode, first checks the rendered entry
+/// against the checksum and signature recorded with its revision and fails
+/// with [`IntegrityError`] on a mismatch. `strict` overrides the space's
+/// `integrity.strict_reads` setting.
+pub async fn get_entry_verified<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+    integrity: &I,
+    strict: Option<bool>,
+) -> Result<Value> {
+    let strict = match strict {
+        Some(strict) => strict,
+        None => crate::integrity::strict_reads(&space::read_space_settings(op, ws_path).await?),
+    };
+    let (form_name, row, form_def, markdown) = read_live_entry(op, ws_path, entry_id).await?;
+    if strict {
+        if !integrity.checksum_matches(&row.integrity.checksum, &markdown) {
+            return Err(IntegrityError::ChecksumMismatch {
+                entry_id: entry_id.to_string(),
+                revision_id: row.revision_id.clone(),
+                expected: row.integrity.checksum.clone(),
+                actual: integrity.checksum(&markdown),
+            }
+            .into());
+        }
+        if integrity.signature(&markdown) != row.integrity.signature {
+            return Err(IntegrityError::SignatureMismatch {
+                entry_id: entry_id.to_string(),
+                revision_id: row.revision_id.clone(),
+            }
+            .into());
+        }
+    }
+    entry_view(op, ws_path, entry_id, &form_name, row, &form_def, markdown).await
+}
+
+async fn entry_view(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+    form_name: &str,
+    row: EntryRow,
+    form_def: &Value,
+    markdown: String,
+) -> Result<Value> {
+    let merged_fields = merge_entry_fields(&row.fields, &row.extra_attributes);
     let frontmatter = serde_json::json!({
         "form": form_name,
         "tags": row.tags,
@@ -2001,14 +3108,16 @@ pub async fn get_entry(op: &Operator, ws_path: &str, entry_id: &str) -> Result<V
         "frontmatter": frontmatter,
         "sections": sections,
         "assets": row.assets,
-        "computed": Value::Object(Map::new()),
+        "computed": rollup::entry_computed(op, ws_path, form_name, entry_id).await?,
+        "asset_refs": asset::resolve_asset_refs(op, ws_path, form_def, &row.fields).await?,
         "title": row.title,
         "form": row.form,
         "tags": row.tags,
         "links": row.links,
-        "created_at": row.created_at,
-        "updated_at": row.updated_at,
+        "created_at": clock::from_micros(row.created_at),
+        "updated_at": clock::from_micros(row.updated_at),
         "integrity": serde_json::to_value(row.integrity)?,
+        "metadata": row.metadata,
     }))
 }
 
@@ -2022,15 +3131,10 @@ pub async fn get_entry_content(
         .ok_or_else(|| anyhow!("Entry content not found: {}", entry_id))?;
     let row = read_entry_row(op, ws_path, &form_name, entry_id).await?;
     let form_def = form::read_form_definition(op, ws_path, &form_name).await?;
-    let field_order = form_field_names(&form_def);
+    let layout = FieldLayout::for_form(&form_def);
     let merged_fields = merge_entry_fields(&row.fields, &row.extra_attributes);
-    let markdown = render_markdown(
-        &row.title,
-        &form_name,
-        &row.tags,
-        &merged_fields,
-        &field_order,
-    );
+    let markdown =
+        render_markdown_with_layout(&row.title, &form_name, &row.tags, &merged_fields, &layout);
     Ok(EntryContent {
         revision_id: row.revision_id,
         parent_revision_id: row.parent_revision_id,
@@ -2042,7 +3146,7 @@ pub async fn get_entry_content(
         }),
         sections: sections_from_fields(&merged_fields),
         assets: row.assets,
-        computed: Value::Object(Map::new()),
+        computed: rollup::entry_computed(op, ws_path, &form_name, entry_id).await?,
     })
 }
 
@@ -2055,147 +3159,632 @@ pub async fn update_entry<I: IntegrityProvider>(
     parent_revision_id: Option<&str>,
     author: &str,
     assets: Option<Vec<Value>>,
+    message: Option<&str>,
+    integrity: &I,
+) -> Result<Value> {
+    update_entry_with_capabilities(
+        op,
+        ws_path,
+        entry_id,
+        content,
+        parent_revision_id,
+        author,
+        assets,
+        message,
+        &[],
+        integrity,
+    )
+    .await
+}
+
+/// [`update_entry`] by a writer holding `capabilities`, which unlock the
+/// fields whose `write_capabilities` name one of them. Changes to
+/// `read_only_after_create` fields and to fields the writer lacks a
+/// capability for fail with a coded error naming the field.
+#[allow(clippy::too_many_arguments)]
+pub async fn update_entry_with_capabilities<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+    content: &str,
+    parent_revision_id: Option<&str>,
+    author: &str,
+    assets: Option<Vec<Value>>,
+    message: Option<&str>,
+    capabilities: &[String],
     integrity: &I,
 ) -> Result<Value> {
     let form_name = find_entry_form(op, ws_path, entry_id)
         .await?
-        .ok_or_else(|| anyhow!("Entry not found: {}", entry_id))?;
+        .ok_or_else(|| entry_not_found(entry_id))?;
     let mut row = read_entry_row(op, ws_path, &form_name, entry_id).await?;
+    let previous_fields = row.fields.clone();
+    let previous_merged = merge_entry_fields(&row.fields, &row.extra_attributes);
 
     if let Some(expected_parent) = parent_revision_id {
         if row.revision_id != expected_parent {
-            return Err(anyhow!(
-                "Revision conflict: expected {}, got {}",
-                expected_parent,
-                row.revision_id
+            crate::storage::record_contention_at(
+                ws_path,
+                crate::storage::ContentionEvent::RevisionConflict,
+            );
+            return Err(i18n::error(
+                "revision_conflict",
+                serde_json::json!({"expected": expected_parent, "actual": row.revision_id}),
             ));
         }
     }
 
     let normalized_content = normalize_ugoite_links(content);
-    let (frontmatter, sections) = parse_markdown(&normalized_content);
+    let (frontmatter, sections) = parse_markdown(&normalized_content)?;
     let updated_form =
         extract_form(&frontmatter).ok_or_else(|| anyhow!("Form is required for entry update"))?;
     if updated_form != form_name {
-        return Err(anyhow!("Form change is not supported"));
+        return Err(anyhow!(
+            "Form change is not supported by update; use change_entry_form"
+        ));
+    }
+
+    let form_def = form::read_form_definition(op, ws_path, &form_name).await?;
+    let (mut fields, extra_attributes) =
+        validated_fields(op, ws_path, &form_def, &normalized_content, &sections).await?;
+
+    form::check_protected_fields(&form_def, &previous_fields, &fields, capabilities)?;
+    let warnings = form::deprecated_field_warnings(&form_def, &previous_fields, &fields);
+    let hook_ctx = hooks::WriteContext {
+        ws_path,
+        form_name: &form_name,
+        entry_id,
+        event: hooks::WriteEvent::Update,
+        author,
+    };
+    run_before_write_hooks(op, &hook_ctx, &form_def, &mut fields).await?;
+    asset::validate_asset_refs(op, ws_path, &form_def, &Value::Object(fields.clone())).await?;
+
+    let timestamp = clock::next_micros(ws_path, Some(row.updated_at));
+    let revision_id = entry_id::new_revision_id(op, ws_path).await?;
+
+    row.title = extract_title(&normalized_content, &row.title);
+    row.updated_at = timestamp;
+    if frontmatter.get("tags").is_some() {
+        row.tags = extract_tags(&frontmatter);
+    }
+    let signed_content = render_markdown_for_form(
+        &row.title,
+        &form_name,
+        &row.tags,
+        &Value::Object(fields.clone()),
+        &extra_attributes,
+        &form_def,
+    );
+    let checksum = integrity.checksum(&signed_content);
+    let signature = integrity.signature(&signed_content);
+    row.fields = Value::Object(fields);
+    row.extra_attributes = extra_attributes.clone();
+    row.parent_revision_id = Some(row.revision_id.clone());
+    row.revision_id = revision_id.clone();
+    row.author = author.to_string();
+    row.integrity = IntegrityPayload {
+        checksum: checksum.clone(),
+        signature: signature.clone(),
+    };
+    row.assets = assets.unwrap_or_else(|| row.assets.clone());
+    row.metadata = url_field::capture_url_metadata(&form_def, &row.fields, &row.metadata).await;
+
+    write_entry_row(op, ws_path, &form_name, entry_id, &row).await?;
+
+    let revision = RevisionRow {
+        revision_id: revision_id.clone(),
+        entry_id: entry_id.to_string(),
+        parent_revision_id: row.parent_revision_id.clone(),
+        timestamp,
+        author: author.to_string(),
+        fields: row.fields.clone(),
+        extra_attributes: row.extra_attributes.clone(),
+        markdown_checksum: checksum.clone(),
+        integrity: IntegrityPayload {
+            checksum: checksum.clone(),
+            signature: signature.clone(),
+        },
+        restored_from: None,
+        changed_fields: diff_fields(
+            &previous_merged,
+            &merge_entry_fields(&row.fields, &row.extra_attributes),
+        ),
+        message: normalize_revision_message(message),
+    };
+    append_revision_row_for_form(op, ws_path, &form_name, &revision, &form_def).await?;
+    hooks::run_after_write(&hook_ctx, &form_def, &revision_id, &row.fields)?;
+    rollup::refresh_after_write(
+        op,
+        ws_path,
+        &form_name,
+        &form_def,
+        entry_id,
+        Some(&previous_fields),
+        Some(&row.fields),
+    )
+    .await?;
+
+    let mut updated = get_entry(op, ws_path, entry_id).await?;
+    if !warnings.is_empty() {
+        updated["warnings"] = Value::Array(warnings);
+    }
+    Ok(updated)
+}
+
+/// Moves an entry to `target_form`, keeping its id and revision history.
+///
+/// Fields are filled as [`form::migrate_form`] fills them: `field_mapping`
+/// gives a strategy per target field, and target fields it does not name
+/// copy the field of the same name. The mapped fields are cast and
+/// validated against the target form like an update; source fields nothing
+/// reads become extra attributes, or fail the move when the target form
+/// denies them. Earlier revisions are mapped the same way and moved along,
+/// and the move itself is recorded as a new revision by `author`. The source
+/// form's tables are rewritten without the entry and both forms' index
+/// shards are rebuilt.
+pub async fn change_entry_form<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+    target_form: &str,
+    field_mapping: Option<&Value>,
+    author: &str,
+    integrity: &I,
+) -> Result<Value> {
+    change_entry_form_with_capabilities(
+        op,
+        ws_path,
+        entry_id,
+        target_form,
+        field_mapping,
+        author,
+        &[],
+        integrity,
+    )
+    .await
+}
+
+/// [`change_entry_form`] by a writer holding `capabilities`. The target
+/// form's protected fields are checked as [`update_entry_with_capabilities`]
+/// checks them, against the entry's fields before the move.
+#[allow(clippy::too_many_arguments)]
+pub async fn change_entry_form_with_capabilities<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+    target_form: &str,
+    field_mapping: Option<&Value>,
+    author: &str,
+    capabilities: &[String],
+    integrity: &I,
+) -> Result<Value> {
+    let source_form = find_entry_form(op, ws_path, entry_id)
+        .await?
+        .ok_or_else(|| entry_not_found(entry_id))?;
+    if source_form == target_form {
+        return Err(anyhow!(
+            "Entry {} is already in form {}",
+            entry_id,
+            target_form
+        ));
+    }
+    let mut row = read_entry_row(op, ws_path, &source_form, entry_id).await?;
+    if row.deleted {
+        return Err(entry_not_found(entry_id));
+    }
+    let source_def = form::read_form_definition(op, ws_path, &source_form).await?;
+    let target_def = form::read_form_definition(op, ws_path, target_form).await?;
+    let tz = SpaceTimeZone::for_space(op, ws_path).await?;
+    // Mapped and cast fields, with the extra attributes they leave behind.
+    let convert = |fields: &Value,
+                   extra_attributes: &Value|
+     -> Result<(Map<String, Value>, Value, Vec<Value>)> {
+        let source = fields.as_object().cloned().unwrap_or_default();
+        let (mut mapped, unread) = form::map_fields_to_form(&target_def, &source, field_mapping)?;
+        let (casted, warnings) =
+            index::validate_properties_in_zone(&Value::Object(mapped.clone()), &target_def, &tz)?;
+        if let Some(casted) = casted.as_object() {
+            for (key, value) in casted {
+                if mapped.contains_key(key) {
+                    mapped.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        let mut extras = extra_attributes.as_object().cloned().unwrap_or_default();
+        for name in unread {
+            let value = source[&name].clone();
+            extras.insert(name, value);
+        }
+        Ok((mapped, Value::Object(extras), warnings))
+    };
+
+    let (mut fields, extra_attributes, warnings) = convert(&row.fields, &row.extra_attributes)?;
+    if !warnings.is_empty() {
+        return Err(i18n::error(
+            "form_validation_failed",
+            serde_json::json!({"warnings": warnings}),
+        ));
+    }
+    let extras: Vec<&String> = extra_attributes
+        .as_object()
+        .map(|extras| extras.keys().collect())
+        .unwrap_or_default();
+    if !extras.is_empty() && extra_attributes_policy(&target_def) == ExtraAttributesPolicy::Deny {
+        return Err(i18n::error(
+            "unknown_form_fields",
+            serde_json::json!({"fields": extras}),
+        ));
+    }
+    form::check_protected_fields(&target_def, &row.fields, &fields, capabilities)?;
+    let hook_ctx = hooks::WriteContext {
+        ws_path,
+        form_name: target_form,
+        entry_id,
+        event: hooks::WriteEvent::Update,
+        author,
+    };
+    run_before_write_hooks(op, &hook_ctx, &target_def, &mut fields).await?;
+    asset::validate_asset_refs(op, ws_path, &target_def, &Value::Object(fields.clone())).await?;
+
+    let mut revisions = Vec::new();
+    for mut revision in list_form_revision_rows(op, ws_path, &source_form, &source_def)
+        .await?
+        .into_iter()
+        .filter(|revision| revision.entry_id == entry_id)
+    {
+        let (fields, extra_attributes, _) =
+            convert(&revision.fields, &revision.extra_attributes)
+                .with_context(|| format!("Cannot move revision {}", revision.revision_id))?;
+        revision.fields = Value::Object(fields);
+        revision.extra_attributes = extra_attributes;
+        revisions.push(revision);
+    }
+
+    let previous_fields = row.fields.clone();
+    let previous_merged = merge_entry_fields(&row.fields, &row.extra_attributes);
+    let timestamp = clock::next_micros(ws_path, Some(row.updated_at));
+    let revision_id = entry_id::new_revision_id(op, ws_path).await?;
+    let signed_content = render_markdown_for_form(
+        &row.title,
+        target_form,
+        &row.tags,
+        &Value::Object(fields.clone()),
+        &extra_attributes,
+        &target_def,
+    );
+    let checksum = integrity.checksum(&signed_content);
+    let signature = integrity.signature(&signed_content);
+    row.form = target_form.to_string();
+    row.fields = Value::Object(fields);
+    row.extra_attributes = extra_attributes;
+    row.updated_at = timestamp;
+    row.parent_revision_id = Some(row.revision_id.clone());
+    row.revision_id = revision_id.clone();
+    row.author = author.to_string();
+    row.integrity = IntegrityPayload {
+        checksum: checksum.clone(),
+        signature: signature.clone(),
+    };
+    row.metadata = url_field::capture_url_metadata(&target_def, &row.fields, &row.metadata).await;
+
+    for revision in &revisions {
+        append_revision_row_for_form(op, ws_path, target_form, revision, &target_def).await?;
+    }
+    write_entry_row(op, ws_path, target_form, entry_id, &row).await?;
+    let revision = RevisionRow {
+        revision_id: revision_id.clone(),
+        entry_id: entry_id.to_string(),
+        parent_revision_id: row.parent_revision_id.clone(),
+        timestamp,
+        author: author.to_string(),
+        fields: row.fields.clone(),
+        extra_attributes: row.extra_attributes.clone(),
+        markdown_checksum: checksum.clone(),
+        integrity: row.integrity.clone(),
+        restored_from: None,
+        changed_fields: diff_fields(
+            &previous_merged,
+            &merge_entry_fields(&row.fields, &row.extra_attributes),
+        ),
+        message: Some(format!("Moved from form {}", source_form)),
+    };
+    append_revision_row_for_form(op, ws_path, target_form, &revision, &target_def).await?;
+
+    let kept_rows: Vec<EntryRow> = list_form_entry_rows(op, ws_path, &source_form, &source_def)
+        .await?
+        .into_iter()
+        .filter(|kept| kept.entry_id != entry_id)
+        .collect();
+    let kept_revisions: Vec<RevisionRow> =
+        list_form_revision_rows(op, ws_path, &source_form, &source_def)
+            .await?
+            .into_iter()
+            .filter(|kept| kept.entry_id != entry_id)
+            .collect();
+    rewrite_form_tables(
+        op,
+        ws_path,
+        &source_form,
+        &source_def,
+        &kept_rows,
+        &kept_revisions,
+    )
+    .await?;
+    index::reindex_form(op, ws_path, &source_form).await?;
+    index::reindex_form(op, ws_path, target_form).await?;
+
+    hooks::run_after_write(&hook_ctx, &target_def, &revision_id, &row.fields)?;
+    rollup::refresh_after_write(
+        op,
+        ws_path,
+        &source_form,
+        &source_def,
+        entry_id,
+        Some(&previous_fields),
+        None,
+    )
+    .await?;
+    rollup::refresh_after_write(
+        op,
+        ws_path,
+        target_form,
+        &target_def,
+        entry_id,
+        None,
+        Some(&row.fields),
+    )
+    .await?;
+    get_entry(op, ws_path, entry_id).await
+}
+
+/// Replaces some sections of an entry and keeps the rest as stored.
+///
+/// `patch` maps section names, with or without their `## ` heading prefix,
+/// to new values; `null` removes a section. The entry is re-rendered from
+/// its stored fields with the patch applied and written through
+/// [`update_entry`], so validation, hooks and the new revision are the same
+/// as for a full update. Without `parent_revision_id` the patch applies to
+/// the revision it was read at.
+#[allow(clippy::too_many_arguments)]
+pub async fn patch_entry<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+    patch: &Value,
+    parent_revision_id: Option<&str>,
+    author: &str,
+    message: Option<&str>,
+    integrity: &I,
+) -> Result<Value> {
+    let patch = patch
+        .as_object()
+        .filter(|patch| !patch.is_empty())
+        .ok_or_else(|| anyhow!("Patch must map at least one section to a value"))?;
+    let form_name = find_entry_form(op, ws_path, entry_id)
+        .await?
+        .ok_or_else(|| entry_not_found(entry_id))?;
+    let row = read_entry_row(op, ws_path, &form_name, entry_id).await?;
+    let form_def = form::read_form_definition(op, ws_path, &form_name).await?;
+    let form_set: HashSet<String> = form_field_names(&form_def).into_iter().collect();
+
+    let mut fields = row.fields.as_object().cloned().unwrap_or_default();
+    let mut extra_attributes = row
+        .extra_attributes
+        .as_object()
+        .cloned()
+        .unwrap_or_default();
+    for (key, value) in patch {
+        let name = key.trim_start_matches('#').trim();
+        if name.is_empty() {
+            return Err(anyhow!("Invalid section name in patch: {}", key));
+        }
+        let target = if form_set.contains(name) {
+            &mut fields
+        } else {
+            &mut extra_attributes
+        };
+        if value.is_null() {
+            target.remove(name);
+        } else {
+            target.insert(name.to_string(), value.clone());
+        }
+    }
+
+    let content = render_markdown_for_form(
+        &row.title,
+        &form_name,
+        &row.tags,
+        &Value::Object(fields),
+        &Value::Object(extra_attributes),
+        &form_def,
+    );
+    update_entry(
+        op,
+        ws_path,
+        entry_id,
+        &content,
+        Some(parent_revision_id.unwrap_or(&row.revision_id)),
+        author,
+        None,
+        message,
+        integrity,
+    )
+    .await
+}
+
+/// Whether an entry was deleted with `hard_delete`: such entries cannot be
+/// undeleted and are removed for good by [`crate::trash::gc_deleted_entries`].
+pub(crate) fn is_hard_deleted(row: &EntryRow) -> bool {
+    row.deleted && row.hard_deleted
+}
+
+pub async fn delete_entry(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+    hard_delete: bool,
+) -> Result<()> {
+    let form_name = find_entry_form(op, ws_path, entry_id)
+        .await?
+        .ok_or_else(|| entry_not_found(entry_id))?;
+    let mut row = read_entry_row(op, ws_path, &form_name, entry_id).await?;
+
+    crate::link::detach_entry_links(op, ws_path, &row).await?;
+    let delete_ts = clock::next_micros(ws_path, Some(row.updated_at));
+    if hard_delete {
+        require_entries_column(op, ws_path, &form_name, "hard_deleted").await?;
+        row.hard_deleted = true;
     }
 
-    let form_def = form::read_form_definition(op, ws_path, &form_name).await?;
-    let form_fields = form_field_names(&form_def);
-    let form_set: HashSet<String> = form_fields.iter().cloned().collect();
-    let policy = extra_attributes_policy(&form_def);
-    let (extras, extra_attributes) = collect_extra_attributes(&sections, &form_set);
-    if !extras.is_empty() && policy == ExtraAttributesPolicy::Deny {
-        return Err(anyhow!("Unknown form fields: {}", extras.join(", ")));
-    }
+    row.deleted = true;
+    row.deleted_at = Some(delete_ts);
+    row.updated_at = delete_ts;
+    write_entry_row(op, ws_path, &form_name, entry_id, &row).await?;
+    refresh_rollups_after_delete(op, ws_path, &form_name, &row).await
+}
 
-    let properties = index::extract_properties(&normalized_content);
-    let (casted, warnings) = index::validate_properties(&properties, &form_def)?;
-    if !warnings.is_empty() {
+/// Brings a soft-deleted entry back, undoing [`delete_entry`].
+///
+/// The entry's links are re-added to the entries they point at, its rollup
+/// contributions are restored, and the index shards of every form touched
+/// are rebuilt before returning, so search and queries see it again at once.
+/// Links to entries that are themselves deleted or purged are dropped.
+pub async fn undelete_entry(op: &Operator, ws_path: &str, entry_id: &str) -> Result<Value> {
+    let form_name = find_entry_form(op, ws_path, entry_id)
+        .await?
+        .ok_or_else(|| entry_not_found(entry_id))?;
+    let mut row = read_entry_row(op, ws_path, &form_name, entry_id).await?;
+    if !row.deleted {
+        return Err(anyhow!("Entry {} is not deleted", entry_id));
+    }
+    if is_hard_deleted(&row) {
         return Err(anyhow!(
-            "Form validation failed: {}",
-            serde_json::to_string(&warnings)?
+            "Entry {} was hard-deleted and cannot be restored",
+            entry_id
         ));
     }
 
-    let mut fields = Map::new();
-    if let Some(obj) = properties.as_object() {
-        for (key, value) in obj {
-            if form_set.contains(key) {
-                fields.insert(key.clone(), value.clone());
-            }
-        }
+    // Everything is read before anything is written.
+    let linked = crate::link::plan_reattach_links(op, ws_path, &mut row).await?;
+    let form_def = form::read_form_definition(op, ws_path, &form_name).await?;
+    row.deleted = false;
+    row.deleted_at = None;
+    row.updated_at = clock::next_micros(ws_path, Some(row.updated_at));
+    write_entry_row(op, ws_path, &form_name, entry_id, &row).await?;
+    let mut touched_forms = std::collections::BTreeSet::from([form_name.clone()]);
+    for (other_form, other_row) in &linked {
+        write_entry_row(op, ws_path, other_form, &other_row.entry_id, other_row).await?;
+        touched_forms.insert(other_form.clone());
     }
-    if let Some(obj) = casted.as_object() {
-        for (key, value) in obj {
-            if form_set.contains(key) {
-                fields.insert(key.clone(), value.clone());
-            }
-        }
+    rollup::refresh_after_write(
+        op,
+        ws_path,
+        &form_name,
+        &form_def,
+        entry_id,
+        None,
+        Some(&row.fields),
+    )
+    .await?;
+    for touched in &touched_forms {
+        index::reindex_form(op, ws_path, touched).await?;
     }
+    get_entry(op, ws_path, entry_id).await
+}
 
-    let mut timestamp = now_ts();
-    if timestamp <= row.updated_at {
-        timestamp = row.updated_at + 0.001;
-    }
-    let revision_id = Uuid::new_v4().to_string();
-    let checksum = integrity.checksum(&normalized_content);
-    let signature = integrity.signature(&normalized_content);
+/// Which entries a listing or search returns by archive state.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchivedFilter {
+    /// Live entries that are not archived.
+    #[default]
+    Exclude,
+    Include,
+    /// Archived entries only.
+    Only,
+}
 
-    row.title = extract_title(&normalized_content, &row.title);
-    row.updated_at = timestamp;
-    if frontmatter.get("tags").is_some() {
-        row.tags = extract_tags(&frontmatter);
+impl ArchivedFilter {
+    pub fn keeps(self, archived: bool) -> bool {
+        match self {
+            ArchivedFilter::Exclude => !archived,
+            ArchivedFilter::Include => true,
+            ArchivedFilter::Only => archived,
+        }
     }
-    row.fields = Value::Object(fields);
-    row.extra_attributes = extra_attributes.clone();
-    row.parent_revision_id = Some(row.revision_id.clone());
-    row.revision_id = revision_id.clone();
-    row.author = author.to_string();
-    row.integrity = IntegrityPayload {
-        checksum: checksum.clone(),
-        signature: signature.clone(),
-    };
-    row.assets = assets.unwrap_or_else(|| row.assets.clone());
+}
 
-    write_entry_row(op, ws_path, &form_name, entry_id, &row).await?;
+impl std::str::FromStr for ArchivedFilter {
+    type Err = anyhow::Error;
 
-    let revision = RevisionRow {
-        revision_id: revision_id.clone(),
-        entry_id: entry_id.to_string(),
-        parent_revision_id: row.parent_revision_id.clone(),
-        timestamp,
-        author: author.to_string(),
-        fields: row.fields.clone(),
-        extra_attributes: row.extra_attributes.clone(),
-        markdown_checksum: checksum.clone(),
-        integrity: IntegrityPayload {
-            checksum: checksum.clone(),
-            signature: signature.clone(),
-        },
-        restored_from: None,
-    };
-    let (rev_catalog, rev_table): (Arc<MemoryCatalog>, iceberg::table::Table) =
-        iceberg_store::load_revisions_table(op, ws_path, &form_name).await?;
-    append_revision_row_to_table(rev_catalog.as_ref(), &rev_table, &revision, &form_def).await?;
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "exclude" => Ok(Self::Exclude),
+            "include" => Ok(Self::Include),
+            "only" => Ok(Self::Only),
+            _ => Err(anyhow!("Unknown archived filter: {}", value)),
+        }
+    }
+}
 
-    get_entry(op, ws_path, entry_id).await
+/// Archives a live entry: it keeps its fields, links and history and stays
+/// readable and queryable through `query_index`, but default listings and
+/// search leave it out. No revision is created. Returns `{id, archived}`.
+pub async fn archive_entry(op: &Operator, ws_path: &str, entry_id: &str) -> Result<Value> {
+    set_archived(op, ws_path, entry_id, true).await
 }
 
-pub async fn delete_entry(
+/// Undoes [`archive_entry`].
+pub async fn unarchive_entry(op: &Operator, ws_path: &str, entry_id: &str) -> Result<Value> {
+    set_archived(op, ws_path, entry_id, false).await
+}
+
+async fn set_archived(
     op: &Operator,
     ws_path: &str,
     entry_id: &str,
-    hard_delete: bool,
-) -> Result<()> {
+    archived: bool,
+) -> Result<Value> {
     let form_name = find_entry_form(op, ws_path, entry_id)
         .await?
-        .ok_or_else(|| anyhow!("Entry not found: {}", entry_id))?;
+        .ok_or_else(|| entry_not_found(entry_id))?;
     let mut row = read_entry_row(op, ws_path, &form_name, entry_id).await?;
-
-    let mut delete_ts = now_ts();
-    if delete_ts <= row.updated_at {
-        delete_ts = row.updated_at + 0.001;
+    if row.deleted {
+        return Err(entry_not_found(entry_id));
     }
-    if hard_delete {
-        row.deleted = true;
-        row.deleted_at = Some(delete_ts);
-        row.updated_at = delete_ts;
+    if row.archived != archived {
+        require_entries_column(op, ws_path, &form_name, "archived").await?;
+        row.archived = archived;
+        row.updated_at = clock::next_micros(ws_path, Some(row.updated_at));
         write_entry_row(op, ws_path, &form_name, entry_id, &row).await?;
-        return Ok(());
     }
+    Ok(serde_json::json!({"id": entry_id, "archived": archived}))
+}
 
-    row.deleted = true;
-    row.deleted_at = Some(delete_ts);
-    row.updated_at = delete_ts;
-    write_entry_row(op, ws_path, &form_name, entry_id, &row).await?;
-    Ok(())
+async fn refresh_rollups_after_delete(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+    row: &EntryRow,
+) -> Result<()> {
+    let form_def = form::read_form_definition(op, ws_path, form_name).await?;
+    rollup::refresh_after_write(
+        op,
+        ws_path,
+        form_name,
+        &form_def,
+        &row.entry_id,
+        Some(&row.fields),
+        None,
+    )
+    .await
 }
 
 pub async fn get_entry_history(op: &Operator, ws_path: &str, entry_id: &str) -> Result<Value> {
     let form_name = find_entry_form(op, ws_path, entry_id)
         .await?
-        .ok_or_else(|| anyhow!("Entry not found: {}", entry_id))?;
+        .ok_or_else(|| entry_not_found(entry_id))?;
     let form_def = form::read_form_definition(op, ws_path, &form_name).await?;
     let (_, table) = iceberg_store::load_revisions_table(op, ws_path, &form_name).await?;
     let batches = scan_table_batches(&table).await?;
@@ -2207,9 +3796,11 @@ pub async fn get_entry_history(op: &Operator, ws_path: &str, entry_id: &str) ->
         .map(|rev| {
             serde_json::json!({
                 "revision_id": rev.revision_id,
-                "timestamp": rev.timestamp,
+                "timestamp": clock::from_micros(rev.timestamp),
                 "checksum": rev.integrity.checksum,
                 "signature": rev.integrity.signature,
+                "changed_fields": rev.changed_fields,
+                "message": rev.message,
             })
         })
         .collect::<Vec<_>>();
@@ -2234,10 +3825,10 @@ pub async fn get_entry_revision(
 ) -> Result<Value> {
     let form_name = find_entry_form(op, ws_path, entry_id)
         .await?
-        .ok_or_else(|| anyhow!("Entry not found: {}", entry_id))?;
+        .ok_or_else(|| entry_not_found(entry_id))?;
     let form_def = form::read_form_definition(op, ws_path, &form_name).await?;
     let (_, table) = iceberg_store::load_revisions_table(op, ws_path, &form_name).await?;
-    let batches = scan_table_batches(&table).await?;
+    let batches = scan_table_batches_matching(&table, "revision_id", revision_id).await?;
     let rows = revision_rows_from_batches(&batches, &form_def)?;
     let revision = rows
         .into_iter()
@@ -2254,14 +3845,42 @@ pub async fn restore_entry<I: IntegrityProvider>(
     entry_id: &str,
     revision_id: &str,
     author: &str,
+    message: Option<&str>,
+    integrity: &I,
+) -> Result<Value> {
+    restore_entry_with_capabilities(
+        op,
+        ws_path,
+        entry_id,
+        revision_id,
+        author,
+        message,
+        &[],
+        integrity,
+    )
+    .await
+}
+
+/// [`restore_entry`] by a writer holding `capabilities`. Restoring may not
+/// change a protected field that [`update_entry_with_capabilities`] would
+/// refuse to change.
+#[allow(clippy::too_many_arguments)]
+pub async fn restore_entry_with_capabilities<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+    revision_id: &str,
+    author: &str,
+    message: Option<&str>,
+    capabilities: &[String],
     integrity: &I,
 ) -> Result<Value> {
     let form_name = find_entry_form(op, ws_path, entry_id)
         .await?
-        .ok_or_else(|| anyhow!("Entry not found: {}", entry_id))?;
+        .ok_or_else(|| entry_not_found(entry_id))?;
     let form_def = form::read_form_definition(op, ws_path, &form_name).await?;
     let (_, revisions_table) = iceberg_store::load_revisions_table(op, ws_path, &form_name).await?;
-    let batches = scan_table_batches(&revisions_table).await?;
+    let batches = scan_table_batches_matching(&revisions_table, "revision_id", revision_id).await?;
     let revisions = revision_rows_from_batches(&batches, &form_def)?;
     let revision = revisions
         .into_iter()
@@ -2269,28 +3888,33 @@ pub async fn restore_entry<I: IntegrityProvider>(
         .ok_or_else(|| anyhow!("Revision {} not found for entry {}", revision_id, entry_id))?;
 
     let mut row = read_entry_row(op, ws_path, &form_name, entry_id).await?;
-    let new_rev_id = Uuid::new_v4().to_string();
-    let mut timestamp = now_ts();
-    if timestamp <= row.updated_at {
-        timestamp = row.updated_at + 0.001;
-    }
-
-    let field_order = form_field_names(&form_def);
-    let merged_fields = merge_entry_fields(&revision.fields, &revision.extra_attributes);
-    let markdown = render_markdown(
-        &row.title,
-        &form_name,
-        &row.tags,
-        &merged_fields,
-        &field_order,
-    );
+    let previous_fields = row.fields.clone();
+    let previous_merged = merge_entry_fields(&row.fields, &row.extra_attributes);
+    let hook_ctx = hooks::WriteContext {
+        ws_path,
+        form_name: &form_name,
+        entry_id,
+        event: hooks::WriteEvent::Restore,
+        author,
+    };
+    let mut fields = revision.fields.as_object().cloned().unwrap_or_default();
+    form::check_protected_fields(&form_def, &previous_fields, &fields, capabilities)?;
+    run_before_write_hooks(op, &hook_ctx, &form_def, &mut fields).await?;
+    let fields = Value::Object(fields);
+    let new_rev_id = entry_id::new_revision_id(op, ws_path).await?;
+    let timestamp = clock::next_micros(ws_path, Some(row.updated_at));
+
+    let layout = FieldLayout::for_form(&form_def);
+    let merged_fields = merge_entry_fields(&fields, &revision.extra_attributes);
+    let markdown =
+        render_markdown_with_layout(&row.title, &form_name, &row.tags, &merged_fields, &layout);
     let checksum = integrity.checksum(&markdown);
     let signature = integrity.signature(&markdown);
 
     row.parent_revision_id = Some(row.revision_id.clone());
     row.revision_id = new_rev_id.clone();
     row.updated_at = timestamp;
-    row.fields = revision.fields.clone();
+    row.fields = fields;
     row.extra_attributes = revision.extra_attributes.clone();
     row.integrity = IntegrityPayload {
         checksum: checksum.clone(),
@@ -2313,20 +3937,461 @@ pub async fn restore_entry<I: IntegrityProvider>(
             signature: signature.clone(),
         },
         restored_from: Some(revision_id.to_string()),
+        changed_fields: diff_fields(&previous_merged, &merged_fields),
+        message: normalize_revision_message(message),
     };
-    let (rev_catalog, rev_table): (Arc<MemoryCatalog>, iceberg::table::Table) =
-        iceberg_store::load_revisions_table(op, ws_path, &form_name).await?;
-    append_revision_row_to_table(
-        rev_catalog.as_ref(),
-        &rev_table,
-        &restore_revision,
+    append_revision_row_for_form(op, ws_path, &form_name, &restore_revision, &form_def).await?;
+    hooks::run_after_write(&hook_ctx, &form_def, &new_rev_id, &row.fields)?;
+    rollup::refresh_after_write(
+        op,
+        ws_path,
+        &form_name,
         &form_def,
+        entry_id,
+        Some(&previous_fields),
+        Some(&row.fields),
     )
     .await?;
 
     Ok(serde_json::json!({
         "revision_id": new_rev_id,
         "restored_from": revision_id,
-        "timestamp": timestamp,
+        "timestamp": clock::from_micros(timestamp),
     }))
 }
+
+/// Replacement text for content removed by [`redact_entry`].
+pub const REDACTION_MARKER: &str = "[REDACTED]";
+
+/// What [`redact_entry`] removes: regex `patterns` are replaced with
+/// [`REDACTION_MARKER`] wherever they match, and `fields` are dropped entirely.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Redaction {
+    pub patterns: Vec<String>,
+    pub fields: Vec<String>,
+}
+
+impl Redaction {
+    fn compile(&self) -> Result<Vec<Regex>> {
+        if self.patterns.is_empty() && self.fields.is_empty() {
+            return Err(anyhow!("Redaction requires at least one pattern or field"));
+        }
+        self.patterns
+            .iter()
+            .map(|p| Regex::new(p).map_err(|e| anyhow!("Invalid redaction pattern: {}", e)))
+            .collect()
+    }
+}
+
+/// Replaces every match in `text`, returning the number of replacements.
+fn redact_text(text: &mut String, patterns: &[Regex]) -> usize {
+    let mut count = 0;
+    for pattern in patterns {
+        let matches = pattern.find_iter(text).count();
+        if matches > 0 {
+            *text = pattern.replace_all(text, REDACTION_MARKER).into_owned();
+            count += matches;
+        }
+    }
+    count
+}
+
+fn redact_value(value: &mut Value, patterns: &[Regex]) -> usize {
+    match value {
+        Value::String(text) => redact_text(text, patterns),
+        Value::Array(items) => items.iter_mut().map(|v| redact_value(v, patterns)).sum(),
+        Value::Object(map) => map.values_mut().map(|v| redact_value(v, patterns)).sum(),
+        _ => 0,
+    }
+}
+
+/// Applies a redaction to one fields/extra_attributes pair.
+fn redact_field_maps(
+    fields: &mut Value,
+    extra_attributes: &mut Value,
+    redaction: &Redaction,
+    patterns: &[Regex],
+) -> usize {
+    let mut count = 0;
+    for map in [fields.as_object_mut(), extra_attributes.as_object_mut()]
+        .into_iter()
+        .flatten()
+    {
+        for name in &redaction.fields {
+            if map.remove(name).is_some() {
+                count += 1;
+            }
+        }
+    }
+    count + redact_value(fields, patterns) + redact_value(extra_attributes, patterns)
+}
+
+/// Applies a redaction to the values a revision's diff recorded. The diff
+/// repeats field values, so its matches are not counted again.
+fn redact_changed_fields(
+    changes: &mut Vec<FieldChange>,
+    redaction: &Redaction,
+    patterns: &[Regex],
+) {
+    changes.retain(|change| !redaction.fields.contains(&change.field));
+    for change in changes.iter_mut() {
+        for value in [change.old.as_mut(), change.new.as_mut()]
+            .into_iter()
+            .flatten()
+        {
+            redact_value(value, patterns);
+        }
+    }
+}
+
+/// Drops and recreates a form's tables with exactly the given rows, so data
+/// files and snapshots holding anything else are gone afterwards.
+pub(crate) async fn rewrite_form_tables(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+    form_def: &Value,
+    entry_rows: &[EntryRow],
+    revision_rows: &[RevisionRow],
+) -> Result<()> {
+    iceberg_store::drop_form_tables(op, ws_path, form_name).await?;
+    iceberg_store::ensure_form_tables(op, ws_path, form_def).await?;
+
+    for row in entry_rows {
+        write_entry_row(op, ws_path, form_name, &row.entry_id, row).await?;
+    }
+    // The fresh tables already have every column; skip the schema check.
+    for rev in revision_rows {
+        let (catalog, table) = iceberg_store::load_revisions_table(op, ws_path, form_name).await?;
+        append_revision_row_to_table(op, ws_path, catalog.as_ref(), &table, rev, form_def).await?;
+    }
+    Ok(())
+}
+
+/// Recomputes the checksum and signature of every entry row and revision of
+/// a form with `integrity`, rewriting the form's tables. Revisions are
+/// signed with their entry's current title and tags, the only header on
+/// record. Returns the numbers of entries and revisions signed.
+pub(crate) async fn resign_form<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+    integrity: &I,
+) -> Result<(usize, usize)> {
+    let form_def = form::read_form_definition(op, ws_path, form_name).await?;
+    let mut entry_rows = list_form_entry_rows(op, ws_path, form_name, &form_def).await?;
+    let mut revision_rows = list_form_revision_rows(op, ws_path, form_name, &form_def).await?;
+    let sign = |title: &str, tags: &[String], fields: &Value, extra_attributes: &Value| {
+        if form_name == crate::saved_sql::SQL_FORM_NAME {
+            return crate::saved_sql::resign_sql_fields(integrity, title, fields);
+        }
+        let markdown =
+            render_markdown_for_form(title, form_name, tags, fields, extra_attributes, &form_def);
+        IntegrityPayload {
+            checksum: integrity.checksum(&markdown),
+            signature: integrity.signature(&markdown),
+        }
+    };
+
+    let mut headers = std::collections::HashMap::new();
+    for row in entry_rows.iter_mut() {
+        row.integrity = sign(&row.title, &row.tags, &row.fields, &row.extra_attributes);
+        headers.insert(row.entry_id.clone(), (row.title.clone(), row.tags.clone()));
+    }
+    let mut revisions = 0;
+    for rev in revision_rows.iter_mut() {
+        let Some((title, tags)) = headers.get(&rev.entry_id) else {
+            continue;
+        };
+        rev.integrity = sign(title, tags, &rev.fields, &rev.extra_attributes);
+        rev.markdown_checksum = rev.integrity.checksum.clone();
+        revisions += 1;
+    }
+    rewrite_form_tables(
+        op,
+        ws_path,
+        form_name,
+        &form_def,
+        &entry_rows,
+        &revision_rows,
+    )
+    .await?;
+    Ok((entry_rows.len(), revisions))
+}
+
+/// Removes content from an entry and every one of its historical revisions,
+/// including revision messages and the entry's client metadata.
+///
+/// The form's tables are rewritten so no data file or snapshot keeps the
+/// removed content, checksums and signatures are recomputed for each changed
+/// row from its own redacted content, and an `entry.redacted` event is added
+/// to the audit log. The event
+/// names the redacted fields but never the patterns, which may themselves be
+/// secrets.
+///
+/// Author signatures of rewritten revisions no longer verify and copies saved
+/// by the retention `archive` action are left as they are; the report lists
+/// both under `author_signatures_invalidated` and `retention_copies`.
+pub async fn redact_entry<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+    redaction: &Redaction,
+    author: &str,
+    integrity: &I,
+) -> Result<Value> {
+    let patterns = redaction.compile()?;
+    let form_name = find_entry_form(op, ws_path, entry_id)
+        .await?
+        .ok_or_else(|| entry_not_found(entry_id))?;
+    let form_def = form::read_form_definition(op, ws_path, &form_name).await?;
+    let layout = FieldLayout::for_form(&form_def);
+
+    let mut entry_rows = list_form_entry_rows(op, ws_path, &form_name, &form_def).await?;
+    let mut revision_rows = list_form_revision_rows(op, ws_path, &form_name, &form_def).await?;
+
+    let row = entry_rows
+        .iter_mut()
+        .find(|row| row.entry_id == entry_id)
+        .ok_or_else(|| entry_not_found(entry_id))?;
+    let original_title = row.title.clone();
+    let original_tags = row.tags.clone();
+    let mut header_changes = redact_text(&mut row.title, &patterns);
+    for tag in row.tags.iter_mut() {
+        header_changes += redact_text(tag, &patterns);
+    }
+    let mut replacements = header_changes
+        + redact_field_maps(
+            &mut row.fields,
+            &mut row.extra_attributes,
+            redaction,
+            &patterns,
+        )
+        + redact_value(&mut row.metadata, &patterns);
+    let title = row.title.clone();
+    let tags = row.tags.clone();
+    if replacements > 0 {
+        let markdown = render_markdown_with_layout(
+            &row.title,
+            &form_name,
+            &row.tags,
+            &merge_entry_fields(&row.fields, &row.extra_attributes),
+            &layout,
+        );
+        row.integrity = IntegrityPayload {
+            checksum: integrity.checksum(&markdown),
+            signature: integrity.signature(&markdown),
+        };
+    }
+
+    // Revisions do not store their title and tags. A revision whose checksum
+    // matches the entry's current header was written under it, so a redacted
+    // title or tag changes its content too; revisions written under an older
+    // header are only re-signed when their own fields or message change, and
+    // then against the current header, the only one on record.
+    let mut rewritten_ids = Vec::new();
+    for rev in revision_rows
+        .iter_mut()
+        .filter(|rev| rev.entry_id == entry_id)
+    {
+        let original_markdown = render_markdown_with_layout(
+            &original_title,
+            &form_name,
+            &original_tags,
+            &merge_entry_fields(&rev.fields, &rev.extra_attributes),
+            &layout,
+        );
+        let under_current_header =
+            integrity.checksum_matches(&rev.markdown_checksum, &original_markdown);
+        let mut changes = redact_field_maps(
+            &mut rev.fields,
+            &mut rev.extra_attributes,
+            redaction,
+            &patterns,
+        );
+        if let Some(message) = rev.message.as_mut() {
+            changes += redact_text(message, &patterns);
+        }
+        redact_changed_fields(&mut rev.changed_fields, redaction, &patterns);
+        if changes == 0 && !(header_changes > 0 && under_current_header) {
+            continue;
+        }
+        replacements += changes;
+        let markdown = render_markdown_with_layout(
+            &title,
+            &form_name,
+            &tags,
+            &merge_entry_fields(&rev.fields, &rev.extra_attributes),
+            &layout,
+        );
+        let checksum = integrity.checksum(&markdown);
+        rev.markdown_checksum = checksum.clone();
+        rev.integrity = IntegrityPayload {
+            checksum,
+            signature: integrity.signature(&markdown),
+        };
+        rewritten_ids.push(rev.revision_id.clone());
+    }
+
+    if replacements > 0 {
+        rewrite_form_tables(
+            op,
+            ws_path,
+            &form_name,
+            &form_def,
+            &entry_rows,
+            &revision_rows,
+        )
+        .await?;
+        index::update_entry_index(op, ws_path, entry_id).await?;
+    }
+
+    let details = serde_json::json!({
+        "entry_id": entry_id,
+        "form": form_name,
+        "fields": redaction.fields,
+        "pattern_count": redaction.patterns.len(),
+        "replacements": replacements,
+        "revisions_rewritten": rewritten_ids.len(),
+    });
+    let event =
+        crate::audit::record_audit_event(op, ws_path, "entry.redacted", author, details.clone())
+            .await?;
+
+    let mut report = details;
+    report["redacted"] = Value::Bool(replacements > 0);
+    report["audit_event_id"] = event["id"].clone();
+    // Neither can be rewritten here: author signatures need the author's
+    // key, and retention copies are outside the form's tables.
+    let signed = crate::author_keys::signed_revision_ids(op, ws_path, entry_id).await?;
+    report["author_signatures_invalidated"] = serde_json::json!(rewritten_ids
+        .iter()
+        .filter(|id| signed.contains(*id))
+        .collect::<Vec<_>>());
+    report["retention_copies"] =
+        serde_json::json!(crate::retention::archived_copies(op, ws_path, entry_id).await?);
+    Ok(report)
+}
+
+/// Entry columns added after the first on-disk format.
+pub(crate) const ENTRY_SYSTEM_COLUMNS: [&str; 3] = ["metadata", "hard_deleted", "archived"];
+
+/// Revision columns added after the first on-disk format; writing a revision
+/// into a table without them would silently drop their values.
+pub(crate) const REVISION_HISTORY_COLUMNS: [&str; 2] = ["changed_fields", "message"];
+
+fn missing_columns(table: &iceberg::table::Table, columns: &[&str]) -> Vec<String> {
+    let schema = table.metadata().current_schema();
+    columns
+        .iter()
+        .filter(|column| schema.field_by_name(column).is_none())
+        .map(|column| column.to_string())
+        .collect()
+}
+
+/// Fails with [`FormatUpgradeRequired`] when a form's entries table was
+/// created before `column` existed. Tables are only rebuilt by
+/// [`space::upgrade_format`](crate::space::upgrade_format).
+async fn require_entries_column(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+    column: &str,
+) -> Result<()> {
+    let (_, table) = iceberg_store::load_entries_table(op, ws_path, form_name).await?;
+    if missing_columns(&table, &[column]).is_empty() {
+        return Ok(());
+    }
+    Err(FormatUpgradeRequired {
+        form: form_name.to_string(),
+        table: iceberg_store::ENTRIES_TABLE_NAME.to_string(),
+        columns: vec![column.to_string()],
+    }
+    .into())
+}
+
+/// Fails with [`FormatUpgradeRequired`] when a form's revisions table was
+/// created before the revision history columns existed.
+fn require_revisions_columns(form_name: &str, table: &iceberg::table::Table) -> Result<()> {
+    let missing = missing_columns(table, &REVISION_HISTORY_COLUMNS);
+    if missing.is_empty() {
+        return Ok(());
+    }
+    Err(FormatUpgradeRequired {
+        form: form_name.to_string(),
+        table: iceberg_store::REVISIONS_TABLE_NAME.to_string(),
+        columns: missing,
+    }
+    .into())
+}
+
+/// Rebuilds a form's tables with the current schema when they were created
+/// before any of [`ENTRY_SYSTEM_COLUMNS`] or [`REVISION_HISTORY_COLUMNS`]
+/// existed. Only called by the format upgrade, after it backed the space up.
+/// Returns `true` when the tables were rebuilt.
+pub(crate) async fn upgrade_system_columns(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+) -> Result<bool> {
+    let (_, entries) = iceberg_store::load_entries_table(op, ws_path, form_name).await?;
+    let (_, revisions) = iceberg_store::load_revisions_table(op, ws_path, form_name).await?;
+    if missing_columns(&entries, &ENTRY_SYSTEM_COLUMNS).is_empty()
+        && missing_columns(&revisions, &REVISION_HISTORY_COLUMNS).is_empty()
+    {
+        return Ok(false);
+    }
+    let form_def = form::read_form_definition(op, ws_path, form_name).await?;
+    let entry_rows = list_form_entry_rows(op, ws_path, form_name, &form_def).await?;
+    let revision_rows = list_form_revision_rows(op, ws_path, form_name, &form_def).await?;
+    rewrite_form_tables(
+        op,
+        ws_path,
+        form_name,
+        &form_def,
+        &entry_rows,
+        &revision_rows,
+    )
+    .await?;
+    Ok(true)
+}
+
+/// Applies a JSON merge patch to an entry's client metadata: keys set to
+/// `null` are removed, others are replaced. Only the entry row is rewritten;
+/// no revision is created and form fields are untouched. Returns the
+/// resulting metadata.
+pub async fn set_entry_metadata(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+    patch: &Value,
+) -> Result<Value> {
+    let patch = patch
+        .as_object()
+        .ok_or_else(|| anyhow!("Metadata patch must be an object"))?;
+    let form_name = find_entry_form(op, ws_path, entry_id)
+        .await?
+        .ok_or_else(|| entry_not_found(entry_id))?;
+    let mut row = read_entry_row(op, ws_path, &form_name, entry_id).await?;
+    if row.deleted {
+        return Err(entry_not_found(entry_id));
+    }
+
+    require_entries_column(op, ws_path, &form_name, "metadata").await?;
+
+    let mut metadata = row.metadata.as_object().cloned().unwrap_or_default();
+    for (key, value) in patch {
+        if value.is_null() {
+            metadata.remove(key);
+        } else {
+            metadata.insert(key.clone(), value.clone());
+        }
+    }
+    row.metadata = Value::Object(metadata);
+    // The newest entry row wins, so the row still has to sort last.
+    let timestamp = clock::next_micros(ws_path, Some(row.updated_at));
+    row.updated_at = timestamp;
+    write_entry_row(op, ws_path, &form_name, entry_id, &row).await?;
+    Ok(row.metadata)
+}
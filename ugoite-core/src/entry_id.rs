@@ -0,0 +1,280 @@
+use anyhow::{anyhow, Result};
+use opendal::Operator;
+use rand::RngExt;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+use crate::entry;
+use crate::space;
+use crate::storage;
+
+const COUNTERS_FILE: &str = "counters.json";
+pub const DEFAULT_ID_PATTERN: &str = "{slug}";
+const MAX_SLUG_CHARS: usize = 64;
+const CROCKFORD: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// How generated ids are minted. `uuidv7` and `ulid` lead with the creation
+/// time in milliseconds, so they sort by creation time.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum IdStrategy {
+    #[default]
+    Uuidv4,
+    Uuidv7,
+    Ulid,
+}
+
+impl std::str::FromStr for IdStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "uuidv4" => Ok(Self::Uuidv4),
+            "uuidv7" => Ok(Self::Uuidv7),
+            "ulid" => Ok(Self::Ulid),
+            other => Err(anyhow!("Unknown id strategy: {}", other)),
+        }
+    }
+}
+
+/// `entry_ids` in settings.json: id patterns per form, with `default` used
+/// for forms not listed. Patterns mix literal text with `{slug}` (the
+/// title), `{form}`, `{date}` (UTC, `YYYY-MM-DD`), `{counter}` or
+/// `{counter:N}` (zero-padded to N digits), `{uuidv7}` and `{ulid}`.
+/// `revision_ids` picks the strategy for new revision ids.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct EntryIdSettings {
+    pub default: String,
+    pub forms: HashMap<String, String>,
+    pub revision_ids: IdStrategy,
+}
+
+impl Default for EntryIdSettings {
+    fn default() -> Self {
+        Self {
+            default: DEFAULT_ID_PATTERN.to_string(),
+            forms: HashMap::new(),
+            revision_ids: IdStrategy::default(),
+        }
+    }
+}
+
+impl EntryIdSettings {
+    pub async fn for_space(op: &Operator, ws_path: &str) -> Result<Self> {
+        let settings = space::read_space_settings(op, ws_path).await?;
+        match settings.get("entry_ids") {
+            Some(value) if value.is_object() => Ok(serde_json::from_value(value.clone())?),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn pattern_for(&self, form_name: &str) -> &str {
+        self.forms
+            .get(form_name)
+            .map(String::as_str)
+            .unwrap_or(&self.default)
+    }
+}
+
+fn unix_millis() -> u64 {
+    chrono::Utc::now().timestamp_millis().max(0) as u64
+}
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    rand::rng().fill(&mut bytes);
+    bytes
+}
+
+/// The last ULID minted by this process, so ids minted within the same
+/// millisecond still sort in creation order.
+static LAST_ULID: Mutex<u128> = Mutex::new(0);
+
+/// A ULID: 48 bits of Unix milliseconds and 80 random bits as 26 Crockford
+/// base32 characters. Within one millisecond the random part of the previous
+/// id is incremented instead, as the ULID spec's monotonic mode does.
+fn ulid(millis: u64) -> String {
+    let random: [u8; 10] = random_bytes();
+    let mut value = u128::from(millis & 0xffff_ffff_ffff) << 80;
+    for (i, byte) in random.iter().enumerate() {
+        value |= u128::from(*byte) << (72 - 8 * i);
+    }
+    {
+        let mut last = LAST_ULID.lock().unwrap_or_else(|e| e.into_inner());
+        if value >> 80 <= *last >> 80 {
+            value = *last + 1;
+        }
+        *last = value;
+    }
+    (0..26)
+        .rev()
+        .map(|i| CROCKFORD[((value >> (5 * i)) & 0x1f) as usize] as char)
+        .collect()
+}
+
+/// Mints a new id with `strategy`.
+pub fn generate_id(strategy: IdStrategy) -> String {
+    match strategy {
+        IdStrategy::Uuidv4 => Uuid::new_v4().to_string(),
+        IdStrategy::Uuidv7 => Uuid::now_v7().to_string(),
+        IdStrategy::Ulid => ulid(unix_millis()),
+    }
+}
+
+/// Mints a revision id with the space's `entry_ids.revision_ids` strategy.
+pub(crate) async fn new_revision_id(op: &Operator, ws_path: &str) -> Result<String> {
+    let settings = EntryIdSettings::for_space(op, ws_path).await?;
+    Ok(generate_id(settings.revision_ids))
+}
+
+/// Lowercases `text` and joins its alphanumeric runs with `-`, e.g.
+/// `"Pump #3: Inspection"` becomes `pump-3-inspection`.
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for ch in text.chars().flat_map(char::to_lowercase) {
+        if ch.is_alphanumeric() {
+            slug.push(ch);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+        if slug.chars().count() >= MAX_SLUG_CHARS {
+            break;
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+enum Part {
+    Text(String),
+    Slug,
+    Form,
+    Date,
+    Counter(usize),
+    Generated(IdStrategy),
+}
+
+fn parse_pattern(pattern: &str) -> Result<Vec<Part>> {
+    let mut parts = Vec::new();
+    let mut rest = pattern;
+    while let Some(start) = rest.find('{') {
+        if start > 0 {
+            parts.push(Part::Text(rest[..start].to_string()));
+        }
+        let end = rest[start..]
+            .find('}')
+            .map(|end| start + end)
+            .ok_or_else(|| anyhow!("Unclosed placeholder in id pattern: {}", pattern))?;
+        let part = match &rest[start + 1..end] {
+            "slug" => Part::Slug,
+            "form" => Part::Form,
+            "date" => Part::Date,
+            "counter" => Part::Counter(0),
+            "uuidv7" => Part::Generated(IdStrategy::Uuidv7),
+            "ulid" => Part::Generated(IdStrategy::Ulid),
+            other => match other.strip_prefix("counter:") {
+                Some(width) => Part::Counter(
+                    width
+                        .parse()
+                        .map_err(|_| anyhow!("Invalid counter width in id pattern: {}", other))?,
+                ),
+                None => return Err(anyhow!("Unknown id pattern placeholder: {{{}}}", other)),
+            },
+        };
+        parts.push(part);
+        rest = &rest[end + 1..];
+    }
+    if !rest.is_empty() {
+        parts.push(Part::Text(rest.to_string()));
+    }
+    if parts
+        .iter()
+        .any(|part| matches!(part, Part::Text(text) if text.contains('/')))
+    {
+        return Err(anyhow!("Id patterns cannot contain '/': {}", pattern));
+    }
+    Ok(parts)
+}
+
+fn render(parts: &[Part], slug: &str, form: &str, date: &str, counter: u64) -> String {
+    let mut id = String::new();
+    for part in parts {
+        match part {
+            Part::Text(text) => id.push_str(text),
+            Part::Slug => id.push_str(slug),
+            Part::Form => id.push_str(form),
+            Part::Date => id.push_str(date),
+            Part::Counter(width) => id.push_str(&format!("{:0width$}", counter, width = *width)),
+            Part::Generated(strategy) => id.push_str(&generate_id(*strategy)),
+        }
+    }
+    id
+}
+
+/// Reserves the next value of `form_name`'s counter. Counters live in
+/// `counters.json` and only move forward, so concurrent callers never get
+/// the same value.
+pub async fn next_counter(op: &Operator, ws_path: &str, form_name: &str) -> Result<u64> {
+    let path = format!("{}/{}", ws_path.trim_end_matches('/'), COUNTERS_FILE);
+    storage::update_json(op, &path, |current| {
+        let mut counters: BTreeMap<String, u64> = match current {
+            Some(value) => serde_json::from_value(value)?,
+            None => BTreeMap::new(),
+        };
+        let next = counters.get(form_name).copied().unwrap_or(0) + 1;
+        counters.insert(form_name.to_string(), next);
+        Ok((serde_json::to_value(&counters)?, next))
+    })
+    .await
+}
+
+/// Suggests an unused, human-readable id for a new entry of `form_name`
+/// from the space's `entry_ids` pattern.
+///
+/// Counter patterns reserve counter values until the id is free; other
+/// patterns add `-2`, `-3`, ... on collision. `{uuidv7}` and `{ulid}` are
+/// minted fresh for each suggestion. Ids of deleted entries are never
+/// suggested again.
+pub async fn suggest_entry_id(
+    op: &Operator,
+    ws_path: &str,
+    title: &str,
+    form_name: &str,
+) -> Result<String> {
+    let settings = EntryIdSettings::for_space(op, ws_path).await?;
+    let parts = parse_pattern(settings.pattern_for(form_name))?;
+    let slug = match slugify(title) {
+        slug if slug.is_empty() => "entry".to_string(),
+        slug => slug,
+    };
+    let form = slugify(form_name);
+    let date = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let taken: HashSet<String> = entry::list_entry_rows(op, ws_path)
+        .await?
+        .into_iter()
+        .map(|(_, row)| row.entry_id)
+        .collect();
+
+    if parts.iter().any(|part| matches!(part, Part::Counter(_))) {
+        loop {
+            let counter = next_counter(op, ws_path, form_name).await?;
+            let id = render(&parts, &slug, &form, &date, counter);
+            if !taken.contains(&id) {
+                return Ok(id);
+            }
+        }
+    }
+    let base = render(&parts, &slug, &form, &date, 0);
+    if base.is_empty() {
+        return Err(anyhow!("Id pattern for {} produced an empty id", form_name));
+    }
+    let mut id = base.clone();
+    let mut n = 1;
+    while taken.contains(&id) {
+        n += 1;
+        id = format!("{}-{}", base, n);
+    }
+    Ok(id)
+}
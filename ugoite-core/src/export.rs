@@ -0,0 +1,1690 @@
+use anyhow::{anyhow, Result};
+use arrow_array::builder::{ListBuilder, StringBuilder};
+use arrow_array::{ArrayRef, Float64Array, Int32Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema};
+use base64::{engine::general_purpose, Engine as _};
+use opendal::Operator;
+use parquet::arrow::ArrowWriter;
+use parquet::file::properties::WriterProperties;
+use ring::signature::{UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::asset;
+use crate::clock;
+use crate::entry;
+use crate::extraction;
+use crate::form;
+use crate::index;
+use crate::integrity::{self, IntegrityProvider};
+use crate::link;
+use crate::progress::{self, ProgressSender};
+use crate::search;
+
+pub const EXPORT_FORMAT: &str = "ugoite-export/1";
+const MANIFEST_FILE: &str = "manifest.json";
+const ENTRIES_FILE: &str = "entries.jsonl";
+const FORMS_FILE: &str = "forms.json";
+/// Edits a partial replica pushes back, one JSON change per line.
+pub const CHANGES_FILE: &str = "changes.jsonl";
+/// Link kind from a conflict copy to the entry it collided with.
+pub const CONFLICT_LINK_KIND: &str = "conflict_of";
+
+/// What an import or replica push does with an entry id that already
+/// exists with different content. Chosen per run; every collision is
+/// reported whichever strategy is used.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CollisionStrategy {
+    /// Keep the existing entry.
+    #[default]
+    Skip,
+    /// Write the incoming content as a new revision of the existing entry.
+    Overwrite,
+    /// Create `{id}-conflict` (or `-conflict-N`) with the incoming content,
+    /// linked to the existing entry.
+    ConflictCopy,
+}
+
+impl std::str::FromStr for CollisionStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "skip" => Ok(Self::Skip),
+            "overwrite" => Ok(Self::Overwrite),
+            "conflict_copy" => Ok(Self::ConflictCopy),
+            other => Err(anyhow!("Unknown collision strategy: {}", other)),
+        }
+    }
+}
+
+/// Selects the entries a partial replica holds. Empty lists and open date
+/// bounds match everything.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct SyncFilter {
+    pub forms: Vec<String>,
+    /// Entries carrying at least one of these tags.
+    pub tags: Vec<String>,
+    /// Lower bound on `updated_at`, inclusive.
+    pub updated_since: Option<f64>,
+    /// Upper bound on `updated_at`, inclusive.
+    pub updated_until: Option<f64>,
+}
+
+impl SyncFilter {
+    pub fn is_empty(&self) -> bool {
+        *self == SyncFilter::default()
+    }
+
+    /// Whether an entry record (as returned by `list_entries`/`get_entry`)
+    /// belongs in the replica.
+    pub fn matches(&self, entry: &Value) -> bool {
+        let form = entry.get("form").and_then(|v| v.as_str()).unwrap_or("");
+        if !self.forms.is_empty() && !self.forms.iter().any(|f| f == form) {
+            return false;
+        }
+        if !self.tags.is_empty() {
+            let tags = entry.get("tags").and_then(|v| v.as_array());
+            let tagged = tags
+                .map(|tags| {
+                    tags.iter()
+                        .filter_map(|t| t.as_str())
+                        .any(|t| self.tags.iter().any(|want| want == t))
+                })
+                .unwrap_or(false);
+            if !tagged {
+                return false;
+            }
+        }
+        let updated_at = entry
+            .get("updated_at")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.0);
+        if self.updated_since.is_some_and(|since| updated_at < since) {
+            return false;
+        }
+        if self.updated_until.is_some_and(|until| updated_at > until) {
+            return false;
+        }
+        true
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TombstoneReason {
+    /// Deleted in the source space; replicas delete it too.
+    Deleted,
+    /// Still in the source but outside the filter; replicas drop their copy
+    /// without treating it as a deletion.
+    Filtered,
+}
+
+/// An entry the previous export into the same bundle held and this one does
+/// not.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Tombstone {
+    pub entry_id: String,
+    pub reason: TombstoneReason,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ExportItem {
+    pub path: String,
+    pub sha256: String,
+    pub bytes: u64,
+}
+
+/// `manifest.json` at the root of an export bundle.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExportManifest {
+    pub format: String,
+    pub space_id: String,
+    pub exported_at: f64,
+    pub items: Vec<ExportItem>,
+    /// Id of the space signing key, see [`integrity::load_signing_key`].
+    pub key_id: String,
+    /// Ed25519 signature over [`ExportManifest::signing_payload`], base64
+    /// encoded.
+    pub signature: String,
+    /// Set for partial exports.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<SyncFilter>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tombstones: Vec<Tombstone>,
+}
+
+impl ExportManifest {
+    /// The bytes covered by the signature: a header line per manifest field,
+    /// then one `path\tsha256\tbytes` line per item in path order. Kept as
+    /// plain text so the signature never depends on JSON formatting. Partial
+    /// exports add a `filter` line and one `tombstone` line per tombstone.
+    pub fn signing_payload(&self) -> Vec<u8> {
+        let mut items: Vec<&ExportItem> = self.items.iter().collect();
+        items.sort_by(|a, b| a.path.cmp(&b.path));
+        let mut payload = format!(
+            "{}\n{}\n{}\n{}\n",
+            self.format, self.space_id, self.exported_at, self.key_id
+        );
+        for item in items {
+            payload.push_str(&format!("{}\t{}\t{}\n", item.path, item.sha256, item.bytes));
+        }
+        if let Some(filter) = &self.filter {
+            let filter = serde_json::to_value(filter).unwrap_or_default();
+            payload.push_str(&format!("filter\t{}\n", integrity::canonical_json(&filter)));
+        }
+        let mut tombstones: Vec<&Tombstone> = self.tombstones.iter().collect();
+        tombstones.sort_by(|a, b| a.entry_id.cmp(&b.entry_id));
+        for tombstone in tombstones {
+            let reason = match tombstone.reason {
+                TombstoneReason::Deleted => "deleted",
+                TombstoneReason::Filtered => "filtered",
+            };
+            payload.push_str(&format!("tombstone\t{}\t{}\n", tombstone.entry_id, reason));
+        }
+        payload.into_bytes()
+    }
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// Whether `manifest` is signed by the Ed25519 `public_key`.
+fn signature_holds(public_key: &[u8], manifest: &ExportManifest) -> bool {
+    let Ok(signature) = general_purpose::STANDARD.decode(&manifest.signature) else {
+        return false;
+    };
+    UnparsedPublicKey::new(&ED25519, public_key)
+        .verify(&manifest.signing_payload(), &signature)
+        .is_ok()
+}
+
+async fn write_item(bundle: &Operator, path: &str, bytes: Vec<u8>) -> Result<ExportItem> {
+    let item = ExportItem {
+        path: path.to_string(),
+        sha256: sha256_hex(&bytes),
+        bytes: bytes.len() as u64,
+    };
+    bundle.write(path, bytes).await?;
+    Ok(item)
+}
+
+/// Exports a space into `bundle`: `entries.jsonl` (one entry per line),
+/// `forms.json`, asset files under `assets/`, and a `manifest.json` listing
+/// every file's SHA-256 signed with the space HMAC key.
+pub async fn export_space(op: &Operator, ws_path: &str, bundle: &Operator) -> Result<Value> {
+    export_space_filtered(op, ws_path, bundle, &SyncFilter::default()).await
+}
+
+async fn read_bundle_manifest(bundle: &Operator) -> Result<Option<ExportManifest>> {
+    if !bundle.exists(MANIFEST_FILE).await? {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_slice(
+        &bundle.read(MANIFEST_FILE).await?.to_vec(),
+    )?))
+}
+
+async fn read_bundle_entry_ids(bundle: &Operator) -> Result<Vec<String>> {
+    if !bundle.exists(ENTRIES_FILE).await? {
+        return Ok(Vec::new());
+    }
+    let bytes = bundle.read(ENTRIES_FILE).await?.to_vec();
+    let mut ids = Vec::new();
+    for line in String::from_utf8(bytes)?.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: Value = serde_json::from_str(line)?;
+        if let Some(id) = record.get("id").and_then(|v| v.as_str()) {
+            ids.push(id.to_string());
+        }
+    }
+    Ok(ids)
+}
+
+/// Exports the entries matching `filter` for a partial replica. Assets are
+/// limited to those the exported entries reference unless the filter is
+/// empty.
+///
+/// When `bundle` already holds an export, entries it held that are not
+/// exported again become tombstones in the manifest, marked `deleted` or
+/// `filtered`, and files the new export no longer lists are removed.
+pub async fn export_space_filtered(
+    op: &Operator,
+    ws_path: &str,
+    bundle: &Operator,
+    filter: &SyncFilter,
+) -> Result<Value> {
+    export_space_with_progress(op, ws_path, bundle, filter, None).await
+}
+
+/// [`export_space_filtered`], reporting `entries` and `assets` progress to
+/// `progress` as it goes.
+pub async fn export_space_with_progress(
+    op: &Operator,
+    ws_path: &str,
+    bundle: &Operator,
+    filter: &SyncFilter,
+    progress: Option<&ProgressSender>,
+) -> Result<Value> {
+    let space_id = ws_path
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or(ws_path)
+        .to_string();
+    let (key_id, signing_key) = integrity::load_signing_key(op, &space_id).await?;
+    let previous_manifest = read_bundle_manifest(bundle).await?;
+    let previous_ids = read_bundle_entry_ids(bundle).await?;
+
+    let entries =
+        entry::list_entries_including(op, ws_path, &[], entry::ArchivedFilter::Include).await?;
+    let live: BTreeMap<&str, &Value> = entries
+        .iter()
+        .filter_map(|e| e.get("id").and_then(|v| v.as_str()).map(|id| (id, e)))
+        .collect();
+    let entry_ids: Vec<&str> = live
+        .iter()
+        .filter(|(_, record)| filter.matches(record))
+        .map(|(id, _)| *id)
+        .collect();
+    let mut lines = Vec::new();
+    let mut referenced_assets = std::collections::HashSet::new();
+    progress::report(progress, "entries", 0, entry_ids.len()).await?;
+    for (done, entry_id) in entry_ids.iter().enumerate() {
+        let record = entry::get_entry(op, ws_path, entry_id).await?;
+        for asset in record
+            .get("assets")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+        {
+            for key in ["id", "path"] {
+                if let Some(value) = asset.get(key).and_then(|v| v.as_str()) {
+                    referenced_assets.insert(value.to_string());
+                }
+            }
+        }
+        lines.extend(serde_json::to_vec(&record)?);
+        lines.push(b'\n');
+        progress::report(progress, "entries", done + 1, entry_ids.len()).await?;
+    }
+
+    let mut items = vec![
+        write_item(bundle, ENTRIES_FILE, lines).await?,
+        write_item(
+            bundle,
+            FORMS_FILE,
+            serde_json::to_vec_pretty(&form::list_forms(op, ws_path).await?)?,
+        )
+        .await?,
+    ];
+    let assets: Vec<_> = asset::list_assets(op, ws_path)
+        .await?
+        .into_iter()
+        .filter(|info| {
+            filter.is_empty()
+                || referenced_assets.contains(&info.id)
+                || referenced_assets.contains(&info.path)
+        })
+        .collect();
+    for (done, info) in assets.iter().enumerate() {
+        let bytes = op
+            .read(&format!("{}/{}", ws_path.trim_end_matches('/'), info.path))
+            .await?
+            .to_vec();
+        items.push(write_item(bundle, &info.path, bytes).await?);
+        progress::report(progress, "assets", done + 1, assets.len()).await?;
+    }
+    if let Some(previous) = &previous_manifest {
+        for item in &previous.items {
+            if !items.iter().any(|current| current.path == item.path) {
+                bundle.delete(&item.path).await?;
+            }
+        }
+    }
+
+    let tombstones: Vec<Tombstone> = previous_ids
+        .into_iter()
+        .filter(|id| !entry_ids.contains(&id.as_str()))
+        .map(|entry_id| Tombstone {
+            reason: if live.contains_key(entry_id.as_str()) {
+                TombstoneReason::Filtered
+            } else {
+                TombstoneReason::Deleted
+            },
+            entry_id,
+        })
+        .collect();
+
+    let mut manifest = ExportManifest {
+        format: EXPORT_FORMAT.to_string(),
+        space_id,
+        exported_at: entry::now_ts(),
+        items,
+        key_id,
+        signature: String::new(),
+        filter: (!filter.is_empty()).then(|| filter.clone()),
+        tombstones,
+    };
+    manifest.signature =
+        general_purpose::STANDARD.encode(signing_key.sign(&manifest.signing_payload()));
+    bundle
+        .write(MANIFEST_FILE, serde_json::to_vec_pretty(&manifest)?)
+        .await?;
+
+    Ok(serde_json::json!({
+        "space_id": manifest.space_id,
+        "exported_at": manifest.exported_at,
+        "entries": entry_ids.len(),
+        "items": manifest.items.len(),
+        "key_id": manifest.key_id,
+        "tombstones": manifest.tombstones,
+    }))
+}
+
+/// One line of [`CHANGES_FILE`]: new `content` for an entry, or `deleted`,
+/// made against `base_revision_id` (absent for entries created on the
+/// replica).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReplicaChange {
+    pub entry_id: String,
+    #[serde(default)]
+    pub base_revision_id: Option<String>,
+    #[serde(default)]
+    pub content: Option<String>,
+    #[serde(default)]
+    pub deleted: bool,
+}
+
+async fn free_conflict_id(op: &Operator, ws_path: &str, entry_id: &str) -> Result<String> {
+    let mut n = 1;
+    loop {
+        let candidate = if n == 1 {
+            format!("{}-conflict", entry_id)
+        } else {
+            format!("{}-conflict-{}", entry_id, n)
+        };
+        if entry::find_entry_form(op, ws_path, &candidate)
+            .await?
+            .is_none()
+        {
+            return Ok(candidate);
+        }
+        n += 1;
+    }
+}
+
+/// Applies `strategy` to `incoming` content for an entry that exists at
+/// `current_revision` with other content. Returns the collision record for
+/// the run summary.
+#[allow(clippy::too_many_arguments)]
+async fn resolve_collision<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+    incoming: &str,
+    current_revision: &str,
+    strategy: CollisionStrategy,
+    author: &str,
+    integrity: &I,
+) -> Result<Value> {
+    let mut record = serde_json::json!({
+        "entry_id": entry_id,
+        "current_revision_id": current_revision,
+        "resolution": strategy,
+    });
+    match strategy {
+        CollisionStrategy::Skip => {}
+        CollisionStrategy::Overwrite => {
+            entry::update_entry(
+                op,
+                ws_path,
+                entry_id,
+                incoming,
+                Some(current_revision),
+                author,
+                None,
+                None,
+                integrity,
+            )
+            .await?;
+        }
+        CollisionStrategy::ConflictCopy => {
+            let copy_id = free_conflict_id(op, ws_path, entry_id).await?;
+            entry::create_entry(op, ws_path, &copy_id, incoming, author, integrity).await?;
+            link::create_link(
+                op,
+                ws_path,
+                &copy_id,
+                entry_id,
+                CONFLICT_LINK_KIND,
+                &uuid::Uuid::new_v4().to_string(),
+            )
+            .await?;
+            record["copy_id"] = serde_json::json!(copy_id);
+        }
+    }
+    Ok(record)
+}
+
+fn failure(entry_id: &str, err: impl std::fmt::Display) -> Value {
+    serde_json::json!({"entry_id": entry_id, "reason": err.to_string()})
+}
+
+/// Applies the edits a partial replica recorded in the bundle's
+/// `changes.jsonl` to the source space.
+///
+/// The bundle must carry a manifest signed by this space, and changes to
+/// existing entries outside its filter are rejected: a replica can only
+/// change what it holds. Edits made against a revision that is no longer
+/// current, and new entries whose id is already taken, are collisions
+/// handled by `strategy` and listed in `conflicts`. Entries missing from the
+/// replica are never treated as deleted; only explicit `deleted` changes
+/// delete.
+pub async fn apply_replica_changes<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    bundle: &Operator,
+    strategy: CollisionStrategy,
+    author: &str,
+    integrity: &I,
+) -> Result<Value> {
+    let manifest = read_bundle_manifest(bundle)
+        .await?
+        .ok_or_else(|| anyhow!("Export bundle has no {}", MANIFEST_FILE))?;
+    let space_id = ws_path
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or(ws_path);
+    if manifest.space_id != space_id {
+        return Err(anyhow!(
+            "Bundle was exported from {}, not {}",
+            manifest.space_id,
+            space_id
+        ));
+    }
+    let (_, public_key) = integrity::load_signing_public_key(op, space_id).await?;
+    if !signature_holds(&public_key, &manifest) {
+        return Err(anyhow!("Bundle manifest signature is invalid"));
+    }
+    let filter = manifest.filter.unwrap_or_default();
+
+    let mut applied = Vec::new();
+    let mut conflicts = Vec::new();
+    let mut rejected = Vec::new();
+    if !bundle.exists(CHANGES_FILE).await? {
+        return Ok(serde_json::json!({
+            "applied": applied,
+            "conflicts": conflicts,
+            "rejected": rejected,
+        }));
+    }
+    let text = String::from_utf8(bundle.read(CHANGES_FILE).await?.to_vec())?;
+    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+        let change: ReplicaChange = serde_json::from_str(line)?;
+        let entry_id = change.entry_id.as_str();
+        let current = entry::get_entry(op, ws_path, entry_id).await.ok();
+
+        let Some(current) = current else {
+            match (&change.content, change.deleted) {
+                (Some(content), false) => {
+                    match entry::create_entry(op, ws_path, entry_id, content, author, integrity)
+                        .await
+                    {
+                        Ok(_) => applied.push(entry_id.to_string()),
+                        Err(err) => rejected.push(failure(entry_id, err)),
+                    }
+                }
+                _ => rejected.push(failure(entry_id, "not_found")),
+            }
+            continue;
+        };
+        if !filter.matches(&current) {
+            rejected.push(failure(entry_id, "out_of_filter"));
+            continue;
+        }
+        let current_revision = current
+            .get("revision_id")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+        if change.base_revision_id.as_deref() != Some(current_revision) {
+            let resolved = match (&change.content, change.deleted) {
+                (Some(content), false) => {
+                    resolve_collision(
+                        op,
+                        ws_path,
+                        entry_id,
+                        content,
+                        current_revision,
+                        strategy,
+                        author,
+                        integrity,
+                    )
+                    .await
+                }
+                // A stale delete is only forced through by `Overwrite`.
+                _ => {
+                    let resolution = if strategy == CollisionStrategy::Overwrite {
+                        entry::delete_entry(op, ws_path, entry_id, false)
+                            .await
+                            .map(|_| strategy)
+                    } else {
+                        Ok(CollisionStrategy::Skip)
+                    };
+                    resolution.map(|resolution| {
+                        serde_json::json!({
+                            "entry_id": entry_id,
+                            "current_revision_id": current_revision,
+                            "resolution": resolution,
+                        })
+                    })
+                }
+            };
+            match resolved {
+                Ok(mut record) => {
+                    record["base_revision_id"] = serde_json::json!(change.base_revision_id);
+                    conflicts.push(record);
+                }
+                Err(err) => rejected.push(failure(entry_id, err)),
+            }
+            continue;
+        }
+        let result = if change.deleted {
+            entry::delete_entry(op, ws_path, entry_id, false).await
+        } else if let Some(content) = &change.content {
+            entry::update_entry(
+                op,
+                ws_path,
+                entry_id,
+                content,
+                Some(current_revision),
+                author,
+                None,
+                None,
+                integrity,
+            )
+            .await
+            .map(|_| ())
+        } else {
+            Err(anyhow!("Change has neither content nor deleted"))
+        };
+        match result {
+            Ok(()) => applied.push(entry_id.to_string()),
+            Err(err) => rejected.push(failure(entry_id, err)),
+        }
+    }
+
+    Ok(serde_json::json!({
+        "applied": applied,
+        "conflicts": conflicts,
+        "rejected": rejected,
+    }))
+}
+
+/// Imports an export bundle from any space into this one.
+///
+/// Missing forms are created and asset files copied, then each entry is
+/// created, left alone when identical, or handled by `strategy` when its id
+/// already holds different content. Per-entry failures are reported in
+/// `failed` rather than aborting the import.
+pub async fn import_bundle<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    bundle: &Operator,
+    strategy: CollisionStrategy,
+    author: &str,
+    integrity: &I,
+) -> Result<Value> {
+    import_bundle_with_progress(op, ws_path, bundle, strategy, author, integrity, None).await
+}
+
+/// [`import_bundle`], reporting `entries` progress to `progress` as it goes.
+pub async fn import_bundle_with_progress<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    bundle: &Operator,
+    strategy: CollisionStrategy,
+    author: &str,
+    integrity: &I,
+    progress: Option<&ProgressSender>,
+) -> Result<Value> {
+    let manifest = read_bundle_manifest(bundle)
+        .await?
+        .ok_or_else(|| anyhow!("Export bundle has no {}", MANIFEST_FILE))?;
+    if manifest.format != EXPORT_FORMAT {
+        return Err(anyhow!("Unsupported export format: {}", manifest.format));
+    }
+    let ws_path = ws_path.trim_end_matches('/');
+    let mut failed = Vec::new();
+
+    if bundle.exists(FORMS_FILE).await? {
+        let forms: Vec<Value> = serde_json::from_slice(&bundle.read(FORMS_FILE).await?.to_vec())?;
+        let existing = form::list_form_names(op, ws_path).await?;
+        for form_def in forms {
+            let name = form_def.get("name").and_then(|v| v.as_str()).unwrap_or("");
+            if existing.iter().any(|e| e == name) || name == asset::ASSET_FORM_NAME {
+                continue;
+            }
+            if let Err(err) = form::upsert_form(op, ws_path, &form_def).await {
+                failed.push(serde_json::json!({"form": name, "reason": err.to_string()}));
+            }
+        }
+    }
+    let assets: Vec<&ExportItem> = manifest
+        .items
+        .iter()
+        .filter(|item| item.path.starts_with("assets/"))
+        .collect();
+    if !assets.is_empty() {
+        asset::ensure_asset_form(op, ws_path).await?;
+    }
+    for item in assets {
+        let target = format!("{}/{}", ws_path, item.path);
+        if !op.exists(&target).await? {
+            op.write(&target, bundle.read(&item.path).await?.to_vec())
+                .await?;
+        }
+    }
+
+    let mut created = Vec::new();
+    let mut unchanged = 0;
+    let mut collisions = Vec::new();
+    let text = String::from_utf8(bundle.read(ENTRIES_FILE).await?.to_vec())?;
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    progress::report(progress, "entries", 0, lines.len()).await?;
+    for (done, line) in lines.iter().enumerate() {
+        if done > 0 {
+            progress::report(progress, "entries", done, lines.len()).await?;
+        }
+        let record: Value = serde_json::from_str(line)?;
+        let (Some(entry_id), Some(content)) = (
+            record.get("id").and_then(|v| v.as_str()),
+            record.get("content").and_then(|v| v.as_str()),
+        ) else {
+            failed.push(failure("", "record without id or content"));
+            continue;
+        };
+        let Ok(current) = entry::get_entry(op, ws_path, entry_id).await else {
+            match entry::create_entry(op, ws_path, entry_id, content, author, integrity).await {
+                Ok(_) => created.push(entry_id.to_string()),
+                Err(err) => failed.push(failure(entry_id, err)),
+            }
+            continue;
+        };
+        if current.get("content").and_then(|v| v.as_str()) == Some(content) {
+            unchanged += 1;
+            continue;
+        }
+        let current_revision = current
+            .get("revision_id")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+        match resolve_collision(
+            op,
+            ws_path,
+            entry_id,
+            content,
+            current_revision,
+            strategy,
+            author,
+            integrity,
+        )
+        .await
+        {
+            Ok(record) => collisions.push(record),
+            Err(err) => failed.push(failure(entry_id, err)),
+        }
+    }
+    if !lines.is_empty() {
+        progress::report(progress, "entries", lines.len(), lines.len()).await?;
+    }
+
+    Ok(serde_json::json!({
+        "source_space_id": manifest.space_id,
+        "created": created,
+        "unchanged": unchanged,
+        "collisions": collisions,
+        "failed": failed,
+    }))
+}
+
+/// Checks an export bundle against its manifest using the exporting space's
+/// Ed25519 public key (as returned by `load_signing_public_key`), so anyone
+/// can verify a bundle without being able to sign one.
+///
+/// The report lists `missing` and `modified` manifest items and `unexpected`
+/// files not named in the manifest; `valid` is true only when the signature
+/// matches and all three lists are empty.
+pub async fn verify_export(bundle: &Operator, public_key: &[u8]) -> Result<Value> {
+    if !bundle.exists(MANIFEST_FILE).await? {
+        return Err(anyhow!("Export bundle has no {}", MANIFEST_FILE));
+    }
+    let manifest: ExportManifest =
+        serde_json::from_slice(&bundle.read(MANIFEST_FILE).await?.to_vec())?;
+    if manifest.format != EXPORT_FORMAT {
+        return Err(anyhow!("Unsupported export format: {}", manifest.format));
+    }
+    let signature_valid = signature_holds(public_key, &manifest);
+
+    let mut missing = Vec::new();
+    let mut modified = Vec::new();
+    let listed: BTreeMap<&str, &ExportItem> = manifest
+        .items
+        .iter()
+        .map(|item| (item.path.as_str(), item))
+        .collect();
+    for (path, item) in &listed {
+        if !bundle.exists(path).await? {
+            missing.push(path.to_string());
+            continue;
+        }
+        let bytes = bundle.read(path).await?.to_vec();
+        if bytes.len() as u64 != item.bytes || sha256_hex(&bytes) != item.sha256 {
+            modified.push(path.to_string());
+        }
+    }
+
+    let mut unexpected = Vec::new();
+    for file in bundle.list_with("/").recursive(true).await? {
+        let path = file.path();
+        if file.metadata().is_file() && path != MANIFEST_FILE && !listed.contains_key(path) {
+            unexpected.push(path.to_string());
+        }
+    }
+
+    Ok(serde_json::json!({
+        "valid": signature_valid && missing.is_empty() && modified.is_empty() && unexpected.is_empty(),
+        "signature_valid": signature_valid,
+        "space_id": manifest.space_id,
+        "key_id": manifest.key_id,
+        "exported_at": manifest.exported_at,
+        "missing": missing,
+        "modified": modified,
+        "unexpected": unexpected,
+    }))
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in bytes {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// MS-DOS `(time, date)` for a zip header; times before 1980 clamp to 1980.
+fn dos_timestamp(ts: f64) -> (u16, u16) {
+    use chrono::{Datelike, Timelike};
+    let dt = chrono::DateTime::<chrono::Utc>::from_timestamp_millis((ts * 1000.0) as i64)
+        .unwrap_or_default()
+        .naive_utc();
+    if dt.year() < 1980 {
+        return (0, (1 << 5) | 1);
+    }
+    let time = (dt.hour() << 11) | (dt.minute() << 5) | (dt.second() / 2);
+    let date = ((dt.year() as u32 - 1980) << 9) | (dt.month() << 5) | dt.day();
+    (time as u16, date as u16)
+}
+
+/// Builds an uncompressed (stored) zip archive from `(name, bytes,
+/// modified_at)` files.
+pub fn zip_archive(files: &[(String, Vec<u8>, f64)]) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut central = Vec::new();
+    for (name, bytes, modified_at) in files {
+        let offset = u32::try_from(out.len()).map_err(|_| anyhow!("Zip archive too large"))?;
+        let size = u32::try_from(bytes.len()).map_err(|_| anyhow!("Zip entry too large"))?;
+        let (time, date) = dos_timestamp(*modified_at);
+        let crc = crc32(bytes);
+        let mut header = Vec::new();
+        header.extend_from_slice(&20u16.to_le_bytes()); // version needed
+        header.extend_from_slice(&0x0800u16.to_le_bytes()); // UTF-8 names
+        header.extend_from_slice(&0u16.to_le_bytes()); // stored
+        header.extend_from_slice(&time.to_le_bytes());
+        header.extend_from_slice(&date.to_le_bytes());
+        header.extend_from_slice(&crc.to_le_bytes());
+        header.extend_from_slice(&size.to_le_bytes());
+        header.extend_from_slice(&size.to_le_bytes());
+        header.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes()); // extra length
+
+        out.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        out.extend_from_slice(&header);
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(bytes);
+
+        central.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        central.extend_from_slice(&20u16.to_le_bytes()); // version made by
+        central.extend_from_slice(&header);
+        central.extend_from_slice(&[0; 6]); // comment length, disk, internal attrs
+        central.extend_from_slice(&0u32.to_le_bytes()); // external attrs
+        central.extend_from_slice(&offset.to_le_bytes());
+        central.extend_from_slice(name.as_bytes());
+    }
+    let count = u16::try_from(files.len()).map_err(|_| anyhow!("Too many zip entries"))?;
+    let central_offset = u32::try_from(out.len()).map_err(|_| anyhow!("Zip archive too large"))?;
+    out.extend_from_slice(&central);
+    out.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+    out.extend_from_slice(&[0; 4]); // disk numbers
+    out.extend_from_slice(&count.to_le_bytes());
+    out.extend_from_slice(&count.to_le_bytes());
+    out.extend_from_slice(&(central.len() as u32).to_le_bytes());
+    out.extend_from_slice(&central_offset.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes()); // comment length
+    Ok(out)
+}
+
+fn le_u16(bytes: &[u8], at: usize) -> Result<u16> {
+    bytes
+        .get(at..at + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or_else(|| anyhow!("Truncated zip archive"))
+}
+
+fn le_u32(bytes: &[u8], at: usize) -> Result<u32> {
+    bytes
+        .get(at..at + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| anyhow!("Truncated zip archive"))
+}
+
+/// Reads the files of a zip archive as `(name, bytes)`, in central
+/// directory order, skipping directories. Supports stored and deflated
+/// entries; ZIP64 and encrypted archives are rejected.
+///
+/// The sizes in the archive are not trusted: an entry inflating past its
+/// recorded size is rejected as soon as it does, and the archive is
+/// rejected once its entries add up to more than `max_unpacked` bytes.
+pub(crate) fn read_zip_archive(bytes: &[u8], max_unpacked: u64) -> Result<Vec<(String, Vec<u8>)>> {
+    use std::io::Read;
+    const EOCD_LEN: usize = 22;
+    let search_from = bytes.len().saturating_sub(EOCD_LEN + usize::from(u16::MAX));
+    let eocd = (search_from..=bytes.len().saturating_sub(EOCD_LEN))
+        .rev()
+        .find(|&at| le_u32(bytes, at).ok() == Some(0x0605_4b50))
+        .ok_or_else(|| anyhow!("Not a zip archive"))?;
+    let count = le_u16(bytes, eocd + 10)?;
+    let central_offset = le_u32(bytes, eocd + 16)?;
+    if count == u16::MAX || central_offset == u32::MAX {
+        return Err(anyhow!("ZIP64 archives are not supported"));
+    }
+    let mut files = Vec::new();
+    let mut remaining = max_unpacked;
+    let mut at = central_offset as usize;
+    for _ in 0..count {
+        if le_u32(bytes, at)? != 0x0201_4b50 {
+            return Err(anyhow!("Corrupt zip central directory"));
+        }
+        let flags = le_u16(bytes, at + 8)?;
+        let method = le_u16(bytes, at + 10)?;
+        let crc = le_u32(bytes, at + 16)?;
+        let compressed = le_u32(bytes, at + 20)?;
+        let size = le_u32(bytes, at + 24)?;
+        let name_len = usize::from(le_u16(bytes, at + 28)?);
+        let extra_len = usize::from(le_u16(bytes, at + 30)?);
+        let comment_len = usize::from(le_u16(bytes, at + 32)?);
+        let local = le_u32(bytes, at + 42)? as usize;
+        let name = bytes
+            .get(at + 46..at + 46 + name_len)
+            .ok_or_else(|| anyhow!("Truncated zip archive"))?;
+        let name = String::from_utf8_lossy(name).replace('\\', "/");
+        at += 46 + name_len + extra_len + comment_len;
+        if name.ends_with('/') {
+            continue;
+        }
+        if flags & 1 != 0 {
+            return Err(anyhow!("Encrypted zip entry: {}", name));
+        }
+        if compressed == u32::MAX || size == u32::MAX || local == u32::MAX as usize {
+            return Err(anyhow!("ZIP64 archives are not supported"));
+        }
+        if le_u32(bytes, local)? != 0x0403_4b50 {
+            return Err(anyhow!("Corrupt zip entry: {}", name));
+        }
+        let start = local
+            + 30
+            + usize::from(le_u16(bytes, local + 26)?)
+            + usize::from(le_u16(bytes, local + 28)?);
+        let data = bytes
+            .get(start..start + compressed as usize)
+            .ok_or_else(|| anyhow!("Truncated zip entry: {}", name))?;
+        remaining = remaining
+            .checked_sub(u64::from(size))
+            .ok_or_else(|| anyhow!("Zip archive unpacks to more than {} bytes", max_unpacked))?;
+        let content = match method {
+            0 => data.to_vec(),
+            8 => {
+                let mut out = Vec::new();
+                flate2::read::DeflateDecoder::new(data)
+                    .take(u64::from(size) + 1)
+                    .read_to_end(&mut out)?;
+                if out.len() as u64 > u64::from(size) {
+                    return Err(anyhow!(
+                        "Zip entry is larger than its recorded size: {}",
+                        name
+                    ));
+                }
+                out
+            }
+            other => {
+                return Err(anyhow!(
+                    "Unsupported zip compression method {} for {}",
+                    other,
+                    name
+                ))
+            }
+        };
+        if content.len() != size as usize || crc32(&content) != crc {
+            return Err(anyhow!("Zip entry failed its checksum: {}", name));
+        }
+        files.push((name, content));
+    }
+    Ok(files)
+}
+
+/// Writes an entry's complete edit trail to a zip at `path` in `target`:
+/// one `NNNN-{revision_id}.md` per revision, oldest first, rendered with the
+/// entry's current title and tags, plus `history.json` describing each
+/// revision (author, time, message, changed fields, checksums) and the
+/// SHA-256 of its file.
+pub async fn export_entry_history(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+    target: &Operator,
+    path: &str,
+) -> Result<Value> {
+    let form_name = entry::find_entry_form(op, ws_path, entry_id)
+        .await?
+        .ok_or_else(|| anyhow!("Entry not found: {}", entry_id))?;
+    let form_def = form::read_form_definition(op, ws_path, &form_name).await?;
+    let row = entry::read_entry_row(op, ws_path, &form_name, entry_id).await?;
+    let mut revisions: Vec<entry::RevisionRow> =
+        entry::list_form_revision_rows(op, ws_path, &form_name, &form_def)
+            .await?
+            .into_iter()
+            .filter(|revision| revision.entry_id == entry_id)
+            .collect();
+    revisions.sort_by_key(|revision| revision.timestamp);
+
+    let mut files = Vec::new();
+    let mut listed = Vec::new();
+    for (index, revision) in revisions.iter().enumerate() {
+        let file = format!("{:04}-{}.md", index + 1, revision.revision_id);
+        let markdown = entry::render_markdown_for_form(
+            &row.title,
+            &form_name,
+            &row.tags,
+            &revision.fields,
+            &revision.extra_attributes,
+            &form_def,
+        );
+        listed.push(serde_json::json!({
+            "file": file,
+            "sha256": sha256_hex(markdown.as_bytes()),
+            "revision_id": revision.revision_id,
+            "parent_revision_id": revision.parent_revision_id,
+            "timestamp": clock::from_micros(revision.timestamp),
+            "author": revision.author,
+            "message": revision.message,
+            "changed_fields": revision.changed_fields,
+            "restored_from": revision.restored_from,
+            "checksum": revision.integrity.checksum,
+            "signature": revision.integrity.signature,
+        }));
+        files.push((
+            file,
+            markdown.into_bytes(),
+            clock::from_micros(revision.timestamp),
+        ));
+    }
+    let exported_at = entry::now_ts();
+    let summary = serde_json::json!({
+        "entry_id": entry_id,
+        "form": form_name,
+        "title": row.title,
+        "deleted": row.deleted,
+        "exported_at": exported_at,
+        "revisions": listed,
+    });
+    files.push((
+        "history.json".to_string(),
+        serde_json::to_vec_pretty(&summary)?,
+        exported_at,
+    ));
+
+    let archive = zip_archive(&files)?;
+    let bytes = archive.len();
+    target.write(path, archive).await?;
+    Ok(serde_json::json!({
+        "entry_id": entry_id,
+        "path": path,
+        "revisions": revisions.len(),
+        "bytes": bytes,
+    }))
+}
+
+/// Rows of query output written per chunk by [`query_to_csv`].
+const CSV_CHUNK_ROWS: usize = 500;
+
+/// One spreadsheet cell of a flattened query row.
+#[derive(Debug, Clone, PartialEq)]
+enum Cell {
+    Text(String),
+    Number(String),
+}
+
+impl Cell {
+    /// The cell as written to a report: numbers as-is, text through
+    /// [`neutralise_formula`].
+    fn export_text(&self) -> Cow<'_, str> {
+        match self {
+            Cell::Text(text) => neutralise_formula(text),
+            Cell::Number(text) => Cow::Borrowed(text),
+        }
+    }
+}
+
+/// Prefixes text a spreadsheet would read as a formula (leading `=`, `+`,
+/// `-`, `@`, tab or carriage return) with `'`, so opening an exported report
+/// never evaluates entry content.
+fn neutralise_formula(text: &str) -> Cow<'_, str> {
+    if text.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        Cow::Owned(format!("'{}", text))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Flattens a query row into `(column, cell)` pairs. Nested objects become
+/// dotted columns, except `properties`, whose fields are columns of their
+/// own; lists of scalars are joined with `; ` and other lists kept as JSON.
+fn flatten_row(prefix: &str, value: &Value, out: &mut Vec<(String, Cell)>) {
+    let column = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", prefix, key)
+        }
+    };
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                if key == "properties" {
+                    flatten_row(prefix, value, out);
+                } else {
+                    flatten_row(&column(key), value, out);
+                }
+            }
+        }
+        Value::Array(items)
+            if items
+                .iter()
+                .all(|item| !item.is_object() && !item.is_array()) =>
+        {
+            let joined: Vec<String> = items
+                .iter()
+                .map(|item| match item {
+                    Value::String(text) => text.clone(),
+                    other => other.to_string(),
+                })
+                .collect();
+            out.push((prefix.to_string(), Cell::Text(joined.join("; "))));
+        }
+        Value::Array(_) => out.push((prefix.to_string(), Cell::Text(value.to_string()))),
+        Value::Null => out.push((prefix.to_string(), Cell::Text(String::new()))),
+        Value::String(text) => out.push((prefix.to_string(), Cell::Text(text.clone()))),
+        Value::Number(number) => out.push((prefix.to_string(), Cell::Number(number.to_string()))),
+        Value::Bool(flag) => out.push((prefix.to_string(), Cell::Text(flag.to_string()))),
+    }
+}
+
+/// Query output read batch by batch: one form at a time through
+/// [`index::SqlBatches`] when the query allows it, otherwise the whole
+/// result (joins, ordering) as a single batch.
+enum QueryRows {
+    Batches(Box<index::SqlBatches>),
+    Whole { rows: Vec<Value>, read: bool },
+}
+
+impl QueryRows {
+    async fn open(op: &Operator, ws_path: &str, query: &str) -> Result<Self> {
+        Ok(match index::SqlBatches::open(op, ws_path, query).await? {
+            Some(batches) => QueryRows::Batches(Box::new(batches)),
+            None => QueryRows::Whole {
+                rows: index::execute_sql_query(op, ws_path, query).await?,
+                read: false,
+            },
+        })
+    }
+
+    fn rewind(&mut self) {
+        match self {
+            QueryRows::Batches(batches) => batches.rewind(),
+            QueryRows::Whole { read, .. } => *read = false,
+        }
+    }
+
+    async fn next_batch(&mut self) -> Result<Option<Vec<Value>>> {
+        match self {
+            QueryRows::Batches(batches) => batches.next_batch().await,
+            QueryRows::Whole { read: true, .. } => Ok(None),
+            QueryRows::Whole { rows, read } => {
+                *read = true;
+                Ok(Some(rows.clone()))
+            }
+        }
+    }
+
+    /// Reads every batch once for the flattened columns in first-seen
+    /// order, then rewinds so the rows can be written.
+    async fn columns(&mut self) -> Result<Vec<String>> {
+        let mut columns = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+        while let Some(batch) = self.next_batch().await? {
+            for row in &batch {
+                let mut cells = Vec::new();
+                flatten_row("", row, &mut cells);
+                for (column, _) in cells {
+                    if seen.insert(column.clone()) {
+                        columns.push(column);
+                    }
+                }
+            }
+        }
+        self.rewind();
+        Ok(columns)
+    }
+}
+
+/// A row's cells in `columns` order; empty where the row has no value.
+fn row_cells(row: &Value, columns: &[String]) -> Vec<Cell> {
+    let mut cells = Vec::new();
+    flatten_row("", row, &mut cells);
+    let mut by_column: BTreeMap<String, Cell> = cells.into_iter().collect();
+    columns
+        .iter()
+        .map(|column| {
+            by_column
+                .remove(column)
+                .unwrap_or_else(|| Cell::Text(String::new()))
+        })
+        .collect()
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) || text.trim() != text {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn csv_line(fields: impl Iterator<Item = String>) -> String {
+    let mut line = fields.collect::<Vec<_>>().join(",");
+    line.push_str("\r\n");
+    line
+}
+
+/// Runs `query` and writes its rows as CSV (RFC 4180, header row first) to
+/// `path` in `target`. Queries over `entries` or one form without JOIN or
+/// ORDER BY are read twice, one form's batch at a time: once for the
+/// columns and once to write the rows, so the result set is never held
+/// whole; other queries are collected first. Columns are the flattened row
+/// keys: entry fields appear under their own names and joined tables under
+/// `alias.field`. Text that would start a formula is prefixed with `'`.
+pub async fn query_to_csv(
+    op: &Operator,
+    ws_path: &str,
+    query: &str,
+    target: &Operator,
+    path: &str,
+) -> Result<Value> {
+    let mut rows = QueryRows::open(op, ws_path, query).await?;
+    let columns = rows.columns().await?;
+    let mut writer = target.writer(path).await?;
+    let mut bytes = 0;
+    let mut written = 0;
+    let header = csv_line(
+        columns
+            .iter()
+            .map(|column| csv_field(&neutralise_formula(column))),
+    );
+    bytes += header.len();
+    writer.write(header.into_bytes()).await?;
+    while let Some(batch) = rows.next_batch().await? {
+        for chunk in batch.chunks(CSV_CHUNK_ROWS) {
+            let mut text = String::new();
+            for row in chunk {
+                text.push_str(&csv_line(
+                    row_cells(row, &columns)
+                        .iter()
+                        .map(|cell| csv_field(&cell.export_text())),
+                ));
+            }
+            bytes += text.len();
+            written += chunk.len();
+            writer.write(text.into_bytes()).await?;
+        }
+    }
+    writer.close().await?;
+    Ok(serde_json::json!({
+        "path": path,
+        "format": "csv",
+        "rows": written,
+        "columns": columns,
+        "bytes": bytes,
+    }))
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            // Control characters other than tab and newlines are not valid XML.
+            '\t' | '\n' | '\r' => out.push(ch),
+            ch if ch < ' ' => {}
+            ch => out.push(ch),
+        }
+    }
+    out
+}
+
+/// Spreadsheet column name for a zero-based index: A..Z, AA, AB, ...
+fn xlsx_column(mut index: usize) -> String {
+    let mut name = Vec::new();
+    loop {
+        name.push(b'A' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+fn xlsx_row(number: usize, cells: &[Cell]) -> String {
+    let mut row = format!("<row r=\"{}\">", number);
+    for (index, cell) in cells.iter().enumerate() {
+        let reference = format!("{}{}", xlsx_column(index), number);
+        match cell {
+            Cell::Number(value) => {
+                row.push_str(&format!("<c r=\"{}\"><v>{}</v></c>", reference, value))
+            }
+            Cell::Text(text) if text.is_empty() => {}
+            Cell::Text(_) => row.push_str(&format!(
+                "<c r=\"{}\" t=\"inlineStr\"><is><t xml:space=\"preserve\">{}</t></is></c>",
+                reference,
+                xml_escape(&cell.export_text())
+            )),
+        }
+    }
+    row.push_str("</row>");
+    row
+}
+
+const XLSX_CONTENT_TYPES: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+    r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#,
+    r#"<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#,
+    r#"<Default Extension="xml" ContentType="application/xml"/>"#,
+    r#"<Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>"#,
+    r#"<Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#,
+    r#"</Types>"#,
+);
+const XLSX_ROOT_RELS: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+    r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+    r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/>"#,
+    r#"</Relationships>"#,
+);
+const XLSX_WORKBOOK: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+    r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">"#,
+    r#"<sheets><sheet name="Results" sheetId="1" r:id="rId1"/></sheets>"#,
+    r#"</workbook>"#,
+);
+const XLSX_WORKBOOK_RELS: &str = concat!(
+    r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+    r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+    r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/>"#,
+    r#"</Relationships>"#,
+);
+
+/// Runs `query` and writes its rows to `path` in `target` as a single-sheet
+/// XLSX workbook with the same columns as [`query_to_csv`]. Numbers stay
+/// numeric cells; everything else is text, with formula-like text prefixed
+/// with `'` as in the CSV.
+pub async fn query_to_xlsx(
+    op: &Operator,
+    ws_path: &str,
+    query: &str,
+    target: &Operator,
+    path: &str,
+) -> Result<Value> {
+    let mut rows = QueryRows::open(op, ws_path, query).await?;
+    let columns = rows.columns().await?;
+    let mut sheet = String::from(concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+    ));
+    let header: Vec<Cell> = columns.iter().cloned().map(Cell::Text).collect();
+    sheet.push_str(&xlsx_row(1, &header));
+    let mut written = 0;
+    while let Some(batch) = rows.next_batch().await? {
+        for row in &batch {
+            written += 1;
+            sheet.push_str(&xlsx_row(written + 1, &row_cells(row, &columns)));
+        }
+    }
+    sheet.push_str("</sheetData></worksheet>");
+
+    let now = entry::now_ts();
+    let files = vec![
+        (
+            "[Content_Types].xml".to_string(),
+            XLSX_CONTENT_TYPES.as_bytes().to_vec(),
+            now,
+        ),
+        (
+            "_rels/.rels".to_string(),
+            XLSX_ROOT_RELS.as_bytes().to_vec(),
+            now,
+        ),
+        (
+            "xl/workbook.xml".to_string(),
+            XLSX_WORKBOOK.as_bytes().to_vec(),
+            now,
+        ),
+        (
+            "xl/_rels/workbook.xml.rels".to_string(),
+            XLSX_WORKBOOK_RELS.as_bytes().to_vec(),
+            now,
+        ),
+        (
+            "xl/worksheets/sheet1.xml".to_string(),
+            sheet.into_bytes(),
+            now,
+        ),
+    ];
+    let archive = zip_archive(&files)?;
+    let bytes = archive.len();
+    target.write(path, archive).await?;
+    Ok(serde_json::json!({
+        "path": path,
+        "format": "xlsx",
+        "rows": written,
+        "columns": columns,
+        "bytes": bytes,
+    }))
+}
+
+pub const OFFLINE_FORMAT: &str = "ugoite-offline/1";
+const OFFLINE_MANIFEST_FILE: &str = "offline.json";
+const OFFLINE_ENTRIES_FILE: &str = "entries.parquet";
+const OFFLINE_POSTINGS_FILE: &str = "postings.parquet";
+
+/// Latest state of one entry as an offline client stores it, with the
+/// analyzed search tokens of the entry and how often each occurs.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OfflineEntry {
+    pub id: String,
+    pub form: String,
+    pub title: String,
+    pub tags: Vec<String>,
+    pub fields: Value,
+    pub content: String,
+    pub revision_id: String,
+    pub updated_at: f64,
+    pub tokens: BTreeMap<String, u32>,
+}
+
+/// Entries changed after `since` (all live entries when `None`) that match
+/// `filter`, entries that left the read model since then, and the cursor
+/// the next delta starts from.
+async fn offline_changes(
+    op: &Operator,
+    ws_path: &str,
+    filter: &SyncFilter,
+    since: Option<f64>,
+) -> Result<(Vec<OfflineEntry>, Vec<Tombstone>, f64)> {
+    let analyzer = search::Analyzer::for_space(op, ws_path).await?;
+    let asset_texts = extraction::list_asset_texts(op, ws_path).await?;
+    let mut rows = entry::list_entry_rows(op, ws_path).await?;
+    rows.sort_by(|a, b| a.1.entry_id.cmp(&b.1.entry_id));
+    let cursor = rows
+        .iter()
+        .map(|(_, row)| clock::from_micros(row.updated_at))
+        .fold(since.unwrap_or(0.0), f64::max);
+
+    let mut entries = Vec::new();
+    let mut removed = Vec::new();
+    for (form_name, row) in rows {
+        if since.is_some_and(|since| clock::from_micros(row.updated_at) <= since) {
+            continue;
+        }
+        let reason = if row.deleted {
+            Some(TombstoneReason::Deleted)
+        } else {
+            let summary = serde_json::json!({
+                "form": form_name,
+                "tags": row.tags,
+                "updated_at": clock::from_micros(row.updated_at),
+            });
+            (!filter.matches(&summary)).then_some(TombstoneReason::Filtered)
+        };
+        if let Some(reason) = reason {
+            // A fresh bundle never held the entry, so there is nothing to drop.
+            if since.is_some() {
+                removed.push(Tombstone {
+                    entry_id: row.entry_id,
+                    reason,
+                });
+            }
+            continue;
+        }
+        let record = entry::get_entry(op, ws_path, &row.entry_id).await?;
+        let mut tokens = BTreeMap::new();
+        let text = search::searchable_text(&row, &asset_texts);
+        for token in search::analyze_text(&text, &analyzer) {
+            *tokens.entry(token).or_insert(0) += 1;
+        }
+        entries.push(OfflineEntry {
+            id: row.entry_id,
+            form: form_name,
+            title: row.title,
+            tags: row.tags,
+            fields: entry::merge_entry_fields(&row.fields, &row.extra_attributes),
+            content: record
+                .get("content")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            revision_id: row.revision_id,
+            updated_at: clock::from_micros(row.updated_at),
+            tokens,
+        });
+    }
+    Ok((entries, removed, cursor))
+}
+
+fn parquet_bytes(batch: RecordBatch, props: WriterProperties) -> Result<Vec<u8>> {
+    let mut writer = ArrowWriter::try_new(Vec::new(), batch.schema(), Some(props))?;
+    writer.write(&batch)?;
+    Ok(writer.into_inner()?)
+}
+
+fn offline_entries_batch(entries: &[OfflineEntry]) -> Result<RecordBatch> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("form", DataType::Utf8, false),
+        Field::new("title", DataType::Utf8, false),
+        Field::new(
+            "tags",
+            DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+            false,
+        ),
+        Field::new("fields", DataType::Utf8, false),
+        Field::new("content", DataType::Utf8, false),
+        Field::new("revision_id", DataType::Utf8, false),
+        Field::new("updated_at", DataType::Float64, false),
+    ]));
+    let mut tags = ListBuilder::new(StringBuilder::new());
+    for entry in entries {
+        for tag in &entry.tags {
+            tags.values().append_value(tag);
+        }
+        tags.append(true);
+    }
+    let column = |get: fn(&OfflineEntry) -> String| -> ArrayRef {
+        Arc::new(StringArray::from_iter_values(entries.iter().map(get)))
+    };
+    let arrays: Vec<ArrayRef> = vec![
+        column(|entry| entry.id.clone()),
+        column(|entry| entry.form.clone()),
+        column(|entry| entry.title.clone()),
+        Arc::new(tags.finish()),
+        column(|entry| entry.fields.to_string()),
+        column(|entry| entry.content.clone()),
+        column(|entry| entry.revision_id.clone()),
+        Arc::new(Float64Array::from_iter_values(
+            entries.iter().map(|entry| entry.updated_at),
+        )),
+    ];
+    RecordBatch::try_new(schema, arrays).map_err(|e| anyhow!("Record batch error: {}", e))
+}
+
+/// One `(token, entry_id, count)` row per token occurrence set, sorted by
+/// token so readers can binary search or push down a token filter.
+fn offline_postings_batch(entries: &[OfflineEntry]) -> Result<RecordBatch> {
+    let mut postings: Vec<(&str, &str, u32)> = entries
+        .iter()
+        .flat_map(|entry| {
+            entry
+                .tokens
+                .iter()
+                .map(|(token, count)| (token.as_str(), entry.id.as_str(), *count))
+        })
+        .collect();
+    postings.sort();
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("token", DataType::Utf8, false),
+        Field::new("entry_id", DataType::Utf8, false),
+        Field::new("count", DataType::Int32, false),
+    ]));
+    let arrays: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(
+            postings.iter().map(|posting| posting.0),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            postings.iter().map(|posting| posting.1),
+        )),
+        Arc::new(Int32Array::from_iter_values(
+            postings.iter().map(|posting| posting.2 as i32),
+        )),
+    ];
+    RecordBatch::try_new(schema, arrays).map_err(|e| anyhow!("Record batch error: {}", e))
+}
+
+/// Writes a read-only snapshot of the entries matching `filter` for clients
+/// that query the space offline: `entries.parquet` with the latest state of
+/// each entry, `postings.parquet` with its analyzed search tokens, and
+/// `offline.json` naming both files, the space's search analyzer settings
+/// (queries must be analyzed the same way) and the changefeed cursor.
+///
+/// Files use the space's parquet settings. Clients keep the bundle current
+/// with [`offline_delta`] from the cursor instead of downloading it again.
+pub async fn offline_bundle(
+    op: &Operator,
+    ws_path: &str,
+    filter: &SyncFilter,
+    bundle: &Operator,
+) -> Result<Value> {
+    let (entries, _, cursor) = offline_changes(op, ws_path, filter, None).await?;
+    let props = entry::ParquetSettings::for_space(op, ws_path)
+        .await?
+        .writer_properties()?;
+    let items = vec![
+        write_item(
+            bundle,
+            OFFLINE_ENTRIES_FILE,
+            parquet_bytes(offline_entries_batch(&entries)?, props.clone())?,
+        )
+        .await?,
+        write_item(
+            bundle,
+            OFFLINE_POSTINGS_FILE,
+            parquet_bytes(offline_postings_batch(&entries)?, props)?,
+        )
+        .await?,
+    ];
+    let space_id = ws_path
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or(ws_path);
+    let manifest = serde_json::json!({
+        "format": OFFLINE_FORMAT,
+        "space_id": space_id,
+        "generated_at": entry::now_ts(),
+        "cursor": cursor,
+        "filter": filter,
+        "analyzer": search::Analyzer::for_space(op, ws_path).await?,
+        "items": items,
+    });
+    bundle
+        .write(OFFLINE_MANIFEST_FILE, serde_json::to_vec_pretty(&manifest)?)
+        .await?;
+    Ok(serde_json::json!({
+        "format": OFFLINE_FORMAT,
+        "cursor": cursor,
+        "entries": entries.len(),
+        "postings": entries.iter().map(|entry| entry.tokens.len()).sum::<usize>(),
+        "items": items,
+    }))
+}
+
+/// Changes an offline bundle built with `filter` needs since `cursor`:
+/// `entries` changed or added (with their search tokens) replace the
+/// client's rows, and `removed` lists entries deleted or no longer matching
+/// `filter`. The returned `cursor` is passed to the next call.
+///
+/// Entries purged from the trash leave no trace, so a client whose cursor is
+/// older than the space's `purge_after_days` should rebuild its bundle.
+pub async fn offline_delta(
+    op: &Operator,
+    ws_path: &str,
+    filter: &SyncFilter,
+    cursor: f64,
+) -> Result<Value> {
+    let (entries, removed, next) = offline_changes(op, ws_path, filter, Some(cursor)).await?;
+    Ok(serde_json::json!({
+        "format": OFFLINE_FORMAT,
+        "since": cursor,
+        "cursor": next,
+        "entries": entries,
+        "removed": removed,
+    }))
+}
@@ -0,0 +1,279 @@
+use anyhow::{anyhow, Result};
+use futures::TryStreamExt;
+use opendal::{EntryMode, Operator};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use crate::asset::ASSET_LINK_PREFIX;
+use crate::entry;
+
+const ASSET_TEXT_DIR: &str = "asset_text";
+/// How long an extractor may run before the upload records a timeout and
+/// moves on. The extractor thread itself cannot be interrupted.
+pub const EXTRACTION_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// What an extractor sees about the asset just uploaded.
+#[derive(Debug, Clone, Copy)]
+pub struct AssetContext<'a> {
+    pub ws_path: &'a str,
+    pub asset_id: &'a str,
+    pub filename: &'a str,
+    /// Declared type from the file name, else the sniffed one.
+    pub content_type: Option<&'a str>,
+}
+
+/// Pulls searchable text out of an uploaded asset, e.g. OCR of a scan.
+///
+/// Extractors are host code registered with [`register_content_extractor`];
+/// like form hooks they apply to every space in the process. They run after
+/// the upload is stored, on a blocking thread bounded by
+/// [`EXTRACTION_TIMEOUT`], and the first one that accepts an asset produces
+/// its text. An error or timeout is recorded with the asset rather than
+/// failing the upload.
+pub trait ContentExtractor: Send + Sync {
+    fn accepts(&self, ctx: &AssetContext) -> bool;
+
+    /// The extracted text, or `None` when the asset has none.
+    fn extract(
+        &self,
+        ctx: &AssetContext,
+        content: &[u8],
+    ) -> std::result::Result<Option<String>, String>;
+}
+
+type ExtractorRegistry = Vec<(String, Arc<dyn ContentExtractor>)>;
+
+fn registry() -> &'static Mutex<ExtractorRegistry> {
+    static REGISTRY: OnceLock<Mutex<ExtractorRegistry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+/// Adds extractor `name`, replacing one registered under the same name;
+/// extractors are tried in registration order.
+pub fn register_content_extractor(name: &str, extractor: Arc<dyn ContentExtractor>) -> Result<()> {
+    let mut registry = registry()
+        .lock()
+        .map_err(|_| anyhow!("extractor registry lock poisoned"))?;
+    match registry.iter_mut().find(|(existing, _)| existing == name) {
+        Some(slot) => slot.1 = extractor,
+        None => registry.push((name.to_string(), extractor)),
+    }
+    Ok(())
+}
+
+/// Removes every registered extractor.
+pub fn clear_content_extractors() -> Result<()> {
+    registry()
+        .lock()
+        .map_err(|_| anyhow!("extractor registry lock poisoned"))?
+        .clear();
+    Ok(())
+}
+
+/// Whether `content_type` matches `pattern`: an exact type, `image/*`, or
+/// `*`.
+pub fn content_type_matches(pattern: &str, content_type: Option<&str>) -> bool {
+    let Some(content_type) = content_type else {
+        return pattern == "*";
+    };
+    let content_type = content_type.to_lowercase();
+    match pattern.to_lowercase().strip_suffix("/*") {
+        Some(major) => content_type.split('/').next() == Some(major),
+        None => pattern == "*" || pattern.eq_ignore_ascii_case(&content_type),
+    }
+}
+
+/// Text derived from one asset, stored in `asset_text/{asset_id}.json`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AssetText {
+    pub asset_id: String,
+    pub extractor: String,
+    #[serde(default)]
+    pub text: Option<String>,
+    /// Why extraction failed, if it did.
+    #[serde(default)]
+    pub error: Option<String>,
+    pub extracted_at: f64,
+}
+
+fn asset_text_path(ws_path: &str, asset_id: &str) -> String {
+    format!(
+        "{}/{}/{}.json",
+        ws_path.trim_end_matches('/'),
+        ASSET_TEXT_DIR,
+        asset_id
+    )
+}
+
+/// Runs the first registered extractor that accepts the asset and stores
+/// its result. Extraction (OCR, PDF parsing, host callbacks holding the GIL)
+/// runs on tokio's blocking pool so it never stalls the runtime. Returns
+/// `None` when no extractor accepts it.
+pub(crate) async fn extract_asset_text(
+    op: &Operator,
+    ctx: &AssetContext<'_>,
+    content: &[u8],
+) -> Result<Option<AssetText>> {
+    let extractors = registry()
+        .lock()
+        .map_err(|_| anyhow!("extractor registry lock poisoned"))?
+        .clone();
+    let Some((name, extractor)) = extractors
+        .into_iter()
+        .find(|(_, extractor)| extractor.accepts(ctx))
+    else {
+        return Ok(None);
+    };
+    let ws_path = ctx.ws_path.to_string();
+    let asset_id = ctx.asset_id.to_string();
+    let filename = ctx.filename.to_string();
+    let content_type = ctx.content_type.map(str::to_string);
+    let content = content.to_vec();
+    let task = tokio::task::spawn_blocking(move || {
+        let ctx = AssetContext {
+            ws_path: &ws_path,
+            asset_id: &asset_id,
+            filename: &filename,
+            content_type: content_type.as_deref(),
+        };
+        extractor.extract(&ctx, &content)
+    });
+    let outcome = match tokio::time::timeout(EXTRACTION_TIMEOUT, task).await {
+        Ok(Ok(outcome)) => outcome,
+        Ok(Err(join_error)) => Err(format!("extractor failed: {}", join_error)),
+        Err(_) => Err(format!(
+            "extractor timed out after {}s",
+            EXTRACTION_TIMEOUT.as_secs()
+        )),
+    };
+    let (text, error) = match outcome {
+        Ok(text) => (text.filter(|text| !text.trim().is_empty()), None),
+        Err(error) => (None, Some(error)),
+    };
+    let record = AssetText {
+        asset_id: ctx.asset_id.to_string(),
+        extractor: name,
+        text,
+        error,
+        extracted_at: entry::now_ts(),
+    };
+    write_asset_text(op, ctx.ws_path, &record).await?;
+    Ok(Some(record))
+}
+
+async fn write_asset_text(op: &Operator, ws_path: &str, record: &AssetText) -> Result<()> {
+    op.write(
+        &asset_text_path(ws_path, &record.asset_id),
+        serde_json::to_vec_pretty(record)?,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Stores text a host extracted out of band, e.g. in a sandboxed OCR job,
+/// replacing any earlier result for the asset.
+pub async fn set_asset_text(
+    op: &Operator,
+    ws_path: &str,
+    asset_id: &str,
+    extractor: &str,
+    text: &str,
+) -> Result<AssetText> {
+    let record = AssetText {
+        asset_id: asset_id.to_string(),
+        extractor: extractor.to_string(),
+        text: Some(text.to_string()).filter(|text| !text.trim().is_empty()),
+        error: None,
+        extracted_at: entry::now_ts(),
+    };
+    write_asset_text(op, ws_path, &record).await?;
+    Ok(record)
+}
+
+pub(crate) async fn delete_asset_text(op: &Operator, ws_path: &str, asset_id: &str) -> Result<()> {
+    op.delete(&asset_text_path(ws_path, asset_id)).await?;
+    Ok(())
+}
+
+async fn read_asset_text(
+    op: &Operator,
+    ws_path: &str,
+    asset_id: &str,
+) -> Result<Option<AssetText>> {
+    let path = asset_text_path(ws_path, asset_id);
+    if !op.exists(&path).await? {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_slice(
+        &op.read(&path).await?.to_vec(),
+    )?))
+}
+
+/// Extracted text of every asset that has some, by asset id.
+pub(crate) async fn list_asset_texts(
+    op: &Operator,
+    ws_path: &str,
+) -> Result<HashMap<String, String>> {
+    let dir = format!("{}/{}/", ws_path.trim_end_matches('/'), ASSET_TEXT_DIR);
+    let mut texts = HashMap::new();
+    if !op.exists(&dir).await? {
+        return Ok(texts);
+    }
+    let mut lister = op.lister(&dir).await?;
+    while let Some(item) = lister.try_next().await? {
+        if item.metadata().mode() != EntryMode::FILE || !item.name().ends_with(".json") {
+            continue;
+        }
+        let bytes = op.read(&format!("{}{}", dir, item.name())).await?.to_vec();
+        let record: AssetText = serde_json::from_slice(&bytes)?;
+        if let Some(text) = record.text {
+            texts.insert(record.asset_id, text);
+        }
+    }
+    Ok(texts)
+}
+
+/// Assets an entry owns: its attachments and every `ugoite://asset/` link in
+/// its fields (which covers `asset_ref` fields). An asset's own metadata
+/// entry owns that asset.
+pub(crate) fn owned_asset_ids(row: &entry::EntryRow) -> HashSet<String> {
+    let mut ids: HashSet<String> = row
+        .assets
+        .iter()
+        .filter_map(|asset| asset.get("id").and_then(Value::as_str))
+        .map(str::to_string)
+        .collect();
+    ids.insert(row.entry_id.clone());
+    let fields = entry::merge_entry_fields(&row.fields, &row.extra_attributes).to_string();
+    for (at, _) in fields.match_indices(ASSET_LINK_PREFIX) {
+        let id: String = fields[at + ASSET_LINK_PREFIX.len()..]
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+            .collect();
+        if !id.is_empty() {
+            ids.insert(id);
+        }
+    }
+    ids
+}
+
+/// The stored text of an asset with the live entries that own it.
+pub async fn get_asset_text(op: &Operator, ws_path: &str, asset_id: &str) -> Result<Value> {
+    let record = read_asset_text(op, ws_path, asset_id)
+        .await?
+        .ok_or_else(|| anyhow!("No extracted text for asset {}", asset_id))?;
+    let mut owners: Vec<String> = entry::list_entry_rows(op, ws_path)
+        .await?
+        .into_iter()
+        .filter(|(_, row)| !row.deleted && row.entry_id != asset_id)
+        .filter(|(_, row)| owned_asset_ids(row).contains(asset_id))
+        .map(|(_, row)| row.entry_id)
+        .collect();
+    owners.sort();
+    let mut value = serde_json::to_value(record)?;
+    value["owners"] = serde_json::json!(owners);
+    Ok(value)
+}
@@ -0,0 +1,218 @@
+use anyhow::{anyhow, Result};
+use opendal::Operator;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::entry;
+use crate::storage;
+
+const FAVORITES_FILE: &str = "favorites.json";
+const PINNED_FILE: &str = "pinned.json";
+
+/// Favorites and pins are separate lists per user.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum FavoriteKind {
+    Favorite,
+    Pin,
+}
+
+impl std::str::FromStr for FavoriteKind {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "favorite" => Ok(Self::Favorite),
+            "pin" => Ok(Self::Pin),
+            other => Err(anyhow!("Unknown favorite kind: {}", other)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Favorite {
+    pub entry_id: String,
+    pub added_at: f64,
+}
+
+/// `favorites.json`: user -> kind -> entries, oldest first.
+type FavoritesDoc = BTreeMap<String, BTreeMap<FavoriteKind, Vec<Favorite>>>;
+
+fn favorites_path(ws_path: &str) -> String {
+    format!("{}/{}", ws_path.trim_end_matches('/'), FAVORITES_FILE)
+}
+
+fn parse_doc(value: Option<Value>) -> Result<FavoritesDoc> {
+    Ok(match value {
+        Some(value) => serde_json::from_value(value)?,
+        None => FavoritesDoc::new(),
+    })
+}
+
+/// Marks an entry as a favorite or pin for `user`. Returns false when it
+/// already was; re-adding does not move it.
+pub async fn add_favorite(
+    op: &Operator,
+    ws_path: &str,
+    user: &str,
+    kind: FavoriteKind,
+    entry_id: &str,
+) -> Result<bool> {
+    if user.is_empty() {
+        return Err(anyhow!("User identity is required"));
+    }
+    let added_at = entry::now_ts();
+    storage::update_json(op, &favorites_path(ws_path), |current| {
+        let mut doc = parse_doc(current)?;
+        let list = doc
+            .entry(user.to_string())
+            .or_default()
+            .entry(kind)
+            .or_default();
+        let added = !list.iter().any(|f| f.entry_id == entry_id);
+        if added {
+            list.push(Favorite {
+                entry_id: entry_id.to_string(),
+                added_at,
+            });
+        }
+        Ok((serde_json::to_value(&doc)?, added))
+    })
+    .await
+}
+
+/// Unmarks an entry for `user`. Returns whether it was marked.
+pub async fn remove_favorite(
+    op: &Operator,
+    ws_path: &str,
+    user: &str,
+    kind: FavoriteKind,
+    entry_id: &str,
+) -> Result<bool> {
+    storage::update_json(op, &favorites_path(ws_path), |current| {
+        let mut doc = parse_doc(current)?;
+        let mut removed = false;
+        if let Some(kinds) = doc.get_mut(user) {
+            if let Some(list) = kinds.get_mut(&kind) {
+                let before = list.len();
+                list.retain(|f| f.entry_id != entry_id);
+                removed = list.len() != before;
+                if list.is_empty() {
+                    kinds.remove(&kind);
+                }
+            }
+            if kinds.is_empty() {
+                doc.remove(user);
+            }
+        }
+        Ok((serde_json::to_value(&doc)?, removed))
+    })
+    .await
+}
+
+/// Lists a user's favorites or pins, most recently added first.
+pub async fn list_favorites(
+    op: &Operator,
+    ws_path: &str,
+    user: &str,
+    kind: FavoriteKind,
+) -> Result<Vec<Favorite>> {
+    let (value, _) = storage::read_json_versioned(op, &favorites_path(ws_path)).await?;
+    let mut doc = parse_doc(value)?;
+    let mut list = doc
+        .remove(user)
+        .and_then(|mut kinds| kinds.remove(&kind))
+        .unwrap_or_default();
+    list.reverse();
+    Ok(list)
+}
+
+/// An entry pinned to the space's sidebar for everyone, unlike a user's
+/// [`FavoriteKind::Pin`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PinnedEntry {
+    pub entry_id: String,
+    pub pinned_at: f64,
+    pub pinned_by: String,
+}
+
+fn pinned_path(ws_path: &str) -> String {
+    format!("{}/{}", ws_path.trim_end_matches('/'), PINNED_FILE)
+}
+
+/// `pinned.json`: `{"entries": [...]}` in sidebar order.
+fn parse_pinned(value: Option<Value>) -> Result<Vec<PinnedEntry>> {
+    Ok(
+        match value.and_then(|mut value| value.get_mut("entries").map(Value::take)) {
+            Some(entries) => serde_json::from_value(entries)?,
+            None => Vec::new(),
+        },
+    )
+}
+
+/// Pins an entry for the whole space at `position` (0 is the top), or last
+/// when `position` is `None` or past the end. Pinning an entry again moves
+/// it when `position` is given and leaves it in place otherwise. Pins live
+/// outside the Form tables, so they never create revisions. Returns the
+/// pinned entries in order.
+pub async fn pin_entry(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+    position: Option<usize>,
+    author: &str,
+) -> Result<Vec<PinnedEntry>> {
+    entry::exists(op, ws_path, entry_id)
+        .await?
+        .filter(|existing| !existing.deleted)
+        .ok_or_else(|| entry::entry_not_found(entry_id))?;
+    let pinned_at = entry::now_ts();
+    storage::update_json(op, &pinned_path(ws_path), |current| {
+        let mut pinned = parse_pinned(current)?;
+        let existing = pinned.iter().position(|p| p.entry_id == entry_id);
+        if existing.is_none() || position.is_some() {
+            let pin = match existing {
+                Some(index) => pinned.remove(index),
+                None => PinnedEntry {
+                    entry_id: entry_id.to_string(),
+                    pinned_at,
+                    pinned_by: author.to_string(),
+                },
+            };
+            let index = position.unwrap_or(pinned.len()).min(pinned.len());
+            pinned.insert(index, pin);
+        }
+        Ok((serde_json::json!({ "entries": pinned }), pinned.clone()))
+    })
+    .await
+}
+
+/// Unpins an entry. Returns whether it was pinned.
+pub async fn unpin_entry(op: &Operator, ws_path: &str, entry_id: &str) -> Result<bool> {
+    storage::update_json(op, &pinned_path(ws_path), |current| {
+        let mut pinned = parse_pinned(current)?;
+        let before = pinned.len();
+        pinned.retain(|p| p.entry_id != entry_id);
+        let removed = pinned.len() != before;
+        Ok((serde_json::json!({ "entries": pinned }), removed))
+    })
+    .await
+}
+
+/// The space's pinned entries in sidebar order. Entries deleted since they
+/// were pinned are left out but keep their place, so undeleting one brings
+/// its pin back.
+pub async fn list_pinned(op: &Operator, ws_path: &str) -> Result<Vec<PinnedEntry>> {
+    let (value, _) = storage::read_json_versioned(op, &pinned_path(ws_path)).await?;
+    let mut listed = Vec::new();
+    for pin in parse_pinned(value)? {
+        let live = entry::exists(op, ws_path, &pin.entry_id)
+            .await?
+            .is_some_and(|existing| !existing.deleted);
+        if live {
+            listed.push(pin);
+        }
+    }
+    Ok(listed)
+}
@@ -0,0 +1,278 @@
+use anyhow::{anyhow, Result};
+use opendal::Operator;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::entry;
+use crate::storage;
+
+const FOLDERS_FILE: &str = "folders.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Folder {
+    pub path: String,
+    pub created_at: f64,
+}
+
+/// Direct children of a folder. `path` is empty for the space root.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FolderListing {
+    pub path: String,
+    pub folders: Vec<String>,
+    pub entries: Vec<String>,
+}
+
+/// `folders.json`: folders by path and the folder each filed entry is in.
+/// Entries without a placement live at the root. Placements of deleted
+/// entries are kept so a restored entry returns to its folder.
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct FoldersDoc {
+    #[serde(default)]
+    folders: BTreeMap<String, Folder>,
+    #[serde(default)]
+    entries: BTreeMap<String, String>,
+}
+
+fn folders_path(ws_path: &str) -> String {
+    format!("{}/{}", ws_path.trim_end_matches('/'), FOLDERS_FILE)
+}
+
+fn parse_doc(value: Option<Value>) -> Result<FoldersDoc> {
+    Ok(match value {
+        Some(value) => serde_json::from_value(value)?,
+        None => FoldersDoc::default(),
+    })
+}
+
+async fn update_folders<F, T>(op: &Operator, ws_path: &str, update: F) -> Result<T>
+where
+    F: Fn(&mut FoldersDoc) -> Result<T>,
+{
+    storage::update_json(op, &folders_path(ws_path), |current| {
+        let mut doc = parse_doc(current)?;
+        let result = update(&mut doc)?;
+        Ok((serde_json::to_value(&doc)?, result))
+    })
+    .await
+}
+
+async fn read_folders(op: &Operator, ws_path: &str) -> Result<FoldersDoc> {
+    let (value, _) = storage::read_json_versioned(op, &folders_path(ws_path)).await?;
+    parse_doc(value)
+}
+
+/// Normalizes a folder path to `a/b/c`. Empty segments are dropped; `.` and
+/// `..` are rejected. The empty string is the root.
+pub fn normalize_folder_path(path: &str) -> Result<String> {
+    let mut segments = Vec::new();
+    for segment in path.split('/').map(str::trim) {
+        match segment {
+            "" => continue,
+            "." | ".." => return Err(anyhow!("Invalid folder path: {}", path)),
+            segment => segments.push(segment),
+        }
+    }
+    Ok(segments.join("/"))
+}
+
+fn parent_of(path: &str) -> &str {
+    path.rsplit_once('/')
+        .map(|(parent, _)| parent)
+        .unwrap_or("")
+}
+
+fn is_within(path: &str, folder: &str) -> bool {
+    path == folder || path.starts_with(&format!("{}/", folder))
+}
+
+/// Creates a folder and any missing parents. Creating an existing folder
+/// returns it unchanged.
+pub async fn create_folder(op: &Operator, ws_path: &str, path: &str) -> Result<Folder> {
+    let path = normalize_folder_path(path)?;
+    if path.is_empty() {
+        return Err(anyhow!("Folder path is required"));
+    }
+    let now = entry::now_ts();
+    update_folders(op, ws_path, |doc| {
+        let mut current = path.as_str();
+        while !current.is_empty() {
+            doc.folders
+                .entry(current.to_string())
+                .or_insert_with(|| Folder {
+                    path: current.to_string(),
+                    created_at: now,
+                });
+            current = parent_of(current);
+        }
+        Ok(doc.folders[&path].clone())
+    })
+    .await
+}
+
+/// Deletes an empty folder. Folders that still hold subfolders or entries,
+/// including deleted entries, are rejected.
+pub async fn delete_folder(op: &Operator, ws_path: &str, path: &str) -> Result<()> {
+    let path = normalize_folder_path(path)?;
+    update_folders(op, ws_path, |doc| {
+        if !doc.folders.contains_key(&path) {
+            return Err(anyhow!("Folder not found: {}", path));
+        }
+        let not_empty = doc.folders.keys().any(|p| parent_of(p) == path)
+            || doc.entries.values().any(|folder| *folder == path);
+        if not_empty {
+            return Err(anyhow!("Folder is not empty: {}", path));
+        }
+        doc.folders.remove(&path);
+        Ok(())
+    })
+    .await
+}
+
+/// Files an entry under `folder`, or back at the root when `folder` is
+/// `None` or empty.
+pub async fn move_entry(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+    folder: Option<&str>,
+) -> Result<()> {
+    let folder = normalize_folder_path(folder.unwrap_or(""))?;
+    match entry::exists(op, ws_path, entry_id).await? {
+        Some(existence) if !existence.deleted => {}
+        _ => return Err(anyhow!("Entry not found: {}", entry_id)),
+    }
+    update_folders(op, ws_path, |doc| {
+        if folder.is_empty() {
+            doc.entries.remove(entry_id);
+            return Ok(());
+        }
+        if !doc.folders.contains_key(&folder) {
+            return Err(anyhow!("Folder not found: {}", folder));
+        }
+        doc.entries.insert(entry_id.to_string(), folder.clone());
+        Ok(())
+    })
+    .await
+}
+
+/// Moves or renames a folder together with everything beneath it.
+pub async fn move_folder(op: &Operator, ws_path: &str, from: &str, to: &str) -> Result<Folder> {
+    let from = normalize_folder_path(from)?;
+    let to = normalize_folder_path(to)?;
+    if to.is_empty() {
+        return Err(anyhow!("Folder path is required"));
+    }
+    if is_within(&to, &from) {
+        return Err(anyhow!("Cannot move folder {} into itself", from));
+    }
+    let now = entry::now_ts();
+    update_folders(op, ws_path, |doc| {
+        if !doc.folders.contains_key(&from) {
+            return Err(anyhow!("Folder not found: {}", from));
+        }
+        if doc.folders.contains_key(&to) {
+            return Err(anyhow!("Folder already exists: {}", to));
+        }
+        let rebase = |path: &str| format!("{}{}", to, &path[from.len()..]);
+        let moved: Vec<String> = doc
+            .folders
+            .keys()
+            .filter(|p| is_within(p, &from))
+            .cloned()
+            .collect();
+        for old in moved {
+            let mut folder = doc.folders.remove(&old).expect("folder listed above");
+            folder.path = rebase(&old);
+            doc.folders.insert(folder.path.clone(), folder);
+        }
+        for folder in doc.entries.values_mut() {
+            if is_within(folder, &from) {
+                *folder = rebase(folder);
+            }
+        }
+        let mut parent = parent_of(&to);
+        while !parent.is_empty() {
+            doc.folders
+                .entry(parent.to_string())
+                .or_insert_with(|| Folder {
+                    path: parent.to_string(),
+                    created_at: now,
+                });
+            parent = parent_of(parent);
+        }
+        Ok(doc.folders[&to].clone())
+    })
+    .await
+}
+
+/// Lists the subfolders and live entries directly inside `path` (the root
+/// when empty), sorted by name.
+pub async fn list_children(op: &Operator, ws_path: &str, path: &str) -> Result<FolderListing> {
+    let path = normalize_folder_path(path)?;
+    let doc = read_folders(op, ws_path).await?;
+    if !path.is_empty() && !doc.folders.contains_key(&path) {
+        return Err(anyhow!("Folder not found: {}", path));
+    }
+    let folders = doc
+        .folders
+        .keys()
+        .filter(|p| parent_of(p) == path)
+        .cloned()
+        .collect();
+
+    let mut entries = Vec::new();
+    if path.is_empty() {
+        for (_, row) in entry::list_entry_rows(op, ws_path).await? {
+            if !row.deleted && !doc.entries.contains_key(&row.entry_id) {
+                entries.push(row.entry_id);
+            }
+        }
+    } else {
+        for (entry_id, folder) in &doc.entries {
+            if *folder != path {
+                continue;
+            }
+            if entry::exists(op, ws_path, entry_id)
+                .await?
+                .is_some_and(|existence| !existence.deleted)
+            {
+                entries.push(entry_id.clone());
+            }
+        }
+    }
+    entries.sort();
+    Ok(FolderListing {
+        path,
+        folders,
+        entries,
+    })
+}
+
+/// The folder an entry is filed under; empty for the root.
+pub async fn entry_folder(op: &Operator, ws_path: &str, entry_id: &str) -> Result<String> {
+    let doc = read_folders(op, ws_path).await?;
+    Ok(doc.entries.get(entry_id).cloned().unwrap_or_default())
+}
+
+/// Resolves a path such as `projects/pumps/pump-3` to the entry id it names.
+/// The last segment is the entry id and must be filed in the folder given by
+/// the segments before it.
+pub async fn resolve_entry_path(op: &Operator, ws_path: &str, path: &str) -> Result<String> {
+    let path = normalize_folder_path(path)?;
+    let (folder, entry_id) = match path.rsplit_once('/') {
+        Some((folder, entry_id)) => (folder, entry_id),
+        None => ("", path.as_str()),
+    };
+    if entry_id.is_empty() {
+        return Err(anyhow!("Entry path is required"));
+    }
+    let not_found = || anyhow!("Entry not found at path: {}", path);
+    if entry_folder(op, ws_path, entry_id).await? != folder {
+        return Err(not_found());
+    }
+    match entry::exists(op, ws_path, entry_id).await? {
+        Some(existence) if !existence.deleted => Ok(entry_id.to_string()),
+        _ => Err(not_found()),
+    }
+}
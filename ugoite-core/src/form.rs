@@ -1,12 +1,24 @@
+use crate::clock;
+use crate::decimal::DecimalType;
 use crate::entry;
+use crate::entry_id;
+use crate::hooks;
+use crate::i18n;
 use crate::iceberg_store;
 use crate::integrity::IntegrityProvider;
 use crate::metadata;
+use crate::rollup;
+use crate::storage;
 use anyhow::{anyhow, Context, Result};
 use opendal::Operator;
+use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
-use std::collections::HashSet;
-use uuid::Uuid;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Author recorded on revisions written by [`migrate_form`].
+pub const MIGRATION_AUTHOR: &str = "system-migration";
+const FORM_HISTORY_FILE: &str = "form_history.json";
+const FORM_LINEAGE_FILE: &str = "form_lineage.json";
 
 pub async fn list_forms(op: &Operator, ws_path: &str) -> Result<Vec<Value>> {
     let mut forms = Vec::new();
@@ -36,7 +48,12 @@ pub async fn list_column_types() -> Result<Vec<String>> {
         "timestamp_ns".to_string(),
         "timestamp_tz_ns".to_string(),
         "uuid".to_string(),
+        "decimal".to_string(),
         "row_reference".to_string(),
+        "asset_ref".to_string(),
+        "url".to_string(),
+        "geopoint".to_string(),
+        "geojson".to_string(),
         "binary".to_string(),
         "list".to_string(),
         "object_list".to_string(),
@@ -49,12 +66,33 @@ pub async fn get_form(op: &Operator, ws_path: &str, form_name: &str) -> Result<V
 }
 
 pub async fn upsert_form(op: &Operator, ws_path: &str, form_def: &Value) -> Result<()> {
+    save_form(op, ws_path, form_def, None).await
+}
+
+/// [`upsert_form`] recording `author` with the definition in the form's
+/// history.
+pub async fn upsert_form_as(
+    op: &Operator,
+    ws_path: &str,
+    form_def: &Value,
+    author: &str,
+) -> Result<()> {
+    save_form(op, ws_path, form_def, Some(author)).await
+}
+
+async fn save_form(
+    op: &Operator,
+    ws_path: &str,
+    form_def: &Value,
+    author: Option<&str>,
+) -> Result<()> {
     let normalized = normalize_form_definition(form_def)?;
     let form_name = normalized
         .get("name")
         .and_then(|v| v.as_str())
         .context("Form definition missing 'name' field")?;
     validate_row_reference_targets(op, ws_path, form_name, &normalized).await?;
+    rollup::validate_rollup_sources(op, ws_path, form_name, &normalized).await?;
     let existing = match iceberg_store::load_form_definition(op, ws_path, form_name).await {
         Ok(def) => Some(def),
         Err(_) => iceberg_store::load_form_definition_from_metadata(op, ws_path, form_name)
@@ -81,12 +119,231 @@ pub async fn upsert_form(op: &Operator, ws_path: &str, form_def: &Value) -> Resu
         };
         if fields_changed || def_changed || schema_mismatch {
             rebuild_form_tables(op, ws_path, form_name, &existing_def, &normalized).await?;
-            return Ok(());
+        } else {
+            iceberg_store::ensure_form_tables(op, ws_path, &normalized).await?;
         }
+    } else {
+        iceberg_store::ensure_form_tables(op, ws_path, &normalized).await?;
     }
 
+    if normalized.get("rollups").is_some() {
+        rollup::recompute_form_rollups(op, ws_path, form_name).await?;
+    }
+    record_form_definition(op, ws_path, form_name, &normalized, author, None).await
+}
+
+fn form_history_path(ws_path: &str) -> String {
+    format!("{}/{}", ws_path.trim_end_matches('/'), FORM_HISTORY_FILE)
+}
+
+/// Appends `form_def` to `form_history.json` unless it is already the last
+/// definition recorded for the form.
+async fn record_form_definition(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+    form_def: &Value,
+    author: Option<&str>,
+    rolled_back_from: Option<usize>,
+) -> Result<()> {
+    let recorded_at = entry::now_ts();
+    storage::update_json(op, &form_history_path(ws_path), |current| {
+        let mut doc = match current {
+            Some(Value::Object(map)) => map,
+            _ => Map::new(),
+        };
+        let history = doc
+            .entry(form_name.to_string())
+            .or_insert_with(|| Value::Array(Vec::new()));
+        if let Value::Array(items) = history {
+            if items.last().and_then(|item| item.get("definition")) != Some(form_def) {
+                let mut item = serde_json::json!({
+                    "recorded_at": recorded_at,
+                    "definition": form_def,
+                });
+                if let Some(author) = author {
+                    item["author"] = Value::String(author.to_string());
+                }
+                if let Some(revision) = rolled_back_from {
+                    item["rolled_back_from"] = serde_json::json!(revision);
+                }
+                items.push(item);
+            }
+        }
+        Ok((Value::Object(doc), ()))
+    })
+    .await
+}
+
+/// One definition of a form recorded in `form_history.json`. Revisions
+/// count from 1 in the order they were recorded.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FormRevision {
+    pub revision: usize,
+    pub recorded_at: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Revision this one restored, for rollbacks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rolled_back_from: Option<usize>,
+    pub definition: Value,
+}
+
+/// Every recorded definition of `form_name`, oldest first. Empty for forms
+/// last written before history was kept.
+async fn form_revisions(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+) -> Result<Vec<FormRevision>> {
+    let (doc, _) = storage::read_json_versioned(op, &form_history_path(ws_path)).await?;
+    let items = doc
+        .as_ref()
+        .and_then(|doc| doc.get(form_name))
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    Ok(items
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, item)| {
+            Some(FormRevision {
+                revision: index + 1,
+                recorded_at: item.get("recorded_at")?.as_f64()?,
+                author: item
+                    .get("author")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                rolled_back_from: item
+                    .get("rolled_back_from")
+                    .and_then(Value::as_u64)
+                    .map(|revision| revision as usize),
+                definition: item.get("definition")?.clone(),
+            })
+        })
+        .collect())
+}
+
+/// Definitions of `form_name` recorded by upserts, migrations and
+/// rollbacks, oldest first, as `(recorded_at, definition)`.
+pub(crate) async fn form_definition_history(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+) -> Result<Vec<(f64, Value)>> {
+    Ok(form_revisions(op, ws_path, form_name)
+        .await?
+        .into_iter()
+        .map(|revision| (revision.recorded_at, revision.definition))
+        .collect())
+}
+
+/// The recorded definitions of a form, newest first, without the
+/// definitions themselves: `revision`, `recorded_at`, `author`, the form's
+/// `version`, its field names and, for rollbacks, `rolled_back_from`.
+pub async fn get_form_history(op: &Operator, ws_path: &str, form_name: &str) -> Result<Vec<Value>> {
+    read_form_definition(op, ws_path, form_name).await?;
+    let mut revisions = form_revisions(op, ws_path, form_name).await?;
+    revisions.reverse();
+    Ok(revisions
+        .into_iter()
+        .map(|revision| {
+            let fields: Vec<&String> = revision
+                .definition
+                .get("fields")
+                .and_then(Value::as_object)
+                .map(|fields| fields.keys().collect())
+                .unwrap_or_default();
+            let mut summary = serde_json::json!({
+                "revision": revision.revision,
+                "recorded_at": revision.recorded_at,
+                "author": revision.author,
+                "version": revision.definition.get("version"),
+                "fields": fields,
+            });
+            if let Some(from) = revision.rolled_back_from {
+                summary["rolled_back_from"] = serde_json::json!(from);
+            }
+            summary
+        })
+        .collect())
+}
+
+/// Revision `revision` of a form's definition.
+pub async fn get_form_version(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+    revision: usize,
+) -> Result<FormRevision> {
+    read_form_definition(op, ws_path, form_name).await?;
+    form_revisions(op, ws_path, form_name)
+        .await?
+        .into_iter()
+        .find(|recorded| recorded.revision == revision)
+        .ok_or_else(|| anyhow!("Form {} has no revision {}", form_name, revision))
+}
+
+/// Field name -> (type, required): what the form's Iceberg tables store.
+fn stored_fields(form_def: &Value) -> BTreeMap<String, (String, bool)> {
+    form_def
+        .get("fields")
+        .and_then(Value::as_object)
+        .map(|fields| {
+            fields
+                .iter()
+                .map(|(name, def)| {
+                    let field_type = def.get("type").and_then(Value::as_str).unwrap_or("string");
+                    let required = def
+                        .get("required")
+                        .and_then(Value::as_bool)
+                        .unwrap_or(false);
+                    (name.clone(), (field_type.to_string(), required))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Makes revision `revision` the form's definition again, recorded as a new
+/// revision by `author`. Entries are not rewritten, so the revision must
+/// store the same fields with the same types as the current definition;
+/// anything else (help text, layout, rollups, extra attribute handling) can
+/// be rolled back. Undo field changes with [`migrate_form`] instead.
+/// Returns the form as [`get_form`] does.
+pub async fn rollback_form(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+    revision: usize,
+    author: &str,
+) -> Result<Value> {
+    let target = get_form_version(op, ws_path, form_name, revision).await?;
+    let normalized = normalize_form_definition(&target.definition)?;
+    let current = read_form_definition(op, ws_path, form_name).await?;
+    if stored_fields(&current) != stored_fields(&normalized) {
+        return Err(anyhow!(
+            "Revision {} of form {} stores different fields; use migrate_form to change them",
+            revision,
+            form_name
+        ));
+    }
+    validate_row_reference_targets(op, ws_path, form_name, &normalized).await?;
+    rollup::validate_rollup_sources(op, ws_path, form_name, &normalized).await?;
     iceberg_store::ensure_form_tables(op, ws_path, &normalized).await?;
-    Ok(())
+    if normalized.get("rollups").is_some() {
+        rollup::recompute_form_rollups(op, ws_path, form_name).await?;
+    }
+    record_form_definition(
+        op,
+        ws_path,
+        form_name,
+        &normalized,
+        Some(author),
+        Some(revision),
+    )
+    .await?;
+    enrich_form_definition(&normalized)
 }
 
 pub(crate) async fn upsert_metadata_form(
@@ -99,6 +356,399 @@ pub(crate) async fn upsert_metadata_form(
     Ok(())
 }
 
+/// Conversion applied to a source field's value by a mapping strategy.
+#[derive(Debug, Clone, PartialEq)]
+enum FieldTransform {
+    Copy,
+    ToString,
+    ToNumber,
+    Lowercase,
+    Uppercase,
+    Trim,
+    Scale(f64),
+}
+
+impl FieldTransform {
+    fn parse(strategy: &Map<String, Value>) -> Result<Self> {
+        let name = match strategy.get("transform") {
+            None => "copy",
+            Some(value) => value
+                .as_str()
+                .ok_or_else(|| anyhow!("Migration transform must be a string"))?,
+        };
+        Ok(match name {
+            "copy" => Self::Copy,
+            "to_string" => Self::ToString,
+            "to_number" => Self::ToNumber,
+            "lowercase" => Self::Lowercase,
+            "uppercase" => Self::Uppercase,
+            "trim" => Self::Trim,
+            "scale" => Self::Scale(
+                strategy
+                    .get("factor")
+                    .and_then(Value::as_f64)
+                    .ok_or_else(|| anyhow!("Migration transform scale needs a numeric factor"))?,
+            ),
+            other => return Err(anyhow!("Unknown migration transform: {}", other)),
+        })
+    }
+
+    /// How the step is shown in lineage, e.g. `scale(1.8)`.
+    fn describe(&self) -> String {
+        match self {
+            Self::Copy => "copy".to_string(),
+            Self::ToString => "to_string".to_string(),
+            Self::ToNumber => "to_number".to_string(),
+            Self::Lowercase => "lowercase".to_string(),
+            Self::Uppercase => "uppercase".to_string(),
+            Self::Trim => "trim".to_string(),
+            Self::Scale(factor) => format!("scale({})", factor),
+        }
+    }
+
+    fn apply(&self, value: &Value) -> Result<Value> {
+        let text = || match value {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        };
+        let number = || -> Result<f64> {
+            match value {
+                Value::Number(number) => number.as_f64(),
+                Value::String(text) => text.trim().parse().ok(),
+                _ => None,
+            }
+            .ok_or_else(|| anyhow!("{} is not a number", value))
+        };
+        let json_number = |number: f64| {
+            serde_json::Number::from_f64(number)
+                .map(Value::Number)
+                .ok_or_else(|| anyhow!("{} is not a finite number", number))
+        };
+        match self {
+            Self::Copy => Ok(value.clone()),
+            Self::ToString => Ok(Value::String(text())),
+            Self::ToNumber => json_number(number()?),
+            Self::Lowercase => Ok(Value::String(text().to_lowercase())),
+            Self::Uppercase => Ok(Value::String(text().to_uppercase())),
+            Self::Trim => Ok(Value::String(text().trim().to_string())),
+            Self::Scale(factor) => json_number(number()? * factor),
+        }
+    }
+}
+
+/// What [`migrate_form`] does to one field of every entry.
+#[derive(Debug, Clone, PartialEq)]
+enum FieldStrategy {
+    Remove,
+    Constant(Value),
+    /// Fill from `from` as it was before the migration, e.g. a rename.
+    Map {
+        from: String,
+        transform: FieldTransform,
+    },
+}
+
+impl FieldStrategy {
+    /// `null` removes the field, `{"from": ..., "transform": ...}` maps
+    /// another field into it and any other value is set as a constant.
+    fn parse(value: &Value) -> Result<Self> {
+        let Some(map) = value.as_object().filter(|map| {
+            map.get("from").is_some_and(Value::is_string)
+                && map
+                    .keys()
+                    .all(|key| matches!(key.as_str(), "from" | "transform" | "factor"))
+        }) else {
+            return Ok(match value {
+                Value::Null => Self::Remove,
+                other => Self::Constant(other.clone()),
+            });
+        };
+        Ok(Self::Map {
+            from: map["from"].as_str().unwrap_or_default().to_string(),
+            transform: FieldTransform::parse(map)?,
+        })
+    }
+}
+
+/// Fills the fields of `target_def` from an entry's `fields`, for moving the
+/// entry to that form. `mapping` gives strategies by target field, as
+/// [`migrate_form`] takes them; target fields it does not name copy the
+/// source field of the same name. Returns the mapped fields and the source
+/// fields with values that nothing read.
+pub(crate) fn map_fields_to_form(
+    target_def: &Value,
+    fields: &Map<String, Value>,
+    mapping: Option<&Value>,
+) -> Result<(Map<String, Value>, Vec<String>)> {
+    let target_fields: Vec<&String> = target_def
+        .get("fields")
+        .and_then(Value::as_object)
+        .map(|fields| fields.keys().collect())
+        .unwrap_or_default();
+    let mapping = match mapping {
+        Some(mapping) => mapping
+            .as_object()
+            .context("Field mapping must be an object")?
+            .clone(),
+        None => Map::new(),
+    };
+    if let Some(unknown) = mapping.keys().find(|field| !target_fields.contains(field)) {
+        return Err(anyhow!(
+            "Field mapping names '{}', which is not a field of form {}",
+            unknown,
+            target_def["name"].as_str().unwrap_or_default()
+        ));
+    }
+
+    let mut mapped = Map::new();
+    let mut read: HashSet<&str> = HashSet::new();
+    for field in target_fields {
+        let strategy = match mapping.get(field) {
+            Some(strategy) => FieldStrategy::parse(strategy)?,
+            None => FieldStrategy::Map {
+                from: field.clone(),
+                transform: FieldTransform::Copy,
+            },
+        };
+        let value = match strategy {
+            FieldStrategy::Remove => continue,
+            FieldStrategy::Constant(value) => value,
+            FieldStrategy::Map { from, transform } => {
+                let Some((from, value)) = fields
+                    .get_key_value(&from)
+                    .filter(|(_, value)| !value.is_null())
+                else {
+                    continue;
+                };
+                read.insert(from.as_str());
+                transform
+                    .apply(value)
+                    .with_context(|| format!("Cannot map {} into {}", from, field))?
+            }
+        };
+        mapped.insert(field.clone(), value);
+    }
+    let unread = fields
+        .iter()
+        .filter(|(name, value)| !value.is_null() && !read.contains(name.as_str()))
+        .map(|(name, _)| name.clone())
+        .collect();
+    Ok((mapped, unread))
+}
+
+/// One field filled by a migration: from another field through a transform,
+/// or with a constant when `from` is `None`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LineageStep {
+    pub field: String,
+    /// Form version the migration produced.
+    pub version: i64,
+    pub recorded_at: f64,
+    pub from: Option<String>,
+    pub transform: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+}
+
+fn form_lineage_path(ws_path: &str) -> String {
+    format!("{}/{}", ws_path.trim_end_matches('/'), FORM_LINEAGE_FILE)
+}
+
+async fn record_field_lineage(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+    steps: Vec<LineageStep>,
+) -> Result<()> {
+    if steps.is_empty() {
+        return Ok(());
+    }
+    storage::update_json(op, &form_lineage_path(ws_path), |current| {
+        let mut doc = match current {
+            Some(Value::Object(map)) => map,
+            _ => Map::new(),
+        };
+        let lineage = doc
+            .entry(form_name.to_string())
+            .or_insert_with(|| Value::Array(Vec::new()));
+        if let Value::Array(items) = lineage {
+            for step in &steps {
+                items.push(serde_json::to_value(step)?);
+            }
+        }
+        Ok((Value::Object(doc), ()))
+    })
+    .await
+}
+
+/// Where `field` of `form_name` came from: the migrations that filled it,
+/// newest first, each naming the field it read and the transform applied,
+/// then the migrations that filled that field, and so on. `origin` is the
+/// field the chain starts from, or `null` when it starts from a constant.
+pub async fn field_lineage(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+    field: &str,
+) -> Result<Value> {
+    read_form_definition(op, ws_path, form_name).await?;
+    let (doc, _) = storage::read_json_versioned(op, &form_lineage_path(ws_path)).await?;
+    let steps: Vec<LineageStep> = match doc.as_ref().and_then(|doc| doc.get(form_name)) {
+        Some(items) => serde_json::from_value(items.clone())?,
+        None => Vec::new(),
+    };
+    let mut chain: Vec<&LineageStep> = Vec::new();
+    let mut origin = Some(field.to_string());
+    let mut upto = steps.len();
+    while let Some(current) = origin.clone() {
+        let Some(at) = steps[..upto].iter().rposition(|step| step.field == current) else {
+            break;
+        };
+        chain.push(&steps[at]);
+        origin = steps[at].from.clone();
+        upto = at;
+    }
+    Ok(serde_json::json!({
+        "form": form_name,
+        "field": field,
+        "origin": origin,
+        "steps": chain,
+    }))
+}
+
+/// A [`migrate_form`] request checked against the form as it is.
+struct MigrationPlan {
+    normalized: Value,
+    existing_def: Option<Value>,
+    strategies: Vec<(String, FieldStrategy)>,
+}
+
+impl MigrationPlan {
+    async fn new(
+        op: &Operator,
+        ws_path: &str,
+        form_def: &Value,
+        strategies: Option<Value>,
+    ) -> Result<Self> {
+        let normalized = normalize_form_definition(form_def)?;
+        let form_name = normalized["name"].as_str().context("Form name required")?;
+        validate_row_reference_targets(op, ws_path, form_name, &normalized).await?;
+        let existing_def = match iceberg_store::load_form_definition(op, ws_path, form_name).await {
+            Ok(def) => Some(def),
+            Err(_) => iceberg_store::load_form_definition_from_metadata(op, ws_path, form_name)
+                .await
+                .ok()
+                .flatten(),
+        };
+
+        let form_set: HashSet<String> = normalized
+            .get("fields")
+            .and_then(|v| v.as_object())
+            .map(|map| map.keys().cloned().collect())
+            .unwrap_or_default();
+        let mut parsed: Vec<(String, FieldStrategy)> = Vec::new();
+        if let Some(strategies) = &strategies {
+            let strategies = strategies
+                .as_object()
+                .context("Strategies must be an object")?;
+            for (field, strategy) in strategies {
+                if form_set.contains(field) {
+                    parsed.push((field.clone(), FieldStrategy::parse(strategy)?));
+                }
+            }
+        }
+        let plan = Self {
+            normalized,
+            existing_def,
+            strategies: parsed,
+        };
+
+        let source_def = plan.existing_def.as_ref().unwrap_or(&plan.normalized);
+        for (from, _) in plan.mappings() {
+            if source_def
+                .get("fields")
+                .and_then(|fields| fields.get(from))
+                .is_none()
+            {
+                return Err(anyhow!(
+                    "Migration source field '{}' does not exist in form {}",
+                    from,
+                    plan.form_name()
+                ));
+            }
+        }
+        Ok(plan)
+    }
+
+    fn form_name(&self) -> &str {
+        self.normalized["name"].as_str().unwrap_or_default()
+    }
+
+    fn mappings(&self) -> Vec<(&str, &FieldTransform)> {
+        self.strategies
+            .iter()
+            .filter_map(|(_, strategy)| match strategy {
+                FieldStrategy::Map { from, transform } => Some((from.as_str(), transform)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Whether saving the definition rebuilds the form's tables, dropping
+    /// the values of fields it no longer has.
+    fn rebuilds_tables(&self) -> bool {
+        self.existing_def
+            .as_ref()
+            .is_some_and(|existing| existing.get("fields") != self.normalized.get("fields"))
+    }
+
+    /// Applies the strategies to `fields`, reading mapped fields from
+    /// `source`. Returns whether anything changed.
+    fn apply(&self, fields: &mut Map<String, Value>, source: &Map<String, Value>) -> Result<bool> {
+        let mut changed = false;
+        for (field, strategy) in &self.strategies {
+            let value = match strategy {
+                FieldStrategy::Remove => {
+                    if fields.remove(field).is_some() {
+                        changed = true;
+                    }
+                    continue;
+                }
+                FieldStrategy::Constant(value) => value.clone(),
+                FieldStrategy::Map { from, transform } => {
+                    match source.get(from).filter(|value| !value.is_null()) {
+                        Some(value) => transform
+                            .apply(value)
+                            .with_context(|| format!("Cannot map {} into {}", from, field))?,
+                        None => continue,
+                    }
+                }
+            };
+
+            let updated = match fields.get(field) {
+                Some(existing) => *existing != value,
+                None => true,
+            };
+            if updated {
+                fields.insert(field.clone(), value);
+                changed = true;
+            }
+        }
+        Ok(changed)
+    }
+}
+
+/// Saves `form_def` and applies `strategies` (field name -> strategy) to
+/// every entry of the form, writing a migration revision for each entry
+/// that changes. Returns the number of entries changed.
+///
+/// A strategy of `null` removes the field, `{"from": "Old", "transform":
+/// "to_number"}` fills it from another field as it was before the
+/// migration (`copy` when `transform` is omitted; `scale` takes a `factor`),
+/// and any other value is set as a constant. Mappings are checked against
+/// every entry before anything is written, and each mapped or constant field
+/// is recorded for [`field_lineage`]. [`preview_migration`] reports what a
+/// migration would do without writing.
 pub async fn migrate_form<I: IntegrityProvider>(
     op: &Operator,
     ws_path: &str,
@@ -106,37 +756,55 @@ pub async fn migrate_form<I: IntegrityProvider>(
     strategies: Option<Value>,
     integrity: &I,
 ) -> Result<usize> {
-    let normalized = normalize_form_definition(form_def)?;
-    let form_name = normalized["name"].as_str().context("Form name required")?;
-    validate_row_reference_targets(op, ws_path, form_name, &normalized).await?;
-    let existing_def = match iceberg_store::load_form_definition(op, ws_path, form_name).await {
-        Ok(def) => Some(def),
-        Err(_) => iceberg_store::load_form_definition_from_metadata(op, ws_path, form_name)
-            .await
-            .ok()
-            .flatten(),
-    };
+    let plan = MigrationPlan::new(op, ws_path, form_def, strategies).await?;
+    let form_name = plan.form_name();
+    let normalized = &plan.normalized;
 
-    if let Some(existing_def) = existing_def {
-        let fields_changed = existing_def.get("fields") != normalized.get("fields");
-        if fields_changed {
-            rebuild_form_tables(op, ws_path, form_name, &existing_def, &normalized).await?;
-        } else {
-            upsert_form(op, ws_path, &normalized).await?;
+    // Mapped fields read the entries as they were before the tables are
+    // rebuilt for the new definition, which drops removed fields.
+    let mut sources: HashMap<String, Map<String, Value>> = HashMap::new();
+    let mappings = plan.mappings();
+    if !mappings.is_empty() {
+        if let Some(existing_def) = &plan.existing_def {
+            for row in entry::list_form_entry_rows(op, ws_path, form_name, existing_def).await? {
+                let fields = row.fields.as_object().cloned().unwrap_or_default();
+                for (from, transform) in &mappings {
+                    if let Some(value) = fields.get(*from).filter(|value| !value.is_null()) {
+                        transform.apply(value).with_context(|| {
+                            format!(
+                                "Cannot migrate {}.{} of entry {}",
+                                form_name, from, row.entry_id
+                            )
+                        })?;
+                    }
+                }
+                sources.insert(row.entry_id, fields);
+            }
         }
-    } else {
-        upsert_form(op, ws_path, &normalized).await?;
     }
 
-    let strategies = match strategies {
-        Some(value) => value,
-        None => return Ok(0),
-    };
-    let strategies_obj = strategies
-        .as_object()
-        .context("Strategies must be an object")?;
+    match &plan.existing_def {
+        Some(existing_def) if plan.rebuilds_tables() => {
+            rebuild_form_tables(op, ws_path, form_name, existing_def, normalized).await?;
+            record_form_definition(
+                op,
+                ws_path,
+                form_name,
+                normalized,
+                Some(MIGRATION_AUTHOR),
+                None,
+            )
+            .await?;
+        }
+        _ => save_form(op, ws_path, normalized, Some(MIGRATION_AUTHOR)).await?,
+    }
+
+    if plan.strategies.is_empty() {
+        return Ok(0);
+    }
 
-    let entry_entries = entry::list_entries(op, ws_path).await?;
+    let entry_entries =
+        entry::list_entries_including(op, ws_path, &[], entry::ArchivedFilter::Include).await?;
     let entry_ids: Vec<String> = entry_entries
         .iter()
         .filter_map(|val| {
@@ -151,12 +819,6 @@ pub async fn migrate_form<I: IntegrityProvider>(
         .collect();
     let mut updated_count = 0;
 
-    let form_set: HashSet<String> = normalized
-        .get("fields")
-        .and_then(|v| v.as_object())
-        .map(|map| map.keys().cloned().collect())
-        .unwrap_or_default();
-
     for entry_id in entry_ids {
         let mut row = match entry::read_entry_row(op, ws_path, form_name, &entry_id).await {
             Ok(r) => r,
@@ -164,44 +826,20 @@ pub async fn migrate_form<I: IntegrityProvider>(
         };
 
         let mut fields = row.fields.as_object().cloned().unwrap_or_else(Map::new);
-        let mut changed = false;
-
-        for (field, strategy) in strategies_obj {
-            if !form_set.contains(field) {
-                continue;
-            }
-            if strategy.is_null() {
-                if fields.remove(field).is_some() {
-                    changed = true;
-                }
-                continue;
-            }
-
-            let updated = match fields.get(field) {
-                Some(existing) => existing != strategy,
-                None => true,
-            };
-            if updated {
-                fields.insert(field.clone(), strategy.clone());
-                changed = true;
-            }
-        }
-
-        if !changed {
+        let source = sources.get(&entry_id).unwrap_or(&fields).clone();
+        if !plan.apply(&mut fields, &source)? {
             continue;
         }
 
-        let mut timestamp = entry::now_ts();
-        if timestamp <= row.updated_at {
-            timestamp = row.updated_at + 0.001;
-        }
-        let new_rev_id = Uuid::new_v4().to_string();
+        let timestamp = clock::next_micros(ws_path, Some(row.updated_at));
+        let new_rev_id = entry_id::new_revision_id(op, ws_path).await?;
 
         row.parent_revision_id = Some(row.revision_id.clone());
         row.revision_id = new_rev_id.clone();
         row.updated_at = timestamp;
+        let changed_fields = entry::diff_fields(&row.fields, &Value::Object(fields.clone()));
         row.fields = Value::Object(fields);
-        row.author = "system-migration".to_string();
+        row.author = MIGRATION_AUTHOR.to_string();
 
         let markdown = entry::render_markdown_for_form(
             &row.title,
@@ -209,7 +847,7 @@ pub async fn migrate_form<I: IntegrityProvider>(
             &row.tags,
             &row.fields,
             &row.extra_attributes,
-            &normalized,
+            normalized,
         );
         let checksum = integrity.checksum(&markdown);
         let signature = integrity.signature(&markdown);
@@ -231,15 +869,213 @@ pub async fn migrate_form<I: IntegrityProvider>(
             markdown_checksum: checksum,
             integrity: row.integrity.clone(),
             restored_from: None,
+            changed_fields,
+            message: None,
         };
-        entry::append_revision_row_for_form(op, ws_path, form_name, &revision, &normalized).await?;
+        entry::append_revision_row_for_form(op, ws_path, form_name, &revision, normalized).await?;
 
         updated_count += 1;
     }
 
+    let version = normalized
+        .get("version")
+        .and_then(Value::as_i64)
+        .unwrap_or(1);
+    let recorded_at = entry::now_ts();
+    let steps = plan
+        .strategies
+        .iter()
+        .filter_map(|(field, strategy)| {
+            let (from, transform, value) = match strategy {
+                FieldStrategy::Remove => return None,
+                FieldStrategy::Constant(value) => {
+                    (None, "constant".to_string(), Some(value.clone()))
+                }
+                FieldStrategy::Map { from, transform } => {
+                    (Some(from.clone()), transform.describe(), None)
+                }
+            };
+            Some(LineageStep {
+                field: field.clone(),
+                version,
+                recorded_at,
+                from,
+                transform,
+                value,
+            })
+        })
+        .collect();
+    record_field_lineage(op, ws_path, form_name, steps).await?;
+
     Ok(updated_count)
 }
 
+fn field_type<'a>(form_def: &'a Value, field: &str) -> Option<&'a str> {
+    form_def.get("fields")?.get(field)?.get("type")?.as_str()
+}
+
+fn json_size(value: &Value) -> usize {
+    serde_json::to_vec(value)
+        .map(|bytes| bytes.len())
+        .unwrap_or_default()
+}
+
+/// What [`migrate_form`] would do with the same arguments, without writing
+/// anything: how many entries change, which fields lose values or change
+/// type, the values of up to `samples` changed entries before and after, and
+/// the rows the migration rewrites with an estimate of their size in bytes.
+/// Entries a transform cannot convert are listed under `failures`, where
+/// the migration itself would fail.
+pub async fn preview_migration(
+    op: &Operator,
+    ws_path: &str,
+    form_def: &Value,
+    strategies: Option<Value>,
+    samples: usize,
+) -> Result<Value> {
+    let plan = MigrationPlan::new(op, ws_path, form_def, strategies).await?;
+    let form_name = plan.form_name();
+    let normalized = &plan.normalized;
+    let mut rows = match &plan.existing_def {
+        Some(existing_def) => {
+            entry::list_form_entry_rows(op, ws_path, form_name, existing_def).await?
+        }
+        None => Vec::new(),
+    };
+    rows.sort_by(|a, b| a.entry_id.cmp(&b.entry_id));
+    let rebuilds_tables = plan.rebuilds_tables();
+    let new_fields: Map<String, Value> = normalized
+        .get("fields")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+
+    let mut entries = 0;
+    let mut changed = 0;
+    let mut revised = 0;
+    let mut revision_bytes = 0;
+    let mut dropped: BTreeMap<String, usize> = BTreeMap::new();
+    let mut failures = Vec::new();
+    let mut sampled = Vec::new();
+    for row in rows.iter().filter(|row| !row.deleted) {
+        entries += 1;
+        let before = row.fields.as_object().cloned().unwrap_or_default();
+        let mut after = before.clone();
+        if rebuilds_tables {
+            after.retain(|field, _| new_fields.contains_key(field));
+        }
+        match plan.apply(&mut after, &before) {
+            Ok(true) => {
+                revised += 1;
+                // The entry row and its migration revision.
+                revision_bytes += 2 * json_size(&Value::Object(after.clone()));
+            }
+            Ok(false) => {}
+            Err(err) => {
+                failures.push(serde_json::json!({
+                    "entry_id": row.entry_id,
+                    "error": format!("{:#}", err),
+                }));
+                continue;
+            }
+        }
+        let changes = entry::diff_fields(&row.fields, &Value::Object(after.clone()));
+        if changes.is_empty() {
+            continue;
+        }
+        changed += 1;
+        for change in &changes {
+            let had = before.get(&change.field).is_some_and(|v| !v.is_null());
+            let has = after.get(&change.field).is_some_and(|v| !v.is_null());
+            if had && !has {
+                *dropped.entry(change.field.clone()).or_default() += 1;
+            }
+        }
+        if sampled.len() < samples {
+            let pick = |fields: &Map<String, Value>| -> Map<String, Value> {
+                changes
+                    .iter()
+                    .map(|change| {
+                        let value = fields.get(&change.field).cloned();
+                        (change.field.clone(), value.unwrap_or(Value::Null))
+                    })
+                    .collect()
+            };
+            sampled.push(serde_json::json!({
+                "entry_id": row.entry_id,
+                "before": pick(&before),
+                "after": pick(&after),
+            }));
+        }
+    }
+
+    let source_def = plan.existing_def.as_ref().unwrap_or(normalized);
+    let added_fields: Vec<&String> = new_fields
+        .keys()
+        .filter(|field| field_type(source_def, field).is_none())
+        .collect();
+    let mut cast_fields = Vec::new();
+    for field in new_fields.keys() {
+        let (from, transform) = match plan.strategies.iter().find(|(name, _)| name == field) {
+            Some((_, FieldStrategy::Map { from, transform })) => (from.as_str(), transform),
+            Some(_) => continue,
+            None => (field.as_str(), &FieldTransform::Copy),
+        };
+        let Some(from_type) = field_type(source_def, from) else {
+            continue;
+        };
+        let to_type = field_type(normalized, field).unwrap_or_default();
+        if from_type == to_type && *transform == FieldTransform::Copy {
+            continue;
+        }
+        let with_values = rows
+            .iter()
+            .filter(|row| !row.deleted && row.fields.get(from).is_some_and(|v| !v.is_null()))
+            .count();
+        cast_fields.push(serde_json::json!({
+            "field": field,
+            "from": from,
+            "from_type": from_type,
+            "to_type": to_type,
+            "transform": transform.describe(),
+            "entries": with_values,
+        }));
+    }
+
+    let (mut entry_rows, mut revision_rows, mut estimated_bytes) =
+        (revised, revised, revision_bytes);
+    if let Some(existing_def) = plan.existing_def.as_ref().filter(|_| rebuilds_tables) {
+        let revisions =
+            entry::list_form_revision_rows(op, ws_path, form_name, existing_def).await?;
+        entry_rows += rows.len();
+        revision_rows += revisions.len();
+        estimated_bytes += rows.iter().map(|row| json_size(&row.fields)).sum::<usize>();
+        estimated_bytes += revisions
+            .iter()
+            .map(|revision| json_size(&revision.fields))
+            .sum::<usize>();
+    }
+
+    Ok(serde_json::json!({
+        "form": form_name,
+        "dry_run": true,
+        "entries": entries,
+        "changed": changed,
+        "revised": revised,
+        "added_fields": added_fields,
+        "dropped_fields": dropped,
+        "cast_fields": cast_fields,
+        "failures": failures,
+        "samples": sampled,
+        "rewrite": {
+            "tables_rebuilt": rebuilds_tables,
+            "entry_rows": entry_rows,
+            "revision_rows": revision_rows,
+            "estimated_bytes": estimated_bytes,
+        },
+    }))
+}
+
 pub(crate) async fn list_form_names(op: &Operator, ws_path: &str) -> Result<Vec<String>> {
     iceberg_store::list_form_names(op, ws_path).await
 }
@@ -276,8 +1112,8 @@ fn normalize_form_definition_with_options(
         .get("version")
         .and_then(|v| v.as_i64())
         .unwrap_or(1);
-    let fields = normalize_form_fields(form_def.get("fields"));
-    if let Some(field_map) = fields.as_object() {
+    let mut fields = normalize_form_fields(form_def.get("fields"));
+    if let Some(field_map) = fields.as_object_mut() {
         for name in field_map.keys() {
             if is_reserved_metadata_column(name) {
                 return Err(anyhow!(
@@ -287,6 +1123,21 @@ fn normalize_form_definition_with_options(
             }
         }
         validate_row_reference_field_defs(field_map)?;
+        normalize_decimal_field_defs(field_map)?;
+        validate_field_help_defs(field_map)?;
+        validate_field_layout_defs(field_map)?;
+        validate_field_access_defs(field_map)?;
+    }
+    let rollups = rollup::rollup_defs(form_def)?;
+    let hook_names = hooks::hook_names(form_def)?;
+    if let Some(name) = rollups
+        .keys()
+        .find(|name| fields.get(name.as_str()).is_some())
+    {
+        return Err(anyhow!(
+            "Rollup '{}' conflicts with a field of the same name",
+            name
+        ));
     }
     let allow_extra_attributes = form_def
         .get("allow_extra_attributes")
@@ -302,12 +1153,207 @@ fn normalize_form_definition_with_options(
         ));
     }
 
-    Ok(serde_json::json!({
+    let mut normalized = serde_json::json!({
         "name": name,
         "version": version,
         "fields": fields,
         "allow_extra_attributes": allow_extra_attributes,
-    }))
+    });
+    if !rollups.is_empty() {
+        normalized["rollups"] = serde_json::to_value(&rollups)?;
+    }
+    if !hook_names.is_empty() {
+        normalized["hooks"] = serde_json::to_value(&hook_names)?;
+    }
+    Ok(normalized)
+}
+
+/// Checks the `description`, `deprecated` and `replaced_by` field metadata.
+/// `replaced_by` must name another field of the form.
+fn validate_field_help_defs(field_map: &Map<String, Value>) -> Result<()> {
+    for (name, def) in field_map {
+        if def.get("description").is_some_and(|v| !v.is_string()) {
+            return Err(anyhow!("Field '{}' description must be a string", name));
+        }
+        if def.get("deprecated").is_some_and(|v| !v.is_boolean()) {
+            return Err(anyhow!("Field '{}' deprecated must be true or false", name));
+        }
+        let Some(replaced_by) = def.get("replaced_by") else {
+            continue;
+        };
+        let valid = replaced_by
+            .as_str()
+            .is_some_and(|target| target != name && field_map.contains_key(target));
+        if !valid {
+            return Err(anyhow!(
+                "Field '{}' replaced_by must name another field of the form",
+                name
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Checks the `order` (an integer), `group` (a non-empty name) and `heavy`
+/// (a boolean) field metadata that lays out rendered entries and listings.
+fn validate_field_layout_defs(field_map: &Map<String, Value>) -> Result<()> {
+    for (name, def) in field_map {
+        if def.get("order").is_some_and(|v| v.as_i64().is_none()) {
+            return Err(anyhow!("Field '{}' order must be an integer", name));
+        }
+        if def.get("heavy").is_some_and(|v| !v.is_boolean()) {
+            return Err(anyhow!("Field '{}' heavy must be true or false", name));
+        }
+        let valid_group = def.get("group").is_none_or(|v| {
+            v.as_str()
+                .is_some_and(|g| !g.trim().is_empty() && !g.contains('\n'))
+        });
+        if !valid_group {
+            return Err(anyhow!("Field '{}' group must be a non-empty name", name));
+        }
+    }
+    Ok(())
+}
+
+/// Fields marked `heavy`, which entry listings leave out unless asked for.
+pub(crate) fn heavy_field_names(form_def: &Value) -> HashSet<String> {
+    form_def
+        .get("fields")
+        .and_then(Value::as_object)
+        .map(|defs| {
+            defs.iter()
+                .filter(|(_, def)| def.get("heavy").and_then(Value::as_bool) == Some(true))
+                .map(|(name, _)| name.clone())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Checks the `read_only_after_create` (a boolean) and `write_capabilities`
+/// (a non-empty list of capability names) field metadata.
+fn validate_field_access_defs(field_map: &Map<String, Value>) -> Result<()> {
+    for (name, def) in field_map {
+        if def
+            .get("read_only_after_create")
+            .is_some_and(|v| !v.is_boolean())
+        {
+            return Err(anyhow!(
+                "Field '{}' read_only_after_create must be true or false",
+                name
+            ));
+        }
+        let valid_capabilities = def.get("write_capabilities").is_none_or(|v| {
+            v.as_array().is_some_and(|items| {
+                !items.is_empty()
+                    && items
+                        .iter()
+                        .all(|item| item.as_str().is_some_and(|c| !c.trim().is_empty()))
+            })
+        });
+        if !valid_capabilities {
+            return Err(anyhow!(
+                "Field '{}' write_capabilities must be a non-empty list of names",
+                name
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Fails an update that changes a protected field away from its `previous`
+/// value: a `read_only_after_create` field, or one whose
+/// `write_capabilities` the writer holds none of. The error is coded and
+/// names the first such field.
+pub(crate) fn check_protected_fields(
+    form_def: &Value,
+    previous: &Value,
+    fields: &Map<String, Value>,
+    capabilities: &[String],
+) -> Result<()> {
+    let Some(defs) = form_def.get("fields").and_then(Value::as_object) else {
+        return Ok(());
+    };
+    for (name, def) in defs {
+        let before = previous.get(name).filter(|v| !v.is_null());
+        let after = fields.get(name).filter(|v| !v.is_null());
+        if before == after {
+            continue;
+        }
+        if def.get("read_only_after_create").and_then(Value::as_bool) == Some(true) {
+            return Err(i18n::error(
+                "read_only_field",
+                serde_json::json!({"field": name}),
+            ));
+        }
+        let Some(required) = def.get("write_capabilities").and_then(Value::as_array) else {
+            continue;
+        };
+        let held = required
+            .iter()
+            .filter_map(Value::as_str)
+            .any(|capability| capabilities.iter().any(|c| c == capability));
+        if !held {
+            return Err(i18n::error(
+                "field_requires_capability",
+                serde_json::json!({"field": name, "capabilities": required}),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Soft warnings for a write that sets or changes a deprecated field. Fields
+/// keeping their `previous` value are not reported.
+pub(crate) fn deprecated_field_warnings(
+    form_def: &Value,
+    previous: &Value,
+    fields: &Map<String, Value>,
+) -> Vec<Value> {
+    let Some(defs) = form_def.get("fields").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    let mut warnings = Vec::new();
+    for (name, def) in defs {
+        if def.get("deprecated").and_then(Value::as_bool) != Some(true) {
+            continue;
+        }
+        let Some(value) = fields.get(name).filter(|v| !v.is_null()) else {
+            continue;
+        };
+        if previous.get(name) == Some(value) {
+            continue;
+        }
+        warnings.push(match def.get("replaced_by").and_then(Value::as_str) {
+            Some(replaced_by) => i18n::warning(
+                "deprecated_field_replaced",
+                serde_json::json!({"field": name, "replaced_by": replaced_by}),
+            ),
+            None => i18n::warning("deprecated_field", serde_json::json!({"field": name})),
+        });
+    }
+    warnings
+}
+
+/// Rewrites decimal field types to the canonical `decimal(precision,scale)`
+/// and rejects ones a Decimal128 column cannot hold.
+fn normalize_decimal_field_defs(field_map: &mut Map<String, Value>) -> Result<()> {
+    for (name, def) in field_map.iter_mut() {
+        let Some(field_type) = def.get("type").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        if !field_type.trim().starts_with("decimal") {
+            continue;
+        }
+        let decimal = DecimalType::parse(field_type).ok_or_else(|| {
+            anyhow!(
+                "Field '{}' has invalid type '{}' (expected decimal(precision, scale) with precision 1-38 and scale <= precision)",
+                name,
+                field_type
+            )
+        })?;
+        def["type"] = Value::String(decimal.field_type());
+    }
+    Ok(())
 }
 
 fn validate_row_reference_field_defs(field_map: &Map<String, Value>) -> Result<()> {
@@ -424,12 +1470,17 @@ fn enrich_form_definition(form_def: &Value) -> Result<Value> {
 
 fn form_template_from_fields(form_name: &str, fields: Option<&Value>) -> String {
     let mut template = format!("# {}\n\n", form_name);
-    if let Some(Value::Object(map)) = fields {
-        let mut field_names: Vec<&String> = map.keys().collect();
-        field_names.sort();
-        for name in field_names {
+    if let Some(fields @ Value::Object(_)) = fields {
+        let layout = entry::FieldLayout::for_fields(Some(fields));
+        for name in &layout.ungrouped {
             template.push_str(&format!("## {}\n\n", name));
         }
+        for (group, names) in &layout.groups {
+            template.push_str(&format!("### {}\n\n", group));
+            for name in names {
+                template.push_str(&format!("## {}\n\n", name));
+            }
+        }
     }
     template
 }
@@ -445,18 +1496,7 @@ async fn rebuild_form_tables(
     let revision_rows =
         entry::list_form_revision_rows(op, ws_path, form_name, existing_def).await?;
 
-    iceberg_store::drop_form_tables(op, ws_path, form_name).await?;
-    iceberg_store::ensure_form_tables(op, ws_path, new_def).await?;
-
-    for row in entry_rows {
-        entry::write_entry_row(op, ws_path, form_name, &row.entry_id, &row).await?;
-    }
-
-    for rev in revision_rows {
-        entry::append_revision_row_for_form(op, ws_path, form_name, &rev, new_def).await?;
-    }
-
-    Ok(())
+    entry::rewrite_form_tables(op, ws_path, form_name, new_def, &entry_rows, &revision_rows).await
 }
 
 // Migration logic handled via Iceberg row updates.
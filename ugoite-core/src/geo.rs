@@ -0,0 +1,250 @@
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+/// Mean Earth radius used for great-circle distances.
+const EARTH_RADIUS_M: f64 = 6_371_008.8;
+
+/// A WGS84 position in degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoPoint {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+impl GeoPoint {
+    pub fn new(lat: f64, lon: f64) -> Option<Self> {
+        let valid = lat.is_finite()
+            && lon.is_finite()
+            && (-90.0..=90.0).contains(&lat)
+            && (-180.0..=180.0).contains(&lon);
+        valid.then_some(Self { lat, lon })
+    }
+
+    pub fn to_value(self) -> Value {
+        serde_json::json!({"lat": self.lat, "lon": self.lon})
+    }
+
+    /// Great-circle distance in meters (haversine).
+    pub fn distance_m(&self, other: &GeoPoint) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let d_lat = lat2 - lat1;
+        let d_lon = (other.lon - self.lon).to_radians();
+        let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_M * a.sqrt().min(1.0).asin()
+    }
+}
+
+fn number(value: Option<&Value>) -> Option<f64> {
+    match value? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Reads a `geopoint` value: `{"lat": .., "lon": ..}` (also as a JSON
+/// string, which is how it renders in Markdown) or `"lat, lon"`.
+pub fn parse_geopoint(value: &Value) -> Option<GeoPoint> {
+    match value {
+        Value::Object(map) => GeoPoint::new(number(map.get("lat"))?, number(map.get("lon"))?),
+        Value::String(s) => {
+            let s = s.trim();
+            if s.starts_with('{') {
+                return parse_geopoint(&serde_json::from_str(s).ok()?);
+            }
+            let (lat, lon) = s.split_once(',')?;
+            GeoPoint::new(lat.trim().parse().ok()?, lon.trim().parse().ok()?)
+        }
+        _ => None,
+    }
+}
+
+/// Nesting depth of `coordinates` below a position, per geometry type.
+fn coordinate_depth(geometry_type: &str) -> Option<usize> {
+    match geometry_type {
+        "Point" => Some(0),
+        "MultiPoint" | "LineString" => Some(1),
+        "MultiLineString" | "Polygon" => Some(2),
+        "MultiPolygon" => Some(3),
+        _ => None,
+    }
+}
+
+fn collect_positions(coordinates: &Value, depth: usize, out: &mut Vec<GeoPoint>) -> bool {
+    let Some(items) = coordinates.as_array() else {
+        return false;
+    };
+    if depth == 0 {
+        // GeoJSON positions are [lon, lat, optional altitude].
+        let point = match items.as_slice() {
+            [lon, lat] | [lon, lat, _] => lon
+                .as_f64()
+                .zip(lat.as_f64())
+                .and_then(|(lon, lat)| GeoPoint::new(lat, lon)),
+            _ => None,
+        };
+        return match point {
+            Some(point) => {
+                out.push(point);
+                true
+            }
+            None => false,
+        };
+    }
+    items
+        .iter()
+        .all(|item| collect_positions(item, depth - 1, out))
+}
+
+/// Validates a GeoJSON object and collects its positions. A feature with a
+/// null geometry has none.
+fn geojson_positions(value: &Value, out: &mut Vec<GeoPoint>) -> bool {
+    let Some(kind) = value.get("type").and_then(Value::as_str) else {
+        return false;
+    };
+    match kind {
+        "Feature" => match value.get("geometry") {
+            Some(Value::Null) => true,
+            Some(geometry) => geojson_positions(geometry, out),
+            None => false,
+        },
+        "FeatureCollection" => {
+            value
+                .get("features")
+                .and_then(Value::as_array)
+                .is_some_and(|features| {
+                    features.iter().all(|feature| {
+                        feature.get("type").and_then(Value::as_str) == Some("Feature")
+                            && geojson_positions(feature, out)
+                    })
+                })
+        }
+        "GeometryCollection" => value
+            .get("geometries")
+            .and_then(Value::as_array)
+            .is_some_and(|geometries| {
+                geometries
+                    .iter()
+                    .all(|geometry| geojson_positions(geometry, out))
+            }),
+        other => match (coordinate_depth(other), value.get("coordinates")) {
+            (Some(depth), Some(coordinates)) => collect_positions(coordinates, depth, out),
+            _ => false,
+        },
+    }
+}
+
+fn parse_geojson_value(value: &Value) -> Option<Value> {
+    match value {
+        Value::String(s) => serde_json::from_str(s.trim()).ok(),
+        Value::Object(_) => Some(value.clone()),
+        _ => None,
+    }
+}
+
+/// Validates a `geojson` value (a geometry, Feature or FeatureCollection,
+/// as an object or JSON text) and returns it as compact JSON text.
+pub fn normalize_geojson(value: &Value) -> Option<String> {
+    let parsed = parse_geojson_value(value)?;
+    geojson_positions(&parsed, &mut Vec::new()).then(|| parsed.to_string())
+}
+
+/// The positions a stored `geopoint` or `geojson` value covers.
+fn value_positions(value: &Value) -> Vec<GeoPoint> {
+    if let Some(point) = parse_geopoint(value) {
+        return vec![point];
+    }
+    let mut positions = Vec::new();
+    if let Some(parsed) = parse_geojson_value(value) {
+        if !geojson_positions(&parsed, &mut positions) {
+            positions.clear();
+        }
+    }
+    positions
+}
+
+/// A geographic filter in a `query_index` filter object.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeoFilter {
+    /// `{"$bbox": [min_lon, min_lat, max_lon, max_lat]}`, in GeoJSON bbox
+    /// order. A box whose `min_lon` exceeds its `max_lon` crosses the
+    /// antimeridian.
+    BoundingBox {
+        min_lon: f64,
+        min_lat: f64,
+        max_lon: f64,
+        max_lat: f64,
+    },
+    /// `{"$near": {"lat": .., "lon": .., "radius_m": ..}}`.
+    Radius { center: GeoPoint, radius_m: f64 },
+}
+
+impl GeoFilter {
+    /// Reads a filter operator object, or `None` when it holds no geo
+    /// operator.
+    pub fn parse(expected: &Value) -> Result<Option<Self>> {
+        if let Some(bbox) = expected.get("$bbox") {
+            let corners: Vec<f64> = bbox
+                .as_array()
+                .map(|items| items.iter().filter_map(Value::as_f64).collect())
+                .unwrap_or_default();
+            let [min_lon, min_lat, max_lon, max_lat] = corners[..] else {
+                return Err(anyhow!(
+                    "$bbox must be [min_lon, min_lat, max_lon, max_lat]"
+                ));
+            };
+            if GeoPoint::new(min_lat, min_lon).is_none()
+                || GeoPoint::new(max_lat, max_lon).is_none()
+                || min_lat > max_lat
+            {
+                return Err(anyhow!("$bbox corners are out of range: {}", bbox));
+            }
+            return Ok(Some(Self::BoundingBox {
+                min_lon,
+                min_lat,
+                max_lon,
+                max_lat,
+            }));
+        }
+        if let Some(near) = expected.get("$near") {
+            let center = GeoPoint::new(
+                number(near.get("lat")).unwrap_or(f64::NAN),
+                number(near.get("lon")).unwrap_or(f64::NAN),
+            )
+            .ok_or_else(|| anyhow!("$near needs a valid lat and lon: {}", near))?;
+            let radius_m = number(near.get("radius_m"))
+                .filter(|radius| radius.is_finite() && *radius >= 0.0)
+                .ok_or_else(|| anyhow!("$near needs a non-negative radius_m: {}", near))?;
+            return Ok(Some(Self::Radius { center, radius_m }));
+        }
+        Ok(None)
+    }
+
+    fn contains(&self, point: &GeoPoint) -> bool {
+        match self {
+            Self::BoundingBox {
+                min_lon,
+                min_lat,
+                max_lon,
+                max_lat,
+            } => {
+                let lon_inside = if min_lon <= max_lon {
+                    (*min_lon..=*max_lon).contains(&point.lon)
+                } else {
+                    point.lon >= *min_lon || point.lon <= *max_lon
+                };
+                lon_inside && (*min_lat..=*max_lat).contains(&point.lat)
+            }
+            Self::Radius { center, radius_m } => center.distance_m(point) <= *radius_m,
+        }
+    }
+
+    /// Whether a stored `geopoint` or `geojson` value falls in the filter.
+    /// Shapes match when any of their positions does, so a polygon that
+    /// only passes through the area without a vertex in it is missed.
+    pub fn matches(&self, value: &Value) -> bool {
+        value_positions(value)
+            .iter()
+            .any(|point| self.contains(point))
+    }
+}
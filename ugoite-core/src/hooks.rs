@@ -0,0 +1,164 @@
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, OnceLock};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteEvent {
+    Create,
+    Update,
+    Restore,
+}
+
+impl WriteEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Update => "update",
+            Self::Restore => "restore",
+        }
+    }
+}
+
+/// What a hook sees about the write in progress.
+#[derive(Debug, Clone, Copy)]
+pub struct WriteContext<'a> {
+    pub ws_path: &'a str,
+    pub form_name: &'a str,
+    pub entry_id: &'a str,
+    pub event: WriteEvent,
+    pub author: &'a str,
+}
+
+/// Lifecycle hook for entries of one form.
+///
+/// Hooks are host code registered per space and form with
+/// [`register_form_hook`], and only run for forms whose definition lists
+/// them in `hooks`. Field values a hook sets are validated against the form
+/// like any other write.
+pub trait FormHook: Send + Sync {
+    /// Runs after validation and before anything is written. May mutate the
+    /// entry's form fields, or reject the write with a validation message.
+    fn before_write(
+        &self,
+        _ctx: &WriteContext,
+        _fields: &mut Map<String, Value>,
+    ) -> std::result::Result<(), String> {
+        Ok(())
+    }
+
+    /// Runs once the entry row and its revision are committed.
+    fn after_write(&self, _ctx: &WriteContext, _revision_id: &str, _fields: &Value) {}
+}
+
+/// Hooks by (space path, form name), then by hook name.
+type HookRegistry = HashMap<(String, String), HashMap<String, Arc<dyn FormHook>>>;
+
+fn registry() -> &'static Mutex<HookRegistry> {
+    static REGISTRY: OnceLock<Mutex<HookRegistry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// The hook names a form definition declares in `hooks`, in run order.
+pub fn hook_names(form_def: &Value) -> Result<Vec<String>> {
+    let names: Vec<String> = match form_def.get("hooks") {
+        None | Some(Value::Null) => Vec::new(),
+        Some(value) => serde_json::from_value(value.clone())
+            .map_err(|_| anyhow!("Form hooks must be a list of hook names"))?,
+    };
+    let mut seen = HashSet::new();
+    for name in &names {
+        if name.trim().is_empty() {
+            return Err(anyhow!("Form hook names must not be empty"));
+        }
+        if !seen.insert(name) {
+            return Err(anyhow!("Form hook '{}' is listed twice", name));
+        }
+    }
+    Ok(names)
+}
+
+/// Registers `hook` as `name` for `form_name` in the space at `ws_path`,
+/// replacing a hook already registered under that name. It runs only while
+/// the form definition lists `name`.
+pub fn register_form_hook(
+    ws_path: &str,
+    form_name: &str,
+    name: &str,
+    hook: Arc<dyn FormHook>,
+) -> Result<()> {
+    registry()
+        .lock()
+        .map_err(|_| anyhow!("hook registry lock poisoned"))?
+        .entry((ws_path.to_string(), form_name.to_string()))
+        .or_default()
+        .insert(name.to_string(), hook);
+    Ok(())
+}
+
+/// Removes every hook registered for `form_name` in the space at `ws_path`.
+pub fn clear_form_hooks(ws_path: &str, form_name: &str) -> Result<()> {
+    registry()
+        .lock()
+        .map_err(|_| anyhow!("hook registry lock poisoned"))?
+        .remove(&(ws_path.to_string(), form_name.to_string()));
+    Ok(())
+}
+
+/// The hooks `form_def` declares, in order. A declared hook that is not
+/// registered fails the write rather than letting it skip the hook.
+fn hooks_for(ctx: &WriteContext, form_def: &Value) -> Result<Vec<Arc<dyn FormHook>>> {
+    let names = hook_names(form_def)?;
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+    let registry = registry()
+        .lock()
+        .map_err(|_| anyhow!("hook registry lock poisoned"))?;
+    let registered = registry.get(&(ctx.ws_path.to_string(), ctx.form_name.to_string()));
+    names
+        .iter()
+        .map(|name| {
+            registered
+                .and_then(|hooks| hooks.get(name))
+                .cloned()
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Form {} declares hook '{}', which is not registered",
+                        ctx.form_name,
+                        name
+                    )
+                })
+        })
+        .collect()
+}
+
+/// Runs the before-write hooks, returning whether any of them changed `fields`.
+pub(crate) fn run_before_write(
+    ctx: &WriteContext,
+    form_def: &Value,
+    fields: &mut Map<String, Value>,
+) -> Result<bool> {
+    let hooks = hooks_for(ctx, form_def)?;
+    if hooks.is_empty() {
+        return Ok(false);
+    }
+    let original = fields.clone();
+    for hook in hooks {
+        hook.before_write(ctx, fields)
+            .map_err(|message| anyhow!("Rejected by {} hook: {}", ctx.form_name, message))?;
+    }
+    Ok(*fields != original)
+}
+
+pub(crate) fn run_after_write(
+    ctx: &WriteContext,
+    form_def: &Value,
+    revision_id: &str,
+    fields: &Value,
+) -> Result<()> {
+    for hook in hooks_for(ctx, form_def)? {
+        hook.after_write(ctx, revision_id, fields);
+    }
+    Ok(())
+}
@@ -0,0 +1,187 @@
+use anyhow::{anyhow, Result};
+use opendal::Operator;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::space;
+
+/// Message templates by code: English, then Japanese. `{name}` is replaced
+/// by the `name` parameter.
+const CATALOG: &[(&str, &str, &str)] = &[
+    (
+        "missing_field",
+        "Missing required field: {field}",
+        "必須フィールドがありません: {field}",
+    ),
+    (
+        "invalid_type",
+        "Field '{field}' has invalid type",
+        "フィールド「{field}」の型が正しくありません",
+    ),
+    (
+        "form_validation_failed",
+        "Form validation failed: {warnings}",
+        "フォームの検証に失敗しました: {warnings}",
+    ),
+    (
+        "unknown_form_fields",
+        "Unknown form fields: {fields}",
+        "フォームにないフィールドがあります: {fields}",
+    ),
+    (
+        "revision_conflict",
+        "Revision conflict: expected {expected}, got {actual}",
+        "リビジョンが競合しています: 期待値 {expected}、現在 {actual}",
+    ),
+    (
+        "deprecated_field",
+        "Field '{field}' is deprecated",
+        "フィールド「{field}」は非推奨です",
+    ),
+    (
+        "deprecated_field_replaced",
+        "Field '{field}' is deprecated; use '{replaced_by}' instead",
+        "フィールド「{field}」は非推奨です。代わりに「{replaced_by}」を使ってください",
+    ),
+    (
+        "read_only_field",
+        "Field '{field}' cannot be changed after the entry is created",
+        "フィールド「{field}」はエントリ作成後に変更できません",
+    ),
+    (
+        "field_requires_capability",
+        "Changing field '{field}' requires one of: {capabilities}",
+        "フィールド「{field}」の変更には次のいずれかの権限が必要です: {capabilities}",
+    ),
+    (
+        "entry_not_found",
+        "Entry not found: {entry_id}",
+        "エントリが見つかりません: {entry_id}",
+    ),
+];
+
+/// `locale` in settings.json: the language of localized messages.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Ja,
+}
+
+impl std::str::FromStr for Locale {
+    type Err = anyhow::Error;
+
+    /// Accepts a language tag; the region is ignored (`ja-JP` is `ja`).
+    fn from_str(value: &str) -> Result<Self> {
+        let language = value.split(['-', '_']).next().unwrap_or_default();
+        match language.to_ascii_lowercase().as_str() {
+            "en" => Ok(Self::En),
+            "ja" => Ok(Self::Ja),
+            _ => Err(anyhow!("Unsupported locale: {}", value)),
+        }
+    }
+}
+
+impl Locale {
+    pub async fn for_space(op: &Operator, ws_path: &str) -> Result<Self> {
+        let settings = space::read_space_settings(op, ws_path).await?;
+        match settings.get("locale").and_then(Value::as_str) {
+            Some(tag) => tag.parse(),
+            None => Ok(Self::default()),
+        }
+    }
+}
+
+/// An error with a message code and parameters. It displays in English so
+/// callers matching on error text keep working; [`localize_error`] renders
+/// it in another locale.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{message}")]
+pub struct MessageError {
+    pub code: String,
+    pub params: Value,
+    message: String,
+}
+
+/// Builds a coded error from `params`, an object of template parameters.
+pub fn error(code: &str, params: Value) -> anyhow::Error {
+    MessageError {
+        code: code.to_string(),
+        message: translate(Locale::En, code, &params).unwrap_or_else(|| code.to_string()),
+        params,
+    }
+    .into()
+}
+
+/// Builds a validation warning: `code`, `params`, the English `message`, and
+/// `field` when the warning is about one.
+pub fn warning(code: &str, params: Value) -> Value {
+    let mut warning = Map::new();
+    warning.insert("code".to_string(), Value::String(code.to_string()));
+    if let Some(field) = params.get("field") {
+        warning.insert("field".to_string(), field.clone());
+    }
+    let message = translate(Locale::En, code, &params).unwrap_or_else(|| code.to_string());
+    warning.insert("message".to_string(), Value::String(message));
+    warning.insert("params".to_string(), params);
+    Value::Object(warning)
+}
+
+fn is_warning_list(items: &[Value]) -> bool {
+    !items.is_empty() && items.iter().all(|item| item.get("code").is_some())
+}
+
+fn render_param(value: &Value, locale: Locale) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Array(items) if is_warning_list(items) => {
+            Value::Array(localize_warnings(items, locale)).to_string()
+        }
+        Value::Array(items) if items.iter().all(Value::is_string) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(", "),
+        other => other.to_string(),
+    }
+}
+
+/// Renders the message for `code` in `locale`, or `None` for unknown codes.
+pub fn translate(locale: Locale, code: &str, params: &Value) -> Option<String> {
+    let (_, en, ja) = CATALOG.iter().find(|(known, _, _)| *known == code)?;
+    let mut message = match locale {
+        Locale::En => en.to_string(),
+        Locale::Ja => ja.to_string(),
+    };
+    if let Some(params) = params.as_object() {
+        for (name, value) in params {
+            message = message.replace(&format!("{{{}}}", name), &render_param(value, locale));
+        }
+    }
+    Some(message)
+}
+
+/// Rewrites the `message` of each coded warning in `locale`. Warnings with
+/// unknown codes are returned unchanged.
+pub fn localize_warnings(warnings: &[Value], locale: Locale) -> Vec<Value> {
+    warnings
+        .iter()
+        .map(|warning| {
+            let mut warning = warning.clone();
+            let code = warning.get("code").and_then(Value::as_str);
+            let params = warning.get("params").cloned().unwrap_or(Value::Null);
+            if let Some(message) = code.and_then(|code| translate(locale, code, &params)) {
+                warning["message"] = Value::String(message);
+            }
+            warning
+        })
+        .collect()
+}
+
+/// The message of `err` in `locale`; errors without a code keep their text.
+pub fn localize_error(err: &anyhow::Error, locale: Locale) -> String {
+    err.downcast_ref::<MessageError>()
+        .and_then(|coded| translate(locale, &coded.code, &coded.params))
+        .unwrap_or_else(|| err.to_string())
+}
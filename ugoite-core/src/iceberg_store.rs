@@ -1,8 +1,9 @@
 use anyhow::{anyhow, Result};
 use futures::TryStreamExt;
+use iceberg::io::S3_ENDPOINT;
 use iceberg::memory::{MemoryCatalogBuilder, MEMORY_CATALOG_WAREHOUSE};
 use iceberg::spec::{ListType, NestedField, Schema, StructType, Type, UnboundPartitionSpec};
-use iceberg::spec::{PrimitiveType, SortOrder};
+use iceberg::spec::{ManifestContentType, ManifestStatus, Operation, PrimitiveType, SortOrder};
 use iceberg::transaction::{ApplyTransactionAction, Transaction};
 use iceberg::{Catalog, CatalogBuilder, MemoryCatalog, NamespaceIdent, TableCreation, TableIdent};
 use opendal::{options, Operator};
@@ -10,10 +11,16 @@ use serde_json::Value;
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex, OnceLock};
 
-const ENTRIES_TABLE_NAME: &str = "entries";
-const REVISIONS_TABLE_NAME: &str = "revisions";
+use crate::decimal::DecimalType;
+use crate::storage::{self, WriteCondition};
+
+pub(crate) const ENTRIES_TABLE_NAME: &str = "entries";
+pub(crate) const REVISIONS_TABLE_NAME: &str = "revisions";
 const FORM_DEF_PROP: &str = "ugoite.form_definition";
 const FORM_VERSION_PROP: &str = "ugoite.form_version";
+const CATALOG_MANIFEST_FILE: &str = "catalog.json";
+const CATALOG_MANIFEST_VERSION: u32 = 1;
+const CATALOG_MANIFEST_RETRIES: usize = 3;
 
 static CATALOG_CACHE: OnceLock<Mutex<HashMap<String, Arc<MemoryCatalog>>>> = OnceLock::new();
 fn catalog_cache() -> &'static Mutex<HashMap<String, Arc<MemoryCatalog>>> {
@@ -54,10 +61,29 @@ fn warehouse_uri(op: &Operator, ws_path: &str) -> Result<String> {
     let prefix = scheme_to_uri_prefix(scheme);
     let root = normalize_root(op.info().root().as_str());
     let ws_path = ws_path.trim_start_matches('/');
+    if matches!(scheme, "s3" | "gcs" | "gs" | "oss") {
+        // Object store URIs carry the bucket as their authority.
+        let bucket = op.info().name();
+        let warehouse_path = format!("{}/{}", root.trim_end_matches('/'), ws_path);
+        return Ok(format!("{}{}{}/forms", prefix, bucket, warehouse_path));
+    }
     let warehouse_path = format!("{}/{}", root, ws_path);
     Ok(format!("{}{}{}", prefix, warehouse_path, "/forms"))
 }
 
+/// FileIO properties for the catalog. Iceberg's S3 IO loads credentials and
+/// region from the standard AWS environment, but not a custom endpoint, so
+/// `AWS_ENDPOINT_URL` (MinIO and other S3-compatible stores) is forwarded.
+fn file_io_props(op: &Operator) -> HashMap<String, String> {
+    let mut props = HashMap::new();
+    if op.info().scheme() == "s3" {
+        if let Ok(endpoint) = std::env::var("AWS_ENDPOINT_URL") {
+            props.insert(S3_ENDPOINT.to_string(), endpoint);
+        }
+    }
+    props
+}
+
 fn table_location(warehouse: &str, form_name: &str, table_name: &str) -> String {
     format!(
         "{}/{}/{}",
@@ -191,36 +217,251 @@ async fn list_form_dirs(op: &Operator, ws_path: &str) -> Result<Vec<String>> {
     Ok(names)
 }
 
-async fn register_existing_tables(
+fn catalog_manifest_path(ws_path: &str) -> String {
+    format!(
+        "{}/{}",
+        ws_path.trim_end_matches('/'),
+        CATALOG_MANIFEST_FILE
+    )
+}
+
+fn catalog_manifest_key(form_name: &str, table_name: &str) -> String {
+    format!("{}/{}", form_name, table_name)
+}
+
+/// Reads the per-space catalog manifest (`"Form/table"` -> metadata location)
+/// together with the condition that guards rewriting it.
+///
+/// Returns `None` when the manifest is missing or unreadable so callers fall
+/// back to discovering tables from the metadata directories.
+async fn read_catalog_manifest(
     op: &Operator,
     ws_path: &str,
-    catalog: &MemoryCatalog,
+) -> Result<Option<(HashMap<String, String>, WriteCondition)>> {
+    let (value, condition) =
+        storage::read_json_versioned(op, &catalog_manifest_path(ws_path)).await?;
+    let Some(tables) = value
+        .as_ref()
+        .and_then(|v| v.get("tables"))
+        .and_then(|v| v.as_object())
+    else {
+        return Ok(None);
+    };
+    let tables = tables
+        .iter()
+        .filter_map(|(key, location)| {
+            location
+                .as_str()
+                .map(|location| (key.clone(), location.to_string()))
+        })
+        .collect();
+    Ok(Some((tables, condition)))
+}
+
+/// Metadata location of every table in the catalog manifest, keyed
+/// `"Form/table"`; `None` when the space has no manifest. Each commit writes a
+/// new metadata file, so equal locations mean equal table versions.
+pub async fn catalog_table_locations(
+    op: &Operator,
+    ws_path: &str,
+) -> Result<Option<HashMap<String, String>>> {
+    Ok(read_catalog_manifest(op, ws_path)
+        .await?
+        .map(|(tables, _)| tables))
+}
+
+async fn write_catalog_manifest(
+    op: &Operator,
+    ws_path: &str,
+    tables: &HashMap<String, String>,
+    condition: &WriteCondition,
 ) -> Result<()> {
-    let form_names = list_form_dirs(op, ws_path).await?;
-    if form_names.is_empty() {
-        return Ok(());
+    let manifest = serde_json::json!({
+        "version": CATALOG_MANIFEST_VERSION,
+        "tables": tables,
+    });
+    storage::write_json_conditional(op, &catalog_manifest_path(ws_path), &manifest, condition).await
+}
+
+/// Applies `update` to the catalog manifest, re-reading and retrying when a
+/// concurrent writer changed it in between. A space without a manifest gets
+/// one here, starting from the tables found in the metadata directories.
+async fn update_catalog_manifest<F>(op: &Operator, ws_path: &str, update: F) -> Result<()>
+where
+    F: Fn(&mut HashMap<String, String>) -> bool,
+{
+    let mut attempt = 0;
+    loop {
+        let (mut tables, condition) = match read_catalog_manifest(op, ws_path).await? {
+            Some(manifest) => manifest,
+            None => (
+                discover_table_locations(op, ws_path).await?,
+                WriteCondition::Absent,
+            ),
+        };
+        // The discovered tables may already match; the manifest is still new.
+        if !update(&mut tables) && condition != WriteCondition::Absent {
+            return Ok(());
+        }
+        match write_catalog_manifest(op, ws_path, &tables, &condition).await {
+            Err(err) if storage::is_write_conflict(&err) && attempt < CATALOG_MANIFEST_RETRIES => {
+                storage::record_contention_at(ws_path, storage::ContentionEvent::CommitRetry);
+                attempt += 1;
+            }
+            Err(err) if storage::is_write_conflict(&err) => {
+                storage::record_contention_at(ws_path, storage::ContentionEvent::RetriesExhausted);
+                return Err(err);
+            }
+            result => return result,
+        }
     }
+}
 
-    let warehouse = warehouse_uri(op, ws_path)?;
-    for form_name in form_names {
-        let namespace = form_namespace(&form_name);
-        if !catalog.namespace_exists(&namespace).await? {
-            if let Err(err) = catalog.create_namespace(&namespace, HashMap::new()).await {
-                let message = err.to_string();
-                if !message.contains("NamespaceAlreadyExists")
-                    && !message.to_lowercase().contains("already exists")
-                {
-                    return Err(err.into());
+/// Points the catalog manifest at a table's current metadata file.
+/// Called after every commit.
+pub(crate) async fn record_table_location(
+    op: &Operator,
+    ws_path: &str,
+    table: &iceberg::table::Table,
+) -> Result<()> {
+    let Some(location) = table.metadata_location() else {
+        return Ok(());
+    };
+    let ident = table.identifier();
+    let Some(form_name) = ident.namespace().as_ref().first() else {
+        return Ok(());
+    };
+    let key = catalog_manifest_key(form_name, ident.name());
+    tracing::debug!(table = %key, location, "committed table");
+    update_catalog_manifest(op, ws_path, |tables| {
+        if tables.get(&key).map(String::as_str) == Some(location) {
+            return false;
+        }
+        tables.insert(key.clone(), location.to_string());
+        true
+    })
+    .await
+}
+
+/// Points the tables in `written` back at their locations in `before`, as
+/// read earlier with [`catalog_table_locations`], and forgets those created
+/// since. `written` holds the location each table was left at by the
+/// caller's own commits; other tables are not touched. When another writer
+/// committed to one of them in between, nothing is restored and the error
+/// names the table. Returns the forms whose tables changed.
+pub(crate) async fn restore_table_locations(
+    op: &Operator,
+    ws_path: &str,
+    before: &HashMap<String, String>,
+    written: &HashMap<String, String>,
+) -> Result<Vec<String>> {
+    let moved = Mutex::new(None);
+    update_catalog_manifest(op, ws_path, |tables| {
+        let foreign = written
+            .iter()
+            .find(|(key, location)| tables.get(*key) != Some(*location))
+            .map(|(key, _)| key.clone());
+        let abort = foreign.is_some();
+        *moved.lock().unwrap_or_else(|e| e.into_inner()) = foreign;
+        if abort {
+            return false;
+        }
+        let mut changed = false;
+        for key in written.keys() {
+            changed |= match before.get(key) {
+                Some(location) => {
+                    tables.insert(key.clone(), location.clone()).as_ref() != Some(location)
                 }
-            }
+                None => tables.remove(key).is_some(),
+            };
         }
+        changed
+    })
+    .await?;
+    if let Some(key) = moved.into_inner().unwrap_or_else(|e| e.into_inner()) {
+        return Err(anyhow!(
+            "Table {} was changed by another writer; not restoring it",
+            key
+        ));
+    }
+    let mut changed: Vec<String> = written
+        .iter()
+        .filter(|(key, location)| before.get(*key) != Some(*location))
+        .filter_map(|(key, _)| key.split_once('/').map(|(form, _)| form.to_string()))
+        .collect();
+    changed.sort();
+    changed.dedup();
+    if !changed.is_empty() {
+        evict_catalog_cache(op, ws_path)?;
+    }
+    Ok(changed)
+}
 
-        for table_name in [ENTRIES_TABLE_NAME, REVISIONS_TABLE_NAME] {
-            let table_ident = TableIdent::new(namespace.clone(), table_name.to_string());
-            if catalog.table_exists(&table_ident).await? {
-                continue;
+/// `Form/table` for a Form table, as keyed in the catalog manifest.
+pub(crate) fn table_key(table: &iceberg::table::Table) -> String {
+    let ident = table.identifier();
+    let form_name = ident
+        .namespace()
+        .as_ref()
+        .first()
+        .map(String::as_str)
+        .unwrap_or_default();
+    catalog_manifest_key(form_name, ident.name())
+}
+
+async fn forget_form_locations(op: &Operator, ws_path: &str, form_name: &str) -> Result<()> {
+    let prefix = format!("{}/", form_name);
+    update_catalog_manifest(op, ws_path, |tables| {
+        let before = tables.len();
+        tables.retain(|key, _| !key.starts_with(&prefix));
+        tables.len() != before
+    })
+    .await
+}
+
+async fn ensure_namespace(catalog: &MemoryCatalog, namespace: &NamespaceIdent) -> Result<()> {
+    if !catalog.namespace_exists(namespace).await? {
+        if let Err(err) = catalog.create_namespace(namespace, HashMap::new()).await {
+            let message = err.to_string();
+            if !message.contains("NamespaceAlreadyExists")
+                && !message.to_lowercase().contains("already exists")
+            {
+                return Err(err.into());
             }
+        }
+    }
+    Ok(())
+}
 
+async fn register_from_manifest(
+    catalog: &MemoryCatalog,
+    tables: &HashMap<String, String>,
+) -> Result<()> {
+    for (key, location) in tables {
+        let Some((form_name, table_name)) = key.split_once('/') else {
+            continue;
+        };
+        let namespace = form_namespace(form_name);
+        ensure_namespace(catalog, &namespace).await?;
+        let table_ident = TableIdent::new(namespace, table_name.to_string());
+        if catalog.table_exists(&table_ident).await? {
+            continue;
+        }
+        catalog
+            .register_table(&table_ident, location.clone())
+            .await?;
+    }
+    Ok(())
+}
+
+/// Metadata location of every Form table, found by scanning the metadata
+/// directories for the latest metadata file. Used while a space has no
+/// catalog manifest.
+async fn discover_table_locations(op: &Operator, ws_path: &str) -> Result<HashMap<String, String>> {
+    let warehouse = warehouse_uri(op, ws_path)?;
+    let mut discovered = HashMap::new();
+    for form_name in list_form_dirs(op, ws_path).await? {
+        for table_name in [ENTRIES_TABLE_NAME, REVISIONS_TABLE_NAME] {
             let metadata_path = format!(
                 "{}/forms/{}/{}/metadata/",
                 ws_path.trim_end_matches('/'),
@@ -230,14 +471,35 @@ async fn register_existing_tables(
             let Some(latest) = latest_metadata_file(op, &metadata_path).await? else {
                 continue;
             };
-            let metadata_location = metadata_location(&warehouse, &form_name, table_name, &latest);
-            catalog
-                .register_table(&table_ident, metadata_location)
-                .await?;
+            discovered.insert(
+                catalog_manifest_key(&form_name, table_name),
+                metadata_location(&warehouse, &form_name, table_name, &latest),
+            );
         }
     }
+    Ok(discovered)
+}
 
-    Ok(())
+/// Registers the space's tables from the catalog manifest, or from the
+/// metadata directories while it has none. Only reads storage: the manifest
+/// is written by commits and [`ensure_catalog_manifest`].
+async fn register_existing_tables(
+    op: &Operator,
+    ws_path: &str,
+    catalog: &MemoryCatalog,
+) -> Result<()> {
+    let tables = match read_catalog_manifest(op, ws_path).await? {
+        Some((tables, _)) => tables,
+        None => discover_table_locations(op, ws_path).await?,
+    };
+    register_from_manifest(catalog, &tables).await
+}
+
+/// Drops the in-process catalog for a space so the next access rebuilds it
+/// from the catalog manifest.
+pub fn evict_catalog_cache(op: &Operator, ws_path: &str) -> Result<()> {
+    let warehouse = warehouse_uri(op, ws_path)?;
+    remove_catalog_cache(&warehouse)
 }
 
 async fn catalog_for_space(op: &Operator, ws_path: &str) -> Result<Arc<MemoryCatalog>> {
@@ -252,7 +514,8 @@ async fn catalog_for_space(op: &Operator, ws_path: &str) -> Result<Arc<MemoryCat
         return Ok(cached);
     }
 
-    let mut props = HashMap::new();
+    crate::space::ensure_compatible(op, ws_path).await?;
+    let mut props = file_io_props(op);
     props.insert(MEMORY_CATALOG_WAREHOUSE.to_string(), warehouse.clone());
     let catalog: MemoryCatalog = MemoryCatalogBuilder::default()
         .load("ugoite", props)
@@ -321,6 +584,12 @@ fn next_id(counter: &mut i32) -> i32 {
 }
 
 fn iceberg_type_for_field(field_type: &str, id_counter: &mut i32) -> Result<Type> {
+    if let Some(decimal) = DecimalType::parse(field_type) {
+        return Ok(Type::Primitive(PrimitiveType::Decimal {
+            precision: u32::from(decimal.precision),
+            scale: u32::from(decimal.scale),
+        }));
+    }
     Ok(match field_type {
         "number" | "double" => Type::Primitive(PrimitiveType::Double),
         "float" => Type::Primitive(PrimitiveType::Float),
@@ -371,7 +640,23 @@ fn iceberg_type_for_field(field_type: &str, id_counter: &mut i32) -> Result<Type
             let element = Arc::new(NestedField::new(element_id, "element", struct_type, false));
             Type::List(ListType::new(element))
         }
-        "sql" | "markdown" | "string" | "row_reference" => Type::Primitive(PrimitiveType::String),
+        "geopoint" => Type::Struct(StructType::new(vec![
+            Arc::new(NestedField::new(
+                next_id(id_counter),
+                "lat",
+                Type::Primitive(PrimitiveType::Double),
+                false,
+            )),
+            Arc::new(NestedField::new(
+                next_id(id_counter),
+                "lon",
+                Type::Primitive(PrimitiveType::Double),
+                false,
+            )),
+        ])),
+        "sql" | "markdown" | "string" | "row_reference" | "asset_ref" | "url" | "geojson" => {
+            Type::Primitive(PrimitiveType::String)
+        }
         _ => Type::Primitive(PrimitiveType::String),
     })
 }
@@ -546,6 +831,24 @@ fn build_entries_schema(form_def: &Value) -> Result<Schema> {
             Type::Primitive(PrimitiveType::Timestamp),
             false,
         )),
+        Arc::new(NestedField::new(
+            next_id(&mut counter),
+            "metadata",
+            Type::Primitive(PrimitiveType::String),
+            false,
+        )),
+        Arc::new(NestedField::new(
+            next_id(&mut counter),
+            "archived",
+            Type::Primitive(PrimitiveType::Boolean),
+            false,
+        )),
+        Arc::new(NestedField::new(
+            next_id(&mut counter),
+            "hard_deleted",
+            Type::Primitive(PrimitiveType::Boolean),
+            false,
+        )),
     ];
 
     Schema::builder()
@@ -633,6 +936,18 @@ fn build_revisions_schema(form_def: &Value) -> Result<Schema> {
             Type::Primitive(PrimitiveType::String),
             false,
         )),
+        Arc::new(NestedField::new(
+            next_id(&mut counter),
+            "changed_fields",
+            Type::Primitive(PrimitiveType::String),
+            false,
+        )),
+        Arc::new(NestedField::new(
+            next_id(&mut counter),
+            "message",
+            Type::Primitive(PrimitiveType::String),
+            false,
+        )),
     ];
 
     Schema::builder()
@@ -660,17 +975,7 @@ pub async fn ensure_form_tables(op: &Operator, ws_path: &str, form_def: &Value)
         .ok_or_else(|| anyhow!("Form definition missing 'name'"))?;
     let catalog: Arc<MemoryCatalog> = catalog_for_space(op, ws_path).await?;
     let namespace = form_namespace(form_name);
-
-    if !catalog.namespace_exists(&namespace).await? {
-        if let Err(err) = catalog.create_namespace(&namespace, HashMap::new()).await {
-            let message = err.to_string();
-            if !message.contains("NamespaceAlreadyExists")
-                && !message.to_lowercase().contains("already exists")
-            {
-                return Err(err.into());
-            }
-        }
-    }
+    ensure_namespace(catalog.as_ref(), &namespace).await?;
 
     let entries_ident = TableIdent::new(namespace.clone(), ENTRIES_TABLE_NAME.to_string());
     if !catalog.table_exists(&entries_ident).await? {
@@ -750,6 +1055,11 @@ pub async fn ensure_form_tables(op: &Operator, ws_path: &str, form_def: &Value)
         tx.commit(catalog.as_ref()).await?;
     }
 
+    for ident in [&entries_ident, &revisions_ident] {
+        let table = catalog.load_table(ident).await?;
+        record_table_location(op, ws_path, &table).await?;
+    }
+
     Ok(())
 }
 
@@ -796,6 +1106,106 @@ pub async fn load_revisions_table(
     Ok((catalog, revisions))
 }
 
+/// Introduces the catalog manifest for a space that predates it, from the
+/// tables in its metadata directories. Returns `true` when this call wrote it.
+pub async fn ensure_catalog_manifest(op: &Operator, ws_path: &str) -> Result<bool> {
+    if read_catalog_manifest(op, ws_path).await?.is_some() {
+        return Ok(false);
+    }
+    let discovered = discover_table_locations(op, ws_path).await?;
+    match write_catalog_manifest(op, ws_path, &discovered, &WriteCondition::Absent).await {
+        Ok(()) => Ok(true),
+        // Another writer introduced it first; its copy wins.
+        Err(err) if storage::is_write_conflict(&err) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/// Current snapshot id and metadata location of every Form table, keyed like
+/// the catalog manifest (`"Form/table"`). Tables can be rolled back to these.
+pub async fn table_states(op: &Operator, ws_path: &str) -> Result<Value> {
+    let mut states = serde_json::Map::new();
+    for form_name in list_form_names(op, ws_path).await? {
+        let Ok((_, entries, revisions)) = load_form_tables(op, ws_path, &form_name).await else {
+            continue;
+        };
+        for (table_name, table) in [
+            (ENTRIES_TABLE_NAME, entries),
+            (REVISIONS_TABLE_NAME, revisions),
+        ] {
+            states.insert(
+                catalog_manifest_key(&form_name, table_name),
+                serde_json::json!({
+                    "snapshot_id": table.metadata().current_snapshot_id(),
+                    "metadata_location": table.metadata_location(),
+                }),
+            );
+        }
+    }
+    Ok(Value::Object(states))
+}
+
+/// Current snapshot id of a form's entries table, or `None` before the first write.
+pub async fn entries_snapshot_id(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+) -> Result<Option<i64>> {
+    let (_, entries) = load_entries_table(op, ws_path, form_name).await?;
+    Ok(entries.metadata().current_snapshot_id())
+}
+
+/// Data files appended to `table` after snapshot `since` (`None` meaning
+/// the empty table), with the current snapshot id they lead up to.
+///
+/// Returns `None` when the snapshots in between are not a chain of appends
+/// back to `since`, e.g. the table was rebuilt or rows were overwritten;
+/// callers then scan the whole table instead.
+pub(crate) async fn data_files_appended_since(
+    table: &iceberg::table::Table,
+    since: Option<i64>,
+) -> Result<Option<(Option<i64>, Vec<String>)>> {
+    let metadata = table.metadata();
+    let current = metadata.current_snapshot_id();
+    let mut appended = HashSet::new();
+    let mut cursor = current;
+    while cursor != since {
+        let Some(snapshot) = cursor.and_then(|id| metadata.snapshot_by_id(id)) else {
+            return Ok(None);
+        };
+        if snapshot.summary().operation != Operation::Append {
+            return Ok(None);
+        }
+        appended.insert(snapshot.snapshot_id());
+        cursor = snapshot.parent_snapshot_id();
+    }
+    let Some(snapshot) = current
+        .filter(|_| !appended.is_empty())
+        .and_then(|id| metadata.snapshot_by_id(id))
+    else {
+        return Ok(Some((current, Vec::new())));
+    };
+    let manifests = snapshot
+        .load_manifest_list(table.file_io(), metadata)
+        .await?;
+    let mut files = Vec::new();
+    for manifest_file in manifests.entries() {
+        if !appended.contains(&manifest_file.added_snapshot_id) {
+            continue;
+        }
+        if manifest_file.content != ManifestContentType::Data {
+            return Ok(None);
+        }
+        let manifest = manifest_file.load_manifest(table.file_io()).await?;
+        for entry in manifest.entries() {
+            if entry.status() == ManifestStatus::Added {
+                files.push(entry.data_file().file_path().to_string());
+            }
+        }
+    }
+    Ok(Some((current, files)))
+}
+
 pub async fn load_form_schema_fields(
     op: &Operator,
     ws_path: &str,
@@ -882,6 +1292,7 @@ pub async fn drop_form_tables(op: &Operator, ws_path: &str, form_name: &str) ->
         let _ = op.remove_all(&form_root).await;
     }
 
+    forget_form_locations(op, ws_path, form_name).await?;
     let warehouse = warehouse_uri(op, ws_path)?;
     remove_catalog_cache(&warehouse)?;
 
@@ -3,17 +3,369 @@ use base64::Engine as _;
 use chrono::{DateTime, NaiveDate, NaiveTime, SecondsFormat, Timelike, Utc};
 use opendal::Operator;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use serde_yaml;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use uuid::Uuid;
 
+use crate::asset;
+use crate::clock;
+use crate::decimal::DecimalType;
 use crate::entry;
+use crate::geo::{self, GeoFilter, GeoPoint};
+use crate::i18n;
+use crate::iceberg_store;
 use crate::sql;
+use crate::storage::{self, WriteCondition};
+use crate::timezone::SpaceTimeZone;
+use crate::url_field;
+
+/// On-disk format version of the derived index artifacts. Bump whenever the
+/// layout of anything under `index/` changes so older stamps get rebuilt.
+pub const INDEX_FORMAT_VERSION: u32 = 1;
+
+const INDEX_DIR: &str = "index";
+const INDEX_MANIFEST_FILE: &str = "manifest.json";
+const INDEX_FORMS_DIR: &str = "forms";
+const INDEX_IDS_DIR: &str = "ids";
+/// Seconds a rebuild may hold the manifest's `rebuilding` status before the
+/// next open assumes it died with its process and starts it again.
+const REBUILD_LEASE_SECS: f64 = 600.0;
+
+/// Index artifact for a single form, rebuilt independently of other forms.
+///
+/// `snapshot_id` is the entries-table snapshot the shard was built from; a
+/// shard is only served while it still matches the table's current snapshot.
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct FormShard {
+    form: String,
+    built_at: f64,
+    snapshot_id: Option<i64>,
+    #[serde(default)]
+    records: Map<String, Value>,
+    /// Soft-deleted entry ids with their deletion timestamp, so cross-form
+    /// merging can tell a deleted entry from one that never existed.
+    #[serde(default)]
+    deleted: HashMap<String, f64>,
+}
+
+/// Entry ids of a [`FormShard`], written next to it so existence checks
+/// look an id up without deserializing every indexed record.
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct FormIdList {
+    snapshot_id: Option<i64>,
+    /// Entry id -> `(deleted, updated_at)`.
+    #[serde(default)]
+    ids: HashMap<String, (bool, f64)>,
+}
+
+impl FormIdList {
+    fn of(shard: &FormShard) -> Self {
+        let mut ids: HashMap<String, (bool, f64)> = shard
+            .records
+            .iter()
+            .map(|(id, record)| {
+                let updated_at = record
+                    .get("updated_at")
+                    .and_then(Value::as_f64)
+                    .unwrap_or(0.0);
+                (id.clone(), (false, updated_at))
+            })
+            .collect();
+        for (id, deleted_at) in &shard.deleted {
+            ids.insert(id.clone(), (true, *deleted_at));
+        }
+        Self {
+            snapshot_id: shard.snapshot_id,
+            ids,
+        }
+    }
+}
+
+fn index_root(ws_path: &str) -> String {
+    format!("{}/{}", ws_path.trim_end_matches('/'), INDEX_DIR)
+}
+
+fn index_manifest_path(ws_path: &str) -> String {
+    format!("{}/{}", index_root(ws_path), INDEX_MANIFEST_FILE)
+}
+
+async fn write_index_manifest(op: &Operator, ws_path: &str, manifest: &Value) -> Result<()> {
+    let root = format!("{}/", index_root(ws_path));
+    if !op.exists(&root).await? {
+        op.create_dir(&root).await?;
+    }
+    op.write(
+        &index_manifest_path(ws_path),
+        serde_json::to_vec_pretty(manifest)?,
+    )
+    .await?;
+    Ok(())
+}
+
+fn form_shards_root(ws_path: &str) -> String {
+    format!("{}/{}/", index_root(ws_path), INDEX_FORMS_DIR)
+}
+
+fn form_shard_path(ws_path: &str, form_name: &str) -> String {
+    format!("{}{}.json", form_shards_root(ws_path), form_name)
+}
+
+fn form_ids_root(ws_path: &str) -> String {
+    format!("{}/{}/", index_root(ws_path), INDEX_IDS_DIR)
+}
+
+fn form_ids_path(ws_path: &str, form_name: &str) -> String {
+    format!("{}{}.json", form_ids_root(ws_path), form_name)
+}
+
+async fn read_form_ids(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+) -> Result<Option<FormIdList>> {
+    let path = form_ids_path(ws_path, form_name);
+    if !op.exists(&path).await? {
+        return Ok(None);
+    }
+    let bytes = op.read(&path).await?;
+    Ok(serde_json::from_slice(&bytes.to_vec()).ok())
+}
+
+async fn read_form_shard(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+) -> Result<Option<FormShard>> {
+    let path = form_shard_path(ws_path, form_name);
+    if !op.exists(&path).await? {
+        return Ok(None);
+    }
+    let bytes = op.read(&path).await?;
+    Ok(serde_json::from_slice(&bytes.to_vec()).ok())
+}
+
+/// Writes the shard and its [`FormIdList`]. Both carry the shard's snapshot
+/// id, so a reader never trusts one built from another snapshot.
+async fn write_form_shard(op: &Operator, ws_path: &str, shard: &FormShard) -> Result<()> {
+    for root in [form_shards_root(ws_path), form_ids_root(ws_path)] {
+        if !op.exists(&root).await? {
+            op.create_dir(&root).await?;
+        }
+    }
+    op.write(
+        &form_ids_path(ws_path, &shard.form),
+        serde_json::to_vec(&FormIdList::of(shard))?,
+    )
+    .await?;
+    op.write(
+        &form_shard_path(ws_path, &shard.form),
+        serde_json::to_vec(shard)?,
+    )
+    .await?;
+    Ok(())
+}
+
+fn shard_summary(shard: &FormShard) -> Value {
+    serde_json::json!({
+        "built_at": shard.built_at,
+        "snapshot_id": shard.snapshot_id,
+        "entry_count": shard.records.len(),
+    })
+}
+
+pub async fn read_index_manifest(op: &Operator, ws_path: &str) -> Result<Option<Value>> {
+    let path = index_manifest_path(ws_path);
+    if !op.exists(&path).await? {
+        return Ok(None);
+    }
+    let bytes = op.read(&path).await?;
+    // A manifest we cannot parse is treated like one from an unknown version.
+    Ok(Some(
+        serde_json::from_slice(&bytes.to_vec()).unwrap_or_else(|_| serde_json::json!({})),
+    ))
+}
+
+fn manifest_state(manifest: Option<&Value>) -> &'static str {
+    let Some(manifest) = manifest else {
+        return "missing";
+    };
+    if manifest.get("status").and_then(|v| v.as_str()) == Some("rebuilding") {
+        let started_at = manifest.get("started_at").and_then(Value::as_f64);
+        return match started_at {
+            Some(started_at) if entry::now_ts() - started_at < REBUILD_LEASE_SECS => "rebuilding",
+            _ => "stalled",
+        };
+    }
+    let version = manifest.get("format_version").and_then(|v| v.as_u64());
+    if version == Some(u64::from(INDEX_FORMAT_VERSION)) {
+        "current"
+    } else {
+        "outdated"
+    }
+}
+
+/// Reports the stored index format against the version this build expects.
+pub async fn index_status(op: &Operator, ws_path: &str) -> Result<Value> {
+    let manifest = read_index_manifest(op, ws_path).await?;
+    let state = manifest_state(manifest.as_ref());
+
+    let mut forms_status = Map::new();
+    for form_name in crate::form::list_form_names(op, ws_path).await? {
+        let snapshot_id = iceberg_store::entries_snapshot_id(op, ws_path, &form_name).await?;
+        let status = match read_form_shard(op, ws_path, &form_name).await? {
+            Some(shard) => {
+                let mut summary = shard_summary(&shard);
+                summary["fresh"] = Value::Bool(shard.snapshot_id == snapshot_id);
+                summary
+            }
+            None => serde_json::json!({ "fresh": false }),
+        };
+        forms_status.insert(form_name, status);
+    }
+
+    Ok(serde_json::json!({
+        "state": state,
+        "forms": forms_status,
+        "format_version": manifest
+            .as_ref()
+            .and_then(|m| m.get("format_version"))
+            .cloned()
+            .unwrap_or(Value::Null),
+        "expected_format_version": INDEX_FORMAT_VERSION,
+        "built_at": manifest
+            .as_ref()
+            .and_then(|m| m.get("built_at"))
+            .cloned()
+            .unwrap_or(Value::Null),
+    }))
+}
+
+/// Returns `true` when the stored index matches [`INDEX_FORMAT_VERSION`],
+/// without changing anything. Reads serve from the live Iceberg scan
+/// otherwise.
+pub async fn index_is_compatible(op: &Operator, ws_path: &str) -> Result<bool> {
+    let manifest = read_index_manifest(op, ws_path).await?;
+    Ok(manifest_state(manifest.as_ref()) == "current")
+}
+
+/// Returns `true` when the stored index matches [`INDEX_FORMAT_VERSION`].
+///
+/// Otherwise, unless another rebuild holds the lease, the manifest is
+/// flagged as rebuilding with its `started_at` and `reindex_all` is started
+/// in the background; reads keep serving from the live Iceberg scan until the
+/// rebuild stamps the new version. A rebuild older than the lease is taken
+/// to have died and is started again. Called from index write paths; see
+/// [`index_is_compatible`] for a check that writes nothing.
+pub async fn ensure_index_compatible(op: &Operator, ws_path: &str) -> Result<bool> {
+    let path = index_manifest_path(ws_path);
+    let (manifest, condition) = match storage::read_json_versioned(op, &path).await {
+        Ok(versioned) => versioned,
+        Err(err) if storage::is_write_conflict(&err) => return Ok(false),
+        // A manifest we cannot parse is treated like one from an unknown
+        // version, and replaced only while its content is unchanged.
+        Err(err) if err.is::<serde_json::Error>() => {
+            let bytes = op.read(&path).await?.to_vec();
+            (
+                Some(serde_json::json!({})),
+                WriteCondition::Unchanged(storage::content_digest(&bytes)),
+            )
+        }
+        Err(err) => return Err(err),
+    };
+    match manifest_state(manifest.as_ref()) {
+        "current" => return Ok(true),
+        "rebuilding" => return Ok(false),
+        _ => {}
+    }
+
+    let mut pending = manifest.unwrap_or_else(|| serde_json::json!({}));
+    if let Some(obj) = pending.as_object_mut() {
+        obj.insert(
+            "status".to_string(),
+            Value::String("rebuilding".to_string()),
+        );
+        obj.insert("started_at".to_string(), serde_json::json!(entry::now_ts()));
+    }
+    let root = format!("{}/", index_root(ws_path));
+    if !op.exists(&root).await? {
+        op.create_dir(&root).await?;
+    }
+    // Only one opener claims the rebuild.
+    match storage::write_json_conditional(op, &path, &pending, &condition).await {
+        Err(err) if storage::is_write_conflict(&err) => return Ok(false),
+        result => result?,
+    }
+
+    let op = op.clone();
+    let ws_path = ws_path.to_string();
+    tokio::spawn(async move {
+        if reindex_all(&op, &ws_path).await.is_err() {
+            // Leave the manifest in a state that retriggers the rebuild next time.
+            let _ = write_index_manifest(&op, &ws_path, &serde_json::json!({})).await;
+        }
+    });
+    Ok(false)
+}
 
 pub async fn query_index(op: &Operator, ws_path: &str, query: &str) -> Result<Vec<Value>> {
+    run_query(op, ws_path, query, None).await
+}
+
+/// [`query_index`] returning only `fields` of each match, as `{id, title,
+/// form, updated_at, tags, properties}` with `properties` cut down to those
+/// fields.
+///
+/// Forms whose index shard is current are answered from it as usual. Stale
+/// forms are read with a projected scan of the requested fields and those
+/// the filters name (see [`entry::list_projected_entry_rows`]) instead of a
+/// full shard rebuild, so rollups and validation warnings are not
+/// available for them. SQL queries run unprojected and are cut down
+/// afterwards.
+pub async fn query_index_fields(
+    op: &Operator,
+    ws_path: &str,
+    query: &str,
+    fields: &[String],
+) -> Result<Vec<Value>> {
+    let rows = run_query(op, ws_path, query, Some(fields)).await?;
+    Ok(rows
+        .iter()
+        .map(|record| project_record(record, fields))
+        .collect())
+}
+
+fn project_record(record: &Value, fields: &[String]) -> Value {
+    let properties: Map<String, Value> = record
+        .get("properties")
+        .and_then(Value::as_object)
+        .map(|props| {
+            props
+                .iter()
+                .filter(|(key, _)| fields.contains(key))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect()
+        })
+        .unwrap_or_default();
+    serde_json::json!({
+        "id": record.get("id").cloned().unwrap_or(Value::Null),
+        "title": record.get("title").cloned().unwrap_or(Value::Null),
+        "form": record.get("form").cloned().unwrap_or(Value::Null),
+        "updated_at": record.get("updated_at").cloned().unwrap_or(Value::Null),
+        "tags": record.get("tags").cloned().unwrap_or(Value::Null),
+        "properties": properties,
+    })
+}
+
+async fn run_query(
+    op: &Operator,
+    ws_path: &str,
+    query: &str,
+    projection: Option<&[String]>,
+) -> Result<Vec<Value>> {
+    let use_shards = index_is_compatible(op, ws_path).await?;
     let forms = load_forms(op, ws_path).await?;
-    let entries_map = collect_entries(op, ws_path, &forms).await?;
 
     let query_value = if query.trim().is_empty() {
         Value::Null
@@ -22,12 +374,40 @@ pub async fn query_index(op: &Operator, ws_path: &str, query: &str) -> Result<Ve
     };
 
     if let Some(sql_query) = extract_sql_query(&query_value) {
+        let entries_map = collect_entries(op, ws_path, &forms, None, use_shards, None).await?;
         let parsed = sql::parse_sql(&sql_query)?;
-        let tables = build_sql_tables(op, ws_path, &forms, &entries_map).await?;
+        let tables = build_sql_tables(&forms, &entries_map);
         return sql::filter_entries_by_sql(&tables, &parsed);
     }
 
     let filters: Option<Map<String, Value>> = query_value.as_object().cloned();
+    // Projected reads also need the fields the filters look at.
+    let read_fields = projection.map(|fields| {
+        let mut read_fields = fields.to_vec();
+        for key in filters.iter().flat_map(|f| f.keys()) {
+            let record_key =
+                ["id", "title", "form", "updated_at", "tag", "tags"].contains(&key.as_str());
+            if !record_key && !read_fields.contains(key) {
+                read_fields.push(key.clone());
+            }
+        }
+        read_fields
+    });
+    // A plain `form` filter lets us skip every other form's shard entirely.
+    let form_scope = filters
+        .as_ref()
+        .and_then(|f| f.get("form"))
+        .and_then(|v| v.as_str())
+        .map(|form| vec![form.to_string()]);
+    let entries_map = collect_entries(
+        op,
+        ws_path,
+        &forms,
+        form_scope.as_deref(),
+        use_shards,
+        read_fields.as_deref(),
+    )
+    .await?;
 
     let mut results = Vec::new();
     for entry in entries_map.values() {
@@ -47,13 +427,492 @@ pub async fn execute_sql_query(
     ws_path: &str,
     sql_query: &str,
 ) -> Result<Vec<Value>> {
+    let use_shards = index_is_compatible(op, ws_path).await?;
     let forms = load_forms(op, ws_path).await?;
-    let entries_map = collect_entries(op, ws_path, &forms).await?;
+    let entries_map = collect_entries(op, ws_path, &forms, None, use_shards, None).await?;
     let parsed = sql::parse_sql(sql_query)?;
-    let tables = build_sql_tables(op, ws_path, &forms, &entries_map).await?;
+    let tables = build_sql_tables(&forms, &entries_map);
     sql::filter_entries_by_sql(&tables, &parsed)
 }
 
+/// A SQL query evaluated one form at a time, so callers can write rows out
+/// as each form's batch is filtered instead of holding the whole result.
+///
+/// Only queries over `entries` or a single form without JOIN or ORDER BY
+/// qualify; [`SqlBatches::open`] returns `None` for the rest, which need
+/// [`execute_sql_query`]. Rows come grouped by form, the newest row of an
+/// entry still wins across forms, and LIMIT caps the total.
+pub struct SqlBatches {
+    op: Operator,
+    ws_path: String,
+    query: sql::SqlQuery,
+    forms: HashMap<String, Value>,
+    use_shards: bool,
+    form_names: Vec<String>,
+    /// Entry id -> form holding the entry's newest row.
+    newest_form: HashMap<String, String>,
+    pending: VecDeque<String>,
+    remaining: usize,
+}
+
+impl SqlBatches {
+    pub async fn open(op: &Operator, ws_path: &str, sql_query: &str) -> Result<Option<Self>> {
+        let query = sql::parse_sql(sql_query)?;
+        let table = query.from.name.to_lowercase();
+        // Links and assets rows refer across entries, so like joins and
+        // ordering they need every row at once.
+        if !query.joins.is_empty()
+            || !query.order_by.is_empty()
+            || table == "links"
+            || table == "assets"
+        {
+            return Ok(None);
+        }
+        let use_shards = index_is_compatible(op, ws_path).await?;
+        let forms = load_forms(op, ws_path).await?;
+        let all_forms = crate::form::list_form_names(op, ws_path).await?;
+        let form_names: Vec<String> = match table.as_str() {
+            "entries" => all_forms.clone(),
+            _ => all_forms
+                .iter()
+                .filter(|name| name.to_lowercase() == table)
+                .cloned()
+                .collect(),
+        };
+        // Unknown tables are left to the whole-result path for its error.
+        if form_names.is_empty() {
+            return Ok(None);
+        }
+
+        let mut newest: HashMap<String, (f64, String)> = HashMap::new();
+        for form_name in &all_forms {
+            for (entry_id, updated_at) in
+                form_entry_versions(op, ws_path, form_name, &forms, use_shards).await?
+            {
+                if newest
+                    .get(&entry_id)
+                    .is_none_or(|(existing, _)| updated_at >= *existing)
+                {
+                    newest.insert(entry_id, (updated_at, form_name.clone()));
+                }
+            }
+        }
+        let mut batches = Self {
+            op: op.clone(),
+            ws_path: ws_path.to_string(),
+            remaining: query.effective_limit(),
+            query,
+            forms,
+            use_shards,
+            form_names,
+            newest_form: newest
+                .into_iter()
+                .map(|(entry_id, (_, form))| (entry_id, form))
+                .collect(),
+            pending: VecDeque::new(),
+        };
+        batches.rewind();
+        Ok(Some(batches))
+    }
+
+    /// Starts over from the first form, re-reading each batch.
+    pub fn rewind(&mut self) {
+        self.pending = self.form_names.iter().cloned().collect();
+        self.remaining = self.query.effective_limit();
+    }
+
+    /// The matching rows of the next form, or `None` once every form is
+    /// done or the limit is reached.
+    pub async fn next_batch(&mut self) -> Result<Option<Vec<Value>>> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        let Some(form_name) = self.pending.pop_front() else {
+            return Ok(None);
+        };
+        let shard = load_form_shard(
+            &self.op,
+            &self.ws_path,
+            &form_name,
+            &self.forms,
+            self.use_shards,
+            None,
+        )
+        .await?;
+        let records: Map<String, Value> = shard
+            .records
+            .into_iter()
+            .filter(|(entry_id, _)| self.newest_form.get(entry_id) == Some(&form_name))
+            .collect();
+        let tables = build_sql_tables(&self.forms, &records);
+        let mut rows = sql::filter_entries_by_sql(&tables, &self.query)?;
+        rows.truncate(self.remaining);
+        self.remaining -= rows.len();
+        Ok(Some(rows))
+    }
+}
+
+/// `(entry_id, updated_at)` of every row of `form_name`, deleted ones
+/// included, from the id list when it is current and otherwise from a scan
+/// that decodes no fields.
+async fn form_entry_versions(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+    forms: &HashMap<String, Value>,
+    use_shards: bool,
+) -> Result<Vec<(String, f64)>> {
+    if use_shards {
+        if let Some(list) = read_form_ids(op, ws_path, form_name).await? {
+            if list.snapshot_id
+                == iceberg_store::entries_snapshot_id(op, ws_path, form_name).await?
+            {
+                return Ok(list
+                    .ids
+                    .into_iter()
+                    .map(|(entry_id, (_, updated_at))| (entry_id, updated_at))
+                    .collect());
+            }
+        }
+    }
+    let shard = load_form_shard(op, ws_path, form_name, forms, false, Some(&[])).await?;
+    Ok(shard
+        .records
+        .into_iter()
+        .map(|(entry_id, record)| {
+            let updated_at = record
+                .get("updated_at")
+                .and_then(Value::as_f64)
+                .unwrap_or(0.0);
+            (entry_id, updated_at)
+        })
+        .chain(shard.deleted)
+        .collect())
+}
+
+/// Runs `query_index` and returns facet buckets for the matching rows.
+///
+/// Buckets are counted over the returned rows rather than during a table
+/// scan: rows may come from the JSON form shards, and only the final rows
+/// reflect filters, the newest row per entry and merged rollup values.
+///
+/// `facets` is a list of specs such as `{"field": "Status"}` (value counts),
+/// `{"field": "Cost", "type": "histogram", "interval": 100}` or
+/// `{"field": "Date", "type": "date_histogram", "interval": "month"}`.
+pub async fn query_index_with_facets(
+    op: &Operator,
+    ws_path: &str,
+    query: &str,
+    facets: &Value,
+) -> Result<Value> {
+    let rows = query_index(op, ws_path, query).await?;
+    let facets = compute_facets(&rows, facets)?;
+    Ok(serde_json::json!({
+        "rows": rows,
+        "facets": facets,
+    }))
+}
+
+fn join_value(record: &Value, field: &str) -> Value {
+    record
+        .get(field)
+        .filter(|_| field != "properties")
+        .or_else(|| record.get("properties").and_then(|props| props.get(field)))
+        .cloned()
+        .unwrap_or(Value::Null)
+}
+
+/// Inner-joins the latest `left_form` entries to the `right_form` entries
+/// their `on_field` row_reference points at.
+///
+/// `projection` lists `Form.field` columns, where `field` is a form field or
+/// a record key such as `id` or `title`; each joined row maps those names to
+/// values. An empty projection returns `{left_form: record, right_form:
+/// record}` pairs instead. Rows are ordered by left then right entry id.
+pub async fn join(
+    op: &Operator,
+    ws_path: &str,
+    left_form: &str,
+    right_form: &str,
+    on_field: &str,
+    projection: &[String],
+) -> Result<Vec<Value>> {
+    let use_shards = index_is_compatible(op, ws_path).await?;
+    let forms = load_forms(op, ws_path).await?;
+    let left_def = forms
+        .get(left_form)
+        .ok_or_else(|| anyhow!("Form not found: {}", left_form))?;
+    if !forms.contains_key(right_form) {
+        return Err(anyhow!("Form not found: {}", right_form));
+    }
+    let target = left_def
+        .get("fields")
+        .and_then(|fields| fields.get(on_field))
+        .filter(|field| field.get("type").and_then(Value::as_str) == Some("row_reference"))
+        .and_then(|field| field.get("target_form"))
+        .and_then(Value::as_str);
+    if target != Some(right_form) {
+        return Err(anyhow!(
+            "{}.{} is not a row_reference to {}",
+            left_form,
+            on_field,
+            right_form
+        ));
+    }
+    let columns = projection
+        .iter()
+        .map(|column| match column.split_once('.') {
+            Some((form, field)) if form == left_form || form == right_form => {
+                Ok((column.clone(), form == left_form, field.to_string()))
+            }
+            _ => Err(anyhow!(
+                "Projection column must be {}.<field> or {}.<field>: {}",
+                left_form,
+                right_form,
+                column
+            )),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let scope = [left_form.to_string(), right_form.to_string()];
+    let entries = collect_entries(op, ws_path, &forms, Some(&scope), use_shards, None).await?;
+    let form_of = |record: &Value| {
+        record
+            .get("form")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+    let right: HashMap<&str, &Value> = entries
+        .iter()
+        .filter(|(_, record)| form_of(record).as_deref() == Some(right_form))
+        .map(|(id, record)| (id.as_str(), record))
+        .collect();
+    let mut left: Vec<(&String, &Value)> = entries
+        .iter()
+        .filter(|(_, record)| form_of(record).as_deref() == Some(left_form))
+        .collect();
+    left.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut rows = Vec::new();
+    for (_, left_record) in left {
+        let Some(right_id) = entry::row_reference_id(&join_value(left_record, on_field)) else {
+            continue;
+        };
+        let Some(right_record) = right.get(right_id.as_str()) else {
+            continue;
+        };
+        if columns.is_empty() {
+            let mut row = Map::new();
+            row.insert(left_form.to_string(), left_record.clone());
+            row.insert(right_form.to_string(), (*right_record).clone());
+            rows.push(Value::Object(row));
+            continue;
+        }
+        let row = columns
+            .iter()
+            .map(|(name, is_left, field)| {
+                let record = if *is_left { left_record } else { *right_record };
+                (name.clone(), join_value(record, field))
+            })
+            .collect::<Map<_, _>>();
+        rows.push(Value::Object(row));
+    }
+    Ok(rows)
+}
+
+fn facet_values(record: &Value, field: &str) -> Vec<Value> {
+    let value = record.get(field).cloned().or_else(|| {
+        record
+            .get("properties")
+            .and_then(|v| v.as_object())
+            .and_then(|props| props.get(field))
+            .cloned()
+    });
+    match value {
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::Array(items)) => items.into_iter().filter(|v| !v.is_null()).collect(),
+        Some(Value::String(text)) if text.trim().is_empty() => Vec::new(),
+        Some(other) => vec![other],
+    }
+}
+
+fn facet_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(num) => num.as_f64(),
+        Value::String(text) => text.trim().parse::<f64>().ok(),
+        _ => None,
+    }
+}
+
+fn facet_date(value: &Value) -> Option<NaiveDate> {
+    match value {
+        // Numeric values are epoch seconds, as used by created_at/updated_at.
+        Value::Number(num) => {
+            let secs = num.as_f64()?;
+            DateTime::from_timestamp(secs.floor() as i64, 0).map(|dt| dt.date_naive())
+        }
+        Value::String(text) => {
+            let text = text.trim();
+            NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .ok()
+                .or_else(|| {
+                    DateTime::parse_from_rfc3339(text)
+                        .ok()
+                        .map(|dt| dt.with_timezone(&Utc).date_naive())
+                })
+        }
+        _ => None,
+    }
+}
+
+fn date_bucket(date: NaiveDate, interval: &str) -> Result<NaiveDate> {
+    use chrono::Datelike;
+    let bucket = match interval {
+        "day" => Some(date),
+        "week" => date.checked_sub_signed(chrono::Duration::days(i64::from(
+            date.weekday().num_days_from_monday(),
+        ))),
+        "month" => NaiveDate::from_ymd_opt(date.year(), date.month(), 1),
+        "year" => NaiveDate::from_ymd_opt(date.year(), 1, 1),
+        other => {
+            return Err(anyhow!(
+                "Unsupported date_histogram interval: {} (expected day, week, month or year)",
+                other
+            ))
+        }
+    };
+    bucket.ok_or_else(|| anyhow!("Date out of range for facet bucket: {}", date))
+}
+
+fn facet_key_string(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+pub fn compute_facets(rows: &[Value], specs: &Value) -> Result<Value> {
+    let specs = match specs {
+        Value::Null => return Ok(Value::Object(Map::new())),
+        Value::Array(items) => items.clone(),
+        Value::Object(_) => vec![specs.clone()],
+        _ => return Err(anyhow!("Facet specification must be an object or a list")),
+    };
+
+    let mut output = Map::new();
+    for spec in specs {
+        let field = spec
+            .get("field")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Facet specification missing 'field'"))?;
+        let name = spec
+            .get("name")
+            .and_then(|v| v.as_str())
+            .unwrap_or(field)
+            .to_string();
+        let facet_type = spec.get("type").and_then(|v| v.as_str()).unwrap_or("terms");
+
+        let mut missing = 0usize;
+        let buckets: Vec<Value> = match facet_type {
+            "terms" => {
+                let size = spec.get("size").and_then(|v| v.as_u64()).unwrap_or(20) as usize;
+                let mut counts: HashMap<String, usize> = HashMap::new();
+                for row in rows {
+                    let values = facet_values(row, field);
+                    if values.is_empty() {
+                        missing += 1;
+                    }
+                    for value in values {
+                        *counts.entry(facet_key_string(&value)).or_insert(0) += 1;
+                    }
+                }
+                let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+                counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+                counts
+                    .into_iter()
+                    .take(size)
+                    .map(|(key, count)| serde_json::json!({ "key": key, "count": count }))
+                    .collect()
+            }
+            "histogram" => {
+                let interval = spec
+                    .get("interval")
+                    .and_then(|v| v.as_f64())
+                    .filter(|v| *v > 0.0)
+                    .ok_or_else(|| {
+                        anyhow!("Histogram facet '{}' needs a positive 'interval'", name)
+                    })?;
+                let mut counts: HashMap<i64, usize> = HashMap::new();
+                for row in rows {
+                    let numbers: Vec<f64> = facet_values(row, field)
+                        .iter()
+                        .filter_map(facet_number)
+                        .collect();
+                    if numbers.is_empty() {
+                        missing += 1;
+                    }
+                    for number in numbers {
+                        *counts
+                            .entry((number / interval).floor() as i64)
+                            .or_insert(0) += 1;
+                    }
+                }
+                let mut counts: Vec<(i64, usize)> = counts.into_iter().collect();
+                counts.sort_by_key(|(bucket, _)| *bucket);
+                counts
+                    .into_iter()
+                    .map(|(bucket, count)| {
+                        serde_json::json!({ "key": bucket as f64 * interval, "count": count })
+                    })
+                    .collect()
+            }
+            "date_histogram" => {
+                let interval = spec
+                    .get("interval")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("month");
+                let mut counts: HashMap<NaiveDate, usize> = HashMap::new();
+                for row in rows {
+                    let dates: Vec<NaiveDate> = facet_values(row, field)
+                        .iter()
+                        .filter_map(facet_date)
+                        .collect();
+                    if dates.is_empty() {
+                        missing += 1;
+                    }
+                    for date in dates {
+                        *counts.entry(date_bucket(date, interval)?).or_insert(0) += 1;
+                    }
+                }
+                let mut counts: Vec<(NaiveDate, usize)> = counts.into_iter().collect();
+                counts.sort_by_key(|(bucket, _)| *bucket);
+                counts
+                    .into_iter()
+                    .map(|(bucket, count)| {
+                        serde_json::json!({
+                            "key": bucket.format("%Y-%m-%d").to_string(),
+                            "count": count,
+                        })
+                    })
+                    .collect()
+            }
+            other => return Err(anyhow!("Unsupported facet type: {}", other)),
+        };
+
+        output.insert(
+            name,
+            serde_json::json!({
+                "type": facet_type,
+                "field": field,
+                "buckets": buckets,
+                "missing": missing,
+            }),
+        );
+    }
+
+    Ok(Value::Object(output))
+}
+
 fn extract_sql_query(value: &Value) -> Option<String> {
     match value {
         Value::String(text) if !text.trim().is_empty() => Some(text.to_string()),
@@ -77,6 +936,13 @@ fn matches_filters(entry: &Value, filters: &Map<String, Value>) -> Result<bool>
                 .cloned();
         }
 
+        if let Some(filter) = GeoFilter::parse(expected)? {
+            if !entry_value.is_some_and(|value| filter.matches(&value)) {
+                return Ok(false);
+            }
+            continue;
+        }
+
         if expected.is_object() {
             return Err(anyhow!(
                 "Structured operators (e.g., $gt) are not implemented for the local query helper yet."
@@ -110,15 +976,193 @@ fn matches_filters(entry: &Value, filters: &Map<String, Value>) -> Result<bool>
 }
 
 pub async fn reindex_all(op: &Operator, ws_path: &str) -> Result<()> {
-    let _ = op;
-    let _ = ws_path;
-    Ok(())
+    let forms = load_forms(op, ws_path).await?;
+    let form_names = crate::form::list_form_names(op, ws_path).await?;
+
+    let mut forms_summary = Map::new();
+    for form_name in &form_names {
+        let shard = build_form_shard(op, ws_path, form_name, &forms).await?;
+        write_form_shard(op, ws_path, &shard).await?;
+        forms_summary.insert(form_name.clone(), shard_summary(&shard));
+    }
+    remove_orphan_shards(op, ws_path, &form_names).await?;
+
+    let manifest = serde_json::json!({
+        "format_version": INDEX_FORMAT_VERSION,
+        "status": "ready",
+        "built_at": entry::now_ts(),
+        "crate_version": env!("CARGO_PKG_VERSION"),
+        "forms": forms_summary,
+    });
+    write_index_manifest(op, ws_path, &manifest).await
+}
+
+/// Rebuilds the index shard of a single form without touching the others.
+pub async fn reindex_form(op: &Operator, ws_path: &str, form_name: &str) -> Result<Value> {
+    let forms = load_forms(op, ws_path).await?;
+    let shard = build_form_shard(op, ws_path, form_name, &forms).await?;
+    write_form_shard(op, ws_path, &shard).await?;
+    let summary = shard_summary(&shard);
+    record_form_summary(op, ws_path, form_name, &summary).await?;
+    Ok(summary)
+}
+
+async fn record_form_summary(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+    summary: &Value,
+) -> Result<()> {
+    let mut manifest = read_index_manifest(op, ws_path)
+        .await?
+        .unwrap_or_else(|| serde_json::json!({}));
+    if let Some(obj) = manifest.as_object_mut() {
+        let forms_entry = obj
+            .entry("forms".to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+        if let Some(forms_obj) = forms_entry.as_object_mut() {
+            forms_obj.insert(form_name.to_string(), summary.clone());
+        }
+    }
+    write_index_manifest(op, ws_path, &manifest).await
+}
+
+/// Brings a form's shard up to the entries table's current snapshot.
+///
+/// The shard's `snapshot_id` is its watermark: when the table has only been
+/// appended to since then, just the rows in the appended data files are
+/// re-indexed. A missing shard or any other table change (a rebuild after a
+/// form migration, a redaction) falls back to a full rebuild. Returns the
+/// shard summary, the mode used (`unchanged`, `incremental` or `full`) and
+/// how many entries were re-indexed.
+async fn refresh_form_shard(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+    forms: &HashMap<String, Value>,
+) -> Result<(Value, &'static str, usize)> {
+    let current = iceberg_store::entries_snapshot_id(op, ws_path, form_name).await?;
+    let existing = read_form_shard(op, ws_path, form_name).await?;
+    if let Some(shard) = existing
+        .as_ref()
+        .filter(|shard| shard.snapshot_id == current)
+    {
+        return Ok((shard_summary(shard), "unchanged", 0));
+    }
+    let form_def = crate::form::read_form_definition(op, ws_path, form_name).await?;
+    let appended = match &existing {
+        Some(shard) => {
+            entry::list_form_entry_rows_since(op, ws_path, form_name, &form_def, shard.snapshot_id)
+                .await?
+        }
+        None => None,
+    };
+    let (Some(mut shard), Some((snapshot_id, rows))) = (existing, appended) else {
+        let shard = build_form_shard(op, ws_path, form_name, forms).await?;
+        write_form_shard(op, ws_path, &shard).await?;
+        let changed = shard.records.len() + shard.deleted.len();
+        return Ok((shard_summary(&shard), "full", changed));
+    };
+
+    let computed = crate::rollup::read_computed(op, ws_path, form_name).await?;
+    let tz = SpaceTimeZone::for_space(op, ws_path).await?;
+    let changed = rows.len();
+    for row in rows {
+        if row.deleted {
+            shard.records.remove(&row.entry_id);
+            shard
+                .deleted
+                .insert(row.entry_id, clock::from_micros(row.updated_at));
+            continue;
+        }
+        shard.deleted.remove(&row.entry_id);
+        let entry_computed = computed.get(&row.entry_id);
+        match build_record(ws_path, form_name, &row, forms, entry_computed, &tz).await? {
+            Some(record) => shard.records.insert(row.entry_id, record),
+            None => shard.records.remove(&row.entry_id),
+        };
+    }
+    shard.snapshot_id = snapshot_id;
+    shard.built_at = entry::now_ts();
+    write_form_shard(op, ws_path, &shard).await?;
+    Ok((shard_summary(&shard), "incremental", changed))
+}
+
+/// Updates every form's shard from its snapshot watermark instead of
+/// rebuilding the whole index; see [`refresh_form_shard`]. An index from
+/// another format version is rebuilt with [`reindex_all`]. Returns the
+/// per-form summaries.
+pub async fn reindex_incremental(op: &Operator, ws_path: &str) -> Result<Value> {
+    let manifest = read_index_manifest(op, ws_path).await?;
+    if manifest_state(manifest.as_ref()) != "current" {
+        reindex_all(op, ws_path).await?;
+        let mut forms = read_index_manifest(op, ws_path)
+            .await?
+            .and_then(|manifest| manifest.get("forms").cloned())
+            .unwrap_or_else(|| Value::Object(Map::new()));
+        for summary in forms
+            .as_object_mut()
+            .into_iter()
+            .flat_map(|map| map.values_mut())
+        {
+            summary["mode"] = Value::from("full");
+            summary["changed"] = summary["entry_count"].clone();
+        }
+        return Ok(serde_json::json!({"forms": forms}));
+    }
+
+    let forms = load_forms(op, ws_path).await?;
+    let form_names = crate::form::list_form_names(op, ws_path).await?;
+    let mut report = Map::new();
+    let mut forms_summary = Map::new();
+    for form_name in &form_names {
+        let (summary, mode, changed) = refresh_form_shard(op, ws_path, form_name, &forms).await?;
+        forms_summary.insert(form_name.clone(), summary.clone());
+        let mut reported = summary;
+        reported["mode"] = Value::from(mode);
+        reported["changed"] = Value::from(changed);
+        report.insert(form_name.clone(), reported);
+    }
+    remove_orphan_shards(op, ws_path, &form_names).await?;
+
+    let mut manifest = manifest.unwrap_or_else(|| serde_json::json!({}));
+    manifest["forms"] = Value::Object(forms_summary);
+    manifest["refreshed_at"] = Value::from(entry::now_ts());
+    write_index_manifest(op, ws_path, &manifest).await?;
+    Ok(serde_json::json!({"forms": report}))
 }
 
+/// Refreshes the shard of the form holding `entry_id` from its watermark.
+/// An index from another format version is left to the background rebuild
+/// [`ensure_index_compatible`] starts, which picks the entry up as well.
 pub async fn update_entry_index(op: &Operator, ws_path: &str, entry_id: &str) -> Result<()> {
-    let _ = op;
-    let _ = ws_path;
-    let _ = entry_id;
+    if !ensure_index_compatible(op, ws_path).await? {
+        return Ok(());
+    }
+    if let Some(form_name) = entry::find_entry_form(op, ws_path, entry_id).await? {
+        let forms = load_forms(op, ws_path).await?;
+        let (summary, _, _) = refresh_form_shard(op, ws_path, &form_name, &forms).await?;
+        record_form_summary(op, ws_path, &form_name, &summary).await?;
+    }
+    Ok(())
+}
+
+async fn remove_orphan_shards(op: &Operator, ws_path: &str, form_names: &[String]) -> Result<()> {
+    let keep: HashSet<String> = form_names
+        .iter()
+        .map(|name| format!("{}.json", name))
+        .collect();
+    for root in [form_shards_root(ws_path), form_ids_root(ws_path)] {
+        if !op.exists(&root).await? {
+            continue;
+        }
+        for item in op.list(&root).await? {
+            let name = item.name().trim_end_matches('/');
+            if name.ends_with(".json") && !keep.contains(name) {
+                op.delete(item.path()).await?;
+            }
+        }
+    }
     Ok(())
 }
 
@@ -205,15 +1249,14 @@ fn parse_boolean(value: &str) -> Option<bool> {
     }
 }
 
-fn normalize_timestamp(value: &str) -> Option<String> {
-    DateTime::parse_from_rfc3339(value)
-        .ok()
-        .map(|dt| dt.with_timezone(&Utc).to_rfc3339())
+fn normalize_timestamp(value: &str, tz: &SpaceTimeZone) -> Option<String> {
+    tz.parse_timestamp(value)
+        .map(|dt| tz.localize(&dt.with_timezone(&Utc)).to_rfc3339())
 }
 
-fn normalize_timestamp_ns(value: &str) -> Option<String> {
-    DateTime::parse_from_rfc3339(value).ok().map(|dt| {
-        dt.with_timezone(&Utc)
+fn normalize_timestamp_ns(value: &str, tz: &SpaceTimeZone) -> Option<String> {
+    tz.parse_timestamp(value).map(|dt| {
+        tz.localize(&dt.with_timezone(&Utc))
             .to_rfc3339_opts(SecondsFormat::Nanos, false)
     })
 }
@@ -309,6 +1352,16 @@ fn parse_object_list(value: &Value) -> Option<Value> {
 }
 
 pub fn validate_properties(properties: &Value, entry_form: &Value) -> Result<(Value, Vec<Value>)> {
+    validate_properties_in_zone(properties, entry_form, &SpaceTimeZone::default())
+}
+
+/// [`validate_properties`] with date-only and local timestamps read in `tz`,
+/// and timestamps rendered as RFC3339 with `tz`'s offset.
+pub fn validate_properties_in_zone(
+    properties: &Value,
+    entry_form: &Value,
+    tz: &SpaceTimeZone,
+) -> Result<(Value, Vec<Value>)> {
     let mut warnings = Vec::new();
     let mut casted = properties.clone();
 
@@ -343,17 +1396,19 @@ pub fn validate_properties(properties: &Value, entry_form: &Value) -> Result<(Va
             .unwrap_or(false);
 
         if required && (value.is_none() || value == Some(Value::String(String::new()))) {
-            warnings.push(serde_json::json!({
-                "code": "missing_field",
-                "field": field_name,
-                "message": format!("Missing required field: {}", field_name)
-            }));
+            warnings.push(i18n::warning(
+                "missing_field",
+                serde_json::json!({"field": field_name}),
+            ));
             continue;
         }
 
         let Some(raw_value) = value else { continue };
 
         let casted_value = match field_type {
+            decimal_type if decimal_type.starts_with("decimal") => DecimalType::parse(decimal_type)
+                .and_then(|decimal| decimal.normalize(&raw_value))
+                .map(Value::String),
             "number" | "double" => match raw_value {
                 Value::Number(_) => Some(raw_value.clone()),
                 Value::String(ref s) => s
@@ -387,8 +1442,8 @@ pub fn validate_properties(properties: &Value, entry_form: &Value) -> Result<(Va
             }
             .map(Value::Number),
             "date" => match raw_value {
-                Value::String(ref s) => NaiveDate::parse_from_str(s, "%Y-%m-%d")
-                    .ok()
+                Value::String(ref s) => tz
+                    .parse_date(s)
                     .map(|d| Value::String(d.format("%Y-%m-%d").to_string())),
                 _ => None,
             },
@@ -397,19 +1452,19 @@ pub fn validate_properties(properties: &Value, entry_form: &Value) -> Result<(Va
                 _ => None,
             },
             "timestamp" => match raw_value {
-                Value::String(ref s) => normalize_timestamp(s).map(Value::String),
+                Value::String(ref s) => normalize_timestamp(s, tz).map(Value::String),
                 _ => None,
             },
             "timestamp_tz" => match raw_value {
-                Value::String(ref s) => normalize_timestamp(s).map(Value::String),
+                Value::String(ref s) => normalize_timestamp(s, tz).map(Value::String),
                 _ => None,
             },
             "timestamp_ns" => match raw_value {
-                Value::String(ref s) => normalize_timestamp_ns(s).map(Value::String),
+                Value::String(ref s) => normalize_timestamp_ns(s, tz).map(Value::String),
                 _ => None,
             },
             "timestamp_tz_ns" => match raw_value {
-                Value::String(ref s) => normalize_timestamp_ns(s).map(Value::String),
+                Value::String(ref s) => normalize_timestamp_ns(s, tz).map(Value::String),
                 _ => None,
             },
             "uuid" => match raw_value {
@@ -433,6 +1488,18 @@ pub fn validate_properties(properties: &Value, entry_form: &Value) -> Result<(Va
                 Value::String(ref s) => parse_boolean(s).map(Value::Bool),
                 _ => None,
             },
+            "url" => match raw_value {
+                Value::String(ref s) => url_field::normalize_url(s).map(Value::String),
+                _ => None,
+            },
+            "asset_ref" => match raw_value {
+                Value::String(ref s) => {
+                    asset::asset_ref_id(s).map(|id| Value::String(format!("ugoite://asset/{}", id)))
+                }
+                _ => None,
+            },
+            "geopoint" => geo::parse_geopoint(&raw_value).map(GeoPoint::to_value),
+            "geojson" => geo::normalize_geojson(&raw_value).map(Value::String),
             "markdown" | "string" | "row_reference" => Some(raw_value.clone()),
             _ => Some(raw_value.clone()),
         };
@@ -442,11 +1509,10 @@ pub fn validate_properties(properties: &Value, entry_form: &Value) -> Result<(Va
                 obj.insert(field_name.clone(), value);
             }
         } else {
-            warnings.push(serde_json::json!({
-                "code": "invalid_type",
-                "field": field_name,
-                "message": format!("Field '{}' has invalid type", field_name)
-            }));
+            warnings.push(i18n::warning(
+                "invalid_type",
+                serde_json::json!({"field": field_name}),
+            ));
         }
     }
 
@@ -537,19 +1603,223 @@ async fn load_forms(op: &Operator, ws_path: &str) -> Result<HashMap<String, Valu
     Ok(forms)
 }
 
+async fn build_form_shard(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+    forms: &HashMap<String, Value>,
+) -> Result<FormShard> {
+    // Read the snapshot before scanning so a concurrent write can only make
+    // the shard look stale, never fresh.
+    let snapshot_id = iceberg_store::entries_snapshot_id(op, ws_path, form_name).await?;
+    let form_def = crate::form::read_form_definition(op, ws_path, form_name).await?;
+    let rows = entry::list_form_entry_rows(op, ws_path, form_name, &form_def).await?;
+    let computed = crate::rollup::read_computed(op, ws_path, form_name).await?;
+    let tz = SpaceTimeZone::for_space(op, ws_path).await?;
+
+    let mut shard = FormShard {
+        form: form_name.to_string(),
+        built_at: entry::now_ts(),
+        snapshot_id,
+        ..Default::default()
+    };
+    for row in rows {
+        if row.deleted {
+            shard
+                .deleted
+                .insert(row.entry_id.clone(), clock::from_micros(row.updated_at));
+            continue;
+        }
+        let entry_computed = computed.get(&row.entry_id);
+        if let Some(record) =
+            build_record(ws_path, form_name, &row, forms, entry_computed, &tz).await?
+        {
+            shard.records.insert(row.entry_id.clone(), record);
+        }
+    }
+    Ok(shard)
+}
+
+async fn load_form_shard(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+    forms: &HashMap<String, Value>,
+    use_shards: bool,
+    projection: Option<&[String]>,
+) -> Result<FormShard> {
+    if use_shards {
+        if let Some(shard) = read_form_shard(op, ws_path, form_name).await? {
+            let current = iceberg_store::entries_snapshot_id(op, ws_path, form_name).await?;
+            if shard.snapshot_id == current {
+                return Ok(shard);
+            }
+        }
+    }
+    match projection {
+        Some(fields) => build_projected_shard(op, ws_path, form_name, fields).await,
+        None => build_form_shard(op, ws_path, form_name, forms).await,
+    }
+}
+
+/// An unsaved shard of `form_name` whose records hold only `fields`, read
+/// without decoding the rest of each row.
+async fn build_projected_shard(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+    fields: &[String],
+) -> Result<FormShard> {
+    let form_def = crate::form::read_form_definition(op, ws_path, form_name).await?;
+    let rows =
+        entry::list_projected_entry_rows(op, ws_path, form_name, &form_def, None, fields).await?;
+    let mut shard = FormShard {
+        form: form_name.to_string(),
+        ..FormShard::default()
+    };
+    for row in rows {
+        if row.deleted {
+            shard
+                .deleted
+                .insert(row.entry_id, clock::from_micros(row.updated_at));
+            continue;
+        }
+        let record = serde_json::json!({
+            "id": row.entry_id.clone(),
+            "title": row.title,
+            "form": form_name,
+            "updated_at": clock::from_micros(row.updated_at),
+            "tags": row.tags,
+            "properties": entry::merge_entry_fields(&row.fields, &row.extra_attributes),
+        });
+        shard.records.insert(row.entry_id, record);
+    }
+    Ok(shard)
+}
+
+/// What a form's index shard knows about one entry id.
+pub(crate) enum IndexedEntry {
+    /// The shard is missing or older than the form's entries table.
+    Stale,
+    Absent,
+    Present {
+        deleted: bool,
+        updated_at: f64,
+    },
+}
+
+/// Looks `entry_id` up in `form_name`'s id list without reading the shard's
+/// records or scanning the table. Callers check [`index_is_compatible`]
+/// first; an id list from another snapshot is stale.
+pub(crate) async fn lookup_indexed_entry(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+    entry_id: &str,
+) -> Result<IndexedEntry> {
+    let Some(list) = read_form_ids(op, ws_path, form_name).await? else {
+        return Ok(IndexedEntry::Stale);
+    };
+    if list.snapshot_id != iceberg_store::entries_snapshot_id(op, ws_path, form_name).await? {
+        return Ok(IndexedEntry::Stale);
+    }
+    Ok(match list.ids.get(entry_id) {
+        Some((deleted, updated_at)) => IndexedEntry::Present {
+            deleted: *deleted,
+            updated_at: *updated_at,
+        },
+        None => IndexedEntry::Absent,
+    })
+}
+
+/// Rollup values are queried like fields, so they are copied into
+/// `properties` alongside the `computed` payload.
+fn merge_computed(properties: &mut Value, computed: &Value) {
+    if let (Some(props), Some(values)) = (properties.as_object_mut(), computed.as_object()) {
+        for (name, value) in values {
+            props.insert(name.clone(), value.clone());
+        }
+    }
+}
+
+/// Updates an entry's rollup values in its form shard. Computed values live
+/// outside the entries table, so a fresh shard would otherwise keep serving
+/// the old ones; stale shards pick them up when rebuilt.
+pub(crate) async fn patch_indexed_computed(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+    entry_id: &str,
+    computed: &Map<String, Value>,
+) -> Result<()> {
+    let Some(mut shard) = read_form_shard(op, ws_path, form_name).await? else {
+        return Ok(());
+    };
+    if shard.snapshot_id != iceberg_store::entries_snapshot_id(op, ws_path, form_name).await? {
+        return Ok(());
+    }
+    let Some(record) = shard.records.get_mut(entry_id) else {
+        return Ok(());
+    };
+    let computed = Value::Object(computed.clone());
+    if let Some(properties) = record.get_mut("properties") {
+        merge_computed(properties, &computed);
+    }
+    record["computed"] = computed;
+    write_form_shard(op, ws_path, &shard).await
+}
+
 async fn collect_entries(
     op: &Operator,
     ws_path: &str,
     forms: &HashMap<String, Value>,
+    form_scope: Option<&[String]>,
+    use_shards: bool,
+    projection: Option<&[String]>,
 ) -> Result<Map<String, Value>> {
-    let mut entries = Map::new();
-    let rows = entry::list_entry_rows(op, ws_path).await?;
-    for (form_name, row) in rows {
-        if let Some(record) = build_record(ws_path, &form_name, &row, forms).await? {
-            entries.insert(row.entry_id.clone(), record);
+    let form_names = match form_scope {
+        Some(scope) => scope.to_vec(),
+        None => crate::form::list_form_names(op, ws_path).await?,
+    };
+
+    // Keep the newest row per entry across forms, mirroring list_entry_rows.
+    let mut latest: HashMap<String, (f64, Option<Value>)> = HashMap::new();
+    for form_name in form_names {
+        if form_scope.is_some() && !forms.contains_key(&form_name) {
+            continue;
+        }
+        let shard = load_form_shard(op, ws_path, &form_name, forms, use_shards, projection).await?;
+        let candidates = shard
+            .records
+            .into_iter()
+            .map(|(id, record)| {
+                let updated_at = record
+                    .get("updated_at")
+                    .and_then(|v| v.as_f64())
+                    .unwrap_or(0.0);
+                (id, updated_at, Some(record))
+            })
+            .chain(
+                shard
+                    .deleted
+                    .into_iter()
+                    .map(|(id, updated_at)| (id, updated_at, None)),
+            );
+        for (entry_id, updated_at, record) in candidates {
+            let replace = match latest.get(&entry_id) {
+                Some((existing, _)) => updated_at >= *existing,
+                None => true,
+            };
+            if replace {
+                latest.insert(entry_id, (updated_at, record));
+            }
         }
     }
-    Ok(entries)
+
+    Ok(latest
+        .into_iter()
+        .filter_map(|(entry_id, (_, record))| record.map(|record| (entry_id, record)))
+        .collect())
 }
 
 async fn build_record(
@@ -557,6 +1827,8 @@ async fn build_record(
     form_name: &str,
     row: &entry::EntryRow,
     forms: &HashMap<String, Value>,
+    computed: Option<&Value>,
+    tz: &SpaceTimeZone,
 ) -> Result<Option<Value>> {
     if row.deleted {
         return Ok(None);
@@ -565,18 +1837,22 @@ async fn build_record(
     let mut warnings = Vec::new();
     let mut properties = entry::merge_entry_fields(&row.fields, &row.extra_attributes);
     if let Some(form_def) = forms.get(form_name) {
-        if let Ok((casted, warns)) = validate_properties(&properties, form_def) {
+        if let Ok((casted, warns)) = validate_properties_in_zone(&properties, form_def, tz) {
             properties = casted;
             warnings = warns;
         }
     }
+    let computed = computed
+        .cloned()
+        .unwrap_or_else(|| Value::Object(Map::new()));
+    merge_computed(&mut properties, &computed);
 
     let word_count = compute_word_count(&serde_json::to_string(&properties)?);
     let record = serde_json::json!({
         "id": row.entry_id,
         "title": row.title,
         "form": form_name,
-        "updated_at": row.updated_at,
+        "updated_at": clock::from_micros(row.updated_at),
         "space_id": ws_path.split('/').next_back().unwrap_or("").to_string(),
         "properties": properties,
         "word_count": word_count,
@@ -585,17 +1861,17 @@ async fn build_record(
         "assets": row.assets,
         "checksum": row.integrity.checksum,
         "validation_warnings": Value::Array(warnings),
+        "computed": computed,
+        "archived": row.archived,
     });
 
     Ok(Some(record))
 }
 
-async fn build_sql_tables(
-    op: &Operator,
-    ws_path: &str,
+fn build_sql_tables(
     forms: &HashMap<String, Value>,
     entries_map: &Map<String, Value>,
-) -> Result<HashMap<String, Vec<Value>>> {
+) -> HashMap<String, Vec<Value>> {
     let mut tables: HashMap<String, Vec<Value>> = HashMap::new();
     tables.insert(
         "entries".to_string(),
@@ -625,29 +1901,33 @@ async fn build_sql_tables(
     }
 
     let mut asset_rows = Vec::new();
-    let entry_rows = entry::list_entry_rows(op, ws_path).await?;
     let mut link_rows = Vec::new();
-    for (_form_name, row) in entry_rows {
-        if row.deleted {
-            continue;
-        }
-        for link_item in row.links {
-            let source_form = entry_form_map.get(&link_item.source).cloned();
-            let target_form = entry_form_map.get(&link_item.target).cloned();
+    for (entry_id, record) in entries_map {
+        let links = record.get("links").and_then(|v| v.as_array());
+        for link_item in links.into_iter().flatten() {
+            let source = link_item
+                .get("source")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            let target = link_item
+                .get("target")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
             link_rows.push(serde_json::json!({
-                "id": link_item.id,
-                "source": link_item.source,
-                "target": link_item.target,
-                "kind": link_item.kind,
-                "source_form": source_form,
-                "target_form": target_form,
+                "id": link_item.get("id").cloned().unwrap_or(Value::Null),
+                "source": source,
+                "target": target,
+                "kind": link_item.get("kind").cloned().unwrap_or(Value::Null),
+                "source_form": entry_form_map.get(source).cloned(),
+                "target_form": entry_form_map.get(target).cloned(),
             }));
         }
-        for asset in row.assets {
+        let assets = record.get("assets").and_then(|v| v.as_array());
+        for asset in assets.into_iter().flatten() {
             if let Some(obj) = asset.as_object() {
                 asset_rows.push(serde_json::json!({
                     "id": obj.get("id").cloned().unwrap_or(Value::Null),
-                    "entry_id": row.entry_id,
+                    "entry_id": entry_id,
                     "name": obj.get("name").cloned().unwrap_or(Value::Null),
                     "path": obj.get("path").cloned().unwrap_or(Value::Null),
                 }));
@@ -657,5 +1937,5 @@ async fn build_sql_tables(
     tables.insert("links".to_string(), link_rows);
     tables.insert("assets".to_string(), asset_rows);
 
-    Ok(tables)
+    tables
 }
@@ -3,13 +3,137 @@ use base64::{engine::general_purpose, Engine as _};
 use chrono::Utc;
 use hmac::{Hmac, Mac};
 use opendal::Operator;
-use rand::RngExt;
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair};
 use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
+use crate::storage;
+
 pub trait IntegrityProvider {
     fn checksum(&self, content: &str) -> String;
     fn signature(&self, content: &str) -> String;
+
+    /// Algorithm for checksums of raw bytes, such as asset digests.
+    fn checksum_algorithm(&self) -> ChecksumAlgorithm {
+        ChecksumAlgorithm::default()
+    }
+
+    /// Whether `recorded` is the checksum of `content`, computed with the
+    /// algorithm `recorded` was made with.
+    fn checksum_matches(&self, recorded: &str, content: &str) -> bool {
+        self.checksum(content) == recorded
+    }
+}
+
+/// Hash behind a checksum, recorded with it as `{tag}:{hex}`. Checksums
+/// recorded before tagging have no prefix and are SHA-256, so they stay
+/// verifiable when the default changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChecksumAlgorithm {
+    Sha256,
+    #[default]
+    Blake3,
+}
+
+impl ChecksumAlgorithm {
+    pub fn tag(self) -> &'static str {
+        match self {
+            ChecksumAlgorithm::Sha256 => "sha256",
+            ChecksumAlgorithm::Blake3 => "blake3",
+        }
+    }
+
+    pub fn from_tag(tag: &str) -> Result<Self> {
+        match tag {
+            "sha256" => Ok(ChecksumAlgorithm::Sha256),
+            "blake3" => Ok(ChecksumAlgorithm::Blake3),
+            other => Err(anyhow!("Unsupported checksum algorithm: {}", other)),
+        }
+    }
+
+    /// The algorithm and hex digest of a recorded checksum.
+    pub fn split(recorded: &str) -> Result<(Self, &str)> {
+        match recorded.split_once(':') {
+            Some((tag, digest)) => Ok((Self::from_tag(tag)?, digest)),
+            None => Ok((ChecksumAlgorithm::Sha256, recorded)),
+        }
+    }
+
+    /// Tagged checksum of `content`.
+    pub fn checksum(self, content: &[u8]) -> String {
+        let digest = match self {
+            ChecksumAlgorithm::Sha256 => hex::encode(Sha256::digest(content)),
+            ChecksumAlgorithm::Blake3 => blake3::hash(content).to_hex().to_string(),
+        };
+        format!("{}:{}", self.tag(), digest)
+    }
+
+    /// Whether `recorded`, tagged or not, is the checksum of `content`.
+    /// Unknown tags never match.
+    pub fn matches(recorded: &str, content: &[u8]) -> bool {
+        match Self::split(recorded) {
+            Ok((algorithm, digest)) => {
+                algorithm.checksum(content) == format!("{}:{}", algorithm.tag(), digest)
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+/// An entry whose stored content no longer matches the checksum and
+/// signature recorded when it was written.
+#[derive(Debug, thiserror::Error)]
+pub enum IntegrityError {
+    #[error(
+        "Integrity check failed for entry {entry_id} at revision {revision_id}: \
+         checksum {actual} does not match recorded {expected}"
+    )]
+    ChecksumMismatch {
+        entry_id: String,
+        revision_id: String,
+        expected: String,
+        actual: String,
+    },
+    #[error(
+        "Integrity check failed for entry {entry_id} at revision {revision_id}: \
+         signature does not match"
+    )]
+    SignatureMismatch {
+        entry_id: String,
+        revision_id: String,
+    },
+    #[error("Integrity check failed for asset {asset_id}: checksum {actual} does not match recorded {expected}")]
+    AssetChecksumMismatch {
+        asset_id: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("Integrity check failed for asset {asset_id}: signature does not match")]
+    AssetSignatureMismatch { asset_id: String },
+    #[error("Integrity check failed for asset {asset_id}: no digest was recorded at upload")]
+    AssetUnrecorded { asset_id: String },
+}
+
+/// Whether `settings` turn on strict reads, which verify every entry read
+/// against its recorded integrity: `{"integrity": {"strict_reads": true}}`.
+pub fn strict_reads(settings: &serde_json::Value) -> bool {
+    settings
+        .pointer("/integrity/strict_reads")
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(false)
+}
+
+/// Algorithm new checksums of a space are made with:
+/// `{"integrity": {"checksum_algorithm": "sha256"}}`, BLAKE3 by default.
+pub fn checksum_algorithm(settings: &serde_json::Value) -> Result<ChecksumAlgorithm> {
+    match settings
+        .pointer("/integrity/checksum_algorithm")
+        .and_then(serde_json::Value::as_str)
+    {
+        Some(tag) => ChecksumAlgorithm::from_tag(tag),
+        None => Ok(ChecksumAlgorithm::default()),
+    }
 }
 
 pub struct FakeIntegrityProvider;
@@ -25,24 +149,41 @@ impl IntegrityProvider for FakeIntegrityProvider {
 
 pub struct RealIntegrityProvider {
     secret: Vec<u8>,
+    algorithm: ChecksumAlgorithm,
 }
 
 impl RealIntegrityProvider {
     pub fn new(secret: Vec<u8>) -> Self {
-        Self { secret }
+        Self {
+            secret,
+            algorithm: ChecksumAlgorithm::default(),
+        }
     }
 
-    pub async fn from_space(op: &Operator, _space_name: &str) -> Result<Self> {
-        let (_key_id, secret) = load_hmac_material(op, _space_name).await?;
-        Ok(Self::new(secret))
+    pub fn with_algorithm(mut self, algorithm: ChecksumAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    pub async fn from_space(op: &Operator, space_name: &str) -> Result<Self> {
+        let (_key_id, secret) = load_hmac_material(op, space_name).await?;
+        let settings =
+            crate::space::read_space_settings(op, &format!("spaces/{}", space_name)).await?;
+        Ok(Self::new(secret).with_algorithm(checksum_algorithm(&settings)?))
     }
 }
 
 impl IntegrityProvider for RealIntegrityProvider {
     fn checksum(&self, content: &str) -> String {
-        let mut hasher = Sha256::new();
-        hasher.update(content.as_bytes());
-        hex::encode(hasher.finalize())
+        self.algorithm.checksum(content.as_bytes())
+    }
+
+    fn checksum_algorithm(&self) -> ChecksumAlgorithm {
+        self.algorithm
+    }
+
+    fn checksum_matches(&self, recorded: &str, content: &str) -> bool {
+        ChecksumAlgorithm::matches(recorded, content.as_bytes())
     }
 
     fn signature(&self, content: &str) -> String {
@@ -54,33 +195,26 @@ impl IntegrityProvider for RealIntegrityProvider {
     }
 }
 
+/// Gives the space an HMAC key if it has none. The key is added with a
+/// conditional write, so when two first requests race the loser re-reads
+/// and keeps the winner's key instead of replacing it.
 async fn ensure_space_hmac(op: &Operator, space_name: &str) -> Result<()> {
     let meta_path = format!("spaces/{}/meta.json", space_name);
-    if !op.exists(&meta_path).await? {
-        return Err(anyhow!("Space not found: {}", space_name));
+    loop {
+        let (meta, condition) = storage::read_json_versioned(op, &meta_path).await?;
+        let mut meta = meta.ok_or_else(|| anyhow!("Space not found: {}", space_name))?;
+        if meta_str(&meta, "hmac_key").is_some_and(|key| !key.is_empty()) {
+            return Ok(());
+        }
+        let (key_id, hmac_key, last_rotation) = crate::space::generate_hmac_material();
+        meta["hmac_key_id"] = serde_json::Value::String(key_id);
+        meta["hmac_key"] = serde_json::Value::String(hmac_key);
+        meta["last_rotation"] = serde_json::Value::String(last_rotation);
+        match storage::write_json_conditional(op, &meta_path, &meta, &condition).await {
+            Err(err) if storage::is_write_conflict(&err) => continue,
+            result => return result,
+        }
     }
-    let bytes = op.read(&meta_path).await?;
-    let mut meta: serde_json::Value = serde_json::from_slice(&bytes.to_vec())?;
-    let has_key = meta
-        .get("hmac_key")
-        .and_then(|v| v.as_str())
-        .map(|v| !v.is_empty())
-        .unwrap_or(false);
-    if has_key {
-        return Ok(());
-    }
-    let mut key_bytes = [0u8; 32];
-    rand::rng().fill(&mut key_bytes);
-    let hmac_key = general_purpose::STANDARD.encode(key_bytes);
-    let key_id = format!("key-{}", Uuid::new_v4().simple());
-
-    meta["hmac_key_id"] = serde_json::Value::String(key_id);
-    meta["hmac_key"] = serde_json::Value::String(hmac_key);
-    meta["last_rotation"] = serde_json::Value::String(Utc::now().to_rfc3339());
-
-    op.write(&meta_path, serde_json::to_vec_pretty(&meta)?)
-        .await?;
-    Ok(())
 }
 
 pub async fn load_hmac_material(op: &Operator, space_name: &str) -> Result<(String, Vec<u8>)> {
@@ -104,20 +238,75 @@ pub async fn load_hmac_material(op: &Operator, space_name: &str) -> Result<(Stri
     Ok((key_id, secret))
 }
 
+/// Loads the space's Ed25519 signing key, which signs export manifests,
+/// creating it on first use. Returns its key id (derived from the public key)
+/// and the key pair. Verifiers only need [`load_signing_public_key`].
+pub async fn load_signing_key(op: &Operator, space_name: &str) -> Result<(String, Ed25519KeyPair)> {
+    let meta_path = format!("spaces/{}/meta.json", space_name);
+    loop {
+        let (meta, condition) = storage::read_json_versioned(op, &meta_path).await?;
+        let mut meta = meta.ok_or_else(|| anyhow!("Space not found: {}", space_name))?;
+        if let Some(pkcs8) = meta.get("signing_key").and_then(|v| v.as_str()) {
+            let pkcs8 = general_purpose::STANDARD.decode(pkcs8)?;
+            let key_pair = Ed25519KeyPair::from_pkcs8(&pkcs8)
+                .map_err(|_| anyhow!("signing_key in space meta.json is not an Ed25519 key"))?;
+            return Ok((signing_key_id(key_pair.public_key().as_ref()), key_pair));
+        }
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+            .map_err(|_| anyhow!("Failed to generate a signing key"))?;
+        meta["signing_key"] = serde_json::Value::String(general_purpose::STANDARD.encode(pkcs8));
+        // A concurrent creator wins; use its key.
+        match storage::write_json_conditional(op, &meta_path, &meta, &condition).await {
+            Err(err) if storage::is_write_conflict(&err) => continue,
+            result => result?,
+        }
+    }
+}
+
+/// The key id and raw 32-byte public key of the space's signing key.
+pub async fn load_signing_public_key(op: &Operator, space_name: &str) -> Result<(String, Vec<u8>)> {
+    let (key_id, key_pair) = load_signing_key(op, space_name).await?;
+    Ok((key_id, key_pair.public_key().as_ref().to_vec()))
+}
+
+fn signing_key_id(public_key: &[u8]) -> String {
+    hex::encode(&Sha256::digest(public_key)[..8])
+}
+
+/// The storage-wide key that signs API responses, created on first use.
+/// Creation is create-only: a request that loses the race reads the key the
+/// winner wrote.
 pub async fn load_response_hmac_material(op: &Operator) -> Result<(String, Vec<u8>)> {
     let path = "hmac.json";
-    if !op.exists(path).await? {
-        let mut key_bytes = [0u8; 32];
-        rand::rng().fill(&mut key_bytes);
-        let payload = serde_json::json!({
-            "hmac_key_id": format!("key-{}", Uuid::new_v4().simple()),
-            "hmac_key": general_purpose::STANDARD.encode(key_bytes),
-            "last_rotation": Utc::now().to_rfc3339(),
-        });
-        op.write(path, serde_json::to_vec_pretty(&payload)?).await?;
-    }
-    let bytes = op.read(path).await?;
-    let payload: serde_json::Value = serde_json::from_slice(&bytes.to_vec())?;
+    let (payload, _) = storage::read_json_versioned(op, path).await?;
+    let payload = match payload {
+        Some(payload) => payload,
+        None => {
+            let (key_id, hmac_key, last_rotation) = crate::space::generate_hmac_material();
+            let payload = serde_json::json!({
+                "hmac_key_id": key_id,
+                "hmac_key": hmac_key,
+                "last_rotation": last_rotation,
+            });
+            match storage::write_json_conditional(
+                op,
+                path,
+                &payload,
+                &storage::WriteCondition::Absent,
+            )
+            .await
+            {
+                Ok(()) => payload,
+                Err(err) if storage::is_write_conflict(&err) => {
+                    storage::read_json_versioned(op, path)
+                        .await?
+                        .0
+                        .ok_or_else(|| anyhow!("hmac.json vanished after a write conflict"))?
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    };
     let key_b64 = payload
         .get("hmac_key")
         .and_then(|v| v.as_str())
@@ -139,3 +328,411 @@ pub async fn build_response_signature(op: &Operator, body: &[u8]) -> Result<(Str
     let signature = hex::encode(mac.finalize().into_bytes());
     Ok((key_id, signature))
 }
+
+/// Serializes JSON per RFC 8785 (JCS) so both ends sign identical bytes:
+/// object keys sorted by UTF-16 code units, no insignificant whitespace, and
+/// numbers written the way ECMAScript's `Number.prototype.toString` writes
+/// them (`1.0` -> `1`, `1e16` -> `10000000000000000`, `1e21` -> `1e+21`).
+pub fn canonical_json(value: &serde_json::Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &serde_json::Value, out: &mut String) {
+    use serde_json::Value;
+    match value {
+        Value::Number(n) => match n.as_f64() {
+            Some(f) => write_jcs_number(f, out),
+            None => out.push_str(&n.to_string()),
+        },
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort_by(|a, b| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(&map[key], out);
+            }
+            out.push('}');
+        }
+        _ => out.push_str(&value.to_string()),
+    }
+}
+
+/// Writes `f` as ECMAScript's Number-to-String does (RFC 8785 section
+/// 3.2.2.3). `{:e}` finds the shortest round-trip digit count; formatting
+/// again at that precision picks the closest digits with ties to even, as
+/// ECMAScript does where `{:e}` alone would round ties up.
+fn write_jcs_number(f: f64, out: &mut String) {
+    if f == 0.0 || !f.is_finite() {
+        out.push('0');
+        return;
+    }
+    if f < 0.0 {
+        out.push('-');
+    }
+    let shortest = format!("{:e}", f.abs());
+    let precision = shortest
+        .split_once('e')
+        .map_or(0, |(m, _)| m.len().saturating_sub(2));
+    let sci = format!("{:.*e}", precision, f.abs());
+    let (mantissa, exponent) = sci.split_once('e').unwrap_or((&sci, "0"));
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let k = digits.len() as i32;
+    // The value is 0.{digits} * 10^n.
+    let n = exponent.parse::<i32>().unwrap_or(0) + 1;
+    if k <= n && n <= 21 {
+        out.push_str(&digits);
+        out.extend(std::iter::repeat_n('0', (n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        out.push_str(&digits[..n as usize]);
+        out.push('.');
+        out.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        out.push_str("0.");
+        out.extend(std::iter::repeat_n('0', (-n) as usize));
+        out.push_str(&digits);
+    } else {
+        out.push_str(&digits[..1]);
+        if k > 1 {
+            out.push('.');
+            out.push_str(&digits[1..]);
+        }
+        out.push('e');
+        out.push(if n - 1 < 0 { '-' } else { '+' });
+        out.push_str(&(n - 1).abs().to_string());
+    }
+}
+
+/// A response signature over canonical JSON, bound to a time and a nonce.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ResponseSignature {
+    pub key_id: String,
+    pub signature: String,
+    /// Unix seconds at signing time.
+    pub timestamp: i64,
+    pub nonce: String,
+}
+
+/// The exact bytes signed: `"{timestamp}\n{nonce}\n{canonical_json(body)}"`.
+pub fn response_signing_payload(body: &serde_json::Value, timestamp: i64, nonce: &str) -> Vec<u8> {
+    format!("{}\n{}\n{}", timestamp, nonce, canonical_json(body)).into_bytes()
+}
+
+/// Signs a JSON response body with the response HMAC key, a fresh nonce and
+/// the current time. Unlike [`build_response_signature`], field order and
+/// number formatting in `body` do not affect the signature.
+pub async fn sign_canonical_response(
+    op: &Operator,
+    body: &serde_json::Value,
+) -> Result<ResponseSignature> {
+    let (key_id, secret) = load_response_hmac_material(op).await?;
+    let timestamp = Utc::now().timestamp();
+    let nonce = Uuid::new_v4().simple().to_string();
+    type HmacSha256 = Hmac<Sha256>;
+    let mut mac = HmacSha256::new_from_slice(&secret)?;
+    mac.update(&response_signing_payload(body, timestamp, &nonce));
+    Ok(ResponseSignature {
+        key_id,
+        signature: hex::encode(mac.finalize().into_bytes()),
+        timestamp,
+        nonce,
+    })
+}
+
+/// Checks a signature made by [`sign_canonical_response`].
+///
+/// Fails closed: an unknown key id, a timestamp more than `max_skew_secs`
+/// away from now, or a mismatched HMAC all return `false`. Rejecting reused
+/// nonces is left to the caller, which owns the replay window.
+pub async fn verify_response_signature(
+    op: &Operator,
+    body: &serde_json::Value,
+    signature: &ResponseSignature,
+    max_skew_secs: i64,
+) -> Result<bool> {
+    let (key_id, secret) = load_response_hmac_material(op).await?;
+    if signature.key_id != key_id
+        || (Utc::now().timestamp() - signature.timestamp).abs() > max_skew_secs
+    {
+        return Ok(false);
+    }
+    let Ok(expected) = hex::decode(&signature.signature) else {
+        return Ok(false);
+    };
+    type HmacSha256 = Hmac<Sha256>;
+    let mut mac = HmacSha256::new_from_slice(&secret)?;
+    mac.update(&response_signing_payload(
+        body,
+        signature.timestamp,
+        &signature.nonce,
+    ));
+    Ok(mac.verify_slice(&expected).is_ok())
+}
+
+fn merkle_parent(left: &[u8], right: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().to_vec()
+}
+
+/// Merkle root over a space's live entries (id, checksum and signature of
+/// the current revision) and assets (id and recorded digest), signed with
+/// the space key. Leaves are sorted by kind and id; an odd node is carried
+/// up unchanged. Two copies of a space agree on the root exactly when their
+/// entries and recorded asset digests agree; [`crate::asset::verify_asset`]
+/// checks the bytes against those digests.
+pub async fn space_merkle_root<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    integrity: &I,
+) -> Result<serde_json::Value> {
+    let mut leaves: Vec<String> = Vec::new();
+    let mut entries = 0;
+    for (_, row) in crate::entry::list_entry_rows(op, ws_path).await? {
+        if row.deleted {
+            continue;
+        }
+        entries += 1;
+        leaves.push(format!(
+            "entry\n{}\n{}\n{}",
+            row.entry_id, row.integrity.checksum, row.integrity.signature
+        ));
+    }
+    let assets = crate::asset::read_asset_integrity(op, ws_path).await?;
+    for (asset_id, record) in &assets {
+        leaves.push(format!("asset\n{}\n{}", asset_id, record.checksum));
+    }
+    leaves.sort();
+
+    let mut level: Vec<Vec<u8>> = leaves
+        .iter()
+        .map(|leaf| Sha256::digest(leaf.as_bytes()).to_vec())
+        .collect();
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => merkle_parent(left, right),
+                _ => pair[0].clone(),
+            })
+            .collect();
+    }
+    let root = hex::encode(level.pop().unwrap_or_else(|| Sha256::digest(b"").to_vec()));
+    Ok(serde_json::json!({
+        "root": root,
+        "signature": integrity.signature(&root),
+        "entries": entries,
+        "assets": assets.len(),
+    }))
+}
+
+const RESIGN_JOB_FILE: &str = "resign_job.json";
+
+/// Progress of [`resign_space`], kept in `resign_job.json`.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+struct ResignJob {
+    key_id: String,
+    started_at: f64,
+    forms_done: Vec<String>,
+    assets_done: bool,
+    finished_at: Option<f64>,
+}
+
+async fn update_resign_job(
+    op: &Operator,
+    job_path: &str,
+    apply: impl Fn(&mut ResignJob),
+) -> Result<()> {
+    crate::storage::update_json(op, job_path, |current| {
+        let mut job: ResignJob = match current {
+            Some(value) => serde_json::from_value(value)?,
+            None => return Err(anyhow!("Re-signing job not found: {}", job_path)),
+        };
+        apply(&mut job);
+        Ok((serde_json::to_value(&job)?, ()))
+    })
+    .await
+}
+
+/// Re-signs every entry row, revision and recorded asset digest of a space
+/// with `provider`, e.g. under a new key or algorithm identified by `key_id`.
+///
+/// The job is resumable: each finished form is recorded in
+/// `resign_job.json`, and calling again with the same `key_id` after an
+/// interruption skips it. A job for a different key must finish first.
+/// Author signatures cover the old signatures and are not carried over.
+pub async fn resign_space<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    provider: &I,
+    key_id: &str,
+) -> Result<serde_json::Value> {
+    let job_path = format!("{}/{}", ws_path.trim_end_matches('/'), RESIGN_JOB_FILE);
+    let started_at = crate::entry::now_ts();
+    let (job, resumed) = crate::storage::update_json(op, &job_path, |current| {
+        let existing: Option<ResignJob> = current.map(serde_json::from_value).transpose()?;
+        let (job, resumed) = match existing {
+            Some(job) if job.finished_at.is_none() && job.key_id == key_id => (job, true),
+            Some(job) if job.finished_at.is_none() => {
+                return Err(anyhow!(
+                    "Re-signing with key {} is still in progress",
+                    job.key_id
+                ))
+            }
+            _ => (
+                ResignJob {
+                    key_id: key_id.to_string(),
+                    started_at,
+                    ..Default::default()
+                },
+                false,
+            ),
+        };
+        Ok((serde_json::to_value(&job)?, (job, resumed)))
+    })
+    .await?;
+
+    let mut forms = 0;
+    let mut entries = 0;
+    let mut revisions = 0;
+    for form_name in crate::form::list_form_names(op, ws_path).await? {
+        if job.forms_done.contains(&form_name) {
+            continue;
+        }
+        let (signed_entries, signed_revisions) =
+            crate::entry::resign_form(op, ws_path, &form_name, provider).await?;
+        forms += 1;
+        entries += signed_entries;
+        revisions += signed_revisions;
+        update_resign_job(op, &job_path, |job| job.forms_done.push(form_name.clone())).await?;
+    }
+    let assets = if job.assets_done {
+        0
+    } else {
+        let signed = crate::asset::resign_asset_integrity(op, ws_path, provider).await?;
+        update_resign_job(op, &job_path, |job| job.assets_done = true).await?;
+        signed
+    };
+    // Index records carry entry checksums.
+    crate::index::reindex_all(op, ws_path).await?;
+    let finished_at = crate::entry::now_ts();
+    update_resign_job(op, &job_path, |job| job.finished_at = Some(finished_at)).await?;
+
+    Ok(serde_json::json!({
+        "key_id": key_id,
+        "resumed": resumed,
+        "forms": forms,
+        "entries": entries,
+        "revisions": revisions,
+        "assets": assets,
+    }))
+}
+
+fn meta_str(meta: &serde_json::Value, key: &str) -> Option<String> {
+    meta.get(key)
+        .and_then(|v| v.as_str())
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+/// Name of the space secret holding a retired HMAC key.
+pub fn escrow_secret_name(key_id: &str) -> String {
+    format!("escrow_{}", key_id.replace('-', "_"))
+}
+
+/// Replaces a space's HMAC key and re-signs the space under the new one.
+///
+/// The new key is staged in `meta.json` as `pending_hmac_key` and the old
+/// key escrowed in the space secret [`escrow_secret_name`] before anything
+/// is re-signed, so a rotation that stops part way resumes with the same
+/// key when called again. The new key becomes current once
+/// [`resign_space`] finishes.
+///
+/// Escrow goes through the secrets store, so rotation needs its master key
+/// ([`crate::secrets::MASTER_KEY_ENV`], or the stored-key opt-in). Without
+/// one it fails before anything is staged.
+pub async fn rotate_space_key(
+    op: &Operator,
+    space_name: &str,
+    actor: &str,
+) -> Result<serde_json::Value> {
+    ensure_space_hmac(op, space_name).await?;
+    crate::secrets::require_master_key(op).await.map_err(|e| {
+        anyhow!(
+            "Key rotation escrows the retired key as a space secret. {}",
+            e
+        )
+    })?;
+    let ws_path = format!("spaces/{}", space_name);
+    let meta_path = format!("{}/meta.json", ws_path);
+    let (previous_key_id, previous_key, key_id, key) =
+        crate::storage::update_json(op, &meta_path, |current| {
+            let mut meta = current.ok_or_else(|| anyhow!("Space not found: {}", space_name))?;
+            if meta_str(&meta, "pending_hmac_key").is_none() {
+                let (key_id, key, _) = crate::space::generate_hmac_material();
+                meta["pending_hmac_key_id"] = serde_json::Value::String(key_id);
+                meta["pending_hmac_key"] = serde_json::Value::String(key);
+            }
+            let staged = (
+                meta_str(&meta, "hmac_key_id").unwrap_or_else(|| "default".to_string()),
+                meta_str(&meta, "hmac_key")
+                    .ok_or_else(|| anyhow!("hmac_key missing in space meta.json"))?,
+                meta_str(&meta, "pending_hmac_key_id")
+                    .ok_or_else(|| anyhow!("pending_hmac_key_id missing in space meta.json"))?,
+                meta_str(&meta, "pending_hmac_key").unwrap_or_default(),
+            );
+            Ok((meta, staged))
+        })
+        .await?;
+
+    let escrow_secret = escrow_secret_name(&previous_key_id);
+    crate::secrets::set_secret(op, &ws_path, &escrow_secret, &previous_key, actor).await?;
+    let provider = RealIntegrityProvider::new(general_purpose::STANDARD.decode(&key)?);
+    let mut report = resign_space(op, &ws_path, &provider, &key_id).await?;
+
+    let rotated_at = Utc::now().to_rfc3339();
+    crate::storage::update_json(op, &meta_path, |current| {
+        let mut meta = current.ok_or_else(|| anyhow!("Space not found: {}", space_name))?;
+        if meta_str(&meta, "pending_hmac_key_id").as_deref() == Some(key_id.as_str()) {
+            meta["hmac_key_id"] = serde_json::Value::String(key_id.clone());
+            meta["hmac_key"] = serde_json::Value::String(key.clone());
+            meta["last_rotation"] = serde_json::Value::String(rotated_at.clone());
+            if let Some(fields) = meta.as_object_mut() {
+                fields.remove("pending_hmac_key_id");
+                fields.remove("pending_hmac_key");
+            }
+        }
+        Ok((meta, ()))
+    })
+    .await?;
+    crate::audit::record_audit_event(
+        op,
+        &ws_path,
+        "integrity.key_rotated",
+        actor,
+        serde_json::json!({"previous_key_id": previous_key_id, "key_id": key_id}),
+    )
+    .await?;
+
+    report["previous_key_id"] = serde_json::Value::String(previous_key_id);
+    report["escrow_secret"] = serde_json::Value::String(escrow_secret);
+    Ok(report)
+}
@@ -2,30 +2,64 @@
 #![deny(clippy::all)]
 
 use opendal::Operator;
-use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::exceptions::{PyRuntimeError, PyStopAsyncIteration, PyValueError};
 use pyo3::prelude::*;
 use pyo3::types::{PyBytes, PyDict, PyList, PyTuple};
 use pyo3::IntoPyObjectExt;
 use serde_json::Value;
+use std::sync::Arc;
 
+pub mod access_log;
 pub mod asset;
+pub mod audit;
+pub mod author_keys;
+pub mod board;
+pub mod clock;
+pub mod decimal;
+pub mod duplicate;
+pub mod email;
 pub mod entry;
+pub mod entry_id;
+pub mod export;
+pub mod extraction;
+pub mod favorites;
+pub mod folder;
 pub mod form;
+pub mod geo;
+pub mod hooks;
+pub mod i18n;
 pub mod iceberg_store;
 pub mod index;
 pub mod integrity;
 pub mod link;
 pub mod materialized_view;
+pub mod merge;
 pub mod metadata;
+pub mod notion;
+pub mod pii;
+pub mod progress;
+pub mod reminder;
+pub mod replica;
+pub mod retention;
+pub mod rollup;
 pub mod sample_data;
 pub mod saved_sql;
+pub mod schedule;
 pub mod search;
+pub mod secrets;
 pub mod space;
 pub mod sql;
 pub mod sql_session;
 pub mod storage;
+pub mod template;
+pub mod timezone;
+pub mod transaction;
+pub mod trash;
+pub mod url_field;
 
+use i18n::Locale;
 use integrity::RealIntegrityProvider;
+use timezone::SpaceTimeZone;
 
 // --- Helpers ---
 
@@ -38,6 +72,96 @@ fn get_operator(_py: Python<'_>, config: &Bound<'_, PyDict>) -> PyResult<Operato
     storage::operator_from_uri(&uri).map_err(|e| PyValueError::new_err(e.to_string()))
 }
 
+/// The request id for a binding call: the storage config's `request_id` when
+/// the caller passes one, else a new one.
+fn request_id(config: &Bound<'_, PyDict>) -> PyResult<String> {
+    match config.get_item("request_id")? {
+        Some(id) if !id.is_none() => id.extract(),
+        _ => Ok(storage::new_request_id()),
+    }
+}
+
+/// [`pyo3_async_runtimes::tokio::future_into_py`] run as request
+/// `request_id`, named after the binding it is called from. Errors carry the
+/// id as `request_id` and are traced before they reach Python.
+fn future_into_py<F, T>(py: Python<'_>, request_id: String, fut: F) -> PyResult<Bound<'_, PyAny>>
+where
+    F: std::future::Future<Output = PyResult<T>> + Send + 'static,
+    T: for<'py> IntoPyObject<'py> + Send + 'static,
+{
+    // The future is an `async` block in the binding, so its type name ends
+    // in `<binding>::{{closure}}`. Only used for diagnostics.
+    let operation = std::any::type_name::<F>()
+        .split("::")
+        .filter(|segment| !segment.starts_with('{'))
+        .last()
+        .unwrap_or_default();
+    let traced = storage::with_request_id(request_id.clone(), operation, async move {
+        let result = fut.await;
+        if let Err(err) = &result {
+            tracing::warn!(error = %err, "request failed");
+            let _ = Python::with_gil(|py| err.value(py).setattr("request_id", &request_id));
+        }
+        result
+    });
+    pyo3_async_runtimes::tokio::future_into_py(py, traced)
+}
+
+/// Resolves the locale for localized messages: `locale` when given, else the
+/// space's `locale` setting.
+async fn resolve_locale(op: &Operator, ws_path: &str, locale: Option<&str>) -> PyResult<Locale> {
+    match locale {
+        Some(tag) => tag.parse(),
+        None => Locale::for_space(op, ws_path).await,
+    }
+    .map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// Maps a core error to RuntimeError. Coded errors keep their English text
+/// and also carry `code`, `params` and `localized_message`.
+fn localized_error(err: anyhow::Error, locale: Locale) -> PyErr {
+    let py_err = PyRuntimeError::new_err(err.to_string());
+    if let Some(coded) = err.downcast_ref::<i18n::MessageError>() {
+        let _ = Python::with_gil(|py| -> PyResult<()> {
+            let value = py_err.value(py);
+            value.setattr("code", &coded.code)?;
+            value.setattr("params", json_to_py(py, coded.params.clone())?)?;
+            value.setattr("localized_message", i18n::localize_error(&err, locale))
+        });
+    }
+    py_err
+}
+
+/// Operators for read-only bindings: the primary `uri` plus an optional
+/// `read_uri` replica used while it is fresh.
+struct ReadOperators {
+    primary: Operator,
+    replica: Option<Operator>,
+}
+
+impl ReadOperators {
+    async fn select(&self, ws_path: &str) -> PyResult<Operator> {
+        match &self.replica {
+            Some(replica) => replica::read_operator(&self.primary, replica, ws_path)
+                .await
+                .map_err(|e| PyRuntimeError::new_err(e.to_string())),
+            None => Ok(self.primary.clone()),
+        }
+    }
+}
+
+fn get_read_operators(py: Python<'_>, config: &Bound<'_, PyDict>) -> PyResult<ReadOperators> {
+    let primary = get_operator(py, config)?;
+    let replica = match config.get_item("read_uri")? {
+        Some(uri) if !uri.is_none() => Some(
+            storage::operator_from_uri(&uri.extract::<String>()?)
+                .map_err(|e| PyValueError::new_err(e.to_string()))?,
+        ),
+        _ => None,
+    };
+    Ok(ReadOperators { primary, replica })
+}
+
 fn json_to_py(py: Python<'_>, value: Value) -> PyResult<PyObject> {
     match value {
         Value::Null => Ok(py.None()),
@@ -79,7 +203,7 @@ fn list_spaces<'a>(
     storage_config: Bound<'a, PyDict>,
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+    future_into_py(py, request_id(&storage_config)?, async move {
         let spaces = space::list_spaces(&op)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
@@ -98,7 +222,7 @@ fn create_space<'a>(
         .ok_or_else(|| PyValueError::new_err("Missing 'uri'"))?
         .extract()?;
     let op = get_operator(py, &storage_config)?;
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+    future_into_py(py, request_id(&storage_config)?, async move {
         space::create_space(&op, &name, &uri)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
@@ -121,7 +245,7 @@ fn create_sample_space<'a>(
         .ok_or_else(|| PyValueError::new_err("Missing 'uri'"))?
         .extract()?;
     let op = get_operator(py, &storage_config)?;
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+    future_into_py(py, request_id(&storage_config)?, async move {
         let options = sample_data::SampleDataOptions {
             space_id,
             scenario: scenario.unwrap_or_else(|| sample_data::DEFAULT_SCENARIO.to_string()),
@@ -160,7 +284,7 @@ fn create_sample_space_job<'a>(
         .ok_or_else(|| PyValueError::new_err("Missing 'uri'"))?
         .extract()?;
     let op = get_operator(py, &storage_config)?;
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+    future_into_py(py, request_id(&storage_config)?, async move {
         let options = sample_data::SampleDataOptions {
             space_id,
             scenario: scenario.unwrap_or_else(|| sample_data::DEFAULT_SCENARIO.to_string()),
@@ -182,7 +306,7 @@ fn get_sample_space_job<'a>(
     job_id: String,
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+    future_into_py(py, request_id(&storage_config)?, async move {
         let job = sample_data::get_sample_space_job(&op, &job_id)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
@@ -214,7 +338,7 @@ fn test_storage_connection_py<'a>(
     } else {
         return Err(PyValueError::new_err("Unsupported storage connector"));
     };
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+    future_into_py(py, request_id(&storage_config)?, async move {
         Python::with_gil(|py| json_to_py(py, payload))
     })
 }
@@ -222,7 +346,7 @@ fn test_storage_connection_py<'a>(
 // Entry
 
 #[pyfunction]
-#[pyo3(signature = (storage_config, space_id, entry_id, content, author=None))]
+#[pyo3(signature = (storage_config, space_id, entry_id, content, author=None, locale=None))]
 fn create_entry<'a>(
     py: Python<'a>,
     storage_config: Bound<'a, PyDict>,
@@ -230,18 +354,142 @@ fn create_entry<'a>(
     entry_id: String,
     content: String,
     author: Option<String>,
+    locale: Option<String>,
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
     let ws_path = format!("spaces/{}", space_id);
     let author = author.unwrap_or_else(|| "unknown".to_string());
 
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+    future_into_py(py, request_id(&storage_config)?, async move {
         let integrity = RealIntegrityProvider::from_space(&op, &space_id)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let locale = resolve_locale(&op, &ws_path, locale.as_deref()).await?;
         let meta = entry::create_entry(&op, &ws_path, &entry_id, &content, &author, &integrity)
+            .await
+            .map_err(|e| localized_error(e, locale))?;
+
+        let val = serde_json::to_value(meta).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, template_id, content, description=None))]
+fn save_template<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    template_id: String,
+    content: String,
+    description: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let val = template::save_template(
+            &op,
+            &ws_path,
+            &template_id,
+            &content,
+            description.as_deref(),
+        )
+        .await
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let val = serde_json::to_value(val).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+fn list_templates<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let val = template::list_templates(&op, &ws_path)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let val = serde_json::to_value(val).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+fn get_template<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    template_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let val = template::get_template(&op, &ws_path, &template_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let val = serde_json::to_value(val).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+fn delete_template<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    template_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        template::delete_template(&op, &ws_path, &template_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, template_id, variables_json=None, entry_id=None, author=None, locale=None))]
+#[allow(clippy::too_many_arguments)]
+fn create_entry_from_template<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    template_id: String,
+    variables_json: Option<String>,
+    entry_id: Option<String>,
+    author: Option<String>,
+    locale: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let author = author.unwrap_or_else(|| "unknown".to_string());
+    let variables: Value = match variables_json {
+        Some(variables_json) => serde_json::from_str(&variables_json)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?,
+        None => Value::Null,
+    };
+
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let integrity = RealIntegrityProvider::from_space(&op, &space_id)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let locale = resolve_locale(&op, &ws_path, locale.as_deref()).await?;
+        let meta = template::create_entry_from_template(
+            &op,
+            &ws_path,
+            &template_id,
+            &variables,
+            entry_id.as_deref(),
+            &author,
+            &integrity,
+        )
+        .await
+        .map_err(|e| localized_error(e, locale))?;
 
         let val = serde_json::to_value(meta).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
         Python::with_gil(|py| json_to_py(py, val))
@@ -258,7 +506,7 @@ fn list_sql<'a>(
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
     let ws_path = format!("spaces/{}", space_id);
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+    future_into_py(py, request_id(&storage_config)?, async move {
         let entries = saved_sql::list_sql(&op, &ws_path)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
@@ -277,7 +525,7 @@ fn get_sql<'a>(
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
     let ws_path = format!("spaces/{}", space_id);
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+    future_into_py(py, request_id(&storage_config)?, async move {
         let entry = saved_sql::get_sql(&op, &ws_path, &sql_id)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
@@ -301,7 +549,7 @@ fn create_sql<'a>(
     let payload: saved_sql::SqlPayload =
         serde_json::from_str(&payload_json).map_err(|e| PyValueError::new_err(e.to_string()))?;
 
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+    future_into_py(py, request_id(&storage_config)?, async move {
         let integrity = RealIntegrityProvider::from_space(&op, &space_id)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
@@ -329,7 +577,7 @@ fn update_sql<'a>(
     let payload: saved_sql::SqlPayload =
         serde_json::from_str(&payload_json).map_err(|e| PyValueError::new_err(e.to_string()))?;
 
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+    future_into_py(py, request_id(&storage_config)?, async move {
         let integrity = RealIntegrityProvider::from_space(&op, &space_id)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
@@ -357,7 +605,7 @@ fn delete_sql<'a>(
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
     let ws_path = format!("spaces/{}", space_id);
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+    future_into_py(py, request_id(&storage_config)?, async move {
         saved_sql::delete_sql(&op, &ws_path, &sql_id)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
@@ -368,16 +616,19 @@ fn delete_sql<'a>(
 // Search
 
 #[pyfunction]
+#[pyo3(signature = (storage_config, space_id, query, archived=None))]
 fn search_entries<'a>(
     py: Python<'a>,
     storage_config: Bound<'a, PyDict>,
     space_id: String,
     query: String,
+    archived: Option<String>,
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
     let ws_path = format!("spaces/{}", space_id);
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
-        let results = search::search_entries(&op, &ws_path, &query)
+    let archived = parse_archived_filter(archived.as_deref())?;
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let results = search::search_entries_filtered(&op, &ws_path, &query, archived)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
 
@@ -399,7 +650,7 @@ fn delete_entry<'a>(
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
     let ws_path = format!("spaces/{}", space_id);
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+    future_into_py(py, request_id(&storage_config)?, async move {
         entry::delete_entry(&op, &ws_path, &entry_id, hard_delete)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
@@ -408,7 +659,7 @@ fn delete_entry<'a>(
 }
 
 #[pyfunction]
-fn get_entry<'a>(
+fn undelete_entry<'a>(
     py: Python<'a>,
     storage_config: Bound<'a, PyDict>,
     space_id: String,
@@ -416,312 +667,2517 @@ fn get_entry<'a>(
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
     let ws_path = format!("spaces/{}", space_id);
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
-        let meta = entry::get_entry(&op, &ws_path, &entry_id)
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let restored = entry::undelete_entry(&op, &ws_path, &entry_id)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
-        let val = serde_json::to_value(meta).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
-        Python::with_gil(|py| json_to_py(py, val))
+        Python::with_gil(|py| json_to_py(py, restored))
     })
 }
 
 #[pyfunction]
-fn list_entries<'a>(
+fn archive_entry<'a>(
     py: Python<'a>,
     storage_config: Bound<'a, PyDict>,
     space_id: String,
+    entry_id: String,
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
     let ws_path = format!("spaces/{}", space_id);
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
-        let entries = entry::list_entries(&op, &ws_path)
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let archived = entry::archive_entry(&op, &ws_path, &entry_id)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
-        let val = serde_json::Value::Array(entries);
-        Python::with_gil(|py| json_to_py(py, val))
+        Python::with_gil(|py| json_to_py(py, archived))
     })
 }
 
 #[pyfunction]
-fn get_space<'a>(
+fn unarchive_entry<'a>(
     py: Python<'a>,
     storage_config: Bound<'a, PyDict>,
-    name: String,
+    space_id: String,
+    entry_id: String,
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
-        let meta = space::get_space_raw(&op, &name)
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let unarchived = entry::unarchive_entry(&op, &ws_path, &entry_id)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
-        let val = serde_json::to_value(meta).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
-        Python::with_gil(|py| json_to_py(py, val))
+        Python::with_gil(|py| json_to_py(py, unarchived))
     })
 }
 
+pyo3::create_exception!(
+    _ugoite_core,
+    IntegrityError,
+    PyRuntimeError,
+    "A stored entry failed its integrity check on a strict read."
+);
+
+/// Maps a core error to RuntimeError, or to IntegrityError when an entry or
+/// asset failed verification.
+fn integrity_read_error(err: anyhow::Error) -> PyErr {
+    if err.downcast_ref::<integrity::IntegrityError>().is_some() {
+        return IntegrityError::new_err(err.to_string());
+    }
+    PyRuntimeError::new_err(err.to_string())
+}
+
 #[pyfunction]
-fn patch_space<'a>(
+#[pyo3(signature = (storage_config, space_id, entry_id, strict=None, fields=None))]
+fn get_entry<'a>(
     py: Python<'a>,
     storage_config: Bound<'a, PyDict>,
     space_id: String,
-    patch_json: String,
+    entry_id: String,
+    strict: Option<bool>,
+    fields: Option<Vec<String>>,
 ) -> PyResult<Bound<'a, PyAny>> {
-    let op = get_operator(py, &storage_config)?;
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
-        let patch_value: serde_json::Value =
-            serde_json::from_str(&patch_json).map_err(|e| PyValueError::new_err(e.to_string()))?;
-        let updated = space::patch_space(&op, &space_id, &patch_value)
-            .await
-            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
-        let val =
-            serde_json::to_value(updated).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    let ops = get_read_operators(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let op = ops.select(&ws_path).await?;
+        let strict = match strict {
+            Some(strict) => strict,
+            None => integrity::strict_reads(
+                &space::read_space_settings(&op, &ws_path)
+                    .await
+                    .map_err(|e| PyRuntimeError::new_err(e.to_string()))?,
+            ),
+        };
+        if let Some(fields) = fields {
+            if strict {
+                // Checksums cover the rendered entry, which a projection never builds.
+                return Err(PyValueError::new_err(
+                    "fields cannot be combined with strict reads",
+                ));
+            }
+            let val = entry::get_entry_fields(&op, &ws_path, &entry_id, &fields)
+                .await
+                .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+            return Python::with_gil(|py| json_to_py(py, val));
+        }
+        let meta = if strict {
+            // The space key lives on the primary; a replica only serves rows.
+            let provider = RealIntegrityProvider::from_space(&ops.primary, &space_id)
+                .await
+                .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+            entry::get_entry_verified(&op, &ws_path, &entry_id, &provider, Some(true)).await
+        } else {
+            entry::get_entry(&op, &ws_path, &entry_id).await
+        }
+        .map_err(integrity_read_error)?;
+        let val = serde_json::to_value(meta).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
         Python::with_gil(|py| json_to_py(py, val))
     })
 }
 
 #[pyfunction]
-fn list_column_types<'a>(py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
-        let types = form::list_column_types()
-            .await
-            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
-        Ok(types)
-    })
-}
-
-#[pyfunction]
-#[pyo3(signature = (storage_config, space_id, form_def_json, strategies_json=None))]
-fn migrate_form<'a>(
+fn replica_status<'a>(
     py: Python<'a>,
     storage_config: Bound<'a, PyDict>,
     space_id: String,
-    form_def_json: String,
-    strategies_json: Option<String>,
 ) -> PyResult<Bound<'a, PyAny>> {
-    let op = get_operator(py, &storage_config)?;
+    let ops = get_read_operators(py, &storage_config)?;
     let ws_path = format!("spaces/{}", space_id);
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
-        let form_def: serde_json::Value = serde_json::from_str(&form_def_json)
-            .map_err(|e| PyValueError::new_err(e.to_string()))?;
-        let strategies = match strategies_json {
-            Some(json) => Some(
-                serde_json::from_str::<serde_json::Value>(&json)
-                    .map_err(|e| PyValueError::new_err(e.to_string()))?,
-            ),
-            None => None,
-        };
-        let integrity = RealIntegrityProvider::from_space(&op, &space_id)
-            .await
-            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
-        let count = form::migrate_form(&op, &ws_path, &form_def, strategies, &integrity)
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let replica = ops
+            .replica
+            .as_ref()
+            .ok_or_else(|| PyValueError::new_err("Missing 'read_uri' in storage config"))?;
+        let status = replica::replica_status(&ops.primary, replica, &ws_path)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
-        Ok(count)
+        let val =
+            serde_json::to_value(status).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
     })
 }
 
 #[pyfunction]
-fn reindex_all<'a>(
+fn entry_exists<'a>(
     py: Python<'a>,
     storage_config: Bound<'a, PyDict>,
     space_id: String,
+    entry_id: String,
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
     let ws_path = format!("spaces/{}", space_id);
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
-        index::reindex_all(&op, &ws_path)
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let existence = entry::exists(&op, &ws_path, &entry_id)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
-        Ok(())
+        let val =
+            serde_json::to_value(existence).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
     })
 }
 
+fn parse_sync_filter(filter_json: Option<&str>) -> PyResult<export::SyncFilter> {
+    match filter_json {
+        Some(raw) => serde_json::from_str(raw).map_err(|e| PyValueError::new_err(e.to_string())),
+        None => Ok(export::SyncFilter::default()),
+    }
+}
+
+/// `archived` is `exclude` (the default), `include` or `only`.
+fn parse_archived_filter(archived: Option<&str>) -> PyResult<entry::ArchivedFilter> {
+    archived
+        .map(str::parse)
+        .transpose()
+        .map(Option::unwrap_or_default)
+        .map_err(|e: anyhow::Error| PyValueError::new_err(e.to_string()))
+}
+
+/// Without paging or a filter this returns every entry, as before; with
+/// them, only the requested page (see `list_entries_page` for the count).
 #[pyfunction]
-fn update_entry_index<'a>(
+#[pyo3(signature = (storage_config, space_id, offset=0, limit=None, filter_json=None, include_fields=None, archived=None))]
+#[allow(clippy::too_many_arguments)]
+fn list_entries<'a>(
     py: Python<'a>,
     storage_config: Bound<'a, PyDict>,
     space_id: String,
-    entry_id: String,
+    offset: usize,
+    limit: Option<usize>,
+    filter_json: Option<String>,
+    include_fields: Option<Vec<String>>,
+    archived: Option<String>,
 ) -> PyResult<Bound<'a, PyAny>> {
-    let op = get_operator(py, &storage_config)?;
+    let ops = get_read_operators(py, &storage_config)?;
     let ws_path = format!("spaces/{}", space_id);
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
-        index::update_entry_index(&op, &ws_path, &entry_id)
+    let filter = parse_sync_filter(filter_json.as_deref())?;
+    let archived = parse_archived_filter(archived.as_deref())?;
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let op = ops.select(&ws_path).await?;
+        let include_fields = include_fields.unwrap_or_default();
+        let entries = if offset == 0 && limit.is_none() && filter.is_empty() {
+            entry::list_entries_including(&op, &ws_path, &include_fields, archived).await
+        } else {
+            entry::list_entries_page(
+                &op,
+                &ws_path,
+                &filter,
+                offset,
+                limit,
+                &include_fields,
+                archived,
+            )
             .await
-            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
-        Ok(())
+            .map(|mut page| match page["entries"].take() {
+                Value::Array(entries) => entries,
+                _ => Vec::new(),
+            })
+        }
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let val = serde_json::Value::Array(entries);
+        Python::with_gil(|py| json_to_py(py, val))
     })
 }
 
 #[pyfunction]
-fn load_hmac_material<'a>(
+#[pyo3(signature = (storage_config, space_id, offset=0, limit=None, filter_json=None, include_fields=None, archived=None))]
+#[allow(clippy::too_many_arguments)]
+fn list_entries_page<'a>(
     py: Python<'a>,
     storage_config: Bound<'a, PyDict>,
     space_id: String,
+    offset: usize,
+    limit: Option<usize>,
+    filter_json: Option<String>,
+    include_fields: Option<Vec<String>>,
+    archived: Option<String>,
 ) -> PyResult<Bound<'a, PyAny>> {
-    let op = get_operator(py, &storage_config)?;
-    pyo3_async_runtimes::tokio::future_into_py::<_, PyObject>(py, async move {
-        let (key_id, secret) = integrity::load_hmac_material(&op, &space_id)
-            .await
-            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
-        Python::with_gil(|py| {
-            let secret_bytes = PyBytes::new(py, &secret);
-            let key_id_obj = key_id.into_py_any(py)?;
-            let secret_obj = secret_bytes.into_py_any(py)?;
-            let tuple = PyTuple::new(py, [key_id_obj, secret_obj])?;
-            tuple.into_py_any(py)
-        })
+    let ops = get_read_operators(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let filter = parse_sync_filter(filter_json.as_deref())?;
+    let archived = parse_archived_filter(archived.as_deref())?;
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let op = ops.select(&ws_path).await?;
+        let include_fields = include_fields.unwrap_or_default();
+        let page = entry::list_entries_page(
+            &op,
+            &ws_path,
+            &filter,
+            offset,
+            limit,
+            &include_fields,
+            archived,
+        )
+        .await
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, page))
     })
 }
 
 #[pyfunction]
-fn load_response_hmac_material<'a>(
+fn get_space<'a>(
     py: Python<'a>,
     storage_config: Bound<'a, PyDict>,
+    name: String,
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
-    pyo3_async_runtimes::tokio::future_into_py::<_, PyObject>(py, async move {
-        let (key_id, secret) = integrity::load_response_hmac_material(&op)
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let meta = space::get_space_raw(&op, &name)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
-        Python::with_gil(|py| {
-            let secret_bytes = PyBytes::new(py, &secret);
-            let key_id_obj = key_id.into_py_any(py)?;
-            let secret_obj = secret_bytes.into_py_any(py)?;
-            let tuple = PyTuple::new(py, [key_id_obj, secret_obj])?;
-            tuple.into_py_any(py)
-        })
+        let val = serde_json::to_value(meta).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
     })
 }
 
 #[pyfunction]
-fn list_forms<'a>(
+fn patch_space<'a>(
     py: Python<'a>,
     storage_config: Bound<'a, PyDict>,
     space_id: String,
+    patch_json: String,
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
-    let ws_path = format!("spaces/{}", space_id);
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
-        let forms = form::list_forms(&op, &ws_path)
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let patch_value: serde_json::Value =
+            serde_json::from_str(&patch_json).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let updated = space::patch_space(&op, &space_id, &patch_value)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
         let val =
-            serde_json::to_value(forms).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+            serde_json::to_value(updated).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
         Python::with_gil(|py| json_to_py(py, val))
     })
 }
 
 #[pyfunction]
-fn upsert_form<'a>(
+fn upgrade_space_format<'a>(
     py: Python<'a>,
     storage_config: Bound<'a, PyDict>,
     space_id: String,
-    form_def: String,
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
     let ws_path = format!("spaces/{}", space_id);
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
-        let parsed: serde_json::Value =
-            serde_json::from_str(&form_def).map_err(|e| PyValueError::new_err(e.to_string()))?;
-        form::upsert_form(&op, &ws_path, &parsed)
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let report = space::upgrade_format(&op, &ws_path)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
-        Ok(())
+        Python::with_gil(|py| json_to_py(py, report))
     })
 }
 
-// Asset
-
 #[pyfunction]
-fn save_asset<'a>(
+fn activity_digest<'a>(
     py: Python<'a>,
     storage_config: Bound<'a, PyDict>,
     space_id: String,
-    filename: String,
-    content: Vec<u8>,
+    since: f64,
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
     let ws_path = format!("spaces/{}", space_id);
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
-        let info = asset::save_asset(&op, &ws_path, &filename, &content)
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let digest = space::activity_digest(&op, &ws_path, since)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
-        let val = serde_json::to_value(info).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
-        Python::with_gil(|py| json_to_py(py, val))
+        Python::with_gil(|py| json_to_py(py, digest))
     })
 }
 
 #[pyfunction]
-fn list_assets<'a>(
+#[pyo3(signature = (storage_config, space_id, from_ts, to_ts=None))]
+fn diff_snapshots<'a>(
     py: Python<'a>,
     storage_config: Bound<'a, PyDict>,
     space_id: String,
+    from_ts: f64,
+    to_ts: Option<f64>,
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
     let ws_path = format!("spaces/{}", space_id);
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
-        let list = asset::list_assets(&op, &ws_path)
+    let to_ts = to_ts.unwrap_or_else(entry::now_ts);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let diff = space::diff_snapshots(&op, &ws_path, from_ts, to_ts)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
-        let val = serde_json::to_value(list).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, diff))
+    })
+}
+
+#[pyfunction]
+fn list_column_types<'a>(py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+    future_into_py(py, storage::new_request_id(), async move {
+        let types = form::list_column_types()
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Ok(types)
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, form_def_json, strategies_json=None, dry_run=false, samples=5))]
+fn migrate_form<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    form_def_json: String,
+    strategies_json: Option<String>,
+    dry_run: bool,
+    samples: usize,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let form_def: serde_json::Value = serde_json::from_str(&form_def_json)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let strategies = match strategies_json {
+            Some(json) => Some(
+                serde_json::from_str::<serde_json::Value>(&json)
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?,
+            ),
+            None => None,
+        };
+        if dry_run {
+            let report = form::preview_migration(&op, &ws_path, &form_def, strategies, samples)
+                .await
+                .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+            return Python::with_gil(|py| json_to_py(py, report));
+        }
+        let integrity = RealIntegrityProvider::from_space(&op, &space_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let count = form::migrate_form(&op, &ws_path, &form_def, strategies, &integrity)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| count.into_py_any(py))
+    })
+}
+
+#[pyfunction]
+fn field_lineage<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    form_name: String,
+    field: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let lineage = form::field_lineage(&op, &ws_path, &form_name, &field)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, lineage))
+    })
+}
+
+#[pyfunction]
+fn reindex_all<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        index::reindex_all(&op, &ws_path)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Ok(())
+    })
+}
+
+#[pyfunction]
+fn reindex_form<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    form_name: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let summary = index::reindex_form(&op, &ws_path, &form_name)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, summary))
+    })
+}
+
+#[pyfunction]
+fn reindex_incremental<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let report = index::reindex_incremental(&op, &ws_path)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, report))
+    })
+}
+
+#[pyfunction]
+fn flush_contention_stats<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        storage::flush_contention_stats(&op, &ws_path)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, since=None))]
+fn contention_stats<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    since: Option<f64>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let stats = storage::contention_stats(&op, &ws_path, since)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, stats))
+    })
+}
+
+#[pyfunction]
+fn flush_slow_log<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        storage::flush_slow_log(&op, &ws_path)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, operation=None, limit=None))]
+fn list_slow_operations<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    operation: Option<String>,
+    limit: Option<usize>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let records = storage::list_slow_operations(&op, &ws_path, operation.as_deref(), limit)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, Value::Array(records)))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, since=None))]
+fn rank_spaces_by_contention<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    since: Option<f64>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let ranked = storage::rank_spaces_by_contention(&op, since)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, ranked))
+    })
+}
+
+#[pyfunction]
+fn index_status<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let status = index::index_status(&op, &ws_path)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, status))
+    })
+}
+
+#[pyfunction]
+fn update_entry_index<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    entry_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        index::update_entry_index(&op, &ws_path, &entry_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Ok(())
+    })
+}
+
+#[pyfunction]
+fn load_hmac_material<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    future_into_py::<_, PyObject>(py, request_id(&storage_config)?, async move {
+        let (key_id, secret) = integrity::load_hmac_material(&op, &space_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| {
+            let secret_bytes = PyBytes::new(py, &secret);
+            let key_id_obj = key_id.into_py_any(py)?;
+            let secret_obj = secret_bytes.into_py_any(py)?;
+            let tuple = PyTuple::new(py, [key_id_obj, secret_obj])?;
+            tuple.into_py_any(py)
+        })
+    })
+}
+
+#[pyfunction]
+fn load_signing_public_key<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    future_into_py::<_, PyObject>(py, request_id(&storage_config)?, async move {
+        let (key_id, public_key) = integrity::load_signing_public_key(&op, &space_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| {
+            let key_id_obj = key_id.into_py_any(py)?;
+            let public_key_obj = PyBytes::new(py, &public_key).into_py_any(py)?;
+            let tuple = PyTuple::new(py, [key_id_obj, public_key_obj])?;
+            tuple.into_py_any(py)
+        })
+    })
+}
+
+#[pyfunction]
+fn load_response_hmac_material<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    future_into_py::<_, PyObject>(py, request_id(&storage_config)?, async move {
+        let (key_id, secret) = integrity::load_response_hmac_material(&op)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| {
+            let secret_bytes = PyBytes::new(py, &secret);
+            let key_id_obj = key_id.into_py_any(py)?;
+            let secret_obj = secret_bytes.into_py_any(py)?;
+            let tuple = PyTuple::new(py, [key_id_obj, secret_obj])?;
+            tuple.into_py_any(py)
+        })
+    })
+}
+
+#[pyfunction]
+fn list_forms<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let forms = form::list_forms(&op, &ws_path)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let val =
+            serde_json::to_value(forms).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, form_def, author=None))]
+fn upsert_form<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    form_def: String,
+    author: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let parsed: serde_json::Value =
+            serde_json::from_str(&form_def).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        match author {
+            Some(author) => form::upsert_form_as(&op, &ws_path, &parsed, &author).await,
+            None => form::upsert_form(&op, &ws_path, &parsed).await,
+        }
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Ok(())
+    })
+}
+
+#[pyfunction]
+fn get_form_history<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    form_name: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let history = form::get_form_history(&op, &ws_path, &form_name)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, Value::Array(history)))
+    })
+}
+
+#[pyfunction]
+fn get_form_version<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    form_name: String,
+    revision: usize,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let recorded = form::get_form_version(&op, &ws_path, &form_name, revision)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let val =
+            serde_json::to_value(recorded).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, form_name, revision, author=None))]
+fn rollback_form<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    form_name: String,
+    revision: usize,
+    author: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let author = author.unwrap_or_else(|| "unknown".to_string());
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let form_def = form::rollback_form(&op, &ws_path, &form_name, revision, &author)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, form_def))
+    })
+}
+
+// Asset
+
+#[pyfunction]
+fn save_asset<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    filename: String,
+    content: Vec<u8>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let info = asset::save_asset(&op, &ws_path, &filename, &content)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let val = serde_json::to_value(info).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+fn list_assets<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let list = asset::list_assets(&op, &ws_path)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let val = serde_json::to_value(list).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, asset_id, offset=0, length=None, strict=None))]
+fn read_asset_range<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    asset_id: String,
+    offset: u64,
+    length: Option<u64>,
+    strict: Option<bool>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let ops = get_read_operators(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py::<_, PyObject>(py, request_id(&storage_config)?, async move {
+        let op = ops.select(&ws_path).await?;
+        let strict = match strict {
+            Some(strict) => strict,
+            None => integrity::strict_reads(
+                &space::read_space_settings(&op, &ws_path)
+                    .await
+                    .map_err(|e| PyRuntimeError::new_err(e.to_string()))?,
+            ),
+        };
+        let range = if strict {
+            let provider = RealIntegrityProvider::from_space(&ops.primary, &space_id)
+                .await
+                .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+            asset::read_asset_range_verified(
+                &op,
+                &ws_path,
+                &asset_id,
+                offset,
+                length,
+                &provider,
+                Some(true),
+            )
+            .await
+        } else {
+            asset::read_asset_range(&op, &ws_path, &asset_id, offset, length).await
+        }
+        .map_err(integrity_read_error)?;
+        Python::with_gil(|py| {
+            let dict = PyDict::new(py);
+            dict.set_item("data", PyBytes::new(py, &range.data))?;
+            dict.set_item("offset", range.offset)?;
+            dict.set_item("length", range.data.len())?;
+            dict.set_item("total_size", range.total_size)?;
+            dict.into_py_any(py)
+        })
+    })
+}
+
+#[pyfunction]
+fn backfill_asset_integrity<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let integrity = RealIntegrityProvider::from_space(&op, &space_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        asset::backfill_asset_integrity(&op, &ws_path, &integrity)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    })
+}
+
+#[pyfunction]
+fn space_merkle_root<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let integrity = RealIntegrityProvider::from_space(&op, &space_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let root = integrity::space_merkle_root(&op, &ws_path, &integrity)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, root))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, actor="system"))]
+fn rotate_space_key<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    actor: &str,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let actor = actor.to_string();
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let report = integrity::rotate_space_key(&op, &space_id, &actor)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, report))
+    })
+}
+
+#[pyfunction]
+fn delete_asset<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    asset_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        asset::delete_asset(&op, &ws_path, &asset_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Ok(())
+    })
+}
+
+/// A Python callable used as an [`extraction::ContentExtractor`]. It is
+/// called as `extract(filename, content_type, content)` and returns the text
+/// or `None`; an exception is recorded as the extraction error.
+struct PyContentExtractor {
+    extract: PyObject,
+    content_types: Vec<String>,
+}
+
+impl extraction::ContentExtractor for PyContentExtractor {
+    fn accepts(&self, ctx: &extraction::AssetContext) -> bool {
+        self.content_types
+            .iter()
+            .any(|pattern| extraction::content_type_matches(pattern, ctx.content_type))
+    }
+
+    fn extract(
+        &self,
+        ctx: &extraction::AssetContext,
+        content: &[u8],
+    ) -> Result<Option<String>, String> {
+        Python::with_gil(|py| {
+            self.extract
+                .call1(
+                    py,
+                    (ctx.filename, ctx.content_type, PyBytes::new(py, content)),
+                )
+                .and_then(|text| text.extract::<Option<String>>(py))
+                .map_err(|e| e.to_string())
+        })
+    }
+}
+
+/// Registers `extract` to run on uploaded assets whose type matches one of
+/// `content_types` (e.g. `image/*`, `application/pdf`).
+#[pyfunction]
+fn register_content_extractor(
+    name: String,
+    extract: PyObject,
+    content_types: Vec<String>,
+) -> PyResult<()> {
+    let extractor = PyContentExtractor {
+        extract,
+        content_types,
+    };
+    extraction::register_content_extractor(&name, Arc::new(extractor))
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn clear_content_extractors() -> PyResult<()> {
+    extraction::clear_content_extractors().map_err(|e| PyRuntimeError::new_err(e.to_string()))
+}
+
+/// Python callables used as a [`hooks::FormHook`]. Both get a `ctx` dict with
+/// `space_id`, `form`, `entry_id`, `event` and `author`.
+/// `before_write(ctx, fields)` returns the new fields dict, or `None` to keep
+/// them, and rejects the write by raising. `after_write(ctx, revision_id,
+/// fields)` cannot fail the committed write, so its exceptions are reported
+/// as unraisable.
+struct PyFormHook {
+    before_write: Option<PyObject>,
+    after_write: Option<PyObject>,
+}
+
+fn hook_context(py: Python<'_>, ctx: &hooks::WriteContext) -> PyResult<PyObject> {
+    json_to_py(
+        py,
+        serde_json::json!({
+            "space_id": ctx.ws_path.trim_start_matches("spaces/"),
+            "form": ctx.form_name,
+            "entry_id": ctx.entry_id,
+            "event": ctx.event.as_str(),
+            "author": ctx.author,
+        }),
+    )
+}
+
+fn py_to_json(py: Python<'_>, value: &Bound<'_, PyAny>) -> PyResult<Value> {
+    let text: String = py
+        .import("json")?
+        .call_method1("dumps", (value,))?
+        .extract()?;
+    serde_json::from_str(&text).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+impl hooks::FormHook for PyFormHook {
+    fn before_write(
+        &self,
+        ctx: &hooks::WriteContext,
+        fields: &mut serde_json::Map<String, Value>,
+    ) -> Result<(), String> {
+        let Some(before_write) = &self.before_write else {
+            return Ok(());
+        };
+        Python::with_gil(|py| {
+            let current = json_to_py(py, Value::Object(fields.clone()))?;
+            let returned = before_write.call1(py, (hook_context(py, ctx)?, current))?;
+            if returned.is_none(py) {
+                return Ok(());
+            }
+            match py_to_json(py, returned.bind(py))? {
+                Value::Object(map) => {
+                    *fields = map;
+                    Ok(())
+                }
+                _ => Err(PyValueError::new_err(
+                    "before_write must return a dict or None",
+                )),
+            }
+        })
+        .map_err(|e: PyErr| e.to_string())
+    }
+
+    fn after_write(&self, ctx: &hooks::WriteContext, revision_id: &str, fields: &Value) {
+        let Some(after_write) = &self.after_write else {
+            return;
+        };
+        Python::with_gil(|py| {
+            let result = hook_context(py, ctx).and_then(|ctx| {
+                after_write.call1(py, (ctx, revision_id, json_to_py(py, fields.clone())?))
+            });
+            if let Err(err) = result {
+                err.write_unraisable(py, None);
+            }
+        })
+    }
+}
+
+/// Registers Python callables as hook `name` for `form_name` in a space.
+/// The hook runs only while the form definition lists `name` in `hooks`.
+#[pyfunction]
+#[pyo3(signature = (space_id, form_name, name, before_write=None, after_write=None))]
+fn register_form_hook(
+    space_id: String,
+    form_name: String,
+    name: String,
+    before_write: Option<PyObject>,
+    after_write: Option<PyObject>,
+) -> PyResult<()> {
+    let hook = PyFormHook {
+        before_write,
+        after_write,
+    };
+    hooks::register_form_hook(
+        &format!("spaces/{}", space_id),
+        &form_name,
+        &name,
+        Arc::new(hook),
+    )
+    .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn clear_form_hooks(space_id: String, form_name: String) -> PyResult<()> {
+    hooks::clear_form_hooks(&format!("spaces/{}", space_id), &form_name)
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+}
+
+#[pyfunction]
+fn set_asset_text<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    asset_id: String,
+    extractor: String,
+    text: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let record = extraction::set_asset_text(&op, &ws_path, &asset_id, &extractor, &text)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let val =
+            serde_json::to_value(record).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+fn get_asset_text<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    asset_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let val = extraction::get_asset_text(&op, &ws_path, &asset_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+fn recompute_rollups<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    form_name: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        rollup::recompute_form_rollups(&op, &ws_path, &form_name)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    })
+}
+
+#[pyfunction]
+fn get_form<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    form_name: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let frm = form::get_form(&op, &ws_path, &form_name)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let val = serde_json::to_value(frm).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+fn get_entry_history<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    entry_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let history = entry::get_entry_history(&op, &ws_path, &entry_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, history))
+    })
+}
+
+#[pyfunction]
+fn get_entry_revision<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    entry_id: String,
+    revision_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let revision = entry::get_entry_revision(&op, &ws_path, &entry_id, &revision_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, revision))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, entry_id, revision_id, author=None, message=None, locale=None, capabilities=None))]
+#[allow(clippy::too_many_arguments)]
+fn restore_entry<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    entry_id: String,
+    revision_id: String,
+    author: Option<String>,
+    message: Option<String>,
+    locale: Option<String>,
+    capabilities: Option<Vec<String>>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let author = author.unwrap_or_else(|| "unknown".to_string());
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let integrity = RealIntegrityProvider::from_space(&op, &space_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let locale = resolve_locale(&op, &ws_path, locale.as_deref()).await?;
+        let result = entry::restore_entry_with_capabilities(
+            &op,
+            &ws_path,
+            &entry_id,
+            &revision_id,
+            &author,
+            message.as_deref(),
+            &capabilities.unwrap_or_default(),
+            &integrity,
+        )
+        .await
+        .map_err(|e| localized_error(e, locale))?;
+        Python::with_gil(|py| json_to_py(py, result))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, primary_id, secondary_id, strategy_json=None, author=None))]
+fn merge_entries<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    primary_id: String,
+    secondary_id: String,
+    strategy_json: Option<String>,
+    author: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let author = author.unwrap_or_else(|| "unknown".to_string());
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let strategy = match strategy_json {
+            Some(json) => serde_json::from_str::<merge::MergeStrategy>(&json)
+                .map_err(|e| PyValueError::new_err(e.to_string()))?,
+            None => merge::MergeStrategy::default(),
+        };
+        let integrity = RealIntegrityProvider::from_space(&op, &space_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let result = merge::merge_entries(
+            &op,
+            &ws_path,
+            &primary_id,
+            &secondary_id,
+            &strategy,
+            &author,
+            &integrity,
+        )
+        .await
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, result))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, entry_id, new_entry_id=None, copy_assets=false, author=None))]
+fn duplicate_entry<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    entry_id: String,
+    new_entry_id: Option<String>,
+    copy_assets: bool,
+    author: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let author = author.unwrap_or_else(|| "unknown".to_string());
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let integrity = RealIntegrityProvider::from_space(&op, &space_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let result = duplicate::duplicate_entry(
+            &op,
+            &ws_path,
+            &entry_id,
+            new_entry_id.as_deref(),
+            copy_assets,
+            &author,
+            &integrity,
+        )
+        .await
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, result))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, ops_json, author=None))]
+fn commit_transaction<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    ops_json: String,
+    author: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let author = author.unwrap_or_else(|| "unknown".to_string());
+    let ops: Vec<transaction::TransactionOp> =
+        serde_json::from_str(&ops_json).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let integrity = RealIntegrityProvider::from_space(&op, &space_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let results = transaction::Transaction::from_ops(ops)
+            .commit(&op, &ws_path, &author, &integrity)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, Value::Array(results)))
+    })
+}
+
+#[pyfunction]
+fn set_entry_metadata<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    entry_id: String,
+    patch_json: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let patch: serde_json::Value =
+        serde_json::from_str(&patch_json).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let metadata = entry::set_entry_metadata(&op, &ws_path, &entry_id, &patch)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, metadata))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, entry_id, redaction_json, author=None))]
+fn redact_entry<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    entry_id: String,
+    redaction_json: String,
+    author: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let author = author.unwrap_or_else(|| "unknown".to_string());
+    let redaction: entry::Redaction =
+        serde_json::from_str(&redaction_json).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let integrity = RealIntegrityProvider::from_space(&op, &space_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let report = entry::redact_entry(&op, &ws_path, &entry_id, &redaction, &author, &integrity)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, report))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, action=None))]
+fn list_audit_events<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    action: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let events = audit::list_audit_events(&op, &ws_path, action.as_deref())
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, Value::Array(events)))
+    })
+}
+
+#[pyfunction]
+fn scan_space_pii<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let report = pii::scan_space(&op, &ws_path, &[])
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, report))
+    })
+}
+
+/// Splits a `target_uri` naming a file into an operator for its directory
+/// and the file name.
+fn target_file(target_uri: &str, kind: &str) -> PyResult<(Operator, String)> {
+    let (dir_uri, file_name) = target_uri
+        .rsplit_once('/')
+        .filter(|(_, name)| !name.is_empty())
+        .ok_or_else(|| PyValueError::new_err(format!("target_uri must name a {} file", kind)))?;
+    let target =
+        storage::operator_from_uri(dir_uri).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok((target, file_name.to_string()))
+}
+
+/// `target_uri` names the zip file itself, e.g. `file:///tmp/audit/pump-3.zip`.
+#[pyfunction]
+fn export_entry_history<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    entry_id: String,
+    target_uri: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let (target, file_name) = target_file(&target_uri, "zip")?;
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let report = export::export_entry_history(&op, &ws_path, &entry_id, &target, &file_name)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, report))
+    })
+}
+
+/// Runs `query` and writes the rows as CSV to the file `target_uri` names.
+#[pyfunction]
+fn query_to_csv<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    query: String,
+    target_uri: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let (target, file_name) = target_file(&target_uri, "csv")?;
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let report = export::query_to_csv(&op, &ws_path, &query, &target, &file_name)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, report))
+    })
+}
+
+/// Runs `query` and writes the rows as an XLSX workbook to the file
+/// `target_uri` names.
+#[pyfunction]
+fn query_to_xlsx<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    query: String,
+    target_uri: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let (target, file_name) = target_file(&target_uri, "xlsx")?;
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let report = export::query_to_xlsx(&op, &ws_path, &query, &target, &file_name)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, report))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, bundle_uri, filter_json=None))]
+fn export_space<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    bundle_uri: String,
+    filter_json: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let bundle = storage::operator_from_uri(&bundle_uri)
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let filter: export::SyncFilter = match filter_json {
+        Some(filter_json) => {
+            serde_json::from_str(&filter_json).map_err(|e| PyValueError::new_err(e.to_string()))?
+        }
+        None => export::SyncFilter::default(),
+    };
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let report = export::export_space_filtered(&op, &ws_path, &bundle, &filter)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, report))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, bundle_uri, filter_json=None))]
+fn offline_bundle<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    bundle_uri: String,
+    filter_json: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let bundle = storage::operator_from_uri(&bundle_uri)
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let filter: export::SyncFilter = match filter_json {
+        Some(filter_json) => {
+            serde_json::from_str(&filter_json).map_err(|e| PyValueError::new_err(e.to_string()))?
+        }
+        None => export::SyncFilter::default(),
+    };
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let report = export::offline_bundle(&op, &ws_path, &filter, &bundle)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, report))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, cursor, filter_json=None))]
+fn offline_delta<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    cursor: f64,
+    filter_json: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let filter: export::SyncFilter = match filter_json {
+        Some(filter_json) => {
+            serde_json::from_str(&filter_json).map_err(|e| PyValueError::new_err(e.to_string()))?
+        }
+        None => export::SyncFilter::default(),
+    };
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let delta = export::offline_delta(&op, &ws_path, &filter, cursor)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, delta))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, bundle_uri, strategy="skip", author=None))]
+fn apply_replica_changes<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    bundle_uri: String,
+    strategy: &str,
+    author: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let author = author.unwrap_or_else(|| "unknown".to_string());
+    let bundle = storage::operator_from_uri(&bundle_uri)
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let strategy: export::CollisionStrategy = strategy
+        .parse()
+        .map_err(|e: anyhow::Error| PyValueError::new_err(e.to_string()))?;
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let integrity = RealIntegrityProvider::from_space(&op, &space_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let report =
+            export::apply_replica_changes(&op, &ws_path, &bundle, strategy, &author, &integrity)
+                .await
+                .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, report))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, bundle_uri, strategy="skip", author=None))]
+fn import_bundle<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    bundle_uri: String,
+    strategy: &str,
+    author: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let author = author.unwrap_or_else(|| "unknown".to_string());
+    let bundle = storage::operator_from_uri(&bundle_uri)
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let strategy: export::CollisionStrategy = strategy
+        .parse()
+        .map_err(|e: anyhow::Error| PyValueError::new_err(e.to_string()))?;
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let integrity = RealIntegrityProvider::from_space(&op, &space_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let report = export::import_bundle(&op, &ws_path, &bundle, strategy, &author, &integrity)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, report))
+    })
+}
+
+/// `content` is the raw `.eml` message.
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, form_name, content, author=None))]
+fn import_eml<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    form_name: String,
+    content: Vec<u8>,
+    author: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let author = author.unwrap_or_else(|| "unknown".to_string());
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let integrity = RealIntegrityProvider::from_space(&op, &space_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let report = email::import_eml(&op, &ws_path, &form_name, &content, &author, &integrity)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, report))
+    })
+}
+
+/// `archive_uri` names the Notion export zip itself.
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, archive_uri, author=None))]
+fn import_notion<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    archive_uri: String,
+    author: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let author = author.unwrap_or_else(|| "unknown".to_string());
+    let (source, file_name) = target_file(&archive_uri, "zip")?;
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let archive = source
+            .read(&file_name)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?
+            .to_vec();
+        let integrity = RealIntegrityProvider::from_space(&op, &space_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let report = notion::import_notion(&op, &ws_path, &archive, &author, &integrity)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, report))
+    })
+}
+
+/// Async iterator over a [`progress::ProgressStream`], returned by the
+/// `*_stream` functions. Each step yields one message dict; the producer
+/// only runs ahead of the consumer by the channel capacity.
+#[pyclass]
+struct ProgressIterator {
+    stream: Arc<tokio::sync::Mutex<progress::ProgressStream>>,
+}
+
+impl ProgressIterator {
+    /// Runs `producer` as request `request_id`, named `operation`.
+    fn spawn<F, Fut>(request_id: String, operation: &'static str, producer: F) -> Self
+    where
+        F: FnOnce(progress::ProgressSender) -> Fut,
+        Fut: std::future::Future<Output = anyhow::Result<()>> + Send + 'static,
+    {
+        let _runtime = pyo3_async_runtimes::tokio::get_runtime().enter();
+        let stream = progress::spawn(progress::DEFAULT_CAPACITY, move |tx| {
+            storage::with_request_id(request_id, operation, producer(tx))
+        });
+        ProgressIterator {
+            stream: Arc::new(tokio::sync::Mutex::new(stream)),
+        }
+    }
+}
+
+#[pymethods]
+impl ProgressIterator {
+    fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __anext__<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let stream = self.stream.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let message = stream.lock().await.next().await;
+            match message {
+                Some(Ok(message)) => Python::with_gil(|py| json_to_py(py, message)),
+                Some(Err(e)) => Err(PyRuntimeError::new_err(e.to_string())),
+                None => Err(PyStopAsyncIteration::new_err(())),
+            }
+        })
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, chunk_size=progress::DEFAULT_CHUNK_SIZE, include_fields=None, archived=None))]
+fn stream_entries(
+    py: Python<'_>,
+    storage_config: Bound<'_, PyDict>,
+    space_id: String,
+    chunk_size: usize,
+    include_fields: Option<Vec<String>>,
+    archived: Option<String>,
+) -> PyResult<ProgressIterator> {
+    let ops = get_read_operators(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let archived = parse_archived_filter(archived.as_deref())?;
+    Ok(ProgressIterator::spawn(
+        request_id(&storage_config)?,
+        "stream_entries",
+        move |tx| async move {
+            let op = ops.select(&ws_path).await?;
+            let include_fields = include_fields.unwrap_or_default();
+            entry::stream_entries(&op, &ws_path, chunk_size, &include_fields, archived, &tx).await
+        },
+    ))
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, bundle_uri, filter_json=None))]
+fn export_space_stream(
+    py: Python<'_>,
+    storage_config: Bound<'_, PyDict>,
+    space_id: String,
+    bundle_uri: String,
+    filter_json: Option<String>,
+) -> PyResult<ProgressIterator> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let bundle = storage::operator_from_uri(&bundle_uri)
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let filter: export::SyncFilter = match filter_json {
+        Some(filter_json) => {
+            serde_json::from_str(&filter_json).map_err(|e| PyValueError::new_err(e.to_string()))?
+        }
+        None => export::SyncFilter::default(),
+    };
+    Ok(ProgressIterator::spawn(
+        request_id(&storage_config)?,
+        "export_space_stream",
+        move |tx| async move {
+            let report =
+                export::export_space_with_progress(&op, &ws_path, &bundle, &filter, Some(&tx))
+                    .await?;
+            tx.result(report).await
+        },
+    ))
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, bundle_uri, strategy="skip", author=None))]
+fn import_bundle_stream(
+    py: Python<'_>,
+    storage_config: Bound<'_, PyDict>,
+    space_id: String,
+    bundle_uri: String,
+    strategy: &str,
+    author: Option<String>,
+) -> PyResult<ProgressIterator> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let author = author.unwrap_or_else(|| "unknown".to_string());
+    let bundle = storage::operator_from_uri(&bundle_uri)
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let strategy: export::CollisionStrategy = strategy
+        .parse()
+        .map_err(|e: anyhow::Error| PyValueError::new_err(e.to_string()))?;
+    Ok(ProgressIterator::spawn(
+        request_id(&storage_config)?,
+        "import_bundle_stream",
+        move |tx| async move {
+            let integrity = RealIntegrityProvider::from_space(&op, &space_id).await?;
+            let report = export::import_bundle_with_progress(
+                &op,
+                &ws_path,
+                &bundle,
+                strategy,
+                &author,
+                &integrity,
+                Some(&tx),
+            )
+            .await?;
+            tx.result(report).await
+        },
+    ))
+}
+
+#[pyfunction]
+fn verify_export<'a>(
+    py: Python<'a>,
+    bundle_uri: String,
+    public_key: Vec<u8>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let bundle = storage::operator_from_uri(&bundle_uri)
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    future_into_py(py, storage::new_request_id(), async move {
+        let report = export::verify_export(&bundle, &public_key)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, report))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, board, column, entry_id, after_id=None))]
+fn move_board_entry<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    board: String,
+    column: String,
+    entry_id: String,
+    after_id: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let placement = board::move_entry(
+            &op,
+            &ws_path,
+            &board,
+            &column,
+            &entry_id,
+            after_id.as_deref(),
+        )
+        .await
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let val =
+            serde_json::to_value(placement).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+fn remove_board_entry<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    board: String,
+    entry_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        board::remove_from_board(&op, &ws_path, &board, &entry_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    })
+}
+
+#[pyfunction]
+fn get_board<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    board: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let val = board::get_board(&op, &ws_path, &board)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, user, entry_id, kind="favorite"))]
+fn add_favorite<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    user: String,
+    entry_id: String,
+    kind: &str,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let kind: favorites::FavoriteKind = kind
+        .parse()
+        .map_err(|e: anyhow::Error| PyValueError::new_err(e.to_string()))?;
+    future_into_py(py, request_id(&storage_config)?, async move {
+        favorites::add_favorite(&op, &ws_path, &user, kind, &entry_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, user, entry_id, kind="favorite"))]
+fn remove_favorite<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    user: String,
+    entry_id: String,
+    kind: &str,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let kind: favorites::FavoriteKind = kind
+        .parse()
+        .map_err(|e: anyhow::Error| PyValueError::new_err(e.to_string()))?;
+    future_into_py(py, request_id(&storage_config)?, async move {
+        favorites::remove_favorite(&op, &ws_path, &user, kind, &entry_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, user, kind="favorite"))]
+fn list_favorites<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    user: String,
+    kind: &str,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let kind: favorites::FavoriteKind = kind
+        .parse()
+        .map_err(|e: anyhow::Error| PyValueError::new_err(e.to_string()))?;
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let list = favorites::list_favorites(&op, &ws_path, &user, kind)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let val = serde_json::to_value(list).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, entry_id, position=None, author=None))]
+fn pin_entry<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    entry_id: String,
+    position: Option<usize>,
+    author: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let author = author.unwrap_or_else(|| "unknown".to_string());
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let pinned = favorites::pin_entry(&op, &ws_path, &entry_id, position, &author)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let val =
+            serde_json::to_value(pinned).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+fn unpin_entry<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    entry_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        favorites::unpin_entry(&op, &ws_path, &entry_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    })
+}
+
+#[pyfunction]
+fn list_pinned<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let pinned = favorites::list_pinned(&op, &ws_path)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let val =
+            serde_json::to_value(pinned).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+fn create_folder<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    path: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let result = folder::create_folder(&op, &ws_path, &path)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let val =
+            serde_json::to_value(result).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+fn delete_folder<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    path: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        folder::delete_folder(&op, &ws_path, &path)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, entry_id, folder=None))]
+fn move_entry_to_folder<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    entry_id: String,
+    folder: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        folder::move_entry(&op, &ws_path, &entry_id, folder.as_deref())
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    })
+}
+
+#[pyfunction]
+fn move_folder<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    from_path: String,
+    to_path: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let result = folder::move_folder(&op, &ws_path, &from_path, &to_path)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let val =
+            serde_json::to_value(result).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, path=None))]
+fn list_folder<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    path: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let result = folder::list_children(&op, &ws_path, path.as_deref().unwrap_or(""))
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let val =
+            serde_json::to_value(result).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+fn resolve_entry_path<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    path: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        folder::resolve_entry_path(&op, &ws_path, &path)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, dry_run=true, now=None))]
+fn apply_retention<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    dry_run: bool,
+    now: Option<f64>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let now = now.unwrap_or_else(entry::now_ts);
+        let report = retention::apply_retention(&op, &ws_path, now, dry_run)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, report))
+    })
+}
+
+#[pyfunction]
+fn list_deleted_entries<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let entries = trash::list_deleted_entries(&op, &ws_path)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, Value::Array(entries)))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, dry_run=true, now=None))]
+fn purge_trash<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    dry_run: bool,
+    now: Option<f64>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let now = now.unwrap_or_else(entry::now_ts);
+        let report = trash::purge_trash(&op, &ws_path, now, dry_run)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, report))
+    })
+}
+
+#[pyfunction]
+fn purge_entry<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    entry_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let report = trash::purge_entry(&op, &ws_path, &entry_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, report))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, dry_run=true))]
+fn gc_deleted_entries<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    dry_run: bool,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let report = trash::gc_deleted_entries(&op, &ws_path, dry_run)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, report))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, entry_id, due_at, recurrence=None, payload_json=None))]
+fn add_reminder<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    entry_id: String,
+    due_at: f64,
+    recurrence: Option<String>,
+    payload_json: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let recurrence = recurrence
+        .map(|value| value.parse::<reminder::Recurrence>())
+        .transpose()
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let payload: Value = match payload_json {
+        Some(payload_json) => {
+            serde_json::from_str(&payload_json).map_err(|e| PyValueError::new_err(e.to_string()))?
+        }
+        None => Value::Null,
+    };
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let created = reminder::add_reminder(&op, &ws_path, &entry_id, due_at, recurrence, payload)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let val =
+            serde_json::to_value(created).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, before, form=None))]
+fn list_due_reminders<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    before: f64,
+    form: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let due = reminder::list_due_reminders(&op, &ws_path, before, form.as_deref())
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let val = serde_json::to_value(due).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+fn list_entry_reminders<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    entry_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let reminders = reminder::list_entry_reminders(&op, &ws_path, &entry_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let val =
+            serde_json::to_value(reminders).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+fn complete_reminder<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    reminder_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let completed = reminder::complete_reminder(&op, &ws_path, &reminder_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let val =
+            serde_json::to_value(completed).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+fn delete_reminder<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    reminder_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        reminder::delete_reminder(&op, &ws_path, &reminder_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, template_id, recurrence, first_run_at, variables_json=None, author=None))]
+#[allow(clippy::too_many_arguments)]
+fn add_schedule<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    template_id: String,
+    recurrence: String,
+    first_run_at: f64,
+    variables_json: Option<String>,
+    author: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let author = author.unwrap_or_else(|| "unknown".to_string());
+    let recurrence = recurrence
+        .parse::<reminder::Recurrence>()
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let variables: Value = match variables_json {
+        Some(variables_json) => serde_json::from_str(&variables_json)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?,
+        None => Value::Null,
+    };
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let created = schedule::add_schedule(
+            &op,
+            &ws_path,
+            &template_id,
+            recurrence,
+            first_run_at,
+            variables,
+            &author,
+        )
+        .await
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let val =
+            serde_json::to_value(created).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+fn list_schedules<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let schedules = schedule::list_schedules(&op, &ws_path)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let val =
+            serde_json::to_value(schedules).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+fn delete_schedule<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    schedule_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        schedule::delete_schedule(&op, &ws_path, &schedule_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, now=None))]
+fn run_due_schedules<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    now: Option<f64>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let now = now.unwrap_or_else(entry::now_ts);
+        let integrity = RealIntegrityProvider::from_space(&op, &space_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let report = schedule::run_due_schedules(&op, &ws_path, now, &integrity)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, report))
+    })
+}
+
+#[pyfunction]
+fn register_author_key<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    author: String,
+    public_key: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let val = author_keys::register_author_key(&op, &ws_path, &author, &public_key)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let val = serde_json::to_value(val).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
         Python::with_gil(|py| json_to_py(py, val))
     })
 }
 
 #[pyfunction]
-fn delete_asset<'a>(
+fn list_author_keys<'a>(
     py: Python<'a>,
     storage_config: Bound<'a, PyDict>,
     space_id: String,
-    asset_id: String,
+    author: String,
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
     let ws_path = format!("spaces/{}", space_id);
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
-        asset::delete_asset(&op, &ws_path, &asset_id)
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let val = author_keys::list_author_keys(&op, &ws_path, &author)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
-        Ok(())
+        let val = serde_json::to_value(val).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
     })
 }
 
 #[pyfunction]
-fn get_form<'a>(
+#[pyo3(signature = (storage_config, space_id, name, value, actor=None))]
+fn set_secret<'a>(
     py: Python<'a>,
     storage_config: Bound<'a, PyDict>,
     space_id: String,
-    form_name: String,
+    name: String,
+    value: String,
+    actor: Option<String>,
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
     let ws_path = format!("spaces/{}", space_id);
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
-        let frm = form::get_form(&op, &ws_path, &form_name)
+    let actor = actor.unwrap_or_else(|| "unknown".to_string());
+    future_into_py(py, request_id(&storage_config)?, async move {
+        secrets::set_secret(&op, &ws_path, &name, &value, &actor)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
-        let val = serde_json::to_value(frm).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
-        Python::with_gil(|py| json_to_py(py, val))
+        Ok(())
     })
 }
 
 #[pyfunction]
-fn get_entry_history<'a>(
+fn get_secret<'a>(
     py: Python<'a>,
     storage_config: Bound<'a, PyDict>,
     space_id: String,
-    entry_id: String,
+    name: String,
+    capability: String,
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
     let ws_path = format!("spaces/{}", space_id);
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
-        let history = entry::get_entry_history(&op, &ws_path, &entry_id)
+    future_into_py(py, request_id(&storage_config)?, async move {
+        secrets::get_secret(&op, &ws_path, &name, &capability)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, name, actor=None))]
+fn delete_secret<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    name: String,
+    actor: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let actor = actor.unwrap_or_else(|| "unknown".to_string());
+    future_into_py(py, request_id(&storage_config)?, async move {
+        secrets::delete_secret(&op, &ws_path, &name, &actor)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    })
+}
+
+#[pyfunction]
+fn list_secrets<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let val = secrets::list_secrets(&op, &ws_path)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
-        Python::with_gil(|py| json_to_py(py, history))
+        Python::with_gil(|py| json_to_py(py, Value::Array(val)))
     })
 }
 
 #[pyfunction]
-fn get_entry_revision<'a>(
+#[pyo3(signature = (storage_config, space_id, holder, secrets, ttl_seconds=3600.0))]
+fn issue_secret_capability<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    holder: String,
+    secrets: Vec<String>,
+    ttl_seconds: f64,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        secrets::issue_secret_capability(&op, &ws_path, &holder, &secrets, ttl_seconds)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    })
+}
+
+#[pyfunction]
+fn revision_signing_payload<'a>(
     py: Python<'a>,
     storage_config: Bound<'a, PyDict>,
     space_id: String,
@@ -730,36 +3186,73 @@ fn get_entry_revision<'a>(
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
     let ws_path = format!("spaces/{}", space_id);
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
-        let revision = entry::get_entry_revision(&op, &ws_path, &entry_id, &revision_id)
+    future_into_py(py, request_id(&storage_config)?, async move {
+        author_keys::revision_signing_payload(&op, &ws_path, &entry_id, &revision_id)
             .await
-            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
-        Python::with_gil(|py| json_to_py(py, revision))
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
     })
 }
 
 #[pyfunction]
-#[pyo3(signature = (storage_config, space_id, entry_id, revision_id, author=None))]
-fn restore_entry<'a>(
+fn attach_revision_signature<'a>(
     py: Python<'a>,
     storage_config: Bound<'a, PyDict>,
     space_id: String,
     entry_id: String,
     revision_id: String,
-    author: Option<String>,
+    key_id: String,
+    signature: String,
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
     let ws_path = format!("spaces/{}", space_id);
-    let author = author.unwrap_or_else(|| "unknown".to_string());
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
-        let integrity = RealIntegrityProvider::from_space(&op, &space_id)
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let val = author_keys::attach_revision_signature(
+            &op,
+            &ws_path,
+            &entry_id,
+            &revision_id,
+            &key_id,
+            &signature,
+        )
+        .await
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let val = serde_json::to_value(val).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+fn verify_revision_author<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    entry_id: String,
+    revision_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let val = author_keys::verify_revision_author(&op, &ws_path, &entry_id, &revision_id)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
-        let result =
-            entry::restore_entry(&op, &ws_path, &entry_id, &revision_id, &author, &integrity)
-                .await
-                .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
-        Python::with_gil(|py| json_to_py(py, result))
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+fn suggest_entry_id<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    title: String,
+    form_name: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        entry_id::suggest_entry_id(&op, &ws_path, &title, &form_name)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
     })
 }
 
@@ -772,17 +3265,33 @@ fn extract_properties_py(py: Python<'_>, markdown: String) -> PyResult<PyObject>
 
 #[pyfunction]
 #[pyo3(name = "validate_properties")]
+#[pyo3(signature = (properties_json, form_json, timezone=None, locale=None))]
 fn validate_properties_py(
     py: Python<'_>,
     properties_json: String,
     form_json: String,
+    timezone: Option<String>,
+    locale: Option<String>,
 ) -> PyResult<PyObject> {
     let properties: serde_json::Value =
         serde_json::from_str(&properties_json).map_err(|e| PyValueError::new_err(e.to_string()))?;
     let form_def: serde_json::Value =
         serde_json::from_str(&form_json).map_err(|e| PyValueError::new_err(e.to_string()))?;
-    let (casted, warnings) = index::validate_properties(&properties, &form_def)
+    let tz = match timezone {
+        Some(name) => {
+            SpaceTimeZone::new(&name).map_err(|e| PyValueError::new_err(e.to_string()))?
+        }
+        None => SpaceTimeZone::default(),
+    };
+    let locale = match locale {
+        Some(tag) => tag
+            .parse()
+            .map_err(|e: anyhow::Error| PyValueError::new_err(e.to_string()))?,
+        None => Locale::default(),
+    };
+    let (casted, warnings) = index::validate_properties_in_zone(&properties, &form_def, &tz)
         .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    let warnings = i18n::localize_warnings(&warnings, locale);
     let casted_obj = json_to_py(py, casted)?;
     let warnings_obj = json_to_py(py, serde_json::Value::Array(warnings))?;
     let tuple = PyTuple::new(py, [casted_obj, warnings_obj])?;
@@ -796,7 +3305,7 @@ fn build_response_signature<'a>(
     body: Vec<u8>,
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+    future_into_py(py, request_id(&storage_config)?, async move {
         let (key_id, signature) = integrity::build_response_signature(&op, &body)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
@@ -805,7 +3314,55 @@ fn build_response_signature<'a>(
 }
 
 #[pyfunction]
-#[pyo3(signature = (storage_config, space_id, entry_id, content, parent_revision_id=None, author=None, assets_json=None))]
+fn sign_canonical_response<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    body_json: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let body: serde_json::Value =
+        serde_json::from_str(&body_json).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let signature = integrity::sign_canonical_response(&op, &body)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let val =
+            serde_json::to_value(signature).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, val))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, body_json, key_id, signature, timestamp, nonce, max_skew_seconds=300))]
+#[allow(clippy::too_many_arguments)]
+fn verify_response_signature<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    body_json: String,
+    key_id: String,
+    signature: String,
+    timestamp: i64,
+    nonce: String,
+    max_skew_seconds: i64,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let body: serde_json::Value =
+        serde_json::from_str(&body_json).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let signature = integrity::ResponseSignature {
+        key_id,
+        signature,
+        timestamp,
+        nonce,
+    };
+    future_into_py(py, request_id(&storage_config)?, async move {
+        integrity::verify_response_signature(&op, &body, &signature, max_skew_seconds)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, entry_id, content, parent_revision_id=None, author=None, assets_json=None, message=None, locale=None, capabilities=None))]
 #[allow(clippy::too_many_arguments)]
 fn update_entry<'a>(
     py: Python<'a>,
@@ -816,12 +3373,15 @@ fn update_entry<'a>(
     parent_revision_id: Option<String>,
     author: Option<String>,
     assets_json: Option<String>,
+    message: Option<String>,
+    locale: Option<String>,
+    capabilities: Option<Vec<String>>,
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
     let ws_path = format!("spaces/{}", space_id);
     let author = author.unwrap_or_else(|| "unknown".to_string());
 
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+    future_into_py(py, request_id(&storage_config)?, async move {
         let integrity = RealIntegrityProvider::from_space(&op, &space_id)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
@@ -831,7 +3391,8 @@ fn update_entry<'a>(
                 .map_err(|e| PyRuntimeError::new_err(e.to_string()))?,
             None => None,
         };
-        let meta = entry::update_entry(
+        let locale = resolve_locale(&op, &ws_path, locale.as_deref()).await?;
+        let meta = entry::update_entry_with_capabilities(
             &op,
             &ws_path,
             &entry_id,
@@ -839,21 +3400,111 @@ fn update_entry<'a>(
             parent_revision_id.as_deref(),
             &author,
             assets,
+            message.as_deref(),
+            &capabilities.unwrap_or_default(),
             &integrity,
         )
         .await
-        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        .map_err(|e| localized_error(e, locale))?;
         let val = serde_json::to_value(meta).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
         Python::with_gil(|py| json_to_py(py, val))
     })
 }
 
 #[pyfunction]
+#[pyo3(signature = (storage_config, space_id, entry_id, target_form, field_mapping_json=None, author=None, locale=None, capabilities=None))]
+#[allow(clippy::too_many_arguments)]
+fn change_entry_form<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    entry_id: String,
+    target_form: String,
+    field_mapping_json: Option<String>,
+    author: Option<String>,
+    locale: Option<String>,
+    capabilities: Option<Vec<String>>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let author = author.unwrap_or_else(|| "unknown".to_string());
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let field_mapping = match field_mapping_json {
+            Some(json) => Some(
+                serde_json::from_str::<serde_json::Value>(&json)
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?,
+            ),
+            None => None,
+        };
+        let integrity = RealIntegrityProvider::from_space(&op, &space_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let locale = resolve_locale(&op, &ws_path, locale.as_deref()).await?;
+        let moved = entry::change_entry_form_with_capabilities(
+            &op,
+            &ws_path,
+            &entry_id,
+            &target_form,
+            field_mapping.as_ref(),
+            &author,
+            &capabilities.unwrap_or_default(),
+            &integrity,
+        )
+        .await
+        .map_err(|e| localized_error(e, locale))?;
+        Python::with_gil(|py| json_to_py(py, moved))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, entry_id, patch_json, parent_revision_id=None, author=None, message=None, locale=None))]
+#[allow(clippy::too_many_arguments)]
+fn patch_entry<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    entry_id: String,
+    patch_json: String,
+    parent_revision_id: Option<String>,
+    author: Option<String>,
+    message: Option<String>,
+    locale: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    let author = author.unwrap_or_else(|| "unknown".to_string());
+    let patch: serde_json::Value =
+        serde_json::from_str(&patch_json).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let integrity = RealIntegrityProvider::from_space(&op, &space_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let locale = resolve_locale(&op, &ws_path, locale.as_deref()).await?;
+        let meta = entry::patch_entry(
+            &op,
+            &ws_path,
+            &entry_id,
+            &patch,
+            parent_revision_id.as_deref(),
+            &author,
+            message.as_deref(),
+            &integrity,
+        )
+        .await
+        .map_err(|e| localized_error(e, locale))?;
+        Python::with_gil(|py| json_to_py(py, meta))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, query, fields=None))]
 fn query_index<'a>(
     py: Python<'a>,
     storage_config: Bound<'a, PyDict>,
     space_id: String,
     query: String,
+    fields: Option<Vec<String>>,
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
     let ws_path = format!("spaces/{}", space_id);
@@ -866,15 +3517,67 @@ fn query_index<'a>(
             .unwrap_or(query.clone()),
         Err(_) => query.clone(),
     };
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
-        let res = index::query_index(&op, &ws_path, &adjusted_query)
-            .await
-            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let res = match fields {
+            Some(fields) => {
+                index::query_index_fields(&op, &ws_path, &adjusted_query, &fields).await
+            }
+            None => index::query_index(&op, &ws_path, &adjusted_query).await,
+        }
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
         let val = serde_json::Value::Array(res);
         Python::with_gil(|py| json_to_py(py, val))
     })
 }
 
+#[pyfunction]
+fn query_index_with_facets<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    query: String,
+    facets_json: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let facets: serde_json::Value =
+            serde_json::from_str(&facets_json).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let res = index::query_index_with_facets(&op, &ws_path, &query, &facets)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, res))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, left_form, right_form, on_field, projection=None))]
+fn join_forms<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    left_form: String,
+    right_form: String,
+    on_field: String,
+    projection: Option<Vec<String>>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let rows = index::join(
+            &op,
+            &ws_path,
+            &left_form,
+            &right_form,
+            &on_field,
+            &projection.unwrap_or_default(),
+        )
+        .await
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, serde_json::Value::Array(rows)))
+    })
+}
+
 #[pyfunction]
 fn create_sql_session<'a>(
     py: Python<'a>,
@@ -884,7 +3587,7 @@ fn create_sql_session<'a>(
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
     let ws_path = format!("spaces/{}", space_id);
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+    future_into_py(py, request_id(&storage_config)?, async move {
         let session = sql_session::create_sql_session(&op, &ws_path, &sql)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
@@ -901,7 +3604,7 @@ fn get_sql_session_status<'a>(
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
     let ws_path = format!("spaces/{}", space_id);
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+    future_into_py(py, request_id(&storage_config)?, async move {
         let session = sql_session::get_sql_session_status(&op, &ws_path, &session_id)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
@@ -918,7 +3621,7 @@ fn get_sql_session_count<'a>(
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
     let ws_path = format!("spaces/{}", space_id);
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+    future_into_py(py, request_id(&storage_config)?, async move {
         let count = sql_session::get_sql_session_count(&op, &ws_path, &session_id)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
@@ -938,7 +3641,7 @@ fn get_sql_session_rows<'a>(
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
     let ws_path = format!("spaces/{}", space_id);
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+    future_into_py(py, request_id(&storage_config)?, async move {
         let rows = sql_session::get_sql_session_rows(&op, &ws_path, &session_id, offset, limit)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
@@ -955,7 +3658,7 @@ fn get_sql_session_rows_all<'a>(
 ) -> PyResult<Bound<'a, PyAny>> {
     let op = get_operator(py, &storage_config)?;
     let ws_path = format!("spaces/{}", space_id);
-    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+    future_into_py(py, request_id(&storage_config)?, async move {
         let rows = sql_session::get_sql_session_rows_all(&op, &ws_path, &session_id)
             .await
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
@@ -964,6 +3667,61 @@ fn get_sql_session_rows_all<'a>(
     })
 }
 
+// Access log
+
+#[pyfunction]
+fn record_entry_access<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    entry_id: String,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        access_log::record_entry_access(&op, &ws_path, &entry_id)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Ok(())
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, limit=20))]
+fn list_recent_entries<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    limit: usize,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let entries = access_log::list_recent_entries(&op, &ws_path, limit)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, Value::Array(entries)))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (storage_config, space_id, limit=20))]
+fn list_frequent_entries<'a>(
+    py: Python<'a>,
+    storage_config: Bound<'a, PyDict>,
+    space_id: String,
+    limit: usize,
+) -> PyResult<Bound<'a, PyAny>> {
+    let op = get_operator(py, &storage_config)?;
+    let ws_path = format!("spaces/{}", space_id);
+    future_into_py(py, request_id(&storage_config)?, async move {
+        let entries = access_log::list_frequent_entries(&op, &ws_path, limit)
+            .await
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Python::with_gil(|py| json_to_py(py, Value::Array(entries)))
+    })
+}
+
 // Stubs using generic signature removed; all bindings are implemented.
 
 /// A Python module implemented in Rust.
@@ -978,13 +3736,89 @@ fn _ugoite_core(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(test_storage_connection_py, m)?)?;
 
     m.add_function(wrap_pyfunction!(create_entry, m)?)?;
+    m.add_function(wrap_pyfunction!(save_template, m)?)?;
+    m.add_function(wrap_pyfunction!(list_templates, m)?)?;
+    m.add_function(wrap_pyfunction!(get_template, m)?)?;
+    m.add_function(wrap_pyfunction!(delete_template, m)?)?;
+    m.add_function(wrap_pyfunction!(create_entry_from_template, m)?)?;
     m.add_function(wrap_pyfunction!(delete_entry, m)?)?;
+    m.add_function(wrap_pyfunction!(undelete_entry, m)?)?;
+    m.add_function(wrap_pyfunction!(archive_entry, m)?)?;
+    m.add_function(wrap_pyfunction!(unarchive_entry, m)?)?;
     m.add_function(wrap_pyfunction!(get_entry, m)?)?;
+    m.add("IntegrityError", m.py().get_type::<IntegrityError>())?;
+    m.add_function(wrap_pyfunction!(entry_exists, m)?)?;
+    m.add_function(wrap_pyfunction!(replica_status, m)?)?;
     m.add_function(wrap_pyfunction!(get_entry_history, m)?)?;
     m.add_function(wrap_pyfunction!(get_entry_revision, m)?)?;
     m.add_function(wrap_pyfunction!(list_entries, m)?)?;
+    m.add_function(wrap_pyfunction!(list_entries_page, m)?)?;
+    m.add_function(wrap_pyfunction!(stream_entries, m)?)?;
+    m.add_class::<ProgressIterator>()?;
     m.add_function(wrap_pyfunction!(restore_entry, m)?)?;
+    m.add_function(wrap_pyfunction!(merge_entries, m)?)?;
+    m.add_function(wrap_pyfunction!(duplicate_entry, m)?)?;
+    m.add_function(wrap_pyfunction!(commit_transaction, m)?)?;
+    m.add_function(wrap_pyfunction!(set_entry_metadata, m)?)?;
+    m.add_function(wrap_pyfunction!(redact_entry, m)?)?;
+    m.add_function(wrap_pyfunction!(list_audit_events, m)?)?;
+    m.add_function(wrap_pyfunction!(scan_space_pii, m)?)?;
+    m.add_function(wrap_pyfunction!(export_space, m)?)?;
+    m.add_function(wrap_pyfunction!(export_space_stream, m)?)?;
+    m.add_function(wrap_pyfunction!(export_entry_history, m)?)?;
+    m.add_function(wrap_pyfunction!(query_to_csv, m)?)?;
+    m.add_function(wrap_pyfunction!(query_to_xlsx, m)?)?;
+    m.add_function(wrap_pyfunction!(offline_bundle, m)?)?;
+    m.add_function(wrap_pyfunction!(offline_delta, m)?)?;
+    m.add_function(wrap_pyfunction!(apply_replica_changes, m)?)?;
+    m.add_function(wrap_pyfunction!(import_bundle, m)?)?;
+    m.add_function(wrap_pyfunction!(import_notion, m)?)?;
+    m.add_function(wrap_pyfunction!(import_eml, m)?)?;
+    m.add_function(wrap_pyfunction!(import_bundle_stream, m)?)?;
+    m.add_function(wrap_pyfunction!(verify_export, m)?)?;
+    m.add_function(wrap_pyfunction!(move_board_entry, m)?)?;
+    m.add_function(wrap_pyfunction!(remove_board_entry, m)?)?;
+    m.add_function(wrap_pyfunction!(get_board, m)?)?;
+    m.add_function(wrap_pyfunction!(add_favorite, m)?)?;
+    m.add_function(wrap_pyfunction!(remove_favorite, m)?)?;
+    m.add_function(wrap_pyfunction!(list_favorites, m)?)?;
+    m.add_function(wrap_pyfunction!(pin_entry, m)?)?;
+    m.add_function(wrap_pyfunction!(unpin_entry, m)?)?;
+    m.add_function(wrap_pyfunction!(list_pinned, m)?)?;
+    m.add_function(wrap_pyfunction!(create_folder, m)?)?;
+    m.add_function(wrap_pyfunction!(delete_folder, m)?)?;
+    m.add_function(wrap_pyfunction!(move_entry_to_folder, m)?)?;
+    m.add_function(wrap_pyfunction!(move_folder, m)?)?;
+    m.add_function(wrap_pyfunction!(list_folder, m)?)?;
+    m.add_function(wrap_pyfunction!(resolve_entry_path, m)?)?;
+    m.add_function(wrap_pyfunction!(apply_retention, m)?)?;
+    m.add_function(wrap_pyfunction!(purge_trash, m)?)?;
+    m.add_function(wrap_pyfunction!(purge_entry, m)?)?;
+    m.add_function(wrap_pyfunction!(gc_deleted_entries, m)?)?;
+    m.add_function(wrap_pyfunction!(list_deleted_entries, m)?)?;
+    m.add_function(wrap_pyfunction!(add_reminder, m)?)?;
+    m.add_function(wrap_pyfunction!(list_due_reminders, m)?)?;
+    m.add_function(wrap_pyfunction!(list_entry_reminders, m)?)?;
+    m.add_function(wrap_pyfunction!(complete_reminder, m)?)?;
+    m.add_function(wrap_pyfunction!(delete_reminder, m)?)?;
+    m.add_function(wrap_pyfunction!(add_schedule, m)?)?;
+    m.add_function(wrap_pyfunction!(list_schedules, m)?)?;
+    m.add_function(wrap_pyfunction!(delete_schedule, m)?)?;
+    m.add_function(wrap_pyfunction!(run_due_schedules, m)?)?;
+    m.add_function(wrap_pyfunction!(register_author_key, m)?)?;
+    m.add_function(wrap_pyfunction!(list_author_keys, m)?)?;
+    m.add_function(wrap_pyfunction!(set_secret, m)?)?;
+    m.add_function(wrap_pyfunction!(get_secret, m)?)?;
+    m.add_function(wrap_pyfunction!(delete_secret, m)?)?;
+    m.add_function(wrap_pyfunction!(list_secrets, m)?)?;
+    m.add_function(wrap_pyfunction!(issue_secret_capability, m)?)?;
+    m.add_function(wrap_pyfunction!(revision_signing_payload, m)?)?;
+    m.add_function(wrap_pyfunction!(attach_revision_signature, m)?)?;
+    m.add_function(wrap_pyfunction!(verify_revision_author, m)?)?;
+    m.add_function(wrap_pyfunction!(suggest_entry_id, m)?)?;
     m.add_function(wrap_pyfunction!(update_entry, m)?)?;
+    m.add_function(wrap_pyfunction!(change_entry_form, m)?)?;
+    m.add_function(wrap_pyfunction!(patch_entry, m)?)?;
     m.add_function(wrap_pyfunction!(list_sql, m)?)?;
     m.add_function(wrap_pyfunction!(get_sql, m)?)?;
     m.add_function(wrap_pyfunction!(create_sql, m)?)?;
@@ -995,30 +3829,65 @@ fn _ugoite_core(m: &Bound<'_, PyModule>) -> PyResult<()> {
 
     m.add_function(wrap_pyfunction!(list_forms, m)?)?;
     m.add_function(wrap_pyfunction!(upsert_form, m)?)?;
+    m.add_function(wrap_pyfunction!(get_form_history, m)?)?;
+    m.add_function(wrap_pyfunction!(get_form_version, m)?)?;
+    m.add_function(wrap_pyfunction!(rollback_form, m)?)?;
     m.add_function(wrap_pyfunction!(get_form, m)?)?;
+    m.add_function(wrap_pyfunction!(recompute_rollups, m)?)?;
     m.add_function(wrap_pyfunction!(list_column_types, m)?)?;
     m.add_function(wrap_pyfunction!(migrate_form, m)?)?;
+    m.add_function(wrap_pyfunction!(field_lineage, m)?)?;
 
     m.add_function(wrap_pyfunction!(save_asset, m)?)?;
     m.add_function(wrap_pyfunction!(list_assets, m)?)?;
+    m.add_function(wrap_pyfunction!(read_asset_range, m)?)?;
     m.add_function(wrap_pyfunction!(delete_asset, m)?)?;
+    m.add_function(wrap_pyfunction!(backfill_asset_integrity, m)?)?;
+    m.add_function(wrap_pyfunction!(space_merkle_root, m)?)?;
+    m.add_function(wrap_pyfunction!(rotate_space_key, m)?)?;
+    m.add_function(wrap_pyfunction!(register_content_extractor, m)?)?;
+    m.add_function(wrap_pyfunction!(clear_content_extractors, m)?)?;
+    m.add_function(wrap_pyfunction!(register_form_hook, m)?)?;
+    m.add_function(wrap_pyfunction!(clear_form_hooks, m)?)?;
+    m.add_function(wrap_pyfunction!(set_asset_text, m)?)?;
+    m.add_function(wrap_pyfunction!(get_asset_text, m)?)?;
 
     m.add_function(wrap_pyfunction!(get_space, m)?)?;
     m.add_function(wrap_pyfunction!(patch_space, m)?)?;
+    m.add_function(wrap_pyfunction!(upgrade_space_format, m)?)?;
+    m.add_function(wrap_pyfunction!(activity_digest, m)?)?;
+    m.add_function(wrap_pyfunction!(diff_snapshots, m)?)?;
 
     m.add_function(wrap_pyfunction!(query_index, m)?)?;
+    m.add_function(wrap_pyfunction!(query_index_with_facets, m)?)?;
+    m.add_function(wrap_pyfunction!(join_forms, m)?)?;
     m.add_function(wrap_pyfunction!(create_sql_session, m)?)?;
     m.add_function(wrap_pyfunction!(get_sql_session_status, m)?)?;
     m.add_function(wrap_pyfunction!(get_sql_session_count, m)?)?;
     m.add_function(wrap_pyfunction!(get_sql_session_rows, m)?)?;
     m.add_function(wrap_pyfunction!(get_sql_session_rows_all, m)?)?;
     m.add_function(wrap_pyfunction!(reindex_all, m)?)?;
+    m.add_function(wrap_pyfunction!(reindex_form, m)?)?;
+    m.add_function(wrap_pyfunction!(reindex_incremental, m)?)?;
     m.add_function(wrap_pyfunction!(update_entry_index, m)?)?;
+    m.add_function(wrap_pyfunction!(index_status, m)?)?;
+    m.add_function(wrap_pyfunction!(flush_contention_stats, m)?)?;
+    m.add_function(wrap_pyfunction!(contention_stats, m)?)?;
+    m.add_function(wrap_pyfunction!(rank_spaces_by_contention, m)?)?;
+    m.add_function(wrap_pyfunction!(flush_slow_log, m)?)?;
+    m.add_function(wrap_pyfunction!(list_slow_operations, m)?)?;
 
     m.add_function(wrap_pyfunction!(search_entries, m)?)?;
     m.add_function(wrap_pyfunction!(build_response_signature, m)?)?;
+    m.add_function(wrap_pyfunction!(sign_canonical_response, m)?)?;
+    m.add_function(wrap_pyfunction!(verify_response_signature, m)?)?;
     m.add_function(wrap_pyfunction!(load_hmac_material, m)?)?;
+    m.add_function(wrap_pyfunction!(load_signing_public_key, m)?)?;
     m.add_function(wrap_pyfunction!(load_response_hmac_material, m)?)?;
 
+    m.add_function(wrap_pyfunction!(record_entry_access, m)?)?;
+    m.add_function(wrap_pyfunction!(list_recent_entries, m)?)?;
+    m.add_function(wrap_pyfunction!(list_frequent_entries, m)?)?;
+
     Ok(())
 }
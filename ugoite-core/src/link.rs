@@ -1,8 +1,11 @@
 use anyhow::{anyhow, Result};
 use opendal::Operator;
 use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
 
-use crate::entry::{find_entry_form, read_entry_row, write_entry_row};
+use crate::clock;
+use crate::entry::{find_entry_form, read_entry_row, write_entry_row, EntryRow};
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Link {
@@ -61,7 +64,7 @@ async fn update_entry_links(
     let mut row = read_entry_row(op, ws_path, form_name, entry_id).await?;
     row.links.retain(|l| l.id != link.id);
     row.links.push(link);
-    row.updated_at = crate::entry::now_ts();
+    row.updated_at = clock::next_micros(ws_path, Some(row.updated_at));
     write_entry_row(op, ws_path, form_name, entry_id, &row).await?;
     Ok(())
 }
@@ -91,7 +94,7 @@ pub async fn delete_link(op: &Operator, ws_path: &str, link_id: &str) -> Result<
         row.links.retain(|l| l.id != link_id);
         if row.links.len() != initial_len {
             found = true;
-            row.updated_at = crate::entry::now_ts();
+            row.updated_at = clock::next_micros(ws_path, Some(row.updated_at));
             write_entry_row(op, ws_path, &form_name, &row.entry_id, &row).await?;
         }
     }
@@ -102,3 +105,91 @@ pub async fn delete_link(op: &Operator, ws_path: &str, link_id: &str) -> Result<
 
     Ok(())
 }
+
+/// The entry at the other end of `link` from `entry_id`.
+fn other_end<'a>(link: &'a Link, entry_id: &str) -> &'a str {
+    if link.source == entry_id {
+        &link.target
+    } else {
+        &link.source
+    }
+}
+
+/// Removes the reciprocal records live entries keep of `row`'s links, so a
+/// deleted entry drops out of the link graph. The deleted row keeps its own
+/// records for [`plan_reattach_links`].
+pub(crate) async fn detach_entry_links(op: &Operator, ws_path: &str, row: &EntryRow) -> Result<()> {
+    let entry_id = row.entry_id.as_str();
+    for link in &row.links {
+        let other = other_end(link, entry_id);
+        let Some(other_form) = find_entry_form(op, ws_path, other).await? else {
+            continue;
+        };
+        let mut other_row = read_entry_row(op, ws_path, &other_form, other).await?;
+        if other_row.deleted {
+            continue;
+        }
+        let before = other_row.links.len();
+        other_row
+            .links
+            .retain(|l| l.id != link.id || (l.source != entry_id && l.target != entry_id));
+        if other_row.links.len() == before {
+            continue;
+        }
+        other_row.updated_at = clock::next_micros(ws_path, Some(other_row.updated_at));
+        write_entry_row(op, ws_path, &other_form, other, &other_row).await?;
+    }
+    Ok(())
+}
+
+/// Works out how to put a restored entry's links back into the graph,
+/// without writing anything: the live entries that need their reciprocal
+/// record again, by form. Links to entries that are deleted or gone are
+/// dropped from `row`; a deleted entry re-adds its side when it is restored.
+pub(crate) async fn plan_reattach_links(
+    op: &Operator,
+    ws_path: &str,
+    row: &mut EntryRow,
+) -> Result<Vec<(String, EntryRow)>> {
+    let entry_id = row.entry_id.clone();
+    let mut others: HashMap<String, (String, EntryRow)> = HashMap::new();
+    let mut changed = HashSet::new();
+    let mut kept = Vec::new();
+    for link in std::mem::take(&mut row.links) {
+        let other = other_end(&link, &entry_id).to_string();
+        if other == entry_id {
+            kept.push(link);
+            continue;
+        }
+        let (_, other_row) = match others.entry(other.clone()) {
+            Entry::Occupied(slot) => slot.into_mut(),
+            Entry::Vacant(slot) => {
+                let Some(other_form) = find_entry_form(op, ws_path, &other).await? else {
+                    continue;
+                };
+                let other_row = read_entry_row(op, ws_path, &other_form, &other).await?;
+                slot.insert((other_form, other_row))
+            }
+        };
+        if other_row.deleted {
+            continue;
+        }
+        if !other_row.links.iter().any(|l| l.id == link.id) {
+            other_row.links.push(Link {
+                id: link.id.clone(),
+                source: link.target.clone(),
+                target: link.source.clone(),
+                kind: link.kind.clone(),
+            });
+            other_row.updated_at = clock::next_micros(ws_path, Some(other_row.updated_at));
+            changed.insert(other);
+        }
+        kept.push(link);
+    }
+    row.links = kept;
+    Ok(others
+        .into_iter()
+        .filter(|(id, _)| changed.contains(id))
+        .map(|(_, update)| update)
+        .collect())
+}
@@ -0,0 +1,400 @@
+use anyhow::{anyhow, Result};
+use opendal::Operator;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::clock;
+use crate::entry::{self, EntryRow};
+use crate::form;
+use crate::integrity::IntegrityProvider;
+use crate::link::Link;
+
+/// How one field is combined when two entries are merged.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldStrategy {
+    /// Keep the primary's value; use the secondary's only where the primary
+    /// has none.
+    #[default]
+    PreferPrimary,
+    /// Take the secondary's value wherever it has one.
+    PreferSecondary,
+    /// Join differing text with a blank line and union lists.
+    Combine,
+}
+
+/// `default` applies to every field unless `fields` names it.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct MergeStrategy {
+    pub default: FieldStrategy,
+    pub fields: HashMap<String, FieldStrategy>,
+}
+
+impl MergeStrategy {
+    pub fn for_field(&self, name: &str) -> FieldStrategy {
+        self.fields.get(name).copied().unwrap_or(self.default)
+    }
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => s.trim().is_empty(),
+        Value::Array(items) => items.is_empty(),
+        Value::Object(map) => map.is_empty(),
+        _ => false,
+    }
+}
+
+fn combine(primary: &Value, secondary: &Value) -> Value {
+    match (primary, secondary) {
+        (Value::Array(a), Value::Array(b)) => {
+            let mut items = a.clone();
+            for item in b {
+                if !items.contains(item) {
+                    items.push(item.clone());
+                }
+            }
+            Value::Array(items)
+        }
+        (Value::String(a), Value::String(b)) if a.trim() != b.trim() => {
+            Value::String(format!("{}\n\n{}", a.trim_end(), b.trim()))
+        }
+        _ => primary.clone(),
+    }
+}
+
+fn merge_value(
+    strategy: FieldStrategy,
+    primary: Option<&Value>,
+    secondary: Option<&Value>,
+) -> Option<Value> {
+    let primary = primary.filter(|value| !is_empty(value));
+    let secondary = secondary.filter(|value| !is_empty(value));
+    match (primary, secondary) {
+        (None, None) => None,
+        (Some(value), None) | (None, Some(value)) => Some(value.clone()),
+        (Some(a), Some(b)) => Some(match strategy {
+            FieldStrategy::PreferPrimary => a.clone(),
+            FieldStrategy::PreferSecondary => b.clone(),
+            FieldStrategy::Combine => combine(a, b),
+        }),
+    }
+}
+
+async fn live_row(op: &Operator, ws_path: &str, entry_id: &str) -> Result<(String, EntryRow)> {
+    let form_name = entry::find_entry_form(op, ws_path, entry_id)
+        .await?
+        .ok_or_else(|| entry::entry_not_found(entry_id))?;
+    let row = entry::read_entry_row(op, ws_path, &form_name, entry_id).await?;
+    if row.deleted {
+        return Err(entry::entry_not_found(entry_id));
+    }
+    Ok((form_name, row))
+}
+
+/// Moves the secondary's links to the primary and repoints the reciprocal
+/// records on the linked entries. Links between the two are dropped.
+async fn move_links(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+    primary_id: &str,
+    secondary: &EntryRow,
+) -> Result<usize> {
+    let secondary_id = secondary.entry_id.as_str();
+    let mut primary = entry::read_entry_row(op, ws_path, form_name, primary_id).await?;
+    let links_before = primary.links.clone();
+    let mut moved = 0;
+    for link in &secondary.links {
+        let other = if link.source == secondary_id {
+            link.target.as_str()
+        } else {
+            link.source.as_str()
+        };
+        if other == primary_id {
+            primary.links.retain(|l| l.id != link.id);
+            continue;
+        }
+        if !primary.links.iter().any(|l| l.id == link.id) {
+            primary.links.push(Link {
+                id: link.id.clone(),
+                source: primary_id.to_string(),
+                target: other.to_string(),
+                kind: link.kind.clone(),
+            });
+            moved += 1;
+        }
+        let Some(other_form) = entry::find_entry_form(op, ws_path, other).await? else {
+            continue;
+        };
+        let mut other_row = entry::read_entry_row(op, ws_path, &other_form, other).await?;
+        for reciprocal in other_row.links.iter_mut().filter(|l| l.id == link.id) {
+            if reciprocal.source == secondary_id {
+                reciprocal.source = primary_id.to_string();
+            }
+            if reciprocal.target == secondary_id {
+                reciprocal.target = primary_id.to_string();
+            }
+        }
+        other_row.updated_at = clock::next_micros(ws_path, Some(other_row.updated_at));
+        entry::write_entry_row(op, ws_path, &other_form, other, &other_row).await?;
+    }
+    if primary.links != links_before {
+        primary.updated_at = clock::next_micros(ws_path, Some(primary.updated_at));
+        entry::write_entry_row(op, ws_path, form_name, primary_id, &primary).await?;
+    }
+    Ok(moved)
+}
+
+fn rewrite_reference(
+    value: &Value,
+    is_reference: bool,
+    from: &str,
+    to: &str,
+    uri: &Regex,
+) -> Value {
+    match value {
+        Value::String(s) if is_reference && s.trim() == from => Value::String(to.to_string()),
+        Value::String(s) => {
+            let rewritten = uri.replace_all(s, |caps: &regex::Captures| {
+                if &caps[1] == from {
+                    format!("ugoite://entry/{}", to)
+                } else {
+                    caps[0].to_string()
+                }
+            });
+            Value::String(rewritten.into_owned())
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| rewrite_reference(item, is_reference, from, to, uri))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Repoints row_reference fields and `ugoite://entry/` links in other
+/// entries from `from` to `to`, one revision per changed entry.
+async fn rewrite_references<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    from: &str,
+    to: &str,
+    author: &str,
+    message: &str,
+    integrity: &I,
+) -> Result<Vec<String>> {
+    let uri = Regex::new(r"ugoite://entry/([^\s)\]/?#]+)")?;
+    let mut forms: HashMap<String, Value> = HashMap::new();
+    let mut rewritten = Vec::new();
+    for (form_name, row) in entry::list_entry_rows(op, ws_path).await? {
+        if row.deleted || row.entry_id == from || row.entry_id == to {
+            continue;
+        }
+        if !forms.contains_key(&form_name) {
+            let form_def = form::read_form_definition(op, ws_path, &form_name).await?;
+            forms.insert(form_name.clone(), form_def);
+        }
+        let form_def = &forms[&form_name];
+        let references: HashSet<&str> = form_def
+            .get("fields")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+            .filter(|(_, def)| def.get("type").and_then(Value::as_str) == Some("row_reference"))
+            .map(|(name, _)| name.as_str())
+            .collect();
+        let rewrite = |values: &Value| -> Value {
+            let Some(map) = values.as_object() else {
+                return values.clone();
+            };
+            Value::Object(
+                map.iter()
+                    .map(|(name, value)| {
+                        let is_reference = references.contains(name.as_str());
+                        (
+                            name.clone(),
+                            rewrite_reference(value, is_reference, from, to, &uri),
+                        )
+                    })
+                    .collect(),
+            )
+        };
+        let fields = rewrite(&row.fields);
+        let extra_attributes = rewrite(&row.extra_attributes);
+        if fields == row.fields && extra_attributes == row.extra_attributes {
+            continue;
+        }
+        // Listed rows carry no revision id; it lives in the revisions table.
+        let current = entry::read_entry_row(op, ws_path, &form_name, &row.entry_id).await?;
+        let content = entry::render_markdown_for_form(
+            &row.title,
+            &form_name,
+            &row.tags,
+            &fields,
+            &extra_attributes,
+            form_def,
+        );
+        entry::update_entry(
+            op,
+            ws_path,
+            &row.entry_id,
+            &content,
+            Some(&current.revision_id),
+            author,
+            None,
+            Some(message),
+            integrity,
+        )
+        .await?;
+        rewritten.push(row.entry_id.clone());
+    }
+    rewritten.sort();
+    Ok(rewritten)
+}
+
+/// Merges `secondary_id` into `primary_id`.
+///
+/// Fields, tags and assets are combined into a new revision of the primary
+/// using `strategy`; secondary fields the primary's form cannot hold are
+/// reported under `skipped_fields`. The secondary's links move to the
+/// primary, and references to it in other entries are rewritten. The
+/// secondary is then soft-deleted with `merged_into` in its metadata, while
+/// the primary lists it under `merged_from`, so both histories stay
+/// reachable.
+pub async fn merge_entries<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    primary_id: &str,
+    secondary_id: &str,
+    strategy: &MergeStrategy,
+    author: &str,
+    integrity: &I,
+) -> Result<Value> {
+    if primary_id == secondary_id {
+        return Err(anyhow!("Cannot merge an entry into itself"));
+    }
+    let (form_name, primary) = live_row(op, ws_path, primary_id).await?;
+    let (_, secondary) = live_row(op, ws_path, secondary_id).await?;
+    let form_def = form::read_form_definition(op, ws_path, &form_name).await?;
+    let form_fields: HashSet<String> = entry::form_field_names(&form_def).into_iter().collect();
+    let allow_extra = entry::allows_extra_attributes(&form_def);
+
+    let primary_values = entry::merge_entry_fields(&primary.fields, &primary.extra_attributes);
+    let secondary_values =
+        entry::merge_entry_fields(&secondary.fields, &secondary.extra_attributes);
+    let names: BTreeSet<&String> = primary_values
+        .as_object()
+        .into_iter()
+        .chain(secondary_values.as_object())
+        .flat_map(|map| map.keys())
+        .collect();
+    let mut fields = Map::new();
+    let mut extra_attributes = Map::new();
+    let mut skipped_fields = Vec::new();
+    for name in names {
+        let Some(value) = merge_value(
+            strategy.for_field(name),
+            primary_values.get(name),
+            secondary_values.get(name),
+        ) else {
+            continue;
+        };
+        if form_fields.contains(name) {
+            fields.insert(name.clone(), value);
+        } else if allow_extra {
+            extra_attributes.insert(name.clone(), value);
+        } else {
+            skipped_fields.push(name.clone());
+        }
+    }
+
+    let mut tags = primary.tags.clone();
+    for tag in &secondary.tags {
+        if !tags.contains(tag) {
+            tags.push(tag.clone());
+        }
+    }
+    let mut assets = primary.assets.clone();
+    for asset in &secondary.assets {
+        let id = asset.get("id");
+        if !assets.iter().any(|existing| existing.get("id") == id) {
+            assets.push(asset.clone());
+        }
+    }
+
+    let message = format!(
+        "Merged {} (revision {}) into {}",
+        secondary_id, secondary.revision_id, primary_id
+    );
+    let content = entry::render_markdown_for_form(
+        &primary.title,
+        &form_name,
+        &tags,
+        &Value::Object(fields),
+        &Value::Object(extra_attributes),
+        &form_def,
+    );
+    let merged = entry::update_entry(
+        op,
+        ws_path,
+        primary_id,
+        &content,
+        Some(&primary.revision_id),
+        author,
+        Some(assets),
+        Some(&message),
+        integrity,
+    )
+    .await?;
+
+    let links_moved = move_links(op, ws_path, &form_name, primary_id, &secondary).await?;
+    let references_rewritten = rewrite_references(
+        op,
+        ws_path,
+        secondary_id,
+        primary_id,
+        author,
+        &message,
+        integrity,
+    )
+    .await?;
+
+    let mut merged_from = primary
+        .metadata
+        .get("merged_from")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    merged_from.push(Value::String(secondary_id.to_string()));
+    entry::set_entry_metadata(
+        op,
+        ws_path,
+        primary_id,
+        &serde_json::json!({"merged_from": merged_from}),
+    )
+    .await?;
+    entry::set_entry_metadata(
+        op,
+        ws_path,
+        secondary_id,
+        &serde_json::json!({"merged_into": primary_id}),
+    )
+    .await?;
+    entry::delete_entry(op, ws_path, secondary_id, false).await?;
+
+    Ok(serde_json::json!({
+        "primary": primary_id,
+        "secondary": secondary_id,
+        "revision_id": merged["revision_id"],
+        "skipped_fields": skipped_fields,
+        "links_moved": links_moved,
+        "references_rewritten": references_rewritten,
+    }))
+}
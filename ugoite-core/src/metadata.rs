@@ -15,6 +15,8 @@ const DEFAULT_METADATA_COLUMNS: &[&str] = &[
     "parent_revision_id",
     "deleted",
     "deleted_at",
+    "archived",
+    "hard_deleted",
     "author",
     "integrity",
     "space_id",
@@ -0,0 +1,713 @@
+use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
+use opendal::Operator;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+use crate::asset;
+use crate::entry;
+use crate::entry_id;
+use crate::export;
+use crate::form;
+use crate::integrity::IntegrityProvider;
+use crate::metadata;
+use crate::url_field;
+
+/// Form for Notion pages that are not rows of an exported database.
+pub const PAGE_FORM: &str = "NotionPage";
+/// Field holding a page's markdown body.
+pub const BODY_FIELD: &str = "Body";
+/// Date formats Notion uses in database CSVs, depending on the workspace.
+const NOTION_DATE_FORMATS: [&str; 5] =
+    ["%B %d, %Y", "%b %d, %Y", "%Y/%m/%d", "%Y-%m-%d", "%m/%d/%Y"];
+
+/// `Page Title 0123...cdef`: a name followed by a 32-hex Notion id.
+fn notion_name_regex() -> &'static Regex {
+    static NOTION_NAME_REGEX: OnceLock<Regex> = OnceLock::new();
+    NOTION_NAME_REGEX.get_or_init(|| {
+        Regex::new(r"^(.*?)\s*([0-9a-f]{32})$").expect("notion name regex must compile")
+    })
+}
+
+/// `[text](href)` and `![alt](href)`.
+fn markdown_link_regex() -> &'static Regex {
+    static MARKDOWN_LINK_REGEX: OnceLock<Regex> = OnceLock::new();
+    MARKDOWN_LINK_REGEX.get_or_init(|| {
+        Regex::new(r"(!?)\[([^\]]*)\]\(([^)\s]+)\)").expect("markdown link regex must compile")
+    })
+}
+
+/// `Title (Title%20id.md)`: how relation cells reference pages.
+fn relation_link_regex() -> &'static Regex {
+    static RELATION_LINK_REGEX: OnceLock<Regex> = OnceLock::new();
+    RELATION_LINK_REGEX.get_or_init(|| {
+        Regex::new(r"\(([^()\s]+\.md)\)").expect("relation link regex must compile")
+    })
+}
+
+/// Splits a file stem into its title and Notion id, if it has one.
+fn split_notion_name(stem: &str) -> (String, Option<String>) {
+    match notion_name_regex().captures(stem) {
+        Some(caps) => (caps[1].trim().to_string(), Some(caps[2].to_string())),
+        None => (stem.trim().to_string(), None),
+    }
+}
+
+fn parent_dir(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(dir, _)| dir)
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit_once('/').map_or(path, |(_, name)| name)
+}
+
+fn file_stem(path: &str) -> &str {
+    let name = file_name(path);
+    name.rsplit_once('.').map_or(name, |(stem, _)| stem)
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            if let Some(byte) = text
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Resolves a relative link `href` from a file in `base_dir` to an archive
+/// path. `None` for external links and links escaping the archive.
+fn resolve_href(base_dir: &str, href: &str) -> Option<String> {
+    if href.contains("://") || href.starts_with("mailto:") || href.starts_with('#') {
+        return None;
+    }
+    let href = href.split(['#', '?']).next().unwrap_or_default();
+    let mut parts: Vec<String> = base_dir
+        .split('/')
+        .filter(|part| !part.is_empty())
+        .map(str::to_string)
+        .collect();
+    for part in percent_decode(href).split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            part => parts.push(part.to_string()),
+        }
+    }
+    Some(parts.join("/"))
+}
+
+/// Minimal RFC 4180 reader; Notion writes a UTF-8 BOM and CRLF lines.
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(ch) = chars.next() {
+        if quoted {
+            match ch {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => quoted = false,
+                ch => field.push(ch),
+            }
+            continue;
+        }
+        match ch {
+            '"' => quoted = true,
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            ch => field.push(ch),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+fn notion_date(value: &str) -> Option<NaiveDate> {
+    NOTION_DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value.trim(), format).ok())
+}
+
+/// Field type for a database column from its values: `number`, `boolean`
+/// (Notion checkboxes export as `Yes`/`No`), `date`, `url` or `string`.
+fn infer_field_type(values: &[&str]) -> &'static str {
+    let present: Vec<&str> = values
+        .iter()
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+        .collect();
+    if present.is_empty() {
+        "string"
+    } else if present
+        .iter()
+        .all(|value| value.parse::<f64>().is_ok_and(f64::is_finite))
+    {
+        "number"
+    } else if present.iter().all(|value| {
+        matches!(
+            value.to_lowercase().as_str(),
+            "yes" | "no" | "true" | "false"
+        )
+    }) {
+        "boolean"
+    } else if present.iter().all(|value| notion_date(value).is_some()) {
+        "date"
+    } else if present
+        .iter()
+        .all(|value| url_field::normalize_url(value).is_some())
+    {
+        "url"
+    } else {
+        "string"
+    }
+}
+
+/// A form name from a database title: its words in CamelCase.
+fn form_name_for(title: &str) -> String {
+    let name: String = title
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect();
+    match name.chars().next() {
+        None => "NotionDatabase".to_string(),
+        Some(first) if first.is_ascii_digit() => format!("Notion{}", name),
+        Some(_) => name,
+    }
+}
+
+/// `base`, or `base 2`, `base 3`, ... when taken (case-insensitively).
+fn unique_name(base: &str, taken: &HashSet<String>, separator: &str) -> String {
+    let mut name = base.to_string();
+    let mut n = 1;
+    while taken.contains(&name.to_lowercase()) {
+        n += 1;
+        name = format!("{}{}{}", base, separator, n);
+    }
+    name
+}
+
+/// Splits a page's markdown into its `# title` (if any) and the rest.
+fn split_title(text: &str) -> (Option<String>, String) {
+    let text = text.trim_start_matches('\u{feff}');
+    let Some(first) = text.lines().find(|line| !line.trim().is_empty()) else {
+        return (None, String::new());
+    };
+    match first.strip_prefix("# ") {
+        Some(title) => {
+            let rest = text.split_once(first).map_or("", |(_, rest)| rest);
+            (Some(title.trim().to_string()), rest.to_string())
+        }
+        None => (None, text.to_string()),
+    }
+}
+
+/// Drops the `Property: value` lines Notion writes under a database row's
+/// title; the values come from the CSV instead.
+fn strip_property_lines(body: &str, columns: &HashSet<String>) -> String {
+    let mut lines = body.lines().peekable();
+    while let Some(line) = lines.peek() {
+        let is_property = line
+            .split_once(':')
+            .is_some_and(|(key, _)| columns.contains(key.trim()));
+        if line.trim().is_empty() || is_property {
+            lines.next();
+        } else {
+            break;
+        }
+    }
+    lines.collect::<Vec<_>>().join("\n")
+}
+
+/// One imported page: a database row or a standalone page.
+struct Page {
+    source: String,
+    /// The CSV for a database row, whose relation links are relative to it.
+    fields_source: String,
+    notion_id: Option<String>,
+    title: String,
+    form: String,
+    /// `(field, type, value)` in form order.
+    fields: Vec<(String, String, String)>,
+    body: Option<String>,
+    entry_id: String,
+}
+
+struct Database {
+    source: String,
+    form: String,
+    /// `(field, type)` per column after the title column.
+    fields: Vec<(String, &'static str)>,
+    body_field: String,
+    renamed: Map<String, Value>,
+}
+
+/// Where archive paths and Notion ids point after the import.
+#[derive(Default)]
+struct LinkTargets {
+    pages: HashMap<String, String>,
+    notion_ids: HashMap<String, String>,
+    assets: HashMap<String, String>,
+}
+
+#[derive(Default)]
+struct LinkStats {
+    remapped: usize,
+    unresolved: Vec<Value>,
+}
+
+impl LinkTargets {
+    fn resolve(&self, base_dir: &str, href: &str) -> Option<Option<String>> {
+        let path = resolve_href(base_dir, href)?;
+        if let Some(entry_id) = self.pages.get(&path) {
+            return Some(Some(format!("ugoite://entry/{}", entry_id)));
+        }
+        if let Some(link) = self.assets.get(&path) {
+            return Some(Some(link.clone()));
+        }
+        let (_, notion_id) = split_notion_name(file_stem(&path));
+        Some(
+            notion_id
+                .and_then(|id| self.notion_ids.get(&id))
+                .map(|entry_id| format!("ugoite://entry/{}", entry_id)),
+        )
+    }
+
+    /// Points markdown links at imported entries and assets. Links to
+    /// files outside the archive are kept and reported.
+    fn remap_markdown(
+        &self,
+        source: &str,
+        text: &str,
+        relations: bool,
+        stats: &mut LinkStats,
+    ) -> String {
+        let base_dir = parent_dir(source);
+        let regex = if relations {
+            relation_link_regex()
+        } else {
+            markdown_link_regex()
+        };
+        regex
+            .replace_all(text, |caps: &regex::Captures| {
+                let href = if relations { &caps[1] } else { &caps[3] };
+                let resolved = match self.resolve(base_dir, href) {
+                    None => return caps[0].to_string(),
+                    Some(Some(target)) => target,
+                    Some(None) => {
+                        stats.unresolved.push(serde_json::json!({
+                            "source": source,
+                            "href": href,
+                        }));
+                        return caps[0].to_string();
+                    }
+                };
+                stats.remapped += 1;
+                if relations {
+                    format!("({})", resolved)
+                } else {
+                    format!("{}[{}]({})", &caps[1], &caps[2], resolved)
+                }
+            })
+            .into_owned()
+    }
+}
+
+/// Most bytes an export may unpack to, nested part zips included.
+const MAX_UNPACKED_BYTES: u64 = 1 << 30;
+/// Levels of zips inside the export: one for the per-part zips of large
+/// exports, plus a zip attached to a page.
+const MAX_ZIP_DEPTH: usize = 3;
+
+/// Unpacks the archive, expanding the per-part zips of large exports.
+/// Fails once the export unpacks to more than [`MAX_UNPACKED_BYTES`] or
+/// nests zips deeper than [`MAX_ZIP_DEPTH`].
+fn unpack(archive: &[u8]) -> Result<Vec<(String, Vec<u8>)>> {
+    let mut files = Vec::new();
+    let mut remaining = MAX_UNPACKED_BYTES;
+    unpack_into(archive, 1, &mut remaining, &mut files)?;
+    Ok(files)
+}
+
+fn unpack_into(
+    archive: &[u8],
+    depth: usize,
+    remaining: &mut u64,
+    files: &mut Vec<(String, Vec<u8>)>,
+) -> Result<()> {
+    if depth > MAX_ZIP_DEPTH {
+        return Err(anyhow!(
+            "Notion export nests zips more than {} levels deep",
+            MAX_ZIP_DEPTH
+        ));
+    }
+    for (name, bytes) in export::read_zip_archive(archive, *remaining)? {
+        *remaining -= bytes.len() as u64;
+        if name.starts_with("__MACOSX/") || file_name(&name) == ".DS_Store" {
+            continue;
+        }
+        if name.to_lowercase().ends_with(".zip") {
+            unpack_into(&bytes, depth + 1, remaining, files)?;
+        } else {
+            files.push((name, bytes));
+        }
+    }
+    Ok(())
+}
+
+/// Imports a Notion "Markdown & CSV" export zip.
+///
+/// Each database CSV becomes a form with fields typed from the column
+/// values, and each row an entry, with the row's page body in a `Body`
+/// field. Other pages become `NotionPage` entries. Every other file is
+/// saved as an asset. Links between pages and to files in the export are
+/// rewritten to `ugoite://entry/` and `ugoite://asset/` links.
+///
+/// Returns a mapping report: the forms created (with field types and any
+/// renamed columns), the source file of every entry and asset, the links
+/// that could not be resolved, warnings, and pages that failed to import.
+pub async fn import_notion<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    archive: &[u8],
+    author: &str,
+    integrity: &I,
+) -> Result<Value> {
+    let files = unpack(archive)?;
+    let mut markdown: Vec<(String, String)> = Vec::new();
+    let mut csvs: Vec<(String, String)> = Vec::new();
+    let mut others: Vec<(String, Vec<u8>)> = Vec::new();
+    for (name, bytes) in files {
+        let lower = name.to_lowercase();
+        if lower.ends_with(".md") {
+            markdown.push((name, String::from_utf8_lossy(&bytes).into_owned()));
+        } else if lower.ends_with(".csv") {
+            csvs.push((name, String::from_utf8_lossy(&bytes).into_owned()));
+        } else {
+            others.push((name, bytes));
+        }
+    }
+    if markdown.is_empty() && csvs.is_empty() {
+        return Err(anyhow!("No Notion pages or databases found in the archive"));
+    }
+    // Newer exports write `Name id.csv` and `Name id_all.csv`; the latter
+    // includes rows hidden by the view's filters.
+    let csv_names: HashSet<String> = csvs.iter().map(|(name, _)| name.clone()).collect();
+    csvs.retain(|(name, _)| {
+        let all = format!("{}_all.csv", &name[..name.len() - 4]);
+        !csv_names.contains(&all)
+    });
+
+    let mut warnings = Vec::new();
+    let mut taken_forms: HashSet<String> = form::list_form_names(op, ws_path)
+        .await?
+        .into_iter()
+        .map(|name| name.to_lowercase())
+        .collect();
+    let mut claimed: HashSet<String> = HashSet::new();
+    let mut databases = Vec::new();
+    let mut pages = Vec::new();
+
+    for (source, text) in &csvs {
+        let mut rows = parse_csv(text).into_iter();
+        let Some(header) = rows.next() else {
+            warnings.push(serde_json::json!({"source": source, "message": "Empty database"}));
+            continue;
+        };
+        let rows: Vec<Vec<String>> = rows
+            .filter(|row| row.iter().any(|cell| !cell.trim().is_empty()))
+            .collect();
+        let stem = file_stem(source);
+        let stem = stem.strip_suffix("_all").unwrap_or(stem);
+        let (db_title, _) = split_notion_name(stem);
+        let form_name = unique_name(&form_name_for(&db_title), &taken_forms, "");
+        taken_forms.insert(form_name.to_lowercase());
+
+        let mut taken_fields = HashSet::new();
+        let mut renamed = Map::new();
+        let mut fields = Vec::new();
+        for (index, column) in header.iter().enumerate().skip(1) {
+            let cleaned = column
+                .trim()
+                .trim_start_matches('#')
+                .replace(['\n', '\r'], " ");
+            let cleaned = cleaned.trim();
+            let mut base = if cleaned.is_empty() {
+                format!("Column {}", index + 1)
+            } else {
+                cleaned.to_string()
+            };
+            if metadata::is_reserved_metadata_column(&base) {
+                base = format!("Notion {}", base);
+            }
+            let name = unique_name(&base, &taken_fields, " ");
+            taken_fields.insert(name.to_lowercase());
+            if name != *column {
+                renamed.insert(column.clone(), Value::String(name.clone()));
+            }
+            let values: Vec<&str> = rows
+                .iter()
+                .map(|row| row.get(index).map(String::as_str).unwrap_or_default())
+                .collect();
+            fields.push((name, infer_field_type(&values)));
+        }
+        let body_field = unique_name(BODY_FIELD, &taken_fields, " ");
+        let columns: HashSet<String> = header.iter().map(|c| c.trim().to_string()).collect();
+
+        // Row pages live in a folder named like the CSV.
+        let folder = match parent_dir(source) {
+            "" => stem.to_string(),
+            dir => format!("{}/{}", dir, stem),
+        };
+        for row in &rows {
+            let title = row
+                .first()
+                .map(|title| title.trim())
+                .filter(|title| !title.is_empty())
+                .unwrap_or("Untitled")
+                .to_string();
+            let page_file = markdown.iter().find(|(path, _)| {
+                parent_dir(path) == folder
+                    && !claimed.contains(path)
+                    && split_notion_name(file_stem(path)).0 == title
+            });
+            let (page_source, notion_id, body) = match page_file {
+                Some((path, text)) => {
+                    claimed.insert(path.clone());
+                    let (_, rest) = split_title(text);
+                    let body = strip_property_lines(&rest, &columns);
+                    (
+                        path.clone(),
+                        split_notion_name(file_stem(path)).1,
+                        Some(body),
+                    )
+                }
+                None => (source.clone(), None, None),
+            };
+            let values = fields
+                .iter()
+                .enumerate()
+                .map(|(i, (name, field_type))| {
+                    let raw = row.get(i + 1).map(|v| v.trim()).unwrap_or_default();
+                    let value = match *field_type {
+                        "date" => notion_date(raw).map_or(raw.to_string(), |d| d.to_string()),
+                        _ => raw.to_string(),
+                    };
+                    (name.clone(), field_type.to_string(), value)
+                })
+                .collect();
+            pages.push(Page {
+                source: page_source,
+                fields_source: source.clone(),
+                notion_id,
+                title,
+                form: form_name.clone(),
+                fields: values,
+                body,
+                entry_id: String::new(),
+            });
+        }
+        databases.push(Database {
+            source: source.clone(),
+            form: form_name,
+            fields,
+            body_field,
+            renamed,
+        });
+    }
+
+    for (source, text) in &markdown {
+        if claimed.contains(source) {
+            continue;
+        }
+        let (name, notion_id) = split_notion_name(file_stem(source));
+        let (title, body) = split_title(text);
+        pages.push(Page {
+            source: source.clone(),
+            fields_source: source.clone(),
+            notion_id,
+            title: title.filter(|t| !t.is_empty()).unwrap_or(name),
+            form: PAGE_FORM.to_string(),
+            fields: Vec::new(),
+            body: Some(body),
+            entry_id: String::new(),
+        });
+    }
+
+    let mut targets = LinkTargets::default();
+    let mut assigned: HashSet<String> = HashSet::new();
+    for page in &mut pages {
+        let suggested = entry_id::suggest_entry_id(op, ws_path, &page.title, &page.form).await?;
+        let mut id = suggested.clone();
+        let mut n = 1;
+        while assigned.contains(&id) {
+            n += 1;
+            id = format!("{}-{}", suggested, n);
+        }
+        assigned.insert(id.clone());
+        if page.source.to_lowercase().ends_with(".md") {
+            targets.pages.insert(page.source.clone(), id.clone());
+        }
+        if let Some(notion_id) = &page.notion_id {
+            targets.notion_ids.insert(notion_id.clone(), id.clone());
+        }
+        page.entry_id = id;
+    }
+
+    let mut failed = Vec::new();
+    let mut assets = Vec::new();
+    for (source, bytes) in &others {
+        match asset::save_asset(op, ws_path, file_name(source), bytes).await {
+            Ok(info) => {
+                targets.assets.insert(source.clone(), info.link.clone());
+                assets.push(serde_json::json!({
+                    "source": source,
+                    "asset_id": info.id,
+                    "link": info.link,
+                }));
+            }
+            Err(err) => failed.push(serde_json::json!({
+                "source": source,
+                "reason": err.to_string(),
+            })),
+        }
+    }
+
+    let mut forms = Vec::new();
+    for db in &databases {
+        let mut defs = Map::new();
+        let mut template = format!("# {}\n\n", db.form);
+        for (name, field_type) in &db.fields {
+            defs.insert(name.clone(), serde_json::json!({"type": field_type}));
+            template.push_str(&format!("## {}\n\n", name));
+        }
+        defs.insert(
+            db.body_field.clone(),
+            serde_json::json!({"type": "markdown"}),
+        );
+        template.push_str(&format!("## {}\n", db.body_field));
+        form::upsert_form(
+            op,
+            ws_path,
+            &serde_json::json!({"name": db.form, "template": template, "fields": defs}),
+        )
+        .await?;
+        forms.push(serde_json::json!({
+            "source": db.source,
+            "form": db.form,
+            "fields": db.fields.iter().map(|(name, t)| (name.clone(), Value::from(*t))).collect::<Map<_, _>>(),
+            "renamed_columns": db.renamed,
+        }));
+    }
+    let has_plain_pages = pages.iter().any(|page| page.form == PAGE_FORM);
+    if has_plain_pages && !taken_forms.contains(&PAGE_FORM.to_lowercase()) {
+        form::upsert_form(
+            op,
+            ws_path,
+            &serde_json::json!({
+                "name": PAGE_FORM,
+                "template": format!("# {}\n\n## {}\n", PAGE_FORM, BODY_FIELD),
+                "fields": {BODY_FIELD: {"type": "markdown"}},
+            }),
+        )
+        .await?;
+    }
+
+    let mut stats = LinkStats::default();
+    let mut entries = Vec::new();
+    for page in &pages {
+        let body_field = databases
+            .iter()
+            .find(|db| db.form == page.form)
+            .map_or(BODY_FIELD, |db| db.body_field.as_str());
+        let mut fields = Map::new();
+        let mut order = Vec::new();
+        for (name, field_type, value) in &page.fields {
+            if value.is_empty() {
+                continue;
+            }
+            let value = if field_type == "string" {
+                targets.remap_markdown(&page.fields_source, value, true, &mut stats)
+            } else {
+                value.clone()
+            };
+            fields.insert(name.clone(), Value::String(value));
+            order.push(name.clone());
+        }
+        if let Some(body) = &page.body {
+            let body = targets.remap_markdown(&page.source, body, false, &mut stats);
+            let (body, rewritten) = entry::section_safe_markdown(&body);
+            if rewritten > 0 {
+                warnings.push(serde_json::json!({
+                    "source": page.source,
+                    "message": format!("Rewrote {} lines starting with '#' to keep the body in one field", rewritten),
+                }));
+            }
+            if !body.is_empty() {
+                fields.insert(body_field.to_string(), Value::String(body));
+                order.push(body_field.to_string());
+            }
+        }
+        let content =
+            entry::render_markdown(&page.title, &page.form, &[], &Value::Object(fields), &order);
+        match entry::create_entry(op, ws_path, &page.entry_id, &content, author, integrity).await {
+            Ok(_) => entries.push(serde_json::json!({
+                "source": page.source,
+                "entry_id": page.entry_id,
+                "form": page.form,
+                "title": page.title,
+            })),
+            Err(err) => failed.push(serde_json::json!({
+                "source": page.source,
+                "entry_id": page.entry_id,
+                "reason": err.to_string(),
+            })),
+        }
+    }
+
+    Ok(serde_json::json!({
+        "forms": forms,
+        "entries": entries,
+        "assets": assets,
+        "links": {
+            "remapped": stats.remapped,
+            "unresolved": stats.unresolved,
+        },
+        "warnings": warnings,
+        "failed": failed,
+    }))
+}
@@ -0,0 +1,222 @@
+use anyhow::{anyhow, Result};
+use opendal::Operator;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::asset;
+use crate::audit;
+use crate::entry;
+use crate::form;
+use crate::space;
+
+/// Finds sensitive spans in text. Regex packs are built in; other detectors
+/// (e.g. ones backed by an external classifier) are passed to [`scan_space`].
+pub trait Detector: Send + Sync {
+    fn name(&self) -> &str;
+    /// Returns byte ranges `(start, end)` of each finding in `text`.
+    fn detect(&self, text: &str) -> Vec<(usize, usize)>;
+}
+
+pub struct RegexDetector {
+    name: String,
+    pattern: Regex,
+}
+
+impl RegexDetector {
+    pub fn new(name: &str, pattern: &str) -> Result<Self> {
+        Ok(Self {
+            name: name.to_string(),
+            pattern: Regex::new(pattern)
+                .map_err(|e| anyhow!("Invalid pattern for detector {}: {}", name, e))?,
+        })
+    }
+}
+
+impl Detector for RegexDetector {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn detect(&self, text: &str) -> Vec<(usize, usize)> {
+        self.pattern
+            .find_iter(text)
+            .map(|m| (m.start(), m.end()))
+            .collect()
+    }
+}
+
+/// Built-in regex packs, by pack name, as (detector name, pattern) pairs.
+const PACKS: &[(&str, &[(&str, &str)])] = &[
+    (
+        "email",
+        &[("email", r"(?i)\b[A-Z0-9._%+-]+@[A-Z0-9.-]+\.[A-Z]{2,}\b")],
+    ),
+    (
+        "phone",
+        &[(
+            "phone",
+            r"(?:\+\d{1,3}[\s.-]?)?(?:\(\d{2,4}\)|\b\d{2,4})[\s.-]\d{3,4}[\s.-]\d{3,4}\b",
+        )],
+    ),
+    (
+        "keys",
+        &[
+            ("aws_access_key", r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b"),
+            ("github_token", r"\bgh[pousr]_[A-Za-z0-9]{36,}\b"),
+            ("secret_key", r"\bsk-[A-Za-z0-9_-]{20,}"),
+            ("private_key", r"-----BEGIN [A-Z ]*PRIVATE KEY-----"),
+        ],
+    ),
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CustomDetector {
+    pub name: String,
+    pub pattern: String,
+}
+
+/// Scan configuration, read from the `pii_scan` object in the space
+/// `settings.json`, e.g. `{"packs": ["email"], "custom": [{"name": "ticket",
+/// "pattern": "TCK-\\d+"}]}`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct PiiScanSettings {
+    pub packs: Vec<String>,
+    pub custom: Vec<CustomDetector>,
+    /// Larger assets are reported as skipped instead of being read.
+    pub max_asset_bytes: u64,
+}
+
+impl Default for PiiScanSettings {
+    fn default() -> Self {
+        Self {
+            packs: PACKS.iter().map(|(name, _)| name.to_string()).collect(),
+            custom: Vec::new(),
+            max_asset_bytes: 10 * 1024 * 1024,
+        }
+    }
+}
+
+impl PiiScanSettings {
+    pub async fn for_space(op: &Operator, ws_path: &str) -> Result<Self> {
+        let settings = space::read_space_settings(op, ws_path).await?;
+        match settings.get("pii_scan") {
+            Some(value) if value.is_object() => Ok(serde_json::from_value(value.clone())?),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn detectors(&self) -> Result<Vec<Box<dyn Detector>>> {
+        let mut detectors: Vec<Box<dyn Detector>> = Vec::new();
+        for pack in &self.packs {
+            let (_, patterns) = PACKS
+                .iter()
+                .find(|(name, _)| name == pack)
+                .ok_or_else(|| anyhow!("Unknown detector pack: {}", pack))?;
+            for (name, pattern) in patterns.iter() {
+                detectors.push(Box::new(RegexDetector::new(name, pattern)?));
+            }
+        }
+        for custom in &self.custom {
+            detectors.push(Box::new(RegexDetector::new(&custom.name, &custom.pattern)?));
+        }
+        Ok(detectors)
+    }
+}
+
+fn detect_all(
+    detectors: &[&dyn Detector],
+    source: &str,
+    id: &str,
+    text: &str,
+    findings: &mut Vec<Value>,
+) {
+    for detector in detectors {
+        for (start, end) in detector.detect(text) {
+            findings.push(serde_json::json!({
+                "source": source,
+                "id": id,
+                "detector": detector.name(),
+                "start": start,
+                "end": end,
+            }));
+        }
+    }
+}
+
+/// Runs the space's configured detectors plus `extra` over every entry and
+/// text asset, and records a `pii.scanned` audit event.
+///
+/// Entry offsets are byte offsets into the entry markdown as returned by
+/// `get_entry`; asset offsets are byte offsets into the asset file. Findings
+/// carry positions only, never the matched text.
+pub async fn scan_space(
+    op: &Operator,
+    ws_path: &str,
+    extra: &[Box<dyn Detector>],
+) -> Result<Value> {
+    let settings = PiiScanSettings::for_space(op, ws_path).await?;
+    let configured = settings.detectors()?;
+    let detectors: Vec<&dyn Detector> = configured
+        .iter()
+        .chain(extra.iter())
+        .map(|d| d.as_ref())
+        .collect();
+
+    let mut findings = Vec::new();
+    let mut entries_scanned = 0;
+    for (form_name, row) in entry::list_entry_rows(op, ws_path).await? {
+        if row.deleted {
+            continue;
+        }
+        let form_def = form::read_form_definition(op, ws_path, &form_name).await?;
+        let markdown = entry::render_markdown_for_form(
+            &row.title,
+            &form_name,
+            &row.tags,
+            &row.fields,
+            &row.extra_attributes,
+            &form_def,
+        );
+        detect_all(&detectors, "entry", &row.entry_id, &markdown, &mut findings);
+        entries_scanned += 1;
+    }
+
+    let mut assets_scanned = 0;
+    let mut assets_skipped = Vec::new();
+    for info in asset::list_assets(op, ws_path).await? {
+        let path = format!("{}/{}", ws_path.trim_end_matches('/'), info.path);
+        if op.stat(&path).await?.content_length() > settings.max_asset_bytes {
+            assets_skipped.push(serde_json::json!({"id": info.id, "reason": "too_large"}));
+            continue;
+        }
+        match String::from_utf8(op.read(&path).await?.to_vec()) {
+            Ok(text) => {
+                detect_all(&detectors, "asset", &info.id, &text, &mut findings);
+                assets_scanned += 1;
+            }
+            Err(_) => assets_skipped.push(serde_json::json!({"id": info.id, "reason": "binary"})),
+        }
+    }
+
+    let detector_names: Vec<&str> = detectors.iter().map(|d| d.name()).collect();
+    let summary = serde_json::json!({
+        "detectors": detector_names,
+        "entries_scanned": entries_scanned,
+        "assets_scanned": assets_scanned,
+        "assets_skipped": assets_skipped.len(),
+        "findings": findings.len(),
+    });
+    let event = audit::record_audit_event(op, ws_path, "pii.scanned", "system", summary).await?;
+
+    Ok(serde_json::json!({
+        "scanned_at": event["recorded_at"],
+        "detectors": detector_names,
+        "entries_scanned": entries_scanned,
+        "assets_scanned": assets_scanned,
+        "assets_skipped": assets_skipped,
+        "findings": findings,
+        "audit_event_id": event["id"],
+    }))
+}
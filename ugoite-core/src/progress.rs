@@ -0,0 +1,102 @@
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::future::Future;
+use tokio::sync::mpsc;
+
+/// Messages a consumer may fall behind by before the producer waits.
+pub const DEFAULT_CAPACITY: usize = 8;
+
+/// Entries per `items` message of [`crate::entry::stream_entries`].
+pub const DEFAULT_CHUNK_SIZE: usize = 500;
+
+/// A `progress` message is sent every this many items of a stage, and at
+/// its end.
+pub const PROGRESS_EVERY: usize = 100;
+
+/// Producer side of a progress stream.
+///
+/// Messages are JSON objects with a `type`: `items` (a chunk of results),
+/// `progress` (`stage`, `done`, `total`) or `result` (the final report).
+#[derive(Clone, Debug)]
+pub struct ProgressSender {
+    tx: mpsc::Sender<Result<Value>>,
+}
+
+impl ProgressSender {
+    /// Sends one message, waiting while the consumer is a full channel
+    /// behind. Fails once the consumer has gone away, so the producer stops
+    /// instead of running to the end unheard.
+    pub async fn send(&self, message: Value) -> Result<()> {
+        self.tx
+            .send(Ok(message))
+            .await
+            .map_err(|_| anyhow!("Progress stream closed by the consumer"))
+    }
+
+    pub async fn items(&self, items: Vec<Value>) -> Result<()> {
+        self.send(serde_json::json!({"type": "items", "items": items}))
+            .await
+    }
+
+    pub async fn result(&self, result: Value) -> Result<()> {
+        self.send(serde_json::json!({"type": "result", "result": result}))
+            .await
+    }
+}
+
+/// Reports `done` of `total` items of `stage` to `progress`, if any, every
+/// [`PROGRESS_EVERY`] items and at the end of the stage.
+pub async fn report(
+    progress: Option<&ProgressSender>,
+    stage: &str,
+    done: usize,
+    total: usize,
+) -> Result<()> {
+    let Some(progress) = progress else {
+        return Ok(());
+    };
+    if !done.is_multiple_of(PROGRESS_EVERY) && done != total {
+        return Ok(());
+    }
+    progress
+        .send(serde_json::json!({
+            "type": "progress",
+            "stage": stage,
+            "done": done,
+            "total": total,
+        }))
+        .await
+}
+
+/// Consumer side of a progress stream.
+#[derive(Debug)]
+pub struct ProgressStream {
+    rx: mpsc::Receiver<Result<Value>>,
+}
+
+impl ProgressStream {
+    /// The next message, or `None` once the producer has finished. A
+    /// producer error arrives as the last message.
+    pub async fn next(&mut self) -> Option<Result<Value>> {
+        self.rx.recv().await
+    }
+}
+
+/// Runs `producer` as a task on the current tokio runtime and returns the
+/// stream of what it sends. At most `capacity` messages are buffered, so a
+/// slow consumer holds the producer back; dropping the stream stops it at
+/// its next send.
+pub fn spawn<F, Fut>(capacity: usize, producer: F) -> ProgressStream
+where
+    F: FnOnce(ProgressSender) -> Fut,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel(capacity.max(1));
+    let task = producer(ProgressSender { tx: tx.clone() });
+    tokio::spawn(async move {
+        if let Err(err) = task.await {
+            let _ = tx.send(Err(err)).await;
+        }
+    });
+    ProgressStream { rx }
+}
@@ -0,0 +1,212 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Months, Utc};
+use opendal::Operator;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::entry;
+use crate::storage;
+
+const REMINDERS_FILE: &str = "reminders.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Recurrence {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl std::str::FromStr for Recurrence {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "daily" => Ok(Self::Daily),
+            "weekly" => Ok(Self::Weekly),
+            "monthly" => Ok(Self::Monthly),
+            other => Err(anyhow!("Unknown recurrence: {}", other)),
+        }
+    }
+}
+
+impl Recurrence {
+    /// The occurrence after `ts`. Monthly steps are one calendar month,
+    /// clamped to the last day of shorter months.
+    pub fn advance(self, ts: f64) -> Result<f64> {
+        let next = match self {
+            Recurrence::Daily => ts + 86_400.0,
+            Recurrence::Weekly => ts + 7.0 * 86_400.0,
+            Recurrence::Monthly => {
+                let due = DateTime::<Utc>::from_timestamp_millis((ts * 1000.0) as i64)
+                    .ok_or_else(|| anyhow!("Invalid reminder timestamp: {}", ts))?;
+                let next = due
+                    .checked_add_months(Months::new(1))
+                    .ok_or_else(|| anyhow!("Reminder timestamp out of range: {}", ts))?;
+                next.timestamp_millis() as f64 / 1000.0
+            }
+        };
+        Ok(next)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Reminder {
+    pub id: String,
+    pub entry_id: String,
+    pub form: String,
+    pub due_at: f64,
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    #[serde(default)]
+    pub payload: Value,
+    pub created_at: f64,
+    /// Set once a one-off reminder is completed; recurring reminders move
+    /// `due_at` forward instead and record `last_completed_at`.
+    #[serde(default)]
+    pub completed_at: Option<f64>,
+    #[serde(default)]
+    pub last_completed_at: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct RemindersDoc {
+    #[serde(default)]
+    reminders: BTreeMap<String, Reminder>,
+}
+
+fn reminders_path(ws_path: &str) -> String {
+    format!("{}/{}", ws_path.trim_end_matches('/'), REMINDERS_FILE)
+}
+
+fn parse_doc(value: Option<Value>) -> Result<RemindersDoc> {
+    Ok(match value {
+        Some(value) => serde_json::from_value(value)?,
+        None => RemindersDoc::default(),
+    })
+}
+
+async fn update_reminders<F, T>(op: &Operator, ws_path: &str, update: F) -> Result<T>
+where
+    F: Fn(&mut RemindersDoc) -> Result<T>,
+{
+    storage::update_json(op, &reminders_path(ws_path), |current| {
+        let mut doc = parse_doc(current)?;
+        let result = update(&mut doc)?;
+        Ok((serde_json::to_value(&doc)?, result))
+    })
+    .await
+}
+
+async fn read_reminders(op: &Operator, ws_path: &str) -> Result<RemindersDoc> {
+    let (value, _) = storage::read_json_versioned(op, &reminders_path(ws_path)).await?;
+    parse_doc(value)
+}
+
+/// Attaches a reminder to an existing entry. `payload` is stored as given
+/// for the host to use when it notifies.
+pub async fn add_reminder(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+    due_at: f64,
+    recurrence: Option<Recurrence>,
+    payload: Value,
+) -> Result<Reminder> {
+    let form = entry::find_entry_form(op, ws_path, entry_id)
+        .await?
+        .ok_or_else(|| anyhow!("Entry not found: {}", entry_id))?;
+    let reminder = Reminder {
+        id: uuid::Uuid::new_v4().to_string(),
+        entry_id: entry_id.to_string(),
+        form,
+        due_at,
+        recurrence,
+        payload,
+        created_at: entry::now_ts(),
+        completed_at: None,
+        last_completed_at: None,
+    };
+    update_reminders(op, ws_path, |doc| {
+        doc.reminders.insert(reminder.id.clone(), reminder.clone());
+        Ok(())
+    })
+    .await?;
+    Ok(reminder)
+}
+
+/// Open reminders due at or before `before`, earliest first, optionally
+/// limited to entries of one form.
+pub async fn list_due_reminders(
+    op: &Operator,
+    ws_path: &str,
+    before: f64,
+    form: Option<&str>,
+) -> Result<Vec<Reminder>> {
+    let doc = read_reminders(op, ws_path).await?;
+    let mut due: Vec<Reminder> = doc
+        .reminders
+        .into_values()
+        .filter(|r| r.completed_at.is_none() && r.due_at <= before)
+        .filter(|r| form.map(|form| r.form == form).unwrap_or(true))
+        .collect();
+    due.sort_by(|a, b| a.due_at.total_cmp(&b.due_at).then(a.id.cmp(&b.id)));
+    Ok(due)
+}
+
+/// All reminders attached to an entry, including completed ones.
+pub async fn list_entry_reminders(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+) -> Result<Vec<Reminder>> {
+    let doc = read_reminders(op, ws_path).await?;
+    let mut reminders: Vec<Reminder> = doc
+        .reminders
+        .into_values()
+        .filter(|r| r.entry_id == entry_id)
+        .collect();
+    reminders.sort_by(|a, b| a.due_at.total_cmp(&b.due_at).then(a.id.cmp(&b.id)));
+    Ok(reminders)
+}
+
+/// Completes a reminder. Recurring reminders are rescheduled to their first
+/// occurrence after now, skipping any that were missed.
+pub async fn complete_reminder(
+    op: &Operator,
+    ws_path: &str,
+    reminder_id: &str,
+) -> Result<Reminder> {
+    let now = entry::now_ts();
+    update_reminders(op, ws_path, |doc| {
+        let reminder = doc
+            .reminders
+            .get_mut(reminder_id)
+            .ok_or_else(|| anyhow!("Reminder not found: {}", reminder_id))?;
+        if reminder.completed_at.is_some() {
+            return Err(anyhow!("Reminder already completed: {}", reminder_id));
+        }
+        match reminder.recurrence {
+            Some(recurrence) => {
+                let mut next = recurrence.advance(reminder.due_at)?;
+                while next <= now {
+                    next = recurrence.advance(next)?;
+                }
+                reminder.due_at = next;
+                reminder.last_completed_at = Some(now);
+            }
+            None => reminder.completed_at = Some(now),
+        }
+        Ok(reminder.clone())
+    })
+    .await
+}
+
+/// Deletes a reminder. Returns whether it existed.
+pub async fn delete_reminder(op: &Operator, ws_path: &str, reminder_id: &str) -> Result<bool> {
+    update_reminders(op, ws_path, |doc| {
+        Ok(doc.reminders.remove(reminder_id).is_some())
+    })
+    .await
+}
@@ -0,0 +1,69 @@
+use anyhow::Result;
+use opendal::Operator;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+use crate::entry;
+use crate::iceberg_store;
+
+/// Whether a read replica has caught up with the primary for a space.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReplicaStatus {
+    pub fresh: bool,
+    /// `"Form/table"` keys whose table version differs between the two,
+    /// including tables only one side has.
+    pub stale_tables: Vec<String>,
+    pub checked_at: f64,
+}
+
+/// Compares the catalog manifests of `primary` and `replica`. The replica is
+/// fresh when every Form table points at the same metadata version on both
+/// sides. A space whose primary has no manifest cannot be checked and is
+/// reported stale.
+///
+/// Only Form tables are compared; JSON documents such as `settings.json` or
+/// `folders.json` may lag on a fresh replica until replication catches up.
+pub async fn replica_status(
+    primary: &Operator,
+    replica: &Operator,
+    ws_path: &str,
+) -> Result<ReplicaStatus> {
+    let checked_at = entry::now_ts();
+    let Some(expected) = iceberg_store::catalog_table_locations(primary, ws_path).await? else {
+        return Ok(ReplicaStatus {
+            fresh: false,
+            stale_tables: Vec::new(),
+            checked_at,
+        });
+    };
+    let actual = iceberg_store::catalog_table_locations(replica, ws_path)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+    let keys: BTreeSet<&String> = expected.keys().chain(actual.keys()).collect();
+    let stale_tables: Vec<String> = keys
+        .into_iter()
+        .filter(|key| expected.get(*key) != actual.get(*key))
+        .cloned()
+        .collect();
+    Ok(ReplicaStatus {
+        fresh: stale_tables.is_empty(),
+        stale_tables,
+        checked_at,
+    })
+}
+
+/// The operator reads for `ws_path` should use: the replica while it is
+/// fresh, otherwise the primary.
+pub async fn read_operator(
+    primary: &Operator,
+    replica: &Operator,
+    ws_path: &str,
+) -> Result<Operator> {
+    if replica_status(primary, replica, ws_path).await?.fresh {
+        Ok(replica.clone())
+    } else {
+        Ok(primary.clone())
+    }
+}
@@ -0,0 +1,204 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDate};
+use opendal::Operator;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::audit;
+use crate::clock;
+use crate::entry;
+use crate::form;
+use crate::space;
+
+const ARCHIVE_DIR: &str = "archive";
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RetentionAction {
+    #[default]
+    Delete,
+    /// Archives the entry with [`entry::archive_entry`]: it stays readable
+    /// but leaves default listings and search.
+    Archive,
+}
+
+impl std::str::FromStr for RetentionAction {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "delete" => Ok(Self::Delete),
+            "archive" => Ok(Self::Archive),
+            other => Err(anyhow!("Unknown retention action: {}", other)),
+        }
+    }
+}
+
+/// Expires entries `after_days` after the date in `field`: `created_at`,
+/// `updated_at`, or a date/timestamp field of the form.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RetentionRule {
+    pub after_days: f64,
+    #[serde(default = "default_retention_field")]
+    pub field: String,
+    #[serde(default)]
+    pub action: RetentionAction,
+}
+
+fn default_retention_field() -> String {
+    "updated_at".to_string()
+}
+
+/// `retention` in settings.json: one rule per form,
+/// e.g. `{"EnergyReport": {"after_days": 365, "field": "Date"}}`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(transparent)]
+pub struct RetentionSettings {
+    pub forms: BTreeMap<String, RetentionRule>,
+}
+
+impl RetentionSettings {
+    pub async fn for_space(op: &Operator, ws_path: &str) -> Result<Self> {
+        let settings = space::read_space_settings(op, ws_path).await?;
+        match settings.get("retention") {
+            Some(value) if value.is_object() => Ok(serde_json::from_value(value.clone())?),
+            _ => Ok(Self::default()),
+        }
+    }
+}
+
+fn parse_reference_ts(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => {
+            let s = s.trim();
+            if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+                return Some(dt.timestamp_millis() as f64 / 1000.0);
+            }
+            let date = NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()?;
+            Some(date.and_hms_opt(0, 0, 0)?.and_utc().timestamp() as f64)
+        }
+        _ => None,
+    }
+}
+
+fn reference_ts(row: &entry::EntryRow, field: &str) -> Option<f64> {
+    match field {
+        "created_at" => Some(clock::from_micros(row.created_at)),
+        "updated_at" => Some(clock::from_micros(row.updated_at)),
+        field => entry::merge_entry_fields(&row.fields, &row.extra_attributes)
+            .get(field)
+            .and_then(parse_reference_ts),
+    }
+}
+
+/// Paths of the copies of `entry_id` that the `archive` action saved under
+/// `archive/{form}/` before it archived entries in place.
+pub(crate) async fn archived_copies(
+    op: &Operator,
+    ws_path: &str,
+    entry_id: &str,
+) -> Result<Vec<String>> {
+    let root = format!("{}/{}/", ws_path.trim_end_matches('/'), ARCHIVE_DIR);
+    if !op.exists(&root).await? {
+        return Ok(Vec::new());
+    }
+    let file_name = format!("{}.json", entry_id);
+    let mut copies: Vec<String> = op
+        .list_with(&root)
+        .recursive(true)
+        .await?
+        .into_iter()
+        .filter(|item| item.metadata().is_file() && item.name() == file_name)
+        .map(|item| item.path().to_string())
+        .collect();
+    copies.sort();
+    Ok(copies)
+}
+
+/// Runs the space's retention rules as of `now`. With `dry_run` nothing is
+/// changed and the report lists what would expire.
+///
+/// Entries whose rule field is empty or not a date are reported under
+/// `skipped` and kept. Applied runs are recorded in the audit log.
+pub async fn apply_retention(
+    op: &Operator,
+    ws_path: &str,
+    now: f64,
+    dry_run: bool,
+) -> Result<Value> {
+    let settings = RetentionSettings::for_space(op, ws_path).await?;
+    let known_forms = form::list_form_names(op, ws_path).await?;
+
+    let mut expired = Vec::new();
+    let mut skipped = Vec::new();
+    let mut totals: BTreeMap<&str, usize> = BTreeMap::new();
+    for (form_name, rule) in &settings.forms {
+        if !known_forms.contains(form_name) {
+            skipped.push(serde_json::json!({"form": form_name, "reason": "unknown_form"}));
+            continue;
+        }
+        let cutoff = now - rule.after_days * SECONDS_PER_DAY;
+        let form_def = form::read_form_definition(op, ws_path, form_name).await?;
+        let mut rows = entry::list_form_entry_rows(op, ws_path, form_name, &form_def).await?;
+        rows.sort_by(|a, b| a.entry_id.cmp(&b.entry_id));
+        // Archiving an archived entry again would change nothing.
+        let live = |row: &&entry::EntryRow| match rule.action {
+            RetentionAction::Delete => !row.deleted,
+            RetentionAction::Archive => !row.deleted && !row.archived,
+        };
+        for row in rows.iter().filter(live) {
+            let Some(reference_at) = reference_ts(row, &rule.field) else {
+                skipped.push(serde_json::json!({
+                    "form": form_name,
+                    "entry_id": row.entry_id,
+                    "reason": "missing_date",
+                }));
+                continue;
+            };
+            if reference_at > cutoff {
+                continue;
+            }
+            if !dry_run {
+                match rule.action {
+                    RetentionAction::Delete => {
+                        entry::delete_entry(op, ws_path, &row.entry_id, false).await?;
+                    }
+                    RetentionAction::Archive => {
+                        entry::archive_entry(op, ws_path, &row.entry_id).await?;
+                    }
+                }
+            }
+            let action = match rule.action {
+                RetentionAction::Delete => "delete",
+                RetentionAction::Archive => "archive",
+            };
+            *totals.entry(action).or_default() += 1;
+            expired.push(serde_json::json!({
+                "form": form_name,
+                "entry_id": row.entry_id,
+                "action": action,
+                "reference_at": reference_at,
+            }));
+        }
+    }
+
+    let expired_count = expired.len();
+    let mut report = serde_json::json!({
+        "dry_run": dry_run,
+        "ran_at": now,
+        "expired": expired,
+        "skipped": skipped,
+        "totals": totals,
+        "audit_event_id": Value::Null,
+    });
+    if !dry_run && expired_count > 0 {
+        let summary = serde_json::json!({"ran_at": now, "totals": report["totals"]});
+        let event =
+            audit::record_audit_event(op, ws_path, "retention.applied", "system", summary).await?;
+        report["audit_event_id"] = event["id"].clone();
+    }
+    Ok(report)
+}
@@ -0,0 +1,367 @@
+use anyhow::{anyhow, Result};
+use opendal::Operator;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::decimal::{self, DecimalType};
+use crate::entry;
+use crate::form;
+use crate::index;
+use crate::storage;
+
+const COMPUTED_DIR: &str = "computed";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Aggregate {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+/// A value on a parent entry computed from the `source_form` entries whose
+/// `via` row_reference field points at it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RollupDef {
+    pub source_form: String,
+    pub via: String,
+    pub aggregate: Aggregate,
+    /// Numeric field of `source_form` to aggregate; unused for `count`.
+    #[serde(default)]
+    pub field: Option<String>,
+}
+
+/// Parses the `rollups` of a form definition, keyed by rollup name.
+pub fn rollup_defs(form_def: &Value) -> Result<BTreeMap<String, RollupDef>> {
+    let defs: BTreeMap<String, RollupDef> = match form_def.get("rollups") {
+        None | Some(Value::Null) => BTreeMap::new(),
+        Some(value) => serde_json::from_value(value.clone())
+            .map_err(|e| anyhow!("Invalid rollup definition: {}", e))?,
+    };
+    for (name, def) in &defs {
+        if def.aggregate != Aggregate::Count && def.field.is_none() {
+            return Err(anyhow!("Rollup '{}' requires a field to aggregate", name));
+        }
+    }
+    Ok(defs)
+}
+
+/// Checks that each rollup's `via` field is a row_reference from its source
+/// form to `form_name`.
+pub(crate) async fn validate_rollup_sources(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+    form_def: &Value,
+) -> Result<()> {
+    for (name, def) in rollup_defs(form_def)? {
+        let source_def = form::read_form_definition(op, ws_path, &def.source_form)
+            .await
+            .map_err(|_| {
+                anyhow!(
+                    "Rollup '{}' source_form '{}' not found",
+                    name,
+                    def.source_form
+                )
+            })?;
+        let target = source_def
+            .get("fields")
+            .and_then(|fields| fields.get(&def.via))
+            .filter(|field| field.get("type").and_then(Value::as_str) == Some("row_reference"))
+            .and_then(|field| field.get("target_form"))
+            .and_then(Value::as_str);
+        if target != Some(form_name) {
+            return Err(anyhow!(
+                "Rollup '{}' via '{}' must be a row_reference from {} to {}",
+                name,
+                def.via,
+                def.source_form,
+                form_name
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn computed_path(ws_path: &str, form_name: &str) -> String {
+    format!(
+        "{}/{}/{}.json",
+        ws_path.trim_end_matches('/'),
+        COMPUTED_DIR,
+        form_name
+    )
+}
+
+/// Computed values of every entry in `form_name`: entry_id -> name -> value.
+pub(crate) async fn read_computed(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+) -> Result<Map<String, Value>> {
+    let (value, _) = storage::read_json_versioned(op, &computed_path(ws_path, form_name)).await?;
+    Ok(match value {
+        Some(Value::Object(map)) => map,
+        _ => Map::new(),
+    })
+}
+
+/// The `computed` payload of one entry; empty when it has no rollups.
+pub async fn entry_computed(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+    entry_id: &str,
+) -> Result<Value> {
+    let mut computed = read_computed(op, ws_path, form_name).await?;
+    Ok(computed
+        .remove(entry_id)
+        .unwrap_or_else(|| Value::Object(Map::new())))
+}
+
+fn numeric(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Aggregates decimal fields exactly on unscaled integers at the field's
+/// scale; `avg` rounds half to even. Values are decimal strings.
+fn aggregate_decimal(
+    def: &RollupDef,
+    decimal_type: DecimalType,
+    count: usize,
+    values: &[Value],
+) -> Value {
+    let values: Vec<i128> = values
+        .iter()
+        .filter_map(|value| decimal_type.parse_value(value))
+        .collect();
+    let value = match def.aggregate {
+        Aggregate::Count => return serde_json::json!(count),
+        Aggregate::Sum => decimal::sum(&values),
+        Aggregate::Avg => decimal::average(&values),
+        Aggregate::Min => values.iter().copied().min(),
+        Aggregate::Max => values.iter().copied().max(),
+    };
+    serde_json::json!(value.map(|unscaled| decimal_type.format(unscaled)))
+}
+
+fn aggregate(
+    def: &RollupDef,
+    count: usize,
+    values: &[Value],
+    decimal_type: Option<DecimalType>,
+) -> Value {
+    if let Some(decimal_type) = decimal_type {
+        return aggregate_decimal(def, decimal_type, count, values);
+    }
+    let values: Vec<f64> = values.iter().filter_map(numeric).collect();
+    let total: f64 = values.iter().sum();
+    let value = match def.aggregate {
+        Aggregate::Count => return serde_json::json!(count),
+        Aggregate::Sum => Some(total),
+        Aggregate::Avg => (!values.is_empty()).then(|| total / values.len() as f64),
+        Aggregate::Min => values.iter().copied().reduce(f64::min),
+        Aggregate::Max => values.iter().copied().reduce(f64::max),
+    };
+    serde_json::json!(value)
+}
+
+/// Recomputes the rollups of `parent_form`, for every entry or just
+/// `only`, and returns the ids whose values changed.
+async fn recompute(
+    op: &Operator,
+    ws_path: &str,
+    parent_form: &str,
+    parent_def: &Value,
+    only: Option<&BTreeSet<String>>,
+) -> Result<Vec<String>> {
+    let defs = rollup_defs(parent_def)?;
+    let parents: BTreeSet<String> = match only {
+        Some(only) => only.clone(),
+        None => entry::list_form_entry_rows(op, ws_path, parent_form, parent_def)
+            .await?
+            .into_iter()
+            .filter(|row| !row.deleted)
+            .map(|row| row.entry_id)
+            .collect(),
+    };
+
+    let mut source_rows = HashMap::new();
+    let mut source_defs = HashMap::new();
+    let mut results: BTreeMap<String, Map<String, Value>> =
+        parents.iter().map(|id| (id.clone(), Map::new())).collect();
+    for (name, def) in &defs {
+        if !source_rows.contains_key(&def.source_form) {
+            let source_def = form::read_form_definition(op, ws_path, &def.source_form).await?;
+            let rows =
+                entry::list_form_entry_rows(op, ws_path, &def.source_form, &source_def).await?;
+            source_rows.insert(def.source_form.clone(), rows);
+            source_defs.insert(def.source_form.clone(), source_def);
+        }
+        let decimal_type = def
+            .field
+            .as_ref()
+            .and_then(|f| decimal::field_decimal_type(&source_defs[&def.source_form], f));
+        let mut children: HashMap<String, (usize, Vec<Value>)> = HashMap::new();
+        for row in &source_rows[&def.source_form] {
+            if row.deleted {
+                continue;
+            }
+            let fields = entry::merge_entry_fields(&row.fields, &row.extra_attributes);
+            let Some(parent) = fields.get(&def.via).and_then(entry::row_reference_id) else {
+                continue;
+            };
+            if !parents.contains(&parent) {
+                continue;
+            }
+            let stats = children.entry(parent).or_default();
+            stats.0 += 1;
+            if let Some(value) = def.field.as_ref().and_then(|f| fields.get(f)) {
+                stats.1.push(value.clone());
+            }
+        }
+        for (parent, values) in results.iter_mut() {
+            let (count, field_values) = children.remove(parent).unwrap_or_default();
+            values.insert(
+                name.clone(),
+                aggregate(def, count, &field_values, decimal_type),
+            );
+        }
+    }
+
+    let path = computed_path(ws_path, parent_form);
+    let changed = storage::update_json(op, &path, |current| {
+        let mut doc = match current {
+            Some(Value::Object(map)) if only.is_some() => map,
+            _ => Map::new(),
+        };
+        let mut changed = Vec::new();
+        for (id, values) in &results {
+            let values = Value::Object(values.clone());
+            if doc.get(id) != Some(&values) {
+                changed.push(id.clone());
+            }
+            doc.insert(id.clone(), values);
+        }
+        Ok((Value::Object(doc), changed))
+    })
+    .await?;
+    for id in &changed {
+        index::patch_indexed_computed(op, ws_path, parent_form, id, &results[id]).await?;
+    }
+    Ok(changed)
+}
+
+/// Recomputes every rollup of `form_name` from scratch, e.g. after its
+/// rollup definitions change. Returns the number of entries whose values
+/// changed.
+pub async fn recompute_form_rollups(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+) -> Result<usize> {
+    let form_def = form::read_form_definition(op, ws_path, form_name).await?;
+    Ok(recompute(op, ws_path, form_name, &form_def, None)
+        .await?
+        .len())
+}
+
+/// The stored rollup document of every form that defines rollups, `None`
+/// where none has been written yet. See [`restore_computed`].
+pub(crate) async fn snapshot_computed(
+    op: &Operator,
+    ws_path: &str,
+) -> Result<HashMap<String, Option<Value>>> {
+    let mut snapshot = HashMap::new();
+    for form_name in form::list_form_names(op, ws_path).await? {
+        let form_def = form::read_form_definition(op, ws_path, &form_name).await?;
+        if rollup_defs(&form_def)?.is_empty() {
+            continue;
+        }
+        let (value, _) =
+            storage::read_json_versioned(op, &computed_path(ws_path, &form_name)).await?;
+        snapshot.insert(form_name, value);
+    }
+    Ok(snapshot)
+}
+
+/// Puts rollup documents back as [`snapshot_computed`] found them. Returns
+/// the forms whose document had changed; their index shards still hold the
+/// newer values.
+pub(crate) async fn restore_computed(
+    op: &Operator,
+    ws_path: &str,
+    snapshot: &HashMap<String, Option<Value>>,
+) -> Result<Vec<String>> {
+    let mut restored = Vec::new();
+    for (form_name, before) in snapshot {
+        let path = computed_path(ws_path, form_name);
+        let (current, _) = storage::read_json_versioned(op, &path).await?;
+        if current == *before {
+            continue;
+        }
+        match before {
+            Some(value) => storage::update_json(op, &path, |_| Ok((value.clone(), ()))).await?,
+            None => op.delete(&path).await?,
+        }
+        restored.push(form_name.clone());
+    }
+    Ok(restored)
+}
+
+/// Keeps rollups current after an entry of `form_name` is written or
+/// deleted (`after` is `None`). Only the parents the entry referenced before
+/// and after the write are recomputed.
+pub(crate) async fn refresh_after_write(
+    op: &Operator,
+    ws_path: &str,
+    form_name: &str,
+    form_def: &Value,
+    entry_id: &str,
+    before: Option<&Value>,
+    after: Option<&Value>,
+) -> Result<()> {
+    let references = form_def
+        .get("fields")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .filter(|(_, def)| def.get("type").and_then(Value::as_str) == Some("row_reference"))
+        .filter_map(|(name, def)| {
+            let target = def.get("target_form").and_then(Value::as_str)?;
+            Some((name.clone(), target.to_string()))
+        })
+        .collect::<Vec<_>>();
+    for (field, target_form) in references {
+        let Ok(target_def) = form::read_form_definition(op, ws_path, &target_form).await else {
+            continue;
+        };
+        let affected = rollup_defs(&target_def)?
+            .values()
+            .any(|def| def.source_form == form_name && def.via == field);
+        if !affected {
+            continue;
+        }
+        let parents: BTreeSet<String> = [before, after]
+            .into_iter()
+            .flatten()
+            .filter_map(|fields| fields.get(&field).and_then(entry::row_reference_id))
+            .collect();
+        if !parents.is_empty() {
+            recompute(op, ws_path, &target_form, &target_def, Some(&parents)).await?;
+        }
+    }
+
+    // A new parent picks up children that referenced it before it existed.
+    if before.is_none() && after.is_some() && !rollup_defs(form_def)?.is_empty() {
+        let only = BTreeSet::from([entry_id.to_string()]);
+        recompute(op, ws_path, form_name, form_def, Some(&only)).await?;
+    }
+    Ok(())
+}
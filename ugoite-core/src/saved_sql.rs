@@ -1,4 +1,6 @@
+use crate::clock;
 use crate::entry;
+use crate::entry_id;
 use crate::form;
 use crate::integrity::IntegrityProvider;
 use crate::materialized_view;
@@ -10,9 +12,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use std::collections::BTreeSet;
 use std::sync::OnceLock;
-use uuid::Uuid;
 
-const SQL_FORM_NAME: &str = "SQL";
+pub(crate) const SQL_FORM_NAME: &str = "SQL";
 const SQL_VALIDATION_PREFIX: &str = "UGOITE_SQL_VALIDATION";
 
 fn validation_error(message: impl std::fmt::Display) -> anyhow::Error {
@@ -178,6 +179,29 @@ fn sql_integrity_payload(
     }
 }
 
+/// Signs a stored SQL row's name, SQL and variables again, e.g. under a new
+/// space key.
+pub(crate) fn resign_sql_fields(
+    integrity: &dyn IntegrityProvider,
+    name: &str,
+    fields: &Value,
+) -> entry::IntegrityPayload {
+    let payload = SqlPayload {
+        name: name.to_string(),
+        sql: fields
+            .get("sql")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+        variables: Value::Null,
+    };
+    let variables = fields
+        .get("variables")
+        .cloned()
+        .unwrap_or_else(|| Value::Array(Vec::new()));
+    sql_integrity_payload(integrity, &payload, &variables)
+}
+
 fn sql_entry_from_row(row: &entry::EntryRow) -> Result<Value> {
     let fields = row
         .fields
@@ -194,8 +218,8 @@ fn sql_entry_from_row(row: &entry::EntryRow) -> Result<Value> {
         "name": row.title,
         "sql": sql_value,
         "variables": variables,
-        "created_at": row.created_at,
-        "updated_at": row.updated_at,
+        "created_at": clock::from_micros(row.created_at),
+        "updated_at": clock::from_micros(row.updated_at),
         "revision_id": row.revision_id,
     }))
 }
@@ -263,8 +287,8 @@ pub async fn create_sql<I: IntegrityProvider>(
     let variables = normalize_sql_variables(Some(&payload.variables))?;
     validate_sql_payload(&payload.sql, &variables)?;
 
-    let timestamp = entry::now_ts();
-    let revision_id = Uuid::new_v4().to_string();
+    let timestamp = clock::next_micros(ws_path, None);
+    let revision_id = entry_id::new_revision_id(op, ws_path).await?;
     let integrity_payload = sql_integrity_payload(integrity, payload, &variables);
 
     let mut fields = Map::new();
@@ -288,6 +312,9 @@ pub async fn create_sql<I: IntegrityProvider>(
         deleted: false,
         deleted_at: None,
         author: author.to_string(),
+        metadata: Value::Object(Map::new()),
+        archived: false,
+        hard_deleted: false,
     };
 
     entry::write_entry_row(op, ws_path, SQL_FORM_NAME, sql_id, &row).await?;
@@ -303,6 +330,8 @@ pub async fn create_sql<I: IntegrityProvider>(
         markdown_checksum: integrity_payload.checksum.clone(),
         integrity: integrity_payload,
         restored_from: None,
+        changed_fields: entry::diff_fields(&Value::Object(Map::new()), &row.fields),
+        message: None,
     };
     entry::append_revision_row_for_form(op, ws_path, SQL_FORM_NAME, &revision, &form_def).await?;
 
@@ -339,17 +368,15 @@ pub async fn update_sql<I: IntegrityProvider>(
 
     let variables = normalize_sql_variables(Some(&payload.variables))?;
     validate_sql_payload(&payload.sql, &variables)?;
-    let mut timestamp = entry::now_ts();
-    if timestamp <= row.updated_at {
-        timestamp = row.updated_at + 0.001;
-    }
-    let revision_id = Uuid::new_v4().to_string();
+    let timestamp = clock::next_micros(ws_path, Some(row.updated_at));
+    let revision_id = entry_id::new_revision_id(op, ws_path).await?;
     let integrity_payload = sql_integrity_payload(integrity, payload, &variables);
 
     let mut fields = Map::new();
     fields.insert("sql".to_string(), Value::String(payload.sql.to_string()));
     fields.insert("variables".to_string(), variables.clone());
 
+    let changed_fields = entry::diff_fields(&row.fields, &Value::Object(fields.clone()));
     row.title = payload.name.to_string();
     row.updated_at = timestamp;
     row.fields = Value::Object(fields);
@@ -371,6 +398,8 @@ pub async fn update_sql<I: IntegrityProvider>(
         markdown_checksum: integrity_payload.checksum.clone(),
         integrity: integrity_payload,
         restored_from: None,
+        changed_fields,
+        message: None,
     };
     entry::append_revision_row_for_form(op, ws_path, SQL_FORM_NAME, &revision, &form_def).await?;
 
@@ -386,10 +415,7 @@ pub async fn delete_sql(op: &Operator, ws_path: &str, sql_id: &str) -> Result<()
         return Err(anyhow!("SQL entry not found: {}", sql_id));
     }
 
-    let mut delete_ts = entry::now_ts();
-    if delete_ts <= row.updated_at {
-        delete_ts = row.updated_at + 0.001;
-    }
+    let delete_ts = clock::next_micros(ws_path, Some(row.updated_at));
     row.deleted = true;
     row.deleted_at = Some(delete_ts);
     row.updated_at = delete_ts;
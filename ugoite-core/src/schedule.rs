@@ -0,0 +1,268 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use opendal::Operator;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+use crate::entry;
+use crate::integrity::IntegrityProvider;
+use crate::link;
+use crate::reminder::Recurrence;
+use crate::storage;
+use crate::template;
+
+const SCHEDULES_FILE: &str = "schedules.json";
+/// Link kind from each generated entry to the one generated before it.
+pub const PREVIOUS_LINK_KIND: &str = "previous";
+/// Template variable holding the previous instance's entry id (empty for
+/// the first run) unless the schedule sets it.
+pub const PREVIOUS_VARIABLE: &str = "previous";
+
+/// Instantiates a template on a cadence, e.g. a daily shift log.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TemplateSchedule {
+    pub id: String,
+    pub template_id: String,
+    pub recurrence: Recurrence,
+    pub next_run_at: f64,
+    /// Variables passed to the template on every run.
+    #[serde(default)]
+    pub variables: Map<String, Value>,
+    pub author: String,
+    pub created_at: f64,
+    #[serde(default)]
+    pub last_run_at: Option<f64>,
+    #[serde(default)]
+    pub last_entry_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct SchedulesDoc {
+    #[serde(default)]
+    schedules: BTreeMap<String, TemplateSchedule>,
+}
+
+fn schedules_path(ws_path: &str) -> String {
+    format!("{}/{}", ws_path.trim_end_matches('/'), SCHEDULES_FILE)
+}
+
+fn parse_doc(value: Option<Value>) -> Result<SchedulesDoc> {
+    Ok(match value {
+        Some(value) => serde_json::from_value(value)?,
+        None => SchedulesDoc::default(),
+    })
+}
+
+async fn update_schedules<F, T>(op: &Operator, ws_path: &str, update: F) -> Result<T>
+where
+    F: Fn(&mut SchedulesDoc) -> Result<T>,
+{
+    storage::update_json(op, &schedules_path(ws_path), |current| {
+        let mut doc = parse_doc(current)?;
+        let result = update(&mut doc)?;
+        Ok((serde_json::to_value(&doc)?, result))
+    })
+    .await
+}
+
+async fn read_schedules(op: &Operator, ws_path: &str) -> Result<SchedulesDoc> {
+    let (value, _) = storage::read_json_versioned(op, &schedules_path(ws_path)).await?;
+    parse_doc(value)
+}
+
+/// Schedules `template_id` to be instantiated at `first_run_at` and then
+/// every `recurrence` step.
+pub async fn add_schedule(
+    op: &Operator,
+    ws_path: &str,
+    template_id: &str,
+    recurrence: Recurrence,
+    first_run_at: f64,
+    variables: Value,
+    author: &str,
+) -> Result<TemplateSchedule> {
+    template::get_template(op, ws_path, template_id).await?;
+    let variables = match variables {
+        Value::Object(map) => map,
+        Value::Null => Map::new(),
+        _ => return Err(anyhow!("Schedule variables must be an object")),
+    };
+    let schedule = TemplateSchedule {
+        id: uuid::Uuid::new_v4().to_string(),
+        template_id: template_id.to_string(),
+        recurrence,
+        next_run_at: first_run_at,
+        variables,
+        author: author.to_string(),
+        created_at: entry::now_ts(),
+        last_run_at: None,
+        last_entry_id: None,
+    };
+    update_schedules(op, ws_path, |doc| {
+        doc.schedules.insert(schedule.id.clone(), schedule.clone());
+        Ok(())
+    })
+    .await?;
+    Ok(schedule)
+}
+
+/// All schedules, soonest first.
+pub async fn list_schedules(op: &Operator, ws_path: &str) -> Result<Vec<TemplateSchedule>> {
+    let mut schedules: Vec<TemplateSchedule> = read_schedules(op, ws_path)
+        .await?
+        .schedules
+        .into_values()
+        .collect();
+    schedules.sort_by(|a, b| {
+        a.next_run_at
+            .total_cmp(&b.next_run_at)
+            .then(a.id.cmp(&b.id))
+    });
+    Ok(schedules)
+}
+
+/// Deletes a schedule. Returns whether it existed; entries it generated
+/// are kept.
+pub async fn delete_schedule(op: &Operator, ws_path: &str, schedule_id: &str) -> Result<bool> {
+    update_schedules(op, ws_path, |doc| {
+        Ok(doc.schedules.remove(schedule_id).is_some())
+    })
+    .await
+}
+
+/// Moves a due schedule's `next_run_at` past `now`, skipping missed runs,
+/// and returns it as it was. `None` when another runner got there first.
+async fn claim_run(
+    op: &Operator,
+    ws_path: &str,
+    schedule_id: &str,
+    due_at: f64,
+    now: f64,
+) -> Result<Option<TemplateSchedule>> {
+    update_schedules(op, ws_path, |doc| {
+        let Some(schedule) = doc.schedules.get_mut(schedule_id) else {
+            return Ok(None);
+        };
+        if schedule.next_run_at != due_at {
+            return Ok(None);
+        }
+        let claimed = schedule.clone();
+        let mut next = schedule.recurrence.advance(schedule.next_run_at)?;
+        while next <= now {
+            next = schedule.recurrence.advance(next)?;
+        }
+        schedule.next_run_at = next;
+        Ok(Some(claimed))
+    })
+    .await
+}
+
+async fn finish_run(
+    op: &Operator,
+    ws_path: &str,
+    claimed: &TemplateSchedule,
+    result: Option<(&str, f64)>,
+) -> Result<()> {
+    update_schedules(op, ws_path, |doc| {
+        let Some(schedule) = doc.schedules.get_mut(&claimed.id) else {
+            return Ok(());
+        };
+        match result {
+            Some((entry_id, ran_at)) => {
+                schedule.last_entry_id = Some(entry_id.to_string());
+                schedule.last_run_at = Some(ran_at);
+            }
+            // A failed run stays due so it is retried on the next call.
+            None => schedule.next_run_at = claimed.next_run_at,
+        }
+        Ok(())
+    })
+    .await
+}
+
+/// Instantiates every schedule due at `now`, once each however many runs
+/// were missed, and links each new entry to the previous instance with a
+/// `previous` link while that entry still exists.
+///
+/// The host calls this periodically, like [`crate::retention::apply_retention`].
+/// A run that fails is reported in `failed` and left due.
+pub async fn run_due_schedules<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    now: f64,
+    integrity: &I,
+) -> Result<Value> {
+    let clock = DateTime::<Utc>::from_timestamp_millis((now * 1000.0) as i64)
+        .ok_or_else(|| anyhow!("Invalid schedule timestamp: {}", now))?;
+    let mut created = Vec::new();
+    let mut failed = Vec::new();
+    let due: Vec<TemplateSchedule> = list_schedules(op, ws_path)
+        .await?
+        .into_iter()
+        .filter(|schedule| schedule.next_run_at <= now)
+        .collect();
+    for schedule in due {
+        let Some(claimed) = claim_run(op, ws_path, &schedule.id, schedule.next_run_at, now).await?
+        else {
+            continue;
+        };
+        let previous = match &claimed.last_entry_id {
+            Some(id) => entry::exists(op, ws_path, id)
+                .await?
+                .filter(|existing| !existing.deleted)
+                .map(|existing| existing.entry_id),
+            None => None,
+        };
+        let mut variables = claimed.variables.clone();
+        variables
+            .entry(PREVIOUS_VARIABLE.to_string())
+            .or_insert_with(|| Value::String(previous.clone().unwrap_or_default()));
+        let result = template::instantiate_template(
+            op,
+            ws_path,
+            &claimed.template_id,
+            &variables,
+            None,
+            &claimed.author,
+            integrity,
+            clock,
+        )
+        .await;
+        let meta = match result {
+            Ok(meta) => meta,
+            Err(err) => {
+                finish_run(op, ws_path, &claimed, None).await?;
+                failed.push(serde_json::json!({
+                    "schedule_id": claimed.id,
+                    "reason": err.to_string(),
+                }));
+                continue;
+            }
+        };
+        if let Some(previous) = &previous {
+            let link_id = uuid::Uuid::new_v4().to_string();
+            link::create_link(
+                op,
+                ws_path,
+                &meta.id,
+                previous,
+                PREVIOUS_LINK_KIND,
+                &link_id,
+            )
+            .await?;
+        }
+        finish_run(op, ws_path, &claimed, Some((&meta.id, now))).await?;
+        created.push(serde_json::json!({
+            "schedule_id": claimed.id,
+            "template_id": claimed.template_id,
+            "entry_id": meta.id,
+            "previous_entry_id": previous,
+        }));
+    }
+    Ok(serde_json::json!({
+        "ran_at": now,
+        "created": created,
+        "failed": failed,
+    }))
+}
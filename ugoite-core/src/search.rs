@@ -1,32 +1,261 @@
 use anyhow::Result;
 use opendal::Operator;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 
 use crate::entry;
+use crate::extraction;
+use crate::space;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SearchResult {
     pub id: String,
 }
 
+/// Text analysis settings applied to both entry content and search queries.
+///
+/// Read from the `search` object in the space `settings.json`, e.g.
+/// `{"language": "en", "stemming": true, "cjk_ngram": 2}`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Analyzer {
+    #[serde(default = "default_language")]
+    pub language: String,
+    #[serde(default)]
+    pub stemming: bool,
+    /// N-gram size for CJK runs; `0` keeps each run as a single token.
+    #[serde(default = "default_cjk_ngram")]
+    pub cjk_ngram: usize,
+}
+
+fn default_language() -> String {
+    "auto".to_string()
+}
+
+fn default_cjk_ngram() -> usize {
+    2
+}
+
+impl Default for Analyzer {
+    fn default() -> Self {
+        Self {
+            language: default_language(),
+            stemming: false,
+            cjk_ngram: default_cjk_ngram(),
+        }
+    }
+}
+
+impl Analyzer {
+    pub async fn for_space(op: &Operator, ws_path: &str) -> Result<Self> {
+        let settings = space::read_space_settings(op, ws_path).await?;
+        match settings.get("search") {
+            Some(value) if value.is_object() => Ok(serde_json::from_value(value.clone())?),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    fn stems_english(&self) -> bool {
+        self.stemming && matches!(self.language.as_str(), "en" | "auto")
+    }
+}
+
+fn is_cjk(ch: char) -> bool {
+    matches!(ch,
+        '\u{3040}'..='\u{30FF}'   // Hiragana, Katakana
+        | '\u{3400}'..='\u{4DBF}' // CJK Extension A
+        | '\u{4E00}'..='\u{9FFF}' // CJK Unified Ideographs
+        | '\u{F900}'..='\u{FAFF}' // CJK Compatibility Ideographs
+        | '\u{AC00}'..='\u{D7AF}' // Hangul Syllables
+        | '\u{FF66}'..='\u{FF9F}') // Halfwidth Katakana
+}
+
+fn light_stem(word: &str) -> String {
+    let len = word.chars().count();
+    if len <= 3 || !word.is_ascii() {
+        return word.to_string();
+    }
+    if let Some(stem) = word.strip_suffix("ies") {
+        if len > 4 {
+            return format!("{}y", stem);
+        }
+    }
+    for suffix in ["ing", "ed"] {
+        if let Some(stem) = word.strip_suffix(suffix) {
+            if stem.len() >= 3 {
+                return stem.to_string();
+            }
+        }
+    }
+    for suffix in ["ches", "shes", "xes", "zes", "sses"] {
+        if word.ends_with(suffix) {
+            return word[..word.len() - 2].to_string();
+        }
+    }
+    if word.ends_with('s') && !word.ends_with("ss") {
+        return word[..word.len() - 1].to_string();
+    }
+    word.to_string()
+}
+
+fn push_cjk_tokens(run: &[char], n: usize, tokens: &mut Vec<String>) {
+    if n == 0 || run.len() <= n {
+        tokens.push(run.iter().collect());
+        return;
+    }
+    for window in run.windows(n) {
+        tokens.push(window.iter().collect());
+    }
+}
+
+/// Splits text into lowercase search tokens.
+///
+/// Latin-script words are split on non-alphanumeric characters (optionally
+/// stemmed); runs of CJK characters, which carry no whitespace, become n-grams.
+pub fn analyze_text(text: &str, analyzer: &Analyzer) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut cjk_run: Vec<char> = Vec::new();
+
+    let flush_word = |word: &mut String, tokens: &mut Vec<String>| {
+        if !word.is_empty() {
+            let token = if analyzer.stems_english() {
+                light_stem(word)
+            } else {
+                word.clone()
+            };
+            tokens.push(token);
+            word.clear();
+        }
+    };
+
+    for ch in text.chars().flat_map(|c| c.to_lowercase()) {
+        if is_cjk(ch) {
+            flush_word(&mut word, &mut tokens);
+            cjk_run.push(ch);
+            continue;
+        }
+        if !cjk_run.is_empty() {
+            push_cjk_tokens(&cjk_run, analyzer.cjk_ngram, &mut tokens);
+            cjk_run.clear();
+        }
+        if ch.is_alphanumeric() {
+            word.push(ch);
+        } else {
+            flush_word(&mut word, &mut tokens);
+        }
+    }
+    flush_word(&mut word, &mut tokens);
+    if !cjk_run.is_empty() {
+        push_cjk_tokens(&cjk_run, analyzer.cjk_ngram, &mut tokens);
+    }
+    tokens
+}
+
+fn collect_text(value: &Value, out: &mut String) {
+    match value {
+        Value::String(text) => {
+            out.push_str(text);
+            out.push('\n');
+        }
+        Value::Number(num) => {
+            out.push_str(&num.to_string());
+            out.push('\n');
+        }
+        Value::Array(items) => items.iter().for_each(|item| collect_text(item, out)),
+        Value::Object(map) => map.values().for_each(|item| collect_text(item, out)),
+        _ => {}
+    }
+}
+
+fn entry_search_text(row: &entry::EntryRow) -> String {
+    let mut text = String::new();
+    text.push_str(&row.title);
+    text.push('\n');
+    for tag in &row.tags {
+        text.push_str(tag);
+        text.push('\n');
+    }
+    collect_text(
+        &entry::merge_entry_fields(&row.fields, &row.extra_attributes),
+        &mut text,
+    );
+    text
+}
+
+/// Text extracted from the assets `row` owns, one per line.
+fn owned_asset_text(row: &entry::EntryRow, asset_texts: &HashMap<String, String>) -> String {
+    if asset_texts.is_empty() {
+        return String::new();
+    }
+    let mut text = String::new();
+    for id in extraction::owned_asset_ids(row) {
+        if let Some(extracted) = asset_texts.get(&id) {
+            text.push('\n');
+            text.push_str(extracted);
+        }
+    }
+    text
+}
+
+/// Everything keyword search matches an entry on: its title, tags, field
+/// values and the text of the assets it owns.
+pub(crate) fn searchable_text(
+    row: &entry::EntryRow,
+    asset_texts: &HashMap<String, String>,
+) -> String {
+    format!(
+        "{}{}",
+        entry_search_text(row),
+        owned_asset_text(row, asset_texts)
+    )
+}
+
 /// Hybrid keyword search using index and content fallback.
+///
+/// An entry matches when every analyzed query token appears among its
+/// analyzed content tokens, or when the raw query is a substring of the entry.
+/// Text extracted from the assets an entry owns counts as its content.
 pub async fn search_entries(
     op: &Operator,
     ws_path: &str,
     query: &str,
 ) -> Result<Vec<SearchResult>> {
+    search_entries_filtered(op, ws_path, query, entry::ArchivedFilter::Exclude).await
+}
+
+/// [`search_entries`] over the entries `archived` keeps; the default leaves
+/// archived entries out.
+pub async fn search_entries_filtered(
+    op: &Operator,
+    ws_path: &str,
+    query: &str,
+    archived: entry::ArchivedFilter,
+) -> Result<Vec<SearchResult>> {
+    let analyzer = Analyzer::for_space(op, ws_path).await?;
+    let query_tokens: HashSet<String> = analyze_text(query, &analyzer).into_iter().collect();
     let query = query.to_lowercase();
     let mut found_ids = HashSet::new();
 
+    let asset_texts = extraction::list_asset_texts(op, ws_path).await?;
     let rows = entry::list_entry_rows(op, ws_path).await?;
     for (_form_name, row) in rows {
-        if row.deleted {
+        if row.deleted || !archived.keeps(row.archived) {
             continue;
         }
-        let dump = serde_json::to_string(&row)?.to_lowercase();
+        let asset_text = owned_asset_text(&row, &asset_texts);
+        let dump = format!("{}{}", serde_json::to_string(&row)?, asset_text).to_lowercase();
         if dump.contains(&query) {
             found_ids.insert(row.entry_id);
+            continue;
+        }
+        if query_tokens.is_empty() {
+            continue;
+        }
+        let text = format!("{}{}", entry_search_text(&row), asset_text);
+        let entry_tokens: HashSet<String> = analyze_text(&text, &analyzer).into_iter().collect();
+        if query_tokens.is_subset(&entry_tokens) {
+            found_ids.insert(row.entry_id);
         }
     }
 
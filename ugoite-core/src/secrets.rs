@@ -0,0 +1,341 @@
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose, Engine as _};
+use hmac::{Hmac, Mac};
+use opendal::Operator;
+use rand::RngExt;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::hkdf;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use std::collections::BTreeMap;
+
+use crate::audit;
+use crate::entry;
+use crate::integrity;
+use crate::storage;
+
+const SECRETS_FILE: &str = "secrets.json";
+/// Storage-root file holding the master key when [`STORED_KEY_OPT_IN_ENV`]
+/// allows it.
+const MASTER_KEY_FILE: &str = "secrets_key.json";
+/// Base64 master key; keeps the key out of the storage the secrets live in.
+pub const MASTER_KEY_ENV: &str = "UGOITE_SECRETS_KEY";
+/// Set to `1` or `true` to keep a generated master key in
+/// [`MASTER_KEY_FILE`] next to the ciphertext when [`MASTER_KEY_ENV`] is
+/// unset. Anyone who can read that storage can then decrypt every secret.
+pub const STORED_KEY_OPT_IN_ENV: &str = "UGOITE_SECRETS_ALLOW_STORED_KEY";
+const KEY_DOMAIN: &[u8] = b"ugoite-space-secrets/1";
+const CAPABILITY_DOMAIN: &str = "ugoite-secret-capability/1";
+
+/// One stored secret. Only the ciphertext is kept; the space id and name are
+/// bound in as associated data so a value cannot be moved to another name.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct StoredSecret {
+    nonce: String,
+    ciphertext: String,
+    updated_at: f64,
+}
+
+/// `secrets.json`: name -> encrypted value.
+type SecretsDoc = BTreeMap<String, StoredSecret>;
+
+/// What a capability token allows: reading `secrets` (or every secret for
+/// `"*"`) in `space_id` until `expires_at`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SecretCapability {
+    pub space_id: String,
+    pub secrets: Vec<String>,
+    pub expires_at: f64,
+    /// Who the capability was issued to, recorded with each read.
+    pub holder: String,
+}
+
+impl SecretCapability {
+    fn allows(&self, space_id: &str, name: &str, now: f64) -> bool {
+        self.space_id == space_id
+            && now < self.expires_at
+            && self.secrets.iter().any(|s| s == "*" || s == name)
+    }
+}
+
+fn secrets_path(ws_path: &str) -> String {
+    format!("{}/{}", ws_path.trim_end_matches('/'), SECRETS_FILE)
+}
+
+fn parse_doc(value: Option<Value>) -> Result<SecretsDoc> {
+    Ok(match value {
+        Some(value) => serde_json::from_value(value)?,
+        None => SecretsDoc::new(),
+    })
+}
+
+fn space_id_of(ws_path: &str) -> &str {
+    let trimmed = ws_path.trim_end_matches('/');
+    trimmed.rsplit('/').next().unwrap_or(trimmed)
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(anyhow!(
+            "Invalid secret name: {} (use letters, digits and underscores)",
+            name
+        ));
+    }
+    Ok(())
+}
+
+async fn load_master_key(op: &Operator) -> Result<Vec<u8>> {
+    if let Ok(key_b64) = std::env::var(MASTER_KEY_ENV) {
+        return general_purpose::STANDARD
+            .decode(key_b64.trim())
+            .map_err(|e| anyhow!("Invalid {}: {}", MASTER_KEY_ENV, e));
+    }
+    let opted_in = std::env::var(STORED_KEY_OPT_IN_ENV)
+        .is_ok_and(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true"));
+    if !opted_in {
+        return Err(anyhow!(
+            "Secrets need a master key: set {} (or {}=1 to keep a generated key in storage)",
+            MASTER_KEY_ENV,
+            STORED_KEY_OPT_IN_ENV
+        ));
+    }
+    let (payload, _) = storage::read_json_versioned(op, MASTER_KEY_FILE).await?;
+    let payload = match payload {
+        Some(payload) => payload,
+        None => {
+            let mut key_bytes = [0u8; 32];
+            rand::rng().fill(&mut key_bytes);
+            let payload = serde_json::json!({
+                "key": general_purpose::STANDARD.encode(key_bytes),
+                "created_at": entry::now_ts(),
+            });
+            // Create-only: when another process wins the race, its key is the
+            // one secrets are sealed under.
+            match storage::write_json_conditional(
+                op,
+                MASTER_KEY_FILE,
+                &payload,
+                &storage::WriteCondition::Absent,
+            )
+            .await
+            {
+                Ok(()) => payload,
+                Err(err) if storage::is_write_conflict(&err) => {
+                    storage::read_json_versioned(op, MASTER_KEY_FILE)
+                        .await?
+                        .0
+                        .ok_or_else(|| {
+                            anyhow!("{} vanished after a write conflict", MASTER_KEY_FILE)
+                        })?
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    };
+    let key_b64 = payload
+        .get("key")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("key missing in {}", MASTER_KEY_FILE))?;
+    Ok(general_purpose::STANDARD.decode(key_b64)?)
+}
+
+/// Fails unless secrets can be sealed: [`MASTER_KEY_ENV`] is set, or
+/// [`STORED_KEY_OPT_IN_ENV`] allows a stored key. The error names both.
+pub async fn require_master_key(op: &Operator) -> Result<()> {
+    load_master_key(op).await.map(|_| ())
+}
+
+/// The space's AES-256-GCM key, derived from the master key with HKDF so
+/// each space encrypts under its own key.
+async fn space_key(op: &Operator, space_id: &str) -> Result<LessSafeKey> {
+    let master = load_master_key(op).await?;
+    let info = [space_id.as_bytes()];
+    let prk = hkdf::Salt::new(hkdf::HKDF_SHA256, KEY_DOMAIN).extract(&master);
+    let okm = prk
+        .expand(&info, &AES_256_GCM)
+        .map_err(|_| anyhow!("Failed to derive the secrets key"))?;
+    Ok(LessSafeKey::new(UnboundKey::from(okm)))
+}
+
+fn associated_data(space_id: &str, name: &str) -> Vec<u8> {
+    format!("{}\n{}", space_id, name).into_bytes()
+}
+
+/// Encrypts `value` and stores it as secret `name`, replacing any previous
+/// value. The value itself is never logged; the audit event names the
+/// secret only.
+pub async fn set_secret(
+    op: &Operator,
+    ws_path: &str,
+    name: &str,
+    value: &str,
+    actor: &str,
+) -> Result<()> {
+    validate_name(name)?;
+    let space_id = space_id_of(ws_path);
+    let key = space_key(op, space_id).await?;
+    let mut nonce_bytes = [0u8; NONCE_LEN];
+    rand::rng().fill(&mut nonce_bytes);
+    let mut sealed = value.as_bytes().to_vec();
+    key.seal_in_place_append_tag(
+        Nonce::assume_unique_for_key(nonce_bytes),
+        Aad::from(associated_data(space_id, name)),
+        &mut sealed,
+    )
+    .map_err(|_| anyhow!("Failed to encrypt secret {}", name))?;
+    let stored = StoredSecret {
+        nonce: general_purpose::STANDARD.encode(nonce_bytes),
+        ciphertext: general_purpose::STANDARD.encode(&sealed),
+        updated_at: entry::now_ts(),
+    };
+    storage::update_json(op, &secrets_path(ws_path), |current| {
+        let mut doc = parse_doc(current)?;
+        doc.insert(name.to_string(), stored.clone());
+        Ok((serde_json::to_value(&doc)?, ()))
+    })
+    .await?;
+    audit::record_audit_event(
+        op,
+        ws_path,
+        "secret.set",
+        actor,
+        serde_json::json!({"name": name}),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Removes secret `name`, returning whether it existed.
+pub async fn delete_secret(op: &Operator, ws_path: &str, name: &str, actor: &str) -> Result<bool> {
+    let removed = storage::update_json(op, &secrets_path(ws_path), |current| {
+        let mut doc = parse_doc(current)?;
+        let removed = doc.remove(name).is_some();
+        Ok((serde_json::to_value(&doc)?, removed))
+    })
+    .await?;
+    if removed {
+        audit::record_audit_event(
+            op,
+            ws_path,
+            "secret.deleted",
+            actor,
+            serde_json::json!({"name": name}),
+        )
+        .await?;
+    }
+    Ok(removed)
+}
+
+/// Names and update times of the space's secrets; values are never listed.
+pub async fn list_secrets(op: &Operator, ws_path: &str) -> Result<Vec<Value>> {
+    let (value, _) = storage::read_json_versioned(op, &secrets_path(ws_path)).await?;
+    let doc = parse_doc(value)?;
+    Ok(doc
+        .into_iter()
+        .map(|(name, stored)| serde_json::json!({"name": name, "updated_at": stored.updated_at}))
+        .collect())
+}
+
+fn capability_mac(secret: &[u8], payload: &str) -> Result<Hmac<Sha256>> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret)?;
+    mac.update(CAPABILITY_DOMAIN.as_bytes());
+    mac.update(b"\n");
+    mac.update(payload.as_bytes());
+    Ok(mac)
+}
+
+/// Issues a capability token letting `holder` read `secrets` (names, or
+/// `"*"`) for `ttl_seconds`. Tokens are signed with the space HMAC key and
+/// are handed to automation scripts and webhooks in place of the values.
+pub async fn issue_secret_capability(
+    op: &Operator,
+    ws_path: &str,
+    holder: &str,
+    secrets: &[String],
+    ttl_seconds: f64,
+) -> Result<String> {
+    if secrets.is_empty() {
+        return Err(anyhow!("A secret capability must name at least one secret"));
+    }
+    for name in secrets.iter().filter(|name| *name != "*") {
+        validate_name(name)?;
+    }
+    if ttl_seconds <= 0.0 {
+        return Err(anyhow!("Capability ttl must be positive"));
+    }
+    let space_id = space_id_of(ws_path);
+    let capability = SecretCapability {
+        space_id: space_id.to_string(),
+        secrets: secrets.to_vec(),
+        expires_at: entry::now_ts() + ttl_seconds,
+        holder: holder.to_string(),
+    };
+    let payload = general_purpose::URL_SAFE_NO_PAD.encode(serde_json::to_vec(&capability)?);
+    let (_, secret) = integrity::load_hmac_material(op, space_id).await?;
+    let signature = capability_mac(&secret, &payload)?.finalize().into_bytes();
+    Ok(format!("{}.{}", payload, hex::encode(signature)))
+}
+
+async fn verify_capability(op: &Operator, space_id: &str, token: &str) -> Result<SecretCapability> {
+    let invalid = || anyhow!("Invalid secret capability");
+    let (payload, signature) = token.split_once('.').ok_or_else(invalid)?;
+    let signature = hex::decode(signature).map_err(|_| invalid())?;
+    let (_, secret) = integrity::load_hmac_material(op, space_id).await?;
+    capability_mac(&secret, payload)?
+        .verify_slice(&signature)
+        .map_err(|_| invalid())?;
+    let bytes = general_purpose::URL_SAFE_NO_PAD
+        .decode(payload)
+        .map_err(|_| invalid())?;
+    serde_json::from_slice(&bytes).map_err(|_| invalid())
+}
+
+/// Decrypts secret `name` for the holder of `capability`, which must be a
+/// token from [`issue_secret_capability`] for this space that names the
+/// secret and has not expired. Each read is recorded in the audit log.
+pub async fn get_secret(
+    op: &Operator,
+    ws_path: &str,
+    name: &str,
+    capability: &str,
+) -> Result<String> {
+    let space_id = space_id_of(ws_path);
+    let granted = verify_capability(op, space_id, capability).await?;
+    if !granted.allows(space_id, name, entry::now_ts()) {
+        return Err(anyhow!("Secret capability does not allow reading {}", name));
+    }
+    let (value, _) = storage::read_json_versioned(op, &secrets_path(ws_path)).await?;
+    let mut doc = parse_doc(value)?;
+    let stored = doc
+        .remove(name)
+        .ok_or_else(|| anyhow!("Secret not found: {}", name))?;
+    let nonce: [u8; NONCE_LEN] = general_purpose::STANDARD
+        .decode(&stored.nonce)?
+        .try_into()
+        .map_err(|_| anyhow!("Secret {} has a malformed nonce", name))?;
+    let mut sealed = general_purpose::STANDARD.decode(&stored.ciphertext)?;
+    let key = space_key(op, space_id).await?;
+    let plain = key
+        .open_in_place(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(associated_data(space_id, name)),
+            &mut sealed,
+        )
+        .map_err(|_| anyhow!("Secret {} could not be decrypted", name))?;
+    let value = String::from_utf8(plain.to_vec())?;
+    audit::record_audit_event(
+        op,
+        ws_path,
+        "secret.read",
+        &granted.holder,
+        serde_json::json!({"name": name}),
+    )
+    .await?;
+    Ok(value)
+}
@@ -6,14 +6,157 @@ use opendal::{EntryMode, Operator};
 use pyo3::prelude::*;
 use rand::TryRng;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use url::Url;
 
+use crate::clock;
+use crate::entry;
+use crate::form;
+use crate::iceberg_store;
+use crate::index;
+use crate::storage::{self, WriteCondition};
+
+/// On-disk layout version written to `meta.json`.
+///
+/// 1. Original layout (no `format_version` recorded).
+/// 2. Catalog manifest, versioned index, Form tables matching their definitions.
+pub const SPACE_FORMAT_VERSION: u32 = 2;
+
+/// Documents copied into a backup before an in-place upgrade.
+const BACKUP_DOCUMENTS: [&str; 4] = [
+    "meta.json",
+    "settings.json",
+    "catalog.json",
+    "index/manifest.json",
+];
+
+/// Number of authors listed in an activity digest.
+const DIGEST_TOP_AUTHORS: usize = 5;
+
+/// Storage features this build understands. Spaces record the features they
+/// were written with, and a space using a feature missing here is refused.
+/// A change an older build would misread or silently drop adds a feature.
+pub const SPACE_FEATURES: [&str; 15] = [
+    "catalog_manifest",
+    "conditional_writes",
+    "index_shards",
+    "parquet_settings",
+    "access_log",
+    // Algorithm-tagged entry checksums (`blake3:...`).
+    "checksum_tags",
+    // Revision columns.
+    "changed_fields",
+    "revision_message",
+    // Entry columns.
+    "entry_metadata",
+    "archived",
+    "hard_deleted",
+    // Form definition keys older builds would ignore.
+    "protected_fields",
+    "form_hooks",
+    // Space documents.
+    "secrets",
+    "asset_integrity",
+];
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SpaceMeta {
     pub id: String,
     pub name: String,
     pub created_at: f64, // Python uses time.time() which is float seconds, not ISO string
     pub storage: StorageConfig,
+    #[serde(default = "legacy_format_version")]
+    pub format_version: u32,
+    #[serde(default)]
+    pub crate_version: Option<String>,
+    #[serde(default)]
+    pub features: Vec<String>,
+}
+
+fn legacy_format_version() -> u32 {
+    1
+}
+
+/// Reasons a space cannot be opened by this build.
+#[derive(Debug, thiserror::Error)]
+pub enum SpaceCompatibilityError {
+    #[error(
+        "Space {space_id} was written with format version {found} (ugoite-core {written_by}), \
+         newer than supported version {supported}; upgrade ugoite to open it"
+    )]
+    NewerFormat {
+        space_id: String,
+        found: u32,
+        supported: u32,
+        written_by: String,
+    },
+    #[error("Space {space_id} requires unsupported storage features: {}", missing.join(", "))]
+    UnsupportedFeatures {
+        space_id: String,
+        missing: Vec<String>,
+    },
+}
+
+/// A write needs columns its Form table was created without. Tables are only
+/// rebuilt by [`upgrade_format`], which backs the space up first.
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "Form {form} {table} table has no {} column; run the space format upgrade first",
+    columns.join(", ")
+)]
+pub struct FormatUpgradeRequired {
+    pub form: String,
+    pub table: String,
+    pub columns: Vec<String>,
+}
+
+/// Checks that this build can read a space described by `meta`.
+pub fn check_compatibility(meta: &serde_json::Value) -> Result<(), SpaceCompatibilityError> {
+    let space_id = meta
+        .get("id")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let found = stored_format_version(meta);
+    if found > SPACE_FORMAT_VERSION {
+        return Err(SpaceCompatibilityError::NewerFormat {
+            space_id,
+            found,
+            supported: SPACE_FORMAT_VERSION,
+            written_by: meta
+                .get("crate_version")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown")
+                .to_string(),
+        });
+    }
+    let missing: Vec<String> = meta
+        .get("features")
+        .and_then(|v| v.as_array())
+        .map(|features| {
+            features
+                .iter()
+                .filter_map(|f| f.as_str())
+                .filter(|f| !SPACE_FEATURES.contains(f))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    if !missing.is_empty() {
+        return Err(SpaceCompatibilityError::UnsupportedFeatures { space_id, missing });
+    }
+    Ok(())
+}
+
+/// Refuses to open a space written by a newer, incompatible version.
+/// Paths without `meta.json` are left alone.
+pub async fn ensure_compatible(op: &Operator, ws_path: &str) -> Result<()> {
+    let meta_path = format!("{}/meta.json", ws_path.trim_end_matches('/'));
+    if !op.exists(&meta_path).await? {
+        return Ok(());
+    }
+    check_compatibility(&read_json(op, &meta_path).await?)?;
+    Ok(())
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -56,7 +199,7 @@ fn storage_type_and_root(root_uri: &str) -> (String, String, String) {
     )
 }
 
-fn generate_hmac_material() -> (String, String, String) {
+pub(crate) fn generate_hmac_material() -> (String, String, String) {
     let now_iso = Utc::now().to_rfc3339();
     let key_id = format!("key-{}", uuid::Uuid::new_v4().simple());
 
@@ -100,10 +243,25 @@ pub async fn create_space(op: &Operator, name: &str, root_path: &str) -> Result<
         "hmac_key_id": hmac_key_id,
         "hmac_key": hmac_key,
         "last_rotation": last_rotation,
+        "format_version": SPACE_FORMAT_VERSION,
+        "crate_version": env!("CARGO_PKG_VERSION"),
+        "features": SPACE_FEATURES,
     });
-    let meta_json = serde_json::to_vec_pretty(&meta)?;
-    op.write(&format!("{}/meta.json", ws_path), meta_json)
-        .await?;
+    // Claim the space with a create-only write so a concurrent creator loses cleanly.
+    storage::write_json_conditional(
+        op,
+        &format!("{}/meta.json", ws_path),
+        &meta,
+        &WriteCondition::Absent,
+    )
+    .await
+    .map_err(|err| {
+        if storage::is_write_conflict(&err) {
+            anyhow!("Space already exists: {}", name)
+        } else {
+            err
+        }
+    })?;
 
     // 3. Create settings.json
     let settings = serde_json::json!({
@@ -150,14 +308,17 @@ pub async fn list_spaces(op: &Operator) -> Result<Vec<String>> {
     Ok(spaces)
 }
 
+/// Reads a space's metadata, refusing layouts this build does not support.
+/// Writes nothing: an outdated index is rebuilt by index writes and
+/// [`upgrade_format`], not by opening the space.
 pub async fn get_space(op: &Operator, name: &str) -> Result<SpaceMeta> {
     if !space_exists(op, name).await? {
         return Err(anyhow!("Space not found: {}", name));
     }
     let meta_path = format!("spaces/{}/meta.json", name);
-    let bytes = op.read(&meta_path).await?;
-    let meta: SpaceMeta = serde_json::from_slice(&bytes.to_vec())?;
-    Ok(meta)
+    let raw = read_json(op, &meta_path).await?;
+    check_compatibility(&raw)?;
+    Ok(serde_json::from_value(raw)?)
 }
 
 async fn read_json(op: &Operator, path: &str) -> Result<serde_json::Value> {
@@ -165,9 +326,13 @@ async fn read_json(op: &Operator, path: &str) -> Result<serde_json::Value> {
     Ok(serde_json::from_slice(&bytes.to_vec())?)
 }
 
-async fn write_json(op: &Operator, path: &str, value: &serde_json::Value) -> Result<()> {
-    op.write(path, serde_json::to_vec_pretty(value)?).await?;
-    Ok(())
+/// Reads `settings.json` for a space path, returning an empty object when absent.
+pub async fn read_space_settings(op: &Operator, ws_path: &str) -> Result<serde_json::Value> {
+    let settings_path = format!("{}/settings.json", ws_path.trim_end_matches('/'));
+    if !op.exists(&settings_path).await? {
+        return Ok(serde_json::json!({}));
+    }
+    read_json(op, &settings_path).await
 }
 
 pub async fn get_space_raw(op: &Operator, name: &str) -> Result<serde_json::Value> {
@@ -175,7 +340,9 @@ pub async fn get_space_raw(op: &Operator, name: &str) -> Result<serde_json::Valu
         return Err(anyhow!("Space not found: {}", name));
     }
     let meta_path = format!("spaces/{}/meta.json", name);
-    read_json(op, &meta_path).await
+    let raw = read_json(op, &meta_path).await?;
+    check_compatibility(&raw)?;
+    Ok(raw)
 }
 
 pub async fn patch_space(
@@ -190,12 +357,11 @@ pub async fn patch_space(
         return Err(anyhow!("Space {} not found", space_id));
     }
 
-    let mut meta = read_json(op, &meta_path).await?;
-    let mut settings = if op.exists(&settings_path).await? {
-        read_json(op, &settings_path).await?
-    } else {
-        serde_json::json!({})
-    };
+    let (meta, meta_condition) = storage::read_json_versioned(op, &meta_path).await?;
+    let mut meta = meta.ok_or_else(|| anyhow!("Space {} not found", space_id))?;
+    check_compatibility(&meta)?;
+    let (settings, settings_condition) = storage::read_json_versioned(op, &settings_path).await?;
+    let mut settings = settings.unwrap_or_else(|| serde_json::json!({}));
 
     if let Some(name) = patch.get("name") {
         meta["name"] = name.clone();
@@ -211,10 +377,414 @@ pub async fn patch_space(
         }
     }
 
-    write_json(op, &meta_path, &meta).await?;
-    write_json(op, &settings_path, &settings).await?;
+    storage::write_json_conditional(op, &meta_path, &meta, &meta_condition).await?;
+    storage::write_json_conditional(op, &settings_path, &settings, &settings_condition).await?;
+    storage::set_space_throttle(
+        space_id,
+        &storage::StorageThrottle::from_settings(&settings),
+    );
+    storage::set_space_slow_log(
+        space_id,
+        &storage::SlowLogSettings::from_settings(&settings),
+    );
 
     let mut merged = meta;
     merged["settings"] = settings;
     Ok(merged)
 }
+
+/// Layout version recorded in space metadata; spaces that predate versioning are 1.
+pub fn stored_format_version(meta: &serde_json::Value) -> u32 {
+    meta.get("format_version")
+        .and_then(|v| v.as_u64())
+        .map(|v| v as u32)
+        .unwrap_or(1)
+}
+
+/// Copies the space metadata documents and records the current snapshot of
+/// every Form table under `backups/<timestamp>/`. Iceberg data files are
+/// immutable, so the snapshot ids are enough to roll tables back.
+async fn backup_space_metadata(op: &Operator, ws_path: &str) -> Result<String> {
+    let ws_path = ws_path.trim_end_matches('/');
+    let backup_root = format!(
+        "{}/backups/{}/",
+        ws_path,
+        Utc::now().format("%Y%m%dT%H%M%S%3fZ")
+    );
+    op.create_dir(&backup_root).await?;
+    for document in BACKUP_DOCUMENTS {
+        let source = format!("{}/{}", ws_path, document);
+        if op.exists(&source).await? {
+            let bytes = op.read(&source).await?;
+            op.write(&format!("{}{}", backup_root, document), bytes)
+                .await?;
+        }
+    }
+    let tables = iceberg_store::table_states(op, ws_path).await?;
+    op.write(
+        &format!("{}tables.json", backup_root),
+        serde_json::to_vec_pretty(&tables)?,
+    )
+    .await?;
+    Ok(backup_root)
+}
+
+/// Adds columns introduced by newer Form definitions to existing tables.
+/// Returns the names of the forms whose table schema changed.
+async fn upgrade_form_columns(op: &Operator, ws_path: &str) -> Result<Vec<String>> {
+    let mut changed = Vec::new();
+    for form_name in form::list_form_names(op, ws_path).await? {
+        let Ok(form_def) = form::read_form_definition(op, ws_path, &form_name).await else {
+            continue;
+        };
+        let before = iceberg_store::load_form_schema_fields(op, ws_path, &form_name)
+            .await
+            .ok()
+            .flatten();
+        form::upsert_form(op, ws_path, &form_def).await?;
+        let after = iceberg_store::load_form_schema_fields(op, ws_path, &form_name)
+            .await
+            .ok()
+            .flatten();
+        if before != after {
+            changed.push(form_name);
+        }
+    }
+    Ok(changed)
+}
+
+/// Rebuilds the tables of forms created before the entry and revision
+/// columns added since the first format. Returns the rebuilt forms.
+async fn upgrade_system_columns(op: &Operator, ws_path: &str) -> Result<Vec<String>> {
+    let mut changed = Vec::new();
+    for form_name in form::list_form_names(op, ws_path).await? {
+        if entry::upgrade_system_columns(op, ws_path, &form_name).await? {
+            changed.push(form_name);
+        }
+    }
+    Ok(changed)
+}
+
+/// Upgrades a space's on-disk layout to [`SPACE_FORMAT_VERSION`] in place.
+///
+/// Backs up the metadata first, then introduces the catalog manifest,
+/// migrates Form table columns, rebuilds tables created before the newer
+/// entry and revision columns and rebuilds an outdated index. Returns a
+/// report of the steps taken; a space already at the current version with
+/// every current feature recorded is untouched.
+pub async fn upgrade_format(op: &Operator, ws_path: &str) -> Result<serde_json::Value> {
+    let ws_path = ws_path.trim_end_matches('/');
+    let meta_path = format!("{}/meta.json", ws_path);
+    let (meta, meta_condition) = storage::read_json_versioned(op, &meta_path).await?;
+    let mut meta = meta.ok_or_else(|| anyhow!("Space not found: {}", ws_path))?;
+    check_compatibility(&meta)?;
+    let from_version = stored_format_version(&meta);
+
+    let mut report = serde_json::json!({
+        "space_id": meta.get("id").cloned().unwrap_or(serde_json::Value::Null),
+        "from_version": from_version,
+        "to_version": SPACE_FORMAT_VERSION,
+        "backup": serde_json::Value::Null,
+        "steps": [],
+    });
+    let recorded: HashSet<&str> = meta
+        .get("features")
+        .and_then(|v| v.as_array())
+        .map(|features| features.iter().filter_map(|f| f.as_str()).collect())
+        .unwrap_or_default();
+    if from_version == SPACE_FORMAT_VERSION && SPACE_FEATURES.iter().all(|f| recorded.contains(f)) {
+        return Ok(report);
+    }
+
+    report["backup"] = serde_json::Value::String(backup_space_metadata(op, ws_path).await?);
+
+    let manifest_created = iceberg_store::ensure_catalog_manifest(op, ws_path).await?;
+    let migrated_forms = upgrade_form_columns(op, ws_path).await?;
+    let rebuilt_forms = upgrade_system_columns(op, ws_path).await?;
+    let index_state = index::index_status(op, ws_path).await?["state"].clone();
+    let index_rebuilt = index_state != "current";
+    if index_rebuilt {
+        index::reindex_all(op, ws_path).await?;
+    }
+    report["steps"] = serde_json::json!([
+        {"step": "catalog_manifest", "changed": manifest_created},
+        {"step": "form_columns", "changed": !migrated_forms.is_empty(), "forms": migrated_forms},
+        {"step": "system_columns", "changed": !rebuilt_forms.is_empty(), "forms": rebuilt_forms},
+        {"step": "index", "changed": index_rebuilt, "previous_state": index_state},
+    ]);
+
+    meta["format_version"] = serde_json::json!(SPACE_FORMAT_VERSION);
+    meta["crate_version"] = serde_json::json!(env!("CARGO_PKG_VERSION"));
+    meta["features"] = serde_json::json!(SPACE_FEATURES);
+    storage::write_json_conditional(op, &meta_path, &meta, &meta_condition).await?;
+    Ok(report)
+}
+
+#[derive(Serialize, Default)]
+struct FormActivity {
+    created: usize,
+    edited: usize,
+    deleted: usize,
+}
+
+fn format_digest_time(ts: f64) -> String {
+    chrono::DateTime::<Utc>::from_timestamp_millis((ts * 1000.0) as i64)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_else(|| ts.to_string())
+}
+
+/// Summarizes activity in a space since `since` (epoch seconds).
+///
+/// Counts entries created, edited and deleted per form from the revision
+/// history, ranks authors by revisions written and lists form migrations
+/// separately. The result carries the same summary rendered as `markdown`,
+/// ready to be mailed or saved as an entry.
+pub async fn activity_digest(
+    op: &Operator,
+    ws_path: &str,
+    since: f64,
+) -> Result<serde_json::Value> {
+    let ws_path = ws_path.trim_end_matches('/');
+    let space_id = ws_path.rsplit('/').next().unwrap_or(ws_path);
+    let mut forms: BTreeMap<String, FormActivity> = BTreeMap::new();
+    let mut authors: HashMap<String, usize> = HashMap::new();
+    let mut migrations = Vec::new();
+
+    for form_name in form::list_form_names(op, ws_path).await? {
+        let form_def = form::read_form_definition(op, ws_path, &form_name).await?;
+        let revisions = entry::list_form_revision_rows(op, ws_path, &form_name, &form_def).await?;
+        let mut activity = FormActivity::default();
+        let mut edited: HashSet<&str> = HashSet::new();
+        let mut migrated: HashSet<&str> = HashSet::new();
+        let mut last_migrated_at = 0.0_f64;
+        for revision in revisions
+            .iter()
+            .filter(|r| clock::from_micros(r.timestamp) >= since)
+        {
+            if revision.author == form::MIGRATION_AUTHOR {
+                migrated.insert(&revision.entry_id);
+                last_migrated_at = last_migrated_at.max(clock::from_micros(revision.timestamp));
+                continue;
+            }
+            *authors.entry(revision.author.clone()).or_default() += 1;
+            if revision.parent_revision_id.is_none() {
+                activity.created += 1;
+            } else {
+                edited.insert(&revision.entry_id);
+            }
+        }
+        activity.edited = edited.len();
+        activity.deleted = entry::list_form_entry_rows(op, ws_path, &form_name, &form_def)
+            .await?
+            .iter()
+            .filter(|row| {
+                row.deleted
+                    && row
+                        .deleted_at
+                        .is_some_and(|at| clock::from_micros(at) >= since)
+            })
+            .count();
+        if !migrated.is_empty() {
+            migrations.push(serde_json::json!({
+                "form": form_name,
+                "entries": migrated.len(),
+                "last_migrated_at": last_migrated_at,
+            }));
+        }
+        if activity.created + activity.edited + activity.deleted > 0 {
+            forms.insert(form_name, activity);
+        }
+    }
+
+    let mut top_authors: Vec<(String, usize)> = authors.into_iter().collect();
+    top_authors.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    top_authors.truncate(DIGEST_TOP_AUTHORS);
+    let totals = FormActivity {
+        created: forms.values().map(|f| f.created).sum(),
+        edited: forms.values().map(|f| f.edited).sum(),
+        deleted: forms.values().map(|f| f.deleted).sum(),
+    };
+
+    let mut markdown = format!(
+        "# Activity since {}\n\n- {} created, {} edited, {} deleted\n",
+        format_digest_time(since),
+        totals.created,
+        totals.edited,
+        totals.deleted
+    );
+    if !forms.is_empty() {
+        markdown
+            .push_str("\n## By form\n\n| Form | Created | Edited | Deleted |\n|---|---|---|---|\n");
+        for (name, activity) in &forms {
+            markdown.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                name, activity.created, activity.edited, activity.deleted
+            ));
+        }
+    }
+    if !top_authors.is_empty() {
+        markdown.push_str("\n## Top authors\n\n");
+        for (rank, (author, count)) in top_authors.iter().enumerate() {
+            markdown.push_str(&format!("{}. {} ({} revisions)\n", rank + 1, author, count));
+        }
+    }
+    if !migrations.is_empty() {
+        markdown.push_str("\n## Migrations\n\n");
+        for migration in &migrations {
+            markdown.push_str(&format!(
+                "- {}: {} entries migrated\n",
+                migration["form"].as_str().unwrap_or_default(),
+                migration["entries"]
+            ));
+        }
+    }
+
+    Ok(serde_json::json!({
+        "space_id": space_id,
+        "since": since,
+        "generated_at": Utc::now().timestamp_millis() as f64 / 1000.0,
+        "totals": totals,
+        "forms": forms,
+        "top_authors": top_authors
+            .iter()
+            .map(|(author, count)| serde_json::json!({"author": author, "revisions": count}))
+            .collect::<Vec<_>>(),
+        "migrations": migrations,
+        "markdown": markdown,
+    }))
+}
+
+fn definition_at(history: &[(f64, serde_json::Value)], ts: f64) -> Option<&serde_json::Value> {
+    history
+        .iter()
+        .rev()
+        .find(|(recorded_at, _)| *recorded_at <= ts)
+        .map(|(_, definition)| definition)
+}
+
+fn form_field_changes(before: &serde_json::Value, after: &serde_json::Value) -> serde_json::Value {
+    let fields = |def: &serde_json::Value| {
+        def.get("fields")
+            .and_then(|f| f.as_object())
+            .cloned()
+            .unwrap_or_default()
+    };
+    let before_fields = fields(before);
+    let after_fields = fields(after);
+    let mut added = Vec::new();
+    let mut changed = Vec::new();
+    for (name, def) in &after_fields {
+        match before_fields.get(name) {
+            None => added.push(name.clone()),
+            Some(previous) if previous != def => changed.push(name.clone()),
+            Some(_) => {}
+        }
+    }
+    let removed: Vec<String> = before_fields
+        .keys()
+        .filter(|name| !after_fields.contains_key(*name))
+        .cloned()
+        .collect();
+    serde_json::json!({
+        "fields_added": added,
+        "fields_removed": removed,
+        "fields_changed": changed,
+    })
+}
+
+/// Lists what changed in a space between two points in time (epoch
+/// seconds, `from` < `to`): entries added, modified or deleted, and forms
+/// created or redefined.
+///
+/// Entries are compared by their latest revision at each point; modified
+/// entries name the fields that differ. An entry created and deleted inside
+/// the window is not reported. Form changes come from `form_history.json`,
+/// so forms not written since history was kept show no changes.
+pub async fn diff_snapshots(
+    op: &Operator,
+    ws_path: &str,
+    from: f64,
+    to: f64,
+) -> Result<serde_json::Value> {
+    if from >= to {
+        return Err(anyhow!("Snapshot diff requires from < to"));
+    }
+    let ws_path = ws_path.trim_end_matches('/');
+    let mut added = Vec::new();
+    let mut modified = Vec::new();
+    let mut deleted = Vec::new();
+    let mut forms_added = Vec::new();
+    let mut forms_changed = Vec::new();
+
+    for form_name in form::list_form_names(op, ws_path).await? {
+        let history = form::form_definition_history(op, ws_path, &form_name).await?;
+        match (definition_at(&history, from), definition_at(&history, to)) {
+            (None, Some(_)) => forms_added.push(form_name.clone()),
+            (Some(before), Some(after)) if before != after => {
+                let mut change = form_field_changes(before, after);
+                change["form"] = serde_json::json!(form_name);
+                forms_changed.push(change);
+            }
+            _ => {}
+        }
+
+        let form_def = form::read_form_definition(op, ws_path, &form_name).await?;
+        let mut revisions: HashMap<String, Vec<entry::RevisionRow>> = HashMap::new();
+        for revision in entry::list_form_revision_rows(op, ws_path, &form_name, &form_def).await? {
+            revisions
+                .entry(revision.entry_id.clone())
+                .or_default()
+                .push(revision);
+        }
+        for row in entry::list_form_entry_rows(op, ws_path, &form_name, &form_def).await? {
+            let Some(history) = revisions.get_mut(&row.entry_id) else {
+                continue;
+            };
+            history.sort_by_key(|revision| revision.timestamp);
+            let latest_at = |ts: f64| {
+                let ts = clock::to_micros(ts);
+                let alive = row.deleted_at.is_none_or(|at| at > ts);
+                history
+                    .iter()
+                    .rev()
+                    .find(|revision| revision.timestamp <= ts)
+                    .filter(|_| alive)
+            };
+            let item = serde_json::json!({"id": row.entry_id, "form": form_name});
+            match (latest_at(from), latest_at(to)) {
+                (None, Some(_)) => added.push(item),
+                (Some(_), None) => deleted.push(item),
+                (Some(before), Some(after)) if before.revision_id != after.revision_id => {
+                    let changed_fields: Vec<String> = entry::diff_fields(
+                        &entry::merge_entry_fields(&before.fields, &before.extra_attributes),
+                        &entry::merge_entry_fields(&after.fields, &after.extra_attributes),
+                    )
+                    .into_iter()
+                    .map(|change| change.field)
+                    .collect();
+                    let mut item = item;
+                    item["changed_fields"] = serde_json::json!(changed_fields);
+                    modified.push(item);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let by_id =
+        |a: &serde_json::Value, b: &serde_json::Value| a["id"].as_str().cmp(&b["id"].as_str());
+    added.sort_by(by_id);
+    modified.sort_by(by_id);
+    deleted.sort_by(by_id);
+    Ok(serde_json::json!({
+        "from": from,
+        "to": to,
+        "totals": {
+            "added": added.len(),
+            "modified": modified.len(),
+            "deleted": deleted.len(),
+        },
+        "entries": {"added": added, "modified": modified, "deleted": deleted},
+        "forms": {"added": forms_added, "changed": forms_changed},
+    }))
+}
@@ -20,6 +20,14 @@ pub struct SqlQuery {
     pub limit: Option<usize>,
 }
 
+impl SqlQuery {
+    /// The row cap applied to the result: the query's LIMIT, else the
+    /// engine maximum.
+    pub fn effective_limit(&self) -> usize {
+        self.limit.unwrap_or(MAX_QUERY_LIMIT)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SqlTableRef {
     pub name: String,
@@ -200,8 +208,7 @@ pub fn filter_entries_by_sql(
         }
     }
 
-    let effective_limit = query.limit.unwrap_or(MAX_QUERY_LIMIT);
-    filtered.truncate(effective_limit);
+    filtered.truncate(query.effective_limit());
 
     Ok(filtered
         .into_iter()
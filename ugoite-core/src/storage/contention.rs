@@ -0,0 +1,230 @@
+use anyhow::{anyhow, Result};
+use opendal::Operator;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use super::throttle::space_of;
+
+const CONTENTION_FILE: &str = "contention.json";
+/// Seconds covered by one bucket of the stats table.
+pub const CONTENTION_BUCKET_SECONDS: i64 = 3600;
+/// Buckets kept per space: two weeks of hours.
+pub const CONTENTION_BUCKETS: usize = 14 * 24;
+
+/// Something a writer ran into while writing to a space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContentionEvent {
+    /// An update named a parent revision that was no longer current.
+    RevisionConflict,
+    /// A conditional write found the document changed under it.
+    WriteConflict,
+    /// A read-modify-write was retried after a write conflict.
+    CommitRetry,
+    /// A read-modify-write gave up after its last retry.
+    RetriesExhausted,
+    /// A request waited on the space's storage throttle.
+    LockWait(Duration),
+}
+
+/// Counts for one space over one bucket, or summed over several.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(default)]
+pub struct ContentionCounts {
+    pub revision_conflicts: u64,
+    pub write_conflicts: u64,
+    pub commit_retries: u64,
+    pub retries_exhausted: u64,
+    pub lock_waits: u64,
+    pub lock_wait_ms: u64,
+}
+
+impl ContentionCounts {
+    fn record(&mut self, event: ContentionEvent) {
+        match event {
+            ContentionEvent::RevisionConflict => self.revision_conflicts += 1,
+            ContentionEvent::WriteConflict => self.write_conflicts += 1,
+            ContentionEvent::CommitRetry => self.commit_retries += 1,
+            ContentionEvent::RetriesExhausted => self.retries_exhausted += 1,
+            ContentionEvent::LockWait(waited) => {
+                self.lock_waits += 1;
+                self.lock_wait_ms += waited.as_millis() as u64;
+            }
+        }
+    }
+
+    fn add(&mut self, other: &ContentionCounts) {
+        self.revision_conflicts += other.revision_conflicts;
+        self.write_conflicts += other.write_conflicts;
+        self.commit_retries += other.commit_retries;
+        self.retries_exhausted += other.retries_exhausted;
+        self.lock_waits += other.lock_waits;
+        self.lock_wait_ms += other.lock_wait_ms;
+    }
+}
+
+/// Buckets by start time in unix seconds.
+type Buckets = BTreeMap<i64, ContentionCounts>;
+
+/// Counts recorded in this process and not yet flushed, by space id.
+fn pending() -> &'static Mutex<HashMap<String, Buckets>> {
+    static PENDING: OnceLock<Mutex<HashMap<String, Buckets>>> = OnceLock::new();
+    PENDING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn bucket_of(now: f64) -> i64 {
+    let seconds = now.floor() as i64;
+    seconds - seconds.rem_euclid(CONTENTION_BUCKET_SECONDS)
+}
+
+/// Counts `event` against `space_id` in the current bucket. Recording never
+/// fails the write that ran into the event.
+pub fn record_contention(space_id: &str, event: ContentionEvent) {
+    let bucket = bucket_of(crate::entry::now_ts());
+    if let Ok(mut pending) = pending().lock() {
+        pending
+            .entry(space_id.to_string())
+            .or_default()
+            .entry(bucket)
+            .or_default()
+            .record(event);
+    }
+}
+
+/// Counts `event` against the space `path` lies in, if any.
+pub(crate) fn record_contention_at(path: &str, event: ContentionEvent) {
+    if let Some(space_id) = space_of(path) {
+        record_contention(space_id, event);
+    }
+}
+
+fn contention_path(ws_path: &str) -> String {
+    format!("{}/{}", ws_path.trim_end_matches('/'), CONTENTION_FILE)
+}
+
+fn space_id_of(ws_path: &str) -> Result<&str> {
+    space_of(ws_path).ok_or_else(|| anyhow!("Not a space path: {}", ws_path))
+}
+
+fn parse_buckets(value: Option<Value>) -> Result<Buckets> {
+    let Some(value) = value else {
+        return Ok(Buckets::new());
+    };
+    let stored: BTreeMap<String, ContentionCounts> =
+        serde_json::from_value(value.get("buckets").cloned().unwrap_or(Value::Null))
+            .unwrap_or_default();
+    stored
+        .into_iter()
+        .map(|(start, counts)| {
+            let start = start
+                .parse()
+                .map_err(|_| anyhow!("Invalid contention bucket: {}", start))?;
+            Ok((start, counts))
+        })
+        .collect()
+}
+
+fn merge_buckets(into: &mut Buckets, from: &Buckets) {
+    for (start, counts) in from {
+        into.entry(*start).or_default().add(counts);
+    }
+}
+
+/// Adds the counts this process recorded for a space to its stats table in
+/// `contention.json`, dropping buckets past [`CONTENTION_BUCKETS`].
+///
+/// The host calls this periodically, like [`crate::retention::apply_retention`];
+/// counts not yet flushed are lost if the process exits. Returns the number
+/// of buckets written.
+pub async fn flush_contention_stats(op: &Operator, ws_path: &str) -> Result<usize> {
+    let space_id = space_id_of(ws_path)?;
+    let taken = pending()
+        .lock()
+        .map_err(|_| anyhow!("contention stats lock poisoned"))?
+        .remove(space_id)
+        .unwrap_or_default();
+    if taken.is_empty() {
+        return Ok(0);
+    }
+    let result = super::update_json(op, &contention_path(ws_path), |current| {
+        let mut buckets = parse_buckets(current)?;
+        merge_buckets(&mut buckets, &taken);
+        while buckets.len() > CONTENTION_BUCKETS {
+            buckets.pop_first();
+        }
+        let stored: BTreeMap<String, ContentionCounts> = buckets
+            .iter()
+            .map(|(start, counts)| (start.to_string(), *counts))
+            .collect();
+        Ok((serde_json::json!({ "buckets": stored }), taken.len()))
+    })
+    .await;
+    if result.is_err() {
+        // Keep the counts for the next flush.
+        if let Ok(mut pending) = pending().lock() {
+            merge_buckets(pending.entry(space_id.to_string()).or_default(), &taken);
+        }
+    }
+    result
+}
+
+/// Contention of one space since `since` (unix seconds), oldest bucket
+/// first, including counts recorded by this process but not yet flushed.
+pub async fn contention_stats(op: &Operator, ws_path: &str, since: Option<f64>) -> Result<Value> {
+    let space_id = space_id_of(ws_path)?;
+    let (stored, _) = super::read_json_versioned(op, &contention_path(ws_path)).await?;
+    let mut buckets = parse_buckets(stored)?;
+    if let Some(unflushed) = pending()
+        .lock()
+        .map_err(|_| anyhow!("contention stats lock poisoned"))?
+        .get(space_id)
+    {
+        merge_buckets(&mut buckets, unflushed);
+    }
+    let from = since.map(bucket_of).unwrap_or(i64::MIN);
+    let mut totals = ContentionCounts::default();
+    let mut rows = Vec::new();
+    for (start, counts) in buckets.range(from..) {
+        totals.add(counts);
+        let mut row = serde_json::to_value(counts)?;
+        row["start"] = serde_json::json!(start);
+        rows.push(row);
+    }
+    Ok(serde_json::json!({
+        "space_id": space_id,
+        "bucket_seconds": CONTENTION_BUCKET_SECONDS,
+        "buckets": rows,
+        "totals": totals,
+    }))
+}
+
+/// Contention totals of every space since `since`, most contended first
+/// (by conflicts and retries, then time spent waiting on the throttle).
+/// Spaces with nothing recorded are left out.
+pub async fn rank_spaces_by_contention(op: &Operator, since: Option<f64>) -> Result<Value> {
+    let mut ranked = Vec::new();
+    for space_id in crate::space::list_spaces(op).await? {
+        let stats = contention_stats(op, &format!("spaces/{}", space_id), since).await?;
+        let totals: ContentionCounts = serde_json::from_value(stats["totals"].clone())?;
+        if totals != ContentionCounts::default() {
+            ranked.push((space_id, totals));
+        }
+    }
+    let conflicts = |counts: &ContentionCounts| {
+        counts.revision_conflicts + counts.write_conflicts + counts.commit_retries
+    };
+    ranked.sort_by(|(a_id, a), (b_id, b)| {
+        conflicts(b)
+            .cmp(&conflicts(a))
+            .then(b.lock_wait_ms.cmp(&a.lock_wait_ms))
+            .then(a_id.cmp(b_id))
+    });
+    Ok(Value::Array(
+        ranked
+            .into_iter()
+            .map(|(space_id, totals)| serde_json::json!({"space_id": space_id, "totals": totals}))
+            .collect(),
+    ))
+}
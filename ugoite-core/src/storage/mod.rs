@@ -1,7 +1,30 @@
 use anyhow::Result;
+use opendal::layers::TracingLayer;
 use opendal::Operator;
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use std::sync::{Mutex, OnceLock};
+use std::sync::{Arc, Mutex, OnceLock};
+
+mod contention;
+mod request;
+mod slow_log;
+mod throttle;
+
+pub(crate) use contention::record_contention_at;
+pub use contention::{
+    contention_stats, flush_contention_stats, rank_spaces_by_contention, record_contention,
+    ContentionCounts, ContentionEvent, CONTENTION_BUCKETS, CONTENTION_BUCKET_SECONDS,
+};
+pub use request::{current_request_id, new_request_id, with_request_id, RequestTiming};
+pub(crate) use request::{record_entry_lookup, record_iceberg_commit, record_iceberg_scan};
+pub use slow_log::{
+    flush_slow_log, list_slow_operations, set_space_slow_log, SlowLogSettings,
+    DEFAULT_SLOW_LOG_RECORDS, SLOW_LOG_SETTINGS_KEY,
+};
+pub use throttle::{
+    forget_space_throttle, set_space_throttle, wait_space_turn, SpaceThrottleLayer,
+    StorageThrottle, THROTTLE_SETTINGS_KEY,
+};
 
 static MEMORY_OPERATORS: OnceLock<Mutex<HashMap<String, Operator>>> = OnceLock::new();
 
@@ -9,6 +32,9 @@ fn memory_cache() -> &'static Mutex<HashMap<String, Operator>> {
     MEMORY_OPERATORS.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
+/// Opens the operator for a storage URI, throttled per space by
+/// [`SpaceThrottleLayer`] and traced, so each storage call shows up inside
+/// the span of the request that made it.
 pub fn operator_from_uri(uri: &str) -> Result<Operator> {
     if uri.starts_with("memory://") {
         let mut cache = memory_cache()
@@ -17,10 +43,220 @@ pub fn operator_from_uri(uri: &str) -> Result<Operator> {
         if let Some(op) = cache.get(uri) {
             return Ok(op.clone());
         }
-        let op = Operator::from_uri(uri)?;
+        let op = Operator::from_uri(uri)?
+            .layer(SpaceThrottleLayer)
+            .layer(TracingLayer);
         cache.insert(uri.to_string(), op.clone());
         return Ok(op);
     }
 
-    Ok(Operator::from_uri(uri)?)
+    Ok(Operator::from_uri(uri)?
+        .layer(SpaceThrottleLayer)
+        .layer(TracingLayer))
+}
+
+/// Precondition applied when writing a metadata document back to storage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WriteCondition {
+    /// Write unconditionally (last writer wins).
+    Any,
+    /// The object must not exist yet.
+    Absent,
+    /// The object must still carry this entity tag.
+    Matches(String),
+    /// The object must still hold content with this SHA-256 digest. Used
+    /// for backends that do not report entity tags.
+    Unchanged(String),
+}
+
+/// A write precondition failed because another writer changed `path`.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Write conflict: {path} was modified concurrently")]
+pub struct WriteConflict {
+    pub path: String,
+}
+
+fn write_conflict(path: &str) -> anyhow::Error {
+    record_contention_at(path, ContentionEvent::WriteConflict);
+    tracing::debug!(path, "write conflict");
+    WriteConflict {
+        path: path.to_string(),
+    }
+    .into()
+}
+
+type WriteLocks = HashMap<String, Arc<tokio::sync::Mutex<()>>>;
+
+fn write_locks() -> &'static Mutex<WriteLocks> {
+    static LOCKS: OnceLock<Mutex<WriteLocks>> = OnceLock::new();
+    LOCKS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn write_lock_key(op: &Operator, path: &str) -> String {
+    let info = op.info();
+    format!(
+        "{}://{}{}/{}",
+        info.scheme(),
+        info.name(),
+        info.root(),
+        path
+    )
+}
+
+/// Serializes conditional writes to `path` within this process, so the
+/// precondition check and the write cannot interleave with another writer's.
+/// Releasing the last guard for a path drops its lock.
+struct PathWriteGuard {
+    key: String,
+    guard: tokio::sync::OwnedMutexGuard<()>,
+}
+
+impl PathWriteGuard {
+    async fn acquire(op: &Operator, path: &str) -> Result<Self> {
+        let key = write_lock_key(op, path);
+        let lock = write_locks()
+            .lock()
+            .map_err(|_| anyhow::anyhow!("write lock table poisoned"))?
+            .entry(key.clone())
+            .or_default()
+            .clone();
+        let guard = lock.lock_owned().await;
+        Ok(Self { key, guard })
+    }
+}
+
+impl Drop for PathWriteGuard {
+    fn drop(&mut self) {
+        let Ok(mut locks) = write_locks().lock() else {
+            return;
+        };
+        // The table and this guard hold the only references: nobody waits.
+        if Arc::strong_count(tokio::sync::OwnedMutexGuard::mutex(&self.guard)) == 2 {
+            locks.remove(&self.key);
+        }
+    }
+}
+
+pub(crate) fn content_digest(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// Reads a JSON document along with the condition that guards writing it back.
+///
+/// Missing documents yield `(None, Absent)`. Backends that do not report
+/// entity tags yield `Unchanged` with a digest of the content read.
+pub async fn read_json_versioned(
+    op: &Operator,
+    path: &str,
+) -> Result<(Option<serde_json::Value>, WriteCondition)> {
+    if !op.exists(path).await? {
+        return Ok((None, WriteCondition::Absent));
+    }
+    let etag = op.stat(path).await?.etag().map(str::to_string);
+    let bytes = match &etag {
+        Some(etag) => op.read_with(path).if_match(etag).await,
+        None => op.read(path).await,
+    }
+    .map_err(|err| match err.kind() {
+        opendal::ErrorKind::ConditionNotMatch => write_conflict(path),
+        _ => err.into(),
+    })?;
+    let bytes = bytes.to_vec();
+    let value = serde_json::from_slice(&bytes)?;
+    let condition = match etag {
+        Some(etag) => WriteCondition::Matches(etag),
+        None => WriteCondition::Unchanged(content_digest(&bytes)),
+    };
+    Ok((Some(value), condition))
+}
+
+/// Writes a JSON document only if `condition` still holds.
+///
+/// Uses the backend's native `If-Match` / `If-None-Match` support when
+/// available and otherwise checks the precondition just before writing, so
+/// concurrent writers get a `Write conflict` error instead of silently
+/// clobbering each other. Conditional writes to one path are serialized
+/// within the process, which makes the fallback check atomic here; writers
+/// in other processes are only excluded by native support.
+pub async fn write_json_conditional(
+    op: &Operator,
+    path: &str,
+    value: &serde_json::Value,
+    condition: &WriteCondition,
+) -> Result<()> {
+    let _guard = match condition {
+        WriteCondition::Any => None,
+        _ => Some(PathWriteGuard::acquire(op, path).await?),
+    };
+    let capability = op.info().full_capability();
+    let mut write = op.write_with(path, serde_json::to_vec_pretty(value)?);
+    match condition {
+        WriteCondition::Any => {}
+        WriteCondition::Absent => {
+            if capability.write_with_if_not_exists {
+                write = write.if_not_exists(true);
+            } else if op.exists(path).await? {
+                return Err(write_conflict(path));
+            }
+        }
+        WriteCondition::Matches(etag) => {
+            if capability.write_with_if_match {
+                write = write.if_match(etag);
+            } else if op.stat(path).await?.etag() != Some(etag.as_str()) {
+                return Err(write_conflict(path));
+            }
+        }
+        WriteCondition::Unchanged(digest) => {
+            let current = match op.read(path).await {
+                Ok(bytes) => Some(content_digest(&bytes.to_vec())),
+                Err(err) if err.kind() == opendal::ErrorKind::NotFound => None,
+                Err(err) => return Err(err.into()),
+            };
+            if current.as_ref() != Some(digest) {
+                return Err(write_conflict(path));
+            }
+        }
+    }
+    write.await.map_err(|err| match err.kind() {
+        opendal::ErrorKind::ConditionNotMatch => write_conflict(path),
+        _ => err.into(),
+    })?;
+    Ok(())
+}
+
+/// Returns true when `err`, or any error it wraps, is a [`WriteConflict`].
+pub fn is_write_conflict(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| cause.is::<WriteConflict>())
+}
+
+/// Number of times [`update_json`] re-reads a document after a conflict.
+pub const UPDATE_RETRIES: usize = 3;
+
+/// Read-modify-write of a JSON document that retries on concurrent changes.
+///
+/// `update` receives the current document (`None` when missing) and returns
+/// the document to write with a result for the caller; it may run several
+/// times, so it must not have side effects.
+pub async fn update_json<F, T>(op: &Operator, path: &str, update: F) -> Result<T>
+where
+    F: Fn(Option<serde_json::Value>) -> Result<(serde_json::Value, T)>,
+{
+    let mut attempt = 0;
+    loop {
+        let (current, condition) = read_json_versioned(op, path).await?;
+        let (next, result) = update(current)?;
+        match write_json_conditional(op, path, &next, &condition).await {
+            Err(err) if is_write_conflict(&err) && attempt < UPDATE_RETRIES => {
+                record_contention_at(path, ContentionEvent::CommitRetry);
+                attempt += 1;
+            }
+            Err(err) => {
+                if is_write_conflict(&err) {
+                    record_contention_at(path, ContentionEvent::RetriesExhausted);
+                }
+                return Err(err);
+            }
+            Ok(()) => return Ok(result),
+        }
+    }
 }
@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::Instrument;
+
+use super::slow_log;
+use super::throttle::space_of;
+
+/// Where a request spent its time in storage. Operator calls and Iceberg's
+/// own file IO are counted separately; throttle waits are part of
+/// `storage_ms` as well as `throttle_wait_ms`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct RequestTiming {
+    pub storage_calls: u64,
+    pub storage_ms: f64,
+    pub throttle_wait_ms: f64,
+    pub iceberg_scans: u64,
+    pub iceberg_scan_ms: f64,
+    pub iceberg_commits: u64,
+    pub iceberg_commit_ms: f64,
+}
+
+/// What a request touched, for the slow operation log.
+#[derive(Debug, Default)]
+pub(super) struct Observed {
+    pub timing: RequestTiming,
+    pub spaces: BTreeSet<String>,
+    /// Iceberg tables scanned or committed to, as `Form/table`.
+    pub tables: BTreeSet<String>,
+    /// Entries looked up by id.
+    pub entry_ids: BTreeSet<String>,
+}
+
+pub(super) struct RequestState {
+    pub id: String,
+    pub observed: Mutex<Observed>,
+}
+
+tokio::task_local! {
+    static REQUEST: Arc<RequestState>;
+}
+
+/// A fresh request id, for callers that did not supply one.
+pub fn new_request_id() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// Runs `fut` as request `request_id`.
+///
+/// Everything it does, including storage and Iceberg calls made on its
+/// behalf, is traced inside a `request` span carrying the id and
+/// `operation`, and [`current_request_id`] returns the id. Tasks `fut`
+/// spawns do not inherit it. When it finishes, the request is added to the
+/// slow log of every space it touched whose threshold it exceeded.
+pub async fn with_request_id<F: Future>(request_id: String, operation: &str, fut: F) -> F::Output {
+    let span = tracing::info_span!("request", request_id = %request_id, operation);
+    let state = Arc::new(RequestState {
+        id: request_id,
+        observed: Mutex::new(Observed::default()),
+    });
+    let started = Instant::now();
+    let output = REQUEST.scope(state.clone(), fut.instrument(span)).await;
+    slow_log::observe_request(operation, &state, started.elapsed());
+    output
+}
+
+/// The id of the request the current task runs for, if any.
+pub fn current_request_id() -> Option<String> {
+    REQUEST.try_with(|state| state.id.clone()).ok()
+}
+
+fn observe(update: impl FnOnce(&mut Observed)) {
+    let _ = REQUEST.try_with(|state| {
+        if let Ok(mut observed) = state.observed.lock() {
+            update(&mut observed);
+        }
+    });
+}
+
+fn millis(elapsed: Duration) -> f64 {
+    elapsed.as_secs_f64() * 1000.0
+}
+
+/// Counts an operator call on `path` against the current request.
+pub(super) fn record_storage_call(path: &str, elapsed: Duration) {
+    observe(|observed| {
+        observed.timing.storage_calls += 1;
+        observed.timing.storage_ms += millis(elapsed);
+        if let Some(space_id) = space_of(path) {
+            if !observed.spaces.contains(space_id) {
+                observed.spaces.insert(space_id.to_string());
+            }
+        }
+    });
+}
+
+/// Adds time spent in a follow-up call of an operator call already counted,
+/// such as reading the next chunk of an object.
+pub(super) fn record_storage_time(elapsed: Duration) {
+    observe(|observed| observed.timing.storage_ms += millis(elapsed));
+}
+
+pub(super) fn record_throttle_wait(elapsed: Duration) {
+    observe(|observed| observed.timing.throttle_wait_ms += millis(elapsed));
+}
+
+/// Counts a scan of Iceberg table `table` (`Form/table`).
+pub(crate) fn record_iceberg_scan(table: &str, elapsed: Duration) {
+    observe(|observed| {
+        observed.timing.iceberg_scans += 1;
+        observed.timing.iceberg_scan_ms += millis(elapsed);
+        observed.tables.insert(table.to_string());
+    });
+}
+
+/// Counts writing a data file to Iceberg table `table` and committing it.
+pub(crate) fn record_iceberg_commit(table: &str, elapsed: Duration) {
+    observe(|observed| {
+        observed.timing.iceberg_commits += 1;
+        observed.timing.iceberg_commit_ms += millis(elapsed);
+        observed.tables.insert(table.to_string());
+    });
+}
+
+/// Notes that the current request looked up entry `entry_id`.
+pub(crate) fn record_entry_lookup(entry_id: &str) {
+    observe(|observed| {
+        observed.entry_ids.insert(entry_id.to_string());
+    });
+}
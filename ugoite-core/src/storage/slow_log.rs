@@ -0,0 +1,201 @@
+use anyhow::{anyhow, Result};
+use opendal::Operator;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use super::request::RequestState;
+use super::throttle::space_of;
+
+const SLOW_LOG_FILE: &str = "slow_log.json";
+/// Key in settings.json holding a space's [`SlowLogSettings`].
+pub const SLOW_LOG_SETTINGS_KEY: &str = "slow_log";
+/// Records kept per space when `max_records` is unset.
+pub const DEFAULT_SLOW_LOG_RECORDS: usize = 500;
+
+/// `slow_log` in settings.json: latency thresholds above which requests
+/// touching the space are logged, e.g.
+/// `{"threshold_ms": 1000, "operations": {"query_index": 5000}}`.
+///
+/// `operations` sets thresholds by binding name; `threshold_ms` applies to
+/// every other operation. With neither, nothing is logged.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct SlowLogSettings {
+    pub threshold_ms: Option<f64>,
+    pub operations: BTreeMap<String, f64>,
+    /// Records kept in the log, oldest dropped first. Defaults to
+    /// [`DEFAULT_SLOW_LOG_RECORDS`].
+    pub max_records: Option<usize>,
+}
+
+impl SlowLogSettings {
+    /// Reads the thresholds from a space's settings; missing or malformed
+    /// settings log nothing.
+    pub fn from_settings(settings: &Value) -> Self {
+        settings
+            .get(SLOW_LOG_SETTINGS_KEY)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Threshold for `operation`, if it is logged at all.
+    pub fn threshold_ms(&self, operation: &str) -> Option<f64> {
+        self.operations
+            .get(operation)
+            .copied()
+            .or(self.threshold_ms)
+            .filter(|threshold| threshold.is_finite() && *threshold >= 0.0)
+    }
+
+    fn max_records(&self) -> usize {
+        self.max_records.unwrap_or(DEFAULT_SLOW_LOG_RECORDS).max(1)
+    }
+}
+
+fn settings_registry() -> &'static Mutex<HashMap<String, SlowLogSettings>> {
+    static SETTINGS: OnceLock<Mutex<HashMap<String, SlowLogSettings>>> = OnceLock::new();
+    SETTINGS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Applies `settings` to `space_id` for the rest of the process. The
+/// throttle layer loads them with the space's storage limits.
+pub fn set_space_slow_log(space_id: &str, settings: &SlowLogSettings) {
+    if let Ok(mut registry) = settings_registry().lock() {
+        registry.insert(space_id.to_string(), settings.clone());
+    }
+}
+
+fn space_slow_log(space_id: &str) -> Option<SlowLogSettings> {
+    settings_registry().lock().ok()?.get(space_id).cloned()
+}
+
+/// Records logged in this process and not yet flushed, by space id.
+fn pending() -> &'static Mutex<HashMap<String, Vec<Value>>> {
+    static PENDING: OnceLock<Mutex<HashMap<String, Vec<Value>>>> = OnceLock::new();
+    PENDING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Logs a finished request with every space it touched whose threshold for
+/// `operation` it exceeded. Logging never fails the request.
+pub(super) fn observe_request(operation: &str, state: &RequestState, elapsed: Duration) {
+    let duration_ms = elapsed.as_secs_f64() * 1000.0;
+    let Ok(observed) = state.observed.lock() else {
+        return;
+    };
+    for space_id in &observed.spaces {
+        let Some(settings) = space_slow_log(space_id) else {
+            continue;
+        };
+        let Some(threshold_ms) = settings.threshold_ms(operation) else {
+            continue;
+        };
+        if duration_ms < threshold_ms {
+            continue;
+        }
+        let timing = &observed.timing;
+        let other_ms =
+            (duration_ms - timing.storage_ms - timing.iceberg_scan_ms - timing.iceberg_commit_ms)
+                .max(0.0);
+        let mut breakdown = serde_json::to_value(timing).unwrap_or_default();
+        breakdown["other_ms"] = serde_json::json!(other_ms);
+        let record = serde_json::json!({
+            "request_id": state.id,
+            "operation": operation,
+            "recorded_at": crate::entry::now_ts(),
+            "duration_ms": duration_ms,
+            "threshold_ms": threshold_ms,
+            "timing": breakdown,
+            "params": {
+                "tables": observed.tables,
+                "entry_ids": observed.entry_ids,
+            },
+        });
+        if let Ok(mut pending) = pending().lock() {
+            let records = pending.entry(space_id.clone()).or_default();
+            records.push(record);
+            let excess = records.len().saturating_sub(settings.max_records());
+            records.drain(..excess);
+        }
+    }
+}
+
+fn slow_log_path(ws_path: &str) -> String {
+    format!("{}/{}", ws_path.trim_end_matches('/'), SLOW_LOG_FILE)
+}
+
+fn space_id_of(ws_path: &str) -> Result<&str> {
+    space_of(ws_path).ok_or_else(|| anyhow!("Not a space path: {}", ws_path))
+}
+
+fn parse_records(value: Option<Value>) -> Vec<Value> {
+    value
+        .and_then(|value| value.get("records").and_then(Value::as_array).cloned())
+        .unwrap_or_default()
+}
+
+/// Appends the slow requests this process logged for a space to its
+/// `slow_log.json`, keeping the newest `max_records`.
+///
+/// The host calls this periodically, like
+/// [`super::flush_contention_stats`]; records not yet flushed are lost if
+/// the process exits. Returns the number of records written.
+pub async fn flush_slow_log(op: &Operator, ws_path: &str) -> Result<usize> {
+    let space_id = space_id_of(ws_path)?;
+    let taken = pending()
+        .lock()
+        .map_err(|_| anyhow!("slow log lock poisoned"))?
+        .remove(space_id)
+        .unwrap_or_default();
+    if taken.is_empty() {
+        return Ok(0);
+    }
+    let max_records = space_slow_log(space_id).unwrap_or_default().max_records();
+    let result = super::update_json(op, &slow_log_path(ws_path), |current| {
+        let mut records = parse_records(current);
+        records.extend(taken.iter().cloned());
+        let excess = records.len().saturating_sub(max_records);
+        records.drain(..excess);
+        Ok((serde_json::json!({ "records": records }), taken.len()))
+    })
+    .await;
+    if result.is_err() {
+        // Keep the records for the next flush.
+        if let Ok(mut pending) = pending().lock() {
+            let records = pending.entry(space_id.to_string()).or_default();
+            records.splice(0..0, taken);
+        }
+    }
+    result
+}
+
+/// Slow requests logged for a space, slowest first, including records this
+/// process has not flushed yet. `operation` keeps only that binding's.
+pub async fn list_slow_operations(
+    op: &Operator,
+    ws_path: &str,
+    operation: Option<&str>,
+    limit: Option<usize>,
+) -> Result<Vec<Value>> {
+    let space_id = space_id_of(ws_path)?;
+    let (stored, _) = super::read_json_versioned(op, &slow_log_path(ws_path)).await?;
+    let mut records = parse_records(stored);
+    if let Some(unflushed) = pending()
+        .lock()
+        .map_err(|_| anyhow!("slow log lock poisoned"))?
+        .get(space_id)
+    {
+        records.extend(unflushed.iter().cloned());
+    }
+    if let Some(operation) = operation {
+        records.retain(|record| record["operation"] == operation);
+    }
+    let duration = |record: &Value| record["duration_ms"].as_f64().unwrap_or_default();
+    records.sort_by(|a, b| duration(b).total_cmp(&duration(a)));
+    if let Some(limit) = limit {
+        records.truncate(limit);
+    }
+    Ok(records)
+}
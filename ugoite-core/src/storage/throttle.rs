@@ -0,0 +1,450 @@
+use opendal::raw::oio::{self, Read as _};
+use opendal::raw::{
+    Access, Layer, LayeredAccess, OpCopy, OpCreateDir, OpDelete, OpList, OpRead, OpRename, OpStat,
+    OpWrite, RpCopy, RpCreateDir, RpDelete, RpList, RpRead, RpRename, RpStat, RpWrite,
+};
+use opendal::{Buffer, Metadata, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use super::contention::{record_contention, ContentionEvent};
+use super::request::{record_storage_call, record_storage_time, record_throttle_wait};
+use super::slow_log::{set_space_slow_log, SlowLogSettings};
+
+/// Key in settings.json holding a space's [`StorageThrottle`].
+pub const THROTTLE_SETTINGS_KEY: &str = "storage_throttle";
+
+/// `storage_throttle` in settings.json: limits on the storage operations
+/// made for one space, e.g. `{"ops_per_second": 50, "max_in_flight": 8}`.
+///
+/// Every operation on a path under `spaces/{id}/` takes a token, so a bulk
+/// job in one space is slowed before the backend starts refusing requests,
+/// and cannot starve other spaces of their own budget. Unset limits are
+/// unlimited.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(default)]
+pub struct StorageThrottle {
+    /// Sustained operations started per second.
+    pub ops_per_second: Option<f64>,
+    /// Operations that may start at once after an idle spell. Defaults to
+    /// one second's worth of `ops_per_second`.
+    pub burst: Option<f64>,
+    /// Requests running at once.
+    pub max_in_flight: Option<usize>,
+}
+
+impl StorageThrottle {
+    /// Reads the throttle from a space's settings; missing or malformed
+    /// settings leave the space unlimited.
+    pub fn from_settings(settings: &serde_json::Value) -> Self {
+        settings
+            .get(THROTTLE_SETTINGS_KEY)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    fn limiter(&self, space_id: &str) -> Option<Arc<SpaceLimiter>> {
+        let bucket = self
+            .ops_per_second
+            .filter(|rate| rate.is_finite() && *rate > 0.0)
+            .map(|rate| {
+                let capacity = self
+                    .burst
+                    .filter(|burst| burst.is_finite() && *burst >= 1.0)
+                    .unwrap_or_else(|| rate.max(1.0));
+                Mutex::new(TokenBucket {
+                    rate,
+                    capacity,
+                    tokens: capacity,
+                    refilled_at: Instant::now(),
+                })
+            });
+        let in_flight = self
+            .max_in_flight
+            .filter(|max| *max > 0)
+            .map(|max| Arc::new(Semaphore::new(max)));
+        if bucket.is_none() && in_flight.is_none() {
+            return None;
+        }
+        Some(Arc::new(SpaceLimiter {
+            space_id: space_id.to_string(),
+            bucket,
+            in_flight,
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    /// Takes a token and returns how long to wait for it. Tokens may go
+    /// negative, so callers are served in the order they reserved.
+    fn reserve(&mut self) -> Duration {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.refilled_at = now;
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+}
+
+#[derive(Debug)]
+struct SpaceLimiter {
+    space_id: String,
+    bucket: Option<Mutex<TokenBucket>>,
+    in_flight: Option<Arc<Semaphore>>,
+}
+
+impl SpaceLimiter {
+    /// Waits for a token from the rate limit.
+    async fn wait_turn(&self) {
+        let wait = self
+            .bucket
+            .as_ref()
+            .and_then(|bucket| bucket.lock().ok().map(|mut bucket| bucket.reserve()))
+            .unwrap_or_default();
+        if !wait.is_zero() {
+            record_contention(&self.space_id, ContentionEvent::LockWait(wait));
+            record_throttle_wait(wait);
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Waits for a free in-flight slot.
+    async fn permit(&self) -> Option<OwnedSemaphorePermit> {
+        let semaphore = self.in_flight.clone()?;
+        if let Ok(permit) = semaphore.clone().try_acquire_owned() {
+            return Some(permit);
+        }
+        let started = Instant::now();
+        let permit = semaphore.acquire_owned().await.ok();
+        record_contention(&self.space_id, ContentionEvent::LockWait(started.elapsed()));
+        record_throttle_wait(started.elapsed());
+        permit
+    }
+}
+
+/// Limiters by space id; `None` marks a space loaded without limits.
+type Registry = HashMap<String, Option<Arc<SpaceLimiter>>>;
+
+fn registry() -> &'static Mutex<Registry> {
+    static LIMITERS: OnceLock<Mutex<Registry>> = OnceLock::new();
+    LIMITERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn cached_limiter(space_id: &str) -> Option<Option<Arc<SpaceLimiter>>> {
+    registry().lock().ok()?.get(space_id).cloned()
+}
+
+/// Applies `throttle` to `space_id` for every throttled operator in the
+/// process, replacing its current limits.
+pub fn set_space_throttle(space_id: &str, throttle: &StorageThrottle) {
+    if let Ok(mut limiters) = registry().lock() {
+        limiters.insert(space_id.to_string(), throttle.limiter(space_id));
+    }
+}
+
+/// Drops the limits loaded for `space_id`, so the next operation on the
+/// space reads them from its settings again.
+pub fn forget_space_throttle(space_id: &str) {
+    if let Ok(mut limiters) = registry().lock() {
+        limiters.remove(space_id);
+    }
+}
+
+pub(super) fn space_of(path: &str) -> Option<&str> {
+    let rest = path.trim_start_matches('/').strip_prefix("spaces/")?;
+    let space_id = rest.split('/').next()?;
+    (!space_id.is_empty()).then_some(space_id)
+}
+
+/// Throttles storage operations per space with the limits in each space's
+/// `storage_throttle` setting, read the first time the process touches the
+/// space. Paths outside `spaces/` are never throttled.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SpaceThrottleLayer;
+
+impl<A: Access> Layer<A> for SpaceThrottleLayer {
+    type LayeredAccess = SpaceThrottleAccessor<A>;
+
+    fn layer(&self, inner: A) -> Self::LayeredAccess {
+        SpaceThrottleAccessor { inner }
+    }
+}
+
+#[derive(Debug)]
+pub struct SpaceThrottleAccessor<A: Access> {
+    inner: A,
+}
+
+impl<A: Access> SpaceThrottleAccessor<A> {
+    /// Reads the space's settings straight from the inner backend, so
+    /// loading limits never waits on them.
+    async fn load_settings(&self, space_id: &str) -> serde_json::Value {
+        let path = format!("spaces/{}/settings.json", space_id);
+        let Ok((_, mut reader)) = self.inner.read(&path, OpRead::new()).await else {
+            return serde_json::Value::Null;
+        };
+        let Ok(buffer) = reader.read_all().await else {
+            return serde_json::Value::Null;
+        };
+        serde_json::from_slice(&buffer.to_vec()).unwrap_or_default()
+    }
+
+    async fn limiter(&self, path: &str) -> Option<Arc<SpaceLimiter>> {
+        let space_id = space_of(path)?;
+        match cached_limiter(space_id) {
+            Some(limiter) => limiter,
+            None => {
+                let settings = self.load_settings(space_id).await;
+                set_space_throttle(space_id, &StorageThrottle::from_settings(&settings));
+                set_space_slow_log(space_id, &SlowLogSettings::from_settings(&settings));
+                cached_limiter(space_id).flatten()
+            }
+        }
+    }
+}
+
+/// Waits for a token, then for a slot held while the request runs.
+async fn start(limiter: &Option<Arc<SpaceLimiter>>) -> Option<OwnedSemaphorePermit> {
+    let limiter = limiter.as_ref()?;
+    limiter.wait_turn().await;
+    limiter.permit().await
+}
+
+/// Waits for a slot for a follow-up request of an operation already started.
+async fn slot(limiter: &Option<Arc<SpaceLimiter>>) -> Option<OwnedSemaphorePermit> {
+    limiter.as_ref()?.permit().await
+}
+
+/// Runs an operator call on `path`, throttle waits included, and counts it
+/// against the current request.
+async fn timed<T>(path: &str, call: impl std::future::Future<Output = T>) -> T {
+    let started = Instant::now();
+    let result = call.await;
+    record_storage_call(path, started.elapsed());
+    result
+}
+
+/// Like [`timed`], for a follow-up call of one already counted.
+async fn timed_follow_up<T>(call: impl std::future::Future<Output = T>) -> T {
+    let started = Instant::now();
+    let result = call.await;
+    record_storage_time(started.elapsed());
+    result
+}
+
+impl<A: Access> LayeredAccess for SpaceThrottleAccessor<A> {
+    type Inner = A;
+    type Reader = Throttled<A::Reader>;
+    type Writer = Throttled<A::Writer>;
+    type Lister = Throttled<A::Lister>;
+    type Deleter = ThrottledDeleter<A::Deleter>;
+
+    fn inner(&self) -> &Self::Inner {
+        &self.inner
+    }
+
+    async fn create_dir(&self, path: &str, args: OpCreateDir) -> Result<RpCreateDir> {
+        timed(path, async {
+            let _permit = start(&self.limiter(path).await).await;
+            self.inner.create_dir(path, args).await
+        })
+        .await
+    }
+
+    async fn read(&self, path: &str, args: OpRead) -> Result<(RpRead, Self::Reader)> {
+        timed(path, async {
+            let limiter = self.limiter(path).await;
+            let _permit = start(&limiter).await;
+            let (rp, reader) = self.inner.read(path, args).await?;
+            Ok((rp, Throttled::new(reader, limiter)))
+        })
+        .await
+    }
+
+    async fn write(&self, path: &str, args: OpWrite) -> Result<(RpWrite, Self::Writer)> {
+        timed(path, async {
+            let limiter = self.limiter(path).await;
+            let _permit = start(&limiter).await;
+            let (rp, writer) = self.inner.write(path, args).await?;
+            Ok((rp, Throttled::new(writer, limiter)))
+        })
+        .await
+    }
+
+    async fn copy(&self, from: &str, to: &str, args: OpCopy) -> Result<RpCopy> {
+        timed(from, async {
+            let _permit = start(&self.limiter(from).await).await;
+            self.inner.copy(from, to, args).await
+        })
+        .await
+    }
+
+    async fn rename(&self, from: &str, to: &str, args: OpRename) -> Result<RpRename> {
+        timed(from, async {
+            let _permit = start(&self.limiter(from).await).await;
+            self.inner.rename(from, to, args).await
+        })
+        .await
+    }
+
+    async fn stat(&self, path: &str, args: OpStat) -> Result<RpStat> {
+        timed(path, async {
+            let _permit = start(&self.limiter(path).await).await;
+            self.inner.stat(path, args).await
+        })
+        .await
+    }
+
+    async fn delete(&self) -> Result<(RpDelete, Self::Deleter)> {
+        let (rp, deleter) = self.inner.delete().await?;
+        Ok((
+            rp,
+            ThrottledDeleter {
+                inner: deleter,
+                queued: Vec::new(),
+            },
+        ))
+    }
+
+    async fn list(&self, path: &str, args: OpList) -> Result<(RpList, Self::Lister)> {
+        timed(path, async {
+            let limiter = self.limiter(path).await;
+            let _permit = start(&limiter).await;
+            let (rp, lister) = self.inner.list(path, args).await?;
+            Ok((rp, Throttled::new(lister, limiter)))
+        })
+        .await
+    }
+}
+
+/// Takes an in-flight slot for each call on a reader, writer or lister.
+/// Slots are never held between calls, so code that keeps a lister open
+/// while it reads other objects cannot starve itself.
+pub struct Throttled<R> {
+    inner: R,
+    limiter: Option<Arc<SpaceLimiter>>,
+}
+
+impl<R> Throttled<R> {
+    fn new(inner: R, limiter: Option<Arc<SpaceLimiter>>) -> Self {
+        Self { inner, limiter }
+    }
+}
+
+impl<R: oio::Read> oio::Read for Throttled<R> {
+    async fn read(&mut self) -> Result<Buffer> {
+        timed_follow_up(async {
+            let _permit = slot(&self.limiter).await;
+            self.inner.read().await
+        })
+        .await
+    }
+}
+
+impl<R: oio::Write> oio::Write for Throttled<R> {
+    async fn write(&mut self, bs: Buffer) -> Result<()> {
+        timed_follow_up(async {
+            let _permit = slot(&self.limiter).await;
+            self.inner.write(bs).await
+        })
+        .await
+    }
+
+    async fn close(&mut self) -> Result<Metadata> {
+        timed_follow_up(async {
+            let _permit = slot(&self.limiter).await;
+            self.inner.close().await
+        })
+        .await
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        self.inner.abort().await
+    }
+}
+
+impl<R: oio::List> oio::List for Throttled<R> {
+    async fn next(&mut self) -> Result<Option<oio::Entry>> {
+        timed_follow_up(async {
+            let _permit = slot(&self.limiter).await;
+            self.inner.next().await
+        })
+        .await
+    }
+}
+
+/// Deletes are queued without I/O and sent on `flush`, so tokens are taken
+/// there: one per queued path, from the limits already loaded for its space.
+pub struct ThrottledDeleter<D> {
+    inner: D,
+    queued: Vec<String>,
+}
+
+impl<D: oio::Delete> oio::Delete for ThrottledDeleter<D> {
+    fn delete(&mut self, path: &str, args: OpDelete) -> Result<()> {
+        self.inner.delete(path, args)?;
+        if let Some(space_id) = space_of(path) {
+            self.queued.push(space_id.to_string());
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<usize> {
+        // One slot per space covers the batch; spaces are taken in order so
+        // concurrent flushes cannot wait on each other.
+        let mut limiters = BTreeMap::new();
+        for space_id in std::mem::take(&mut self.queued) {
+            if let Some(Some(limiter)) = cached_limiter(&space_id) {
+                limiter.wait_turn().await;
+                limiters.insert(space_id, limiter);
+            }
+        }
+        let mut permits = Vec::new();
+        for limiter in limiters.values() {
+            permits.push(limiter.permit().await);
+        }
+        timed_follow_up(self.inner.flush()).await
+    }
+}
+
+/// Takes a token from `ws_path`'s rate limit for storage I/O that bypasses
+/// the operator, such as Iceberg's own file IO. No in-flight slot is held,
+/// since the caller keeps using the operator meanwhile.
+pub async fn wait_space_turn(op: &opendal::Operator, ws_path: &str) {
+    let Some(space_id) = space_of(ws_path) else {
+        return;
+    };
+    let limiter = match cached_limiter(space_id) {
+        Some(limiter) => limiter,
+        None => {
+            let settings = crate::space::read_space_settings(op, ws_path)
+                .await
+                .unwrap_or_default();
+            // A throttled operator has loaded the limits while reading.
+            if cached_limiter(space_id).is_none() {
+                set_space_throttle(space_id, &StorageThrottle::from_settings(&settings));
+                set_space_slow_log(space_id, &SlowLogSettings::from_settings(&settings));
+            }
+            cached_limiter(space_id).flatten()
+        }
+    };
+    if let Some(limiter) = limiter {
+        limiter.wait_turn().await;
+    }
+}
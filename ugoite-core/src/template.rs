@@ -0,0 +1,338 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Months, NaiveDate, SecondsFormat, Utc};
+use opendal::Operator;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::OnceLock;
+
+use crate::entry::{self, EntryMeta};
+use crate::entry_id;
+use crate::integrity::IntegrityProvider;
+use crate::storage;
+use crate::timezone::SpaceTimeZone;
+
+const TEMPLATES_FILE: &str = "templates.json";
+/// Placeholders filled from the clock rather than from variables.
+const BUILTIN_VARIABLES: [&str; 2] = ["today", "now"];
+
+/// A stored entry template: markdown (frontmatter included) with
+/// `{{placeholders}}` filled in by [`create_entry_from_template`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EntryTemplate {
+    pub id: String,
+    pub content: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Variables the caller must supply, i.e. placeholders other than
+    /// `today` and `now`.
+    pub variables: Vec<String>,
+    pub created_at: f64,
+    pub updated_at: f64,
+}
+
+/// `templates.json`: template id -> template.
+type TemplatesDoc = BTreeMap<String, EntryTemplate>;
+
+fn templates_path(ws_path: &str) -> String {
+    format!("{}/{}", ws_path.trim_end_matches('/'), TEMPLATES_FILE)
+}
+
+fn parse_doc(value: Option<Value>) -> Result<TemplatesDoc> {
+    Ok(match value {
+        Some(value) => serde_json::from_value(value)?,
+        None => TemplatesDoc::new(),
+    })
+}
+
+/// `{{name}}` or `{{name+7d}}` / `{{name-1m}}`: an offset in days, weeks,
+/// months or years.
+fn placeholder_regex() -> &'static Regex {
+    static PLACEHOLDER_REGEX: OnceLock<Regex> = OnceLock::new();
+    PLACEHOLDER_REGEX.get_or_init(|| {
+        Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*(?:([+-])\s*(\d+)\s*([dwmy]))?\s*\}\}")
+            .expect("template placeholder regex must compile")
+    })
+}
+
+/// Variables `content` needs from the caller, sorted.
+pub fn template_variables(content: &str) -> Vec<String> {
+    placeholder_regex()
+        .captures_iter(content)
+        .map(|caps| caps[1].to_string())
+        .filter(|name| !BUILTIN_VARIABLES.contains(&name.as_str()))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+enum Offset {
+    Days(i64),
+    Months(i64),
+}
+
+fn parse_offset(sign: &str, amount: &str, unit: &str) -> Result<Offset> {
+    let too_large = || anyhow!("Template offset too large: {}{}", amount, unit);
+    let value: i64 = amount.parse().map_err(|_| too_large())?;
+    let value = if sign == "-" { -value } else { value };
+    Ok(match unit {
+        "d" => Offset::Days(value),
+        "w" => Offset::Days(value.checked_mul(7).ok_or_else(too_large)?),
+        "m" => Offset::Months(value),
+        _ => Offset::Months(value.checked_mul(12).ok_or_else(too_large)?),
+    })
+}
+
+fn shift_date(date: NaiveDate, offset: &Offset) -> Option<NaiveDate> {
+    match *offset {
+        Offset::Days(days) => date.checked_add_signed(Duration::try_days(days)?),
+        Offset::Months(months) if months >= 0 => {
+            date.checked_add_months(Months::new(u32::try_from(months).ok()?))
+        }
+        Offset::Months(months) => {
+            date.checked_sub_months(Months::new(u32::try_from(-months).ok()?))
+        }
+    }
+}
+
+fn shift_instant(instant: DateTime<Utc>, offset: &Offset) -> Option<DateTime<Utc>> {
+    match *offset {
+        Offset::Days(days) => instant.checked_add_signed(Duration::try_days(days)?),
+        Offset::Months(months) if months >= 0 => {
+            instant.checked_add_months(Months::new(u32::try_from(months).ok()?))
+        }
+        Offset::Months(months) => {
+            instant.checked_sub_months(Months::new(u32::try_from(-months).ok()?))
+        }
+    }
+}
+
+fn variable_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Rejects a variable value that could add structure to the entry: a line
+/// break (new sections, frontmatter keys) or, for a placeholder that starts a
+/// line, a leading heading (`#`) or frontmatter/rule (`---`) marker.
+fn check_variable_value(name: &str, value: &str, at_line_start: bool) -> Result<()> {
+    if value.contains(['\n', '\r']) {
+        return Err(anyhow!(
+            "Template variable {} must not contain line breaks",
+            name
+        ));
+    }
+    let lead = value.trim_start();
+    if at_line_start && (lead.starts_with('#') || lead.starts_with("---")) {
+        return Err(anyhow!(
+            "Template variable {} must not start a line with a markdown marker: {}",
+            name,
+            value
+        ));
+    }
+    Ok(())
+}
+
+/// Fills the placeholders of `content`.
+///
+/// `{{today}}` is the date in `tz` at `now` (`YYYY-MM-DD`) and `{{now}}` the
+/// timestamp (RFC3339 in `tz`); other names come from `variables`. An offset
+/// such as `{{today+7d}}` or `{{due-1w}}` applies to `today`, `now` or any
+/// variable holding a date or timestamp. Every missing variable is reported
+/// at once. Variable values are inline text: line breaks, and `#` or `---`
+/// at the start of a line, are rejected so they cannot add sections or
+/// frontmatter.
+pub fn render_template(
+    content: &str,
+    variables: &Map<String, Value>,
+    tz: &SpaceTimeZone,
+    now: DateTime<Utc>,
+) -> Result<String> {
+    let missing: Vec<String> = template_variables(content)
+        .into_iter()
+        .filter(|name| !variables.contains_key(name))
+        .collect();
+    if !missing.is_empty() {
+        return Err(anyhow!(
+            "Missing template variables: {}",
+            missing.join(", ")
+        ));
+    }
+
+    let mut rendered = String::with_capacity(content.len());
+    let mut last = 0;
+    for caps in placeholder_regex().captures_iter(content) {
+        let whole = caps.get(0).expect("capture 0 is the match");
+        rendered.push_str(&content[last..whole.start()]);
+        last = whole.end();
+        let name = &caps[1];
+        let offset = match (caps.get(2), caps.get(3), caps.get(4)) {
+            (Some(sign), Some(amount), Some(unit)) => {
+                Some(parse_offset(sign.as_str(), amount.as_str(), unit.as_str())?)
+            }
+            _ => None,
+        };
+        let value = match name {
+            "today" => tz.localize(&now).date_naive().to_string(),
+            "now" => tz
+                .localize(&now)
+                .to_rfc3339_opts(SecondsFormat::Secs, false),
+            _ => {
+                let value = variable_text(&variables[name]);
+                let line = &content[..whole.start()];
+                let line = &line[line.rfind('\n').map_or(0, |i| i + 1)..];
+                check_variable_value(name, &value, line.trim().is_empty())?;
+                value
+            }
+        };
+        let Some(offset) = offset else {
+            rendered.push_str(&value);
+            continue;
+        };
+        let out_of_range = || anyhow!("Template date out of range: {}", whole.as_str());
+        if let Ok(date) = NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d") {
+            let shifted = shift_date(date, &offset).ok_or_else(out_of_range)?;
+            rendered.push_str(&shifted.to_string());
+        } else if let Some(instant) = tz.parse_timestamp(&value) {
+            let shifted =
+                shift_instant(instant.with_timezone(&Utc), &offset).ok_or_else(out_of_range)?;
+            rendered.push_str(
+                &tz.localize(&shifted)
+                    .to_rfc3339_opts(SecondsFormat::Secs, false),
+            );
+        } else {
+            return Err(anyhow!(
+                "Template variable {} is not a date: {}",
+                name,
+                value
+            ));
+        }
+    }
+    rendered.push_str(&content[last..]);
+    Ok(rendered)
+}
+
+fn validate_template_id(template_id: &str) -> Result<()> {
+    if template_id.trim().is_empty() || template_id.contains('/') {
+        return Err(anyhow!("Invalid template id: {}", template_id));
+    }
+    Ok(())
+}
+
+/// Creates or replaces template `template_id`.
+pub async fn save_template(
+    op: &Operator,
+    ws_path: &str,
+    template_id: &str,
+    content: &str,
+    description: Option<&str>,
+) -> Result<EntryTemplate> {
+    validate_template_id(template_id)?;
+    let now = entry::now_ts();
+    storage::update_json(op, &templates_path(ws_path), |current| {
+        let mut doc = parse_doc(current)?;
+        let created_at = doc.get(template_id).map_or(now, |t| t.created_at);
+        let template = EntryTemplate {
+            id: template_id.to_string(),
+            content: content.to_string(),
+            description: description.map(str::to_string),
+            variables: template_variables(content),
+            created_at,
+            updated_at: now,
+        };
+        doc.insert(template_id.to_string(), template.clone());
+        Ok((serde_json::to_value(&doc)?, template))
+    })
+    .await
+}
+
+pub async fn list_templates(op: &Operator, ws_path: &str) -> Result<Vec<EntryTemplate>> {
+    let (value, _) = storage::read_json_versioned(op, &templates_path(ws_path)).await?;
+    Ok(parse_doc(value)?.into_values().collect())
+}
+
+pub async fn get_template(
+    op: &Operator,
+    ws_path: &str,
+    template_id: &str,
+) -> Result<EntryTemplate> {
+    let (value, _) = storage::read_json_versioned(op, &templates_path(ws_path)).await?;
+    parse_doc(value)?
+        .remove(template_id)
+        .ok_or_else(|| anyhow!("Template not found: {}", template_id))
+}
+
+/// Removes template `template_id`, returning whether it existed.
+pub async fn delete_template(op: &Operator, ws_path: &str, template_id: &str) -> Result<bool> {
+    storage::update_json(op, &templates_path(ws_path), |current| {
+        let mut doc = parse_doc(current)?;
+        let removed = doc.remove(template_id).is_some();
+        Ok((serde_json::to_value(&doc)?, removed))
+    })
+    .await
+}
+
+/// Renders template `template_id` with `variables` (a JSON object) in the
+/// space's timezone and creates the entry, validated against its form like
+/// any other. Without `entry_id` one is suggested from the rendered title
+/// and the space's id pattern.
+pub async fn create_entry_from_template<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    template_id: &str,
+    variables: &Value,
+    entry_id: Option<&str>,
+    author: &str,
+    integrity: &I,
+) -> Result<EntryMeta> {
+    let variables = match variables {
+        Value::Object(map) => map.clone(),
+        Value::Null => Map::new(),
+        _ => return Err(anyhow!("Template variables must be an object")),
+    };
+    instantiate_template(
+        op,
+        ws_path,
+        template_id,
+        &variables,
+        entry_id,
+        author,
+        integrity,
+        Utc::now(),
+    )
+    .await
+}
+
+/// [`create_entry_from_template`] with the clock placeholders read at `now`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn instantiate_template<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    template_id: &str,
+    variables: &Map<String, Value>,
+    entry_id: Option<&str>,
+    author: &str,
+    integrity: &I,
+    now: DateTime<Utc>,
+) -> Result<EntryMeta> {
+    let template = get_template(op, ws_path, template_id).await?;
+    let tz = SpaceTimeZone::for_space(op, ws_path).await?;
+    let content = render_template(&template.content, variables, &tz, now)?;
+    let entry_id = match entry_id {
+        Some(entry_id) => entry_id.to_string(),
+        None => {
+            let (frontmatter, _) = entry::extract_frontmatter(&content)?;
+            let form_name = frontmatter
+                .get("form")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            let title = entry::extract_title(&content, template_id);
+            entry_id::suggest_entry_id(op, ws_path, &title, form_name).await?
+        }
+    };
+    entry::create_entry(op, ws_path, &entry_id, &content, author, integrity).await
+}
@@ -0,0 +1,122 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, Timelike, Utc};
+use opendal::Operator;
+
+use crate::space;
+
+pub const DEFAULT_TIMEZONE: &str = "UTC";
+
+const LOCAL_TIMESTAMP_FORMATS: [&str; 4] = [
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+];
+
+/// `timezone` in settings.json: the IANA zone (default `UTC`) that date-only
+/// and local timestamps are read in when fields are cast, and that cast
+/// timestamps are rendered in.
+#[derive(Debug, Clone)]
+pub struct SpaceTimeZone {
+    name: String,
+    zone: jiff::tz::TimeZone,
+}
+
+impl Default for SpaceTimeZone {
+    fn default() -> Self {
+        Self {
+            name: DEFAULT_TIMEZONE.to_string(),
+            zone: jiff::tz::TimeZone::UTC,
+        }
+    }
+}
+
+impl SpaceTimeZone {
+    pub fn new(name: &str) -> Result<Self> {
+        let name = name.trim();
+        if name.eq_ignore_ascii_case(DEFAULT_TIMEZONE) {
+            return Ok(Self::default());
+        }
+        let zone = jiff::tz::TimeZone::get(name)
+            .map_err(|e| anyhow!("Unknown timezone '{}': {}", name, e))?;
+        Ok(Self {
+            name: name.to_string(),
+            zone,
+        })
+    }
+
+    pub async fn for_space(op: &Operator, ws_path: &str) -> Result<Self> {
+        let settings = space::read_space_settings(op, ws_path).await?;
+        match settings.get("timezone").and_then(|value| value.as_str()) {
+            Some(name) => Self::new(name),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The zone's offset at `instant`.
+    fn offset_at(&self, instant: &DateTime<Utc>) -> Option<FixedOffset> {
+        let timestamp = jiff::Timestamp::from_second(instant.timestamp()).ok()?;
+        FixedOffset::east_opt(self.zone.to_offset(timestamp).seconds())
+    }
+
+    /// Pins a wall-clock time to the zone. Times skipped by a DST change move
+    /// forward and repeated times take the earlier offset.
+    fn resolve_local(&self, local: &NaiveDateTime) -> Option<DateTime<FixedOffset>> {
+        let civil = jiff::civil::DateTime::new(
+            i16::try_from(local.year()).ok()?,
+            local.month() as i8,
+            local.day() as i8,
+            local.hour() as i8,
+            local.minute() as i8,
+            local.second() as i8,
+            local.nanosecond().min(999_999_999) as i32,
+        )
+        .ok()?;
+        let zoned = self.zone.to_ambiguous_zoned(civil).compatible().ok()?;
+        let offset = FixedOffset::east_opt(zoned.offset().seconds())?;
+        let instant = DateTime::from_timestamp(zoned.timestamp().as_second(), local.nanosecond())?;
+        Some(instant.with_timezone(&offset))
+    }
+
+    /// Renders `instant` with the zone's offset at that moment.
+    pub fn localize(&self, instant: &DateTime<Utc>) -> DateTime<FixedOffset> {
+        let offset = self
+            .offset_at(instant)
+            .unwrap_or_else(|| FixedOffset::east_opt(0).expect("zero offset is valid"));
+        instant.with_timezone(&offset)
+    }
+
+    /// Parses an RFC3339 timestamp, or a local `YYYY-MM-DD[ T]HH:MM[:SS]` or
+    /// date-only value read in the zone (date-only means local midnight).
+    pub fn parse_timestamp(&self, value: &str) -> Option<DateTime<FixedOffset>> {
+        let value = value.trim();
+        if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+            return Some(dt);
+        }
+        let local = LOCAL_TIMESTAMP_FORMATS
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+            .or_else(|| {
+                NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                    .ok()
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+            })?;
+        self.resolve_local(&local)
+    }
+
+    /// Parses a `YYYY-MM-DD` date, or takes the local date of a timestamp in
+    /// the zone, so an instant near midnight lands on the day it was in the
+    /// space rather than in UTC.
+    pub fn parse_date(&self, value: &str) -> Option<NaiveDate> {
+        let value = value.trim();
+        if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            return Some(date);
+        }
+        let dt = self.parse_timestamp(value)?;
+        Some(self.localize(&dt.with_timezone(&Utc)).date_naive())
+    }
+}
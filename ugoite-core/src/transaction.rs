@@ -0,0 +1,284 @@
+use anyhow::{anyhow, Result};
+use opendal::Operator;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::entry;
+use crate::iceberg_store;
+use crate::index;
+use crate::integrity::IntegrityProvider;
+use crate::rollup;
+
+/// One write in a [`Transaction`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum TransactionOp {
+    Create {
+        entry_id: String,
+        content: String,
+    },
+    Update {
+        entry_id: String,
+        content: String,
+        #[serde(default)]
+        parent_revision_id: Option<String>,
+        #[serde(default)]
+        message: Option<String>,
+    },
+    Delete {
+        entry_id: String,
+        #[serde(default)]
+        hard_delete: bool,
+    },
+}
+
+impl TransactionOp {
+    pub fn entry_id(&self) -> &str {
+        match self {
+            TransactionOp::Create { entry_id, .. }
+            | TransactionOp::Update { entry_id, .. }
+            | TransactionOp::Delete { entry_id, .. } => entry_id,
+        }
+    }
+}
+
+/// Entry writes, across any forms of one space, that land together or not
+/// at all.
+///
+/// Every operation is checked before any is applied: contents must validate
+/// against their form, created entries must not exist yet and updated or
+/// deleted ones must, counting the operations before them. They then run in
+/// order through [`entry::create_entry`], [`entry::update_entry`] and
+/// [`entry::delete_entry`]. If one still fails, the tables this transaction
+/// wrote are put back at the versions they had when the commit began, which
+/// also undoes link and backlink records and entry integrity records, as
+/// both are row columns. The rollup documents kept outside the tables are
+/// snapshotted before the first operation and restored, and the affected
+/// index shards are rebuilt. Operations carry their entry ids, so no id
+/// counter is reserved. Readers may see the earlier operations until then,
+/// and form hooks that already ran for them are not undone. When another
+/// writer committed to one of those tables in the meantime, nothing is put
+/// back and the error says so.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Transaction {
+    ops: Vec<TransactionOp>,
+}
+
+impl Transaction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_ops(ops: Vec<TransactionOp>) -> Self {
+        Self { ops }
+    }
+
+    pub fn create(&mut self, entry_id: &str, content: &str) -> &mut Self {
+        self.ops.push(TransactionOp::Create {
+            entry_id: entry_id.to_string(),
+            content: content.to_string(),
+        });
+        self
+    }
+
+    pub fn update(
+        &mut self,
+        entry_id: &str,
+        content: &str,
+        parent_revision_id: Option<&str>,
+    ) -> &mut Self {
+        self.ops.push(TransactionOp::Update {
+            entry_id: entry_id.to_string(),
+            content: content.to_string(),
+            parent_revision_id: parent_revision_id.map(str::to_string),
+            message: None,
+        });
+        self
+    }
+
+    pub fn delete(&mut self, entry_id: &str, hard_delete: bool) -> &mut Self {
+        self.ops.push(TransactionOp::Delete {
+            entry_id: entry_id.to_string(),
+            hard_delete,
+        });
+        self
+    }
+
+    /// Applies every operation as `author`. Returns one result per
+    /// operation: the created entry's metadata, the updated entry, or
+    /// `{id, deleted}`.
+    pub async fn commit<I: IntegrityProvider>(
+        &self,
+        op: &Operator,
+        ws_path: &str,
+        author: &str,
+        integrity: &I,
+    ) -> Result<Vec<Value>> {
+        if self.ops.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.validate(op, ws_path).await?;
+        iceberg_store::ensure_catalog_manifest(op, ws_path).await?;
+        let before = table_locations(op, ws_path).await?;
+        let computed = rollup::snapshot_computed(op, ws_path).await?;
+
+        let mut seen = before.clone();
+        let mut written = HashMap::new();
+        let mut results = Vec::with_capacity(self.ops.len());
+        for (position, step) in self.ops.iter().enumerate() {
+            let applied = apply(op, ws_path, step, author, integrity).await;
+            let after = table_locations(op, ws_path).await?;
+            for (key, location) in &after {
+                if seen.get(key) != Some(location) {
+                    written.insert(key.clone(), location.clone());
+                }
+            }
+            seen = after;
+            match applied {
+                Ok(result) => results.push(result),
+                Err(err) => {
+                    if let Err(rollback_err) =
+                        roll_back(op, ws_path, &before, &written, &computed).await
+                    {
+                        return Err(anyhow!(
+                            "Operation {} on {} failed: {}; rolling back also failed: {}",
+                            position,
+                            step.entry_id(),
+                            err,
+                            rollback_err
+                        ));
+                    }
+                    return Err(anyhow!(
+                        "Operation {} on {} failed, transaction rolled back: {}",
+                        position,
+                        step.entry_id(),
+                        err
+                    ));
+                }
+            }
+        }
+        Ok(results)
+    }
+
+    /// Checks every operation against the space as the ones before it
+    /// would leave it, without writing anything.
+    async fn validate(&self, op: &Operator, ws_path: &str) -> Result<()> {
+        let mut forms: HashMap<&str, Option<String>> = HashMap::new();
+        for (position, step) in self.ops.iter().enumerate() {
+            let entry_id = step.entry_id();
+            let existing = match forms.get(entry_id) {
+                Some(form_name) => form_name.clone(),
+                None => entry::exists(op, ws_path, entry_id)
+                    .await?
+                    .map(|existence| existence.form),
+            };
+            let checked = match (step, &existing) {
+                (TransactionOp::Create { .. }, Some(_)) => {
+                    Err(anyhow!("Entry already exists: {}", entry_id))
+                }
+                (TransactionOp::Create { content, .. }, None) => {
+                    entry::validate_entry_content(op, ws_path, content).await
+                }
+                (_, None) => Err(entry::entry_not_found(entry_id)),
+                (TransactionOp::Update { content, .. }, Some(form_name)) => {
+                    entry::validate_entry_content(op, ws_path, content)
+                        .await
+                        .and_then(|updated| {
+                            if updated == *form_name {
+                                Ok(updated)
+                            } else {
+                                Err(anyhow!(
+                                    "Form change is not supported by update; use change_entry_form"
+                                ))
+                            }
+                        })
+                }
+                (TransactionOp::Delete { .. }, Some(form_name)) => Ok(form_name.clone()),
+            };
+            match checked {
+                Ok(form_name) => {
+                    forms.insert(entry_id, Some(form_name));
+                }
+                Err(err) => {
+                    return Err(anyhow!(
+                        "Operation {} on {} is invalid, nothing was written: {}",
+                        position,
+                        entry_id,
+                        err
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn table_locations(op: &Operator, ws_path: &str) -> Result<HashMap<String, String>> {
+    iceberg_store::catalog_table_locations(op, ws_path)
+        .await?
+        .ok_or_else(|| anyhow!("Space has no catalog manifest: {}", ws_path))
+}
+
+async fn apply<I: IntegrityProvider>(
+    op: &Operator,
+    ws_path: &str,
+    step: &TransactionOp,
+    author: &str,
+    integrity: &I,
+) -> Result<Value> {
+    match step {
+        TransactionOp::Create { entry_id, content } => {
+            let meta =
+                entry::create_entry(op, ws_path, entry_id, content, author, integrity).await?;
+            Ok(serde_json::to_value(meta)?)
+        }
+        TransactionOp::Update {
+            entry_id,
+            content,
+            parent_revision_id,
+            message,
+        } => {
+            entry::update_entry(
+                op,
+                ws_path,
+                entry_id,
+                content,
+                parent_revision_id.as_deref(),
+                author,
+                None,
+                message.as_deref(),
+                integrity,
+            )
+            .await
+        }
+        TransactionOp::Delete {
+            entry_id,
+            hard_delete,
+        } => {
+            entry::delete_entry(op, ws_path, entry_id, *hard_delete).await?;
+            Ok(serde_json::json!({"id": entry_id, "deleted": true}))
+        }
+    }
+}
+
+/// Restores the tables in `written` to `before` and the rollup documents to
+/// `computed`, then rebuilds the index shards of the forms either changed.
+async fn roll_back(
+    op: &Operator,
+    ws_path: &str,
+    before: &HashMap<String, String>,
+    written: &HashMap<String, String>,
+    computed: &HashMap<String, Option<Value>>,
+) -> Result<()> {
+    let mut changed = iceberg_store::restore_table_locations(op, ws_path, before, written).await?;
+    for form_name in rollup::restore_computed(op, ws_path, computed).await? {
+        if !changed.contains(&form_name) {
+            changed.push(form_name);
+        }
+    }
+    for form_name in &changed {
+        index::reindex_form(op, ws_path, form_name).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,223 @@
+use anyhow::{anyhow, Result};
+use opendal::Operator;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+
+use crate::audit;
+use crate::clock;
+use crate::entry::{self, EntryRow};
+use crate::form;
+use crate::index;
+use crate::space;
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// `trash` in settings.json, e.g. `{"purge_after_days": 30}`. Without
+/// `purge_after_days` deleted entries are kept until purged by hand.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TrashSettings {
+    #[serde(default)]
+    pub purge_after_days: Option<f64>,
+}
+
+impl TrashSettings {
+    pub async fn for_space(op: &Operator, ws_path: &str) -> Result<Self> {
+        let settings = space::read_space_settings(op, ws_path).await?;
+        match settings.get("trash") {
+            Some(value) if value.is_object() => Ok(serde_json::from_value(value.clone())?),
+            _ => Ok(Self::default()),
+        }
+    }
+}
+
+/// Soft-deleted entries, most recently deleted first, as [`entry::list_entries`]
+/// lists live ones plus `deleted_at` and `purge_at`, when the space's
+/// `purge_after_days` will remove them (null when they are kept until purged
+/// by hand). [`entry::undelete_entry`] brings one back.
+pub async fn list_deleted_entries(op: &Operator, ws_path: &str) -> Result<Vec<Value>> {
+    let settings = TrashSettings::for_space(op, ws_path).await?;
+    let mut deleted: Vec<(String, EntryRow)> = entry::list_entry_rows(op, ws_path)
+        .await?
+        .into_iter()
+        .filter(|(_, row)| row.deleted)
+        .collect();
+    deleted.sort_by(|(_, a), (_, b)| {
+        b.deleted_at
+            .unwrap_or(0)
+            .cmp(&a.deleted_at.unwrap_or(0))
+            .then_with(|| a.entry_id.cmp(&b.entry_id))
+    });
+    Ok(deleted
+        .iter()
+        .map(|(form_name, row)| {
+            let mut summary = entry::entry_summary(form_name, row);
+            let purge_at = settings
+                .purge_after_days
+                .zip(row.deleted_at)
+                .map(|(days, deleted_at)| clock::from_micros(deleted_at) + days * SECONDS_PER_DAY);
+            summary["deleted_at"] = serde_json::json!(row.deleted_at.map(clock::from_micros));
+            summary["purge_at"] = serde_json::json!(purge_at);
+            summary
+        })
+        .collect())
+}
+
+/// Drops the reciprocal records other entries keep of the purged entries'
+/// links.
+async fn drop_reciprocal_links(
+    op: &Operator,
+    ws_path: &str,
+    purged: &[EntryRow],
+    purged_ids: &HashSet<String>,
+) -> Result<()> {
+    for row in purged {
+        for link in &row.links {
+            let other = if link.source == row.entry_id {
+                link.target.as_str()
+            } else {
+                link.source.as_str()
+            };
+            if purged_ids.contains(other) {
+                continue;
+            }
+            let Some(other_form) = entry::find_entry_form(op, ws_path, other).await? else {
+                continue;
+            };
+            let mut other_row = entry::read_entry_row(op, ws_path, &other_form, other).await?;
+            let before = other_row.links.len();
+            other_row.links.retain(|l| l.id != link.id);
+            if other_row.links.len() == before {
+                continue;
+            }
+            other_row.updated_at = clock::next_micros(ws_path, Some(other_row.updated_at));
+            entry::write_entry_row(op, ws_path, &other_form, other, &other_row).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Physically removes the deleted entries `select` picks, form by form, and
+/// returns `{form, entry_id, deleted_at}` for each. With `dry_run` nothing
+/// is changed.
+///
+/// Each affected form's tables are rewritten without the entries' rows and
+/// revisions, which drops the data files that held them, and its index
+/// shard is rebuilt.
+async fn purge_rows<F>(op: &Operator, ws_path: &str, select: F, dry_run: bool) -> Result<Vec<Value>>
+where
+    F: Fn(&EntryRow) -> bool,
+{
+    let mut purged = Vec::new();
+    for form_name in form::list_form_names(op, ws_path).await? {
+        let form_def = form::read_form_definition(op, ws_path, &form_name).await?;
+        let rows = entry::list_form_entry_rows(op, ws_path, &form_name, &form_def).await?;
+        let (mut expired, kept): (Vec<EntryRow>, Vec<EntryRow>) =
+            rows.into_iter().partition(|row| row.deleted && select(row));
+        if expired.is_empty() {
+            continue;
+        }
+        expired.sort_by(|a, b| a.entry_id.cmp(&b.entry_id));
+        for row in &expired {
+            purged.push(serde_json::json!({
+                "form": form_name,
+                "entry_id": row.entry_id,
+                "deleted_at": row.deleted_at.map(clock::from_micros),
+            }));
+        }
+        if dry_run {
+            continue;
+        }
+        let expired_ids: HashSet<String> = expired.iter().map(|row| row.entry_id.clone()).collect();
+        let revisions: Vec<_> = entry::list_form_revision_rows(op, ws_path, &form_name, &form_def)
+            .await?
+            .into_iter()
+            .filter(|rev| !expired_ids.contains(&rev.entry_id))
+            .collect();
+        entry::rewrite_form_tables(op, ws_path, &form_name, &form_def, &kept, &revisions).await?;
+        index::reindex_form(op, ws_path, &form_name).await?;
+        drop_reciprocal_links(op, ws_path, &expired, &expired_ids).await?;
+    }
+    Ok(purged)
+}
+
+/// Permanently removes soft-deleted entries whose `deleted_at` is older than
+/// the space's `purge_after_days`, as of `now`. With `dry_run` nothing is
+/// changed and the report lists what would be purged.
+///
+/// Purged entries lose their rows and every revision: each affected form's
+/// tables are rewritten without them and its index shard is rebuilt. Applied
+/// runs that purge anything are recorded in the audit log.
+pub async fn purge_trash(op: &Operator, ws_path: &str, now: f64, dry_run: bool) -> Result<Value> {
+    let settings = TrashSettings::for_space(op, ws_path).await?;
+    let purged = match settings.purge_after_days {
+        Some(after_days) => {
+            let cutoff = now - after_days * SECONDS_PER_DAY;
+            let expired = |row: &EntryRow| {
+                row.deleted_at
+                    .is_some_and(|at| clock::from_micros(at) <= cutoff)
+            };
+            purge_rows(op, ws_path, expired, dry_run).await?
+        }
+        None => Vec::new(),
+    };
+
+    let purged_count = purged.len();
+    let mut report = serde_json::json!({
+        "dry_run": dry_run,
+        "ran_at": now,
+        "purge_after_days": settings.purge_after_days,
+        "purged": purged,
+        "audit_event_id": Value::Null,
+    });
+    if !dry_run && purged_count > 0 {
+        let details = serde_json::json!({
+            "ran_at": now,
+            "purge_after_days": settings.purge_after_days,
+            "entries": report["purged"],
+        });
+        report["audit_event_id"] = record_purge(op, ws_path, details).await?;
+    }
+    Ok(report)
+}
+
+async fn record_purge(op: &Operator, ws_path: &str, details: Value) -> Result<Value> {
+    let event = audit::record_audit_event(op, ws_path, "trash.purged", "system", details).await?;
+    Ok(event["id"].clone())
+}
+
+/// Physically removes one deleted entry, soft or hard, with its revisions,
+/// without waiting for `purge_after_days`. Live entries must be deleted
+/// first.
+pub async fn purge_entry(op: &Operator, ws_path: &str, entry_id: &str) -> Result<Value> {
+    match entry::exists(op, ws_path, entry_id).await? {
+        Some(existence) if existence.deleted => {}
+        Some(_) => return Err(anyhow!("Entry {} is not deleted", entry_id)),
+        None => return Err(entry::entry_not_found(entry_id)),
+    }
+    let purged = purge_rows(op, ws_path, |row| row.entry_id == entry_id, false).await?;
+    let details = serde_json::json!({"ran_at": entry::now_ts(), "entries": purged});
+    let audit_event_id = record_purge(op, ws_path, details).await?;
+    Ok(serde_json::json!({"purged": purged, "audit_event_id": audit_event_id}))
+}
+
+/// Physically removes every hard-deleted entry with its revisions and data
+/// files, regardless of the trash window. With `dry_run` the report only
+/// lists them.
+pub async fn gc_deleted_entries(op: &Operator, ws_path: &str, dry_run: bool) -> Result<Value> {
+    let purged = purge_rows(op, ws_path, entry::is_hard_deleted, dry_run).await?;
+    let mut report = serde_json::json!({
+        "dry_run": dry_run,
+        "purged": purged,
+        "audit_event_id": Value::Null,
+    });
+    if !dry_run && !purged.is_empty() {
+        let details = serde_json::json!({
+            "ran_at": entry::now_ts(),
+            "hard_deleted": true,
+            "entries": report["purged"],
+        });
+        report["audit_event_id"] = record_purge(op, ws_path, details).await?;
+    }
+    Ok(report)
+}
@@ -0,0 +1,148 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use crate::entry;
+
+/// Key in an entry's metadata holding captured page metadata per field.
+pub const URL_METADATA_KEY: &str = "url_metadata";
+/// Default bound on one metadata fetch; see [`UrlMetadataFetcher::timeout`].
+pub const DEFAULT_FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Page metadata captured for the value of a `url` field.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct UrlMetadata {
+    pub url: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub favicon: Option<String>,
+    #[serde(default)]
+    pub fetched_at: f64,
+}
+
+/// Looks up page metadata for `url` fields declared with
+/// `"capture_metadata": true`.
+///
+/// The fetcher is host code registered with [`register_url_metadata_fetcher`],
+/// so network access stays with the host. It runs during entry writes on
+/// tokio's blocking pool, bounded by [`UrlMetadataFetcher::timeout`]; a
+/// failed or timed-out fetch leaves the field without metadata and never
+/// fails the write.
+pub trait UrlMetadataFetcher: Send + Sync {
+    fn fetch(&self, url: &str) -> std::result::Result<UrlMetadata, String>;
+
+    /// How long a write waits for [`fetch`](Self::fetch) before saving
+    /// without metadata. A fetch that outlives it keeps running on its
+    /// blocking thread but is ignored.
+    fn timeout(&self) -> Duration {
+        DEFAULT_FETCH_TIMEOUT
+    }
+}
+
+async fn fetch_with_timeout(
+    fetcher_impl: &Arc<dyn UrlMetadataFetcher>,
+    url: &str,
+) -> Option<UrlMetadata> {
+    let timeout = fetcher_impl.timeout();
+    let fetcher_impl = Arc::clone(fetcher_impl);
+    let target = url.to_string();
+    let task = tokio::task::spawn_blocking(move || fetcher_impl.fetch(&target));
+    match tokio::time::timeout(timeout, task).await {
+        Ok(Ok(Ok(page))) => Some(page),
+        _ => None,
+    }
+}
+
+fn fetcher() -> &'static Mutex<Option<Arc<dyn UrlMetadataFetcher>>> {
+    static FETCHER: OnceLock<Mutex<Option<Arc<dyn UrlMetadataFetcher>>>> = OnceLock::new();
+    FETCHER.get_or_init(|| Mutex::new(None))
+}
+
+/// Sets the process-wide fetcher, replacing any earlier one.
+pub fn register_url_metadata_fetcher(fetcher_impl: Arc<dyn UrlMetadataFetcher>) -> Result<()> {
+    *fetcher()
+        .lock()
+        .map_err(|_| anyhow!("url fetcher lock poisoned"))? = Some(fetcher_impl);
+    Ok(())
+}
+
+/// Removes the registered fetcher; metadata is no longer captured.
+pub fn clear_url_metadata_fetcher() -> Result<()> {
+    *fetcher()
+        .lock()
+        .map_err(|_| anyhow!("url fetcher lock poisoned"))? = None;
+    Ok(())
+}
+
+/// Parses an absolute `http`/`https` URL and returns its normalized form.
+pub fn normalize_url(value: &str) -> Option<String> {
+    let parsed = url::Url::parse(value.trim()).ok()?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        return None;
+    }
+    Some(parsed.to_string())
+}
+
+/// Refreshes the captured metadata in `metadata` for the `url` fields of
+/// `form_def` that opt in. Captures are reused while a field's URL is
+/// unchanged and dropped when the field is cleared.
+pub(crate) async fn capture_url_metadata(
+    form_def: &Value,
+    fields: &Value,
+    metadata: &Value,
+) -> Value {
+    let capture_fields: Vec<&String> = form_def
+        .get("fields")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .filter(|(_, def)| {
+            def.get("type").and_then(Value::as_str) == Some("url")
+                && def.get("capture_metadata").and_then(Value::as_bool) == Some(true)
+        })
+        .map(|(name, _)| name)
+        .collect();
+    let mut metadata = metadata.as_object().cloned().unwrap_or_default();
+    if capture_fields.is_empty() {
+        return Value::Object(metadata);
+    }
+    let fetcher_impl = fetcher().lock().ok().and_then(|guard| guard.clone());
+    let previous = metadata
+        .get(URL_METADATA_KEY)
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+
+    let mut captured = Map::new();
+    for name in capture_fields {
+        let Some(url) = fields.get(name).and_then(Value::as_str) else {
+            continue;
+        };
+        if let Some(existing) = previous
+            .get(name)
+            .filter(|existing| existing.get("url").and_then(Value::as_str) == Some(url))
+        {
+            captured.insert(name.clone(), existing.clone());
+            continue;
+        }
+        let Some(fetcher_impl) = &fetcher_impl else {
+            continue;
+        };
+        if let Some(mut page) = fetch_with_timeout(fetcher_impl, url).await {
+            page.url = url.to_string();
+            page.fetched_at = entry::now_ts();
+            if let Ok(value) = serde_json::to_value(page) {
+                captured.insert(name.clone(), value);
+            }
+        }
+    }
+    if captured.is_empty() {
+        metadata.remove(URL_METADATA_KEY);
+    } else {
+        metadata.insert(URL_METADATA_KEY.to_string(), Value::Object(captured));
+    }
+    Value::Object(metadata)
+}
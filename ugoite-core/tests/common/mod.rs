@@ -8,3 +8,16 @@ pub fn setup_operator() -> Result<Operator> {
     let op = Operator::new(builder)?.finish();
     Ok(op)
 }
+
+/// Local-filesystem operator rooted at a fresh temp directory, with the
+/// directory's guard: keep it alive for the test, it removes the directory
+/// when dropped.
+///
+/// Iceberg keeps `memory://` table files in its own store, so tests that
+/// inspect data files or rebuild a catalog from storage need a real backend.
+#[allow(dead_code)]
+pub fn setup_fs_operator() -> Result<(Operator, tempfile::TempDir)> {
+    let root = tempfile::Builder::new().prefix("ugoite-test-").tempdir()?;
+    let builder = opendal::services::Fs::default().root(&root.path().to_string_lossy());
+    Ok((Operator::new(builder)?.finish(), root))
+}
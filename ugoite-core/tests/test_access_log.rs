@@ -0,0 +1,104 @@
+mod common;
+
+use _ugoite_core::access_log;
+use _ugoite_core::space;
+use common::setup_operator;
+use tokio::time::{sleep, Duration};
+
+#[tokio::test]
+/// REQ-ENTRY-010
+async fn test_access_log_req_entry_010_recent_and_frequent() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "access-space", "/tmp").await?;
+    let ws_path = "spaces/access-space";
+
+    access_log::record_entry_access(&op, ws_path, "entry-a").await?;
+    access_log::record_entry_access(&op, ws_path, "entry-a").await?;
+    access_log::record_entry_access(&op, ws_path, "entry-a").await?;
+    sleep(Duration::from_millis(5)).await;
+    access_log::record_entry_access(&op, ws_path, "entry-b").await?;
+
+    let recent = access_log::list_recent_entries(&op, ws_path, 10).await?;
+    assert_eq!(recent.len(), 2);
+    assert_eq!(recent[0]["entry_id"], "entry-b");
+
+    let frequent = access_log::list_frequent_entries(&op, ws_path, 1).await?;
+    assert_eq!(frequent.len(), 1);
+    assert_eq!(frequent[0]["entry_id"], "entry-a");
+    assert_eq!(frequent[0]["access_count"], 3);
+
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-ENTRY-010
+async fn test_access_log_req_entry_010_compaction_preserves_stats() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "access-compact", "/tmp").await?;
+    let ws_path = "spaces/access-compact";
+
+    access_log::record_entry_access(&op, ws_path, "entry-a").await?;
+    access_log::record_entry_access(&op, ws_path, "entry-b").await?;
+    let compacted = access_log::compact_access_log(&op, ws_path).await?;
+    assert_eq!(compacted, 2);
+    assert_eq!(access_log::compact_access_log(&op, ws_path).await?, 0);
+
+    access_log::record_entry_access(&op, ws_path, "entry-b").await?;
+    let stats = access_log::load_access_stats(&op, ws_path).await?;
+    assert_eq!(stats["entry-a"].access_count, 1);
+    assert_eq!(stats["entry-b"].access_count, 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-ENTRY-010
+async fn test_access_log_req_entry_010_replayed_compaction_counts_once() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "access-replay", "/tmp").await?;
+    let ws_path = "spaces/access-replay";
+    let events_dir = format!("{}/access_log/events/", ws_path);
+
+    access_log::record_entry_access(&op, ws_path, "entry-a").await?;
+    access_log::record_entry_access(&op, ws_path, "entry-a").await?;
+    let mut events = Vec::new();
+    for item in op.list(&events_dir).await? {
+        if item.name().ends_with(".json") {
+            events.push((item.path().to_string(), op.read(item.path()).await?));
+        }
+    }
+    assert_eq!(events.len(), 2);
+    assert_eq!(access_log::compact_access_log(&op, ws_path).await?, 2);
+
+    // A compaction that stopped before deleting its events leaves them behind.
+    for (path, bytes) in events {
+        op.write(&path, bytes).await?;
+    }
+    assert_eq!(
+        access_log::load_access_stats(&op, ws_path).await?["entry-a"].access_count,
+        2
+    );
+    assert_eq!(access_log::compact_access_log(&op, ws_path).await?, 0);
+    assert_eq!(
+        access_log::load_access_stats(&op, ws_path).await?["entry-a"].access_count,
+        2
+    );
+
+    // Recording compacts on its own after enough events.
+    for _ in 0..access_log::COMPACTION_THRESHOLD {
+        access_log::record_entry_access(&op, ws_path, "entry-b").await?;
+    }
+    let pending = op
+        .list(&events_dir)
+        .await?
+        .into_iter()
+        .filter(|item| item.name().ends_with(".json"))
+        .count();
+    assert_eq!(pending, 0);
+    assert_eq!(
+        access_log::load_access_stats(&op, ws_path).await?["entry-b"].access_count,
+        access_log::COMPACTION_THRESHOLD as u64
+    );
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 mod common;
 use _ugoite_core::asset;
+use _ugoite_core::integrity::{self, IntegrityError, IntegrityProvider, RealIntegrityProvider};
 use _ugoite_core::space;
 use common::setup_operator;
 
@@ -40,3 +41,236 @@ async fn test_asset_req_asset_001_delete_asset() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+/// REQ-ASSET-002
+async fn test_asset_req_asset_002_read_asset_range() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "test-space", "/tmp").await?;
+    let ws_path = "spaces/test-space";
+
+    let info = asset::save_asset(&op, ws_path, "clip.mp4", b"0123456789").await?;
+
+    let range = asset::read_asset_range(&op, ws_path, &info.id, 2, Some(4)).await?;
+    assert_eq!(range.data, b"2345");
+    assert_eq!(range.offset, 2);
+    assert_eq!(range.total_size, 10);
+
+    let tail = asset::read_asset_range(&op, ws_path, &info.id, 7, None).await?;
+    assert_eq!(tail.data, b"789");
+    let clamped = asset::read_asset_range(&op, ws_path, &info.id, 8, Some(100)).await?;
+    assert_eq!(clamped.data, b"89");
+
+    assert!(asset::read_asset_range(&op, ws_path, &info.id, 10, None)
+        .await
+        .is_err());
+    assert!(asset::read_asset_range(&op, ws_path, "missing", 0, None)
+        .await
+        .is_err());
+
+    // The file is resolved from the asset record, not by listing the assets
+    // directory: a stray file that a listing would reach first is ignored.
+    op.write(
+        &format!("{}/assets/{}_aaa", ws_path, info.id),
+        b"decoy".to_vec(),
+    )
+    .await?;
+    let range = asset::read_asset_range(&op, ws_path, &info.id, 0, None).await?;
+    assert_eq!(range.data, b"0123456789");
+
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-ASSET-003
+async fn test_asset_req_asset_003_flags_spoofed_content_type() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "test-space", "/tmp").await?;
+    let ws_path = "spaces/test-space";
+
+    let png = asset::save_asset(&op, ws_path, "photo.PNG", b"\x89PNG\r\n\x1a\n....").await?;
+    assert_eq!(png.declared_type.as_deref(), Some("image/png"));
+    assert_eq!(png.detected_type.as_deref(), Some("image/png"));
+    assert!(!png.type_mismatch);
+    let entry_md = asset::save_asset(&op, ws_path, "readme.md", b"# Notes").await?;
+    assert!(!entry_md.type_mismatch);
+
+    let spoofed = asset::save_asset(&op, ws_path, "notes.md", b"\x7fELF\x02\x01\x01").await?;
+    assert_eq!(spoofed.declared_type.as_deref(), Some("text/markdown"));
+    assert_eq!(
+        spoofed.detected_type.as_deref(),
+        Some("application/x-executable")
+    );
+    assert!(spoofed.type_mismatch);
+    assert!(asset::check_asset_type("scan.pdf", b"plain text").mismatch);
+
+    let listed = asset::list_assets(&op, ws_path).await?;
+    let flagged = listed.iter().find(|a| a.id == spoofed.id).unwrap();
+    assert!(flagged.type_mismatch);
+
+    space::patch_space(
+        &op,
+        "test-space",
+        &serde_json::json!({"settings": {"assets": {"reject_type_mismatch": true}}}),
+    )
+    .await?;
+    assert!(asset::save_asset(&op, ws_path, "notes.md", b"MZ\x90\x00")
+        .await
+        .is_err());
+    assert_eq!(asset::list_assets(&op, ws_path).await?.len(), 3);
+
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-ASSET-004
+async fn test_asset_req_asset_004_asset_ref_fields_resolve_metadata() -> anyhow::Result<()> {
+    use _ugoite_core::integrity::FakeIntegrityProvider;
+    use _ugoite_core::{entry, form};
+
+    let op = setup_operator()?;
+    space::create_space(&op, "asset-ref-space", "/tmp").await?;
+    let ws_path = "spaces/asset-ref-space";
+    let integrity = FakeIntegrityProvider;
+    let info = asset::save_asset(&op, ws_path, "manual.pdf", b"%PDF-1.7 manual").await?;
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Device",
+            "template": "# Device\n\n## Manual\n",
+            "fields": {"Manual": {"type": "asset_ref"}},
+        }),
+    )
+    .await?;
+
+    let missing = "---\nform: Device\n---\n# Pump\n\n## Manual\nugoite://asset/no-such-asset";
+    let err = entry::create_entry(&op, ws_path, "pump", missing, "author", &integrity)
+        .await
+        .expect_err("missing asset is rejected");
+    assert!(err.to_string().contains("not found"));
+
+    let content = format!("---\nform: Device\n---\n# Pump\n\n## Manual\n{}", info.id);
+    entry::create_entry(&op, ws_path, "pump", &content, "author", &integrity).await?;
+    let pump = entry::get_entry(&op, ws_path, "pump").await?;
+    assert_eq!(
+        pump["asset_refs"]["Manual"],
+        serde_json::json!({
+            "id": info.id,
+            "name": "manual.pdf",
+            "size": 15,
+            "content_type": "application/pdf",
+            "link": info.link,
+        })
+    );
+    assert!(pump["content"]
+        .as_str()
+        .unwrap_or_default()
+        .contains(&info.link));
+
+    // A referenced asset cannot be deleted out from under the field.
+    assert!(asset::delete_asset(&op, ws_path, &info.id).await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-ASSET-006
+async fn test_asset_req_asset_006_integrity_records_and_verification() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "asset-integrity", "/tmp").await?;
+    let ws_path = "spaces/asset-integrity";
+    let provider = RealIntegrityProvider::from_space(&op, "asset-integrity").await?;
+
+    let info = asset::save_asset(&op, ws_path, "scan.txt", b"original scan").await?;
+    let records = asset::read_asset_integrity(&op, ws_path).await?;
+    let record = &records[&info.id];
+    assert_eq!(record.size, 13);
+    assert_eq!(
+        record.checksum,
+        "blake3:316eceb80dc6ec646d1805791ba54210aea81d4468e00f898c9faff3266f6ed6"
+    );
+    assert_eq!(
+        asset::verify_asset(&op, ws_path, &info.id, &provider).await?,
+        *record
+    );
+    let range =
+        asset::read_asset_range_verified(&op, ws_path, &info.id, 0, Some(8), &provider, Some(true))
+            .await?;
+    assert_eq!(range.data, b"original");
+    let before = integrity::space_merkle_root(&op, ws_path, &provider).await?;
+    assert_eq!(before["assets"], 1);
+
+    // Another space key cannot vouch for the asset.
+    let other_key = RealIntegrityProvider::new(b"another-secret".to_vec());
+    let err = asset::verify_asset(&op, ws_path, &info.id, &other_key)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<IntegrityError>(),
+        Some(IntegrityError::AssetSignatureMismatch { .. })
+    ));
+
+    // Rewritten bytes fail strict downloads only.
+    op.write(
+        &format!("{}/{}", ws_path, info.path),
+        b"tampered scan".to_vec(),
+    )
+    .await?;
+    let err =
+        asset::read_asset_range_verified(&op, ws_path, &info.id, 0, None, &provider, Some(true))
+            .await
+            .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<IntegrityError>(),
+        Some(IntegrityError::AssetChecksumMismatch { asset_id, .. }) if *asset_id == info.id
+    ));
+    asset::read_asset_range_verified(&op, ws_path, &info.id, 0, None, &provider, None).await?;
+    space::patch_space(
+        &op,
+        "asset-integrity",
+        &serde_json::json!({"settings": {"integrity": {"strict_reads": true}}}),
+    )
+    .await?;
+    assert!(
+        asset::read_asset_range_verified(&op, ws_path, &info.id, 0, None, &provider, None)
+            .await
+            .is_err()
+    );
+
+    // Assets stored before uploads were recorded are backfilled.
+    let legacy_id = "legacy-asset";
+    op.write(
+        &format!("{}/assets/{}_old.txt", ws_path, legacy_id),
+        b"legacy".to_vec(),
+    )
+    .await?;
+    let err = asset::verify_asset(&op, ws_path, legacy_id, &provider)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<IntegrityError>(),
+        Some(IntegrityError::AssetUnrecorded { .. })
+    ));
+    assert_eq!(
+        asset::backfill_asset_integrity(&op, ws_path, &provider).await?,
+        vec![legacy_id.to_string()]
+    );
+    asset::verify_asset(&op, ws_path, legacy_id, &provider).await?;
+    let after = integrity::space_merkle_root(&op, ws_path, &provider).await?;
+    assert_eq!(after["assets"], 2);
+    assert_ne!(after["root"], before["root"]);
+    assert_eq!(
+        after["signature"],
+        provider.signature(after["root"].as_str().unwrap())
+    );
+    assert_eq!(
+        integrity::space_merkle_root(&op, ws_path, &provider).await?,
+        after
+    );
+
+    asset::delete_asset(&op, ws_path, legacy_id).await?;
+    assert!(!asset::read_asset_integrity(&op, ws_path)
+        .await?
+        .contains_key(legacy_id));
+    Ok(())
+}
@@ -88,3 +88,16 @@ Body text""",
     )
     assert entry["id"] == "entry-1"
     assert entry["created_at"]
+
+
+@pytest.mark.asyncio
+async def test_binding_errors_carry_request_id(tmp_path: pathlib.Path) -> None:
+    """REQ-STO-024: binding errors carry the request id from the storage config."""
+    config = {"uri": f"fs://{tmp_path}", "request_id": "req-123"}
+    with pytest.raises(RuntimeError) as excinfo:
+        await ugoite_core.get_space(config, "missing-space")
+    assert excinfo.value.request_id == "req-123"
+
+    with pytest.raises(RuntimeError) as excinfo:
+        await ugoite_core.get_space({"uri": f"fs://{tmp_path}"}, "missing-space")
+    assert len(excinfo.value.request_id) == 32
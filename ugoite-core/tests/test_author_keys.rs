@@ -0,0 +1,174 @@
+mod common;
+use _ugoite_core::author_keys;
+use _ugoite_core::entry;
+use _ugoite_core::form;
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::space;
+use base64::{engine::general_purpose, Engine as _};
+use common::setup_operator;
+use ring::signature::{Ed25519KeyPair, KeyPair};
+
+fn generate_key() -> anyhow::Result<Ed25519KeyPair> {
+    let rng = ring::rand::SystemRandom::new();
+    let pkcs8 =
+        Ed25519KeyPair::generate_pkcs8(&rng).map_err(|_| anyhow::anyhow!("keygen failed"))?;
+    Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).map_err(|e| anyhow::anyhow!("{}", e))
+}
+
+fn encode(bytes: &[u8]) -> String {
+    general_purpose::STANDARD.encode(bytes)
+}
+
+#[tokio::test]
+/// REQ-INT-006
+async fn test_author_keys_req_int_006_verify_revision_author() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "signed-space", "/tmp").await?;
+    let ws_path = "spaces/signed-space";
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Entry",
+            "template": "# Entry\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    let integrity = FakeIntegrityProvider;
+    entry::create_entry(
+        &op,
+        ws_path,
+        "memo",
+        "---\nform: Entry\n---\n# memo\n\n## Body\nfirst",
+        "alice",
+        &integrity,
+    )
+    .await?;
+    let first = entry::get_entry_content(&op, ws_path, "memo")
+        .await?
+        .revision_id;
+    entry::update_entry(
+        &op,
+        ws_path,
+        "memo",
+        "---\nform: Entry\n---\n# memo\n\n## Body\nsecond",
+        Some(&first),
+        "bob",
+        None,
+        None,
+        &integrity,
+    )
+    .await?;
+    let second = entry::get_entry_content(&op, ws_path, "memo")
+        .await?
+        .revision_id;
+
+    let alice_pair = generate_key()?;
+    let alice_key = author_keys::register_author_key(
+        &op,
+        ws_path,
+        "alice",
+        &encode(alice_pair.public_key().as_ref()),
+    )
+    .await?;
+    assert_eq!(alice_key.algorithm, "ed25519");
+    let again = author_keys::register_author_key(
+        &op,
+        ws_path,
+        "alice",
+        &encode(alice_pair.public_key().as_ref()),
+    )
+    .await?;
+    assert_eq!(again, alice_key);
+    assert!(author_keys::register_author_key(
+        &op,
+        ws_path,
+        "bob",
+        &encode(alice_pair.public_key().as_ref())
+    )
+    .await
+    .is_err());
+    assert!(
+        author_keys::register_author_key(&op, ws_path, "bob", &encode(b"short"))
+            .await
+            .is_err()
+    );
+
+    let unsigned = author_keys::verify_revision_author(&op, ws_path, "memo", &first).await?;
+    assert_eq!(unsigned["signed"], false);
+    assert_eq!(unsigned["reason"], "unsigned");
+
+    let payload = author_keys::revision_signing_payload(&op, ws_path, "memo", &first).await?;
+    assert!(payload.contains(&first));
+    let signature = encode(alice_pair.sign(payload.as_bytes()).as_ref());
+    author_keys::attach_revision_signature(
+        &op,
+        ws_path,
+        "memo",
+        &first,
+        &alice_key.key_id,
+        &signature,
+    )
+    .await?;
+    let report = author_keys::verify_revision_author(&op, ws_path, "memo", &first).await?;
+    assert_eq!(report["valid"], true, "{}", report);
+    assert_eq!(report["author"], "alice");
+    assert_eq!(report["key_id"], alice_key.key_id.as_str());
+
+    // Alice cannot vouch for Bob's revision, and forged signatures are refused.
+    let bob_payload = author_keys::revision_signing_payload(&op, ws_path, "memo", &second).await?;
+    let bob_forgery = encode(alice_pair.sign(bob_payload.as_bytes()).as_ref());
+    assert!(author_keys::attach_revision_signature(
+        &op,
+        ws_path,
+        "memo",
+        &second,
+        &alice_key.key_id,
+        &bob_forgery,
+    )
+    .await
+    .is_err());
+    let bob_pair = generate_key()?;
+    let bob_key = author_keys::register_author_key(
+        &op,
+        ws_path,
+        "bob",
+        &encode(bob_pair.public_key().as_ref()),
+    )
+    .await?;
+    let wrong_key = encode(alice_pair.sign(bob_payload.as_bytes()).as_ref());
+    assert!(author_keys::attach_revision_signature(
+        &op,
+        ws_path,
+        "memo",
+        &second,
+        &bob_key.key_id,
+        &wrong_key,
+    )
+    .await
+    .is_err());
+
+    // A stored signature that no longer matches is reported, not trusted.
+    let path = "spaces/signed-space/revision_signatures/memo.json";
+    let mut stored: serde_json::Value = serde_json::from_slice(&op.read(path).await?.to_vec())?;
+    stored[&first]["signature"] = serde_json::json!(bob_forgery);
+    op.write(path, serde_json::to_vec(&stored)?).await?;
+    let report = author_keys::verify_revision_author(&op, ws_path, "memo", &first).await?;
+    assert_eq!(report["signed"], true);
+    assert_eq!(report["valid"], false);
+    assert_eq!(report["reason"], "bad_signature");
+
+    // Redaction cannot re-sign for the author; it names the signatures it broke.
+    let redaction = entry::Redaction {
+        patterns: vec!["first".to_string()],
+        fields: Vec::new(),
+    };
+    let redacted =
+        entry::redact_entry(&op, ws_path, "memo", &redaction, "auditor", &integrity).await?;
+    assert_eq!(
+        redacted["author_signatures_invalidated"],
+        serde_json::json!([first])
+    );
+    Ok(())
+}
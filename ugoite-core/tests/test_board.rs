@@ -0,0 +1,87 @@
+mod common;
+use _ugoite_core::board;
+use _ugoite_core::space;
+use common::setup_operator;
+
+fn column_ids(board: &serde_json::Value, column: &str) -> Vec<String> {
+    board["columns"][column]
+        .as_array()
+        .map(|cards| {
+            cards
+                .iter()
+                .map(|card| card["entry_id"].as_str().unwrap().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[test]
+/// REQ-ENTRY-013
+fn test_board_req_entry_013_positions_always_fit_between() -> anyhow::Result<()> {
+    assert_eq!(board::position_between(None, None)?, "V");
+
+    // Repeatedly squeezing into the same gap keeps producing ordered keys.
+    let low = board::position_between(None, None)?;
+    let mut high = board::position_between(Some(&low), None)?;
+    for _ in 0..200 {
+        let mid = board::position_between(Some(&low), Some(&high))?;
+        assert!(low < mid && mid < high, "{} < {} < {}", low, mid, high);
+        assert!(!mid.ends_with('0'));
+        high = mid;
+    }
+    let mut top = low;
+    for _ in 0..200 {
+        let above = board::position_between(None, Some(&top))?;
+        assert!(above < top);
+        top = above;
+    }
+
+    assert!(board::position_between(Some("b"), Some("a")).is_err());
+    assert!(board::position_between(Some("a0"), None).is_err());
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-ENTRY-013
+async fn test_board_req_entry_013_move_entries_within_and_across_columns() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "board-space", "/tmp").await?;
+    let ws_path = "spaces/board-space";
+
+    board::move_entry(&op, ws_path, "sprint", "todo", "a", None).await?;
+    board::move_entry(&op, ws_path, "sprint", "todo", "b", Some("a")).await?;
+    let c = board::move_entry(&op, ws_path, "sprint", "todo", "c", Some("b")).await?;
+    let before = board::get_board(&op, ws_path, "sprint").await?;
+    assert_eq!(column_ids(&before, "todo"), ["a", "b", "c"]);
+
+    // Moving c to the top only touches c.
+    board::move_entry(&op, ws_path, "sprint", "todo", "c", None).await?;
+    let after = board::get_board(&op, ws_path, "sprint").await?;
+    assert_eq!(column_ids(&after, "todo"), ["c", "a", "b"]);
+    assert_eq!(after["columns"]["todo"][1], before["columns"]["todo"][0]);
+    assert_eq!(after["columns"]["todo"][2], before["columns"]["todo"][1]);
+    assert_ne!(after["columns"]["todo"][0]["position"], c.position.as_str());
+
+    // Between two neighbours, and across columns.
+    board::move_entry(&op, ws_path, "sprint", "todo", "b", Some("c")).await?;
+    board::move_entry(&op, ws_path, "sprint", "done", "a", None).await?;
+    let moved = board::get_board(&op, ws_path, "sprint").await?;
+    assert_eq!(column_ids(&moved, "todo"), ["c", "b"]);
+    assert_eq!(column_ids(&moved, "done"), ["a"]);
+
+    let missing = board::move_entry(&op, ws_path, "sprint", "done", "d", Some("b")).await;
+    assert!(missing.is_err());
+    assert!(board::move_entry(&op, ws_path, "../x", "todo", "d", None)
+        .await
+        .is_err());
+
+    assert!(board::remove_from_board(&op, ws_path, "sprint", "c").await?);
+    assert!(!board::remove_from_board(&op, ws_path, "sprint", "c").await?);
+    let remaining = board::get_board(&op, ws_path, "sprint").await?;
+    assert_eq!(column_ids(&remaining, "todo"), ["b"]);
+
+    // Boards are independent of each other.
+    let other = board::get_board(&op, ws_path, "backlog").await?;
+    assert!(other["columns"].as_object().unwrap().is_empty());
+    Ok(())
+}
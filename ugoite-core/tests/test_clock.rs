@@ -0,0 +1,89 @@
+mod common;
+use _ugoite_core::clock;
+use _ugoite_core::entry;
+use _ugoite_core::form;
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::space;
+use common::setup_operator;
+
+#[test]
+/// REQ-STO-021
+fn test_clock_req_sto_021_monotonic_per_space() {
+    let ts = 1_700_000_000.123_456;
+    assert_eq!(clock::to_micros(ts), 1_700_000_000_123_456);
+    assert_eq!(clock::from_micros(clock::to_micros(ts)), ts);
+
+    let mut last = clock::next_micros("spaces/clock-a", None);
+    for _ in 0..1000 {
+        let next = clock::next_micros("spaces/clock-a", None);
+        assert!(next > last);
+        last = next;
+    }
+    // A floor ahead of the wall clock is respected, then kept.
+    let future = clock::now_micros() + 60_000_000;
+    assert_eq!(
+        clock::next_micros("spaces/clock-a", Some(future)),
+        future + 1
+    );
+    assert_eq!(clock::next_micros("spaces/clock-a/", None), future + 2);
+    // Other spaces follow the wall clock.
+    assert!(clock::next_micros("spaces/clock-b", None) < future);
+}
+
+#[tokio::test]
+/// REQ-STO-021
+async fn test_clock_req_sto_021_revisions_strictly_ordered() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "clock-space", "/tmp").await?;
+    let ws_path = "spaces/clock-space";
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Entry",
+            "template": "# Entry\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    let integrity = FakeIntegrityProvider;
+    entry::create_entry(
+        &op,
+        ws_path,
+        "fast",
+        "---\nform: Entry\n---\n# Fast\n\n## Body\nv0",
+        "author",
+        &integrity,
+    )
+    .await?;
+    for i in 1..6 {
+        let current = entry::get_entry(&op, ws_path, "fast").await?;
+        entry::update_entry(
+            &op,
+            ws_path,
+            "fast",
+            &format!("---\nform: Entry\n---\n# Fast\n\n## Body\nv{}", i),
+            current["revision_id"].as_str(),
+            "author",
+            None,
+            None,
+            &integrity,
+        )
+        .await?;
+    }
+
+    let history = entry::get_entry_history(&op, ws_path, "fast").await?;
+    let timestamps: Vec<f64> = history["revisions"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|rev| rev["timestamp"].as_f64())
+        .collect();
+    assert_eq!(timestamps.len(), 6);
+    let mut sorted = timestamps.clone();
+    sorted.sort_by(f64::total_cmp);
+    sorted.dedup();
+    assert_eq!(sorted.len(), 6);
+    Ok(())
+}
@@ -0,0 +1,122 @@
+mod common;
+use _ugoite_core::decimal::DecimalType;
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::{entry, form, index, space};
+use common::setup_fs_operator;
+
+#[tokio::test]
+/// REQ-FORM-011
+async fn test_decimal_req_form_011_exact_decimal_fields_and_rollups() -> anyhow::Result<()> {
+    let money = DecimalType::parse("decimal(12, 2)").expect("valid decimal type");
+    assert_eq!(money.field_type(), "decimal(12,2)");
+    assert_eq!(DecimalType::parse("decimal"), DecimalType::new(18, 2));
+    assert!(DecimalType::parse("decimal(40,2)").is_none());
+    assert_eq!(
+        money.normalize(&serde_json::json!("1,234.5")),
+        Some("1234.50".to_string())
+    );
+    assert_eq!(
+        money.normalize(&serde_json::json!(0.1)),
+        Some("0.10".to_string())
+    );
+    assert_eq!(
+        money.normalize(&serde_json::json!("-0.05")),
+        Some("-0.05".to_string())
+    );
+    // Digits beyond the scale or precision are rejected, never rounded.
+    assert_eq!(money.normalize(&serde_json::json!("0.125")), None);
+    assert_eq!(money.normalize(&serde_json::json!("12345678901")), None);
+
+    let (op, _root) = setup_fs_operator()?;
+    space::create_space(&op, "decimal-space", "/tmp").await?;
+    let ws_path = "spaces/decimal-space";
+    let integrity = FakeIntegrityProvider;
+    assert!(form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({"name": "Bad", "fields": {"Cost": {"type": "decimal(2,3)"}}}),
+    )
+    .await
+    .is_err());
+
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Project",
+            "template": "# Project\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Expense",
+            "template": "# Expense\n\n## Project\n\n## CostUSD\n",
+            "fields": {
+                "Project": {"type": "row_reference", "target_form": "Project"},
+                "CostUSD": {"type": "decimal( 12 , 2 )"},
+            },
+        }),
+    )
+    .await?;
+    let expense_def = form::get_form(&op, ws_path, "Expense").await?;
+    assert_eq!(expense_def["fields"]["CostUSD"]["type"], "decimal(12,2)");
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Project",
+            "template": "# Project\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+            "rollups": {
+                "Total": {"source_form": "Expense", "via": "Project", "aggregate": "sum", "field": "CostUSD"},
+                "Average": {"source_form": "Expense", "via": "Project", "aggregate": "avg", "field": "CostUSD"},
+            },
+        }),
+    )
+    .await?;
+    entry::create_entry(
+        &op,
+        ws_path,
+        "apollo",
+        "---\nform: Project\n---\n# Apollo\n\n## Body\nLaunch",
+        "author",
+        &integrity,
+    )
+    .await?;
+
+    let bad = "---\nform: Expense\n---\n# Bad\n\n## Project\napollo\n\n## CostUSD\n0.125";
+    assert!(
+        entry::create_entry(&op, ws_path, "bad", bad, "author", &integrity)
+            .await
+            .is_err()
+    );
+    for (id, cost) in [("e-1", "0.10"), ("e-2", "0.20"), ("e-3", "0.3")] {
+        let content = format!(
+            "---\nform: Expense\n---\n# {}\n\n## Project\napollo\n\n## CostUSD\n{}",
+            id, cost
+        );
+        entry::create_entry(&op, ws_path, id, &content, "author", &integrity).await?;
+    }
+    let expenses = entry::list_entries(&op, ws_path).await?;
+    let third = expenses
+        .iter()
+        .find(|row| row["id"] == "e-3")
+        .expect("e-3 entry");
+    assert_eq!(third["properties"]["CostUSD"], "0.30");
+
+    // f64 arithmetic would give 0.6000000000000001 here.
+    let apollo = entry::get_entry(&op, ws_path, "apollo").await?;
+    assert_eq!(
+        apollo["computed"],
+        serde_json::json!({"Total": "0.60", "Average": "0.20"})
+    );
+    let (casted, warnings) =
+        index::validate_properties(&serde_json::json!({"CostUSD": "19.99"}), &expense_def)?;
+    assert!(warnings.is_empty());
+    assert_eq!(casted["CostUSD"], "19.99");
+    Ok(())
+}
@@ -0,0 +1,123 @@
+mod common;
+use _ugoite_core::duplicate;
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::{asset, board, entry, folder, form, space};
+use common::setup_operator;
+
+#[tokio::test]
+/// REQ-ENTRY-032
+async fn test_duplicate_req_entry_032_duplicate_entry() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "duplicate-space", "/tmp").await?;
+    let ws_path = "spaces/duplicate-space";
+    let integrity = FakeIntegrityProvider;
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Device",
+            "template": "# Device\n\n## Manual\n\n## Notes\n",
+            "fields": {
+                "Manual": {"type": "asset_ref"},
+                "Notes": {"type": "markdown"},
+            },
+        }),
+    )
+    .await?;
+    let manual = asset::save_asset(&op, ws_path, "manual.pdf", b"%PDF-1.7 manual").await?;
+    let photo = asset::save_asset(&op, ws_path, "photo.txt", b"front view").await?;
+    entry::create_entry(
+        &op,
+        ws_path,
+        "pump",
+        &format!(
+            "---\nform: Device\ntags: [plant]\n---\n# Pump\n\n## Manual\n{}\n\n## Notes\nSee {}",
+            manual.id, photo.link
+        ),
+        "author1",
+        &integrity,
+    )
+    .await?;
+    folder::create_folder(&op, ws_path, "plant").await?;
+    folder::move_entry(&op, ws_path, "pump", Some("plant")).await?;
+    entry::create_entry(
+        &op,
+        ws_path,
+        "valve",
+        "---\nform: Device\n---\n# Valve\n\n## Notes\nSpare",
+        "author1",
+        &integrity,
+    )
+    .await?;
+    board::move_entry(&op, ws_path, "floor", "hall", "pump", None).await?;
+    board::move_entry(&op, ws_path, "floor", "hall", "valve", Some("pump")).await?;
+
+    // A shallow copy shares the assets and sits next to the original.
+    let shallow = duplicate::duplicate_entry(
+        &op,
+        ws_path,
+        "pump",
+        Some("pump-2"),
+        false,
+        "author2",
+        &integrity,
+    )
+    .await?;
+    assert_eq!(shallow["id"], "pump-2");
+    assert_eq!(shallow["duplicated_from"], "pump");
+    assert_eq!(shallow["title"], "Pump");
+    assert_eq!(shallow["tags"], serde_json::json!(["plant"]));
+    assert_eq!(shallow["sections"]["Manual"], manual.link.as_str());
+    assert_eq!(shallow["copied_assets"], serde_json::json!({}));
+    assert_eq!(shallow["boards"], serde_json::json!(["floor"]));
+    assert_eq!(folder::entry_folder(&op, ws_path, "pump-2").await?, "plant");
+    let floor = board::get_board(&op, ws_path, "floor").await?;
+    let hall: Vec<&str> = floor["columns"]["hall"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|card| card["entry_id"].as_str().unwrap())
+        .collect();
+    assert_eq!(hall, ["pump", "pump-2", "valve"]);
+    let history = entry::get_entry_history(&op, ws_path, "pump-2").await?;
+    assert_eq!(history["revisions"].as_array().unwrap().len(), 1);
+
+    // A deep copy uploads the assets again and points at the copies.
+    let deep =
+        duplicate::duplicate_entry(&op, ws_path, "pump", None, true, "author2", &integrity).await?;
+    let copied = deep["copied_assets"].as_object().unwrap();
+    assert_eq!(copied.len(), 2);
+    let manual_copy = copied[&manual.id].as_str().unwrap();
+    let photo_copy = copied[&photo.id].as_str().unwrap();
+    assert_eq!(
+        deep["sections"]["Manual"],
+        format!("ugoite://asset/{}", manual_copy)
+    );
+    assert!(deep["sections"]["Notes"]
+        .as_str()
+        .unwrap()
+        .contains(&format!("ugoite://asset/{}", photo_copy)));
+    let content = asset::read_asset_range(&op, ws_path, manual_copy, 0, None).await?;
+    assert_eq!(content.data, b"%PDF-1.7 manual");
+    // The original keeps its assets.
+    let original = entry::get_entry(&op, ws_path, "pump").await?;
+    assert_eq!(original["sections"]["Manual"], manual.link.as_str());
+
+    assert!(duplicate::duplicate_entry(
+        &op,
+        ws_path,
+        "pump",
+        Some("valve"),
+        false,
+        "a",
+        &integrity
+    )
+    .await
+    .is_err());
+    assert!(
+        duplicate::duplicate_entry(&op, ws_path, &manual.id, None, false, "a", &integrity)
+            .await
+            .is_err()
+    );
+    Ok(())
+}
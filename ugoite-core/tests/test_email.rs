@@ -0,0 +1,124 @@
+mod common;
+use _ugoite_core::asset;
+use _ugoite_core::email;
+use _ugoite_core::entry;
+use _ugoite_core::form;
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::space;
+use common::setup_operator;
+
+const REPORT_EML: &str = "From: =?UTF-8?Q?J=C3=BCrgen_M=C3=BCller?= <jm@example.com>\r
+To: reports@example.com\r
+Subject: =?UTF-8?B?V2Vla2x5?= =?UTF-8?B?IHJlcG9ydA==?=\r
+Date: Mon, 3 Mar 2025 23:30:00 +0000\r
+Message-ID: <abc123@example.com>\r
+MIME-Version: 1.0\r
+Content-Type: multipart/mixed; boundary=\"outer\"\r
+\r
+This is a multi-part message.\r
+--outer\r
+Content-Type: multipart/alternative; boundary=\"inner\"\r
+\r
+--inner\r
+Content-Type: text/plain; charset=utf-8\r
+Content-Transfer-Encoding: quoted-printable\r
+\r
+# Summary\r
+Output is up 5% =E2=80=94 see the chart.\r
+--inner\r
+Content-Type: text/html; charset=utf-8\r
+\r
+<p>Output is <b>up</b></p>\r
+--inner--\r
+--outer\r
+Content-Type: text/csv; name=\"figures.csv\"\r
+Content-Disposition: attachment; filename=\"figures.csv\"\r
+Content-Transfer-Encoding: base64\r
+\r
+d2VlayxvdXRwdXQKMTAsNDIK\r
+--outer--\r
+";
+
+#[tokio::test]
+/// REQ-ENTRY-029
+async fn test_email_req_entry_029_import_eml() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "mail-space", "/tmp").await?;
+    let ws_path = "spaces/mail-space";
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Report",
+            "template": "# Report\n\n## From\n\n## Date\n\n## Message ID\n\n## Body\n\n## Attachments\n",
+            "fields": {
+                "From": {"type": "string"},
+                "Date": {"type": "date"},
+                "Message ID": {"type": "string"},
+                "Body": {"type": "markdown"},
+                "Attachments": {"type": "list"},
+            },
+        }),
+    )
+    .await?;
+    let integrity = FakeIntegrityProvider;
+
+    let report = email::import_eml(
+        &op,
+        ws_path,
+        "Report",
+        REPORT_EML.as_bytes(),
+        "mailbot",
+        &integrity,
+    )
+    .await?;
+    assert_eq!(report["title"], "Weekly report");
+    assert_eq!(report["fields"]["Message-ID"], "Message ID");
+    assert_eq!(report["attachments"][0]["filename"], "figures.csv");
+    assert_eq!(report["attachments"][0]["content_type"], "text/csv");
+    assert_eq!(report["warnings"].as_array().unwrap().len(), 1);
+
+    let stored = entry::get_entry(&op, ws_path, report["entry_id"].as_str().unwrap()).await?;
+    let content = stored["content"].as_str().unwrap();
+    assert!(content.contains("# Weekly report"));
+    assert!(content.contains("## From\nJürgen Müller <jm@example.com>"));
+    assert!(content.contains("## Date\n2025-03-03"));
+    assert!(content.contains("**Summary**\nOutput is up 5% — see the chart."));
+    assert!(!content.contains("<b>"));
+    let link = report["attachments"][0]["link"].as_str().unwrap();
+    assert!(content.contains(&format!("## Attachments\n- {}", link)));
+
+    let assets = asset::list_assets(&op, ws_path).await?;
+    assert_eq!(assets.len(), 1);
+    let asset_id = report["attachments"][0]["asset_id"].as_str().unwrap();
+    let bytes = op
+        .read(&format!("{}/{}", ws_path, assets[0].path))
+        .await?
+        .to_vec();
+    assert_eq!(assets[0].id, asset_id);
+    assert_eq!(bytes, b"week,output\n10,42\n");
+
+    // An HTML-only message becomes markdown.
+    let html = "From: a@example.com\nSubject: Note\nContent-Type: text/html\n\n<p>See <a href=\"https://example.com\">the site</a></p><ul><li>one</li></ul>";
+    let report = email::import_eml(
+        &op,
+        ws_path,
+        "Report",
+        html.as_bytes(),
+        "mailbot",
+        &integrity,
+    )
+    .await?;
+    let stored = entry::get_entry(&op, ws_path, report["entry_id"].as_str().unwrap()).await?;
+    assert!(stored["content"]
+        .as_str()
+        .unwrap()
+        .contains("See [the site](https://example.com)\n- one"));
+
+    assert!(
+        email::import_eml(&op, ws_path, "Missing", html.as_bytes(), "m", &integrity)
+            .await
+            .is_err()
+    );
+    Ok(())
+}
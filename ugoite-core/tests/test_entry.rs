@@ -1,10 +1,15 @@
 mod common;
 use _ugoite_core::asset;
-use _ugoite_core::entry;
+use _ugoite_core::audit;
+use _ugoite_core::entry::{self, ArchivedFilter};
+use _ugoite_core::export::SyncFilter;
 use _ugoite_core::form;
+use _ugoite_core::index;
 use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::link;
+use _ugoite_core::search;
 use _ugoite_core::space;
-use common::setup_operator;
+use common::{setup_fs_operator, setup_operator};
 
 async fn ensure_entry_form(op: &opendal::Operator, ws_path: &str) -> anyhow::Result<()> {
     let form_def = serde_json::json!({
@@ -75,6 +80,7 @@ async fn test_entry_req_entry_003_update_entry_success() -> anyhow::Result<()> {
         Some(&initial_revision),
         "author1",
         None,
+        None,
         &integrity,
     )
     .await?;
@@ -120,6 +126,7 @@ async fn test_entry_req_entry_002_update_entry_conflict() -> anyhow::Result<()>
         Some(wrong_revision),
         "author1",
         None,
+        None,
         &integrity,
     )
     .await;
@@ -163,6 +170,7 @@ async fn test_entry_req_entry_005_entry_history_append() -> anyhow::Result<()> {
         Some(&rev_v1),
         "author1",
         None,
+        None,
         &integrity,
     )
     .await?;
@@ -386,6 +394,7 @@ async fn test_entry_req_entry_008_assets_linking() -> anyhow::Result<()> {
         Some(&current.revision_id),
         "author",
         Some(assets),
+        None,
         &integrity,
     )
     .await?;
@@ -402,3 +411,1045 @@ async fn test_entry_req_entry_008_assets_linking() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+/// REQ-ENTRY-012
+async fn test_entry_req_entry_012_redact_rewrites_history() -> anyhow::Result<()> {
+    let (op, _root) = setup_fs_operator()?;
+    space::create_space(&op, "redact-space", "/tmp").await?;
+    let ws_path = "spaces/redact-space";
+    // Uncompressed pages keep the secret greppable if any file still holds it.
+    space::patch_space(
+        &op,
+        "redact-space",
+        &serde_json::json!({"settings": {"parquet": {"compression": "none"}}}),
+    )
+    .await?;
+    ensure_entry_form(&op, ws_path).await?;
+    let integrity = FakeIntegrityProvider;
+    let entry_id = "leaky";
+    let secret = "sk-live-ABC123xyz";
+
+    entry::create_entry(
+        &op,
+        ws_path,
+        entry_id,
+        &format!("---\nform: Entry\n---\n# Keys\n\n## Body\ntoken {}", secret),
+        "author",
+        &integrity,
+    )
+    .await?;
+    let first_revision = entry::get_entry(&op, ws_path, entry_id).await?["revision_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    entry::update_entry(
+        &op,
+        ws_path,
+        entry_id,
+        &format!("---\nform: Entry\n---\n# Keys\n\n## Body\nstill {}", secret),
+        None,
+        "author",
+        None,
+        None,
+        &integrity,
+    )
+    .await?;
+    entry::update_entry(
+        &op,
+        ws_path,
+        entry_id,
+        "---\nform: Entry\n---\n# Keys\n\n## Body\nrotated",
+        None,
+        "author",
+        None,
+        Some(&format!("rotated {}", secret)),
+        &integrity,
+    )
+    .await?;
+    entry::set_entry_metadata(
+        &op,
+        ws_path,
+        entry_id,
+        &serde_json::json!({"note": format!("old key {}", secret)}),
+    )
+    .await?;
+
+    let empty = entry::redact_entry(
+        &op,
+        ws_path,
+        entry_id,
+        &entry::Redaction::default(),
+        "auditor",
+        &integrity,
+    )
+    .await;
+    assert!(empty.is_err());
+
+    let redaction = entry::Redaction {
+        patterns: vec!["sk-live-[A-Za-z0-9]+".to_string()],
+        fields: Vec::new(),
+    };
+    let report =
+        entry::redact_entry(&op, ws_path, entry_id, &redaction, "auditor", &integrity).await?;
+    assert_eq!(report["redacted"], true);
+    assert_eq!(report["replacements"], 4);
+    assert_eq!(report["revisions_rewritten"], 3);
+
+    let history = entry::get_entry_history(&op, ws_path, entry_id).await?;
+    assert_eq!(history["revisions"].as_array().unwrap().len(), 3);
+    let revision = entry::get_entry_revision(&op, ws_path, entry_id, &first_revision).await?;
+    assert_eq!(revision["fields"]["Body"], "token [REDACTED]");
+    assert_eq!(
+        revision["markdown_checksum"],
+        revision["integrity"]["checksum"]
+    );
+    let history = history["revisions"].as_array().unwrap();
+    assert!(history
+        .iter()
+        .any(|rev| rev["message"] == "rotated [REDACTED]"));
+    let current = entry::get_entry(&op, ws_path, entry_id).await?;
+    assert!(current["content"].as_str().unwrap().contains("rotated"));
+    assert_eq!(current["metadata"]["note"], "old key [REDACTED]");
+
+    let files = op
+        .list_with(&format!("{}/", ws_path))
+        .recursive(true)
+        .await?;
+    for file in files {
+        if file.metadata().is_file() {
+            let bytes = op.read(file.path()).await?.to_vec();
+            assert!(
+                !bytes.windows(secret.len()).any(|w| w == secret.as_bytes()),
+                "{} still contains the secret",
+                file.path()
+            );
+        }
+    }
+
+    let events = audit::list_audit_events(&op, ws_path, Some("entry.redacted")).await?;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["actor"], "auditor");
+    assert_eq!(events[0]["details"]["pattern_count"], 1);
+    assert_eq!(events[0]["id"], report["audit_event_id"]);
+
+    // A secret only in the latest title leaves revisions written under an
+    // earlier title with the checksums of their own content.
+    entry::create_entry(
+        &op,
+        ws_path,
+        "renamed",
+        "---\nform: Entry\n---\n# Plain\n\n## Body\nok",
+        "author",
+        &integrity,
+    )
+    .await?;
+    let plain = entry::get_entry(&op, ws_path, "renamed").await?;
+    let plain_revision = plain["revision_id"].as_str().unwrap().to_string();
+    entry::update_entry(
+        &op,
+        ws_path,
+        "renamed",
+        &format!("---\nform: Entry\n---\n# Plain {}\n\n## Body\nok", secret),
+        None,
+        "author",
+        None,
+        None,
+        &integrity,
+    )
+    .await?;
+    let report =
+        entry::redact_entry(&op, ws_path, "renamed", &redaction, "auditor", &integrity).await?;
+    assert_eq!(report["revisions_rewritten"], 1);
+    let revision = entry::get_entry_revision(&op, ws_path, "renamed", &plain_revision).await?;
+    assert_eq!(
+        revision["markdown_checksum"],
+        plain["integrity"]["checksum"]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-ENTRY-014
+async fn test_entry_req_entry_014_metadata_without_revisions() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "metadata-space", "/tmp").await?;
+    let ws_path = "spaces/metadata-space";
+    ensure_entry_form(&op, ws_path).await?;
+    let integrity = FakeIntegrityProvider;
+    entry::create_entry(
+        &op,
+        ws_path,
+        "pinned",
+        "---\nform: Entry\n---\n# Pinned\n\n## Body\nKeep me on top",
+        "author",
+        &integrity,
+    )
+    .await?;
+    let before = entry::get_entry(&op, ws_path, "pinned").await?;
+    assert_eq!(before["metadata"], serde_json::json!({}));
+
+    let metadata = entry::set_entry_metadata(
+        &op,
+        ws_path,
+        "pinned",
+        &serde_json::json!({"pinned": true, "color": "amber"}),
+    )
+    .await?;
+    assert_eq!(
+        metadata,
+        serde_json::json!({"pinned": true, "color": "amber"})
+    );
+    let metadata = entry::set_entry_metadata(
+        &op,
+        ws_path,
+        "pinned",
+        &serde_json::json!({"color": null, "icon": "star"}),
+    )
+    .await?;
+    assert_eq!(
+        metadata,
+        serde_json::json!({"pinned": true, "icon": "star"})
+    );
+
+    let after = entry::get_entry(&op, ws_path, "pinned").await?;
+    assert_eq!(after["metadata"], metadata);
+    assert_eq!(after["revision_id"], before["revision_id"]);
+    assert_eq!(after["content"], before["content"]);
+    let history = entry::get_entry_history(&op, ws_path, "pinned").await?;
+    assert_eq!(history["revisions"].as_array().unwrap().len(), 1);
+
+    // Content edits keep the metadata.
+    let updated = entry::update_entry(
+        &op,
+        ws_path,
+        "pinned",
+        "---\nform: Entry\n---\n# Pinned\n\n## Body\nEdited",
+        None,
+        "author",
+        None,
+        None,
+        &integrity,
+    )
+    .await?;
+    assert_eq!(updated["metadata"], metadata);
+    let listed = entry::list_entries(&op, ws_path).await?;
+    assert_eq!(listed[0]["metadata"]["icon"], "star");
+
+    assert!(
+        entry::set_entry_metadata(&op, ws_path, "pinned", &serde_json::json!(["x"]))
+            .await
+            .is_err()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-ENTRY-018
+async fn test_entry_req_entry_018_exists_reports_form_and_deleted() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "exists-space", "/tmp").await?;
+    let ws_path = "spaces/exists-space";
+    ensure_entry_form(&op, ws_path).await?;
+    let integrity = FakeIntegrityProvider;
+    let content = "---\nform: Entry\n---\n# Present\n\n## Body\nHere";
+
+    assert!(entry::exists(&op, ws_path, "present").await?.is_none());
+    entry::create_entry(&op, ws_path, "present", content, "author", &integrity).await?;
+    let existence = entry::exists(&op, ws_path, "present")
+        .await?
+        .expect("entry exists");
+    assert_eq!(existence.form, "Entry");
+    assert!(!existence.deleted);
+
+    // Served from the index shard once it is current.
+    _ugoite_core::index::reindex_all(&op, ws_path).await?;
+    let indexed = entry::exists(&op, ws_path, "present")
+        .await?
+        .expect("entry exists");
+    assert_eq!(indexed, existence);
+    assert!(entry::exists(&op, ws_path, "missing").await?.is_none());
+
+    // Lookups use the compact id list, not the shard's records: emptying the
+    // shard's records (same snapshot) does not hide the entry.
+    let shard_path = format!("{}/index/forms/Entry.json", ws_path);
+    let mut shard: serde_json::Value =
+        serde_json::from_slice(&op.read(&shard_path).await?.to_vec())?;
+    shard["records"] = serde_json::json!({});
+    op.write(&shard_path, serde_json::to_vec(&shard)?).await?;
+    assert_eq!(
+        entry::exists(&op, ws_path, "present").await?,
+        Some(existence.clone())
+    );
+    assert!(
+        entry::create_entry(&op, ws_path, "present", content, "author", &integrity)
+            .await
+            .is_err()
+    );
+
+    entry::delete_entry(&op, ws_path, "present", false).await?;
+    let deleted = entry::exists(&op, ws_path, "present")
+        .await?
+        .expect("deleted entry keeps its id");
+    assert!(deleted.deleted);
+    assert_eq!(deleted.form, "Entry");
+
+    let err = entry::create_entry(&op, ws_path, "present", content, "author", &integrity)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("already exists"));
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-ENTRY-021
+async fn test_entry_req_entry_021_revisions_record_changed_fields() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "changes-space", "/tmp").await?;
+    let ws_path = "spaces/changes-space";
+    let integrity = FakeIntegrityProvider;
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Ticket",
+            "template": "# Ticket\n\n## Status\n\n## Notes\n",
+            "fields": {"Status": {"type": "string"}, "Notes": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    let ticket = |status: &str, notes: &str| {
+        format!(
+            "---\nform: Ticket\n---\n# Leak\n\n## Status\n{}\n\n## Notes\n{}\n",
+            status, notes
+        )
+    };
+    entry::create_entry(
+        &op,
+        ws_path,
+        "leak",
+        &ticket("Open", "first line\nsecond line"),
+        "author",
+        &integrity,
+    )
+    .await?;
+    entry::update_entry(
+        &op,
+        ws_path,
+        "leak",
+        &ticket("Resolved", "first line\nsecond line\nthird line"),
+        None,
+        "author",
+        None,
+        None,
+        &integrity,
+    )
+    .await?;
+
+    let history = entry::get_entry_history(&op, ws_path, "leak").await?;
+    let revisions = history["revisions"].as_array().unwrap();
+    assert_eq!(revisions.len(), 2);
+    assert_eq!(revisions[0]["changed_fields"][1]["field"], "Status");
+    assert_eq!(revisions[0]["changed_fields"][1]["new"], "Open");
+
+    let changes = &revisions[1]["changed_fields"];
+    assert_eq!(
+        changes[1],
+        serde_json::json!({"field": "Status", "old": "Open", "new": "Resolved"})
+    );
+    // Multi-line values are named without their contents.
+    assert_eq!(changes[0], serde_json::json!({"field": "Notes"}));
+    assert_eq!(changes.as_array().unwrap().len(), 2);
+
+    let revision_id = revisions[1]["revision_id"].as_str().unwrap();
+    let revision = entry::get_entry_revision(&op, ws_path, "leak", revision_id).await?;
+    assert_eq!(&revision["changed_fields"], changes);
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-ENTRY-022
+async fn test_entry_req_entry_022_revision_messages_in_history() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "message-space", "/tmp").await?;
+    let ws_path = "spaces/message-space";
+    ensure_entry_form(&op, ws_path).await?;
+    let integrity = FakeIntegrityProvider;
+    let content = |body: &str| format!("---\nform: Entry\n---\n# Memo\n\n## Body\n{}", body);
+
+    entry::create_entry(
+        &op,
+        ws_path,
+        "memo",
+        &content("draft"),
+        "author",
+        &integrity,
+    )
+    .await?;
+    let first_revision = entry::get_entry(&op, ws_path, "memo").await?["revision_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    entry::update_entry(
+        &op,
+        ws_path,
+        "memo",
+        &content("final"),
+        None,
+        "author",
+        None,
+        Some("  Fix the pump number  "),
+        &integrity,
+    )
+    .await?;
+    entry::restore_entry(
+        &op,
+        ws_path,
+        "memo",
+        &first_revision,
+        "author",
+        Some("Revert: wrong pump"),
+        &integrity,
+    )
+    .await?;
+
+    let history = entry::get_entry_history(&op, ws_path, "memo").await?;
+    let messages: Vec<&serde_json::Value> = history["revisions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|rev| &rev["message"])
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            &serde_json::Value::Null,
+            &serde_json::json!("Fix the pump number"),
+            &serde_json::json!("Revert: wrong pump"),
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-ENTRY-026
+async fn test_entry_req_entry_026_undelete_restores_links_and_search() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "undelete-space", "/tmp").await?;
+    let ws_path = "spaces/undelete-space";
+    ensure_entry_form(&op, ws_path).await?;
+    let integrity = FakeIntegrityProvider;
+    for (id, body) in [
+        ("pump", "impeller wear"),
+        ("valve", "seat leak"),
+        ("log", "inspection"),
+    ] {
+        let content = format!("---\nform: Entry\n---\n# {id}\n\n## Body\n{body}");
+        entry::create_entry(&op, ws_path, id, &content, "author", &integrity).await?;
+    }
+    link::create_link(&op, ws_path, "pump", "valve", "related", "link-1").await?;
+    link::create_link(&op, ws_path, "log", "pump", "mentions", "link-2").await?;
+
+    let link_ids = |mut links: Vec<link::Link>| {
+        links.sort_by(|a, b| a.id.cmp(&b.id));
+        links.into_iter().map(|l| l.id).collect::<Vec<_>>()
+    };
+    let hit_ids = |hits: Vec<search::SearchResult>| {
+        let mut ids: Vec<String> = hits.into_iter().map(|hit| hit.id).collect();
+        ids.sort();
+        ids
+    };
+    let query_ids = |rows: Vec<serde_json::Value>| {
+        let mut ids: Vec<String> = rows
+            .iter()
+            .map(|row| row["id"].as_str().unwrap().to_string())
+            .collect();
+        ids.sort();
+        ids
+    };
+    let before_links = link_ids(link::list_links(&op, ws_path).await?);
+    let before_valve = entry::get_entry(&op, ws_path, "valve").await?["links"].clone();
+    let before_log = entry::get_entry(&op, ws_path, "log").await?["links"].clone();
+    let before_hits = hit_ids(search::search_entries(&op, ws_path, "impeller").await?);
+    let before_rows = query_ids(index::query_index(&op, ws_path, "{}").await?);
+    assert_eq!(before_links, vec!["link-1", "link-2"]);
+    assert_eq!(before_hits, vec!["pump"]);
+
+    entry::delete_entry(&op, ws_path, "pump", false).await?;
+    assert!(link::list_links(&op, ws_path).await?.is_empty());
+    assert_eq!(
+        entry::get_entry(&op, ws_path, "valve").await?["links"],
+        serde_json::json!([])
+    );
+    assert!(search::search_entries(&op, ws_path, "impeller")
+        .await?
+        .is_empty());
+    assert_eq!(
+        query_ids(index::query_index(&op, ws_path, "{}").await?),
+        vec!["log", "valve"]
+    );
+
+    let restored = entry::undelete_entry(&op, ws_path, "pump").await?;
+    assert_eq!(restored["id"], "pump");
+    assert_eq!(
+        link_ids(link::list_links(&op, ws_path).await?),
+        before_links
+    );
+    assert_eq!(
+        entry::get_entry(&op, ws_path, "valve").await?["links"],
+        before_valve
+    );
+    assert_eq!(
+        entry::get_entry(&op, ws_path, "log").await?["links"],
+        before_log
+    );
+    assert_eq!(
+        hit_ids(search::search_entries(&op, ws_path, "impeller").await?),
+        before_hits
+    );
+    assert_eq!(
+        query_ids(index::query_index(&op, ws_path, "{}").await?),
+        before_rows
+    );
+    let existence = entry::exists(&op, ws_path, "pump").await?.unwrap();
+    assert!(!existence.deleted);
+
+    let err = entry::undelete_entry(&op, ws_path, "pump")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("not deleted"));
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-ENTRY-030
+async fn test_entry_req_entry_030_list_entries_page_and_filters() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "paged-space", "/tmp").await?;
+    let ws_path = "spaces/paged-space";
+    ensure_entry_form(&op, ws_path).await?;
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Task",
+            "template": "# Task\n\n## Status\n",
+            "fields": {"Status": {"type": "string"}},
+        }),
+    )
+    .await?;
+    let integrity = FakeIntegrityProvider;
+    for (id, form_name, tag) in [
+        ("e1", "Entry", "red"),
+        ("e2", "Entry", "blue"),
+        ("e3", "Entry", "red"),
+        ("t1", "Task", "red"),
+        ("t2", "Task", "blue"),
+    ] {
+        let section = if form_name == "Task" {
+            "Status"
+        } else {
+            "Body"
+        };
+        entry::create_entry(
+            &op,
+            ws_path,
+            id,
+            &format!(
+                "---\nform: {}\ntags: [{}]\n---\n# {}\n\n## {}\nText",
+                form_name, tag, id, section
+            ),
+            "author1",
+            &integrity,
+        )
+        .await?;
+    }
+    let ids = |page: &serde_json::Value| -> Vec<String> {
+        page["entries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry["id"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    let all = SyncFilter::default();
+    let first =
+        entry::list_entries_page(&op, ws_path, &all, 0, Some(2), &[], ArchivedFilter::Exclude)
+            .await?;
+    assert_eq!(ids(&first), ["e1", "e2"]);
+    assert_eq!(first["total_count"], 5);
+    let last =
+        entry::list_entries_page(&op, ws_path, &all, 4, Some(2), &[], ArchivedFilter::Exclude)
+            .await?;
+    assert_eq!(ids(&last), ["t2"]);
+    let past =
+        entry::list_entries_page(&op, ws_path, &all, 9, Some(2), &[], ArchivedFilter::Exclude)
+            .await?;
+    assert!(ids(&past).is_empty());
+    assert_eq!(
+        ids(
+            &entry::list_entries_page(&op, ws_path, &all, 0, None, &[], ArchivedFilter::Exclude)
+                .await?
+        )
+        .len(),
+        entry::list_entries(&op, ws_path).await?.len()
+    );
+
+    let red_tasks = SyncFilter {
+        forms: vec!["Task".to_string()],
+        tags: vec!["red".to_string()],
+        ..Default::default()
+    };
+    let page = entry::list_entries_page(
+        &op,
+        ws_path,
+        &red_tasks,
+        0,
+        Some(10),
+        &[],
+        ArchivedFilter::Exclude,
+    )
+    .await?;
+    assert_eq!(ids(&page), ["t1"]);
+    assert_eq!(page["total_count"], 1);
+
+    let e3 = entry::get_entry(&op, ws_path, "e3").await?;
+    let since = e3["updated_at"].as_f64().unwrap();
+    let recent = SyncFilter {
+        updated_since: Some(since),
+        ..Default::default()
+    };
+    let page =
+        entry::list_entries_page(&op, ws_path, &recent, 0, None, &[], ArchivedFilter::Exclude)
+            .await?;
+    assert_eq!(ids(&page), ["e3", "t1", "t2"]);
+    let older = SyncFilter {
+        updated_until: Some(since),
+        tags: vec!["red".to_string()],
+        ..Default::default()
+    };
+    let page =
+        entry::list_entries_page(&op, ws_path, &older, 0, None, &[], ArchivedFilter::Exclude)
+            .await?;
+    assert_eq!(ids(&page), ["e1", "e3"]);
+
+    entry::delete_entry(&op, ws_path, "e2", false).await?;
+    let page =
+        entry::list_entries_page(&op, ws_path, &all, 0, Some(2), &[], ArchivedFilter::Exclude)
+            .await?;
+    assert_eq!(ids(&page), ["e1", "e3"]);
+    assert_eq!(page["total_count"], 4);
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-ENTRY-031
+async fn test_entry_req_entry_031_patch_entry_sections() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "patch-space", "/tmp").await?;
+    let ws_path = "spaces/patch-space";
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Task",
+            "template": "# Task\n\n## Status\n\n## Notes\n",
+            "fields": {
+                "Status": {"type": "string"},
+                "Notes": {"type": "markdown"},
+            },
+        }),
+    )
+    .await?;
+    let integrity = FakeIntegrityProvider;
+    entry::create_entry(
+        &op,
+        ws_path,
+        "task-1",
+        "---\nform: Task\ntags: [ops]\n---\n# Fix the pump\n\n## Status\nOpen\n\n## Notes\nCheck the valve first.\n\n- order seals",
+        "author1",
+        &integrity,
+    )
+    .await?;
+    let before = entry::get_entry(&op, ws_path, "task-1").await?;
+
+    let patched = entry::patch_entry(
+        &op,
+        ws_path,
+        "task-1",
+        &serde_json::json!({"## Status": "Done"}),
+        None,
+        "author2",
+        Some("Close task"),
+        &integrity,
+    )
+    .await?;
+    assert_eq!(patched["sections"]["Status"], "Done");
+    assert_eq!(patched["sections"]["Notes"], before["sections"]["Notes"]);
+    assert_eq!(patched["title"], "Fix the pump");
+    assert_eq!(patched["tags"], serde_json::json!(["ops"]));
+    assert_ne!(patched["revision_id"], before["revision_id"]);
+    let history = entry::get_entry_history(&op, ws_path, "task-1").await?;
+    let revisions = history["revisions"].as_array().unwrap();
+    assert_eq!(revisions.len(), 2);
+
+    // A stale parent revision is a conflict, and an empty patch is refused.
+    assert!(entry::patch_entry(
+        &op,
+        ws_path,
+        "task-1",
+        &serde_json::json!({"Status": "Open"}),
+        before["revision_id"].as_str(),
+        "author2",
+        None,
+        &integrity,
+    )
+    .await
+    .is_err());
+    assert!(entry::patch_entry(
+        &op,
+        ws_path,
+        "task-1",
+        &serde_json::json!({}),
+        None,
+        "author2",
+        None,
+        &integrity,
+    )
+    .await
+    .is_err());
+
+    let cleared = entry::patch_entry(
+        &op,
+        ws_path,
+        "task-1",
+        &serde_json::json!({"Notes": null}),
+        None,
+        "author2",
+        None,
+        &integrity,
+    )
+    .await?;
+    assert!(cleared["sections"].get("Notes").is_none());
+    assert_eq!(cleared["sections"]["Status"], "Done");
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-ENTRY-037
+async fn test_entry_req_entry_037_change_entry_form() -> anyhow::Result<()> {
+    let (op, _root) = setup_fs_operator()?;
+    space::create_space(&op, "move-space", "/tmp").await?;
+    let ws_path = "spaces/move-space";
+    let integrity = FakeIntegrityProvider;
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Task",
+            "fields": {"Effort": {"type": "string"}, "Owner": {"type": "string"}},
+        }),
+    )
+    .await?;
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Ticket",
+            "fields": {"Points": {"type": "double"}, "Owner": {"type": "string"}},
+        }),
+    )
+    .await?;
+    let task = |effort: &str| {
+        format!(
+            "---\nform: Task\n---\n# Fix login\n\n## Effort\n{}\n\n## Owner\nann\n",
+            effort
+        )
+    };
+    entry::create_entry(&op, ws_path, "t1", &task("3"), "ann", &integrity).await?;
+    entry::update_entry(
+        &op,
+        ws_path,
+        "t1",
+        &task("5"),
+        None,
+        "ann",
+        None,
+        None,
+        &integrity,
+    )
+    .await?;
+
+    // Ticket has no Effort field, and it does not allow extra attributes.
+    assert!(
+        entry::change_entry_form(&op, ws_path, "t1", "Ticket", None, "bob", &integrity)
+            .await
+            .is_err()
+    );
+    assert_eq!(entry::get_entry(&op, ws_path, "t1").await?["form"], "Task");
+
+    let mapping = serde_json::json!({"Points": {"from": "Effort", "transform": "to_number"}});
+    let moved = entry::change_entry_form(
+        &op,
+        ws_path,
+        "t1",
+        "Ticket",
+        Some(&mapping),
+        "bob",
+        &integrity,
+    )
+    .await?;
+    assert_eq!(moved["form"], "Ticket");
+    assert_eq!(moved["title"], "Fix login");
+    assert_eq!(
+        moved["sections"]["Points"]
+            .as_str()
+            .unwrap()
+            .parse::<f64>()?,
+        5.0
+    );
+    assert_eq!(moved["sections"]["Owner"], "ann");
+
+    // The history moved along, followed by a revision for the move.
+    let history = entry::get_entry_history(&op, ws_path, "t1").await?;
+    let revisions = history["revisions"].as_array().unwrap();
+    assert_eq!(revisions.len(), 3);
+    assert_eq!(revisions[2]["message"], "Moved from form Task");
+    let entries = entry::list_entries(&op, ws_path).await?;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["form"], "Ticket");
+
+    // Values the mapping cannot convert leave the entry where it is.
+    entry::create_entry(&op, ws_path, "t2", &task("lots"), "ann", &integrity).await?;
+    let err = entry::change_entry_form(
+        &op,
+        ws_path,
+        "t2",
+        "Ticket",
+        Some(&mapping),
+        "bob",
+        &integrity,
+    )
+    .await
+    .unwrap_err();
+    assert!(format!("{:#}", err).contains("Effort"));
+    assert_eq!(entry::get_entry(&op, ws_path, "t2").await?["form"], "Task");
+    assert!(
+        entry::change_entry_form(&op, ws_path, "t2", "Task", None, "bob", &integrity)
+            .await
+            .is_err()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-ENTRY-038
+async fn test_entry_req_entry_038_field_projection() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "projection-space", "/tmp").await?;
+    let ws_path = "spaces/projection-space";
+    let integrity = FakeIntegrityProvider;
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Reading",
+            "template": "# Reading\n\n## Value\n\n## Notes\n",
+            "fields": {"Value": {"type": "number"}, "Notes": {"type": "markdown"}},
+            "allow_extra_attributes": "allow_json",
+        }),
+    )
+    .await?;
+    for (id, value, site) in [("r1", "21.5", "north"), ("r2", "19", "south")] {
+        entry::create_entry(
+            &op,
+            ws_path,
+            id,
+            &format!(
+                "---\nform: Reading\ntags: [lab]\n---\n# {id}\n\n## Value\n{value}\n\n## Notes\nLong notes\n\n## Site\n{site}"
+            ),
+            "author",
+            &integrity,
+        )
+        .await?;
+    }
+    let fields = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+
+    let reading = entry::get_entry_fields(&op, ws_path, "r1", &fields(&["Value"])).await?;
+    assert_eq!(reading["form"], "Reading");
+    assert_eq!(reading["title"], "r1");
+    assert_eq!(reading["tags"], serde_json::json!(["lab"]));
+    assert_eq!(reading["fields"], serde_json::json!({"Value": 21.5}));
+    let reading =
+        entry::get_entry_fields(&op, ws_path, "r1", &fields(&["Site", "Missing"])).await?;
+    assert_eq!(reading["fields"], serde_json::json!({"Site": "north"}));
+
+    // With the index gone, matches come from a projected table scan.
+    op.remove_all(&format!("{ws_path}/index/")).await?;
+    let mut rows =
+        index::query_index_fields(&op, ws_path, r#"{"Site": "south"}"#, &fields(&["Value"]))
+            .await?;
+    assert_eq!(rows.len(), 1);
+    let row = rows.remove(0);
+    assert_eq!(row["id"], "r2");
+    assert_eq!(row["properties"], serde_json::json!({"Value": 19.0}));
+    assert!(row.get("links").is_none());
+
+    entry::delete_entry(&op, ws_path, "r1", false).await?;
+    assert!(
+        entry::get_entry_fields(&op, ws_path, "r1", &fields(&["Value"]))
+            .await
+            .is_err()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-ENTRY-039
+async fn test_entry_req_entry_039_heavy_fields_left_out_of_listings() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "heavy-space", "/tmp").await?;
+    let ws_path = "spaces/heavy-space";
+    let integrity = FakeIntegrityProvider;
+    assert!(form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Incident",
+            "fields": {"Findings": {"type": "markdown", "heavy": "yes"}},
+        }),
+    )
+    .await
+    .is_err());
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Incident",
+            "template": "# Incident\n\n## Severity\n\n## Findings\n\n## ResolutionNotes\n",
+            "fields": {
+                "Severity": {"type": "string"},
+                "Findings": {"type": "markdown", "heavy": true},
+                "ResolutionNotes": {"type": "markdown", "heavy": true},
+            },
+        }),
+    )
+    .await?;
+    entry::create_entry(
+        &op,
+        ws_path,
+        "outage",
+        "---\nform: Incident\n---\n# Outage\n\n## Severity\nhigh\n\n## Findings\nA very long write-up\n\n## ResolutionNotes\nRestarted",
+        "author",
+        &integrity,
+    )
+    .await?;
+    let properties = |entries: &[serde_json::Value]| entries[0]["properties"].clone();
+
+    let listed = entry::list_entries(&op, ws_path).await?;
+    assert_eq!(properties(&listed), serde_json::json!({"Severity": "high"}));
+    let listed = entry::list_entries_including(
+        &op,
+        ws_path,
+        &["Findings".to_string()],
+        ArchivedFilter::Exclude,
+    )
+    .await?;
+    assert_eq!(
+        properties(&listed),
+        serde_json::json!({"Severity": "high", "Findings": "A very long write-up"})
+    );
+    let page = entry::list_entries_page(
+        &op,
+        ws_path,
+        &SyncFilter::default(),
+        0,
+        Some(10),
+        &["ResolutionNotes".to_string()],
+        ArchivedFilter::Exclude,
+    )
+    .await?;
+    assert_eq!(
+        page["entries"][0]["properties"],
+        serde_json::json!({"Severity": "high", "ResolutionNotes": "Restarted"})
+    );
+
+    // Reading the entry itself still returns every field.
+    let outage = entry::get_entry(&op, ws_path, "outage").await?;
+    assert_eq!(outage["sections"]["Findings"], "A very long write-up");
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-ENTRY-040
+async fn test_entry_req_entry_040_archive_entry() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "archive-space", "/tmp").await?;
+    let ws_path = "spaces/archive-space";
+    ensure_entry_form(&op, ws_path).await?;
+    let integrity = FakeIntegrityProvider;
+    for (id, body) in [
+        ("old-plan", "quarterly roadmap"),
+        ("new-plan", "yearly roadmap"),
+    ] {
+        entry::create_entry(
+            &op,
+            ws_path,
+            id,
+            &format!("---\nform: Entry\n---\n# {id}\n\n## Body\n{body}"),
+            "author",
+            &integrity,
+        )
+        .await?;
+    }
+    let ids = |entries: Vec<serde_json::Value>| -> Vec<String> {
+        let mut ids: Vec<String> = entries
+            .iter()
+            .map(|e| e["id"].as_str().unwrap().to_string())
+            .collect();
+        ids.sort();
+        ids
+    };
+    let hits = |results: Vec<search::SearchResult>| -> Vec<String> {
+        let mut ids: Vec<String> = results.into_iter().map(|hit| hit.id).collect();
+        ids.sort();
+        ids
+    };
+
+    assert_eq!(
+        entry::archive_entry(&op, ws_path, "old-plan").await?,
+        serde_json::json!({"id": "old-plan", "archived": true})
+    );
+    assert_eq!(ids(entry::list_entries(&op, ws_path).await?), ["new-plan"]);
+    let archived = entry::list_entries_including(&op, ws_path, &[], ArchivedFilter::Only).await?;
+    assert_eq!(archived[0]["archived"], true);
+    assert_eq!(ids(archived), ["old-plan"]);
+    assert_eq!(
+        hits(search::search_entries(&op, ws_path, "roadmap").await?),
+        ["new-plan"]
+    );
+    assert_eq!(
+        hits(
+            search::search_entries_filtered(&op, ws_path, "roadmap", ArchivedFilter::Include)
+                .await?
+        ),
+        ["new-plan", "old-plan"]
+    );
+    // Archived entries stay readable and queryable.
+    let old_plan = entry::get_entry(&op, ws_path, "old-plan").await?;
+    assert_eq!(old_plan["sections"]["Body"], "quarterly roadmap");
+    let rows = index::query_index(&op, ws_path, r#"{"archived": true}"#).await?;
+    assert_eq!(ids(rows), ["old-plan"]);
+
+    entry::unarchive_entry(&op, ws_path, "old-plan").await?;
+    assert_eq!(
+        ids(entry::list_entries(&op, ws_path).await?),
+        ["new-plan", "old-plan"]
+    );
+    entry::delete_entry(&op, ws_path, "new-plan", false).await?;
+    assert!(entry::archive_entry(&op, ws_path, "new-plan")
+        .await
+        .is_err());
+    Ok(())
+}
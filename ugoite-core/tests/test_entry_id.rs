@@ -0,0 +1,193 @@
+mod common;
+use _ugoite_core::entry;
+use _ugoite_core::entry_id;
+use _ugoite_core::form;
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::space;
+use common::setup_operator;
+
+#[test]
+/// REQ-ENTRY-017
+fn test_entry_id_req_entry_017_slugify() {
+    assert_eq!(
+        entry_id::slugify("Pump #3: Inspection"),
+        "pump-3-inspection"
+    );
+    assert_eq!(entry_id::slugify("  --Hello  World--  "), "hello-world");
+    assert_eq!(entry_id::slugify("会議メモ 2024"), "会議メモ-2024");
+    assert_eq!(entry_id::slugify("!!!"), "");
+    assert!(entry_id::slugify(&"a".repeat(200)).chars().count() <= 64);
+}
+
+#[tokio::test]
+/// REQ-ENTRY-017
+async fn test_entry_id_req_entry_017_suggest_unique_ids() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "id-space", "/tmp").await?;
+    let ws_path = "spaces/id-space";
+    for name in ["Maintenance", "Entry"] {
+        form::upsert_form(
+            &op,
+            ws_path,
+            &serde_json::json!({
+                "name": name,
+                "template": format!("# {}\n\n## Body\n", name),
+                "fields": {"Body": {"type": "markdown"}},
+            }),
+        )
+        .await?;
+    }
+    space::patch_space(
+        &op,
+        "id-space",
+        &serde_json::json!({"settings": {"entry_ids": {
+            "forms": {"Maintenance": "maintenance-{counter:05}"},
+        }}}),
+    )
+    .await?;
+    let integrity = FakeIntegrityProvider;
+
+    // Slug ids are bumped past existing (and deleted) entries.
+    assert_eq!(
+        entry_id::suggest_entry_id(&op, ws_path, "Weekly Sync", "Entry").await?,
+        "weekly-sync"
+    );
+    entry::create_entry(
+        &op,
+        ws_path,
+        "weekly-sync",
+        "---\nform: Entry\n---\n# Weekly Sync\n\n## Body\nnotes",
+        "author",
+        &integrity,
+    )
+    .await?;
+    entry::delete_entry(&op, ws_path, "weekly-sync", false).await?;
+    assert_eq!(
+        entry_id::suggest_entry_id(&op, ws_path, "Weekly Sync", "Entry").await?,
+        "weekly-sync-2"
+    );
+    assert_eq!(
+        entry_id::suggest_entry_id(&op, ws_path, "???", "Entry").await?,
+        "entry"
+    );
+
+    // Counters are per form, zero padded, and skip ids already in use.
+    entry::create_entry(
+        &op,
+        ws_path,
+        "maintenance-00002",
+        "---\nform: Maintenance\n---\n# Imported\n\n## Body\nold",
+        "author",
+        &integrity,
+    )
+    .await?;
+    assert_eq!(
+        entry_id::suggest_entry_id(&op, ws_path, "Pump", "Maintenance").await?,
+        "maintenance-00001"
+    );
+    assert_eq!(
+        entry_id::suggest_entry_id(&op, ws_path, "Pump", "Maintenance").await?,
+        "maintenance-00003"
+    );
+    assert_eq!(entry_id::next_counter(&op, ws_path, "Entry").await?, 1);
+
+    space::patch_space(
+        &op,
+        "id-space",
+        &serde_json::json!({"settings": {"entry_ids": {"default": "{form}-{unknown}"}}}),
+    )
+    .await?;
+    assert!(entry_id::suggest_entry_id(&op, ws_path, "x", "Entry")
+        .await
+        .is_err());
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-ENTRY-023
+async fn test_entry_id_req_entry_023_id_strategies() -> anyhow::Result<()> {
+    use entry_id::IdStrategy;
+
+    let v7 = entry_id::generate_id(IdStrategy::Uuidv7);
+    assert_eq!(v7.len(), 36);
+    assert_eq!(&v7[14..15], "7");
+    let ulid = entry_id::generate_id(IdStrategy::Ulid);
+    assert_eq!(ulid.len(), 26);
+    assert!(ulid
+        .chars()
+        .all(|c| c.is_ascii_digit() || c.is_ascii_uppercase()));
+
+    // Time-ordered ids sort by creation time.
+    for strategy in [IdStrategy::Uuidv7, IdStrategy::Ulid] {
+        let first = entry_id::generate_id(strategy);
+        std::thread::sleep(std::time::Duration::from_millis(3));
+        let second = entry_id::generate_id(strategy);
+        assert!(first < second, "{:?}: {} < {}", strategy, first, second);
+    }
+
+    // Ids minted within the same millisecond still sort in creation order.
+    for strategy in [IdStrategy::Uuidv7, IdStrategy::Ulid] {
+        let ids: Vec<String> = (0..500).map(|_| entry_id::generate_id(strategy)).collect();
+        for pair in ids.windows(2) {
+            assert!(
+                pair[0] < pair[1],
+                "{:?}: {} < {}",
+                strategy,
+                pair[0],
+                pair[1]
+            );
+        }
+    }
+
+    let op = setup_operator()?;
+    space::create_space(&op, "strategy-space", "/tmp").await?;
+    let ws_path = "spaces/strategy-space";
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Entry",
+            "template": "# Entry\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    space::patch_space(
+        &op,
+        "strategy-space",
+        &serde_json::json!({"settings": {"entry_ids": {
+            "default": "log-{ulid}",
+            "revision_ids": "uuidv7",
+        }}}),
+    )
+    .await?;
+
+    let suggested = entry_id::suggest_entry_id(&op, ws_path, "Any", "Entry").await?;
+    assert!(suggested.starts_with("log-"));
+    assert_eq!(suggested.len(), 4 + 26);
+
+    let integrity = FakeIntegrityProvider;
+    entry::create_entry(
+        &op,
+        ws_path,
+        &suggested,
+        "---\nform: Entry\n---\n# Any\n\n## Body\nfirst",
+        "author",
+        &integrity,
+    )
+    .await?;
+    let created = entry::get_entry(&op, ws_path, &suggested).await?;
+    let revision_id = created["revision_id"].as_str().unwrap_or_default();
+    assert_eq!(&revision_id[14..15], "7");
+
+    space::patch_space(
+        &op,
+        "strategy-space",
+        &serde_json::json!({"settings": {"entry_ids": {"revision_ids": "snowflake"}}}),
+    )
+    .await?;
+    assert!(entry_id::suggest_entry_id(&op, ws_path, "x", "Entry")
+        .await
+        .is_err());
+    Ok(())
+}
@@ -0,0 +1,630 @@
+mod common;
+use _ugoite_core::asset;
+use _ugoite_core::entry;
+use _ugoite_core::export;
+use _ugoite_core::form;
+use _ugoite_core::index;
+use _ugoite_core::integrity::{self, FakeIntegrityProvider};
+use _ugoite_core::space;
+use common::setup_operator;
+use opendal::Operator;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::RowAccessor;
+
+#[tokio::test]
+/// REQ-INT-004
+async fn test_export_req_int_004_signed_bundle_detects_tampering() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "export-space", "/tmp").await?;
+    let ws_path = "spaces/export-space";
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Entry",
+            "template": "# Entry\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    let integrity = FakeIntegrityProvider;
+    for (id, body) in [("alpha", "First"), ("beta", "Second")] {
+        entry::create_entry(
+            &op,
+            ws_path,
+            id,
+            &format!("---\nform: Entry\n---\n# {}\n\n## Body\n{}", id, body),
+            "author",
+            &integrity,
+        )
+        .await?;
+    }
+    let info = asset::save_asset(&op, ws_path, "notes.txt", b"attached").await?;
+
+    let bundle = setup_operator()?;
+    let summary = export::export_space(&op, ws_path, &bundle).await?;
+    assert_eq!(summary["space_id"], "export-space");
+    let (key_id, public_key) = integrity::load_signing_public_key(&op, "export-space").await?;
+    assert_eq!(summary["key_id"], key_id.as_str());
+
+    let lines = String::from_utf8(bundle.read("entries.jsonl").await?.to_vec())?;
+    assert!(lines.lines().any(|l| l.contains("\"id\":\"alpha\"")));
+    assert!(bundle.exists(&info.path).await?);
+
+    let report = export::verify_export(&bundle, &public_key).await?;
+    assert_eq!(report["valid"], true, "{}", report);
+
+    // Another space's key cannot vouch for the bundle.
+    space::create_space(&op, "other-space", "/tmp").await?;
+    let (_, other_key) = integrity::load_signing_public_key(&op, "other-space").await?;
+    assert_ne!(other_key, public_key);
+    let report = export::verify_export(&bundle, &other_key).await?;
+    assert_eq!(report["signature_valid"], false);
+    assert_eq!(report["valid"], false);
+
+    // Edited, removed and added files are each reported.
+    let edited = lines.replace("Second", "Forged");
+    bundle.write("entries.jsonl", edited.into_bytes()).await?;
+    bundle.delete(&info.path).await?;
+    bundle.write("extra.json", b"{}".to_vec()).await?;
+    let report = export::verify_export(&bundle, &public_key).await?;
+    assert_eq!(report["valid"], false);
+    assert_eq!(report["signature_valid"], true);
+    assert_eq!(report["modified"], serde_json::json!(["entries.jsonl"]));
+    assert_eq!(report["missing"], serde_json::json!([info.path]));
+    assert_eq!(report["unexpected"], serde_json::json!(["extra.json"]));
+
+    // Rewriting the manifest to match breaks the signature instead.
+    let mut manifest: export::ExportManifest =
+        serde_json::from_slice(&bundle.read("manifest.json").await?.to_vec())?;
+    manifest.items.retain(|item| item.path != info.path);
+    bundle
+        .write("manifest.json", serde_json::to_vec(&manifest)?)
+        .await?;
+    let report = export::verify_export(&bundle, &public_key).await?;
+    assert_eq!(report["signature_valid"], false);
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-INT-007
+async fn test_export_req_int_007_filtered_replica_round_trip() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "replica-space", "/tmp").await?;
+    let ws_path = "spaces/replica-space";
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Entry",
+            "template": "# Entry\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    let integrity = FakeIntegrityProvider;
+    let content = |id: &str, tag: &str, body: &str| {
+        format!(
+            "---\nform: Entry\ntags: [{}]\n---\n# {}\n\n## Body\n{}",
+            tag, id, body
+        )
+    };
+    for (id, tag) in [("a", "site-a"), ("b", "site-b"), ("c", "site-a")] {
+        entry::create_entry(&op, ws_path, id, &content(id, tag, "v1"), "hq", &integrity).await?;
+    }
+
+    let filter = export::SyncFilter {
+        tags: vec!["site-a".to_string()],
+        ..Default::default()
+    };
+    let bundle = setup_operator()?;
+    let summary = export::export_space_filtered(&op, ws_path, &bundle, &filter).await?;
+    assert_eq!(summary["entries"], 2);
+    let lines = String::from_utf8(bundle.read("entries.jsonl").await?.to_vec())?;
+    assert!(!lines.contains("\"id\":\"b\""));
+    let (_, public_key) = integrity::load_signing_public_key(&op, "replica-space").await?;
+    assert_eq!(
+        export::verify_export(&bundle, &public_key).await?["valid"],
+        true
+    );
+
+    // The replica pushes back edits; it cannot touch what it does not hold.
+    let a_rev = entry::get_entry(&op, ws_path, "a").await?["revision_id"].clone();
+    let b_rev = entry::get_entry(&op, ws_path, "b").await?["revision_id"].clone();
+    let changes = [
+        serde_json::json!({"entry_id": "a", "base_revision_id": a_rev, "content": content("a", "site-a", "v2")}),
+        serde_json::json!({"entry_id": "a", "base_revision_id": a_rev, "content": content("a", "site-a", "v3")}),
+        serde_json::json!({"entry_id": "b", "base_revision_id": b_rev, "deleted": true}),
+        serde_json::json!({"entry_id": "d", "content": content("d", "site-a", "new")}),
+    ];
+    let mut jsonl = Vec::new();
+    for change in &changes {
+        jsonl.extend(serde_json::to_vec(change)?);
+        jsonl.push(b'\n');
+    }
+    bundle.write(export::CHANGES_FILE, jsonl).await?;
+    let report = export::apply_replica_changes(
+        &op,
+        ws_path,
+        &bundle,
+        export::CollisionStrategy::Skip,
+        "field-laptop",
+        &integrity,
+    )
+    .await?;
+    assert_eq!(report["applied"], serde_json::json!(["a", "d"]));
+    assert_eq!(report["conflicts"][0]["entry_id"], "a");
+    assert_eq!(report["rejected"][0]["entry_id"], "b");
+    assert_eq!(report["rejected"][0]["reason"], "out_of_filter");
+    assert!(entry::get_entry(&op, ws_path, "b").await.is_ok());
+    let a = entry::get_entry_content(&op, ws_path, "a").await?;
+    assert!(a.markdown.contains("v2"));
+    bundle.delete(export::CHANGES_FILE).await?;
+
+    // Re-exporting tells the replica which entries to drop, and why.
+    let a_rev = entry::get_entry(&op, ws_path, "a").await?["revision_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    entry::update_entry(
+        &op,
+        ws_path,
+        "a",
+        &content("a", "site-b", "moved"),
+        Some(&a_rev),
+        "hq",
+        None,
+        None,
+        &integrity,
+    )
+    .await?;
+    entry::delete_entry(&op, ws_path, "c", false).await?;
+    let summary = export::export_space_filtered(&op, ws_path, &bundle, &filter).await?;
+    assert_eq!(summary["entries"], 1);
+    assert_eq!(
+        summary["tombstones"],
+        serde_json::json!([
+            {"entry_id": "a", "reason": "filtered"},
+            {"entry_id": "c", "reason": "deleted"},
+        ])
+    );
+    assert_eq!(
+        export::verify_export(&bundle, &public_key).await?["valid"],
+        true
+    );
+
+    // Tombstones and the filter are covered by the signature.
+    let mut manifest: export::ExportManifest =
+        serde_json::from_slice(&bundle.read("manifest.json").await?.to_vec())?;
+    manifest.filter = None;
+    bundle
+        .write("manifest.json", serde_json::to_vec(&manifest)?)
+        .await?;
+    assert_eq!(
+        export::verify_export(&bundle, &public_key).await?["signature_valid"],
+        false
+    );
+    assert!(export::apply_replica_changes(
+        &op,
+        ws_path,
+        &bundle,
+        export::CollisionStrategy::Skip,
+        "field-laptop",
+        &integrity,
+    )
+    .await
+    .is_err());
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-INT-008
+async fn test_export_req_int_008_import_collision_strategies() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    let integrity = FakeIntegrityProvider;
+    let page =
+        |id: &str, body: &str| format!("---\nform: Entry\n---\n# {}\n\n## Body\n{}", id, body);
+    for space_id in ["import-src", "import-dst"] {
+        space::create_space(&op, space_id, "/tmp").await?;
+        form::upsert_form(
+            &op,
+            &format!("spaces/{}", space_id),
+            &serde_json::json!({
+                "name": "Entry",
+                "template": "# Entry\n\n## Body\n",
+                "fields": {"Body": {"type": "markdown"}},
+            }),
+        )
+        .await?;
+    }
+    let src = "spaces/import-src";
+    let dst = "spaces/import-dst";
+    form::upsert_form(
+        &op,
+        src,
+        &serde_json::json!({
+            "name": "Task",
+            "template": "# Task\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    entry::create_entry(&op, src, "x", &page("x", "from source"), "a", &integrity).await?;
+    entry::create_entry(&op, src, "y", &page("y", "shared"), "a", &integrity).await?;
+    entry::create_entry(
+        &op,
+        src,
+        "t1",
+        "---\nform: Task\n---\n# t1\n\n## Body\ntodo",
+        "a",
+        &integrity,
+    )
+    .await?;
+    entry::create_entry(&op, dst, "x", &page("x", "local edit"), "b", &integrity).await?;
+    entry::create_entry(&op, dst, "y", &page("y", "shared"), "b", &integrity).await?;
+
+    let bundle = setup_operator()?;
+    export::export_space(&op, src, &bundle).await?;
+
+    let report = export::import_bundle(
+        &op,
+        dst,
+        &bundle,
+        export::CollisionStrategy::ConflictCopy,
+        "importer",
+        &integrity,
+    )
+    .await?;
+    assert_eq!(report["created"], serde_json::json!(["t1"]));
+    assert_eq!(report["unchanged"], 1);
+    assert_eq!(report["failed"], serde_json::json!([]));
+    assert_eq!(report["collisions"][0]["entry_id"], "x");
+    assert_eq!(report["collisions"][0]["resolution"], "conflict_copy");
+    assert_eq!(report["collisions"][0]["copy_id"], "x-conflict");
+    let original = entry::get_entry_content(&op, dst, "x").await?;
+    assert!(original.markdown.contains("local edit"));
+    let copy = entry::get_entry(&op, dst, "x-conflict").await?;
+    assert!(copy["content"].as_str().unwrap().contains("from source"));
+    assert!(copy["links"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|l| l["target"] == "x" && l["kind"] == export::CONFLICT_LINK_KIND));
+
+    // Copies never reuse an id; skip leaves everything alone.
+    let again = export::import_bundle(
+        &op,
+        dst,
+        &bundle,
+        export::CollisionStrategy::ConflictCopy,
+        "importer",
+        &integrity,
+    )
+    .await?;
+    assert_eq!(again["collisions"][0]["copy_id"], "x-conflict-2");
+    assert_eq!(again["unchanged"], 2);
+    let skipped = export::import_bundle(
+        &op,
+        dst,
+        &bundle,
+        export::CollisionStrategy::Skip,
+        "importer",
+        &integrity,
+    )
+    .await?;
+    assert_eq!(skipped["collisions"][0]["resolution"], "skip");
+    assert!(entry::get_entry_content(&op, dst, "x")
+        .await?
+        .markdown
+        .contains("local edit"));
+
+    let overwritten = export::import_bundle(
+        &op,
+        dst,
+        &bundle,
+        export::CollisionStrategy::Overwrite,
+        "importer",
+        &integrity,
+    )
+    .await?;
+    assert_eq!(overwritten["collisions"][0]["resolution"], "overwrite");
+    assert!(entry::get_entry_content(&op, dst, "x")
+        .await?
+        .markdown
+        .contains("from source"));
+    assert!("merge".parse::<export::CollisionStrategy>().is_err());
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-INT-009
+async fn test_export_req_int_009_entry_history_zip() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "history-space", "/tmp").await?;
+    let ws_path = "spaces/history-space";
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Entry",
+            "template": "# Entry\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    let integrity = FakeIntegrityProvider;
+    let content = |body: &str| format!("---\nform: Entry\n---\n# Pump 3\n\n## Body\n{}", body);
+    entry::create_entry(
+        &op,
+        ws_path,
+        "pump-3",
+        &content("installed"),
+        "a",
+        &integrity,
+    )
+    .await?;
+    entry::update_entry(
+        &op,
+        ws_path,
+        "pump-3",
+        &content("replaced seal"),
+        None,
+        "b",
+        None,
+        Some("Seal failure"),
+        &integrity,
+    )
+    .await?;
+
+    let target = setup_operator()?;
+    let report =
+        export::export_entry_history(&op, ws_path, "pump-3", &target, "pump-3.zip").await?;
+    assert_eq!(report["revisions"], 2);
+
+    let zip = target.read("pump-3.zip").await?.to_vec();
+    assert_eq!(&zip[..4], b"PK\x03\x04");
+    let eocd = &zip[zip.len() - 22..];
+    assert_eq!(&eocd[..4], b"PK\x05\x06");
+    // Two revision files plus history.json.
+    assert_eq!(u16::from_le_bytes([eocd[10], eocd[11]]), 3);
+    let text = String::from_utf8_lossy(&zip);
+    assert!(text.contains("0001-"));
+    assert!(text.contains("## Body\ninstalled"));
+    assert!(text.contains("## Body\nreplaced seal"));
+    assert!(text.contains("\"message\": \"Seal failure\""));
+
+    assert!(
+        export::export_entry_history(&op, ws_path, "missing", &target, "x.zip")
+            .await
+            .is_err()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-INT-010
+async fn test_export_req_int_010_query_results_csv_xlsx() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "report-space", "/tmp").await?;
+    let ws_path = "spaces/report-space";
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Inspection",
+            "template": "# Inspection\n\n## Site\n\n## Score\n",
+            "fields": {
+                "Site": {"type": "string"},
+                "Score": {"type": "number"},
+            },
+        }),
+    )
+    .await?;
+    let integrity = FakeIntegrityProvider;
+    for (id, site, score) in [
+        ("insp-1", "North, Gate \"A\"", "7"),
+        ("insp-2", "South", "9"),
+        ("insp-3", "=HYPERLINK(\"http://x\")", "-3"),
+    ] {
+        let content = format!(
+            "---\nform: Inspection\n---\n# {}\n\n## Site\n{}\n\n## Score\n{}\n",
+            id, site, score
+        );
+        entry::create_entry(&op, ws_path, id, &content, "a", &integrity).await?;
+    }
+    let query = "SELECT * FROM Inspection ORDER BY id";
+    assert_eq!(
+        index::execute_sql_query(&op, ws_path, query).await?.len(),
+        3
+    );
+
+    let target = setup_operator()?;
+    let report = export::query_to_csv(&op, ws_path, query, &target, "inspections.csv").await?;
+    assert_eq!(report["rows"], 3);
+    let columns: Vec<&str> = report["columns"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c.as_str().unwrap())
+        .collect();
+    assert!(columns.contains(&"id"));
+    assert!(columns.contains(&"Site"));
+    assert!(!columns.contains(&"properties"));
+
+    let csv = String::from_utf8(target.read("inspections.csv").await?.to_vec())?;
+    let lines: Vec<&str> = csv.split("\r\n").collect();
+    assert_eq!(lines.len(), 5);
+    assert_eq!(lines[4], "");
+    assert!(lines[0].contains("Site"));
+    assert!(lines[1].contains("insp-1"));
+    assert!(lines[1].contains("\"North, Gate \"\"A\"\"\""));
+    assert!(lines[2].contains("South"));
+    assert!(lines[3].contains("\"'=HYPERLINK(\"\"http://x\"\")\""));
+    assert!(!lines[3].contains(",=HYPERLINK"));
+    assert!(lines[3].contains(",-3.0,"));
+    assert_eq!(report["bytes"], csv.len());
+
+    let report = export::query_to_xlsx(&op, ws_path, query, &target, "inspections.xlsx").await?;
+    assert_eq!(report["rows"], 3);
+    let xlsx = target.read("inspections.xlsx").await?.to_vec();
+    assert_eq!(&xlsx[..4], b"PK\x03\x04");
+    let eocd = &xlsx[xlsx.len() - 22..];
+    assert_eq!(u16::from_le_bytes([eocd[10], eocd[11]]), 5);
+    let text = String::from_utf8_lossy(&xlsx);
+    assert!(text.contains("xl/worksheets/sheet1.xml"));
+    assert!(text.contains("North, Gate &quot;A&quot;"));
+    assert!(text.contains(">'=HYPERLINK(&quot;http://x&quot;)<"));
+    assert!(text.contains("<v>-3.0</v>"));
+
+    // Without ORDER BY the rows are written form by form as they are read;
+    // LIMIT still caps the total.
+    let report = export::query_to_csv(
+        &op,
+        ws_path,
+        "SELECT * FROM entries WHERE form = 'Inspection' LIMIT 2",
+        &target,
+        "batched.csv",
+    )
+    .await?;
+    assert_eq!(report["rows"], 2);
+    let csv = String::from_utf8(target.read("batched.csv").await?.to_vec())?;
+    assert_eq!(csv.split("\r\n").count(), 4);
+    assert_eq!(report["bytes"], csv.len());
+    let mut batches = index::SqlBatches::open(&op, ws_path, "SELECT * FROM inspection")
+        .await?
+        .expect("plain query reads in batches");
+    let mut ids = Vec::new();
+    while let Some(batch) = batches.next_batch().await? {
+        ids.extend(
+            batch
+                .iter()
+                .map(|row| row["id"].as_str().unwrap().to_string()),
+        );
+    }
+    assert_eq!(ids, ["insp-1", "insp-2", "insp-3"]);
+    assert!(index::SqlBatches::open(&op, ws_path, query)
+        .await?
+        .is_none());
+
+    assert!(
+        export::query_to_csv(&op, ws_path, "DELETE FROM entries", &target, "x.csv")
+            .await
+            .is_err()
+    );
+    Ok(())
+}
+
+async fn parquet_column(
+    bundle: &Operator,
+    path: &str,
+    column: usize,
+) -> anyhow::Result<Vec<String>> {
+    let reader = SerializedFileReader::new(bundle.read(path).await?.to_bytes())?;
+    let mut values = Vec::new();
+    for row in reader.get_row_iter(None)? {
+        values.push(row?.get_string(column)?.clone());
+    }
+    Ok(values)
+}
+
+#[tokio::test]
+/// REQ-INT-012
+async fn test_export_req_int_012_offline_bundle_and_delta() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "offline-space", "/tmp").await?;
+    let ws_path = "spaces/offline-space";
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Entry",
+            "template": "# Entry\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    let integrity = FakeIntegrityProvider;
+    let content = |id: &str, tag: &str, body: &str| {
+        format!(
+            "---\nform: Entry\ntags: [{}]\n---\n# {}\n\n## Body\n{}",
+            tag, id, body
+        )
+    };
+    for (id, tag, body) in [
+        ("a", "site-a", "valve inspection"),
+        ("b", "site-b", "pump overhaul"),
+        ("c", "site-a", "filter swap"),
+    ] {
+        entry::create_entry(&op, ws_path, id, &content(id, tag, body), "hq", &integrity).await?;
+    }
+
+    let filter = export::SyncFilter {
+        tags: vec!["site-a".to_string()],
+        ..Default::default()
+    };
+    let bundle = setup_operator()?;
+    let report = export::offline_bundle(&op, ws_path, &filter, &bundle).await?;
+    assert_eq!(report["entries"], 2);
+    assert_eq!(
+        parquet_column(&bundle, "entries.parquet", 0).await?,
+        vec!["a", "c"]
+    );
+    let tokens = parquet_column(&bundle, "postings.parquet", 0).await?;
+    let entry_ids = parquet_column(&bundle, "postings.parquet", 1).await?;
+    let valve = tokens.iter().position(|token| token == "valve").unwrap();
+    assert_eq!(entry_ids[valve], "a");
+    assert!(!tokens.iter().any(|token| token == "pump"));
+    let manifest: serde_json::Value =
+        serde_json::from_slice(&bundle.read("offline.json").await?.to_vec())?;
+    assert_eq!(manifest["format"], export::OFFLINE_FORMAT);
+    assert_eq!(manifest["cursor"], report["cursor"]);
+    assert_eq!(manifest["analyzer"]["language"], "auto");
+
+    let cursor = report["cursor"].as_f64().unwrap();
+    let idle = export::offline_delta(&op, ws_path, &filter, cursor).await?;
+    assert_eq!(idle["entries"], serde_json::json!([]));
+    assert_eq!(idle["removed"], serde_json::json!([]));
+    assert_eq!(idle["cursor"], report["cursor"]);
+
+    let c_rev = entry::get_entry(&op, ws_path, "c").await?["revision_id"].clone();
+    entry::update_entry(
+        &op,
+        ws_path,
+        "c",
+        &content("c", "site-b", "filter swap"),
+        c_rev.as_str(),
+        "hq",
+        None,
+        None,
+        &integrity,
+    )
+    .await?;
+    entry::delete_entry(&op, ws_path, "a", false).await?;
+    entry::create_entry(
+        &op,
+        ws_path,
+        "d",
+        &content("d", "site-a", "gasket leak"),
+        "hq",
+        &integrity,
+    )
+    .await?;
+
+    let delta = export::offline_delta(&op, ws_path, &filter, cursor).await?;
+    assert_eq!(delta["entries"].as_array().unwrap().len(), 1);
+    assert_eq!(delta["entries"][0]["id"], "d");
+    assert_eq!(delta["entries"][0]["tokens"]["gasket"], 1);
+    assert_eq!(
+        delta["removed"],
+        serde_json::json!([
+            {"entry_id": "a", "reason": "deleted"},
+            {"entry_id": "c", "reason": "filtered"},
+        ])
+    );
+    let next = delta["cursor"].as_f64().unwrap();
+    assert!(next > cursor);
+    let caught_up = export::offline_delta(&op, ws_path, &filter, next).await?;
+    assert_eq!(caught_up["entries"], serde_json::json!([]));
+    assert_eq!(caught_up["removed"], serde_json::json!([]));
+    Ok(())
+}
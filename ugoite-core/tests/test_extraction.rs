@@ -0,0 +1,138 @@
+mod common;
+use _ugoite_core::asset;
+use _ugoite_core::entry;
+use _ugoite_core::extraction::{self, AssetContext, ContentExtractor};
+use _ugoite_core::form;
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::search;
+use _ugoite_core::space;
+use common::setup_operator;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+struct FakeOcr;
+
+impl ContentExtractor for FakeOcr {
+    fn accepts(&self, ctx: &AssetContext) -> bool {
+        extraction::content_type_matches("image/*", ctx.content_type)
+            || ctx.filename.ends_with(".pdf")
+    }
+
+    fn extract(&self, ctx: &AssetContext, content: &[u8]) -> Result<Option<String>, String> {
+        if ctx.filename.ends_with(".pdf") {
+            return Err("encrypted document".to_string());
+        }
+        assert!(content.starts_with(b"\x89PNG"));
+        Ok(Some(format!("INVOICE 4711 total due ({})", ctx.filename)))
+    }
+}
+
+/// Blocks until the test signals it, which can only happen if extraction
+/// leaves the (single-threaded) test runtime free to run other tasks.
+struct WaitingExtractor(Mutex<mpsc::Receiver<()>>);
+
+impl ContentExtractor for WaitingExtractor {
+    fn accepts(&self, ctx: &AssetContext) -> bool {
+        ctx.filename.ends_with(".tiff")
+    }
+
+    fn extract(&self, _ctx: &AssetContext, _content: &[u8]) -> Result<Option<String>, String> {
+        let receiver = self.0.lock().map_err(|e| e.to_string())?;
+        match receiver.recv_timeout(Duration::from_secs(10)) {
+            Ok(()) => Ok(Some("unblocked".to_string())),
+            Err(_) => Err("extraction blocked the runtime".to_string()),
+        }
+    }
+}
+
+#[tokio::test]
+/// REQ-ASSET-005
+async fn test_extraction_req_asset_005_extracted_text_is_searchable() -> anyhow::Result<()> {
+    assert!(extraction::content_type_matches(
+        "image/*",
+        Some("image/png")
+    ));
+    assert!(!extraction::content_type_matches(
+        "image/*",
+        Some("application/pdf")
+    ));
+    assert!(extraction::content_type_matches("*", None));
+
+    extraction::register_content_extractor("fake-ocr", Arc::new(FakeOcr))?;
+    let op = setup_operator()?;
+    space::create_space(&op, "scan-space", "/tmp").await?;
+    let ws_path = "spaces/scan-space";
+
+    let scan = asset::save_asset(&op, ws_path, "scan.png", b"\x89PNG\r\n\x1a\nscan").await?;
+    let notes = asset::save_asset(&op, ws_path, "notes.txt", b"plain notes").await?;
+    let locked = asset::save_asset(&op, ws_path, "locked.pdf", b"%PDF-1.7 locked").await?;
+
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Expense",
+            "template": "# Expense\n\n## Receipt\n",
+            "fields": {"Receipt": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    let content = format!(
+        "---\nform: Expense\n---\n# Taxi\n\n## Receipt\n![scan]({})\n",
+        scan.link
+    );
+    entry::create_entry(&op, ws_path, "taxi", &content, "a", &FakeIntegrityProvider).await?;
+
+    let text = extraction::get_asset_text(&op, ws_path, &scan.id).await?;
+    assert_eq!(text["extractor"], "fake-ocr");
+    assert_eq!(text["text"], "INVOICE 4711 total due (scan.png)");
+    assert_eq!(text["owners"], serde_json::json!(["taxi"]));
+    assert!(extraction::get_asset_text(&op, ws_path, &notes.id)
+        .await
+        .is_err());
+    let failed = extraction::get_asset_text(&op, ws_path, &locked.id).await?;
+    assert_eq!(failed["error"], "encrypted document");
+    assert!(failed["text"].is_null());
+
+    let mut hits: Vec<String> = search::search_entries(&op, ws_path, "invoice total")
+        .await?
+        .into_iter()
+        .map(|hit| hit.id)
+        .collect();
+    hits.sort();
+    let mut expected = vec!["taxi".to_string(), scan.id.clone()];
+    expected.sort();
+    assert_eq!(hits, expected);
+
+    // Hosts that extract out of band store the text themselves.
+    extraction::set_asset_text(&op, ws_path, &locked.id, "sandbox-ocr", "Quarterly audit").await?;
+    let hits = search::search_entries(&op, ws_path, "quarterly").await?;
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].id, locked.id);
+
+    // Extraction runs off the runtime: the upload waits on it while another
+    // task on the same thread sends the signal it is blocked on.
+    let (signal, receiver) = mpsc::channel();
+    extraction::register_content_extractor(
+        "waiting",
+        Arc::new(WaitingExtractor(Mutex::new(receiver))),
+    )?;
+    let (slow, _) = tokio::join!(
+        asset::save_asset(&op, ws_path, "slow.tiff", b"II*\0slow"),
+        async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            signal.send(()).unwrap();
+        }
+    );
+    let slow = extraction::get_asset_text(&op, ws_path, &slow?.id).await?;
+    assert_eq!(slow["extractor"], "waiting");
+    assert_eq!(slow["text"], "unblocked");
+
+    extraction::clear_content_extractors()?;
+    let later = asset::save_asset(&op, ws_path, "later.png", b"\x89PNG\r\n\x1a\nlater").await?;
+    assert!(extraction::get_asset_text(&op, ws_path, &later.id)
+        .await
+        .is_err());
+    Ok(())
+}
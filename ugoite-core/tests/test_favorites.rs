@@ -0,0 +1,121 @@
+mod common;
+use _ugoite_core::favorites::{self, FavoriteKind};
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::{entry, form, space};
+use common::setup_operator;
+
+fn ids(list: &[favorites::Favorite]) -> Vec<&str> {
+    list.iter().map(|f| f.entry_id.as_str()).collect()
+}
+
+#[tokio::test]
+/// REQ-ENTRY-015
+async fn test_favorites_req_entry_015_scoped_per_user_and_kind() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "favorites-space", "/tmp").await?;
+    let ws_path = "spaces/favorites-space";
+
+    assert!(
+        favorites::list_favorites(&op, ws_path, "alice", FavoriteKind::Favorite)
+            .await?
+            .is_empty()
+    );
+
+    assert!(favorites::add_favorite(&op, ws_path, "alice", FavoriteKind::Favorite, "a").await?);
+    assert!(favorites::add_favorite(&op, ws_path, "alice", FavoriteKind::Favorite, "b").await?);
+    assert!(!favorites::add_favorite(&op, ws_path, "alice", FavoriteKind::Favorite, "a").await?);
+    favorites::add_favorite(&op, ws_path, "alice", FavoriteKind::Pin, "c").await?;
+    favorites::add_favorite(&op, ws_path, "bob", FavoriteKind::Favorite, "c").await?;
+
+    // Newest first; re-adding does not reorder.
+    let alice = favorites::list_favorites(&op, ws_path, "alice", FavoriteKind::Favorite).await?;
+    assert_eq!(ids(&alice), ["b", "a"]);
+    let pins = favorites::list_favorites(&op, ws_path, "alice", FavoriteKind::Pin).await?;
+    assert_eq!(ids(&pins), ["c"]);
+    let bob = favorites::list_favorites(&op, ws_path, "bob", FavoriteKind::Favorite).await?;
+    assert_eq!(ids(&bob), ["c"]);
+
+    assert!(favorites::remove_favorite(&op, ws_path, "alice", FavoriteKind::Favorite, "a").await?);
+    assert!(!favorites::remove_favorite(&op, ws_path, "alice", FavoriteKind::Favorite, "a").await?);
+    let alice = favorites::list_favorites(&op, ws_path, "alice", FavoriteKind::Favorite).await?;
+    assert_eq!(ids(&alice), ["b"]);
+
+    // Everything lives in one small file per space.
+    let stored: serde_json::Value = serde_json::from_slice(
+        &op.read("spaces/favorites-space/favorites.json")
+            .await?
+            .to_vec(),
+    )?;
+    assert_eq!(stored["bob"]["favorite"][0]["entry_id"], "c");
+
+    assert!("star".parse::<FavoriteKind>().is_err());
+    assert!(
+        favorites::add_favorite(&op, ws_path, "", FavoriteKind::Pin, "a")
+            .await
+            .is_err()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-ENTRY-036
+async fn test_favorites_req_entry_036_space_pins_in_order() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "pinned-space", "/tmp").await?;
+    let ws_path = "spaces/pinned-space";
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Note",
+            "template": "# Note\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    for id in ["a", "b", "c"] {
+        let content = format!("---\nform: Note\n---\n# {}\n\n## Body\ntext", id);
+        entry::create_entry(&op, ws_path, id, &content, "alice", &FakeIntegrityProvider).await?;
+    }
+    let pinned_ids = |list: Vec<favorites::PinnedEntry>| -> Vec<String> {
+        list.into_iter().map(|p| p.entry_id).collect()
+    };
+
+    favorites::pin_entry(&op, ws_path, "a", None, "alice").await?;
+    favorites::pin_entry(&op, ws_path, "b", None, "bob").await?;
+    let pinned = favorites::pin_entry(&op, ws_path, "c", Some(0), "alice").await?;
+    assert_eq!(pinned_ids(pinned), ["c", "a", "b"]);
+    // Pinning again keeps the place unless a position is given.
+    let pinned = favorites::pin_entry(&op, ws_path, "a", None, "bob").await?;
+    assert_eq!(pinned_ids(pinned.clone()), ["c", "a", "b"]);
+    assert_eq!(pinned[1].pinned_by, "alice");
+    let pinned = favorites::pin_entry(&op, ws_path, "c", Some(10), "alice").await?;
+    assert_eq!(pinned_ids(pinned), ["a", "b", "c"]);
+    assert!(favorites::pin_entry(&op, ws_path, "missing", None, "alice")
+        .await
+        .is_err());
+
+    // Pinning writes no revision.
+    let history = entry::get_entry_history(&op, ws_path, "a").await?;
+    assert_eq!(history["revisions"].as_array().unwrap().len(), 1);
+
+    // Deleted entries are hidden but keep their place.
+    entry::delete_entry(&op, ws_path, "b", false).await?;
+    assert_eq!(
+        pinned_ids(favorites::list_pinned(&op, ws_path).await?),
+        ["a", "c"]
+    );
+    entry::undelete_entry(&op, ws_path, "b").await?;
+    assert_eq!(
+        pinned_ids(favorites::list_pinned(&op, ws_path).await?),
+        ["a", "b", "c"]
+    );
+
+    assert!(favorites::unpin_entry(&op, ws_path, "a").await?);
+    assert!(!favorites::unpin_entry(&op, ws_path, "a").await?);
+    assert_eq!(
+        pinned_ids(favorites::list_pinned(&op, ws_path).await?),
+        ["b", "c"]
+    );
+    Ok(())
+}
@@ -0,0 +1,106 @@
+mod common;
+use _ugoite_core::entry;
+use _ugoite_core::folder;
+use _ugoite_core::form;
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::space;
+use common::setup_operator;
+
+async fn setup_space(op: &opendal::Operator, ws_path: &str, ids: &[&str]) -> anyhow::Result<()> {
+    let form_def = serde_json::json!({
+        "name": "Entry",
+        "template": "# Entry\n\n## Body\n",
+        "fields": {"Body": {"type": "markdown"}},
+    });
+    form::upsert_form(op, ws_path, &form_def).await?;
+    for id in ids {
+        let content = format!("---\nform: Entry\n---\n# {}\n\n## Body\nText", id);
+        entry::create_entry(op, ws_path, id, &content, "author", &FakeIntegrityProvider).await?;
+    }
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-ENTRY-019
+async fn test_folder_req_entry_019_move_and_list_children() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "folder-space", "/tmp").await?;
+    let ws_path = "spaces/folder-space";
+    setup_space(&op, ws_path, &["pump-1", "pump-2", "notes"]).await?;
+
+    let created = folder::create_folder(&op, ws_path, "/projects//pumps/").await?;
+    assert_eq!(created.path, "projects/pumps");
+    folder::move_entry(&op, ws_path, "pump-1", Some("projects/pumps")).await?;
+    folder::move_entry(&op, ws_path, "pump-2", Some("projects/pumps")).await?;
+    assert!(folder::move_entry(&op, ws_path, "notes", Some("missing"))
+        .await
+        .is_err());
+    assert!(folder::move_entry(&op, ws_path, "ghost", Some("projects"))
+        .await
+        .is_err());
+
+    let root = folder::list_children(&op, ws_path, "").await?;
+    assert_eq!(root.folders, vec!["projects"]);
+    assert_eq!(root.entries, vec!["notes"]);
+    let projects = folder::list_children(&op, ws_path, "projects").await?;
+    assert_eq!(projects.folders, vec!["projects/pumps"]);
+    assert!(projects.entries.is_empty());
+    let pumps = folder::list_children(&op, ws_path, "projects/pumps").await?;
+    assert_eq!(pumps.entries, vec!["pump-1", "pump-2"]);
+
+    // Deleted entries drop out of listings but keep their placement.
+    entry::delete_entry(&op, ws_path, "pump-2", false).await?;
+    folder::move_entry(&op, ws_path, "pump-1", None).await?;
+    let pumps = folder::list_children(&op, ws_path, "projects/pumps").await?;
+    assert!(pumps.entries.is_empty());
+    assert!(folder::delete_folder(&op, ws_path, "projects/pumps")
+        .await
+        .is_err());
+    let root = folder::list_children(&op, ws_path, "").await?;
+    assert_eq!(root.entries, vec!["notes", "pump-1"]);
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-ENTRY-019
+async fn test_folder_req_entry_019_move_folder_and_resolve_paths() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "folder-paths", "/tmp").await?;
+    let ws_path = "spaces/folder-paths";
+    setup_space(&op, ws_path, &["pump-1"]).await?;
+
+    folder::create_folder(&op, ws_path, "projects/pumps").await?;
+    folder::move_entry(&op, ws_path, "pump-1", Some("projects/pumps")).await?;
+    assert_eq!(
+        folder::resolve_entry_path(&op, ws_path, "projects/pumps/pump-1").await?,
+        "pump-1"
+    );
+    assert!(folder::resolve_entry_path(&op, ws_path, "pump-1")
+        .await
+        .is_err());
+    assert!(folder::normalize_folder_path("a/../b").is_err());
+
+    assert!(
+        folder::move_folder(&op, ws_path, "projects", "projects/inner")
+            .await
+            .is_err()
+    );
+    let moved = folder::move_folder(&op, ws_path, "projects", "archive/2024").await?;
+    assert_eq!(moved.path, "archive/2024");
+    assert_eq!(
+        folder::entry_folder(&op, ws_path, "pump-1").await?,
+        "archive/2024/pumps"
+    );
+    assert_eq!(
+        folder::resolve_entry_path(&op, ws_path, "archive/2024/pumps/pump-1").await?,
+        "pump-1"
+    );
+    let root = folder::list_children(&op, ws_path, "").await?;
+    assert_eq!(root.folders, vec!["archive"]);
+    assert!(root.entries.is_empty());
+
+    folder::create_folder(&op, ws_path, "empty").await?;
+    folder::delete_folder(&op, ws_path, "empty").await?;
+    assert!(folder::list_children(&op, ws_path, "empty").await.is_err());
+    Ok(())
+}
@@ -1,7 +1,11 @@
 mod common;
-use _ugoite_core::form;
+use _ugoite_core::hooks::{self, FormHook, WriteContext, WriteEvent};
+use _ugoite_core::integrity::FakeIntegrityProvider;
 use _ugoite_core::space;
+use _ugoite_core::{entry, form};
 use common::setup_operator;
+use serde_json::{Map, Value};
+use std::sync::{Arc, Mutex};
 
 #[tokio::test]
 /// REQ-FORM-002
@@ -135,3 +139,769 @@ async fn test_form_req_form_007_row_reference_requires_target() -> anyhow::Resul
 
     Ok(())
 }
+
+/// Normalizes ticket status, refuses closing without a resolution, sets an
+/// invalid priority on urgent tickets and logs writes.
+struct TicketRules {
+    written: Mutex<Vec<(WriteEvent, String)>>,
+}
+
+impl FormHook for TicketRules {
+    fn before_write(
+        &self,
+        _ctx: &WriteContext,
+        fields: &mut Map<String, Value>,
+    ) -> Result<(), String> {
+        if let Some(Value::String(status)) = fields.get_mut("Status") {
+            *status = status.to_lowercase();
+        }
+        let closed = fields.get("Status").and_then(|v| v.as_str()) == Some("closed");
+        if closed && !fields.contains_key("Resolution") {
+            return Err("closed tickets need a Resolution".to_string());
+        }
+        if fields.get("Status").and_then(|v| v.as_str()) == Some("urgent") {
+            fields.insert("Priority".to_string(), Value::String("high".to_string()));
+        }
+        Ok(())
+    }
+
+    fn after_write(&self, ctx: &WriteContext, revision_id: &str, _fields: &Value) {
+        self.written
+            .lock()
+            .unwrap()
+            .push((ctx.event, revision_id.to_string()));
+    }
+}
+
+#[tokio::test]
+/// REQ-FORM-008
+async fn test_form_req_form_008_lifecycle_hooks() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "hook-space", "/tmp").await?;
+    let ws_path = "spaces/hook-space";
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "HookTicket",
+            "template": "# HookTicket\n\n## Status\n\n## Resolution\n",
+            "fields": {
+                "Status": {"type": "string"},
+                "Resolution": {"type": "string"},
+                "Priority": {"type": "integer"},
+            },
+            "hooks": ["ticket_rules"],
+        }),
+    )
+    .await?;
+    let rules = Arc::new(TicketRules {
+        written: Mutex::new(Vec::new()),
+    });
+    hooks::register_form_hook(ws_path, "HookTicket", "ticket_rules", rules.clone())?;
+    let integrity = FakeIntegrityProvider;
+
+    entry::create_entry(
+        &op,
+        ws_path,
+        "t-1",
+        "---\nform: HookTicket\n---\n# Printer\n\n## Status\nOPEN",
+        "author",
+        &integrity,
+    )
+    .await?;
+    let created = entry::get_entry(&op, ws_path, "t-1").await?;
+    assert_eq!(created["sections"]["Status"], "open");
+
+    let invalid = entry::update_entry(
+        &op,
+        ws_path,
+        "t-1",
+        "---\nform: HookTicket\n---\n# Printer\n\n## Status\nUrgent",
+        None,
+        "author",
+        None,
+        None,
+        &integrity,
+    )
+    .await
+    .expect_err("fields set by a hook are validated");
+    assert!(invalid.to_string().contains("Priority"), "{invalid}");
+
+    let rejected = entry::update_entry(
+        &op,
+        ws_path,
+        "t-1",
+        "---\nform: HookTicket\n---\n# Printer\n\n## Status\nClosed",
+        None,
+        "author",
+        None,
+        None,
+        &integrity,
+    )
+    .await
+    .expect_err("closing without a resolution must be rejected");
+    assert!(rejected
+        .to_string()
+        .contains("closed tickets need a Resolution"));
+    assert_eq!(
+        entry::get_entry(&op, ws_path, "t-1").await?["revision_id"],
+        created["revision_id"]
+    );
+
+    let updated = entry::update_entry(
+        &op,
+        ws_path,
+        "t-1",
+        "---\nform: HookTicket\n---\n# Printer\n\n## Status\nClosed\n\n## Resolution\nToner",
+        None,
+        "author",
+        None,
+        None,
+        &integrity,
+    )
+    .await?;
+    assert_eq!(updated["sections"]["Status"], "closed");
+
+    let restored = entry::restore_entry(
+        &op,
+        ws_path,
+        "t-1",
+        created["revision_id"].as_str().unwrap(),
+        "author",
+        None,
+        &integrity,
+    )
+    .await?;
+
+    let written = rules.written.lock().unwrap().clone();
+    assert_eq!(written.len(), 3);
+    assert_eq!(written[0].0, WriteEvent::Create);
+    assert_eq!(
+        written[1],
+        (
+            WriteEvent::Update,
+            updated["revision_id"].as_str().unwrap().to_string()
+        )
+    );
+    assert_eq!(
+        written[2],
+        (
+            WriteEvent::Restore,
+            restored["revision_id"].as_str().unwrap().to_string()
+        )
+    );
+
+    // Hooks belong to one space; a form declaring a hook this process has
+    // not registered for its space refuses writes.
+    space::create_space(&op, "other-hook-space", "/tmp").await?;
+    let other_path = "spaces/other-hook-space";
+    form::upsert_form(
+        &op,
+        other_path,
+        &form::get_form(&op, ws_path, "HookTicket").await?,
+    )
+    .await?;
+    let unregistered = entry::create_entry(
+        &op,
+        other_path,
+        "t-2",
+        "---\nform: HookTicket\n---\n# Scanner\n\n## Status\nOPEN",
+        "author",
+        &integrity,
+    )
+    .await
+    .expect_err("declared hooks must be registered");
+    assert!(unregistered.to_string().contains("ticket_rules"));
+    assert_eq!(rules.written.lock().unwrap().len(), 3);
+
+    hooks::clear_form_hooks(ws_path, "HookTicket")?;
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-FORM-012
+async fn test_form_req_form_012_field_help_and_deprecation() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "deprecation-space", "/tmp").await?;
+    let ws_path = "spaces/deprecation-space";
+    let integrity = FakeIntegrityProvider;
+
+    let bad = serde_json::json!({
+        "name": "Asset",
+        "fields": {"Cost": {"type": "double", "deprecated": true, "replaced_by": "Missing"}},
+    });
+    assert!(form::upsert_form(&op, ws_path, &bad).await.is_err());
+
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Asset",
+            "template": "# Asset\n\n## Cost\n\n## CostUSD\n\n## Notes\n",
+            "fields": {
+                "Cost": {
+                    "type": "double",
+                    "description": "Purchase cost in the local currency.",
+                    "deprecated": true,
+                    "replaced_by": "CostUSD",
+                },
+                "CostUSD": {"type": "double", "description": "Purchase cost in US dollars."},
+                "Notes": {"type": "markdown"},
+            },
+        }),
+    )
+    .await?;
+    let asset_form = form::get_form(&op, ws_path, "Asset").await?;
+    assert_eq!(asset_form["fields"]["Cost"]["deprecated"], true);
+    assert_eq!(asset_form["fields"]["Cost"]["replaced_by"], "CostUSD");
+    assert_eq!(
+        asset_form["fields"]["CostUSD"]["description"],
+        "Purchase cost in US dollars."
+    );
+
+    // Writing a deprecated field succeeds with a warning.
+    let created = entry::create_entry(
+        &op,
+        ws_path,
+        "pump",
+        "---\nform: Asset\n---\n# Pump\n\n## Cost\n120\n\n## Notes\nold",
+        "author",
+        &integrity,
+    )
+    .await?;
+    assert_eq!(created.warnings.len(), 1);
+    assert_eq!(created.warnings[0]["code"], "deprecated_field_replaced");
+    assert_eq!(created.warnings[0]["field"], "Cost");
+
+    // Keeping the old value is not a new write to the field.
+    let pump = entry::get_entry(&op, ws_path, "pump").await?;
+    let updated = entry::update_entry(
+        &op,
+        ws_path,
+        "pump",
+        "---\nform: Asset\n---\n# Pump\n\n## Cost\n120\n\n## CostUSD\n80\n\n## Notes\nnew",
+        pump["revision_id"].as_str(),
+        "author",
+        None,
+        None,
+        &integrity,
+    )
+    .await?;
+    assert!(updated.get("warnings").is_none());
+    let updated = entry::update_entry(
+        &op,
+        ws_path,
+        "pump",
+        "---\nform: Asset\n---\n# Pump\n\n## Cost\n130\n\n## CostUSD\n80\n\n## Notes\nnew",
+        updated["revision_id"].as_str(),
+        "author",
+        None,
+        None,
+        &integrity,
+    )
+    .await?;
+    assert_eq!(updated["warnings"][0]["field"], "Cost");
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-FORM-013
+async fn test_form_req_form_013_field_order_and_groups_shape_rendering() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "layout-space", "/tmp").await?;
+    let ws_path = "spaces/layout-space";
+    let integrity = FakeIntegrityProvider;
+
+    let bad = serde_json::json!({
+        "name": "Inspection",
+        "fields": {"Site": {"type": "string", "order": "first"}},
+    });
+    assert!(form::upsert_form(&op, ws_path, &bad).await.is_err());
+
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Inspection",
+            "fields": {
+                "Site": {"type": "string", "order": 1},
+                "Summary": {"type": "markdown", "order": 2},
+                "Score": {"type": "integer", "group": "Findings", "order": 4},
+                "Defects": {"type": "list", "group": "Findings", "order": 3},
+                "Inspector": {"type": "string", "group": "Sign-off"},
+            },
+        }),
+    )
+    .await?;
+    let inspection = form::get_form(&op, ws_path, "Inspection").await?;
+    assert_eq!(inspection["fields"]["Score"]["group"], "Findings");
+    assert_eq!(
+        inspection["template"],
+        "# Inspection\n\n## Site\n\n## Summary\n\n### Findings\n\n## Defects\n\n## Score\n\n### Sign-off\n\n## Inspector\n\n"
+    );
+
+    let content = "---\nform: Inspection\n---\n# Bridge\n\n## Inspector\nAoi\n\n## Score\n7\n\n## Site\nNorth\n\n## Defects\n- rust\n\n## Summary\nFine";
+    entry::create_entry(&op, ws_path, "bridge", content, "author", &integrity).await?;
+    let bridge = entry::get_entry(&op, ws_path, "bridge").await?;
+    let rendered = bridge["content"].as_str().unwrap_or_default();
+    assert!(rendered.ends_with(
+        "# Bridge\n\n## Site\nNorth\n\n## Summary\nFine\n\n### Findings\n\n## Defects\n- rust\n\n## Score\n7\n\n### Sign-off\n\n## Inspector\nAoi"
+    ));
+
+    // Group subheadings are layout only; the rendered markdown reads back
+    // to the same fields.
+    let updated = entry::update_entry(
+        &op,
+        ws_path,
+        "bridge",
+        rendered,
+        bridge["revision_id"].as_str(),
+        "author",
+        None,
+        None,
+        &integrity,
+    )
+    .await?;
+    assert_eq!(updated["sections"], bridge["sections"]);
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-FORM-014
+async fn test_form_req_form_014_migration_field_lineage() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "lineage-space", "/tmp").await?;
+    let ws_path = "spaces/lineage-space";
+    let integrity = FakeIntegrityProvider;
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Reading",
+            "fields": {"Site": {"type": "string"}, "Pressure": {"type": "string"}},
+        }),
+    )
+    .await?;
+    for (id, site, pressure) in [("r1", "North", "250"), ("r2", "South", "400")] {
+        let content = format!(
+            "---\nform: Reading\n---\n# {}\n\n## Site\n{}\n\n## Pressure\n{}\n",
+            id, site, pressure
+        );
+        entry::create_entry(&op, ws_path, id, &content, "alice", &integrity).await?;
+    }
+
+    // v2 renames Pressure to a numeric PressureKpa.
+    let v2 = serde_json::json!({
+        "name": "Reading",
+        "version": 2,
+        "fields": {"Site": {"type": "string"}, "PressureKpa": {"type": "double"}},
+    });
+    let migrated = form::migrate_form(
+        &op,
+        ws_path,
+        &v2,
+        Some(serde_json::json!({"PressureKpa": {"from": "Pressure", "transform": "to_number"}})),
+        &integrity,
+    )
+    .await?;
+    assert_eq!(migrated, 2);
+
+    // v3 converts to pascals and backfills a constant.
+    let v3 = serde_json::json!({
+        "name": "Reading",
+        "version": 3,
+        "fields": {
+            "Site": {"type": "string"},
+            "PressurePa": {"type": "double"},
+            "Status": {"type": "string"},
+        },
+    });
+    form::migrate_form(
+        &op,
+        ws_path,
+        &v3,
+        Some(serde_json::json!({
+            "PressurePa": {"from": "PressureKpa", "transform": "scale", "factor": 1000},
+            "Status": "checked",
+        })),
+        &integrity,
+    )
+    .await?;
+    let r1 = entry::get_entry(&op, ws_path, "r1").await?;
+    assert_eq!(
+        r1["sections"]["PressurePa"]
+            .as_str()
+            .unwrap()
+            .parse::<f64>()?,
+        250000.0
+    );
+    assert_eq!(r1["sections"]["Status"], "checked");
+
+    // A value the transform cannot convert stops the migration before any write.
+    let v4 = serde_json::json!({
+        "name": "Reading",
+        "version": 4,
+        "fields": {
+            "SiteCode": {"type": "double"},
+            "PressurePa": {"type": "double"},
+            "Status": {"type": "string"},
+        },
+    });
+    let err = form::migrate_form(
+        &op,
+        ws_path,
+        &v4,
+        Some(serde_json::json!({"SiteCode": {"from": "Site", "transform": "to_number"}})),
+        &integrity,
+    )
+    .await
+    .unwrap_err();
+    assert!(format!("{:#}", err).contains("Site"));
+    assert_eq!(form::get_form(&op, ws_path, "Reading").await?["version"], 3);
+    assert!(form::migrate_form(
+        &op,
+        ws_path,
+        &v4,
+        Some(serde_json::json!({"SiteCode": {"from": "Missing"}})),
+        &integrity,
+    )
+    .await
+    .is_err());
+
+    let lineage = form::field_lineage(&op, ws_path, "Reading", "PressurePa").await?;
+    assert_eq!(lineage["origin"], "Pressure");
+    let steps = lineage["steps"].as_array().unwrap();
+    assert_eq!(steps.len(), 2);
+    assert_eq!(steps[0]["from"], "PressureKpa");
+    assert_eq!(steps[0]["transform"], "scale(1000)");
+    assert_eq!(steps[0]["version"], 3);
+    assert_eq!(steps[1]["field"], "PressureKpa");
+    assert_eq!(steps[1]["from"], "Pressure");
+    assert_eq!(steps[1]["transform"], "to_number");
+    assert_eq!(steps[1]["version"], 2);
+
+    let status = form::field_lineage(&op, ws_path, "Reading", "Status").await?;
+    assert!(status["origin"].is_null());
+    assert_eq!(status["steps"][0]["transform"], "constant");
+    assert_eq!(status["steps"][0]["value"], "checked");
+    let site = form::field_lineage(&op, ws_path, "Reading", "Site").await?;
+    assert_eq!(site["origin"], "Site");
+    assert_eq!(site["steps"], serde_json::json!([]));
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-FORM-015
+async fn test_form_req_form_015_migration_dry_run_report() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "dry-run-space", "/tmp").await?;
+    let ws_path = "spaces/dry-run-space";
+    let integrity = FakeIntegrityProvider;
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Reading",
+            "fields": {
+                "Site": {"type": "string"},
+                "Pressure": {"type": "string"},
+                "Notes": {"type": "string"},
+            },
+        }),
+    )
+    .await?;
+    for (id, pressure, notes) in [
+        ("r1", "250", Some("gauge A")),
+        ("r2", "high", None),
+        ("r3", "400", None),
+    ] {
+        let mut content = format!(
+            "---\nform: Reading\n---\n# {}\n\n## Site\nNorth\n\n## Pressure\n{}\n",
+            id, pressure
+        );
+        if let Some(notes) = notes {
+            content.push_str(&format!("\n## Notes\n{}\n", notes));
+        }
+        entry::create_entry(&op, ws_path, id, &content, "alice", &integrity).await?;
+    }
+
+    let v2 = serde_json::json!({
+        "name": "Reading",
+        "version": 2,
+        "fields": {"Site": {"type": "string"}, "PressureKpa": {"type": "double"}},
+    });
+    let strategies =
+        serde_json::json!({"PressureKpa": {"from": "Pressure", "transform": "to_number"}});
+    let report = form::preview_migration(&op, ws_path, &v2, Some(strategies.clone()), 1).await?;
+    assert_eq!(report["dry_run"], true);
+    assert_eq!(report["entries"], 3);
+    assert_eq!(report["changed"], 2);
+    assert_eq!(report["revised"], 2);
+    assert_eq!(report["added_fields"], serde_json::json!(["PressureKpa"]));
+    assert_eq!(report["dropped_fields"]["Pressure"], 2);
+    assert_eq!(report["dropped_fields"]["Notes"], 1);
+    let failures = report["failures"].as_array().unwrap();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0]["entry_id"], "r2");
+    assert!(failures[0]["error"].as_str().unwrap().contains("Pressure"));
+    let cast = &report["cast_fields"][0];
+    assert_eq!(cast["field"], "PressureKpa");
+    assert_eq!(cast["from"], "Pressure");
+    assert_eq!(cast["from_type"], "string");
+    assert_eq!(cast["to_type"], "double");
+    assert_eq!(cast["transform"], "to_number");
+    assert_eq!(cast["entries"], 3);
+    let samples = report["samples"].as_array().unwrap();
+    assert_eq!(samples.len(), 1);
+    assert_eq!(samples[0]["entry_id"], "r1");
+    assert_eq!(samples[0]["before"]["Pressure"], "250");
+    assert_eq!(samples[0]["after"]["Pressure"], Value::Null);
+    assert_eq!(samples[0]["after"]["PressureKpa"].as_f64(), Some(250.0));
+    assert_eq!(report["rewrite"]["tables_rebuilt"], true);
+    assert_eq!(report["rewrite"]["entry_rows"], 5);
+    assert_eq!(report["rewrite"]["revision_rows"], 5);
+    assert!(report["rewrite"]["estimated_bytes"].as_u64().unwrap() > 0);
+
+    // Nothing was written.
+    assert_eq!(form::get_form(&op, ws_path, "Reading").await?["version"], 1);
+    let r1 = entry::get_entry(&op, ws_path, "r1").await?;
+    assert_eq!(r1["sections"]["Pressure"], "250");
+    let history = entry::get_entry_history(&op, ws_path, "r1").await?;
+    assert_eq!(history["revisions"].as_array().unwrap().len(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-FORM-016
+async fn test_form_req_form_016_history_and_rollback() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "form-history-space", "/tmp").await?;
+    let ws_path = "spaces/form-history-space";
+    let v1 = serde_json::json!({
+        "name": "Memo",
+        "fields": {"Body": {"type": "markdown"}},
+    });
+    form::upsert_form_as(&op, ws_path, &v1, "alice").await?;
+    entry::create_entry(
+        &op,
+        ws_path,
+        "m1",
+        "---\nform: Memo\n---\n# M1\n\n## Body\ntext",
+        "alice",
+        &FakeIntegrityProvider,
+    )
+    .await?;
+    // A mistaken change that keeps the fields.
+    let v2 = serde_json::json!({
+        "name": "Memo",
+        "version": 2,
+        "fields": {"Body": {"type": "markdown"}},
+        "allow_extra_attributes": "allow_json",
+    });
+    form::upsert_form_as(&op, ws_path, &v2, "bob").await?;
+
+    let history = form::get_form_history(&op, ws_path, "Memo").await?;
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0]["revision"], 2);
+    assert_eq!(history[0]["author"], "bob");
+    assert_eq!(history[0]["version"], 2);
+    assert_eq!(history[1]["author"], "alice");
+    assert_eq!(history[1]["fields"], serde_json::json!(["Body"]));
+    let first = form::get_form_version(&op, ws_path, "Memo", 1).await?;
+    assert_eq!(first.author.as_deref(), Some("alice"));
+    assert_eq!(first.definition["allow_extra_attributes"], "deny");
+
+    let rolled_back = form::rollback_form(&op, ws_path, "Memo", 1, "carol").await?;
+    assert_eq!(rolled_back["allow_extra_attributes"], "deny");
+    let current = form::get_form(&op, ws_path, "Memo").await?;
+    assert_eq!(current["version"], 1);
+    assert_eq!(current["allow_extra_attributes"], "deny");
+    let history = form::get_form_history(&op, ws_path, "Memo").await?;
+    assert_eq!(history.len(), 3);
+    assert_eq!(history[0]["author"], "carol");
+    assert_eq!(history[0]["rolled_back_from"], 1);
+    // Entries were not rewritten.
+    let m1 = entry::get_entry(&op, ws_path, "m1").await?;
+    assert_eq!(m1["sections"]["Body"], "text");
+    let revisions = entry::get_entry_history(&op, ws_path, "m1").await?;
+    assert_eq!(revisions["revisions"].as_array().unwrap().len(), 1);
+
+    // Field changes need a migration.
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Memo",
+            "version": 3,
+            "fields": {"Body": {"type": "markdown"}, "Status": {"type": "string"}},
+        }),
+    )
+    .await?;
+    let err = form::rollback_form(&op, ws_path, "Memo", 1, "carol")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("migrate_form"));
+    assert!(form::get_form_version(&op, ws_path, "Memo", 9)
+        .await
+        .is_err());
+    assert!(form::get_form_history(&op, ws_path, "Missing")
+        .await
+        .is_err());
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-FORM-017
+async fn test_form_req_form_017_protected_fields() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "protected-space", "/tmp").await?;
+    let ws_path = "spaces/protected-space";
+    let integrity = FakeIntegrityProvider;
+
+    let bad = serde_json::json!({
+        "name": "Device",
+        "fields": {"ExternalId": {"type": "string", "write_capabilities": []}},
+    });
+    assert!(form::upsert_form(&op, ws_path, &bad).await.is_err());
+
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Device",
+            "template": "# Device\n\n## CommissionedOn\n\n## ExternalId\n\n## Notes\n",
+            "fields": {
+                "CommissionedOn": {"type": "date", "read_only_after_create": true},
+                "ExternalId": {"type": "string", "write_capabilities": ["asset-admin"]},
+                "Notes": {"type": "markdown"},
+            },
+        }),
+    )
+    .await?;
+    let content = |commissioned: &str, external: &str, notes: &str| {
+        format!(
+            "---\nform: Device\n---\n# Pump\n\n## CommissionedOn\n{commissioned}\n\n## ExternalId\n{external}\n\n## Notes\n{notes}"
+        )
+    };
+    // Protected fields are set freely at creation.
+    entry::create_entry(
+        &op,
+        ws_path,
+        "pump",
+        &content("2024-03-01", "SAP-1", "new"),
+        "author",
+        &integrity,
+    )
+    .await?;
+
+    let update = |body: String, capabilities: Vec<String>| {
+        let op = op.clone();
+        async move {
+            entry::update_entry_with_capabilities(
+                &op,
+                ws_path,
+                "pump",
+                &body,
+                None,
+                "author",
+                None,
+                None,
+                &capabilities,
+                &FakeIntegrityProvider,
+            )
+            .await
+        }
+    };
+    let coded = |err: anyhow::Error| {
+        let coded = err
+            .downcast_ref::<_ugoite_core::i18n::MessageError>()
+            .expect("coded error")
+            .clone();
+        (coded.code, coded.params)
+    };
+
+    // Unprotected fields change as usual next to unchanged protected ones.
+    update(content("2024-03-01", "SAP-1", "serviced"), vec![]).await?;
+
+    let err = update(content("2024-04-01", "SAP-1", "serviced"), vec![])
+        .await
+        .unwrap_err();
+    let (code, params) = coded(err);
+    assert_eq!(code, "read_only_field");
+    assert_eq!(params["field"], "CommissionedOn");
+
+    let err = update(content("2024-03-01", "SAP-2", "serviced"), vec![])
+        .await
+        .unwrap_err();
+    let (code, params) = coded(err);
+    assert_eq!(code, "field_requires_capability");
+    assert_eq!(params["field"], "ExternalId");
+    assert_eq!(params["capabilities"], serde_json::json!(["asset-admin"]));
+
+    // Holding the capability unlocks the field, but not read-only ones.
+    let admin = vec!["asset-admin".to_string()];
+    let updated = update(content("2024-03-01", "SAP-2", "serviced"), admin.clone()).await?;
+    assert_eq!(updated["sections"]["ExternalId"], "SAP-2");
+    assert!(update(content("2024-04-01", "SAP-2", "serviced"), admin)
+        .await
+        .is_err());
+    let pump = entry::get_entry(&op, ws_path, "pump").await?;
+    assert_eq!(pump["sections"]["CommissionedOn"], "2024-03-01");
+
+    // Restoring and moving between forms are checked the same way.
+    let history = entry::get_entry_history(&op, ws_path, "pump").await?;
+    let first = history["revisions"][0]["revision_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let err = entry::restore_entry(&op, ws_path, "pump", &first, "author", None, &integrity)
+        .await
+        .unwrap_err();
+    assert_eq!(coded(err).0, "field_requires_capability");
+    entry::restore_entry_with_capabilities(
+        &op,
+        ws_path,
+        "pump",
+        &first,
+        "author",
+        None,
+        &["asset-admin".to_string()],
+        &integrity,
+    )
+    .await?;
+    assert_eq!(
+        entry::get_entry(&op, ws_path, "pump").await?["sections"]["ExternalId"],
+        "SAP-1"
+    );
+
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Machine",
+            "template": "# Machine\n\n## CommissionedOn\n\n## ExternalId\n",
+            "fields": {
+                "CommissionedOn": {"type": "date", "read_only_after_create": true},
+                "ExternalId": {"type": "string"},
+            },
+            "allow_extra_attributes": "allow_json",
+        }),
+    )
+    .await?;
+    let mapping = serde_json::json!({"CommissionedOn": "2025-01-01"});
+    let err = entry::change_entry_form(
+        &op,
+        ws_path,
+        "pump",
+        "Machine",
+        Some(&mapping),
+        "author",
+        &integrity,
+    )
+    .await
+    .unwrap_err();
+    let (code, params) = coded(err);
+    assert_eq!(code, "read_only_field");
+    assert_eq!(params["field"], "CommissionedOn");
+    Ok(())
+}
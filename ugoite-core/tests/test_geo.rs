@@ -0,0 +1,99 @@
+mod common;
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::{entry, form, geo, index, space};
+use common::setup_operator;
+
+fn ids(rows: &[serde_json::Value]) -> Vec<&str> {
+    let mut ids: Vec<&str> = rows.iter().filter_map(|row| row["id"].as_str()).collect();
+    ids.sort();
+    ids
+}
+
+#[tokio::test]
+/// REQ-IDX-016
+async fn test_geo_req_idx_016_geo_fields_and_area_filters() -> anyhow::Result<()> {
+    let form_def = serde_json::json!({
+        "name": "Site",
+        "template": "# Site\n\n## Location\n\n## Area\n",
+        "fields": {
+            "Location": {"type": "geopoint"},
+            "Area": {"type": "geojson"},
+        }
+    });
+    let (casted, warnings) = index::validate_properties(
+        &serde_json::json!({
+            "Location": "35.6812, 139.7671",
+            "Area": "{\"type\": \"Point\", \"coordinates\": [139.7, 35.6]}",
+        }),
+        &form_def,
+    )?;
+    assert!(warnings.is_empty());
+    assert_eq!(
+        casted["Location"],
+        serde_json::json!({"lat": 35.6812, "lon": 139.7671})
+    );
+    assert_eq!(
+        casted["Area"],
+        r#"{"coordinates":[139.7,35.6],"type":"Point"}"#
+    );
+    let (_, warnings) = index::validate_properties(
+        &serde_json::json!({
+            "Location": "95, 10",
+            "Area": "{\"type\": \"Polygon\", \"coordinates\": [1, 2]}",
+        }),
+        &form_def,
+    )?;
+    assert_eq!(warnings.len(), 2);
+
+    let op = setup_operator()?;
+    space::create_space(&op, "geo-space", "/tmp").await?;
+    let ws_path = "spaces/geo-space";
+    form::upsert_form(&op, ws_path, &form_def).await?;
+    let sites = [
+        ("tokyo", "35.6812, 139.7671", ""),
+        ("yokohama", "35.4437, 139.6380", ""),
+        ("osaka", "34.7025, 135.4959", ""),
+        (
+            "kanto-plain",
+            "",
+            r#"{"type": "Polygon", "coordinates": [[[139.0, 35.5], [140.5, 35.5], [140.5, 36.5], [139.0, 35.5]]]}"#,
+        ),
+    ];
+    for (id, location, area) in sites {
+        let mut content = format!("---\nform: Site\n---\n# {}\n", id);
+        for (section, value) in [("Location", location), ("Area", area)] {
+            if !value.is_empty() {
+                content.push_str(&format!("\n## {}\n{}\n", section, value));
+            }
+        }
+        entry::create_entry(&op, ws_path, id, &content, "author", &FakeIntegrityProvider).await?;
+    }
+    let stored = entry::list_entries(&op, ws_path).await?;
+    let tokyo = stored
+        .iter()
+        .find(|row| row["id"] == "tokyo")
+        .expect("tokyo entry");
+    let point = geo::parse_geopoint(&tokyo["properties"]["Location"]).expect("stored geopoint");
+    assert_eq!((point.lat, point.lon), (35.6812, 139.7671));
+
+    let bbox = serde_json::json!({"Location": {"$bbox": [139.0, 35.0, 140.0, 36.0]}});
+    let rows = index::query_index(&op, ws_path, &bbox.to_string()).await?;
+    assert_eq!(ids(&rows), vec!["tokyo", "yokohama"]);
+
+    let near = serde_json::json!({"Location": {"$near": {"lat": 35.6812, "lon": 139.7671, "radius_m": 10_000}}});
+    let rows = index::query_index(&op, ws_path, &near.to_string()).await?;
+    assert_eq!(ids(&rows), vec!["tokyo"]);
+    let far = serde_json::json!({"Location": {"$near": {"lat": 35.6812, "lon": 139.7671, "radius_m": 500_000}}});
+    let rows = index::query_index(&op, ws_path, &far.to_string()).await?;
+    assert_eq!(ids(&rows), vec!["osaka", "tokyo", "yokohama"]);
+
+    let area = serde_json::json!({"Area": {"$bbox": [140.0, 35.0, 141.0, 36.0]}});
+    let rows = index::query_index(&op, ws_path, &area.to_string()).await?;
+    assert_eq!(ids(&rows), vec!["kanto-plain"]);
+
+    let invalid = serde_json::json!({"Location": {"$bbox": [139.0, 36.0, 140.0, 35.0]}});
+    assert!(index::query_index(&op, ws_path, &invalid.to_string())
+        .await
+        .is_err());
+    Ok(())
+}
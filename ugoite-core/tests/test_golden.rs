@@ -0,0 +1,319 @@
+mod common;
+
+use _ugoite_core::entry::{self, ParquetSettings};
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::search::{self, Analyzer};
+use _ugoite_core::{access_log, form, iceberg_store, index, space};
+use common::setup_operator;
+use opendal::Operator;
+use std::path::{Path, PathBuf};
+
+/// Length of the root the Iceberg fixture is blessed under. Iceberg metadata
+/// embeds absolute paths, so reading it swaps in a root of the same length,
+/// which keeps the length-prefixed strings in its Avro manifests valid.
+const GOLDEN_ROOT_LEN: usize = 160;
+/// File in the Iceberg fixture holding the root it was blessed under.
+const GOLDEN_ROOT_FILE: &str = ".golden-root";
+
+fn fixture_dir(relative: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/golden")
+        .join(relative)
+}
+
+fn blessing() -> bool {
+    std::env::var_os("UGOITE_BLESS_GOLDEN").is_some()
+}
+
+/// Fixture directory `relative`, failing when it has not been committed.
+fn require_fixture(relative: &str) -> anyhow::Result<PathBuf> {
+    let fixture = fixture_dir(relative);
+    anyhow::ensure!(
+        fixture.exists(),
+        "golden fixture {} is missing; generate it with UGOITE_BLESS_GOLDEN=1",
+        fixture.display()
+    );
+    Ok(fixture)
+}
+
+/// Copies a fixture tree into `op`, keeping empty directories.
+async fn load_fixture(op: &Operator, dir: &Path, prefix: &str) -> anyhow::Result<()> {
+    op.create_dir(&format!("{}/", prefix)).await?;
+    for item in std::fs::read_dir(dir)? {
+        let item = item?;
+        let name = item.file_name().to_string_lossy().to_string();
+        let path = format!("{}/{}", prefix, name);
+        if item.file_type()?.is_dir() {
+            Box::pin(load_fixture(op, &item.path(), &path)).await?;
+        } else if name != ".gitkeep" {
+            op.write(&path, std::fs::read(item.path())?).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Copies `from` to `to`, replacing `old_root` with the equally long
+/// `new_root` in every file. Empty directories get a `.gitkeep` when
+/// `keep_empty` is set, and existing ones are dropped otherwise.
+fn copy_tree(
+    from: &Path,
+    to: &Path,
+    old_root: &[u8],
+    new_root: &[u8],
+    keep_empty: bool,
+) -> anyhow::Result<()> {
+    assert_eq!(old_root.len(), new_root.len());
+    std::fs::create_dir_all(to)?;
+    let mut empty = true;
+    for item in std::fs::read_dir(from)? {
+        let item = item?;
+        let name = item.file_name();
+        if name == ".gitkeep" || name == GOLDEN_ROOT_FILE {
+            continue;
+        }
+        empty = false;
+        let target = to.join(&name);
+        if item.file_type()?.is_dir() {
+            copy_tree(&item.path(), &target, old_root, new_root, keep_empty)?;
+        } else {
+            let mut bytes = std::fs::read(item.path())?;
+            if !old_root.is_empty() {
+                let mut at = 0;
+                while let Some(found) = bytes[at..]
+                    .windows(old_root.len())
+                    .position(|window| window == old_root)
+                {
+                    let start = at + found;
+                    bytes[start..start + new_root.len()].copy_from_slice(new_root);
+                    at = start + new_root.len();
+                }
+            }
+            std::fs::write(target, bytes)?;
+        }
+    }
+    if empty && keep_empty {
+        std::fs::write(to.join(".gitkeep"), b"")?;
+    }
+    Ok(())
+}
+
+/// A directory under a fresh temp dir whose path is exactly `len` bytes long.
+fn golden_root(temp: &tempfile::TempDir, len: usize) -> anyhow::Result<PathBuf> {
+    let base = temp.path().to_string_lossy().len();
+    anyhow::ensure!(
+        base + 2 <= len,
+        "temp dir {} is too long to hold a {}-byte golden root",
+        temp.path().display(),
+        len
+    );
+    let root = temp.path().join("r".repeat(len - base - 1));
+    std::fs::create_dir_all(&root)?;
+    Ok(root)
+}
+
+fn fs_operator(root: &Path) -> anyhow::Result<Operator> {
+    let builder = opendal::services::Fs::default().root(&root.to_string_lossy());
+    Ok(Operator::new(builder)?.finish())
+}
+
+/// Builds the JSON fixture with the current code. Only run when blessing.
+async fn bless_json_fixture(op: &Operator) -> anyhow::Result<()> {
+    let ws_path = "spaces/golden-json";
+    space::create_space(op, "golden-json", "/tmp/ugoite").await?;
+    space::patch_space(
+        op,
+        "golden-json",
+        &serde_json::json!({
+            "name": "Golden JSON",
+            "settings": {
+                "search": {"language": "en", "stemming": true, "cjk_ngram": 2},
+                "parquet": {"compression": "zstd", "dictionary": true},
+            },
+        }),
+    )
+    .await?;
+    for entry_id in ["entry-a", "entry-a", "entry-a", "entry-b"] {
+        access_log::record_entry_access(op, ws_path, entry_id).await?;
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    }
+    access_log::compact_access_log(op, ws_path).await?;
+    index::reindex_all(op, ws_path).await?;
+    entry::list_entries(op, ws_path).await?;
+
+    // Readers must ignore keys written by newer versions.
+    let meta_path = format!("{}/meta.json", ws_path);
+    let mut meta: serde_json::Value = serde_json::from_slice(&op.read(&meta_path).await?.to_vec())?;
+    meta["x_written_by_newer_version"] =
+        serde_json::json!({"note": "unknown keys must be ignored"});
+    op.write(&meta_path, serde_json::to_vec_pretty(&meta)?)
+        .await?;
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-STO-015
+async fn test_golden_req_sto_015_json_layout_v1() -> anyhow::Result<()> {
+    if blessing() {
+        let temp = tempfile::tempdir()?;
+        bless_json_fixture(&fs_operator(temp.path())?).await?;
+        let fixture = fixture_dir("v1/json");
+        let _ = std::fs::remove_dir_all(&fixture);
+        copy_tree(temp.path(), &fixture, b"", b"", true)?;
+    }
+    let op = setup_operator()?;
+    load_fixture(&op, &require_fixture("v1/json")?.join("spaces"), "spaces").await?;
+    let ws_path = "spaces/golden-json";
+
+    assert!(space::list_spaces(&op)
+        .await?
+        .contains(&"golden-json".to_string()));
+    let meta = space::get_space(&op, "golden-json").await?;
+    assert_eq!(meta.name, "Golden JSON");
+    assert_eq!(meta.storage.root, "/tmp/ugoite");
+
+    let analyzer = Analyzer::for_space(&op, ws_path).await?;
+    assert!(analyzer.stemming);
+    assert_eq!(analyzer.language, "en");
+    let parquet = ParquetSettings::for_space(&op, ws_path).await?;
+    assert_eq!(parquet.compression, "zstd");
+
+    let frequent = access_log::list_frequent_entries(&op, ws_path, 10).await?;
+    assert_eq!(frequent[0]["entry_id"], "entry-a");
+    assert_eq!(frequent[0]["access_count"], 3);
+    let recent = access_log::list_recent_entries(&op, ws_path, 10).await?;
+    assert_eq!(recent[0]["entry_id"], "entry-b");
+
+    let status = index::index_status(&op, ws_path).await?;
+    assert_eq!(status["state"], "current");
+    assert!(entry::list_entries(&op, ws_path).await?.is_empty());
+    Ok(())
+}
+
+/// Builds the Iceberg fixture with the current code. Only run when blessing.
+async fn bless_iceberg_fixture(op: &Operator, root: &Path, ws_path: &str) -> anyhow::Result<()> {
+    space::create_space(op, "golden-iceberg", &root.to_string_lossy()).await?;
+    // Bloom filters are sized for large tables; keep the fixture small.
+    space::patch_space(
+        op,
+        "golden-iceberg",
+        &serde_json::json!({"settings": {"parquet": {"bloom_filters": false}}}),
+    )
+    .await?;
+    form::upsert_form(
+        op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Entry",
+            "template": "# Entry\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    form::upsert_form(
+        op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Task",
+            "template": "# Task\n\n## Status\n\n## Priority\n\n## Due\n",
+            "fields": {
+                "Status": {"type": "string", "required": true},
+                "Priority": {"type": "integer"},
+                "Due": {"type": "date"},
+            },
+        }),
+    )
+    .await?;
+    entry::create_entry(
+        op,
+        ws_path,
+        "golden-entry",
+        "---\nform: Entry\ntags:\n  - golden\n---\n# Golden Entry\n\n## Body\nfirst draft",
+        "author",
+        &FakeIntegrityProvider,
+    )
+    .await?;
+    let current = entry::get_entry(op, ws_path, "golden-entry").await?;
+    entry::update_entry(
+        op,
+        ws_path,
+        "golden-entry",
+        "---\nform: Entry\ntags:\n  - golden\n---\n# Golden Entry\n\n## Body\nsecond draft",
+        current["revision_id"].as_str(),
+        "author",
+        None,
+        None,
+        &FakeIntegrityProvider,
+    )
+    .await?;
+    entry::create_entry(
+        op,
+        ws_path,
+        "golden-task",
+        "---\nform: Task\n---\n# Golden Task\n\n## Status\nopen\n\n## Priority\n2\n\n## Due\n2026-01-31",
+        "author",
+        &FakeIntegrityProvider,
+    )
+    .await?;
+    index::reindex_all(op, ws_path).await?;
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-STO-015
+async fn test_golden_req_sto_015_iceberg_tables_v1() -> anyhow::Result<()> {
+    let ws_path = "spaces/golden-iceberg";
+    if blessing() {
+        let temp = tempfile::tempdir()?;
+        let root = golden_root(&temp, GOLDEN_ROOT_LEN)?;
+        bless_iceberg_fixture(&fs_operator(&root)?, &root, ws_path).await?;
+        let fixture = fixture_dir("v1/iceberg");
+        let _ = std::fs::remove_dir_all(&fixture);
+        copy_tree(&root, &fixture, b"", b"", true)?;
+        std::fs::write(
+            fixture.join(GOLDEN_ROOT_FILE),
+            root.to_string_lossy().as_bytes(),
+        )?;
+    }
+    let fixture = require_fixture("v1/iceberg")?;
+
+    let blessed_root = std::fs::read(fixture.join(GOLDEN_ROOT_FILE))?;
+    let temp = tempfile::tempdir()?;
+    let root = golden_root(&temp, blessed_root.len())?;
+    copy_tree(
+        &fixture,
+        &root,
+        &blessed_root,
+        root.to_string_lossy().as_bytes(),
+        false,
+    )?;
+    let op = fs_operator(&root)?;
+    iceberg_store::evict_catalog_cache(&op, ws_path)?;
+
+    let mut ids: Vec<String> = entry::list_entries(&op, ws_path)
+        .await?
+        .iter()
+        .filter_map(|e| e["id"].as_str().map(str::to_string))
+        .collect();
+    ids.sort();
+    assert_eq!(ids, vec!["golden-entry", "golden-task"]);
+
+    let golden_entry = entry::get_entry(&op, ws_path, "golden-entry").await?;
+    assert_eq!(golden_entry["title"], "Golden Entry");
+    assert_eq!(golden_entry["tags"], serde_json::json!(["golden"]));
+    assert!(golden_entry["content"]
+        .as_str()
+        .unwrap_or_default()
+        .contains("second draft"));
+    let history = entry::get_entry_history(&op, ws_path, "golden-entry").await?;
+    assert_eq!(history["revisions"].as_array().map(Vec::len), Some(2));
+
+    let task = entry::get_entry(&op, ws_path, "golden-task").await?;
+    let content = task["content"].as_str().unwrap_or_default();
+    assert!(content.contains("open"));
+    assert!(content.contains("2026-01-31"));
+
+    let hits = search::search_entries(&op, ws_path, "second").await?;
+    assert!(hits.iter().any(|hit| hit.id == "golden-entry"));
+    assert_eq!(index::index_status(&op, ws_path).await?["state"], "current");
+    Ok(())
+}
@@ -0,0 +1,82 @@
+mod common;
+use _ugoite_core::i18n::{self, Locale};
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::{entry, form, index, space};
+use common::setup_operator;
+
+#[tokio::test]
+/// REQ-API-011
+async fn test_i18n_req_api_011_localized_messages() -> anyhow::Result<()> {
+    assert_eq!("ja-JP".parse::<Locale>()?, Locale::Ja);
+    assert_eq!("EN".parse::<Locale>()?, Locale::En);
+    assert!("xx".parse::<Locale>().is_err());
+
+    let form_def = serde_json::json!({
+        "name": "Task",
+        "template": "# Task\n\n## Owner\n\n## Due\n",
+        "fields": {
+            "Owner": {"type": "string", "required": true},
+            "Due": {"type": "date"},
+        },
+    });
+    let (_, warnings) =
+        index::validate_properties(&serde_json::json!({"Due": "someday"}), &form_def)?;
+    let mut codes: Vec<&str> = warnings.iter().filter_map(|w| w["code"].as_str()).collect();
+    codes.sort();
+    assert_eq!(codes, ["invalid_type", "missing_field"]);
+    let localized = i18n::localize_warnings(&warnings, Locale::Ja);
+    let missing = localized
+        .iter()
+        .find(|w| w["code"] == "missing_field")
+        .expect("missing_field warning");
+    assert_eq!(missing["message"], "必須フィールドがありません: Owner");
+    assert_eq!(missing["field"], "Owner");
+    assert_eq!(missing["params"], serde_json::json!({"field": "Owner"}));
+
+    let op = setup_operator()?;
+    space::create_space(&op, "i18n-space", "/tmp").await?;
+    let ws_path = "spaces/i18n-space";
+    form::upsert_form(&op, ws_path, &form_def).await?;
+    space::patch_space(
+        &op,
+        "i18n-space",
+        &serde_json::json!({"settings": {"locale": "ja"}}),
+    )
+    .await?;
+    assert_eq!(Locale::for_space(&op, ws_path).await?, Locale::Ja);
+
+    // Errors keep their English text and localize on request.
+    let err = entry::create_entry(
+        &op,
+        ws_path,
+        "task-1",
+        "---\nform: Task\n---\n# Task\n\n## Due\n2025-01-01\n",
+        "author",
+        &FakeIntegrityProvider,
+    )
+    .await
+    .expect_err("missing owner");
+    assert!(err.to_string().starts_with("Form validation failed: "));
+    assert!(err.to_string().contains("Missing required field: Owner"));
+    let ja = i18n::localize_error(&err, Locale::Ja);
+    assert!(ja.starts_with("フォームの検証に失敗しました: "));
+    assert!(ja.contains("必須フィールドがありません: Owner"));
+    let coded = err
+        .downcast_ref::<i18n::MessageError>()
+        .expect("coded error");
+    assert_eq!(coded.code, "form_validation_failed");
+
+    let err = entry::get_entry(&op, ws_path, "nope")
+        .await
+        .expect_err("unknown entry");
+    assert_eq!(err.to_string(), "Entry not found: nope");
+    assert_eq!(
+        i18n::localize_error(&err, Locale::Ja),
+        "エントリが見つかりません: nope"
+    );
+    assert_eq!(
+        i18n::localize_error(&anyhow::anyhow!("plain"), Locale::Ja),
+        "plain"
+    );
+    Ok(())
+}
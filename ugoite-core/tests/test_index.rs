@@ -219,6 +219,7 @@ async fn test_index_req_idx_009_query_sql_joins() -> anyhow::Result<()> {
 
 #[test]
 /// REQ-IDX-010
+#[allow(clippy::approx_constant)]
 fn test_index_req_idx_010_rich_content_parsing() -> anyhow::Result<()> {
     let class_def = serde_json::json!({
         "name": "Meeting",
@@ -280,3 +281,423 @@ fn test_index_req_idx_010_rich_content_parsing() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+/// REQ-IDX-011
+async fn test_index_req_idx_011_outdated_format_triggers_rebuild() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "index-version", "/tmp").await?;
+    let ws_path = "spaces/index-version";
+
+    let status = index::index_status(&op, ws_path).await?;
+    assert_eq!(status["state"], "missing");
+
+    op.write(
+        &format!("{}/index/manifest.json", ws_path),
+        serde_json::to_vec(&serde_json::json!({"format_version": 0, "status": "ready"}))?,
+    )
+    .await?;
+    let status = index::index_status(&op, ws_path).await?;
+    assert_eq!(status["state"], "outdated");
+
+    // Queries read through the live scan and leave the manifest alone.
+    index::query_index(&op, ws_path, "{}").await?;
+    assert_eq!(
+        index::index_status(&op, ws_path).await?["state"],
+        "outdated"
+    );
+
+    // Reading the space metadata writes nothing either.
+    space::get_space(&op, "index-version").await?;
+    assert_eq!(
+        index::index_status(&op, ws_path).await?["state"],
+        "outdated"
+    );
+
+    // An index write starts the rebuild; reads still succeed meanwhile.
+    assert!(!index::ensure_index_compatible(&op, ws_path).await?);
+    index::query_index(&op, ws_path, "{}").await?;
+
+    // A rebuild whose lease ran out is taken to have died and restarts.
+    op.write(
+        &format!("{}/index/manifest.json", ws_path),
+        serde_json::to_vec(&serde_json::json!({
+            "format_version": 0,
+            "status": "rebuilding",
+            "started_at": 0.0,
+        }))?,
+    )
+    .await?;
+    assert_eq!(index::index_status(&op, ws_path).await?["state"], "stalled");
+    assert!(!index::ensure_index_compatible(&op, ws_path).await?);
+    let state = index::index_status(&op, ws_path).await?["state"].clone();
+    assert!(state == "rebuilding" || state == "current", "{state}");
+
+    index::reindex_all(&op, ws_path).await?;
+    let status = index::index_status(&op, ws_path).await?;
+    assert_eq!(status["state"], "current");
+    assert_eq!(
+        status["format_version"],
+        serde_json::json!(index::INDEX_FORMAT_VERSION)
+    );
+    assert!(index::ensure_index_compatible(&op, ws_path).await?);
+
+    // A manifest that does not parse counts as outdated and is rebuilt.
+    op.write(&format!("{}/index/manifest.json", ws_path), "{not json")
+        .await?;
+    assert!(!index::ensure_index_compatible(&op, ws_path).await?);
+    index::reindex_all(&op, ws_path).await?;
+    assert_eq!(index::index_status(&op, ws_path).await?["state"], "current");
+
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-IDX-012
+async fn test_index_req_idx_012_form_shards_rebuild_independently() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "index-shards", "/tmp").await?;
+    let ws_path = "spaces/index-shards";
+    let integrity = _ugoite_core::integrity::FakeIntegrityProvider;
+
+    for name in ["Task", "Note"] {
+        let form_def = serde_json::json!({
+            "name": name,
+            "template": format!("# {}\n\n## Body\n", name),
+            "fields": {"Body": {"type": "markdown"}},
+        });
+        form::upsert_form(&op, ws_path, &form_def).await?;
+    }
+    entry::create_entry(
+        &op,
+        ws_path,
+        "task-1",
+        "---\nform: Task\n---\n# Task 1\n\n## Body\nfirst",
+        "author",
+        &integrity,
+    )
+    .await?;
+    entry::create_entry(
+        &op,
+        ws_path,
+        "note-1",
+        "---\nform: Note\n---\n# Note 1\n\n## Body\nsecond",
+        "author",
+        &integrity,
+    )
+    .await?;
+
+    index::reindex_all(&op, ws_path).await?;
+    assert!(
+        op.exists(&format!("{}/index/forms/Task.json", ws_path))
+            .await?
+    );
+    assert!(
+        op.exists(&format!("{}/index/forms/Note.json", ws_path))
+            .await?
+    );
+
+    entry::create_entry(
+        &op,
+        ws_path,
+        "task-2",
+        "---\nform: Task\n---\n# Task 2\n\n## Body\nthird",
+        "author",
+        &integrity,
+    )
+    .await?;
+    let status = index::index_status(&op, ws_path).await?;
+    assert_eq!(status["forms"]["Task"]["fresh"], false);
+    assert_eq!(status["forms"]["Note"]["fresh"], true);
+
+    // Stale shards are bypassed, so queries still see the new entry.
+    let tasks = index::query_index(&op, ws_path, r#"{"form": "Task"}"#).await?;
+    assert_eq!(tasks.len(), 2);
+
+    let summary = index::reindex_form(&op, ws_path, "Task").await?;
+    assert_eq!(summary["entry_count"], 2);
+    let status = index::index_status(&op, ws_path).await?;
+    assert_eq!(status["forms"]["Task"]["fresh"], true);
+    assert_eq!(status["forms"]["Note"]["fresh"], true);
+
+    let all = index::query_index(&op, ws_path, "{}").await?;
+    assert_eq!(all.len(), 3);
+
+    Ok(())
+}
+
+#[test]
+/// REQ-IDX-013
+fn test_index_req_idx_013_compute_facets() -> anyhow::Result<()> {
+    let rows = vec![
+        serde_json::json!({"id": "a", "tags": ["x", "y"], "properties": {"Status": "Open", "Cost": 12.5, "Date": "2025-01-03"}}),
+        serde_json::json!({"id": "b", "tags": ["x"], "properties": {"Status": "Open", "Cost": "27", "Date": "2025-01-28"}}),
+        serde_json::json!({"id": "c", "tags": [], "properties": {"Status": "Done", "Date": "2025-02-14"}}),
+    ];
+    let specs = serde_json::json!([
+        {"field": "Status"},
+        {"field": "tags", "name": "tag_counts"},
+        {"field": "Cost", "type": "histogram", "interval": 10},
+        {"field": "Date", "type": "date_histogram", "interval": "month"},
+    ]);
+
+    let facets = index::compute_facets(&rows, &specs)?;
+    assert_eq!(
+        facets["Status"]["buckets"],
+        serde_json::json!([{"key": "Open", "count": 2}, {"key": "Done", "count": 1}])
+    );
+    assert_eq!(
+        facets["tag_counts"]["buckets"][0],
+        serde_json::json!({"key": "x", "count": 2})
+    );
+    assert_eq!(facets["tag_counts"]["missing"], 1);
+    assert_eq!(
+        facets["Cost"]["buckets"],
+        serde_json::json!([{"key": 10.0, "count": 1}, {"key": 20.0, "count": 1}])
+    );
+    assert_eq!(facets["Cost"]["missing"], 1);
+    assert_eq!(
+        facets["Date"]["buckets"],
+        serde_json::json!([{"key": "2025-01-01", "count": 2}, {"key": "2025-02-01", "count": 1}])
+    );
+
+    assert!(index::compute_facets(
+        &rows,
+        &serde_json::json!([{"field": "Cost", "type": "histogram"}])
+    )
+    .is_err());
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-IDX-014
+async fn test_index_req_idx_014_join_follows_row_reference() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "join-space", "/tmp").await?;
+    let ws_path = "spaces/join-space";
+    let integrity = _ugoite_core::integrity::FakeIntegrityProvider;
+
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Site",
+            "template": "# Site\n\n## Region\n",
+            "fields": {"Region": {"type": "string"}},
+        }),
+    )
+    .await?;
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Inspection",
+            "template": "# Inspection\n\n## Site\n\n## Score\n",
+            "fields": {
+                "Site": {"type": "row_reference", "target_form": "Site"},
+                "Score": {"type": "double"},
+            },
+        }),
+    )
+    .await?;
+    for (id, region) in [("north", "N"), ("south", "S")] {
+        let content = format!("---\nform: Site\n---\n# {}\n\n## Region\n{}\n", id, region);
+        entry::create_entry(&op, ws_path, id, &content, "author", &integrity).await?;
+    }
+    for (id, site, score) in [
+        ("i-1", "ugoite://entry/north", "3"),
+        ("i-2", "south", "4"),
+        ("i-3", "missing", "5"),
+    ] {
+        let content = format!(
+            "---\nform: Inspection\n---\n# {}\n\n## Site\n{}\n\n## Score\n{}\n",
+            id, site, score
+        );
+        entry::create_entry(&op, ws_path, id, &content, "author", &integrity).await?;
+    }
+
+    let projection = vec![
+        "Inspection.id".to_string(),
+        "Inspection.Score".to_string(),
+        "Site.Region".to_string(),
+    ];
+    let rows = index::join(&op, ws_path, "Inspection", "Site", "Site", &projection).await?;
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0]["Inspection.id"], "i-1");
+    assert_eq!(rows[0]["Site.Region"], "N");
+    assert_eq!(rows[1]["Inspection.id"], "i-2");
+    assert_eq!(rows[1]["Site.Region"], "S");
+
+    let pairs = index::join(&op, ws_path, "Inspection", "Site", "Site", &[]).await?;
+    assert_eq!(pairs[0]["Site"]["id"], "north");
+
+    assert!(
+        index::join(&op, ws_path, "Inspection", "Site", "Score", &[])
+            .await
+            .is_err()
+    );
+    assert!(index::join(
+        &op,
+        ws_path,
+        "Inspection",
+        "Site",
+        "Site",
+        &["Other.x".to_string()]
+    )
+    .await
+    .is_err());
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-IDX-015
+async fn test_index_req_idx_015_cast_dates_in_space_timezone() -> anyhow::Result<()> {
+    use _ugoite_core::timezone::SpaceTimeZone;
+
+    let form_def = serde_json::json!({
+        "name": "Shift",
+        "fields": {
+            "Day": {"type": "date"},
+            "Start": {"type": "timestamp_tz"},
+            "End": {"type": "timestamp"},
+            "Logged": {"type": "timestamp_ns"},
+        }
+    });
+    let props = serde_json::json!({
+        "Day": "2025-01-01T20:00:00Z",
+        "Start": "2025-01-02 09:30",
+        "End": "2025-01-02",
+        "Logged": "2025-01-02T00:00:00.5Z",
+    });
+
+    let tokyo = SpaceTimeZone::new("Asia/Tokyo")?;
+    let (casted, warnings) = index::validate_properties_in_zone(&props, &form_def, &tokyo)?;
+    assert!(warnings.is_empty());
+    assert_eq!(casted["Day"], "2025-01-02");
+    assert_eq!(casted["Start"], "2025-01-02T09:30:00+09:00");
+    assert_eq!(casted["End"], "2025-01-02T00:00:00+09:00");
+    assert_eq!(casted["Logged"], "2025-01-02T09:00:00.500000000+09:00");
+
+    // UTC stays the default, and offsets follow daylight saving time.
+    let (casted, _) = index::validate_properties(&props, &form_def)?;
+    assert_eq!(casted["Day"], "2025-01-01");
+    assert_eq!(casted["Start"], "2025-01-02T09:30:00+00:00");
+    let new_york = SpaceTimeZone::new("America/New_York")?;
+    let summer = serde_json::json!({"Start": "2025-07-01 12:00"});
+    let (casted, _) = index::validate_properties_in_zone(&summer, &form_def, &new_york)?;
+    assert_eq!(casted["Start"], "2025-07-01T12:00:00-04:00");
+    assert!(SpaceTimeZone::new("Mars/Olympus").is_err());
+
+    let op = setup_operator()?;
+    space::create_space(&op, "tz-space", "/tmp").await?;
+    space::patch_space(
+        &op,
+        "tz-space",
+        &serde_json::json!({"settings": {"timezone": "Asia/Tokyo"}}),
+    )
+    .await?;
+    let tz = SpaceTimeZone::for_space(&op, "spaces/tz-space").await?;
+    assert_eq!(tz.name(), "Asia/Tokyo");
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-IDX-017
+async fn test_index_req_idx_017_incremental_reindex_from_snapshot() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "index-incremental", "/tmp").await?;
+    let ws_path = "spaces/index-incremental";
+    let integrity = _ugoite_core::integrity::FakeIntegrityProvider;
+    let task_form = |fields: serde_json::Value| {
+        serde_json::json!({
+            "name": "Task",
+            "template": "# Task\n\n## Body\n",
+            "fields": fields,
+        })
+    };
+    form::upsert_form(
+        &op,
+        ws_path,
+        &task_form(serde_json::json!({"Body": {"type": "markdown"}})),
+    )
+    .await?;
+    let content =
+        |title: &str, body: &str| format!("---\nform: Task\n---\n# {}\n\n## Body\n{}", title, body);
+    for id in ["task-1", "task-2", "task-3"] {
+        entry::create_entry(
+            &op,
+            ws_path,
+            id,
+            &content(id, "first"),
+            "author",
+            &integrity,
+        )
+        .await?;
+    }
+    index::reindex_all(&op, ws_path).await?;
+
+    let idle = index::reindex_incremental(&op, ws_path).await?;
+    assert_eq!(idle["forms"]["Task"]["mode"], "unchanged");
+
+    let rev = entry::get_entry(&op, ws_path, "task-1").await?["revision_id"].clone();
+    entry::update_entry(
+        &op,
+        ws_path,
+        "task-1",
+        &content("Task 1 renamed", "second"),
+        rev.as_str(),
+        "author",
+        None,
+        None,
+        &integrity,
+    )
+    .await?;
+    entry::delete_entry(&op, ws_path, "task-2", false).await?;
+    entry::create_entry(
+        &op,
+        ws_path,
+        "task-4",
+        &content("task-4", "new"),
+        "author",
+        &integrity,
+    )
+    .await?;
+
+    let report = index::reindex_incremental(&op, ws_path).await?;
+    assert_eq!(report["forms"]["Task"]["mode"], "incremental");
+    assert_eq!(report["forms"]["Task"]["changed"], 3);
+    assert_eq!(report["forms"]["Task"]["entry_count"], 3);
+    let status = index::index_status(&op, ws_path).await?;
+    assert_eq!(status["forms"]["Task"]["fresh"], true);
+    let manifest = index::read_index_manifest(&op, ws_path).await?.unwrap();
+    assert_eq!(
+        manifest["forms"]["Task"]["snapshot_id"],
+        report["forms"]["Task"]["snapshot_id"]
+    );
+
+    // The refreshed shard serves queries like a full rebuild would.
+    let mut ids: Vec<String> = index::query_index(&op, ws_path, r#"{"form": "Task"}"#)
+        .await?
+        .iter()
+        .map(|record| record["id"].as_str().unwrap().to_string())
+        .collect();
+    ids.sort();
+    assert_eq!(ids, vec!["task-1", "task-3", "task-4"]);
+    let renamed = index::query_index(&op, ws_path, r#"{"title": "Task 1 renamed"}"#).await?;
+    assert_eq!(renamed.len(), 1);
+
+    // Rebuilt tables break the snapshot chain, so the shard is rebuilt in full.
+    form::upsert_form(
+        &op,
+        ws_path,
+        &task_form(serde_json::json!({
+            "Body": {"type": "markdown"},
+            "Owner": {"type": "string"},
+        })),
+    )
+    .await?;
+    let rebuilt = index::reindex_incremental(&op, ws_path).await?;
+    assert_eq!(rebuilt["forms"]["Task"]["mode"], "full");
+    assert_eq!(rebuilt["forms"]["Task"]["entry_count"], 3);
+    Ok(())
+}
@@ -1,7 +1,12 @@
 mod common;
-use _ugoite_core::integrity::{FakeIntegrityProvider, IntegrityProvider, RealIntegrityProvider};
+use _ugoite_core::entry;
+use _ugoite_core::form;
+use _ugoite_core::integrity::{
+    self, ChecksumAlgorithm, FakeIntegrityProvider, IntegrityError, IntegrityProvider,
+    RealIntegrityProvider,
+};
 use _ugoite_core::space;
-use common::setup_operator;
+use common::{setup_fs_operator, setup_operator};
 
 #[test]
 /// REQ-INT-001
@@ -32,11 +37,10 @@ async fn test_integrity_req_int_001_real_integrity_provider() -> anyhow::Result<
     let checksum = provider.checksum(content);
     let signature = provider.signature(content);
 
-    // Check SHA-256 for "hello world"
-    // b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9
+    // Check BLAKE3 for "hello world", tagged with the algorithm
     assert_eq!(
         checksum,
-        "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
+        "blake3:d74981efa70a0c880b8d8c1985d075dbcbf679b99a5f9914e5aaf96b831a9e24"
     );
 
     // Signature should be valid hex and different from checksum
@@ -45,3 +49,319 @@ async fn test_integrity_req_int_001_real_integrity_provider() -> anyhow::Result<
 
     Ok(())
 }
+
+#[tokio::test]
+/// REQ-INT-005
+async fn test_integrity_req_int_005_canonical_response_signatures() -> anyhow::Result<()> {
+    let a: serde_json::Value =
+        serde_json::from_str(r#"{"b": [1.0, 2.5, "x"], "a": {"z": true, "y": null}}"#)?;
+    let b: serde_json::Value =
+        serde_json::from_str(r#"{"a":{"y":null,"z":true},"b":[1,2.5,"x"]}"#)?;
+    assert_eq!(
+        integrity::canonical_json(&a),
+        r#"{"a":{"y":null,"z":true},"b":[1,2.5,"x"]}"#
+    );
+    assert_eq!(integrity::canonical_json(&a), integrity::canonical_json(&b));
+
+    // RFC 8785 Appendix B vectors: what JSON.stringify in JS and JCS
+    // libraries in Python and Java emit for the same IEEE 754 doubles.
+    for (bits, expected) in [
+        (0x0000000000000000_u64, "0"),
+        (0x8000000000000000, "0"),
+        (0x0000000000000001, "5e-324"),
+        (0x8000000000000001, "-5e-324"),
+        (0x7fefffffffffffff, "1.7976931348623157e+308"),
+        (0xffefffffffffffff, "-1.7976931348623157e+308"),
+        (0x4340000000000000, "9007199254740992"),
+        (0xc340000000000000, "-9007199254740992"),
+        (0x4430000000000000, "295147905179352830000"),
+        (0x44b52d02c7e14af5, "9.999999999999997e+22"),
+        (0x44b52d02c7e14af6, "1e+23"),
+        (0x44b52d02c7e14af7, "1.0000000000000001e+23"),
+        (0x444b1ae4d6e2ef4e, "999999999999999700000"),
+        (0x444b1ae4d6e2ef4f, "999999999999999900000"),
+        (0x444b1ae4d6e2ef50, "1e+21"),
+        (0x3eb0c6f7a0b5ed8c, "9.999999999999997e-7"),
+        (0x3eb0c6f7a0b5ed8d, "0.000001"),
+        (0x41b3de4355555553, "333333333.3333332"),
+        (0x41b3de4355555554, "333333333.33333325"),
+        (0x41b3de4355555555, "333333333.3333333"),
+        (0x41b3de4355555556, "333333333.3333334"),
+        (0x41b3de4355555557, "333333333.33333343"),
+        (0xbecbf647612f3696, "-0.0000033333333333333333"),
+        (0x43143ff3c1cb0959, "1424953923781206.2"),
+    ] {
+        let value = serde_json::json!(f64::from_bits(bits));
+        assert_eq!(integrity::canonical_json(&value), expected, "{:#x}", bits);
+    }
+    assert_eq!(
+        integrity::canonical_json(&serde_json::json!(1e16)),
+        "10000000000000000"
+    );
+    assert_eq!(
+        integrity::canonical_json(&serde_json::json!(i64::MAX)),
+        "9223372036854776000"
+    );
+
+    // RFC 8785 section 3.2.2 example, plus keys ordered by UTF-16 code units
+    // (U+1F600 is a surrogate pair, so it sorts before U+E000).
+    let rfc = serde_json::json!({
+        "numbers": [333333333.3333333_f64, 1e30, 4.50, 2e-3, 0.000000000000000000000000001],
+        "string": "\u{20ac}$\u{f}\nA'B\"\\\\\"/",
+        "literals": [null, true, false],
+    });
+    assert_eq!(
+        integrity::canonical_json(&rfc),
+        r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#
+    );
+    let keys = serde_json::json!({"\u{e000}": 1, "\u{1f600}": 2, "a": 3});
+    assert_eq!(
+        integrity::canonical_json(&keys),
+        "{\"a\":3,\"\u{1f600}\":2,\"\u{e000}\":1}"
+    );
+
+    let op = setup_operator()?;
+    let signature = integrity::sign_canonical_response(&op, &a).await?;
+    assert!(integrity::verify_response_signature(&op, &b, &signature, 300).await?);
+
+    let tampered = serde_json::json!({"a": {"y": null, "z": false}, "b": [1, 2.5, "x"]});
+    assert!(!integrity::verify_response_signature(&op, &tampered, &signature, 300).await?);
+
+    let mut other_nonce = signature.clone();
+    other_nonce.nonce = "replayed".to_string();
+    assert!(!integrity::verify_response_signature(&op, &a, &other_nonce, 300).await?);
+
+    let mut stale = signature.clone();
+    stale.timestamp -= 3600;
+    assert!(!integrity::verify_response_signature(&op, &a, &stale, 300).await?);
+
+    let mut unknown_key = signature;
+    unknown_key.key_id = "key-retired".to_string();
+    assert!(!integrity::verify_response_signature(&op, &a, &unknown_key, 300).await?);
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-INT-013
+async fn test_integrity_req_int_013_strict_reads_verify_entries() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "strict-space", "/tmp").await?;
+    let ws_path = "spaces/strict-space";
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Entry",
+            "template": "# Entry\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    let provider = RealIntegrityProvider::from_space(&op, "strict-space").await?;
+    // Stored content renders differently from this input (spacing), yet
+    // still verifies: the checksum covers the entry as stored.
+    entry::create_entry(
+        &op,
+        ws_path,
+        "signed",
+        "---\nform: Entry\n---\n# Signed\n## Body\nFirst   \n",
+        "author1",
+        &provider,
+    )
+    .await?;
+    let verified = entry::get_entry_verified(&op, ws_path, "signed", &provider, Some(true)).await?;
+    assert_eq!(verified["id"], "signed");
+    let revision = verified["revision_id"].as_str().unwrap().to_string();
+    entry::update_entry(
+        &op,
+        ws_path,
+        "signed",
+        "---\nform: Entry\n---\n# Signed\n\n## Body\nSecond",
+        Some(&revision),
+        "author1",
+        None,
+        None,
+        &provider,
+    )
+    .await?;
+    entry::get_entry_verified(&op, ws_path, "signed", &provider, Some(true)).await?;
+
+    // Content signed under other material fails only in strict mode.
+    entry::create_entry(
+        &op,
+        ws_path,
+        "unverifiable",
+        "---\nform: Entry\n---\n# Unverifiable\n\n## Body\nText",
+        "author1",
+        &FakeIntegrityProvider,
+    )
+    .await?;
+    let err = entry::get_entry_verified(&op, ws_path, "unverifiable", &provider, Some(true))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<IntegrityError>(),
+        Some(IntegrityError::ChecksumMismatch { entry_id, .. }) if entry_id == "unverifiable"
+    ));
+    let other_key = RealIntegrityProvider::new(b"another-secret".to_vec());
+    let err = entry::get_entry_verified(&op, ws_path, "signed", &other_key, Some(true))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<IntegrityError>(),
+        Some(IntegrityError::SignatureMismatch { .. })
+    ));
+    entry::get_entry_verified(&op, ws_path, "unverifiable", &provider, None).await?;
+    entry::get_entry(&op, ws_path, "unverifiable").await?;
+
+    // The space setting turns strict reads on unless the call overrides it.
+    space::patch_space(
+        &op,
+        "strict-space",
+        &serde_json::json!({"settings": {"integrity": {"strict_reads": true}}}),
+    )
+    .await?;
+    assert!(integrity::strict_reads(
+        &space::read_space_settings(&op, ws_path).await?
+    ));
+    assert!(
+        entry::get_entry_verified(&op, ws_path, "unverifiable", &provider, None)
+            .await
+            .is_err()
+    );
+    entry::get_entry_verified(&op, ws_path, "unverifiable", &provider, Some(false)).await?;
+    entry::get_entry_verified(&op, ws_path, "signed", &provider, None).await?;
+    Ok(())
+}
+
+/// Writes checksums the way they were recorded before tagging: bare
+/// SHA-256 hex.
+struct UntaggedProvider(RealIntegrityProvider);
+
+impl IntegrityProvider for UntaggedProvider {
+    fn checksum(&self, content: &str) -> String {
+        ChecksumAlgorithm::Sha256
+            .checksum(content.as_bytes())
+            .trim_start_matches("sha256:")
+            .to_string()
+    }
+    fn signature(&self, content: &str) -> String {
+        self.0.signature(content)
+    }
+}
+
+#[tokio::test]
+/// REQ-INT-015
+async fn test_integrity_req_int_015_tagged_checksum_algorithms() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "tagged-space", "/tmp").await?;
+    let ws_path = "spaces/tagged-space";
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Entry",
+            "template": "# Entry\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    let (_, secret) = integrity::load_hmac_material(&op, "tagged-space").await?;
+    let provider = RealIntegrityProvider::from_space(&op, "tagged-space").await?;
+    let sha256 =
+        RealIntegrityProvider::new(secret.clone()).with_algorithm(ChecksumAlgorithm::Sha256);
+    let untagged = UntaggedProvider(RealIntegrityProvider::new(secret));
+    let content = |title: &str| format!("---\nform: Entry\n---\n# {}\n\n## Body\nText", title);
+    entry::create_entry(&op, ws_path, "blake", &content("Blake"), "a", &provider).await?;
+    entry::create_entry(&op, ws_path, "sha", &content("Sha"), "a", &sha256).await?;
+    entry::create_entry(&op, ws_path, "legacy", &content("Legacy"), "a", &untagged).await?;
+    let checksum =
+        |entry: &serde_json::Value| entry["integrity"]["checksum"].as_str().unwrap().to_string();
+    assert!(checksum(&entry::get_entry(&op, ws_path, "blake").await?).starts_with("blake3:"));
+    assert!(checksum(&entry::get_entry(&op, ws_path, "sha").await?).starts_with("sha256:"));
+    assert!(!checksum(&entry::get_entry(&op, ws_path, "legacy").await?).contains(':'));
+
+    // Each checksum verifies with the algorithm it was made with.
+    for entry_id in ["blake", "sha", "legacy"] {
+        entry::get_entry_verified(&op, ws_path, entry_id, &provider, Some(true)).await?;
+    }
+    assert!(ChecksumAlgorithm::matches(
+        "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9",
+        b"hello world"
+    ));
+    assert!(!ChecksumAlgorithm::matches(
+        "md5:5eb63bbbe01eeed093cb22bb8f5acdc3",
+        b"hello world"
+    ));
+    assert!(ChecksumAlgorithm::from_tag("md5").is_err());
+
+    // A space can keep SHA-256 for new checksums.
+    space::patch_space(
+        &op,
+        "tagged-space",
+        &serde_json::json!({"settings": {"integrity": {"checksum_algorithm": "sha256"}}}),
+    )
+    .await?;
+    let configured = RealIntegrityProvider::from_space(&op, "tagged-space").await?;
+    assert_eq!(
+        configured.checksum("hello world"),
+        "sha256:b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-ENTRY-012, REQ-INT-015
+async fn test_integrity_req_int_015_redaction_resigns_untagged_revisions() -> anyhow::Result<()> {
+    let (op, _root) = setup_fs_operator()?;
+    space::create_space(&op, "legacy-redact", "/tmp").await?;
+    let ws_path = "spaces/legacy-redact";
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Entry",
+            "template": "# Entry\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    let (_, secret) = integrity::load_hmac_material(&op, "legacy-redact").await?;
+    let untagged = UntaggedProvider(RealIntegrityProvider::new(secret));
+    entry::create_entry(
+        &op,
+        ws_path,
+        "legacy",
+        "---\nform: Entry\n---\n# Key sk-live-ABC123\n\n## Body\nText",
+        "a",
+        &untagged,
+    )
+    .await?;
+    let revision_id = entry::get_entry(&op, ws_path, "legacy").await?["revision_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    // The revision was written under the redacted title, so it is re-signed
+    // even though its SHA-256 checksum predates the current algorithm.
+    let provider = RealIntegrityProvider::from_space(&op, "legacy-redact").await?;
+    let redaction = entry::Redaction {
+        patterns: vec!["sk-live-[A-Za-z0-9]+".to_string()],
+        fields: Vec::new(),
+    };
+    let report =
+        entry::redact_entry(&op, ws_path, "legacy", &redaction, "auditor", &provider).await?;
+    assert_eq!(report["revisions_rewritten"], 1);
+    assert_eq!(
+        report["author_signatures_invalidated"],
+        serde_json::json!([])
+    );
+    assert_eq!(report["retention_copies"], serde_json::json!([]));
+    let revision = entry::get_entry_revision(&op, ws_path, "legacy", &revision_id).await?;
+    assert!(revision["markdown_checksum"]
+        .as_str()
+        .unwrap()
+        .starts_with("blake3:"));
+    entry::get_entry_verified(&op, ws_path, "legacy", &provider, Some(true)).await?;
+    Ok(())
+}
@@ -0,0 +1,133 @@
+//! Key rotation escrows the old key through the secrets store, whose
+//! configuration is read from the environment; this test runs in its own
+//! binary so setting it does not affect other tests.
+mod common;
+use _ugoite_core::asset;
+use _ugoite_core::entry;
+use _ugoite_core::form;
+use _ugoite_core::integrity::{self, RealIntegrityProvider};
+use _ugoite_core::secrets;
+use _ugoite_core::space;
+use common::setup_fs_operator;
+
+#[tokio::test]
+/// REQ-INT-014
+async fn test_key_rotation_req_int_014_rotate_key_and_resign_space() -> anyhow::Result<()> {
+    let (op, _root) = setup_fs_operator()?;
+    space::create_space(&op, "rotate-space", "/tmp").await?;
+    let ws_path = "spaces/rotate-space";
+
+    // The previous key is escrowed in the secrets store, so rotation needs
+    // its master key and stages nothing without one.
+    if std::env::var(secrets::MASTER_KEY_ENV).is_err() {
+        let err = integrity::rotate_space_key(&op, "rotate-space", "admin")
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains(secrets::MASTER_KEY_ENV), "{}", err);
+        assert!(err.contains(secrets::STORED_KEY_OPT_IN_ENV), "{}", err);
+        let meta = space::get_space_raw(&op, "rotate-space").await?;
+        assert!(meta.get("pending_hmac_key").is_none());
+        // This test is the only one in its binary, so the process-wide
+        // variable does not leak into other tests.
+        std::env::set_var(secrets::STORED_KEY_OPT_IN_ENV, "1");
+    }
+
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Entry",
+            "template": "# Entry\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    let old_provider = RealIntegrityProvider::from_space(&op, "rotate-space").await?;
+    let (old_key_id, _) = integrity::load_hmac_material(&op, "rotate-space").await?;
+    entry::create_entry(
+        &op,
+        ws_path,
+        "signed",
+        "---\nform: Entry\n---\n# Signed\n\n## Body\nFirst",
+        "author1",
+        &old_provider,
+    )
+    .await?;
+    let revision = entry::get_entry(&op, ws_path, "signed").await?["revision_id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    entry::update_entry(
+        &op,
+        ws_path,
+        "signed",
+        "---\nform: Entry\n---\n# Signed\n\n## Body\nSecond",
+        Some(&revision),
+        "author1",
+        None,
+        None,
+        &old_provider,
+    )
+    .await?;
+    let scan = asset::save_asset(&op, ws_path, "scan.txt", b"scanned page").await?;
+
+    let report = integrity::rotate_space_key(&op, "rotate-space", "admin").await?;
+    assert_eq!(report["previous_key_id"], old_key_id.as_str());
+    // The asset's metadata entry is re-signed along with the others.
+    assert_eq!(report["entries"], 2);
+    assert_eq!(report["revisions"], 3);
+    assert_eq!(report["assets"], 1);
+    let (new_key_id, _) = integrity::load_hmac_material(&op, "rotate-space").await?;
+    assert_eq!(report["key_id"], new_key_id.as_str());
+    assert_ne!(new_key_id, old_key_id);
+
+    // Everything verifies under the new key and nothing under the old one.
+    let new_provider = RealIntegrityProvider::from_space(&op, "rotate-space").await?;
+    entry::get_entry_verified(&op, ws_path, "signed", &new_provider, Some(true)).await?;
+    assert!(
+        entry::get_entry_verified(&op, ws_path, "signed", &old_provider, Some(true))
+            .await
+            .is_err()
+    );
+    asset::verify_asset(&op, ws_path, &scan.id, &new_provider).await?;
+    assert!(asset::verify_asset(&op, ws_path, &scan.id, &old_provider)
+        .await
+        .is_err());
+
+    // The retired key is escrowed as a space secret.
+    let escrow = integrity::escrow_secret_name(&old_key_id);
+    assert_eq!(report["escrow_secret"], escrow.as_str());
+    assert!(secrets::list_secrets(&op, ws_path)
+        .await?
+        .iter()
+        .any(|secret| secret["name"] == escrow.as_str()));
+
+    // An interrupted job resumes after the forms it finished, and a job for
+    // another key waits for it.
+    op.write(
+        &format!("{}/resign_job.json", ws_path),
+        serde_json::to_vec(&serde_json::json!({
+            "key_id": new_key_id,
+            "started_at": 0.0,
+            "forms_done": ["Entry"],
+            "assets_done": false,
+        }))?,
+    )
+    .await?;
+    assert!(
+        integrity::resign_space(&op, ws_path, &new_provider, "key-other")
+            .await
+            .is_err()
+    );
+    let resumed = integrity::resign_space(&op, ws_path, &new_provider, &new_key_id).await?;
+    assert_eq!(resumed["resumed"], true);
+    assert_eq!(resumed["forms"], 1);
+    assert_eq!(resumed["entries"], 1);
+    assert_eq!(resumed["assets"], 1);
+    let fresh = integrity::resign_space(&op, ws_path, &new_provider, &new_key_id).await?;
+    assert_eq!(fresh["resumed"], false);
+    assert_eq!(fresh["entries"], 2);
+    entry::get_entry_verified(&op, ws_path, "signed", &new_provider, Some(true)).await?;
+    Ok(())
+}
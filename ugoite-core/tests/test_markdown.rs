@@ -0,0 +1,142 @@
+mod common;
+
+use _ugoite_core::entry;
+use _ugoite_core::form;
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::space;
+use common::setup_operator;
+use proptest::collection::{btree_map, vec};
+use proptest::prelude::*;
+use serde_json::Value;
+
+/// Single-line text without surrounding whitespace.
+fn line_text() -> impl Strategy<Value = String> {
+    "[^\\n\\r#]{0,24}".prop_map(|s| s.trim().to_string())
+}
+
+fn title() -> impl Strategy<Value = String> {
+    line_text().prop_filter("non-empty title", |s| !s.is_empty())
+}
+
+/// Scalars that YAML would otherwise retype or split: numbers, bools,
+/// nulls, `key: value` pairs and flow/comment markers.
+fn yaml_tricky() -> impl Strategy<Value = String> {
+    prop_oneof![
+        Just("123".to_string()),
+        Just("true".to_string()),
+        Just("null".to_string()),
+        Just("~".to_string()),
+        Just("a: b".to_string()),
+        Just("[x]".to_string()),
+        Just("tag # note".to_string()),
+        Just("- item".to_string()),
+    ]
+}
+
+fn scalar() -> impl Strategy<Value = String> {
+    prop_oneof![
+        3 => "[A-Za-z][A-Za-z0-9_-]{0,11}",
+        1 => yaml_tricky(),
+        1 => title(),
+    ]
+}
+
+/// Section bodies: any lines that do not start a heading, even once the
+/// body is trimmed.
+fn section_body() -> impl Strategy<Value = String> {
+    vec("[^\\s#][^\\n\\r]{0,30}|", 0..4).prop_map(|lines| lines.join("\n").trim().to_string())
+}
+
+fn sections() -> impl Strategy<Value = std::collections::BTreeMap<String, String>> {
+    btree_map(title(), section_body(), 0..5)
+}
+
+fn fields_value(sections: &std::collections::BTreeMap<String, String>) -> Value {
+    Value::Object(
+        sections
+            .iter()
+            .map(|(k, v)| (k.clone(), Value::String(v.clone())))
+            .collect(),
+    )
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(256))]
+
+    /// REQ-ENTRY-011
+    #[test]
+    fn test_markdown_req_entry_011_render_parse_round_trip(
+        title in title(),
+        form_name in scalar(),
+        tags in vec(scalar(), 0..4),
+        sections in sections(),
+    ) {
+        let fields = fields_value(&sections);
+        let markdown = entry::render_markdown(&title, &form_name, &tags, &fields, &[]);
+
+        let (frontmatter, parsed) = entry::parse_markdown(&markdown).expect("parse rendered");
+        prop_assert_eq!(entry::extract_title(&markdown, ""), title.clone());
+        prop_assert_eq!(frontmatter["form"].as_str(), Some(form_name.as_str()));
+        let parsed_tags: Vec<String> = frontmatter
+            .get("tags")
+            .and_then(|v| v.as_array())
+            .map(|items| items.iter().filter_map(|t| t.as_str().map(str::to_string)).collect())
+            .unwrap_or_default();
+        prop_assert_eq!(parsed_tags, tags.clone());
+        prop_assert_eq!(&parsed, &fields);
+
+        // parse -> render -> parse is stable.
+        let rerendered = entry::render_markdown(&title, &form_name, &tags, &parsed, &[]);
+        prop_assert_eq!(&rerendered, &markdown);
+        let (_, reparsed) = entry::parse_markdown(&rerendered).expect("parse re-rendered");
+        prop_assert_eq!(reparsed, parsed);
+    }
+}
+
+#[test]
+/// REQ-ENTRY-011
+fn test_markdown_req_entry_011_malformed_frontmatter_is_rejected() {
+    for content in [
+        "---\nform: [Entry\n---\n# Title\n",
+        "---\n- just\n- a list\n---\n# Title\n",
+        "---\nform: \"Entry\n---\n# Title\n",
+    ] {
+        let err = entry::parse_markdown(content).expect_err(content);
+        assert!(
+            err.to_string().contains("Invalid frontmatter YAML"),
+            "{}",
+            err
+        );
+    }
+
+    let (frontmatter, _) = entry::parse_markdown("---\n\n---\n# Title\n").expect("empty block");
+    assert_eq!(frontmatter, serde_json::json!({}));
+}
+
+#[tokio::test]
+/// REQ-ENTRY-011
+async fn test_markdown_req_entry_011_create_entry_rejects_malformed_frontmatter(
+) -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "markdown-space", "/tmp").await?;
+    let ws_path = "spaces/markdown-space";
+    let form_def = serde_json::json!({
+        "name": "Entry",
+        "template": "# Entry\n\n## Body\n",
+        "fields": {"Body": {"type": "markdown"}},
+    });
+    form::upsert_form(&op, ws_path, &form_def).await?;
+
+    let result = entry::create_entry(
+        &op,
+        ws_path,
+        "broken",
+        "---\nform: Entry\ntags: [a, b\n---\n# Broken\n\n## Body\ntext",
+        "author",
+        &FakeIntegrityProvider,
+    )
+    .await;
+    assert!(result.is_err());
+    assert!(entry::get_entry(&op, ws_path, "broken").await.is_err());
+    Ok(())
+}
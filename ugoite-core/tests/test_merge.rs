@@ -0,0 +1,128 @@
+mod common;
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::merge::{self, MergeStrategy};
+use _ugoite_core::{entry, form, link, space};
+use common::setup_operator;
+
+#[tokio::test]
+/// REQ-ENTRY-024
+async fn test_merge_req_entry_024_merge_entries() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "merge-space", "/tmp").await?;
+    let ws_path = "spaces/merge-space";
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Contact",
+            "template": "# Contact\n\n## Email\n\n## Phone\n\n## Notes\n",
+            "fields": {
+                "Email": {"type": "string"},
+                "Phone": {"type": "string"},
+                "Notes": {"type": "markdown"},
+            },
+        }),
+    )
+    .await?;
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Task",
+            "template": "# Task\n\n## Owner\n\n## Body\n",
+            "fields": {
+                "Owner": {"type": "row_reference", "target_form": "Contact"},
+                "Body": {"type": "markdown"},
+            },
+        }),
+    )
+    .await?;
+    let integrity = FakeIntegrityProvider;
+    for (id, content) in [
+        (
+            "alice",
+            "---\nform: Contact\ntags: [customer]\n---\n# Alice\n\n## Email\nalice@example.com\n\n## Notes\nMet at the expo.",
+        ),
+        (
+            "alice-dup",
+            "---\nform: Contact\ntags: [vip]\n---\n# Alice S.\n\n## Email\nold@example.com\n\n## Phone\n555-0100\n\n## Notes\nPrefers tea.",
+        ),
+        (
+            "task-1",
+            "---\nform: Task\n---\n# Call\n\n## Owner\nalice-dup\n\n## Body\nSee ugoite://entry/alice-dup first.",
+        ),
+    ] {
+        entry::create_entry(&op, ws_path, id, content, "author", &integrity).await?;
+    }
+    link::create_link(&op, ws_path, "alice-dup", "task-1", "related", "link-1").await?;
+    link::create_link(&op, ws_path, "alice", "alice-dup", "same", "link-2").await?;
+
+    let strategy: MergeStrategy = serde_json::from_value(serde_json::json!({
+        "fields": {"Notes": "combine"},
+    }))?;
+    let report = merge::merge_entries(
+        &op,
+        ws_path,
+        "alice",
+        "alice-dup",
+        &strategy,
+        "merger",
+        &integrity,
+    )
+    .await?;
+    assert_eq!(report["links_moved"], 1);
+    assert_eq!(
+        report["references_rewritten"],
+        serde_json::json!(["task-1"])
+    );
+
+    let alice = entry::get_entry(&op, ws_path, "alice").await?;
+    assert_eq!(alice["title"], "Alice");
+    assert_eq!(alice["sections"]["Email"], "alice@example.com");
+    assert_eq!(alice["sections"]["Phone"], "555-0100");
+    assert_eq!(
+        alice["sections"]["Notes"],
+        "Met at the expo.\n\nPrefers tea."
+    );
+    assert_eq!(alice["tags"], serde_json::json!(["customer", "vip"]));
+    assert_eq!(
+        alice["metadata"]["merged_from"],
+        serde_json::json!(["alice-dup"])
+    );
+    let links = alice["links"].as_array().cloned().unwrap_or_default();
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0]["target"], "task-1");
+
+    let task = entry::get_entry(&op, ws_path, "task-1").await?;
+    assert_eq!(task["sections"]["Owner"], "alice");
+    assert_eq!(task["sections"]["Body"], "See ugoite://entry/alice first.");
+    assert_eq!(task["links"][0]["target"], "alice");
+
+    // The secondary is soft-deleted but keeps its history and a pointer.
+    assert!(entry::get_entry(&op, ws_path, "alice-dup").await.is_err());
+    let existence = entry::exists(&op, ws_path, "alice-dup").await?;
+    assert!(existence.is_some_and(|e| e.deleted));
+    let history = entry::get_entry_history(&op, ws_path, "alice-dup").await?;
+    assert!(!history["revisions"].as_array().unwrap().is_empty());
+    let history = entry::get_entry_history(&op, ws_path, "alice").await?;
+    assert!(history["revisions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|rev| rev["message"]
+            .as_str()
+            .is_some_and(|m| m.starts_with("Merged alice-dup"))));
+
+    assert!(merge::merge_entries(
+        &op,
+        ws_path,
+        "alice",
+        "alice",
+        &MergeStrategy::default(),
+        "merger",
+        &integrity
+    )
+    .await
+    .is_err());
+    Ok(())
+}
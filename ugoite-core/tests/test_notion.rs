@@ -0,0 +1,160 @@
+mod common;
+use _ugoite_core::entry;
+use _ugoite_core::export;
+use _ugoite_core::form;
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::notion;
+use _ugoite_core::space;
+use common::setup_operator;
+
+const HOME: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+const TASKS: &str = "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+const ALPHA: &str = "cccccccccccccccccccccccccccccccc";
+
+fn notion_export() -> anyhow::Result<Vec<u8>> {
+    let home = format!(
+        "# Home\n\nSee [Alpha](Tasks%20{TASKS}/Alpha%20{ALPHA}.md) and ![diagram](Home%20{HOME}/diagram.png).\n\n## Notes\nplain\n\n```sh\n# comment\n```\n\n[site](https://example.com) [gone](Missing%20dddddddddddddddddddddddddddddddd.md)\n"
+    );
+    let header = "\u{feff}Name,Status,Points,Due,Done,Related\r\n";
+    let visible = format!(
+        "{header}Alpha,In progress,3,\"January 5, 2025\",Yes,Home (Home%20{HOME}.md)\r\nBeta,\"Done, really\",5,\"February 1, 2025\",No,\r\n"
+    );
+    let all = format!("{visible}Gamma,Backlog,,,No,\r\n");
+    let alpha = format!(
+        "# Alpha\n\nStatus: In progress\nPoints: 3\n\nWork on [home](../Home%20{HOME}.md).\n"
+    );
+    let files = vec![
+        (format!("Home {HOME}.md"), home.into_bytes(), 0.0),
+        (
+            format!("Home {HOME}/diagram.png"),
+            b"\x89PNG\r\n\x1a\n0000".to_vec(),
+            0.0,
+        ),
+        (format!("Tasks {TASKS}.csv"), visible.into_bytes(), 0.0),
+        (format!("Tasks {TASKS}_all.csv"), all.into_bytes(), 0.0),
+        (
+            format!("Tasks {TASKS}/Alpha {ALPHA}.md"),
+            alpha.into_bytes(),
+            0.0,
+        ),
+    ];
+    let part = export::zip_archive(&files)?;
+    // Large exports nest one zip per part.
+    export::zip_archive(&[("Export-Part-1.zip".to_string(), part, 0.0)])
+}
+
+#[tokio::test]
+/// REQ-INT-011
+async fn test_notion_req_int_011_import_markdown_csv_export() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "notion-space", "/tmp").await?;
+    let ws_path = "spaces/notion-space";
+    let integrity = FakeIntegrityProvider;
+
+    let report =
+        notion::import_notion(&op, ws_path, &notion_export()?, "importer", &integrity).await?;
+    assert_eq!(report["failed"], serde_json::json!([]));
+    assert_eq!(report["forms"][0]["form"], "Tasks");
+    assert_eq!(
+        report["forms"][0]["fields"],
+        serde_json::json!({
+            "Status": "string",
+            "Points": "number",
+            "Due": "date",
+            "Done": "boolean",
+            "Related": "string",
+        })
+    );
+    let tasks = form::get_form(&op, ws_path, "Tasks").await?;
+    assert_eq!(tasks["fields"]["Body"]["type"], "markdown");
+
+    let entries = report["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 4);
+    let id_of = |title: &str| {
+        entries
+            .iter()
+            .find(|e| e["title"] == title)
+            .map(|e| e["entry_id"].as_str().unwrap().to_string())
+            .unwrap()
+    };
+    assert_eq!(
+        entries.iter().find(|e| e["title"] == "Home").unwrap()["form"],
+        notion::PAGE_FORM
+    );
+    assert_eq!(report["assets"].as_array().unwrap().len(), 1);
+    let asset_link = report["assets"][0]["link"].as_str().unwrap();
+
+    let home = entry::get_entry(&op, ws_path, &id_of("Home")).await?;
+    let home_content = home["content"].as_str().unwrap();
+    assert!(home_content.contains(&format!("[Alpha](ugoite://entry/{})", id_of("Alpha"))));
+    assert!(home_content.contains(&format!("![diagram]({})", asset_link)));
+    assert!(home_content.contains("**Notes**"));
+    assert!(home_content.contains(" # comment"));
+    assert!(home_content.contains("https://example.com"));
+
+    let alpha = entry::get_entry(&op, ws_path, &id_of("Alpha")).await?;
+    let alpha_content = alpha["content"].as_str().unwrap();
+    assert!(alpha_content.contains("## Due\n2025-01-05"));
+    assert!(alpha_content.contains(&format!("Home (ugoite://entry/{})", id_of("Home"))));
+    assert!(alpha_content.contains(&format!("[home](ugoite://entry/{})", id_of("Home"))));
+    assert!(!alpha_content.contains("Status: In progress"));
+    // Rows hidden by the view come from the `_all` CSV.
+    entry::get_entry(&op, ws_path, &id_of("Gamma")).await?;
+
+    assert_eq!(report["links"]["remapped"], 4);
+    assert_eq!(report["links"]["unresolved"].as_array().unwrap().len(), 1);
+    assert!(
+        notion::import_notion(&op, ws_path, b"not a zip", "importer", &integrity)
+            .await
+            .is_err()
+    );
+    Ok(())
+}
+
+/// Single-entry zip whose entry is deflated `content` while its headers
+/// record `claimed_size` bytes.
+fn deflated_zip(content: &[u8], claimed_size: u32) -> anyhow::Result<Vec<u8>> {
+    use std::io::Write;
+    let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::best());
+    encoder.write_all(content)?;
+    let mut zip = export::zip_archive(&[("page.md".to_string(), encoder.finish()?, 0.0)])?;
+    let central = zip.len() - 22 - 46 - "page.md".len();
+    for (header, method_at, size_at) in [(0, 8, 22), (central, 10, 24)] {
+        zip[header + method_at..header + method_at + 2].copy_from_slice(&8u16.to_le_bytes());
+        zip[header + size_at..header + size_at + 4].copy_from_slice(&claimed_size.to_le_bytes());
+    }
+    Ok(zip)
+}
+
+#[tokio::test]
+/// REQ-INT-011
+async fn test_notion_req_int_011_rejects_malicious_archives() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "notion-bomb", "/tmp").await?;
+    let ws_path = "spaces/notion-bomb";
+    let import = |archive: Vec<u8>| {
+        let op = op.clone();
+        async move {
+            notion::import_notion(&op, ws_path, &archive, "importer", &FakeIntegrityProvider)
+                .await
+                .expect_err("malicious archive must be rejected")
+                .to_string()
+        }
+    };
+
+    // Inflates far past the size its headers record.
+    let bomb = deflated_zip(&vec![0u8; 16 << 20], 1024)?;
+    assert!(import(bomb).await.contains("recorded size"));
+    // Records a size beyond what an export may unpack to.
+    let huge = deflated_zip(b"tiny", u32::MAX - 1)?;
+    assert!(import(huge).await.contains("more than"));
+    // Zips nested inside zips, over and over.
+    let mut nested = export::zip_archive(&[("page.md".to_string(), b"# Page\n".to_vec(), 0.0)])?;
+    for _ in 0..5 {
+        nested = export::zip_archive(&[("part.zip".to_string(), nested, 0.0)])?;
+    }
+    assert!(import(nested).await.contains("levels deep"));
+
+    assert!(entry::list_entries(&op, ws_path).await?.is_empty());
+    Ok(())
+}
@@ -0,0 +1,118 @@
+mod common;
+use _ugoite_core::entry::{self, ArchivedFilter};
+use _ugoite_core::export;
+use _ugoite_core::form;
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::progress;
+use _ugoite_core::space;
+use common::setup_operator;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[tokio::test]
+/// REQ-API-012
+async fn test_progress_req_api_012_streams_with_backpressure() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "progress-space", "/tmp").await?;
+    let ws_path = "spaces/progress-space";
+    let integrity = FakeIntegrityProvider;
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Note",
+            "template": "# Note\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    for id in ["e1", "e2", "e3", "e4", "e5"] {
+        let content = format!("---\nform: Note\n---\n# {id}\n\n## Body\ntext");
+        entry::create_entry(&op, ws_path, id, &content, "author", &integrity).await?;
+    }
+    entry::delete_entry(&op, ws_path, "e5", false).await?;
+
+    let stream_op = op.clone();
+    let mut stream = progress::spawn(1, move |tx| async move {
+        entry::stream_entries(
+            &stream_op,
+            "spaces/progress-space",
+            2,
+            &[],
+            ArchivedFilter::Exclude,
+            &tx,
+        )
+        .await
+    });
+    let mut chunks = Vec::new();
+    while let Some(message) = stream.next().await {
+        let message = message?;
+        assert_eq!(message["type"], "items");
+        let ids: Vec<String> = message["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["id"].as_str().unwrap().to_string())
+            .collect();
+        chunks.push(ids);
+    }
+    assert_eq!(chunks, vec![vec!["e1", "e2"], vec!["e3", "e4"]]);
+
+    // A consumer that does not read holds the producer at the capacity, and
+    // dropping the stream makes its next send fail.
+    let sent = Arc::new(AtomicUsize::new(0));
+    let finished = Arc::new(tokio::sync::Notify::new());
+    let stream = progress::spawn(1, {
+        let sent = sent.clone();
+        let finished = finished.clone();
+        move |tx| async move {
+            let result = async {
+                for n in 0..10 {
+                    tx.send(serde_json::json!({"n": n})).await?;
+                    sent.fetch_add(1, Ordering::SeqCst);
+                }
+                anyhow::Ok(())
+            }
+            .await;
+            finished.notify_one();
+            result
+        }
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(sent.load(Ordering::SeqCst), 1);
+    drop(stream);
+    tokio::time::timeout(Duration::from_secs(1), finished.notified()).await?;
+    assert_eq!(sent.load(Ordering::SeqCst), 1);
+
+    let bundle = setup_operator()?;
+    let export_op = op.clone();
+    let mut stream = progress::spawn(progress::DEFAULT_CAPACITY, move |tx| async move {
+        let report = export::export_space_with_progress(
+            &export_op,
+            "spaces/progress-space",
+            &bundle,
+            &export::SyncFilter::default(),
+            Some(&tx),
+        )
+        .await?;
+        tx.result(report).await
+    });
+    let mut messages = Vec::new();
+    while let Some(message) = stream.next().await {
+        messages.push(message?);
+    }
+    let last = messages.pop().unwrap();
+    assert_eq!(last["type"], "result");
+    assert_eq!(last["result"]["entries"], 4);
+    assert_eq!(
+        messages.last().unwrap(),
+        &serde_json::json!({"type": "progress", "stage": "entries", "done": 4, "total": 4})
+    );
+
+    let mut failing = progress::spawn(1, |_tx| async { Err(anyhow::anyhow!("boom")) });
+    let err = failing.next().await.unwrap().unwrap_err();
+    assert_eq!(err.to_string(), "boom");
+    assert!(failing.next().await.is_none());
+    Ok(())
+}
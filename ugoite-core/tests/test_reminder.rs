@@ -0,0 +1,117 @@
+mod common;
+use _ugoite_core::entry;
+use _ugoite_core::form;
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::reminder::{self, Recurrence};
+use _ugoite_core::space;
+use common::setup_operator;
+
+#[tokio::test]
+/// REQ-ENTRY-016
+async fn test_reminder_req_entry_016_due_reminders_and_completion() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "reminder-space", "/tmp").await?;
+    let ws_path = "spaces/reminder-space";
+    for name in ["MaintenanceTicket", "Entry"] {
+        form::upsert_form(
+            &op,
+            ws_path,
+            &serde_json::json!({
+                "name": name,
+                "template": format!("# {}\n\n## Body\n", name),
+                "fields": {"Body": {"type": "markdown"}},
+            }),
+        )
+        .await?;
+    }
+    let integrity = FakeIntegrityProvider;
+    for (id, form_name) in [("pump", "MaintenanceTicket"), ("memo", "Entry")] {
+        entry::create_entry(
+            &op,
+            ws_path,
+            id,
+            &format!("---\nform: {}\n---\n# {}\n\n## Body\ncheck", form_name, id),
+            "author",
+            &integrity,
+        )
+        .await?;
+    }
+
+    let once = reminder::add_reminder(
+        &op,
+        ws_path,
+        "pump",
+        1_000.0,
+        None,
+        serde_json::json!({"channel": "email"}),
+    )
+    .await?;
+    assert_eq!(once.form, "MaintenanceTicket");
+    let weekly = reminder::add_reminder(
+        &op,
+        ws_path,
+        "pump",
+        2_000.0,
+        Some(Recurrence::Weekly),
+        serde_json::Value::Null,
+    )
+    .await?;
+    reminder::add_reminder(&op, ws_path, "memo", 1_500.0, None, serde_json::Value::Null).await?;
+    reminder::add_reminder(&op, ws_path, "memo", 1.0e12, None, serde_json::Value::Null).await?;
+    assert!(
+        reminder::add_reminder(&op, ws_path, "missing", 1.0, None, serde_json::Value::Null)
+            .await
+            .is_err()
+    );
+
+    // Earliest first, bounded by `before`, optionally per form.
+    let due = reminder::list_due_reminders(&op, ws_path, 2_000.0, None).await?;
+    let due_at: Vec<f64> = due.iter().map(|r| r.due_at).collect();
+    assert_eq!(due_at, [1_000.0, 1_500.0, 2_000.0]);
+    assert_eq!(due[0].payload["channel"], "email");
+    let tickets =
+        reminder::list_due_reminders(&op, ws_path, 2_000.0, Some("MaintenanceTicket")).await?;
+    assert_eq!(tickets.len(), 2);
+
+    // One-off reminders close; recurring ones move past now.
+    let done = reminder::complete_reminder(&op, ws_path, &once.id).await?;
+    assert!(done.completed_at.is_some());
+    assert!(reminder::complete_reminder(&op, ws_path, &once.id)
+        .await
+        .is_err());
+    let next = reminder::complete_reminder(&op, ws_path, &weekly.id).await?;
+    assert!(next.completed_at.is_none());
+    assert!(next.last_completed_at.is_some());
+    assert!(next.due_at > next.last_completed_at.unwrap());
+    assert_eq!((next.due_at - 2_000.0) % (7.0 * 86_400.0), 0.0);
+
+    let due =
+        reminder::list_due_reminders(&op, ws_path, 2_000.0, Some("MaintenanceTicket")).await?;
+    assert!(due.is_empty());
+    assert_eq!(
+        reminder::list_entry_reminders(&op, ws_path, "pump")
+            .await?
+            .len(),
+        2
+    );
+
+    assert!(reminder::delete_reminder(&op, ws_path, &weekly.id).await?);
+    assert!(!reminder::delete_reminder(&op, ws_path, &weekly.id).await?);
+    assert_eq!(
+        reminder::list_entry_reminders(&op, ws_path, "pump")
+            .await?
+            .len(),
+        1
+    );
+    Ok(())
+}
+
+#[test]
+/// REQ-ENTRY-016
+fn test_reminder_req_entry_016_monthly_recurrence_clamps() -> anyhow::Result<()> {
+    // 2024-01-31T00:00:00Z -> 2024-02-29T00:00:00Z
+    let next = Recurrence::Monthly.advance(1_706_659_200.0)?;
+    assert_eq!(next, 1_709_164_800.0);
+    assert!("hourly".parse::<Recurrence>().is_err());
+    Ok(())
+}
@@ -0,0 +1,60 @@
+mod common;
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::{entry, form, iceberg_store, replica, space};
+use common::setup_operator;
+
+async fn copy_catalog(from: &opendal::Operator, to: &opendal::Operator) -> anyhow::Result<()> {
+    let path = "spaces/replica-space/catalog.json";
+    to.write(path, from.read(path).await?.to_vec()).await?;
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-STO-020
+async fn test_replica_req_sto_020_stale_replica_falls_back_to_primary() -> anyhow::Result<()> {
+    let primary = setup_operator()?;
+    let replica_op = setup_operator()?;
+    space::create_space(&primary, "replica-space", "/tmp").await?;
+    let ws_path = "spaces/replica-space";
+    form::upsert_form(
+        &primary,
+        ws_path,
+        &serde_json::json!({
+            "name": "Entry",
+            "template": "# Entry\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    let integrity = FakeIntegrityProvider;
+    let content = "---\nform: Entry\n---\n# One\n\n## Body\nfirst";
+    entry::create_entry(&primary, ws_path, "one", content, "author", &integrity).await?;
+    iceberg_store::ensure_catalog_manifest(&primary, ws_path).await?;
+
+    let status = replica::replica_status(&primary, &replica_op, ws_path).await?;
+    assert!(!status.fresh);
+    assert!(status.stale_tables.contains(&"Entry/entries".to_string()));
+
+    copy_catalog(&primary, &replica_op).await?;
+    replica_op
+        .write("spaces/replica-space/marker", b"replica".to_vec())
+        .await?;
+    let status = replica::replica_status(&primary, &replica_op, ws_path).await?;
+    assert!(status.fresh);
+    assert!(status.stale_tables.is_empty());
+    let reader = replica::read_operator(&primary, &replica_op, ws_path).await?;
+    assert!(reader.exists("spaces/replica-space/marker").await?);
+
+    // A write on the primary moves its table version past the replica's.
+    let content = "---\nform: Entry\n---\n# Two\n\n## Body\nsecond";
+    entry::create_entry(&primary, ws_path, "two", content, "author", &integrity).await?;
+    let status = replica::replica_status(&primary, &replica_op, ws_path).await?;
+    assert!(!status.fresh);
+    assert_eq!(
+        status.stale_tables,
+        vec!["Entry/entries", "Entry/revisions"]
+    );
+    let reader = replica::read_operator(&primary, &replica_op, ws_path).await?;
+    assert!(!reader.exists("spaces/replica-space/marker").await?);
+    Ok(())
+}
@@ -0,0 +1,23 @@
+use _ugoite_core::storage;
+
+#[tokio::test]
+/// REQ-STO-024
+async fn test_request_req_sto_024_request_id_scope() -> anyhow::Result<()> {
+    assert_eq!(storage::current_request_id(), None);
+    let seen = storage::with_request_id("req-1".to_string(), "get_entry", async {
+        let nested = storage::with_request_id("req-2".to_string(), "list_entries", async {
+            storage::current_request_id()
+        })
+        .await;
+        (storage::current_request_id(), nested)
+    })
+    .await;
+    assert_eq!(seen.0.as_deref(), Some("req-1"));
+    assert_eq!(seen.1.as_deref(), Some("req-2"));
+    assert_eq!(storage::current_request_id(), None);
+
+    let generated = storage::new_request_id();
+    assert_eq!(generated.len(), 32);
+    assert_ne!(generated, storage::new_request_id());
+    Ok(())
+}
@@ -0,0 +1,110 @@
+mod common;
+use _ugoite_core::audit;
+use _ugoite_core::entry::{self, ArchivedFilter};
+use _ugoite_core::form;
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::retention;
+use _ugoite_core::space;
+use common::setup_operator;
+
+#[tokio::test]
+/// REQ-ENTRY-020
+async fn test_retention_req_entry_020_dry_run_then_apply() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "retention-space", "/tmp").await?;
+    let ws_path = "spaces/retention-space";
+    let integrity = FakeIntegrityProvider;
+
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "EnergyReport",
+            "template": "# EnergyReport\n\n## Date\n\n## Body\n",
+            "fields": {"Date": {"type": "date"}, "Body": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "RouteLog",
+            "template": "# RouteLog\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    for (id, date) in [
+        ("old", "2020-01-01"),
+        ("future", "2999-01-01"),
+        ("undated", ""),
+    ] {
+        let content =
+            format!("---\nform: EnergyReport\n---\n# {id}\n\n## Date\n{date}\n\n## Body\nkWh");
+        entry::create_entry(&op, ws_path, id, &content, "author", &integrity).await?;
+    }
+    entry::create_entry(
+        &op,
+        ws_path,
+        "route-1",
+        "---\nform: RouteLog\n---\n# route-1\n\n## Body\nA to B",
+        "author",
+        &integrity,
+    )
+    .await?;
+    space::patch_space(
+        &op,
+        "retention-space",
+        &serde_json::json!({"settings": {"retention": {
+            "EnergyReport": {"after_days": 30, "field": "Date", "action": "archive"},
+            "RouteLog": {"after_days": 7},
+            "Missing": {"after_days": 1},
+        }}}),
+    )
+    .await?;
+    let now = chrono::Utc::now().timestamp() as f64 + 10.0 * 86_400.0;
+
+    let dry = retention::apply_retention(&op, ws_path, now, true).await?;
+    let expired: Vec<&str> = dry["expired"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["entry_id"].as_str().unwrap())
+        .collect();
+    assert_eq!(expired, vec!["old", "route-1"]);
+    assert_eq!(
+        dry["totals"],
+        serde_json::json!({"archive": 1, "delete": 1})
+    );
+    let skipped = dry["skipped"].as_array().unwrap();
+    assert!(skipped.iter().any(|s| s["form"] == "Missing"));
+    assert!(skipped
+        .iter()
+        .any(|s| s["entry_id"] == "undated" && s["reason"] == "missing_date"));
+    assert!(entry::get_entry(&op, ws_path, "old").await.is_ok());
+
+    let applied = retention::apply_retention(&op, ws_path, now, false).await?;
+    assert_eq!(applied["expired"], dry["expired"]);
+    assert!(entry::get_entry(&op, ws_path, "route-1").await.is_err());
+    assert!(entry::get_entry(&op, ws_path, "future").await.is_ok());
+    // Archived entries stay readable but leave default listings.
+    let archived = entry::get_entry(&op, ws_path, "old").await?;
+    assert!(archived["content"].as_str().unwrap().contains("2020-01-01"));
+    let archived = entry::list_entries_including(&op, ws_path, &[], ArchivedFilter::Only).await?;
+    assert_eq!(archived.len(), 1);
+    assert_eq!(archived[0]["id"], "old");
+    assert!(!entry::list_entries(&op, ws_path)
+        .await?
+        .iter()
+        .any(|e| e["id"] == "old"));
+    assert!(!op.exists(&format!("{}/archive/", ws_path)).await?);
+    let events = audit::list_audit_events(&op, ws_path, Some("retention.applied")).await?;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["id"], applied["audit_event_id"]);
+
+    // Entries already archived are not expired again.
+    let again = retention::apply_retention(&op, ws_path, now, true).await?;
+    assert_eq!(again["expired"], serde_json::json!([]));
+    Ok(())
+}
@@ -0,0 +1,170 @@
+mod common;
+use _ugoite_core::entry;
+use _ugoite_core::form;
+use _ugoite_core::index;
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::space;
+use common::{setup_fs_operator, setup_operator};
+
+fn site_form(rollups: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
+        "name": "Site",
+        "template": "# Site\n\n## Body\n",
+        "fields": {"Body": {"type": "markdown"}},
+        "rollups": rollups,
+    })
+}
+
+fn inspection(site: &str, score: &str) -> String {
+    format!(
+        "---\nform: Inspection\n---\n# Inspection\n\n## Site\n{}\n\n## ConditionScore\n{}\n",
+        site, score
+    )
+}
+
+#[tokio::test]
+/// REQ-FORM-009
+async fn test_rollup_req_form_009_rollups_follow_child_writes() -> anyhow::Result<()> {
+    let (op, _root) = setup_fs_operator()?;
+    space::create_space(&op, "rollup-space", "/tmp").await?;
+    let ws_path = "spaces/rollup-space";
+    let integrity = FakeIntegrityProvider;
+
+    form::upsert_form(&op, ws_path, &site_form(serde_json::Value::Null)).await?;
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Inspection",
+            "template": "# Inspection\n\n## Site\n\n## ConditionScore\n",
+            "fields": {
+                "Site": {"type": "row_reference", "target_form": "Site"},
+                "ConditionScore": {"type": "double"},
+            },
+        }),
+    )
+    .await?;
+    for id in ["north", "south"] {
+        let content = format!("---\nform: Site\n---\n# {}\n\n## Body\nSite", id);
+        entry::create_entry(&op, ws_path, id, &content, "author", &integrity).await?;
+    }
+    entry::create_entry(
+        &op,
+        ws_path,
+        "i-1",
+        &inspection("north", "3"),
+        "a",
+        &integrity,
+    )
+    .await?;
+
+    // Defining rollups computes them for existing entries.
+    form::upsert_form(
+        &op,
+        ws_path,
+        &site_form(serde_json::json!({
+            "InspectionCount": {"source_form": "Inspection", "via": "Site", "aggregate": "count"},
+            "AvgCondition": {
+                "source_form": "Inspection",
+                "via": "Site",
+                "aggregate": "avg",
+                "field": "ConditionScore",
+            },
+        })),
+    )
+    .await?;
+    let north = entry::get_entry(&op, ws_path, "north").await?;
+    assert_eq!(
+        north["computed"],
+        serde_json::json!({"InspectionCount": 1, "AvgCondition": 3.0})
+    );
+
+    // Child writes update only the parents they touch.
+    entry::create_entry(
+        &op,
+        ws_path,
+        "i-2",
+        &inspection("ugoite://entry/north", "5"),
+        "a",
+        &integrity,
+    )
+    .await?;
+    let north = entry::get_entry(&op, ws_path, "north").await?;
+    assert_eq!(north["computed"]["InspectionCount"], 2);
+    assert_eq!(north["computed"]["AvgCondition"], 4.0);
+
+    entry::update_entry(
+        &op,
+        ws_path,
+        "i-1",
+        &inspection("south", "1"),
+        None,
+        "a",
+        None,
+        None,
+        &integrity,
+    )
+    .await?;
+    let south = entry::get_entry_content(&op, ws_path, "south").await?;
+    assert_eq!(
+        south.computed,
+        serde_json::json!({"InspectionCount": 1, "AvgCondition": 1.0})
+    );
+    entry::delete_entry(&op, ws_path, "i-2", false).await?;
+    let north = entry::get_entry(&op, ws_path, "north").await?;
+    assert_eq!(
+        north["computed"],
+        serde_json::json!({"InspectionCount": 0, "AvgCondition": null})
+    );
+
+    // Rollups are queryable like fields, including from a fresh index.
+    index::reindex_all(&op, ws_path).await?;
+    entry::create_entry(
+        &op,
+        ws_path,
+        "i-3",
+        &inspection("north", "2"),
+        "a",
+        &integrity,
+    )
+    .await?;
+    let rows =
+        index::query_index(&op, ws_path, r#"{"form": "Site", "InspectionCount": 1}"#).await?;
+    let mut ids: Vec<&str> = rows.iter().map(|r| r["id"].as_str().unwrap()).collect();
+    ids.sort();
+    assert_eq!(ids, vec!["north", "south"]);
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-FORM-009
+async fn test_rollup_req_form_009_rejects_invalid_definitions() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "rollup-invalid", "/tmp").await?;
+    let ws_path = "spaces/rollup-invalid";
+
+    form::upsert_form(&op, ws_path, &site_form(serde_json::Value::Null)).await?;
+    let missing_source = site_form(serde_json::json!({
+        "Count": {"source_form": "Inspection", "via": "Site", "aggregate": "count"},
+    }));
+    assert!(form::upsert_form(&op, ws_path, &missing_source)
+        .await
+        .is_err());
+    let missing_field = site_form(serde_json::json!({
+        "Total": {"source_form": "Site", "via": "Body", "aggregate": "sum"},
+    }));
+    assert!(form::upsert_form(&op, ws_path, &missing_field)
+        .await
+        .is_err());
+    let not_a_reference = site_form(serde_json::json!({
+        "Count": {"source_form": "Site", "via": "Body", "aggregate": "count"},
+    }));
+    assert!(form::upsert_form(&op, ws_path, &not_a_reference)
+        .await
+        .is_err());
+    let shadowing = site_form(serde_json::json!({
+        "Body": {"source_form": "Site", "via": "Body", "aggregate": "count"},
+    }));
+    assert!(form::upsert_form(&op, ws_path, &shadowing).await.is_err());
+    Ok(())
+}
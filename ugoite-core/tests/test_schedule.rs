@@ -0,0 +1,119 @@
+mod common;
+use _ugoite_core::entry;
+use _ugoite_core::form;
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::reminder::Recurrence;
+use _ugoite_core::schedule;
+use _ugoite_core::space;
+use _ugoite_core::template;
+use chrono::{TimeZone, Utc};
+use common::setup_operator;
+
+#[tokio::test]
+/// REQ-ENTRY-028
+async fn test_schedule_req_entry_028_recurring_template_instances() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "schedule-space", "/tmp").await?;
+    let ws_path = "spaces/schedule-space";
+    let integrity = FakeIntegrityProvider;
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "ShiftLog",
+            "template": "# ShiftLog\n\n## Site\n\n## Previous\n",
+            "fields": {
+                "Site": {"type": "string", "required": true},
+                "Previous": {"type": "string"},
+            },
+        }),
+    )
+    .await?;
+    template::save_template(
+        &op,
+        ws_path,
+        "shift-log",
+        "---\nform: ShiftLog\n---\n# Shift log {{today}}\n\n## Site\n{{site}}\n\n## Previous\n{{previous}}\n",
+        None,
+    )
+    .await?;
+
+    let day = 86_400.0;
+    let start = Utc
+        .with_ymd_and_hms(2025, 3, 1, 6, 0, 0)
+        .unwrap()
+        .timestamp() as f64;
+    let added = schedule::add_schedule(
+        &op,
+        ws_path,
+        "shift-log",
+        Recurrence::Daily,
+        start,
+        serde_json::json!({"site": "North"}),
+        "scheduler",
+    )
+    .await?;
+    assert!(schedule::add_schedule(
+        &op,
+        ws_path,
+        "missing",
+        Recurrence::Daily,
+        start,
+        serde_json::Value::Null,
+        "scheduler",
+    )
+    .await
+    .is_err());
+
+    let early = schedule::run_due_schedules(&op, ws_path, start - 1.0, &integrity).await?;
+    assert_eq!(early["created"], serde_json::json!([]));
+
+    let first = schedule::run_due_schedules(&op, ws_path, start, &integrity).await?;
+    assert_eq!(first["created"][0]["entry_id"], "shift-log-2025-03-01");
+    assert!(first["created"][0]["previous_entry_id"].is_null());
+    let again = schedule::run_due_schedules(&op, ws_path, start + 60.0, &integrity).await?;
+    assert_eq!(again["created"], serde_json::json!([]));
+
+    let second = schedule::run_due_schedules(&op, ws_path, start + day, &integrity).await?;
+    assert_eq!(second["created"][0]["entry_id"], "shift-log-2025-03-02");
+    assert_eq!(
+        second["created"][0]["previous_entry_id"],
+        "shift-log-2025-03-01"
+    );
+    let stored = entry::get_entry(&op, ws_path, "shift-log-2025-03-02").await?;
+    assert!(stored["content"]
+        .as_str()
+        .unwrap()
+        .contains("shift-log-2025-03-01"));
+    let links = stored["links"].as_array().unwrap();
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0]["kind"], schedule::PREVIOUS_LINK_KIND);
+    assert_eq!(links[0]["target"], "shift-log-2025-03-01");
+
+    // Missed runs produce a single instance and the schedule moves past now.
+    entry::delete_entry(&op, ws_path, "shift-log-2025-03-02", false).await?;
+    let late = schedule::run_due_schedules(&op, ws_path, start + 5.5 * day, &integrity).await?;
+    assert_eq!(late["created"].as_array().unwrap().len(), 1);
+    assert_eq!(late["created"][0]["entry_id"], "shift-log-2025-03-06");
+    assert!(late["created"][0]["previous_entry_id"].is_null());
+    let schedules = schedule::list_schedules(&op, ws_path).await?;
+    assert_eq!(schedules[0].id, added.id);
+    assert_eq!(schedules[0].next_run_at, start + 6.0 * day);
+    assert_eq!(
+        schedules[0].last_entry_id.as_deref(),
+        Some("shift-log-2025-03-06")
+    );
+
+    // A failing run is reported and stays due.
+    template::save_template(&op, ws_path, "shift-log", "# {{missing}}\n", None).await?;
+    let failing = schedule::run_due_schedules(&op, ws_path, start + 6.0 * day, &integrity).await?;
+    assert_eq!(failing["failed"][0]["schedule_id"], added.id.as_str());
+    assert_eq!(
+        schedule::list_schedules(&op, ws_path).await?[0].next_run_at,
+        start + 6.0 * day
+    );
+
+    assert!(schedule::delete_schedule(&op, ws_path, &added.id).await?);
+    assert!(schedule::list_schedules(&op, ws_path).await?.is_empty());
+    Ok(())
+}
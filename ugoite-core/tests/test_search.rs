@@ -92,3 +92,59 @@ async fn test_search_req_srch_002_fallback_scan() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+/// REQ-SRCH-004
+fn test_search_req_srch_004_analyzer_tokenization() {
+    let analyzer = search::Analyzer {
+        language: "en".to_string(),
+        stemming: true,
+        cjk_ngram: 2,
+    };
+    assert_eq!(
+        search::analyze_text("Inspecting pumps, daily!", &analyzer),
+        vec!["inspect", "pump", "daily"]
+    );
+    assert_eq!(
+        search::analyze_text("東京タワー", &analyzer),
+        vec!["東京", "京タ", "タワ", "ワー"]
+    );
+
+    let plain = search::Analyzer::default();
+    assert_eq!(
+        search::analyze_text("Pumps 東京", &plain),
+        vec!["pumps", "東京"]
+    );
+}
+
+#[tokio::test]
+/// REQ-SRCH-004
+async fn test_search_req_srch_004_space_analyzer_settings() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    let ws_id = "test-search-analyzer";
+    space::create_space(&op, ws_id, "/tmp").await?;
+    let ws_path = format!("spaces/{}", ws_id);
+
+    create_test_entry(&op, &ws_path, "entry1", "The project kickoff").await?;
+    create_test_entry(&op, &ws_path, "entry2", "東京タワーに行きました").await?;
+
+    // Whitespace-separated CJK terms match through n-grams.
+    let results = search::search_entries(&op, &ws_path, "東京 タワー").await?;
+    let ids: Vec<String> = results.into_iter().map(|r| r.id).collect();
+    assert_eq!(ids, vec!["entry2".to_string()]);
+
+    assert!(search::search_entries(&op, &ws_path, "projects")
+        .await?
+        .is_empty());
+    space::patch_space(
+        &op,
+        ws_id,
+        &serde_json::json!({"settings": {"search": {"language": "en", "stemming": true}}}),
+    )
+    .await?;
+    let results = search::search_entries(&op, &ws_path, "projects").await?;
+    let ids: Vec<String> = results.into_iter().map(|r| r.id).collect();
+    assert_eq!(ids, vec!["entry1".to_string()]);
+
+    Ok(())
+}
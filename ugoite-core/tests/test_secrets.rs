@@ -0,0 +1,98 @@
+mod common;
+use _ugoite_core::audit;
+use _ugoite_core::secrets;
+use _ugoite_core::space;
+use common::setup_operator;
+
+#[tokio::test]
+/// REQ-SEC-004
+async fn test_secrets_req_sec_004_encrypted_and_capability_gated() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "secret-space", "/tmp").await?;
+    space::create_space(&op, "other-space", "/tmp").await?;
+    let ws_path = "spaces/secret-space";
+
+    // Without a master key the store fails closed rather than keeping a key
+    // next to the ciphertext.
+    if std::env::var(secrets::MASTER_KEY_ENV).is_err() {
+        assert!(
+            secrets::set_secret(&op, ws_path, "GITHUB_TOKEN", "x", "admin")
+                .await
+                .is_err()
+        );
+        std::env::set_var(secrets::STORED_KEY_OPT_IN_ENV, "1");
+    }
+
+    secrets::set_secret(&op, ws_path, "GITHUB_TOKEN", "ghp-very-secret", "admin").await?;
+    secrets::set_secret(&op, ws_path, "SLACK_HOOK", "https://hooks/x", "admin").await?;
+    assert!(secrets::set_secret(&op, ws_path, "bad name", "x", "admin")
+        .await
+        .is_err());
+    let stored = String::from_utf8(op.read(&format!("{ws_path}/secrets.json")).await?.to_vec())?;
+    assert!(stored.contains("GITHUB_TOKEN"));
+    assert!(!stored.contains("ghp-very-secret"));
+
+    let names: Vec<String> = secrets::list_secrets(&op, ws_path)
+        .await?
+        .iter()
+        .map(|s| s["name"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(names, vec!["GITHUB_TOKEN", "SLACK_HOOK"]);
+
+    let token = secrets::issue_secret_capability(
+        &op,
+        ws_path,
+        "webhook:deploy",
+        &["GITHUB_TOKEN".to_string()],
+        60.0,
+    )
+    .await?;
+    assert_eq!(
+        secrets::get_secret(&op, ws_path, "GITHUB_TOKEN", &token).await?,
+        "ghp-very-secret"
+    );
+    let denied = secrets::get_secret(&op, ws_path, "SLACK_HOOK", &token)
+        .await
+        .unwrap_err();
+    assert!(denied.to_string().contains("does not allow"));
+    assert!(
+        secrets::get_secret(&op, ws_path, "GITHUB_TOKEN", "forged.00")
+            .await
+            .is_err()
+    );
+    let (payload, _) = token.split_once('.').unwrap();
+    let tampered = format!("{payload}.{}", "0".repeat(64));
+    assert!(secrets::get_secret(&op, ws_path, "GITHUB_TOKEN", &tampered)
+        .await
+        .is_err());
+
+    // A token is bound to the space it was issued for.
+    secrets::set_secret(&op, "spaces/other-space", "GITHUB_TOKEN", "other", "admin").await?;
+    assert!(
+        secrets::get_secret(&op, "spaces/other-space", "GITHUB_TOKEN", &token)
+            .await
+            .is_err()
+    );
+
+    let expired =
+        secrets::issue_secret_capability(&op, ws_path, "script", &["*".to_string()], 0.05).await?;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    assert!(secrets::get_secret(&op, ws_path, "SLACK_HOOK", &expired)
+        .await
+        .is_err());
+
+    let reads = audit::list_audit_events(&op, ws_path, Some("secret.read")).await?;
+    assert_eq!(reads.len(), 1);
+    assert_eq!(reads[0]["actor"], "webhook:deploy");
+    assert_eq!(
+        reads[0]["details"],
+        serde_json::json!({"name": "GITHUB_TOKEN"})
+    );
+
+    assert!(secrets::delete_secret(&op, ws_path, "GITHUB_TOKEN", "admin").await?);
+    assert!(!secrets::delete_secret(&op, ws_path, "GITHUB_TOKEN", "admin").await?);
+    assert!(secrets::get_secret(&op, ws_path, "GITHUB_TOKEN", &token)
+        .await
+        .is_err());
+    Ok(())
+}
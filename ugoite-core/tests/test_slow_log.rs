@@ -0,0 +1,109 @@
+mod common;
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::storage::{self, SpaceThrottleLayer};
+use _ugoite_core::{entry, form, space};
+use common::setup_operator;
+
+#[tokio::test]
+/// REQ-STO-025
+async fn test_slow_log_req_sto_025_logs_slow_operations() -> anyhow::Result<()> {
+    let op = setup_operator()?.layer(SpaceThrottleLayer);
+    space::create_space(&op, "slow-space", "/tmp").await?;
+    let ws_path = "spaces/slow-space";
+    space::patch_space(
+        &op,
+        "slow-space",
+        &serde_json::json!({"settings": {"slow_log": {
+            "threshold_ms": 0,
+            "operations": {"list_forms": 1e9},
+        }}}),
+    )
+    .await?;
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Note",
+            "template": "# Note\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    entry::create_entry(
+        &op,
+        ws_path,
+        "pump",
+        "---\nform: Note\n---\n# Pump\n\n## Body\nhello",
+        "author",
+        &FakeIntegrityProvider,
+    )
+    .await?;
+    assert!(storage::list_slow_operations(&op, ws_path, None, None)
+        .await?
+        .is_empty());
+
+    // Every request is over the zero threshold except list_forms.
+    storage::with_request_id(
+        "req-get".to_string(),
+        "get_entry",
+        entry::get_entry(&op, ws_path, "pump"),
+    )
+    .await?;
+    storage::with_request_id(
+        "req-list".to_string(),
+        "list_forms",
+        form::list_forms(&op, ws_path),
+    )
+    .await?;
+    storage::with_request_id(
+        "req-entries".to_string(),
+        "list_entries",
+        entry::list_entries(&op, ws_path),
+    )
+    .await?;
+
+    let records = storage::list_slow_operations(&op, ws_path, Some("get_entry"), None).await?;
+    assert_eq!(records.len(), 1);
+    let record = &records[0];
+    assert_eq!(record["request_id"], "req-get");
+    assert_eq!(record["threshold_ms"], 0.0);
+    assert!(record["duration_ms"].as_f64().unwrap() >= 0.0);
+    assert!(record["timing"]["storage_calls"].as_u64().unwrap() > 0);
+    assert!(record["timing"]["iceberg_scans"].as_u64().unwrap() > 0);
+    assert!(record["timing"]["other_ms"].as_f64().is_some());
+    assert_eq!(record["params"]["entry_ids"], serde_json::json!(["pump"]));
+    assert!(record["params"]["tables"]
+        .as_array()
+        .unwrap()
+        .contains(&serde_json::json!("Note/entries")));
+
+    let operations = |records: Vec<serde_json::Value>| -> Vec<String> {
+        let mut operations: Vec<String> = records
+            .iter()
+            .map(|r| r["operation"].as_str().unwrap().to_string())
+            .collect();
+        operations.sort();
+        operations
+    };
+    let all = storage::list_slow_operations(&op, ws_path, None, None).await?;
+    assert_eq!(operations(all.clone()), ["get_entry", "list_entries"]);
+    assert!(all[0]["duration_ms"].as_f64() >= all[1]["duration_ms"].as_f64());
+    assert_eq!(
+        storage::list_slow_operations(&op, ws_path, None, Some(1))
+            .await?
+            .len(),
+        1
+    );
+
+    // Flushing persists the records, and they are listed once.
+    assert_eq!(storage::flush_slow_log(&op, ws_path).await?, 2);
+    assert_eq!(storage::flush_slow_log(&op, ws_path).await?, 0);
+    let (stored, _) =
+        storage::read_json_versioned(&op, &format!("{}/slow_log.json", ws_path)).await?;
+    assert_eq!(stored.unwrap()["records"].as_array().unwrap().len(), 2);
+    assert_eq!(
+        operations(storage::list_slow_operations(&op, ws_path, None, None).await?),
+        ["get_entry", "list_entries"]
+    );
+    Ok(())
+}
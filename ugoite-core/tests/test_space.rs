@@ -1,5 +1,8 @@
 mod common;
+use _ugoite_core::integrity::{self, FakeIntegrityProvider};
 use _ugoite_core::space;
+use _ugoite_core::storage::{self, WriteCondition};
+use _ugoite_core::{entry, form};
 use common::setup_operator;
 use serde_json::Value;
 
@@ -79,3 +82,416 @@ async fn test_space_req_sto_008_list_spaces_ignores_missing_meta() -> anyhow::Re
 
     Ok(())
 }
+
+#[tokio::test]
+/// REQ-STO-011
+async fn test_space_req_sto_011_conditional_metadata_writes() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "conditional-space", "/tmp/ugoite").await?;
+    let meta_path = "spaces/conditional-space/meta.json";
+
+    let (meta, condition) = storage::read_json_versioned(&op, meta_path).await?;
+    assert!(meta.is_some());
+    assert_ne!(condition, WriteCondition::Absent);
+
+    // Create-only writes refuse to clobber an existing document.
+    let err = storage::write_json_conditional(
+        &op,
+        meta_path,
+        &serde_json::json!({"id": "other"}),
+        &WriteCondition::Absent,
+    )
+    .await
+    .expect_err("existing meta.json must not be overwritten");
+    assert!(storage::is_write_conflict(&err));
+    assert_eq!(
+        err.downcast_ref::<storage::WriteConflict>()
+            .map(|conflict| conflict.path.as_str()),
+        Some(meta_path)
+    );
+    // Context added by callers does not hide the conflict.
+    assert!(storage::is_write_conflict(&err.context("saving space")));
+
+    // A condition read before another writer's change no longer holds.
+    let meta = meta.expect("meta.json");
+    storage::write_json_conditional(&op, meta_path, &meta, &condition).await?;
+    let (_, fresh) = storage::read_json_versioned(&op, meta_path).await?;
+    let mut changed = meta.clone();
+    changed["name"] = serde_json::json!("Changed");
+    storage::write_json_conditional(&op, meta_path, &changed, &fresh).await?;
+    let err = storage::write_json_conditional(&op, meta_path, &meta, &fresh)
+        .await
+        .expect_err("stale condition must be rejected");
+    assert!(storage::is_write_conflict(&err));
+    assert_eq!(
+        space::get_space(&op, "conditional-space").await?.id,
+        "conditional-space"
+    );
+
+    let (missing, condition) =
+        storage::read_json_versioned(&op, "spaces/conditional-space/missing.json").await?;
+    assert!(missing.is_none());
+    assert_eq!(condition, WriteCondition::Absent);
+
+    let patched = space::patch_space(
+        &op,
+        "conditional-space",
+        &serde_json::json!({"name": "Renamed", "settings": {"theme": "dark"}}),
+    )
+    .await?;
+    assert_eq!(patched["name"], "Renamed");
+    assert_eq!(patched["settings"]["theme"], "dark");
+
+    // Keys created on first use: concurrent first callers all end up with
+    // the one key that was stored.
+    let mut meta: Value = serde_json::from_slice(&op.read(meta_path).await?.to_vec())?;
+    meta.as_object_mut().unwrap().remove("hmac_key");
+    op.write(meta_path, serde_json::to_vec(&meta)?).await?;
+    let space_keys = futures::future::try_join_all(
+        (0..8).map(|_| integrity::load_hmac_material(&op, "conditional-space")),
+    )
+    .await?;
+    let stored = integrity::load_hmac_material(&op, "conditional-space").await?;
+    assert!(space_keys.iter().all(|key| *key == stored));
+    let response_keys =
+        futures::future::try_join_all((0..8).map(|_| integrity::load_response_hmac_material(&op)))
+            .await?;
+    let stored = integrity::load_response_hmac_material(&op).await?;
+    assert!(response_keys.iter().all(|key| *key == stored));
+
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-STO-016
+async fn test_space_req_sto_016_upgrade_format_from_v1() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "upgrade-space", "/tmp/ugoite").await?;
+    let ws_path = "spaces/upgrade-space";
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Entry",
+            "template": "# Entry\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    entry::create_entry(
+        &op,
+        ws_path,
+        "entry-1",
+        "---\nform: Entry\n---\n# Upgrade\n\n## Body\nlegacy",
+        "author",
+        &FakeIntegrityProvider,
+    )
+    .await?;
+
+    // Rewind to the v1 layout: no recorded version and no catalog manifest.
+    let meta_path = format!("{}/meta.json", ws_path);
+    let mut meta: Value = serde_json::from_slice(&op.read(&meta_path).await?.to_vec())?;
+    assert_eq!(meta["format_version"], space::SPACE_FORMAT_VERSION);
+    meta.as_object_mut().unwrap().remove("format_version");
+    op.write(&meta_path, serde_json::to_vec_pretty(&meta)?)
+        .await?;
+    op.delete(&format!("{}/catalog.json", ws_path)).await?;
+
+    let report = space::upgrade_format(&op, ws_path).await?;
+    assert_eq!(report["from_version"], 1);
+    assert_eq!(report["to_version"], space::SPACE_FORMAT_VERSION);
+    let steps = report["steps"].as_array().expect("steps");
+    assert!(steps
+        .iter()
+        .any(|s| s["step"] == "catalog_manifest" && s["changed"] == true));
+    // Tables created by this build already have every system column.
+    assert!(steps
+        .iter()
+        .any(|s| s["step"] == "system_columns" && s["changed"] == false));
+    let backup = report["backup"].as_str().expect("backup path");
+    assert!(op.exists(&format!("{}meta.json", backup)).await?);
+    let tables: Value =
+        serde_json::from_slice(&op.read(&format!("{}tables.json", backup)).await?.to_vec())?;
+    assert!(tables.get("Entry/entries").is_some());
+
+    assert!(op.exists(&format!("{}/catalog.json", ws_path)).await?);
+    let upgraded: Value = serde_json::from_slice(&op.read(&meta_path).await?.to_vec())?;
+    assert_eq!(upgraded["format_version"], space::SPACE_FORMAT_VERSION);
+    assert_eq!(
+        entry::get_entry(&op, ws_path, "entry-1").await?["title"],
+        "Upgrade"
+    );
+
+    // Already current: nothing to do, no new backup.
+    let again = space::upgrade_format(&op, ws_path).await?;
+    assert!(again["steps"].as_array().expect("steps").is_empty());
+    assert!(again["backup"].is_null());
+
+    // A current version written before a feature existed is upgraded again.
+    let mut meta: Value = serde_json::from_slice(&op.read(&meta_path).await?.to_vec())?;
+    meta["features"]
+        .as_array_mut()
+        .expect("features")
+        .retain(|f| f != "archived");
+    op.write(&meta_path, serde_json::to_vec_pretty(&meta)?)
+        .await?;
+    let upgraded = space::upgrade_format(&op, ws_path).await?;
+    assert!(upgraded["backup"].is_string());
+    let raw = space::get_space_raw(&op, "upgrade-space").await?;
+    assert!(raw["features"]
+        .as_array()
+        .expect("features")
+        .iter()
+        .any(|f| f == "archived"));
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-STO-017
+async fn test_space_req_sto_017_refuses_newer_incompatible_spaces() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "negotiated-space", "/tmp/ugoite").await?;
+    let meta_path = "spaces/negotiated-space/meta.json";
+
+    let meta = space::get_space(&op, "negotiated-space").await?;
+    assert_eq!(meta.format_version, space::SPACE_FORMAT_VERSION);
+    assert_eq!(
+        meta.crate_version.as_deref(),
+        Some(env!("CARGO_PKG_VERSION"))
+    );
+    assert_eq!(meta.features.len(), space::SPACE_FEATURES.len());
+    let raw = space::get_space_raw(&op, "negotiated-space").await?;
+    assert!(raw["features"]
+        .as_array()
+        .expect("features")
+        .iter()
+        .any(|f| f == "catalog_manifest"));
+
+    let original: Value = serde_json::from_slice(&op.read(meta_path).await?.to_vec())?;
+
+    let mut newer = original.clone();
+    newer["format_version"] = serde_json::json!(space::SPACE_FORMAT_VERSION + 1);
+    newer["crate_version"] = serde_json::json!("99.0.0");
+    op.write(meta_path, serde_json::to_vec_pretty(&newer)?)
+        .await?;
+    let err = space::get_space(&op, "negotiated-space")
+        .await
+        .expect_err("newer format must be refused");
+    assert!(matches!(
+        err.downcast_ref::<space::SpaceCompatibilityError>(),
+        Some(space::SpaceCompatibilityError::NewerFormat { .. })
+    ));
+    assert!(err.to_string().contains("99.0.0"));
+    assert!(space::upgrade_format(&op, "spaces/negotiated-space")
+        .await
+        .is_err());
+
+    let mut unknown = original.clone();
+    unknown["features"]
+        .as_array_mut()
+        .expect("features")
+        .push(serde_json::json!("time_travel_v9"));
+    op.write(meta_path, serde_json::to_vec_pretty(&unknown)?)
+        .await?;
+    let err = space::get_space_raw(&op, "negotiated-space")
+        .await
+        .expect_err("unknown feature must be refused");
+    match err.downcast_ref::<space::SpaceCompatibilityError>() {
+        Some(space::SpaceCompatibilityError::UnsupportedFeatures { missing, .. }) => {
+            assert_eq!(missing, &vec!["time_travel_v9".to_string()]);
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+
+    // Older spaces without recorded versions still open.
+    let mut legacy = original;
+    let obj = legacy.as_object_mut().unwrap();
+    obj.remove("format_version");
+    obj.remove("crate_version");
+    obj.remove("features");
+    op.write(meta_path, serde_json::to_vec_pretty(&legacy)?)
+        .await?;
+    let meta = space::get_space(&op, "negotiated-space").await?;
+    assert_eq!(meta.format_version, 1);
+    assert!(meta.crate_version.is_none());
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-STO-018
+async fn test_space_req_sto_018_activity_digest() -> anyhow::Result<()> {
+    let (op, _root) = common::setup_fs_operator()?;
+    space::create_space(&op, "digest-space", "/tmp/ugoite").await?;
+    let ws_path = "spaces/digest-space";
+    let form_def = serde_json::json!({
+        "name": "Ticket",
+        "template": "# Ticket\n\n## Body\n",
+        "fields": {"Body": {"type": "markdown"}},
+    });
+    form::upsert_form(&op, ws_path, &form_def).await?;
+    let integrity = FakeIntegrityProvider;
+    for (id, author) in [("t1", "alice"), ("t2", "alice"), ("t3", "bob")] {
+        entry::create_entry(
+            &op,
+            ws_path,
+            id,
+            &format!("---\nform: Ticket\n---\n# {}\n\n## Body\nopen", id),
+            author,
+            &integrity,
+        )
+        .await?;
+    }
+    let current = entry::get_entry_content(&op, ws_path, "t1").await?;
+    entry::update_entry(
+        &op,
+        ws_path,
+        "t1",
+        "---\nform: Ticket\n---\n# t1\n\n## Body\nclosed",
+        Some(&current.revision_id),
+        "bob",
+        None,
+        None,
+        &integrity,
+    )
+    .await?;
+    entry::delete_entry(&op, ws_path, "t3", false).await?;
+    let mut migrated_def = form_def.clone();
+    migrated_def["fields"]["Status"] = serde_json::json!({"type": "string"});
+    form::migrate_form(
+        &op,
+        ws_path,
+        &migrated_def,
+        Some(serde_json::json!({"Status": "open"})),
+        &integrity,
+    )
+    .await?;
+
+    let digest = space::activity_digest(&op, ws_path, 0.0).await?;
+    assert_eq!(digest["space_id"], "digest-space");
+    assert_eq!(
+        digest["forms"]["Ticket"],
+        serde_json::json!({"created": 3, "edited": 1, "deleted": 1})
+    );
+    assert_eq!(digest["totals"]["created"], 3);
+    // Migration revisions are reported apart from author activity.
+    assert_eq!(
+        digest["top_authors"],
+        serde_json::json!([
+            {"author": "alice", "revisions": 2},
+            {"author": "bob", "revisions": 2},
+        ])
+    );
+    assert_eq!(digest["migrations"][0]["form"], "Ticket");
+    assert_eq!(digest["migrations"][0]["entries"], 2);
+    let markdown = digest["markdown"].as_str().expect("markdown");
+    assert!(markdown.starts_with("# Activity since 1970-01-01 00:00 UTC"));
+    assert!(markdown.contains("| Ticket | 3 | 1 | 1 |"));
+    assert!(markdown.contains("1. alice (2 revisions)"));
+    assert!(markdown.contains("- Ticket: 2 entries migrated"));
+
+    let quiet = space::activity_digest(&op, ws_path, 1.0e12).await?;
+    assert_eq!(quiet["totals"]["created"], 0);
+    assert!(quiet["forms"].as_object().unwrap().is_empty());
+    assert!(quiet["top_authors"].as_array().unwrap().is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-STO-019
+async fn test_space_req_sto_019_diff_snapshots_between_times() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "diff-space", "/tmp").await?;
+    let ws_path = "spaces/diff-space";
+    let integrity = FakeIntegrityProvider;
+    let ticket_def = serde_json::json!({
+        "name": "Ticket",
+        "template": "# Ticket\n\n## Status\n",
+        "fields": {"Status": {"type": "string"}},
+    });
+    form::upsert_form(&op, ws_path, &ticket_def).await?;
+    let ticket = |id: &str, status: &str| {
+        format!(
+            "---\nform: Ticket\n---\n# {}\n\n## Status\n{}\n",
+            id, status
+        )
+    };
+    for id in ["t1", "t2", "t3"] {
+        entry::create_entry(&op, ws_path, id, &ticket(id, "Open"), "author", &integrity).await?;
+    }
+
+    let now = || {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs_f64()
+    };
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    let checkpoint = now();
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+
+    entry::update_entry(
+        &op,
+        ws_path,
+        "t1",
+        &ticket("t1", "Resolved"),
+        None,
+        "author",
+        None,
+        None,
+        &integrity,
+    )
+    .await?;
+    entry::delete_entry(&op, ws_path, "t2", false).await?;
+    entry::create_entry(
+        &op,
+        ws_path,
+        "t4",
+        &ticket("t4", "Open"),
+        "author",
+        &integrity,
+    )
+    .await?;
+    entry::create_entry(
+        &op,
+        ws_path,
+        "t5",
+        &ticket("t5", "Open"),
+        "author",
+        &integrity,
+    )
+    .await?;
+    entry::delete_entry(&op, ws_path, "t5", false).await?;
+    let mut redefined = ticket_def.clone();
+    redefined["fields"]["Priority"] = serde_json::json!({"type": "integer"});
+    form::upsert_form(&op, ws_path, &redefined).await?;
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    let later = now();
+
+    let diff = space::diff_snapshots(&op, ws_path, checkpoint, later).await?;
+    assert_eq!(
+        diff["entries"]["added"],
+        serde_json::json!([{"id": "t4", "form": "Ticket"}])
+    );
+    assert_eq!(
+        diff["entries"]["modified"],
+        serde_json::json!([{"id": "t1", "form": "Ticket", "changed_fields": ["Status"]}])
+    );
+    assert_eq!(
+        diff["entries"]["deleted"],
+        serde_json::json!([{"id": "t2", "form": "Ticket"}])
+    );
+    assert_eq!(diff["forms"]["added"], serde_json::json!([]));
+    assert_eq!(diff["forms"]["changed"][0]["form"], "Ticket");
+    assert_eq!(
+        diff["forms"]["changed"][0]["fields_added"],
+        serde_json::json!(["Priority"])
+    );
+
+    let initial = space::diff_snapshots(&op, ws_path, 0.0, checkpoint).await?;
+    assert_eq!(initial["totals"]["added"], 3);
+    assert_eq!(initial["forms"]["added"], serde_json::json!(["Ticket"]));
+    assert!(space::diff_snapshots(&op, ws_path, later, checkpoint)
+        .await
+        .is_err());
+    Ok(())
+}
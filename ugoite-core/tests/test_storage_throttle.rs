@@ -0,0 +1,171 @@
+mod common;
+use _ugoite_core::audit;
+use _ugoite_core::entry;
+use _ugoite_core::form;
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::space;
+use _ugoite_core::storage::{self, SpaceThrottleLayer, WriteCondition};
+use common::setup_operator;
+use std::time::{Duration, Instant};
+
+#[tokio::test]
+/// REQ-STO-022
+async fn test_storage_throttle_req_sto_022_limits_per_space() -> anyhow::Result<()> {
+    let op = setup_operator()?.layer(SpaceThrottleLayer);
+    space::create_space(&op, "throttled-space", "/tmp").await?;
+    space::create_space(&op, "free-space", "/tmp").await?;
+    space::patch_space(
+        &op,
+        "throttled-space",
+        &serde_json::json!({"settings": {"storage_throttle": {
+            "ops_per_second": 20,
+            "burst": 1,
+            "max_in_flight": 1,
+        }}}),
+    )
+    .await?;
+    assert_eq!(
+        storage::StorageThrottle::from_settings(
+            &space::read_space_settings(&op, "spaces/throttled-space").await?
+        ),
+        storage::StorageThrottle {
+            ops_per_second: Some(20.0),
+            burst: Some(1.0),
+            max_in_flight: Some(1),
+        }
+    );
+
+    let timed = |space_id: &'static str| {
+        let op = op.clone();
+        async move {
+            let path = format!("spaces/{}/meta.json", space_id);
+            let started = Instant::now();
+            for _ in 0..11 {
+                op.stat(&path).await?;
+            }
+            anyhow::Ok(started.elapsed())
+        }
+    };
+    let throttled = timed("throttled-space").await?;
+    let free = timed("free-space").await?;
+    assert!(throttled >= Duration::from_millis(450), "{:?}", throttled);
+    assert!(free < throttled);
+
+    // A lister stays open while each listed event is read; with one slot
+    // this must not wait on itself.
+    space::patch_space(
+        &op,
+        "throttled-space",
+        &serde_json::json!({"settings": {"storage_throttle": {"max_in_flight": 1}}}),
+    )
+    .await?;
+    audit::record_audit_event(
+        &op,
+        "spaces/throttled-space",
+        "test.event",
+        "system",
+        serde_json::json!({}),
+    )
+    .await?;
+    let events = audit::list_audit_events(&op, "spaces/throttled-space", None).await?;
+    assert_eq!(events.len(), 1);
+
+    space::patch_space(
+        &op,
+        "throttled-space",
+        &serde_json::json!({"settings": {"storage_throttle": null}}),
+    )
+    .await?;
+    assert!(timed("throttled-space").await? < throttled);
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-STO-023
+async fn test_storage_throttle_req_sto_023_contention_stats_per_space() -> anyhow::Result<()> {
+    let op = setup_operator()?.layer(SpaceThrottleLayer);
+    space::create_space(&op, "contended-space", "/tmp").await?;
+    space::create_space(&op, "calm-space", "/tmp").await?;
+    let ws_path = "spaces/contended-space";
+    let integrity = FakeIntegrityProvider;
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Entry",
+            "template": "# Entry\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    entry::create_entry(
+        &op,
+        ws_path,
+        "contended",
+        "---\nform: Entry\n---\n# Contended",
+        "author1",
+        &integrity,
+    )
+    .await?;
+
+    let stale = entry::update_entry(
+        &op,
+        ws_path,
+        "contended",
+        "---\nform: Entry\n---\n# Stale",
+        Some("stale-revision"),
+        "author1",
+        None,
+        None,
+        &integrity,
+    )
+    .await;
+    assert!(stale.is_err());
+    let doc = format!("{}/contended.json", ws_path);
+    storage::write_json_conditional(&op, &doc, &serde_json::json!({}), &WriteCondition::Absent)
+        .await?;
+    let conflict =
+        storage::write_json_conditional(&op, &doc, &serde_json::json!({}), &WriteCondition::Absent)
+            .await;
+    assert!(conflict.is_err_and(|err| storage::is_write_conflict(&err)));
+
+    space::patch_space(
+        &op,
+        "contended-space",
+        &serde_json::json!({"settings": {"storage_throttle": {"ops_per_second": 50, "burst": 1}}}),
+    )
+    .await?;
+    for _ in 0..3 {
+        op.stat(&doc).await?;
+    }
+
+    let stats = storage::contention_stats(&op, ws_path, None).await?;
+    assert_eq!(stats["space_id"], "contended-space");
+    assert_eq!(stats["totals"]["revision_conflicts"], 1);
+    assert_eq!(stats["totals"]["write_conflicts"], 1);
+    assert!(stats["totals"]["lock_waits"].as_u64().unwrap() >= 2);
+
+    // Flushed counts are kept in the space's stats table.
+    space::patch_space(
+        &op,
+        "contended-space",
+        &serde_json::json!({"settings": {"storage_throttle": null}}),
+    )
+    .await?;
+    assert!(storage::flush_contention_stats(&op, ws_path).await? >= 1);
+    assert_eq!(storage::flush_contention_stats(&op, ws_path).await?, 0);
+    assert!(op.exists(&format!("{}/contention.json", ws_path)).await?);
+    let flushed = storage::contention_stats(&op, ws_path, None).await?;
+    assert_eq!(flushed["totals"]["revision_conflicts"], 1);
+    assert_eq!(flushed["totals"]["write_conflicts"], 1);
+    let later =
+        chrono::Utc::now().timestamp() as f64 + 2.0 * storage::CONTENTION_BUCKET_SECONDS as f64;
+    let none = storage::contention_stats(&op, ws_path, Some(later)).await?;
+    assert_eq!(none["buckets"], serde_json::json!([]));
+
+    let ranked = storage::rank_spaces_by_contention(&op, None).await?;
+    let ranked = ranked.as_array().unwrap();
+    assert_eq!(ranked[0]["space_id"], "contended-space");
+    assert!(ranked.iter().all(|space| space["space_id"] != "calm-space"));
+    Ok(())
+}
@@ -0,0 +1,128 @@
+mod common;
+use _ugoite_core::entry;
+use _ugoite_core::form;
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::space;
+use _ugoite_core::template;
+use _ugoite_core::timezone::SpaceTimeZone;
+use chrono::{TimeZone, Utc};
+use common::setup_operator;
+
+#[tokio::test]
+/// REQ-ENTRY-027
+async fn test_template_req_entry_027_create_entry_from_template() -> anyhow::Result<()> {
+    let tz = SpaceTimeZone::new("Asia/Tokyo")?;
+    let now = Utc.with_ymd_and_hms(2025, 1, 30, 20, 0, 0).unwrap();
+    let variables = serde_json::json!({"start": "2025-01-31", "count": 3});
+    let rendered = template::render_template(
+        "{{today}} {{ today+7d }} {{today-1w}} {{start+1m}} {{start+1y}} {{now+1d}} {{count}}",
+        variables.as_object().unwrap(),
+        &tz,
+        now,
+    )?;
+    assert_eq!(
+        rendered,
+        "2025-01-31 2025-02-07 2025-01-24 2025-02-28 2026-01-31 2025-02-01T05:00:00+09:00 3"
+    );
+    assert!(
+        template::render_template("{{count+1d}}", variables.as_object().unwrap(), &tz, now)
+            .is_err()
+    );
+
+    // Variables cannot inject sections or frontmatter.
+    let body = "---\nform: Checklist\n---\n# {{title}}\n\n## Owner\n{{owner}}\n";
+    for malicious in [
+        serde_json::json!({"title": "Review", "owner": "alice\n## Due\n2099-01-01"}),
+        serde_json::json!({"title": "Review\r\n---\nform: Other", "owner": "alice"}),
+        serde_json::json!({"title": "Review", "owner": "## Due"}),
+        serde_json::json!({"title": "Review", "owner": " ---"}),
+    ] {
+        assert!(
+            template::render_template(body, malicious.as_object().unwrap(), &tz, now).is_err(),
+            "{}",
+            malicious
+        );
+    }
+    let inline = serde_json::json!({"title": "#3 --- pump", "owner": "alice"});
+    assert_eq!(
+        template::render_template(body, inline.as_object().unwrap(), &tz, now)?,
+        "---\nform: Checklist\n---\n# #3 --- pump\n\n## Owner\nalice\n"
+    );
+
+    let op = setup_operator()?;
+    space::create_space(&op, "template-space", "/tmp").await?;
+    let ws_path = "spaces/template-space";
+    let integrity = FakeIntegrityProvider;
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Checklist",
+            "template": "# Checklist\n\n## Due\n\n## Owner\n",
+            "fields": {
+                "Due": {"type": "date", "required": true},
+                "Owner": {"type": "string", "required": true},
+            },
+        }),
+    )
+    .await?;
+    let saved = template::save_template(
+        &op,
+        ws_path,
+        "weekly-review",
+        "---\nform: Checklist\n---\n# Weekly review {{today}}\n\n## Due\n{{today+7d}}\n\n## Owner\n{{owner}}\n",
+        Some("Recurring review"),
+    )
+    .await?;
+    assert_eq!(saved.variables, vec!["owner"]);
+    assert_eq!(template::list_templates(&op, ws_path).await?.len(), 1);
+
+    let missing = template::create_entry_from_template(
+        &op,
+        ws_path,
+        "weekly-review",
+        &serde_json::json!({}),
+        None,
+        "author",
+        &integrity,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(missing.to_string(), "Missing template variables: owner");
+
+    let created = template::create_entry_from_template(
+        &op,
+        ws_path,
+        "weekly-review",
+        &serde_json::json!({"owner": "alice"}),
+        None,
+        "author",
+        &integrity,
+    )
+    .await?;
+    let today = Utc::now().date_naive();
+    assert_eq!(created.id, format!("weekly-review-{}", today));
+    let stored = entry::get_entry(&op, ws_path, &created.id).await?;
+    let due = (today + chrono::Duration::days(7)).to_string();
+    assert_eq!(stored["form"], "Checklist");
+    assert!(stored["content"].as_str().unwrap().contains(&due));
+    assert!(stored["content"].as_str().unwrap().contains("alice"));
+
+    let explicit = template::create_entry_from_template(
+        &op,
+        ws_path,
+        "weekly-review",
+        &serde_json::json!({"owner": "bob"}),
+        Some("review-bob"),
+        "author",
+        &integrity,
+    )
+    .await?;
+    assert_eq!(explicit.id, "review-bob");
+
+    assert!(template::delete_template(&op, ws_path, "weekly-review").await?);
+    assert!(template::get_template(&op, ws_path, "weekly-review")
+        .await
+        .is_err());
+    Ok(())
+}
@@ -0,0 +1,200 @@
+mod common;
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::transaction::{Transaction, TransactionOp};
+use _ugoite_core::{entry, form, index, link, space};
+use common::setup_fs_operator;
+
+#[tokio::test]
+/// REQ-ENTRY-035
+async fn test_transaction_req_entry_035_commit_or_roll_back() -> anyhow::Result<()> {
+    let (op, _root) = setup_fs_operator()?;
+    space::create_space(&op, "txn-space", "/tmp").await?;
+    let ws_path = "spaces/txn-space";
+    let integrity = FakeIntegrityProvider;
+    for (name, field, field_type) in [("Note", "Body", "markdown"), ("Task", "Effort", "number")] {
+        form::upsert_form(
+            &op,
+            ws_path,
+            &serde_json::json!({
+                "name": name,
+                "template": format!("# {name}\n\n## {field}\n"),
+                "fields": {field: {"type": field_type}},
+            }),
+        )
+        .await?;
+    }
+    entry::create_entry(
+        &op,
+        ws_path,
+        "kept",
+        "---\nform: Note\n---\n# Kept\n\n## Body\nbefore",
+        "author",
+        &integrity,
+    )
+    .await?;
+    let ids = |entries: Vec<serde_json::Value>| -> Vec<String> {
+        let mut ids: Vec<String> = entries
+            .iter()
+            .map(|e| e["id"].as_str().unwrap().to_string())
+            .collect();
+        ids.sort();
+        ids
+    };
+
+    // The last operation fails validation, so none of them run.
+    let mut invalid = Transaction::new();
+    invalid
+        .create("note-1", "---\nform: Note\n---\n# Note 1\n\n## Body\nnew")
+        .update(
+            "kept",
+            "---\nform: Note\n---\n# Kept\n\n## Body\nafter",
+            None,
+        )
+        .create(
+            "task-1",
+            "---\nform: Task\n---\n# Task 1\n\n## Effort\nlots",
+        );
+    let err = invalid
+        .commit(&op, ws_path, "importer", &integrity)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Operation 2 on task-1"));
+    assert!(err.to_string().contains("nothing was written"));
+    let mut unknown = Transaction::new();
+    unknown.delete("missing", false);
+    let err = unknown
+        .commit(&op, ws_path, "importer", &integrity)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Operation 0 on missing"));
+
+    // The last operation only fails when applied, so the earlier ones are
+    // rolled back.
+    let mut failing = Transaction::new();
+    failing
+        .create("note-1", "---\nform: Note\n---\n# Note 1\n\n## Body\nnew")
+        .update(
+            "kept",
+            "---\nform: Note\n---\n# Kept\n\n## Body\nafter",
+            None,
+        )
+        .update(
+            "kept",
+            "---\nform: Note\n---\n# Kept\n\n## Body\nagain",
+            Some("stale-revision"),
+        );
+    let err = failing
+        .commit(&op, ws_path, "importer", &integrity)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Operation 2 on kept"));
+    assert!(err.to_string().contains("rolled back"));
+    assert_eq!(ids(entry::list_entries(&op, ws_path).await?), ["kept"]);
+    assert!(entry::exists(&op, ws_path, "note-1").await?.is_none());
+    let kept = entry::get_entry(&op, ws_path, "kept").await?;
+    assert_eq!(kept["sections"]["Body"], "before");
+    let history = entry::get_entry_history(&op, ws_path, "kept").await?;
+    assert_eq!(history["revisions"].as_array().unwrap().len(), 1);
+
+    // The same batch, fixed and given as JSON, lands in full.
+    let ops: Vec<TransactionOp> = serde_json::from_value(serde_json::json!([
+        {"op": "create", "entry_id": "note-1", "content": "---\nform: Note\n---\n# Note 1\n\n## Body\nnew"},
+        {"op": "update", "entry_id": "kept", "content": "---\nform: Note\n---\n# Kept\n\n## Body\nafter",
+         "parent_revision_id": kept["revision_id"], "message": "import"},
+        {"op": "create", "entry_id": "task-1", "content": "---\nform: Task\n---\n# Task 1\n\n## Effort\n3"},
+        {"op": "delete", "entry_id": "note-1"},
+    ]))?;
+    let results = Transaction::from_ops(ops)
+        .commit(&op, ws_path, "importer", &integrity)
+        .await?;
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0]["id"], "note-1");
+    assert_eq!(results[1]["sections"]["Body"], "after");
+    assert_eq!(
+        results[3],
+        serde_json::json!({"id": "note-1", "deleted": true})
+    );
+    assert_eq!(
+        ids(entry::list_entries(&op, ws_path).await?),
+        ["kept", "task-1"]
+    );
+    let history = entry::get_entry_history(&op, ws_path, "kept").await?;
+    assert_eq!(history["revisions"].as_array().unwrap().len(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-ENTRY-035
+async fn test_transaction_req_entry_035_roll_back_links_and_rollups() -> anyhow::Result<()> {
+    let (op, _root) = setup_fs_operator()?;
+    space::create_space(&op, "txn-links", "/tmp").await?;
+    let ws_path = "spaces/txn-links";
+    let integrity = FakeIntegrityProvider;
+    for form_def in [
+        serde_json::json!({
+            "name": "Note",
+            "template": "# Note\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+        }),
+        serde_json::json!({
+            "name": "Site",
+            "template": "# Site\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+        }),
+        serde_json::json!({
+            "name": "Inspection",
+            "template": "# Inspection\n\n## Site\n",
+            "fields": {"Site": {"type": "row_reference", "target_form": "Site"}},
+        }),
+        serde_json::json!({
+            "name": "Site",
+            "template": "# Site\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+            "rollups": {
+                "InspectionCount": {"source_form": "Inspection", "via": "Site", "aggregate": "count"},
+            },
+        }),
+    ] {
+        form::upsert_form(&op, ws_path, &form_def).await?;
+    }
+    for (id, content) in [
+        ("note-a", "---\nform: Note\n---\n# A\n\n## Body\na"),
+        ("note-b", "---\nform: Note\n---\n# B\n\n## Body\nb"),
+        ("north", "---\nform: Site\n---\n# North\n\n## Body\nsite"),
+        ("i-1", "---\nform: Inspection\n---\n# I 1\n\n## Site\nnorth"),
+    ] {
+        entry::create_entry(&op, ws_path, id, content, "author", &integrity).await?;
+    }
+    link::create_link(&op, ws_path, "note-a", "note-b", "related", "link-1").await?;
+    let computed = entry::get_entry(&op, ws_path, "north").await?["computed"].clone();
+    assert_eq!(computed, serde_json::json!({"InspectionCount": 1}));
+
+    // Deleting note-b drops the backlink note-a keeps and the new inspection
+    // bumps north's rollup, then the stale update fails: both are undone.
+    let mut failing = Transaction::new();
+    failing
+        .create("i-2", "---\nform: Inspection\n---\n# I 2\n\n## Site\nnorth")
+        .delete("note-b", false)
+        .update(
+            "note-a",
+            "---\nform: Note\n---\n# A\n\n## Body\nchanged",
+            Some("stale-revision"),
+        );
+    let err = failing
+        .commit(&op, ws_path, "importer", &integrity)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("rolled back"));
+
+    assert!(entry::exists(&op, ws_path, "i-2").await?.is_none());
+    assert!(entry::exists(&op, ws_path, "note-b").await?.is_some());
+    let note_a = entry::get_entry(&op, ws_path, "note-a").await?;
+    assert_eq!(note_a["links"].as_array().unwrap().len(), 1);
+    assert_eq!(note_a["links"][0]["id"], "link-1");
+    assert_eq!(link::list_links(&op, ws_path).await?.len(), 1);
+    let north = entry::get_entry(&op, ws_path, "north").await?;
+    assert_eq!(north["computed"], computed);
+    let sites = index::query_index(&op, ws_path, "{\"form\": \"Site\"}").await?;
+    assert_eq!(sites[0]["computed"], computed);
+    Ok(())
+}
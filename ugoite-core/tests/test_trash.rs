@@ -0,0 +1,213 @@
+mod common;
+use _ugoite_core::audit;
+use _ugoite_core::entry;
+use _ugoite_core::form;
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::link;
+use _ugoite_core::space;
+use _ugoite_core::trash;
+use common::setup_operator;
+
+#[tokio::test]
+/// REQ-ENTRY-025
+async fn test_trash_req_entry_025_purge_after_window() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "trash-space", "/tmp").await?;
+    let ws_path = "spaces/trash-space";
+    let integrity = FakeIntegrityProvider;
+
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Note",
+            "template": "# Note\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    for id in ["kept", "trashed-a", "trashed-b"] {
+        let content = format!("---\nform: Note\n---\n# {id}\n\n## Body\ntext");
+        entry::create_entry(&op, ws_path, id, &content, "author", &integrity).await?;
+    }
+    link::create_link(&op, ws_path, "trashed-a", "kept", "related", "link-1").await?;
+    entry::delete_entry(&op, ws_path, "trashed-a", false).await?;
+    entry::delete_entry(&op, ws_path, "trashed-b", false).await?;
+    let day = 86_400.0;
+    let now = chrono::Utc::now().timestamp() as f64;
+
+    let unset = trash::purge_trash(&op, ws_path, now + 365.0 * day, false).await?;
+    assert_eq!(unset["purged"], serde_json::json!([]));
+    assert!(entry::exists(&op, ws_path, "trashed-a").await?.is_some());
+
+    space::patch_space(
+        &op,
+        "trash-space",
+        &serde_json::json!({"settings": {"trash": {"purge_after_days": 30}}}),
+    )
+    .await?;
+    let inside = trash::purge_trash(&op, ws_path, now + 10.0 * day, false).await?;
+    assert_eq!(inside["purged"], serde_json::json!([]));
+
+    let dry = trash::purge_trash(&op, ws_path, now + 31.0 * day, true).await?;
+    let purged: Vec<&str> = dry["purged"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["entry_id"].as_str().unwrap())
+        .collect();
+    assert_eq!(purged, vec!["trashed-a", "trashed-b"]);
+    assert!(dry["audit_event_id"].is_null());
+    assert!(entry::get_entry_history(&op, ws_path, "trashed-a")
+        .await
+        .is_ok());
+
+    let applied = trash::purge_trash(&op, ws_path, now + 31.0 * day, false).await?;
+    assert_eq!(applied["purged"], dry["purged"]);
+    assert!(entry::exists(&op, ws_path, "trashed-a").await?.is_none());
+    assert!(entry::exists(&op, ws_path, "trashed-b").await?.is_none());
+    assert!(entry::get_entry_history(&op, ws_path, "trashed-a")
+        .await
+        .is_err());
+    let kept = entry::get_entry(&op, ws_path, "kept").await?;
+    assert_eq!(kept["links"], serde_json::json!([]));
+    assert!(link::list_links(&op, ws_path).await?.is_empty());
+    assert_eq!(
+        entry::get_entry_history(&op, ws_path, "kept").await?["revisions"]
+            .as_array()
+            .unwrap()
+            .len(),
+        1
+    );
+
+    let events = audit::list_audit_events(&op, ws_path, Some("trash.purged")).await?;
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["id"], applied["audit_event_id"]);
+    assert_eq!(events[0]["details"]["entries"], applied["purged"]);
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-ENTRY-033
+async fn test_trash_req_entry_033_list_deleted_and_restore() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "restore-space", "/tmp").await?;
+    let ws_path = "spaces/restore-space";
+    let integrity = FakeIntegrityProvider;
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Note",
+            "template": "# Note\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    for id in ["live", "first", "second"] {
+        let content = format!("---\nform: Note\n---\n# {id}\n\n## Body\ntext");
+        entry::create_entry(&op, ws_path, id, &content, "author", &integrity).await?;
+    }
+    assert!(trash::list_deleted_entries(&op, ws_path).await?.is_empty());
+    entry::delete_entry(&op, ws_path, "first", false).await?;
+    entry::delete_entry(&op, ws_path, "second", false).await?;
+
+    let ids = |entries: &[serde_json::Value]| -> Vec<String> {
+        entries
+            .iter()
+            .map(|e| e["id"].as_str().unwrap().to_string())
+            .collect()
+    };
+    let deleted = trash::list_deleted_entries(&op, ws_path).await?;
+    assert_eq!(ids(&deleted), ["second", "first"]);
+    assert_eq!(deleted[0]["title"], "second");
+    assert_eq!(deleted[0]["form"], "Note");
+    assert!(deleted[0]["deleted_at"].as_f64().is_some());
+    assert!(deleted[0]["purge_at"].is_null());
+
+    space::patch_space(
+        &op,
+        "restore-space",
+        &serde_json::json!({"settings": {"trash": {"purge_after_days": 30}}}),
+    )
+    .await?;
+    let deleted = trash::list_deleted_entries(&op, ws_path).await?;
+    assert_eq!(
+        deleted[1]["purge_at"].as_f64().unwrap(),
+        deleted[1]["deleted_at"].as_f64().unwrap() + 30.0 * 86_400.0
+    );
+
+    entry::undelete_entry(&op, ws_path, "first").await?;
+    assert_eq!(
+        ids(&trash::list_deleted_entries(&op, ws_path).await?),
+        ["second"]
+    );
+    let mut live = ids(&entry::list_entries(&op, ws_path).await?);
+    live.sort();
+    assert_eq!(live, ["first", "live"]);
+    Ok(())
+}
+
+#[tokio::test]
+/// REQ-ENTRY-034
+async fn test_trash_req_entry_034_gc_hard_deleted() -> anyhow::Result<()> {
+    let op = setup_operator()?;
+    space::create_space(&op, "gc-space", "/tmp").await?;
+    let ws_path = "spaces/gc-space";
+    let integrity = FakeIntegrityProvider;
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Note",
+            "template": "# Note\n\n## Body\n",
+            "fields": {"Body": {"type": "markdown"}},
+        }),
+    )
+    .await?;
+    for id in ["live", "hard", "soft"] {
+        let content = format!("---\nform: Note\n---\n# {id}\n\n## Body\ntext");
+        entry::create_entry(&op, ws_path, id, &content, "author", &integrity).await?;
+    }
+    // Client metadata cannot mark an entry as hard-deleted.
+    entry::set_entry_metadata(
+        &op,
+        ws_path,
+        "soft",
+        &serde_json::json!({"hard_deleted": true}),
+    )
+    .await?;
+    entry::delete_entry(&op, ws_path, "hard", true).await?;
+    entry::delete_entry(&op, ws_path, "soft", false).await?;
+    assert!(entry::undelete_entry(&op, ws_path, "hard").await.is_err());
+
+    let dry = trash::gc_deleted_entries(&op, ws_path, true).await?;
+    assert_eq!(dry["purged"].as_array().unwrap().len(), 1);
+    assert_eq!(dry["purged"][0]["entry_id"], "hard");
+    assert!(dry["audit_event_id"].is_null());
+    assert!(entry::exists(&op, ws_path, "hard").await?.is_some());
+
+    let applied = trash::gc_deleted_entries(&op, ws_path, false).await?;
+    assert_eq!(applied["purged"], dry["purged"]);
+    assert!(applied["audit_event_id"].is_string());
+    assert!(entry::exists(&op, ws_path, "hard").await?.is_none());
+    assert!(entry::get_entry_history(&op, ws_path, "hard")
+        .await
+        .is_err());
+    assert!(entry::exists(&op, ws_path, "soft").await?.is_some());
+    let again = trash::gc_deleted_entries(&op, ws_path, false).await?;
+    assert_eq!(again["purged"], serde_json::json!([]));
+
+    assert!(trash::purge_entry(&op, ws_path, "live").await.is_err());
+    let purged = trash::purge_entry(&op, ws_path, "soft").await?;
+    assert_eq!(purged["purged"][0]["entry_id"], "soft");
+    assert!(entry::exists(&op, ws_path, "soft").await?.is_none());
+    assert!(entry::exists(&op, ws_path, "live").await?.is_some());
+    assert_eq!(
+        audit::list_audit_events(&op, ws_path, Some("trash.purged"))
+            .await?
+            .len(),
+        2
+    );
+    Ok(())
+}
@@ -0,0 +1,148 @@
+mod common;
+use _ugoite_core::integrity::FakeIntegrityProvider;
+use _ugoite_core::url_field::{self, UrlMetadata, UrlMetadataFetcher};
+use _ugoite_core::{entry, form, space};
+use common::setup_operator;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+struct FakeFetcher {
+    calls: AtomicUsize,
+}
+
+impl UrlMetadataFetcher for FakeFetcher {
+    fn fetch(&self, url: &str) -> Result<UrlMetadata, String> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        if url.contains("offline") {
+            return Err("unreachable".to_string());
+        }
+        if url.contains("slow") {
+            std::thread::sleep(Duration::from_secs(3));
+        }
+        Ok(UrlMetadata {
+            title: Some("Example Domain".to_string()),
+            favicon: Some(format!("{}favicon.ico", url)),
+            ..Default::default()
+        })
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_millis(200)
+    }
+}
+
+#[tokio::test]
+/// REQ-FORM-010
+async fn test_url_field_req_form_010_validate_and_capture() -> anyhow::Result<()> {
+    assert_eq!(
+        url_field::normalize_url(" https://Example.com "),
+        Some("https://example.com/".to_string())
+    );
+    assert_eq!(url_field::normalize_url("ftp://example.com/file"), None);
+    assert_eq!(url_field::normalize_url("example.com"), None);
+
+    let op = setup_operator()?;
+    space::create_space(&op, "url-space", "/tmp").await?;
+    let ws_path = "spaces/url-space";
+    form::upsert_form(
+        &op,
+        ws_path,
+        &serde_json::json!({
+            "name": "Bookmark",
+            "template": "# Bookmark\n\n## Link\n\n## Mirror\n",
+            "fields": {
+                "Link": {"type": "url", "capture_metadata": true},
+                "Mirror": {"type": "url"},
+            },
+        }),
+    )
+    .await?;
+    let fetcher = Arc::new(FakeFetcher {
+        calls: AtomicUsize::new(0),
+    });
+    url_field::register_url_metadata_fetcher(fetcher.clone())?;
+    let integrity = FakeIntegrityProvider;
+
+    assert!(entry::create_entry(
+        &op,
+        ws_path,
+        "bad",
+        "---\nform: Bookmark\n---\n# Bad\n\n## Link\nnot a url",
+        "author",
+        &integrity,
+    )
+    .await
+    .is_err());
+
+    entry::create_entry(
+        &op,
+        ws_path,
+        "site",
+        "---\nform: Bookmark\n---\n# Site\n\n## Link\nhttps://example.com\n\n## Mirror\nhttps://mirror.example.com",
+        "author",
+        &integrity,
+    )
+    .await?;
+    let site = entry::get_entry(&op, ws_path, "site").await?;
+    let captured = &site["metadata"]["url_metadata"];
+    assert_eq!(captured["Link"]["url"], "https://example.com/");
+    assert_eq!(captured["Link"]["title"], "Example Domain");
+    assert!(captured.get("Mirror").is_none());
+    assert_eq!(fetcher.calls.load(Ordering::SeqCst), 1);
+
+    // An unchanged URL reuses its capture; a failed fetch never blocks a write.
+    entry::update_entry(
+        &op,
+        ws_path,
+        "site",
+        "---\nform: Bookmark\n---\n# Site v2\n\n## Link\nhttps://example.com/",
+        site["revision_id"].as_str(),
+        "author",
+        None,
+        None,
+        &integrity,
+    )
+    .await?;
+    assert_eq!(fetcher.calls.load(Ordering::SeqCst), 1);
+    let site = entry::get_entry(&op, ws_path, "site").await?;
+    entry::update_entry(
+        &op,
+        ws_path,
+        "site",
+        "---\nform: Bookmark\n---\n# Site v3\n\n## Link\nhttps://offline.example.com",
+        site["revision_id"].as_str(),
+        "author",
+        None,
+        None,
+        &integrity,
+    )
+    .await?;
+    let site = entry::get_entry(&op, ws_path, "site").await?;
+    assert_eq!(fetcher.calls.load(Ordering::SeqCst), 2);
+    assert!(site["metadata"].get("url_metadata").is_none());
+
+    // A hung fetch is cut off by the fetcher's timeout instead of holding up
+    // the save.
+    let started = Instant::now();
+    entry::update_entry(
+        &op,
+        ws_path,
+        "site",
+        "---\nform: Bookmark\n---\n# Site v4\n\n## Link\nhttps://slow.example.com",
+        site["revision_id"].as_str(),
+        "author",
+        None,
+        None,
+        &integrity,
+    )
+    .await?;
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert_eq!(fetcher.calls.load(Ordering::SeqCst), 3);
+    let site = entry::get_entry(&op, ws_path, "site").await?;
+    assert_eq!(site["title"], "Site v4");
+    assert!(site["metadata"].get("url_metadata").is_none());
+
+    url_field::clear_url_metadata_fetcher()?;
+    Ok(())
+}
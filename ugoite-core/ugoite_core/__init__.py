@@ -43,6 +43,7 @@ list_forms = _core_any.list_forms
 list_spaces = _core_any.list_spaces
 load_hmac_material = _core_any.load_hmac_material
 load_response_hmac_material = _core_any.load_response_hmac_material
+load_signing_public_key = _core_any.load_signing_public_key
 migrate_form = _core_any.migrate_form
 patch_space = _core_any.patch_space
 query_index = _core_any.query_index
@@ -65,14 +66,140 @@ get_sql_session_status = _core_any.get_sql_session_status
 get_sql_session_count = _core_any.get_sql_session_count
 get_sql_session_rows = _core_any.get_sql_session_rows
 get_sql_session_rows_all = _core_any.get_sql_session_rows_all
+record_entry_access = _core_any.record_entry_access
+list_recent_entries = _core_any.list_recent_entries
+list_frequent_entries = _core_any.list_frequent_entries
+index_status = _core_any.index_status
+reindex_form = _core_any.reindex_form
+query_index_with_facets = _core_any.query_index_with_facets
+upgrade_space_format = _core_any.upgrade_space_format
+redact_entry = _core_any.redact_entry
+list_audit_events = _core_any.list_audit_events
+scan_space_pii = _core_any.scan_space_pii
+export_space = _core_any.export_space
+verify_export = _core_any.verify_export
+sign_canonical_response = _core_any.sign_canonical_response
+verify_response_signature = _core_any.verify_response_signature
+move_board_entry = _core_any.move_board_entry
+remove_board_entry = _core_any.remove_board_entry
+get_board = _core_any.get_board
+set_entry_metadata = _core_any.set_entry_metadata
+add_favorite = _core_any.add_favorite
+remove_favorite = _core_any.remove_favorite
+list_favorites = _core_any.list_favorites
+add_reminder = _core_any.add_reminder
+list_due_reminders = _core_any.list_due_reminders
+list_entry_reminders = _core_any.list_entry_reminders
+complete_reminder = _core_any.complete_reminder
+delete_reminder = _core_any.delete_reminder
+activity_digest = _core_any.activity_digest
+register_author_key = _core_any.register_author_key
+list_author_keys = _core_any.list_author_keys
+revision_signing_payload = _core_any.revision_signing_payload
+attach_revision_signature = _core_any.attach_revision_signature
+verify_revision_author = _core_any.verify_revision_author
+apply_replica_changes = _core_any.apply_replica_changes
+import_bundle = _core_any.import_bundle
+suggest_entry_id = _core_any.suggest_entry_id
+entry_exists = _core_any.entry_exists
+create_folder = _core_any.create_folder
+delete_folder = _core_any.delete_folder
+move_entry_to_folder = _core_any.move_entry_to_folder
+move_folder = _core_any.move_folder
+list_folder = _core_any.list_folder
+resolve_entry_path = _core_any.resolve_entry_path
+read_asset_range = _core_any.read_asset_range
+apply_retention = _core_any.apply_retention
+recompute_rollups = _core_any.recompute_rollups
+join_forms = _core_any.join_forms
+diff_snapshots = _core_any.diff_snapshots
+export_entry_history = _core_any.export_entry_history
+replica_status = _core_any.replica_status
+merge_entries = _core_any.merge_entries
+purge_trash = _core_any.purge_trash
+undelete_entry = _core_any.undelete_entry
+stream_entries = _core_any.stream_entries
+export_space_stream = _core_any.export_space_stream
+import_bundle_stream = _core_any.import_bundle_stream
+set_secret = _core_any.set_secret
+get_secret = _core_any.get_secret
+delete_secret = _core_any.delete_secret
+list_secrets = _core_any.list_secrets
+issue_secret_capability = _core_any.issue_secret_capability
+save_template = _core_any.save_template
+list_templates = _core_any.list_templates
+get_template = _core_any.get_template
+delete_template = _core_any.delete_template
+create_entry_from_template = _core_any.create_entry_from_template
+add_schedule = _core_any.add_schedule
+list_schedules = _core_any.list_schedules
+delete_schedule = _core_any.delete_schedule
+run_due_schedules = _core_any.run_due_schedules
+query_to_csv = _core_any.query_to_csv
+query_to_xlsx = _core_any.query_to_xlsx
+import_notion = _core_any.import_notion
+import_eml = _core_any.import_eml
+register_content_extractor = _core_any.register_content_extractor
+clear_content_extractors = _core_any.clear_content_extractors
+register_form_hook = _core_any.register_form_hook
+clear_form_hooks = _core_any.clear_form_hooks
+set_asset_text = _core_any.set_asset_text
+get_asset_text = _core_any.get_asset_text
+offline_bundle = _core_any.offline_bundle
+offline_delta = _core_any.offline_delta
+field_lineage = _core_any.field_lineage
+reindex_incremental = _core_any.reindex_incremental
+flush_contention_stats = _core_any.flush_contention_stats
+contention_stats = _core_any.contention_stats
+rank_spaces_by_contention = _core_any.rank_spaces_by_contention
+IntegrityError = _core_any.IntegrityError
+backfill_asset_integrity = _core_any.backfill_asset_integrity
+space_merkle_root = _core_any.space_merkle_root
+list_entries_page = _core_any.list_entries_page
+rotate_space_key = _core_any.rotate_space_key
+patch_entry = _core_any.patch_entry
+duplicate_entry = _core_any.duplicate_entry
+list_deleted_entries = _core_any.list_deleted_entries
+purge_entry = _core_any.purge_entry
+gc_deleted_entries = _core_any.gc_deleted_entries
+commit_transaction = _core_any.commit_transaction
+flush_slow_log = _core_any.flush_slow_log
+list_slow_operations = _core_any.list_slow_operations
+pin_entry = _core_any.pin_entry
+unpin_entry = _core_any.unpin_entry
+list_pinned = _core_any.list_pinned
+get_form_history = _core_any.get_form_history
+get_form_version = _core_any.get_form_version
+rollback_form = _core_any.rollback_form
+change_entry_form = _core_any.change_entry_form
+archive_entry = _core_any.archive_entry
+unarchive_entry = _core_any.unarchive_entry
 
 __all__ = [
+    "IntegrityError",
     "SqlLintDiagnostic",
+    "activity_digest",
+    "add_favorite",
+    "add_reminder",
+    "add_schedule",
+    "apply_replica_changes",
+    "apply_retention",
+    "archive_entry",
+    "attach_revision_signature",
+    "backfill_asset_integrity",
     "build_response_signature",
     "build_sql_schema",
+    "change_entry_form",
+    "clear_content_extractors",
+    "clear_form_hooks",
+    "commit_transaction",
+    "complete_reminder",
     "compose_entry_markdown_from_chat",
     "compose_entry_markdown_from_fields",
+    "contention_stats",
     "create_entry",
+    "create_entry_from_template",
+    "create_folder",
     "create_sample_space",
     "create_sample_space_job",
     "create_space",
@@ -80,42 +207,133 @@ __all__ = [
     "create_sql_session",
     "delete_asset",
     "delete_entry",
+    "delete_folder",
+    "delete_reminder",
+    "delete_schedule",
+    "delete_secret",
     "delete_sql",
+    "delete_template",
+    "diff_snapshots",
+    "duplicate_entry",
+    "entry_exists",
+    "export_entry_history",
+    "export_space",
+    "export_space_stream",
     "extract_properties",
+    "field_lineage",
+    "flush_contention_stats",
+    "flush_slow_log",
+    "gc_deleted_entries",
+    "get_asset_text",
+    "get_board",
     "get_entry",
     "get_entry_history",
     "get_entry_revision",
     "get_form",
+    "get_form_history",
+    "get_form_version",
     "get_sample_space_job",
+    "get_secret",
     "get_space",
     "get_sql",
     "get_sql_session_count",
     "get_sql_session_rows",
     "get_sql_session_rows_all",
     "get_sql_session_status",
+    "get_template",
+    "import_bundle",
+    "import_bundle_stream",
+    "import_eml",
+    "import_notion",
+    "index_status",
+    "issue_secret_capability",
+    "join_forms",
     "lint_sql",
     "list_assets",
+    "list_audit_events",
+    "list_author_keys",
     "list_column_types",
+    "list_deleted_entries",
+    "list_due_reminders",
     "list_entries",
+    "list_entries_page",
+    "list_entry_reminders",
+    "list_favorites",
+    "list_folder",
     "list_forms",
+    "list_frequent_entries",
+    "list_pinned",
+    "list_recent_entries",
     "list_sample_scenarios",
+    "list_schedules",
+    "list_secrets",
+    "list_slow_operations",
     "list_spaces",
     "list_sql",
+    "list_templates",
     "load_hmac_material",
     "load_response_hmac_material",
+    "load_signing_public_key",
     "load_sql_rules",
+    "merge_entries",
     "migrate_form",
+    "move_board_entry",
+    "move_entry_to_folder",
+    "move_folder",
+    "offline_bundle",
+    "offline_delta",
+    "patch_entry",
     "patch_space",
+    "pin_entry",
+    "purge_entry",
+    "purge_trash",
     "query_index",
+    "query_index_with_facets",
+    "query_to_csv",
+    "query_to_xlsx",
+    "rank_spaces_by_contention",
+    "read_asset_range",
+    "recompute_rollups",
+    "record_entry_access",
+    "redact_entry",
+    "register_author_key",
+    "register_content_extractor",
+    "register_form_hook",
     "reindex_all",
+    "reindex_form",
+    "reindex_incremental",
+    "remove_board_entry",
+    "remove_favorite",
+    "replica_status",
+    "resolve_entry_path",
     "restore_entry",
+    "revision_signing_payload",
+    "rollback_form",
+    "rotate_space_key",
+    "run_due_schedules",
     "save_asset",
+    "save_template",
+    "scan_space_pii",
     "search_entries",
+    "set_asset_text",
+    "set_entry_metadata",
+    "set_secret",
+    "sign_canonical_response",
+    "space_merkle_root",
     "sql_completions",
+    "stream_entries",
+    "suggest_entry_id",
     "test_storage_connection",
+    "unarchive_entry",
+    "undelete_entry",
+    "unpin_entry",
     "update_entry",
     "update_entry_index",
     "update_sql",
+    "upgrade_space_format",
     "upsert_form",
     "validate_properties",
+    "verify_export",
+    "verify_response_signature",
+    "verify_revision_author",
 ]